@@ -41,18 +41,18 @@ macro_rules! dynamic_loading {
 
         impl $wrapper {
             /// Loads the library.
-            pub fn load() -> crate::Result<$wrapper> {
+            pub fn load() -> Result<$wrapper> {
                 unsafe {
                     let handle = blazar_dl::_load_library!(blazar_dl::_library_filename!($name $(,$version)?));
                     if handle.is_null() {
-                        Err(crate::$error::OpenFailed)
+                        Err($error::OpenFailed)
                     }
                     else {
                         $(
                             let $fn = blazar_dl::_load_function!(handle, $fn);
                             if $fn.is_null() {
                                 blazar_dl::_unload_library!(handle);
-                                return Err(crate::$error::FunctionNotFound(String::from(stringify!($fn))));
+                                return Err($error::FunctionNotFound(String::from(stringify!($fn))));
                             }
                         )*
                         Ok($wrapper {
@@ -17,6 +17,9 @@ pub enum Event {
     MouseScrollUp,
     MouseScrollDown,
     MouseMove { x: i32, y: i32 },
+    // Graphics, reported by `DeviceRecovery::poll_event` of `blazar_graphics`
+    DeviceLost,
+    DeviceRestored,
 }
 
 /// Keyboard keys.
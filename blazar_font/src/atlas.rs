@@ -0,0 +1,286 @@
+//! Dynamic glyph atlas.
+
+use crate::{Font, GlyphBitmap, GlyphId, GlyphMode};
+use std::collections::HashMap;
+
+/// Empty pixels kept around each glyph so that bilinear filtering does not bleed.
+const PADDING: u32 = 1;
+
+/// Identifies a rasterized glyph.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct GlyphKey {
+    /// Identifier of the font, chosen by the caller.
+    pub font: u32,
+    pub glyph: GlyphId,
+    /// Bits of the size in pixels per em the glyph is rasterized at.
+    pub size: u32,
+}
+
+impl GlyphKey {
+    /// Creates a key.
+    pub fn new(font: u32, glyph: GlyphId, size: f32) -> GlyphKey {
+        GlyphKey {
+            font,
+            glyph,
+            size: size.to_bits(),
+        }
+    }
+}
+
+/// Location of a glyph in an atlas.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct AtlasGlyph {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    /// Horizontal distance in pixels from the pen position to the left edge.
+    pub left: i32,
+    /// Vertical distance in pixels from the baseline up to the top edge.
+    pub top: i32,
+}
+
+/// Rectangle of an atlas, in pixels.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct AtlasRegion {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Row of glyphs of similar heights.
+struct Shelf {
+    y: u32,
+    height: u32,
+    x: u32,
+}
+
+/// Texture holding rendered glyphs, packed on shelves.
+///
+/// Glyphs are added on demand. When it is full, the atlas doubles its height up to its
+/// maximum height, keeping glyph locations.
+pub struct GlyphAtlas {
+    mode: GlyphMode,
+    width: u32,
+    height: u32,
+    max_height: u32,
+    data: Vec<u8>,
+    shelves: Vec<Shelf>,
+    glyphs: HashMap<GlyphKey, AtlasGlyph>,
+    dirty: Option<AtlasRegion>,
+}
+
+impl GlyphAtlas {
+    /// Creates an empty atlas of coverage bitmaps.
+    pub fn new(width: u32, height: u32, max_height: u32) -> GlyphAtlas {
+        GlyphAtlas::with_mode(GlyphMode::Coverage, width, height, max_height)
+    }
+
+    /// Creates an empty atlas of glyphs rendered with `mode`.
+    pub fn with_mode(mode: GlyphMode, width: u32, height: u32, max_height: u32) -> GlyphAtlas {
+        GlyphAtlas {
+            mode,
+            width,
+            height,
+            max_height: max_height.max(height),
+            data: vec![0; width as usize * height as usize * mode.channels() as usize],
+            shelves: Vec::new(),
+            glyphs: HashMap::new(),
+            dirty: None,
+        }
+    }
+
+    /// Returns how glyphs are rendered.
+    pub fn mode(&self) -> GlyphMode {
+        self.mode
+    }
+
+    /// Returns the number of bytes per pixel.
+    pub fn channels(&self) -> u32 {
+        self.mode.channels()
+    }
+
+    /// Returns the width in pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the height in pixels, which grows as glyphs are added.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the pixels, top row first.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns the number of glyphs.
+    pub fn len(&self) -> usize {
+        self.glyphs.len()
+    }
+
+    /// Returns `true` if the atlas holds no glyphs.
+    pub fn is_empty(&self) -> bool {
+        self.glyphs.is_empty()
+    }
+
+    /// Returns a glyph previously added.
+    pub fn get(&self, key: &GlyphKey) -> Option<AtlasGlyph> {
+        self.glyphs.get(key).cloned()
+    }
+
+    /// Returns a glyph, rendering and adding it if needed, or `None` if the atlas is
+    /// full.
+    ///
+    /// The size of the key must be the raster size of the atlas mode.
+    pub fn get_or_insert(&mut self, key: GlyphKey, font: &Font) -> Option<AtlasGlyph> {
+        if let Some(glyph) = self.glyphs.get(&key) {
+            return Some(*glyph);
+        }
+        let bitmap = font.render(key.glyph, f32::from_bits(key.size), self.mode);
+        self.insert(key, &bitmap)
+    }
+
+    /// Adds a bitmap, or returns `None` if the atlas is full or the bitmap has a
+    /// different number of channels.
+    pub fn insert(&mut self, key: GlyphKey, bitmap: &GlyphBitmap) -> Option<AtlasGlyph> {
+        let (x, y) = if bitmap.width == 0 || bitmap.height == 0 {
+            (0, 0)
+        } else {
+            let channels = self.channels() as usize;
+            if bitmap.channels as usize != channels {
+                return None;
+            }
+            let (x, y) = self.allocate(bitmap.width, bitmap.height)?;
+            let row_size = bitmap.width as usize * channels;
+            for row in 0..bitmap.height as usize {
+                let source = row * row_size;
+                let destination =
+                    ((y as usize + row) * self.width as usize + x as usize) * channels;
+                self.data[destination..destination + row_size]
+                    .copy_from_slice(&bitmap.data[source..source + row_size]);
+            }
+            self.mark_dirty(AtlasRegion {
+                x,
+                y,
+                width: bitmap.width,
+                height: bitmap.height,
+            });
+            (x, y)
+        };
+        let glyph = AtlasGlyph {
+            x,
+            y,
+            width: bitmap.width,
+            height: bitmap.height,
+            left: bitmap.left,
+            top: bitmap.top,
+        };
+        self.glyphs.insert(key, glyph);
+        Some(glyph)
+    }
+
+    /// Removes every glyph.
+    pub fn clear(&mut self) {
+        for value in &mut self.data {
+            *value = 0;
+        }
+        self.shelves.clear();
+        self.glyphs.clear();
+        self.mark_dirty(AtlasRegion {
+            x: 0,
+            y: 0,
+            width: self.width,
+            height: self.height,
+        });
+    }
+
+    /// Returns the region modified since the last call, which must be uploaded to the
+    /// texture, recreated beforehand if the atlas grew.
+    pub fn take_dirty_region(&mut self) -> Option<AtlasRegion> {
+        self.dirty.take()
+    }
+
+    /// Returns the pixels of a region, tightly packed.
+    pub fn region_data(&self, region: AtlasRegion) -> Vec<u8> {
+        let channels = self.channels() as usize;
+        let row_size = region.width as usize * channels;
+        let mut data = Vec::with_capacity(row_size * region.height as usize);
+        for row in region.y..region.y + region.height {
+            let start = (row as usize * self.width as usize + region.x as usize) * channels;
+            data.extend_from_slice(&self.data[start..start + row_size]);
+        }
+        data
+    }
+
+    /// Reserves space for a bitmap, growing the atlas if needed.
+    fn allocate(&mut self, width: u32, height: u32) -> Option<(u32, u32)> {
+        let (padded_width, padded_height) = (width + PADDING, height + PADDING);
+        if padded_width + PADDING > self.width {
+            return None;
+        }
+        loop {
+            // Picks the shortest shelf the glyph fits in.
+            let atlas_width = self.width;
+            let shelf = self
+                .shelves
+                .iter_mut()
+                .filter(|shelf| {
+                    shelf.height >= padded_height && shelf.x + padded_width + PADDING <= atlas_width
+                })
+                .min_by_key(|shelf| shelf.height);
+            if let Some(shelf) = shelf {
+                let x = shelf.x + PADDING;
+                shelf.x += padded_width;
+                return Some((x, shelf.y + PADDING));
+            }
+            let y = self
+                .shelves
+                .last()
+                .map_or(0, |shelf| shelf.y + shelf.height);
+            if y + padded_height + PADDING <= self.height {
+                self.shelves.push(Shelf {
+                    y,
+                    height: padded_height,
+                    x: 0,
+                });
+            } else if self.height < self.max_height {
+                self.grow();
+            } else {
+                return None;
+            }
+        }
+    }
+
+    /// Doubles the height of the atlas.
+    fn grow(&mut self) {
+        self.height = (self.height.max(1) * 2).min(self.max_height);
+        let size = self.width as usize * self.height as usize * self.channels() as usize;
+        self.data.resize(size, 0);
+        self.mark_dirty(AtlasRegion {
+            x: 0,
+            y: 0,
+            width: self.width,
+            height: self.height,
+        });
+    }
+
+    /// Extends the dirty region to include `region`.
+    fn mark_dirty(&mut self, region: AtlasRegion) {
+        self.dirty = Some(match self.dirty {
+            Some(dirty) => {
+                let x = dirty.x.min(region.x);
+                let y = dirty.y.min(region.y);
+                AtlasRegion {
+                    x,
+                    y,
+                    width: (dirty.x + dirty.width).max(region.x + region.width) - x,
+                    height: (dirty.y + dirty.height).max(region.y + region.height) - y,
+                }
+            }
+            None => region,
+        });
+    }
+}
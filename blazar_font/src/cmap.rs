@@ -0,0 +1,136 @@
+//! Character to glyph mapping (`cmap` table).
+
+use crate::reader::{u16_at, u32_at};
+use std::ops::Range;
+
+/// Supported subtable formats.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Format {
+    /// Segment mapping to delta values, for the Basic Multilingual Plane.
+    SegmentDeltas,
+    /// Segmented coverage, for the full Unicode range.
+    SegmentedCoverage,
+}
+
+/// Unicode subtable of the `cmap` table.
+pub(crate) struct Cmap {
+    subtable: Range<usize>,
+    format: Format,
+}
+
+impl Cmap {
+    /// Selects the best Unicode subtable of the `cmap` table.
+    pub(crate) fn parse(data: &[u8], table: Range<usize>) -> Option<Cmap> {
+        let cmap = &data[table.clone()];
+        let count = u16_at(cmap, 2)? as usize;
+        let mut best: Option<(u8, Cmap)> = None;
+        for index in 0..count {
+            let record = 4 + index * 8;
+            let platform = u16_at(cmap, record)?;
+            let encoding = u16_at(cmap, record + 2)?;
+            let offset = u32_at(cmap, record + 4)? as usize;
+            let format = match u16_at(cmap, offset) {
+                Some(4) => Format::SegmentDeltas,
+                Some(12) => Format::SegmentedCoverage,
+                _ => continue,
+            };
+            if !matches!((platform, encoding), (0, _) | (3, 1) | (3, 10)) {
+                continue;
+            }
+            // Prefers subtables covering the full Unicode range.
+            let priority = match format {
+                Format::SegmentedCoverage => 2,
+                Format::SegmentDeltas => 1,
+            };
+            let length = match format {
+                Format::SegmentDeltas => u16_at(cmap, offset + 2)? as usize,
+                Format::SegmentedCoverage => u32_at(cmap, offset + 4)? as usize,
+            };
+            let start = table.start + offset;
+            let end = start.checked_add(length)?.min(table.end);
+            if best.as_ref().map_or(true, |(other, _)| priority > *other) {
+                best = Some((
+                    priority,
+                    Cmap {
+                        subtable: start..end,
+                        format,
+                    },
+                ));
+            }
+        }
+        best.map(|(_, cmap)| cmap)
+    }
+
+    /// Returns the glyph index of a code point, 0 if it is not mapped.
+    pub(crate) fn glyph_index(&self, data: &[u8], code_point: u32) -> Option<u16> {
+        let subtable = &data[self.subtable.clone()];
+        match self.format {
+            Format::SegmentDeltas => segment_deltas(subtable, code_point),
+            Format::SegmentedCoverage => segmented_coverage(subtable, code_point),
+        }
+    }
+}
+
+/// Looks up a code point in a format 4 subtable.
+fn segment_deltas(subtable: &[u8], code_point: u32) -> Option<u16> {
+    if code_point > 0xffff {
+        return Some(0);
+    }
+    let code_point = code_point as u16;
+    let segment_count = u16_at(subtable, 6)? as usize / 2;
+    let end_codes = 14;
+    let start_codes = end_codes + segment_count * 2 + 2;
+    let deltas = start_codes + segment_count * 2;
+    let range_offsets = deltas + segment_count * 2;
+
+    // Finds the first segment whose end code is greater than or equal to the code point.
+    let (mut low, mut high) = (0, segment_count);
+    while low < high {
+        let middle = (low + high) / 2;
+        if u16_at(subtable, end_codes + middle * 2)? < code_point {
+            low = middle + 1;
+        } else {
+            high = middle;
+        }
+    }
+    if low == segment_count {
+        return Some(0);
+    }
+    let segment = low;
+    let start = u16_at(subtable, start_codes + segment * 2)?;
+    if code_point < start {
+        return Some(0);
+    }
+    let delta = u16_at(subtable, deltas + segment * 2)?;
+    let range_offset_position = range_offsets + segment * 2;
+    let range_offset = u16_at(subtable, range_offset_position)? as usize;
+    if range_offset == 0 {
+        return Some(code_point.wrapping_add(delta));
+    }
+    let position = range_offset_position + range_offset + (code_point - start) as usize * 2;
+    match u16_at(subtable, position)? {
+        0 => Some(0),
+        glyph => Some(glyph.wrapping_add(delta)),
+    }
+}
+
+/// Looks up a code point in a format 12 subtable.
+fn segmented_coverage(subtable: &[u8], code_point: u32) -> Option<u16> {
+    let group_count = u32_at(subtable, 12)? as usize;
+    let (mut low, mut high) = (0, group_count);
+    while low < high {
+        let middle = (low + high) / 2;
+        let group = 16 + middle * 12;
+        let start = u32_at(subtable, group)?;
+        let end = u32_at(subtable, group + 4)?;
+        if code_point < start {
+            high = middle;
+        } else if code_point > end {
+            low = middle + 1;
+        } else {
+            let glyph = u32_at(subtable, group + 8)?.checked_add(code_point - start)?;
+            return Some(if glyph > 0xffff { 0 } else { glyph as u16 });
+        }
+    }
+    Some(0)
+}
@@ -0,0 +1,539 @@
+//! Signed distance field generation.
+//!
+//! Multi-channel fields follow the approach of msdfgen: edges of each contour are
+//! colored so that sharp corners lie between edges of different colors, and each
+//! channel stores the distance to the nearest edge of its color, the median of the
+//! channels reconstructing corners.
+
+use crate::{raster::flattening_steps, Font, GlyphBitmap, GlyphId, Outline, Point, Segment};
+
+/// Maximum width and height of a distance field.
+const MAX_FIELD_SIZE: f32 = 4096.0;
+
+/// Sine of the angle beyond which the junction of two edges is a corner.
+const CORNER_THRESHOLD: f32 = 0.141;
+
+/// Distances closer than this, in pixels, are considered equal.
+const DISTANCE_EPSILON: f32 = 1e-4;
+
+// Edge colors, as sets of channels.
+const RED: u8 = 0b001;
+const GREEN: u8 = 0b010;
+const BLUE: u8 = 0b100;
+const YELLOW: u8 = RED | GREEN;
+const MAGENTA: u8 = RED | BLUE;
+const CYAN: u8 = GREEN | BLUE;
+const WHITE: u8 = RED | GREEN | BLUE;
+
+/// How glyphs are rendered into bitmaps.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GlyphMode {
+    /// Coverage bitmaps, rasterized at each requested size.
+    Coverage,
+    /// Single-channel signed distance fields rasterized once at `size` pixels per em,
+    /// storing distances up to `range` pixels.
+    Sdf { size: f32, range: f32 },
+    /// Multi-channel signed distance fields rasterized once at `size` pixels per em,
+    /// storing distances up to `range` pixels, the true distance being in the alpha
+    /// channel.
+    Msdf { size: f32, range: f32 },
+}
+
+impl GlyphMode {
+    /// Returns the number of bytes per pixel of bitmaps.
+    pub fn channels(self) -> u32 {
+        match self {
+            GlyphMode::Coverage | GlyphMode::Sdf { .. } => 1,
+            GlyphMode::Msdf { .. } => 4,
+        }
+    }
+
+    /// Returns the size glyphs are rasterized at to be displayed at `size`.
+    pub fn raster_size(self, size: f32) -> f32 {
+        match self {
+            GlyphMode::Coverage => size,
+            GlyphMode::Sdf { size, .. } | GlyphMode::Msdf { size, .. } => size,
+        }
+    }
+
+    /// Returns the distance range in pixels of distance fields, 0 for coverage bitmaps.
+    pub fn range(self) -> f32 {
+        match self {
+            GlyphMode::Coverage => 0.0,
+            GlyphMode::Sdf { range, .. } | GlyphMode::Msdf { range, .. } => range,
+        }
+    }
+}
+
+impl Default for GlyphMode {
+    fn default() -> GlyphMode {
+        GlyphMode::Coverage
+    }
+}
+
+impl Font {
+    /// Renders a glyph displayed at `size` pixels per em.
+    pub fn render(&self, glyph: GlyphId, size: f32, mode: GlyphMode) -> GlyphBitmap {
+        match mode {
+            GlyphMode::Coverage => self.rasterize(glyph, size),
+            GlyphMode::Sdf { size, range } => self.rasterize_sdf(glyph, size, range),
+            GlyphMode::Msdf { size, range } => self.rasterize_msdf(glyph, size, range),
+        }
+    }
+
+    /// Generates the single-channel signed distance field of a glyph at `size` pixels
+    /// per em.
+    ///
+    /// Values map distances from `-range` to `range` pixels to 0 to 255, inside being
+    /// above 127. The bitmap is padded by `range` pixels.
+    pub fn rasterize_sdf(&self, glyph: GlyphId, size: f32, range: f32) -> GlyphBitmap {
+        match self.outline(glyph) {
+            Some(outline) => distance_field(&outline, self.scale(size), range, false),
+            None => GlyphBitmap::default(),
+        }
+    }
+
+    /// Generates the multi-channel signed distance field of a glyph at `size` pixels per
+    /// em, as RGBA pixels.
+    ///
+    /// The median of the color channels gives sharp corners, while the alpha channel
+    /// holds the true distance, suited to wide outlines and shadows.
+    pub fn rasterize_msdf(&self, glyph: GlyphId, size: f32, range: f32) -> GlyphBitmap {
+        match self.outline(glyph) {
+            Some(outline) => distance_field(&outline, self.scale(size), range, true),
+            None => GlyphBitmap::default(),
+        }
+    }
+}
+
+/// Generates the distance field of an outline in font units, scaled by `scale`.
+fn distance_field(outline: &Outline, scale: f32, range: f32, multi_channel: bool) -> GlyphBitmap {
+    if outline.is_empty() || scale.is_nan() || scale <= 0.0 || range.is_nan() || range <= 0.0 {
+        return GlyphBitmap::default();
+    }
+    let padding = range.ceil();
+    let left = (outline.bounds.min.x * scale).floor() - padding;
+    let right = (outline.bounds.max.x * scale).ceil() + padding;
+    let top = (outline.bounds.max.y * scale).ceil() + padding;
+    let bottom = (outline.bounds.min.y * scale).floor() - padding;
+    let (width, height) = (right - left, top - bottom);
+    let valid = |extent: f32| (1.0..=MAX_FIELD_SIZE).contains(&extent);
+    if !valid(width) || !valid(height) {
+        return GlyphBitmap::default();
+    }
+
+    let mut contours: Vec<Vec<Edge>> = outline
+        .contours
+        .iter()
+        .map(|contour| edges(contour, scale))
+        .filter(|edges| !edges.is_empty())
+        .collect();
+    if multi_channel {
+        let mut seed = 0;
+        for contour in &mut contours {
+            color_edges(contour, &mut seed);
+        }
+    }
+    let edges: Vec<&Edge> = contours.iter().flatten().collect();
+    // Outer contours of TrueType glyphs are clockwise, leaving the inside on the right.
+    let area: f32 = edges.iter().map(|edge| edge.area()).sum();
+    let orientation = if area <= 0.0 { -1.0 } else { 1.0 };
+
+    let channels = if multi_channel { 4 } else { 1 };
+    let (width, height) = (width as usize, height as usize);
+    let mut data = Vec::with_capacity(width * height * channels);
+    let encode =
+        |distance: f32| ((0.5 + distance / (2.0 * range)).max(0.0).min(1.0) * 255.0 + 0.5) as u8;
+    for row in 0..height {
+        for column in 0..width {
+            let point = Point::new(left + column as f32 + 0.5, top - row as f32 - 0.5);
+            let mut nearest = Nearest::default();
+            let mut channel_nearest = [Nearest::default(); 3];
+            for edge in &edges {
+                let distance = edge.distance(point);
+                nearest.update(&distance);
+                if multi_channel {
+                    for (channel, channel_nearest) in channel_nearest.iter_mut().enumerate() {
+                        if edge.color & (1 << channel) != 0 {
+                            channel_nearest.update(&distance);
+                        }
+                    }
+                }
+            }
+            // The winding number decides the sign of the true distance, which remains
+            // correct for overlapping contours.
+            let inside = edges.iter().map(|edge| edge.winding(point)).sum::<i32>() != 0;
+            let true_distance = if inside {
+                nearest.distance.abs()
+            } else {
+                -nearest.distance.abs()
+            };
+            if !multi_channel {
+                data.push(encode(true_distance));
+                continue;
+            }
+            let mut distances = [true_distance; 3];
+            for (distance, channel_nearest) in distances.iter_mut().zip(&channel_nearest) {
+                if channel_nearest.distance.is_finite() {
+                    *distance = channel_nearest.pseudo_distance * orientation;
+                }
+            }
+            // Falls back to the true distance where the channels disagree with it, which
+            // happens near edges of different contours.
+            if (median(distances) > 0.0) != inside {
+                distances = [true_distance; 3];
+            }
+            data.extend_from_slice(&[
+                encode(distances[0]),
+                encode(distances[1]),
+                encode(distances[2]),
+                encode(true_distance),
+            ]);
+        }
+    }
+    GlyphBitmap {
+        width: width as u32,
+        height: height as u32,
+        left: left as i32,
+        top: top as i32,
+        channels: channels as u32,
+        data,
+    }
+}
+
+/// Returns the median of three values.
+fn median(values: [f32; 3]) -> f32 {
+    values[0]
+        .min(values[1])
+        .max(values[0].max(values[1]).min(values[2]))
+}
+
+/// Returns the cross product of two vectors.
+fn cross(a: Point, b: Point) -> f32 {
+    a.x * b.y - a.y * b.x
+}
+
+/// Returns the dot product of two vectors.
+fn dot(a: Point, b: Point) -> f32 {
+    a.x * b.x + a.y * b.y
+}
+
+/// Returns `b - a`.
+fn sub(b: Point, a: Point) -> Point {
+    Point::new(b.x - a.x, b.y - a.y)
+}
+
+/// Returns a vector of length 1, or the zero vector.
+fn normalize(vector: Point) -> Point {
+    let length = dot(vector, vector).sqrt();
+    if length > 0.0 {
+        Point::new(vector.x / length, vector.y / length)
+    } else {
+        vector
+    }
+}
+
+/// Segment of a contour approximated by lines, its points being in pixels.
+struct Edge {
+    points: Vec<Point>,
+    color: u8,
+}
+
+/// Distance from a point to an edge.
+#[derive(Clone, Copy)]
+struct EdgeDistance {
+    /// Signed distance, negative on the right of the edge.
+    distance: f32,
+    /// Distance to the lines extending the edge at its ends, where they are closer.
+    pseudo_distance: f32,
+    /// Cosine of the angle between the edge and the direction to the point, telling
+    /// edges meeting at the nearest point apart.
+    orthogonality: f32,
+}
+
+/// Nearest edge found so far.
+#[derive(Clone, Copy)]
+struct Nearest {
+    distance: f32,
+    pseudo_distance: f32,
+    orthogonality: f32,
+}
+
+impl Default for Nearest {
+    fn default() -> Nearest {
+        Nearest {
+            distance: f32::INFINITY,
+            pseudo_distance: f32::INFINITY,
+            orthogonality: 1.0,
+        }
+    }
+}
+
+impl Nearest {
+    /// Keeps `other` if it is nearer, or as near and more orthogonal.
+    fn update(&mut self, other: &EdgeDistance) {
+        let (current, candidate) = (self.distance.abs(), other.distance.abs());
+        if candidate < current - DISTANCE_EPSILON
+            || (candidate <= current + DISTANCE_EPSILON && other.orthogonality < self.orthogonality)
+        {
+            self.distance = other.distance;
+            self.pseudo_distance = other.pseudo_distance;
+            self.orthogonality = other.orthogonality;
+        }
+    }
+}
+
+impl Edge {
+    /// Returns the direction at the start.
+    fn start_direction(&self) -> Point {
+        sub(self.points[1], self.points[0])
+    }
+
+    /// Returns the direction at the end.
+    fn end_direction(&self) -> Point {
+        let count = self.points.len();
+        sub(self.points[count - 1], self.points[count - 2])
+    }
+
+    /// Returns twice the signed area swept between the edge and the origin.
+    fn area(&self) -> f32 {
+        self.points
+            .windows(2)
+            .map(|line| cross(line[0], line[1]))
+            .sum()
+    }
+
+    /// Returns the contribution of the edge to the winding number around a point.
+    fn winding(&self, point: Point) -> i32 {
+        self.points
+            .windows(2)
+            .map(|line| {
+                let (start, end) = (line[0], line[1]);
+                let side = cross(sub(end, start), sub(point, start));
+                if start.y <= point.y && end.y > point.y && side > 0.0 {
+                    1
+                } else if end.y <= point.y && start.y > point.y && side < 0.0 {
+                    -1
+                } else {
+                    0
+                }
+            })
+            .sum()
+    }
+
+    /// Returns the distance from a point.
+    fn distance(&self, point: Point) -> EdgeDistance {
+        let last = self.points.len() - 2;
+        let mut best = EdgeDistance {
+            distance: f32::INFINITY,
+            pseudo_distance: f32::INFINITY,
+            orthogonality: 1.0,
+        };
+        // Parameter along the nearest line, and whether it is the first or last line.
+        let mut best_line = (0.0, false, false);
+        for (index, line) in self.points.windows(2).enumerate() {
+            let (start, end) = (line[0], line[1]);
+            let direction = sub(end, start);
+            let offset = sub(point, start);
+            let length = dot(direction, direction);
+            let t = if length > 0.0 {
+                dot(offset, direction) / length
+            } else {
+                0.0
+            };
+            let clamped = t.max(0.0).min(1.0);
+            let nearest = Point::new(
+                start.x + direction.x * clamped,
+                start.y + direction.y * clamped,
+            );
+            let to_point = sub(point, nearest);
+            let distance = dot(to_point, to_point).sqrt();
+            let orthogonality = if distance > 0.0 {
+                dot(normalize(direction), normalize(to_point)).abs()
+            } else {
+                0.0
+            };
+            let candidate = best.distance.abs();
+            if distance < candidate - DISTANCE_EPSILON
+                || (distance <= candidate + DISTANCE_EPSILON && orthogonality < best.orthogonality)
+            {
+                let sign = if cross(direction, offset) < 0.0 {
+                    -1.0
+                } else {
+                    1.0
+                };
+                best.distance = sign * distance;
+                best.orthogonality = orthogonality;
+                best_line = (t, index == 0, index == last);
+            }
+        }
+        best.pseudo_distance = best.distance;
+        let (t, first, last) = best_line;
+        let extension = if first && t < 0.0 {
+            Some((self.points[0], self.start_direction()))
+        } else if last && t > 1.0 {
+            Some((self.points[self.points.len() - 1], self.end_direction()))
+        } else {
+            None
+        };
+        if let Some((origin, direction)) = extension {
+            let pseudo_distance = cross(normalize(direction), sub(point, origin));
+            if pseudo_distance.abs() <= best.distance.abs() {
+                best.pseudo_distance = pseudo_distance;
+            }
+        }
+        best
+    }
+
+    /// Splits the edge in three parts of equal length.
+    fn split_in_thirds(&self) -> [Edge; 3] {
+        let lengths: Vec<f32> = self
+            .points
+            .windows(2)
+            .map(|line| {
+                let direction = sub(line[1], line[0]);
+                dot(direction, direction).sqrt()
+            })
+            .collect();
+        let total: f32 = lengths.iter().sum();
+        let mut parts = [Vec::new(), Vec::new(), Vec::new()];
+        parts[0].push(self.points[0]);
+        let mut part = 0;
+        let mut travelled = 0.0;
+        for (line, length) in self.points.windows(2).zip(&lengths) {
+            // Cuts the line where it crosses a third of the total length.
+            while part < 2 && travelled + length >= total * (part + 1) as f32 / 3.0 {
+                let t = if *length > 0.0 {
+                    (total * (part + 1) as f32 / 3.0 - travelled) / length
+                } else {
+                    0.0
+                };
+                let cut = Segment::Line(line[0], line[1]).point(t);
+                parts[part].push(cut);
+                part += 1;
+                parts[part].push(cut);
+            }
+            travelled += length;
+            parts[part].push(line[1]);
+        }
+        let [first, second, third] = parts;
+        let edge = |points: Vec<Point>| Edge {
+            points,
+            color: self.color,
+        };
+        [edge(first), edge(second), edge(third)]
+    }
+}
+
+/// Converts the segments of a contour into edges in pixels, skipping degenerate ones.
+fn edges(contour: &[Segment], scale: f32) -> Vec<Edge> {
+    let transform = |point: Point| Point::new(point.x * scale, point.y * scale);
+    contour
+        .iter()
+        .filter_map(|segment| {
+            let points = match segment.map(transform) {
+                Segment::Line(start, end) => vec![start, end],
+                Segment::Quad(start, control, end) => {
+                    let count = flattening_steps(start, control, end);
+                    let quad = Segment::Quad(start, control, end);
+                    (0..=count)
+                        .map(|step| quad.point(step as f32 / count as f32))
+                        .collect()
+                }
+            };
+            let mut points = points;
+            points.dedup();
+            if points.len() < 2 {
+                None
+            } else {
+                Some(Edge {
+                    points,
+                    color: WHITE,
+                })
+            }
+        })
+        .collect()
+}
+
+/// Returns `true` if the junction of two directions is a corner.
+fn is_corner(a: Point, b: Point) -> bool {
+    let (a, b) = (normalize(a), normalize(b));
+    dot(a, b) <= 0.0 || cross(a, b).abs() > CORNER_THRESHOLD
+}
+
+/// Picks the next color of a contour, never sharing two channels with `banned`.
+fn switch_color(color: &mut u8, seed: &mut u64, banned: u8) {
+    let combined = *color & banned;
+    if combined == RED || combined == GREEN || combined == BLUE {
+        *color = combined ^ WHITE;
+        return;
+    }
+    if *color == 0 || *color == WHITE {
+        *color = [CYAN, MAGENTA, YELLOW][(*seed % 3) as usize];
+        *seed /= 3;
+        return;
+    }
+    let shifted = *color << (1 + (*seed & 1));
+    *color = (shifted | shifted >> 3) & WHITE;
+    *seed >>= 1;
+}
+
+/// Colors the edges of a contour, switching color at each corner.
+fn color_edges(contour: &mut Vec<Edge>, seed: &mut u64) {
+    let mut corners = Vec::new();
+    let mut previous = contour[contour.len() - 1].end_direction();
+    for (index, edge) in contour.iter().enumerate() {
+        if is_corner(previous, edge.start_direction()) {
+            corners.push(index);
+        }
+        previous = edge.end_direction();
+    }
+    match corners.len() {
+        0 => {}
+        1 => {
+            // Teardrop shape, split into three differently colored parts.
+            let mut colors = [WHITE, WHITE, WHITE];
+            switch_color(&mut colors[0], seed, 0);
+            colors[2] = colors[0];
+            switch_color(&mut colors[2], seed, 0);
+            if contour.len() < 3 {
+                let parts: Vec<Edge> = contour
+                    .drain(..)
+                    .flat_map(|edge| {
+                        let [first, second, third] = edge.split_in_thirds();
+                        vec![first, second, third]
+                    })
+                    .collect();
+                *contour = parts;
+            }
+            let count = contour.len();
+            let corner = corners[0];
+            for index in 0..count {
+                let third =
+                    (3.0 + 2.875 * index as f32 / (count - 1) as f32 - 1.4375 + 0.5) as usize - 2;
+                contour[(corner + index) % count].color = colors[third];
+            }
+        }
+        corner_count => {
+            let count = contour.len();
+            let start = corners[0];
+            let mut spline = 0;
+            let mut color = WHITE;
+            switch_color(&mut color, seed, 0);
+            let initial = color;
+            for index in 0..count {
+                let edge = (start + index) % count;
+                if spline + 1 < corner_count && corners[spline + 1] == edge {
+                    spline += 1;
+                    let banned = if spline == corner_count - 1 {
+                        initial
+                    } else {
+                        0
+                    };
+                    switch_color(&mut color, seed, banned);
+                }
+                contour[edge].color = color;
+            }
+        }
+    }
+}
@@ -0,0 +1,301 @@
+//! Pair kerning from the `GPOS` or `kern` tables.
+
+use crate::reader::{i16_at, u16_at, u32_at};
+use std::{cmp::Ordering, ops::Range};
+
+/// Lookup type of pair adjustments.
+const PAIR_ADJUSTMENT: u16 = 2;
+
+/// Lookup type of extension lookups, pointing to a subtable of another type.
+const EXTENSION: u16 = 9;
+
+/// Bit of value formats marking the presence of a horizontal advance adjustment.
+const X_ADVANCE: u16 = 0x0004;
+
+/// Kinds of kerning subtables.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum SubtableKind {
+    /// Pair adjustment listing glyph pairs.
+    GlyphPairs,
+    /// Pair adjustment between glyph classes.
+    ClassPairs,
+    /// Format 0 subtable of the `kern` table.
+    Kern,
+}
+
+/// Kerning subtable, its offset being absolute.
+#[derive(Clone, Copy, Debug)]
+struct Subtable {
+    kind: SubtableKind,
+    offset: usize,
+}
+
+/// Kerning subtables grouped by lookup.
+///
+/// Within a lookup only the first subtable covering a pair applies, while adjustments of
+/// different lookups add up.
+pub(crate) struct Kerning {
+    lookups: Vec<Vec<Subtable>>,
+}
+
+impl Kerning {
+    /// Collects the kerning subtables, preferring the `kern` feature of the `GPOS` table
+    /// over the `kern` table.
+    pub(crate) fn parse(
+        data: &[u8],
+        gpos: Option<Range<usize>>,
+        kern: Option<Range<usize>>,
+    ) -> Kerning {
+        let lookups = gpos
+            .and_then(|gpos| parse_gpos(data, gpos.start))
+            .filter(|lookups| !lookups.is_empty())
+            .or_else(|| kern.and_then(|kern| parse_kern(data, kern.start)))
+            .unwrap_or_default();
+        Kerning { lookups }
+    }
+
+    /// Returns the adjustment of the advance of `left` when followed by `right`.
+    pub(crate) fn get(&self, data: &[u8], left: u16, right: u16) -> i16 {
+        let mut adjustment = 0i16;
+        for lookup in &self.lookups {
+            for subtable in lookup {
+                let value = match subtable.kind {
+                    SubtableKind::GlyphPairs => glyph_pairs(data, subtable.offset, left, right),
+                    SubtableKind::ClassPairs => class_pairs(data, subtable.offset, left, right),
+                    SubtableKind::Kern => kern_pairs(data, subtable.offset, left, right),
+                };
+                if let Some(value) = value {
+                    adjustment = adjustment.saturating_add(value);
+                    break;
+                }
+            }
+        }
+        adjustment
+    }
+}
+
+/// Collects pair adjustment lookups referenced by `kern` features.
+fn parse_gpos(data: &[u8], gpos: usize) -> Option<Vec<Vec<Subtable>>> {
+    let features = gpos + u16_at(data, gpos + 6)? as usize;
+    let lookup_list = gpos + u16_at(data, gpos + 8)? as usize;
+    let mut indices = Vec::new();
+    for feature in 0..u16_at(data, features)? as usize {
+        let record = features + 2 + feature * 6;
+        if data.get(record..record + 4)? != b"kern" {
+            continue;
+        }
+        let table = features + u16_at(data, record + 4)? as usize;
+        for index in 0..u16_at(data, table + 2)? as usize {
+            indices.push(u16_at(data, table + 4 + index * 2)?);
+        }
+    }
+    indices.sort_unstable();
+    indices.dedup();
+
+    let mut lookups = Vec::new();
+    for index in indices {
+        let lookup = lookup_list + u16_at(data, lookup_list + 2 + index as usize * 2)? as usize;
+        let lookup_type = u16_at(data, lookup)?;
+        let mut subtables = Vec::new();
+        for subtable in 0..u16_at(data, lookup + 4)? as usize {
+            let mut offset = lookup + u16_at(data, lookup + 6 + subtable * 2)? as usize;
+            let mut subtable_type = lookup_type;
+            if subtable_type == EXTENSION {
+                subtable_type = u16_at(data, offset + 2)?;
+                offset += u32_at(data, offset + 4)? as usize;
+            }
+            if subtable_type != PAIR_ADJUSTMENT {
+                continue;
+            }
+            let kind = match u16_at(data, offset)? {
+                1 => SubtableKind::GlyphPairs,
+                2 => SubtableKind::ClassPairs,
+                _ => continue,
+            };
+            subtables.push(Subtable { kind, offset });
+        }
+        if !subtables.is_empty() {
+            lookups.push(subtables);
+        }
+    }
+    Some(lookups)
+}
+
+/// Collects horizontal format 0 subtables of the `kern` table.
+fn parse_kern(data: &[u8], kern: usize) -> Option<Vec<Vec<Subtable>>> {
+    if u16_at(data, kern)? != 0 {
+        return None;
+    }
+    let mut lookups = Vec::new();
+    let mut offset = kern + 4;
+    for _ in 0..u16_at(data, kern + 2)? {
+        let length = u16_at(data, offset + 2)? as usize;
+        let coverage = u16_at(data, offset + 4)?;
+        // Keeps horizontal kerning values, skipping minimum and cross-stream ones.
+        if coverage >> 8 == 0 && coverage & 0x07 == 0x01 {
+            lookups.push(vec![Subtable {
+                kind: SubtableKind::Kern,
+                offset,
+            }]);
+        }
+        if length == 0 {
+            break;
+        }
+        offset += length;
+    }
+    Some(lookups)
+}
+
+/// Returns the size in bytes of a value record.
+fn value_record_size(format: u16) -> usize {
+    (format & 0xff).count_ones() as usize * 2
+}
+
+/// Returns the horizontal advance adjustment of a value record.
+fn x_advance(data: &[u8], record: usize, format: u16) -> Option<i16> {
+    if format & X_ADVANCE == 0 {
+        return Some(0);
+    }
+    i16_at(data, record + (format & 0x03).count_ones() as usize * 2)
+}
+
+/// Returns the coverage index of a glyph.
+fn coverage_index(data: &[u8], coverage: usize, glyph: u16) -> Option<usize> {
+    match u16_at(data, coverage)? {
+        1 => {
+            let count = u16_at(data, coverage + 2)? as usize;
+            binary_search(count, |index| {
+                Some(u16_at(data, coverage + 4 + index * 2)?.cmp(&glyph))
+            })
+        }
+        2 => {
+            let count = u16_at(data, coverage + 2)? as usize;
+            let range = binary_search_range(
+                count,
+                |index| {
+                    let record = coverage + 4 + index * 6;
+                    Some((u16_at(data, record)?, u16_at(data, record + 2)?))
+                },
+                glyph,
+            )?;
+            let record = coverage + 4 + range * 6;
+            Some(u16_at(data, record + 4)? as usize + (glyph - u16_at(data, record)?) as usize)
+        }
+        _ => None,
+    }
+}
+
+/// Returns the class of a glyph, 0 if it is not listed.
+fn class_of(data: &[u8], class_def: usize, glyph: u16) -> Option<u16> {
+    match u16_at(data, class_def)? {
+        1 => {
+            let start = u16_at(data, class_def + 2)?;
+            let count = u16_at(data, class_def + 4)?;
+            if glyph >= start && glyph - start < count {
+                u16_at(data, class_def + 6 + (glyph - start) as usize * 2)
+            } else {
+                Some(0)
+            }
+        }
+        2 => {
+            let count = u16_at(data, class_def + 2)? as usize;
+            match binary_search_range(
+                count,
+                |index| {
+                    let record = class_def + 4 + index * 6;
+                    Some((u16_at(data, record)?, u16_at(data, record + 2)?))
+                },
+                glyph,
+            ) {
+                Some(range) => u16_at(data, class_def + 4 + range * 6 + 4),
+                None => Some(0),
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Looks up a pair in a pair adjustment subtable of format 1.
+fn glyph_pairs(data: &[u8], subtable: usize, left: u16, right: u16) -> Option<i16> {
+    let coverage = subtable + u16_at(data, subtable + 2)? as usize;
+    let index = coverage_index(data, coverage, left)?;
+    let format1 = u16_at(data, subtable + 4)?;
+    let format2 = u16_at(data, subtable + 6)?;
+    if index >= u16_at(data, subtable + 8)? as usize {
+        return None;
+    }
+    let pair_set = subtable + u16_at(data, subtable + 10 + index * 2)? as usize;
+    let record_size = 2 + value_record_size(format1) + value_record_size(format2);
+    let count = u16_at(data, pair_set)? as usize;
+    let found = binary_search(count, |index| {
+        Some(u16_at(data, pair_set + 2 + index * record_size)?.cmp(&right))
+    })?;
+    x_advance(data, pair_set + 2 + found * record_size + 2, format1)
+}
+
+/// Looks up a pair in a pair adjustment subtable of format 2.
+fn class_pairs(data: &[u8], subtable: usize, left: u16, right: u16) -> Option<i16> {
+    let coverage = subtable + u16_at(data, subtable + 2)? as usize;
+    coverage_index(data, coverage, left)?;
+    let format1 = u16_at(data, subtable + 4)?;
+    let format2 = u16_at(data, subtable + 6)?;
+    let class1 = class_of(data, subtable + u16_at(data, subtable + 8)? as usize, left)?;
+    let class2 = class_of(
+        data,
+        subtable + u16_at(data, subtable + 10)? as usize,
+        right,
+    )?;
+    let class1_count = u16_at(data, subtable + 12)?;
+    let class2_count = u16_at(data, subtable + 14)?;
+    if class1 >= class1_count || class2 >= class2_count {
+        return None;
+    }
+    let record_size = value_record_size(format1) + value_record_size(format2);
+    let record =
+        subtable + 16 + (class1 as usize * class2_count as usize + class2 as usize) * record_size;
+    x_advance(data, record, format1)
+}
+
+/// Looks up a pair in a format 0 subtable of the `kern` table.
+fn kern_pairs(data: &[u8], subtable: usize, left: u16, right: u16) -> Option<i16> {
+    let count = u16_at(data, subtable + 6)? as usize;
+    let key = u32::from(left) << 16 | u32::from(right);
+    let found = binary_search(count, |index| {
+        Some(u32_at(data, subtable + 14 + index * 6)?.cmp(&key))
+    })?;
+    i16_at(data, subtable + 14 + found * 6 + 4)
+}
+
+/// Finds the index whose element compares equal among `count` sorted elements.
+fn binary_search<F>(count: usize, compare: F) -> Option<usize>
+where
+    F: Fn(usize) -> Option<Ordering>,
+{
+    let (mut low, mut high) = (0, count);
+    while low < high {
+        let middle = (low + high) / 2;
+        match compare(middle)? {
+            Ordering::Less => low = middle + 1,
+            Ordering::Greater => high = middle,
+            Ordering::Equal => return Some(middle),
+        }
+    }
+    None
+}
+
+/// Finds the index of the range containing `glyph` among `count` sorted ranges.
+fn binary_search_range<F>(count: usize, range: F, glyph: u16) -> Option<usize>
+where
+    F: Fn(usize) -> Option<(u16, u16)>,
+{
+    binary_search(count, |index| {
+        let (start, end) = range(index)?;
+        Some(if end < glyph {
+            Ordering::Less
+        } else if start > glyph {
+            Ordering::Greater
+        } else {
+            Ordering::Equal
+        })
+    })
+}
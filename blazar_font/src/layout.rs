@@ -0,0 +1,253 @@
+//! Text layout.
+
+use crate::{Font, GlyphId};
+
+/// Number of spaces a tabulation advances by.
+const TAB_WIDTH: f32 = 4.0;
+
+/// Horizontal alignment of lines.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Alignment {
+    Left,
+    Center,
+    Right,
+}
+
+impl Default for Alignment {
+    fn default() -> Alignment {
+        Alignment::Left
+    }
+}
+
+/// Parameters of a layout.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LayoutOptions {
+    /// Size in pixels per em.
+    pub size: f32,
+    /// Width beyond which lines wrap.
+    pub max_width: Option<f32>,
+    /// Alignment of lines, relative to the maximum width if any, to the widest line
+    /// otherwise.
+    pub alignment: Alignment,
+    /// Factor applied to the line height of the font.
+    pub line_spacing: f32,
+}
+
+impl LayoutOptions {
+    /// Creates options for unwrapped, left-aligned text.
+    pub fn new(size: f32) -> LayoutOptions {
+        LayoutOptions {
+            size,
+            max_width: None,
+            alignment: Alignment::Left,
+            line_spacing: 1.0,
+        }
+    }
+
+    /// Sets the width beyond which lines wrap.
+    pub fn max_width(mut self, max_width: f32) -> LayoutOptions {
+        self.max_width = Some(max_width);
+        self
+    }
+
+    /// Sets the alignment of lines.
+    pub fn alignment(mut self, alignment: Alignment) -> LayoutOptions {
+        self.alignment = alignment;
+        self
+    }
+
+    /// Sets the factor applied to the line height of the font.
+    pub fn line_spacing(mut self, line_spacing: f32) -> LayoutOptions {
+        self.line_spacing = line_spacing;
+        self
+    }
+}
+
+/// Glyph placed by a layout.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PositionedGlyph {
+    pub glyph: GlyphId,
+    /// Horizontal pen position in pixels.
+    pub x: f32,
+    /// Vertical position of the baseline in pixels, downward from the top of the text.
+    pub y: f32,
+    /// Byte index of the character in the text.
+    pub index: usize,
+}
+
+/// Text laid out in lines.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TextLayout {
+    pub glyphs: Vec<PositionedGlyph>,
+    /// Width of the widest line in pixels, trailing whitespace excluded.
+    pub width: f32,
+    /// Height from the top of the first line to the bottom of the last one, in pixels.
+    pub height: f32,
+    /// Number of lines.
+    pub lines: usize,
+    /// Size in pixels per em.
+    pub size: f32,
+}
+
+/// Character of a paragraph.
+struct Item {
+    glyph: GlyphId,
+    index: usize,
+    advance: f32,
+    whitespace: bool,
+}
+
+/// Line of a paragraph, before alignment.
+struct Line {
+    glyphs: Vec<(GlyphId, usize, f32)>,
+    width: f32,
+}
+
+impl Font {
+    /// Lays out UTF-8 text, applying kerning, breaking lines at line feeds and wrapping
+    /// them at whitespace, or between characters for words wider than the maximum width.
+    ///
+    /// Characters missing from the font use the glyph 0.
+    pub fn layout(&self, text: &str, options: &LayoutOptions) -> TextLayout {
+        let scale = self.scale(options.size);
+        let mut lines = Vec::new();
+        let mut offset = 0;
+        for paragraph in text.split('\n') {
+            let items = self.paragraph_items(paragraph, offset, scale);
+            self.break_lines(&items, scale, options.max_width, &mut lines);
+            offset += paragraph.len() + 1;
+        }
+
+        let widest = lines
+            .iter()
+            .fold(0.0f32, |width, line| width.max(line.width));
+        let alignment_width = options.max_width.unwrap_or(widest);
+        let ascent = f32::from(self.ascent()) * scale;
+        let descent = f32::from(self.descent()) * scale;
+        let line_height =
+            (ascent - descent + f32::from(self.line_gap()) * scale) * options.line_spacing;
+        let mut glyphs = Vec::new();
+        for (number, line) in lines.iter().enumerate() {
+            let shift = match options.alignment {
+                Alignment::Left => 0.0,
+                Alignment::Center => 0.5 * (alignment_width - line.width),
+                Alignment::Right => alignment_width - line.width,
+            };
+            let y = ascent + number as f32 * line_height;
+            glyphs.extend(
+                line.glyphs
+                    .iter()
+                    .map(|&(glyph, index, x)| PositionedGlyph {
+                        glyph,
+                        x: x + shift,
+                        y,
+                        index,
+                    }),
+            );
+        }
+        TextLayout {
+            glyphs,
+            width: widest,
+            height: ascent - descent + (lines.len() - 1) as f32 * line_height,
+            lines: lines.len(),
+            size: options.size,
+        }
+    }
+
+    /// Returns the scaled kerning between two glyphs.
+    fn scaled_kerning(&self, left: GlyphId, right: GlyphId, scale: f32) -> f32 {
+        f32::from(self.kerning(left, right)) * scale
+    }
+
+    /// Maps the characters of a paragraph to glyphs.
+    fn paragraph_items(&self, paragraph: &str, offset: usize, scale: f32) -> Vec<Item> {
+        let space = self.glyph_index(' ').unwrap_or(GlyphId(0));
+        paragraph
+            .char_indices()
+            .filter(|&(_, character)| character != '\r')
+            .map(|(index, character)| {
+                let (glyph, advance) = if character == '\t' {
+                    let advance = f32::from(self.h_metrics(space).advance_width) * TAB_WIDTH;
+                    (space, advance)
+                } else {
+                    let glyph = self.glyph_index(character).unwrap_or(GlyphId(0));
+                    (glyph, f32::from(self.h_metrics(glyph).advance_width))
+                };
+                Item {
+                    glyph,
+                    index: offset + index,
+                    advance: advance * scale,
+                    whitespace: character.is_whitespace(),
+                }
+            })
+            .collect()
+    }
+
+    /// Splits a paragraph into lines.
+    fn break_lines(
+        &self,
+        items: &[Item],
+        scale: f32,
+        max_width: Option<f32>,
+        lines: &mut Vec<Line>,
+    ) {
+        let mut line_start = 0;
+        let mut x = 0.0;
+        // First item following the last whitespace of the line.
+        let mut break_point = None;
+        for (position, item) in items.iter().enumerate() {
+            let kerning = |position: usize, line_start: usize| {
+                if position > line_start {
+                    self.scaled_kerning(items[position - 1].glyph, items[position].glyph, scale)
+                } else {
+                    0.0
+                }
+            };
+            let overflows = max_width.map_or(false, |max_width| {
+                x + kerning(position, line_start) + item.advance > max_width
+            });
+            // Whitespace hangs past the end of lines instead of wrapping.
+            if overflows && !item.whitespace && position > line_start {
+                let start = match break_point {
+                    Some(start) if start > line_start => start,
+                    _ => position,
+                };
+                lines.push(self.line(&items[line_start..start], scale));
+                line_start = start;
+                break_point = None;
+                x = (line_start..position)
+                    .map(|other| kerning(other, line_start) + items[other].advance)
+                    .sum();
+            }
+            x += kerning(position, line_start) + item.advance;
+            if item.whitespace
+                && items
+                    .get(position + 1)
+                    .map_or(true, |next| !next.whitespace)
+            {
+                break_point = Some(position + 1);
+            }
+        }
+        lines.push(self.line(&items[line_start..], scale));
+    }
+
+    /// Positions the glyphs of a line.
+    fn line(&self, items: &[Item], scale: f32) -> Line {
+        let mut glyphs = Vec::with_capacity(items.len());
+        let mut x = 0.0;
+        let mut width = 0.0;
+        let mut previous: Option<GlyphId> = None;
+        for item in items {
+            if let Some(previous) = previous {
+                x += self.scaled_kerning(previous, item.glyph, scale);
+            }
+            glyphs.push((item.glyph, item.index, x));
+            x += item.advance;
+            if !item.whitespace {
+                width = x;
+            }
+            previous = Some(item.glyph);
+        }
+        Line { glyphs, width }
+    }
+}
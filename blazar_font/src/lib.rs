@@ -0,0 +1,278 @@
+//! TrueType font parsing, rasterization and text layout.
+
+mod atlas;
+mod cmap;
+mod distance;
+mod kerning;
+mod layout;
+mod outline;
+mod raster;
+mod reader;
+
+pub use atlas::*;
+pub use distance::*;
+pub use layout::*;
+pub use outline::*;
+pub use raster::*;
+
+use crate::reader::{i16_at, u16_at, u32_at};
+use std::{fs, io, ops::Range, path::Path};
+
+/// Kinds of font parsing errors.
+#[derive(Debug)]
+pub enum ParseFontError {
+    Io(io::Error),
+    Unsupported(String),
+    InvalidData(String),
+}
+
+impl From<io::Error> for ParseFontError {
+    fn from(error: io::Error) -> ParseFontError {
+        ParseFontError::Io(error)
+    }
+}
+
+/// Convenient result type consisting of a return type and a `ParseFontError`.
+pub type Result<T = ()> = std::result::Result<T, ParseFontError>;
+
+/// Index of a glyph in a font.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct GlyphId(pub u16);
+
+/// Horizontal metrics of a glyph, in font units.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct HMetrics {
+    pub advance_width: u16,
+    pub left_side_bearing: i16,
+}
+
+/// Represents a TrueType font with `glyf` outlines.
+pub struct Font {
+    data: Vec<u8>,
+    glyf: Range<usize>,
+    loca: Range<usize>,
+    hmtx: Range<usize>,
+    cmap: cmap::Cmap,
+    kerning: kerning::Kerning,
+    long_loca: bool,
+    glyph_count: u16,
+    h_metric_count: u16,
+    units_per_em: u16,
+    ascent: i16,
+    descent: i16,
+    line_gap: i16,
+}
+
+impl Font {
+    /// Parses a font from the content of a `.ttf` or `.otf` file, or the first font of a
+    /// `.ttc` collection.
+    pub fn from_bytes(data: Vec<u8>) -> Result<Font> {
+        let mut directory = 0;
+        match u32_at(&data, 0) {
+            Some(0x0001_0000) | Some(0x7472_7565) => {}
+            Some(0x7474_6366) => {
+                directory =
+                    u32_at(&data, 12).ok_or_else(|| invalid("Truncated collection"))? as usize;
+            }
+            Some(0x4f54_544f) => {
+                return Err(ParseFontError::Unsupported(String::from("CFF outlines")))
+            }
+            _ => return Err(invalid("Invalid font signature")),
+        }
+        let tables =
+            Tables::parse(&data, directory).ok_or_else(|| invalid("Invalid table directory"))?;
+        let head = tables.require(&data, b"head")?;
+        let maxp = tables.require(&data, b"maxp")?;
+        let hhea = tables.require(&data, b"hhea")?;
+        let units_per_em = u16_at(head, 18).ok_or_else(|| invalid("Invalid head table"))?;
+        let long_loca = i16_at(head, 50).ok_or_else(|| invalid("Invalid head table"))? != 0;
+        let glyph_count = u16_at(maxp, 4).ok_or_else(|| invalid("Invalid maxp table"))?;
+        let (ascent, descent, line_gap, h_metric_count) = match (
+            i16_at(hhea, 4),
+            i16_at(hhea, 6),
+            i16_at(hhea, 8),
+            u16_at(hhea, 34),
+        ) {
+            (Some(ascent), Some(descent), Some(line_gap), Some(count)) => {
+                (ascent, descent, line_gap, count)
+            }
+            _ => return Err(invalid("Invalid hhea table")),
+        };
+        if units_per_em == 0 || h_metric_count == 0 {
+            return Err(invalid("Invalid font metrics"));
+        }
+        let cmap = tables.get(b"cmap").ok_or_else(|| missing(b"cmap"))?;
+        let cmap = cmap::Cmap::parse(&data, cmap)
+            .ok_or_else(|| ParseFontError::Unsupported(String::from("Character map")))?;
+        let kerning = kerning::Kerning::parse(&data, tables.get(b"GPOS"), tables.get(b"kern"));
+        Ok(Font {
+            glyf: tables.get(b"glyf").ok_or_else(|| missing(b"glyf"))?,
+            loca: tables.get(b"loca").ok_or_else(|| missing(b"loca"))?,
+            hmtx: tables.get(b"hmtx").ok_or_else(|| missing(b"hmtx"))?,
+            cmap,
+            kerning,
+            long_loca,
+            glyph_count,
+            h_metric_count,
+            units_per_em,
+            ascent,
+            descent,
+            line_gap,
+            data,
+        })
+    }
+
+    /// Opens and parses a font file.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Font> {
+        Font::from_bytes(fs::read(path)?)
+    }
+
+    /// Returns the number of font units per em.
+    pub fn units_per_em(&self) -> u16 {
+        self.units_per_em
+    }
+
+    /// Returns the distance from the baseline to the top of the highest glyphs, in font
+    /// units.
+    pub fn ascent(&self) -> i16 {
+        self.ascent
+    }
+
+    /// Returns the distance from the baseline to the bottom of the lowest glyphs, in font
+    /// units, usually negative.
+    pub fn descent(&self) -> i16 {
+        self.descent
+    }
+
+    /// Returns the gap between two lines, in font units.
+    pub fn line_gap(&self) -> i16 {
+        self.line_gap
+    }
+
+    /// Returns the number of glyphs.
+    pub fn glyph_count(&self) -> u16 {
+        self.glyph_count
+    }
+
+    /// Returns the factor converting font units to pixels for a given size in pixels per
+    /// em.
+    pub fn scale(&self, size: f32) -> f32 {
+        size / f32::from(self.units_per_em)
+    }
+
+    /// Returns the glyph of a character, if the font has one.
+    pub fn glyph_index(&self, character: char) -> Option<GlyphId> {
+        self.cmap
+            .glyph_index(&self.data, character as u32)
+            .filter(|glyph| *glyph != 0 && *glyph < self.glyph_count)
+            .map(GlyphId)
+    }
+
+    /// Returns the horizontal metrics of a glyph.
+    pub fn h_metrics(&self, glyph: GlyphId) -> HMetrics {
+        let hmtx = &self.data[self.hmtx.clone()];
+        let count = self.h_metric_count as usize;
+        let index = glyph.0 as usize;
+        let advance_width = u16_at(hmtx, 4 * index.min(count - 1)).unwrap_or(0);
+        let left_side_bearing = if index < count {
+            i16_at(hmtx, 4 * index + 2)
+        } else {
+            i16_at(hmtx, 4 * count + 2 * (index - count))
+        };
+        HMetrics {
+            advance_width,
+            left_side_bearing: left_side_bearing.unwrap_or(0),
+        }
+    }
+
+    /// Returns the kerning adjustment between two glyphs, in font units.
+    pub fn kerning(&self, left: GlyphId, right: GlyphId) -> i16 {
+        self.kerning.get(&self.data, left.0, right.0)
+    }
+
+    /// Returns the outline of a glyph, in font units with the y-axis pointing up, or `None`
+    /// if its data is invalid.
+    pub fn outline(&self, glyph: GlyphId) -> Option<Outline> {
+        let mut outline = Outline::default();
+        let mut budget = outline::MAX_VISITED_GLYPHS;
+        outline::append_glyph(
+            self,
+            glyph.0,
+            outline::Transform::IDENTITY,
+            0,
+            &mut budget,
+            &mut outline,
+        )?;
+        outline.update_bounds();
+        Some(outline)
+    }
+
+    /// Returns the `glyf` data of a glyph, empty for glyphs without outline.
+    fn glyph_data(&self, glyph: u16) -> Option<&[u8]> {
+        if glyph >= self.glyph_count {
+            return None;
+        }
+        let loca = &self.data[self.loca.clone()];
+        let index = glyph as usize;
+        let (start, end) = if self.long_loca {
+            (
+                u32_at(loca, index * 4)? as usize,
+                u32_at(loca, index * 4 + 4)? as usize,
+            )
+        } else {
+            (
+                u16_at(loca, index * 2)? as usize * 2,
+                u16_at(loca, index * 2 + 2)? as usize * 2,
+            )
+        };
+        self.data[self.glyf.clone()].get(start..end)
+    }
+}
+
+/// Table directory of a font.
+struct Tables {
+    records: Vec<([u8; 4], Range<usize>)>,
+}
+
+impl Tables {
+    /// Parses the table directory at `offset`.
+    fn parse(data: &[u8], offset: usize) -> Option<Tables> {
+        let count = u16_at(data, offset + 4)? as usize;
+        let mut records = Vec::with_capacity(count);
+        for index in 0..count {
+            let record = offset + 12 + index * 16;
+            let tag = data.get(record..record + 4)?;
+            let start = u32_at(data, record + 8)? as usize;
+            let end = start.checked_add(u32_at(data, record + 12)? as usize)?;
+            if end <= data.len() {
+                records.push(([tag[0], tag[1], tag[2], tag[3]], start..end));
+            }
+        }
+        Some(Tables { records })
+    }
+
+    /// Returns the range of a table.
+    fn get(&self, tag: &[u8; 4]) -> Option<Range<usize>> {
+        self.records
+            .iter()
+            .find(|(other, _)| other == tag)
+            .map(|(_, range)| range.clone())
+    }
+
+    /// Returns the content of a table, or an error if it is missing.
+    fn require<'a>(&self, data: &'a [u8], tag: &[u8; 4]) -> Result<&'a [u8]> {
+        self.get(tag)
+            .map(|range| &data[range])
+            .ok_or_else(|| missing(tag))
+    }
+}
+
+/// Creates an invalid data error.
+fn invalid(message: &str) -> ParseFontError {
+    ParseFontError::InvalidData(String::from(message))
+}
+
+/// Creates a missing table error.
+fn missing(tag: &[u8; 4]) -> ParseFontError {
+    ParseFontError::InvalidData(format!("Missing {} table", String::from_utf8_lossy(tag)))
+}
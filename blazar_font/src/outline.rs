@@ -0,0 +1,429 @@
+//! Glyph outlines (`glyf` table).
+
+use crate::{
+    reader::{f2dot14_at, i16_at, u16_at, u8_at},
+    Font,
+};
+
+/// Maximum nesting of composite glyphs.
+const MAX_COMPONENT_DEPTH: u32 = 8;
+
+/// Maximum number of glyphs visited while building an outline, composite glyphs
+/// included.
+pub(crate) const MAX_VISITED_GLYPHS: u32 = 1024;
+
+// Simple glyph flags.
+const ON_CURVE: u8 = 0x01;
+const X_SHORT: u8 = 0x02;
+const Y_SHORT: u8 = 0x04;
+const REPEAT: u8 = 0x08;
+const X_SAME_OR_POSITIVE: u8 = 0x10;
+const Y_SAME_OR_POSITIVE: u8 = 0x20;
+
+// Composite glyph flags.
+const ARGS_ARE_WORDS: u16 = 0x0001;
+const ARGS_ARE_XY_VALUES: u16 = 0x0002;
+const HAS_SCALE: u16 = 0x0008;
+const MORE_COMPONENTS: u16 = 0x0020;
+const HAS_XY_SCALE: u16 = 0x0040;
+const HAS_2X2: u16 = 0x0080;
+
+/// Point of an outline.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Point {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Point {
+    /// Creates a point.
+    pub fn new(x: f32, y: f32) -> Point {
+        Point { x, y }
+    }
+
+    /// Returns the point halfway to `other`.
+    pub fn midpoint(self, other: Point) -> Point {
+        Point::new((self.x + other.x) / 2.0, (self.y + other.y) / 2.0)
+    }
+}
+
+/// Segment of a contour.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Segment {
+    Line(Point, Point),
+    /// Quadratic Bézier curve from its start point, control point and end point.
+    Quad(Point, Point, Point),
+}
+
+impl Segment {
+    /// Returns the start point.
+    pub fn start(&self) -> Point {
+        match *self {
+            Segment::Line(start, _) | Segment::Quad(start, _, _) => start,
+        }
+    }
+
+    /// Returns the end point.
+    pub fn end(&self) -> Point {
+        match *self {
+            Segment::Line(_, end) | Segment::Quad(_, _, end) => end,
+        }
+    }
+
+    /// Returns the point at parameter `t` in `[0, 1]`.
+    pub fn point(&self, t: f32) -> Point {
+        match *self {
+            Segment::Line(start, end) => Point::new(
+                start.x + (end.x - start.x) * t,
+                start.y + (end.y - start.y) * t,
+            ),
+            Segment::Quad(start, control, end) => {
+                let u = 1.0 - t;
+                Point::new(
+                    u * u * start.x + 2.0 * u * t * control.x + t * t * end.x,
+                    u * u * start.y + 2.0 * u * t * control.y + t * t * end.y,
+                )
+            }
+        }
+    }
+
+    /// Returns a copy with every point transformed by `map`.
+    pub fn map<F: Fn(Point) -> Point>(&self, map: F) -> Segment {
+        match *self {
+            Segment::Line(start, end) => Segment::Line(map(start), map(end)),
+            Segment::Quad(start, control, end) => Segment::Quad(map(start), map(control), map(end)),
+        }
+    }
+}
+
+/// Axis-aligned bounding box.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Bounds {
+    pub min: Point,
+    pub max: Point,
+}
+
+/// Outline of a glyph made of closed contours, the non-zero winding rule telling its
+/// inside.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Outline {
+    pub contours: Vec<Vec<Segment>>,
+    /// Bounds of every point, control points included.
+    pub bounds: Bounds,
+}
+
+impl Outline {
+    /// Returns `true` if the outline has no contours.
+    pub fn is_empty(&self) -> bool {
+        self.contours.is_empty()
+    }
+
+    /// Returns an iterator over the segments of all contours.
+    pub fn segments(&self) -> impl Iterator<Item = &Segment> {
+        self.contours.iter().flatten()
+    }
+
+    /// Recomputes the bounds from the segments.
+    pub(crate) fn update_bounds(&mut self) {
+        let mut bounds: Option<Bounds> = None;
+        for segment in self.segments() {
+            let points = match *segment {
+                Segment::Line(start, end) => [start, end, end],
+                Segment::Quad(start, control, end) => [start, control, end],
+            };
+            for point in &points {
+                let bounds = bounds.get_or_insert(Bounds {
+                    min: *point,
+                    max: *point,
+                });
+                bounds.min = Point::new(bounds.min.x.min(point.x), bounds.min.y.min(point.y));
+                bounds.max = Point::new(bounds.max.x.max(point.x), bounds.max.y.max(point.y));
+            }
+        }
+        self.bounds = bounds.unwrap_or_default();
+    }
+}
+
+/// Affine transform of composite glyph components.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Transform {
+    matrix: [f32; 4],
+    offset: [f32; 2],
+}
+
+impl Transform {
+    pub(crate) const IDENTITY: Transform = Transform {
+        matrix: [1.0, 0.0, 0.0, 1.0],
+        offset: [0.0, 0.0],
+    };
+
+    /// Applies the transform to a point.
+    fn apply(&self, x: f32, y: f32) -> Point {
+        Point::new(
+            self.matrix[0] * x + self.matrix[2] * y + self.offset[0],
+            self.matrix[1] * x + self.matrix[3] * y + self.offset[1],
+        )
+    }
+
+    /// Returns the transform applying `self` after `inner`.
+    fn then(&self, inner: &Transform) -> Transform {
+        let [a, b, c, d] = self.matrix;
+        let [e, f, g, h] = inner.matrix;
+        Transform {
+            matrix: [a * e + c * f, b * e + d * f, a * g + c * h, b * g + d * h],
+            offset: {
+                let offset = self.apply(inner.offset[0], inner.offset[1]);
+                [offset.x, offset.y]
+            },
+        }
+    }
+}
+
+/// Appends the contours of a glyph to an outline.
+pub(crate) fn append_glyph(
+    font: &Font,
+    glyph: u16,
+    transform: Transform,
+    depth: u32,
+    budget: &mut u32,
+    outline: &mut Outline,
+) -> Option<()> {
+    *budget = budget.checked_sub(1)?;
+    let data = font.glyph_data(glyph)?;
+    if data.is_empty() {
+        return Some(());
+    }
+    let contour_count = i16_at(data, 0)?;
+    if contour_count >= 0 {
+        append_simple(data, contour_count as usize, &transform, outline)
+    } else if depth < MAX_COMPONENT_DEPTH {
+        append_composite(font, data, &transform, depth, budget, outline)
+    } else {
+        None
+    }
+}
+
+/// Appends the contours of a simple glyph.
+fn append_simple(
+    data: &[u8],
+    contour_count: usize,
+    transform: &Transform,
+    outline: &mut Outline,
+) -> Option<()> {
+    let mut end_points = Vec::with_capacity(contour_count);
+    for contour in 0..contour_count {
+        end_points.push(u16_at(data, 10 + contour * 2)? as usize);
+    }
+    let point_count = match end_points.last() {
+        Some(last) => last + 1,
+        None => return Some(()),
+    };
+    let instructions = 10 + contour_count * 2;
+    let mut offset = instructions + 2 + u16_at(data, instructions)? as usize;
+
+    // Reads flags.
+    let mut flags = Vec::with_capacity(point_count);
+    while flags.len() < point_count {
+        let flag = u8_at(data, offset)?;
+        offset += 1;
+        let mut count = 1;
+        if flag & REPEAT != 0 {
+            count += u8_at(data, offset)? as usize;
+            offset += 1;
+        }
+        for _ in 0..count.min(point_count - flags.len()) {
+            flags.push(flag);
+        }
+    }
+
+    // Reads coordinates.
+    let mut xs = Vec::with_capacity(point_count);
+    let mut value = 0i16;
+    for flag in &flags {
+        if flag & X_SHORT != 0 {
+            let delta = i16::from(u8_at(data, offset)?);
+            offset += 1;
+            value = value.wrapping_add(if flag & X_SAME_OR_POSITIVE != 0 {
+                delta
+            } else {
+                -delta
+            });
+        } else if flag & X_SAME_OR_POSITIVE == 0 {
+            value = value.wrapping_add(i16_at(data, offset)?);
+            offset += 2;
+        }
+        xs.push(value);
+    }
+    let mut ys = Vec::with_capacity(point_count);
+    let mut value = 0i16;
+    for flag in &flags {
+        if flag & Y_SHORT != 0 {
+            let delta = i16::from(u8_at(data, offset)?);
+            offset += 1;
+            value = value.wrapping_add(if flag & Y_SAME_OR_POSITIVE != 0 {
+                delta
+            } else {
+                -delta
+            });
+        } else if flag & Y_SAME_OR_POSITIVE == 0 {
+            value = value.wrapping_add(i16_at(data, offset)?);
+            offset += 2;
+        }
+        ys.push(value);
+    }
+
+    let mut start = 0;
+    for end in end_points {
+        if end < start || end >= point_count {
+            return None;
+        }
+        let points: Vec<(Point, bool)> = (start..=end)
+            .map(|index| {
+                (
+                    transform.apply(f32::from(xs[index]), f32::from(ys[index])),
+                    flags[index] & ON_CURVE != 0,
+                )
+            })
+            .collect();
+        let contour = contour_segments(&points);
+        if !contour.is_empty() {
+            outline.contours.push(contour);
+        }
+        start = end + 1;
+    }
+    Some(())
+}
+
+/// Converts the points of a contour to segments, inserting the on-curve points implied
+/// between consecutive off-curve points.
+fn contour_segments(points: &[(Point, bool)]) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    if points.len() < 2 {
+        return segments;
+    }
+    // Starts from an on-curve point, implied if needed.
+    let first = match points.iter().position(|(_, on_curve)| *on_curve) {
+        Some(index) => index,
+        None => {
+            let start = points[0].0.midpoint(points[1].0);
+            return quads_from(start, 1, points);
+        }
+    };
+    let start = points[first].0;
+    let mut current = start;
+    let mut control: Option<Point> = None;
+    for step in 1..=points.len() {
+        let (point, on_curve) = points[(first + step) % points.len()];
+        match (on_curve, control) {
+            (true, None) => {
+                segments.push(Segment::Line(current, point));
+                current = point;
+            }
+            (true, Some(control_point)) => {
+                segments.push(Segment::Quad(current, control_point, point));
+                current = point;
+                control = None;
+            }
+            (false, None) => control = Some(point),
+            (false, Some(control_point)) => {
+                let middle = control_point.midpoint(point);
+                segments.push(Segment::Quad(current, control_point, middle));
+                current = middle;
+                control = Some(point);
+            }
+        }
+    }
+    segments
+        .retain(|segment| segment.start() != segment.end() || matches!(segment, Segment::Quad(..)));
+    segments
+}
+
+/// Converts a contour made only of off-curve points, starting from the implied point
+/// preceding `points[first]`.
+fn quads_from(start: Point, first: usize, points: &[(Point, bool)]) -> Vec<Segment> {
+    let mut segments = Vec::with_capacity(points.len());
+    let mut current = start;
+    for step in 0..points.len() {
+        let control = points[(first + step) % points.len()].0;
+        let next = points[(first + step + 1) % points.len()].0;
+        let end = if step + 1 == points.len() {
+            start
+        } else {
+            control.midpoint(next)
+        };
+        segments.push(Segment::Quad(current, control, end));
+        current = end;
+    }
+    segments
+}
+
+/// Appends the components of a composite glyph.
+fn append_composite(
+    font: &Font,
+    data: &[u8],
+    transform: &Transform,
+    depth: u32,
+    budget: &mut u32,
+    outline: &mut Outline,
+) -> Option<()> {
+    let mut offset = 10;
+    loop {
+        let flags = u16_at(data, offset)?;
+        let glyph = u16_at(data, offset + 2)?;
+        offset += 4;
+        let (dx, dy) = if flags & ARGS_ARE_WORDS != 0 {
+            offset += 4;
+            (i16_at(data, offset - 4)?, i16_at(data, offset - 2)?)
+        } else {
+            offset += 2;
+            (
+                i16::from(u8_at(data, offset - 2)? as i8),
+                i16::from(u8_at(data, offset - 1)? as i8),
+            )
+        };
+        let mut matrix = [1.0, 0.0, 0.0, 1.0];
+        if flags & HAS_SCALE != 0 {
+            let scale = f2dot14_at(data, offset)?;
+            matrix = [scale, 0.0, 0.0, scale];
+            offset += 2;
+        } else if flags & HAS_XY_SCALE != 0 {
+            matrix = [
+                f2dot14_at(data, offset)?,
+                0.0,
+                0.0,
+                f2dot14_at(data, offset + 2)?,
+            ];
+            offset += 4;
+        } else if flags & HAS_2X2 != 0 {
+            matrix = [
+                f2dot14_at(data, offset)?,
+                f2dot14_at(data, offset + 2)?,
+                f2dot14_at(data, offset + 4)?,
+                f2dot14_at(data, offset + 6)?,
+            ];
+            offset += 8;
+        }
+        // Matching points instead of offsets are not supported, components are then
+        // left in place.
+        let component_offset = if flags & ARGS_ARE_XY_VALUES != 0 {
+            [f32::from(dx), f32::from(dy)]
+        } else {
+            [0.0, 0.0]
+        };
+        let component = Transform {
+            matrix,
+            offset: component_offset,
+        };
+        append_glyph(
+            font,
+            glyph,
+            transform.then(&component),
+            depth + 1,
+            budget,
+            outline,
+        )?;
+        if flags & MORE_COMPONENTS == 0 {
+            return Some(());
+        }
+    }
+}
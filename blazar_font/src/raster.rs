@@ -0,0 +1,188 @@
+//! Anti-aliased glyph rasterization.
+
+use crate::{Font, GlyphId, Outline, Point, Segment};
+
+/// Maximum width and height of a glyph bitmap.
+const MAX_BITMAP_SIZE: f32 = 4096.0;
+
+/// Maximum distance in pixels between a curve and its flattened approximation.
+const FLATNESS: f32 = 0.1;
+
+/// Coverage bitmap of a glyph, top row first.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct GlyphBitmap {
+    pub width: u32,
+    pub height: u32,
+    /// Horizontal distance in pixels from the pen position to the left edge.
+    pub left: i32,
+    /// Vertical distance in pixels from the baseline up to the top edge.
+    pub top: i32,
+    /// Number of bytes per pixel.
+    pub channels: u32,
+    /// Coverage of each pixel from 0 to 255, or its distance to the outline for distance
+    /// fields.
+    pub data: Vec<u8>,
+}
+
+impl Font {
+    /// Rasterizes a glyph at `size` pixels per em.
+    ///
+    /// Glyphs without outline, or whose outline is invalid or too large, give an empty
+    /// bitmap.
+    pub fn rasterize(&self, glyph: GlyphId, size: f32) -> GlyphBitmap {
+        match self.outline(glyph) {
+            Some(outline) => rasterize_outline(&outline, self.scale(size)),
+            None => GlyphBitmap::default(),
+        }
+    }
+}
+
+/// Rasterizes an outline in font units, scaled by `scale`.
+pub fn rasterize_outline(outline: &Outline, scale: f32) -> GlyphBitmap {
+    if outline.is_empty() || scale.is_nan() || scale <= 0.0 {
+        return GlyphBitmap::default();
+    }
+    let left = (outline.bounds.min.x * scale).floor();
+    let right = (outline.bounds.max.x * scale).ceil();
+    let top = (outline.bounds.max.y * scale).ceil();
+    let bottom = (outline.bounds.min.y * scale).floor();
+    let (width, height) = (right - left, top - bottom);
+    let valid = |extent: f32| (1.0..=MAX_BITMAP_SIZE).contains(&extent);
+    if !valid(width) || !valid(height) {
+        return GlyphBitmap::default();
+    }
+    let mut rasterizer = Rasterizer::new(width as usize, height as usize);
+    let transform = |point: Point| Point::new(point.x * scale - left, top - point.y * scale);
+    for segment in outline.segments() {
+        match segment.map(transform) {
+            Segment::Line(start, end) => rasterizer.line(start, end),
+            Segment::Quad(start, control, end) => rasterizer.quad(start, control, end),
+        }
+    }
+    GlyphBitmap {
+        width: width as u32,
+        height: height as u32,
+        left: left as i32,
+        top: top as i32,
+        channels: 1,
+        data: rasterizer.coverage(),
+    }
+}
+
+/// Returns the number of lines approximating a quadratic curve within the flatness
+/// tolerance, its points being in pixels.
+pub(crate) fn flattening_steps(start: Point, control: Point, end: Point) -> usize {
+    let deviation_x = start.x - 2.0 * control.x + end.x;
+    let deviation_y = start.y - 2.0 * control.y + end.y;
+    let deviation = (deviation_x * deviation_x + deviation_y * deviation_y).sqrt();
+    ((deviation / (8.0 * FLATNESS)).sqrt().ceil() as usize)
+        .max(1)
+        .min(64)
+}
+
+/// Computes exact area coverage by accumulating signed areas of line segments, then
+/// summing them along rows.
+struct Rasterizer {
+    width: usize,
+    height: usize,
+    areas: Vec<f32>,
+}
+
+impl Rasterizer {
+    fn new(width: usize, height: usize) -> Rasterizer {
+        Rasterizer {
+            width,
+            height,
+            // Lines may touch the cell following the last pixel.
+            areas: vec![0.0; width * height + 2],
+        }
+    }
+
+    /// Adds a line, its points lying within the bitmap.
+    fn line(&mut self, start: Point, end: Point) {
+        let clamp = |point: Point| {
+            Point::new(
+                point.x.max(0.0).min(self.width as f32),
+                point.y.max(0.0).min(self.height as f32),
+            )
+        };
+        let (start, end) = (clamp(start), clamp(end));
+        if (start.y - end.y).abs() <= f32::EPSILON {
+            return;
+        }
+        let (direction, top, bottom) = if start.y < end.y {
+            (1.0, start, end)
+        } else {
+            (-1.0, end, start)
+        };
+        let slope = (bottom.x - top.x) / (bottom.y - top.y);
+        let mut x = top.x;
+        let first_row = top.y as usize;
+        let last_row = (bottom.y.ceil() as usize).min(self.height);
+        for row in first_row..last_row {
+            let row_start = row * self.width;
+            let dy = ((row + 1) as f32).min(bottom.y) - (row as f32).max(top.y);
+            let next_x = x + slope * dy;
+            let area = dy * direction;
+            let (x0, x1) = if x < next_x { (x, next_x) } else { (next_x, x) };
+            let x0_floor = x0.floor();
+            let x0_cell = x0_floor as usize;
+            let x1_ceil = x1.ceil();
+            let x1_cell = x1_ceil as usize;
+            if x1_cell <= x0_cell + 1 {
+                // The line crosses a single cell of the row.
+                let middle = 0.5 * (x + next_x) - x0_floor;
+                self.areas[row_start + x0_cell] += area - area * middle;
+                self.areas[row_start + x0_cell + 1] += area * middle;
+            } else {
+                let inverse_width = (x1 - x0).recip();
+                let x0_fraction = x0 - x0_floor;
+                let first = 0.5 * inverse_width * (1.0 - x0_fraction) * (1.0 - x0_fraction);
+                let x1_fraction = x1 - x1_ceil + 1.0;
+                let last = 0.5 * inverse_width * x1_fraction * x1_fraction;
+                self.areas[row_start + x0_cell] += area * first;
+                if x1_cell == x0_cell + 2 {
+                    self.areas[row_start + x0_cell + 1] += area * (1.0 - first - last);
+                } else {
+                    let second = inverse_width * (1.5 - x0_fraction);
+                    self.areas[row_start + x0_cell + 1] += area * (second - first);
+                    for cell in x0_cell + 2..x1_cell - 1 {
+                        self.areas[row_start + cell] += area * inverse_width;
+                    }
+                    let covered = second + (x1_cell - x0_cell - 3) as f32 * inverse_width;
+                    self.areas[row_start + x1_cell - 1] += area * (1.0 - covered - last);
+                }
+                self.areas[row_start + x1_cell] += area * last;
+            }
+            x = next_x;
+        }
+    }
+
+    /// Adds a quadratic curve, flattened into lines.
+    fn quad(&mut self, start: Point, control: Point, end: Point) {
+        let count = flattening_steps(start, control, end);
+        let segment = Segment::Quad(start, control, end);
+        let mut previous = start;
+        for step in 1..=count {
+            let point = if step == count {
+                end
+            } else {
+                segment.point(step as f32 / count as f32)
+            };
+            self.line(previous, point);
+            previous = point;
+        }
+    }
+
+    /// Returns the coverage of each pixel.
+    fn coverage(&self) -> Vec<u8> {
+        let mut sum = 0.0;
+        self.areas[..self.width * self.height]
+            .iter()
+            .map(|area| {
+                sum += area;
+                (sum.abs().min(1.0) * 255.0 + 0.5) as u8
+            })
+            .collect()
+    }
+}
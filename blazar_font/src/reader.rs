@@ -0,0 +1,28 @@
+//! Big-endian reads of font data.
+
+/// Reads a `u8` at `offset`.
+pub(crate) fn u8_at(data: &[u8], offset: usize) -> Option<u8> {
+    data.get(offset).cloned()
+}
+
+/// Reads a `u16` at `offset`.
+pub(crate) fn u16_at(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset.checked_add(2)?)?;
+    Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+/// Reads an `i16` at `offset`.
+pub(crate) fn i16_at(data: &[u8], offset: usize) -> Option<i16> {
+    u16_at(data, offset).map(|value| value as i16)
+}
+
+/// Reads a `u32` at `offset`.
+pub(crate) fn u32_at(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset.checked_add(4)?)?;
+    Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+/// Reads a 2.14 fixed-point number at `offset`.
+pub(crate) fn f2dot14_at(data: &[u8], offset: usize) -> Option<f32> {
+    i16_at(data, offset).map(|value| f32::from(value) / 16384.0)
+}
@@ -0,0 +1,203 @@
+use blazar_font::{AtlasRegion, Font, GlyphAtlas, GlyphId, GlyphKey, GlyphMode, LayoutOptions};
+
+/// Font with the glyphs ` `, `A`, `B`, `O` and `V`, `B` being an `O` component moved
+/// 50 units right, and kerning between `A` and `V`.
+fn font() -> Font {
+    Font::from_bytes(include_bytes!("data/glyphs.ttf").to_vec()).unwrap()
+}
+
+#[test]
+fn metrics() {
+    let font = font();
+    assert_eq!(font.units_per_em(), 1000);
+    assert_eq!(
+        (font.ascent(), font.descent(), font.line_gap()),
+        (800, -200, 100)
+    );
+    assert_eq!(font.glyph_count(), 6);
+    assert_eq!(font.glyph_index(' '), Some(GlyphId(1)));
+    assert_eq!(font.glyph_index('A'), Some(GlyphId(2)));
+    assert_eq!(font.glyph_index('V'), Some(GlyphId(5)));
+    assert_eq!(font.glyph_index('Z'), None);
+    assert_eq!(font.glyph_index('😀'), None);
+    let metrics = font.h_metrics(GlyphId(4));
+    assert_eq!(
+        (metrics.advance_width, metrics.left_side_bearing),
+        (600, 100)
+    );
+}
+
+#[test]
+fn outlines() {
+    let font = font();
+    let o = font.outline(GlyphId(4)).unwrap();
+    assert_eq!(o.contours.len(), 2);
+    let b = font.outline(GlyphId(3)).unwrap();
+    assert_eq!(b.contours.len(), 2);
+    assert_eq!(b.bounds.min.x, o.bounds.min.x + 50.0);
+    assert_eq!(b.bounds.max.y, o.bounds.max.y);
+    // Bounds include the control point of the curve of `V`.
+    let v = font.outline(GlyphId(5)).unwrap();
+    assert_eq!(v.bounds.max.y, 800.0);
+    assert!(font.outline(GlyphId(1)).unwrap().is_empty());
+    assert!(font.outline(GlyphId(6)).is_none());
+}
+
+#[test]
+fn coverage() {
+    let font = font();
+    let o = font.rasterize(GlyphId(4), 100.0);
+    assert_eq!((o.width, o.height, o.left, o.top), (40, 70, 10, 70));
+    assert_eq!(o.channels, 1);
+    let pixel = |x: u32, y: u32| o.data[(y * o.width + x) as usize];
+    // Inside the ring, in the counter and on the border between them.
+    assert_eq!(pixel(5, 35), 255);
+    assert_eq!(pixel(20, 35), 0);
+    assert_eq!(pixel(5, 5), 255);
+    let a = font.rasterize(GlyphId(2), 100.0);
+    let pixel = |x: u32, y: u32| a.data[(y * a.width + x) as usize];
+    assert_eq!(pixel(a.width / 2, a.height - 1), 255);
+    assert_eq!(pixel(0, 0), 0);
+    assert_eq!(pixel(a.width - 1, 0), 0);
+    let empty = font.rasterize(GlyphId(1), 100.0);
+    assert_eq!((empty.width, empty.height), (0, 0));
+}
+
+#[test]
+fn distance_field_sign() {
+    let font = font();
+    // Padded by 4 pixels, pixel centers of column `c` and row `r` are at `6.5 + c` and
+    // `73.5 - r`; the ring spans 10 to 50 horizontally, its counter 20 to 40.
+    let sdf = font.rasterize_sdf(GlyphId(4), 100.0, 4.0);
+    assert_eq!(sdf.channels, 1);
+    let pixel = |x: u32, y: u32| sdf.data[(y * sdf.width + x) as usize];
+    // Half a pixel outside and inside the left edge.
+    assert_eq!(pixel(3, 39), 112);
+    assert_eq!(pixel(4, 39), 143);
+    // Deep inside the ring, in the counter and outside the glyph.
+    assert_eq!(pixel(8, 39), 255);
+    assert_eq!(pixel(23, 39), 0);
+    assert_eq!(pixel(0, 0), 0);
+
+    let msdf = font.rasterize_msdf(GlyphId(4), 100.0, 4.0);
+    assert_eq!(msdf.channels, 4);
+    let median = |x: u32, y: u32| {
+        let index = ((y * msdf.width + x) * 4) as usize;
+        let mut rgb = [msdf.data[index], msdf.data[index + 1], msdf.data[index + 2]];
+        rgb.sort();
+        rgb[1]
+    };
+    assert!(median(3, 39) < 128);
+    assert!(median(4, 39) > 127);
+    assert!(median(8, 39) > 127);
+    assert!(median(23, 39) < 128);
+    assert!(median(0, 0) < 128);
+    // The alpha channel holds the true distance.
+    let alpha: Vec<u8> = msdf.data.chunks(4).map(|pixel| pixel[3]).collect();
+    assert_eq!(alpha, sdf.data);
+}
+
+#[test]
+fn distance_field_dimensions() {
+    let font = font();
+    // The coverage bitmap is 40x70 at (10, 70), padded by the range rounded up.
+    for &(range, padding) in &[(1.0, 1), (2.5, 3), (4.0, 4)] {
+        for &mode in &[
+            GlyphMode::Sdf { size: 100.0, range },
+            GlyphMode::Msdf { size: 100.0, range },
+        ] {
+            let field = font.render(GlyphId(4), 50.0, mode);
+            assert_eq!(
+                (field.width, field.height, field.left, field.top),
+                (
+                    40 + 2 * padding,
+                    70 + 2 * padding,
+                    10 - padding as i32,
+                    70 + padding as i32
+                )
+            );
+            assert_eq!(field.channels, mode.channels());
+            assert_eq!(
+                field.data.len(),
+                (field.width * field.height * field.channels) as usize
+            );
+        }
+    }
+    assert_eq!(font.rasterize_sdf(GlyphId(1), 100.0, 4.0).width, 0);
+    assert_eq!(font.rasterize_sdf(GlyphId(4), 100.0, 0.0).width, 0);
+}
+
+#[test]
+fn kerning() {
+    let font = font();
+    let (a, v) = (GlyphId(2), GlyphId(5));
+    assert_eq!(font.kerning(a, v), -80);
+    assert_eq!(font.kerning(v, a), -60);
+    assert_eq!(font.kerning(a, a), 0);
+    let layout = font.layout("AV", &LayoutOptions::new(100.0));
+    assert_eq!(layout.glyphs[1].x, 52.0);
+    assert_eq!(layout.width, 112.0);
+    assert_eq!(font.layout("AA", &LayoutOptions::new(100.0)).width, 120.0);
+}
+
+#[test]
+fn line_wrapping() {
+    let font = font();
+    let layout = font.layout("AV AV AV", &LayoutOptions::new(100.0).max_width(130.0));
+    assert_eq!(layout.lines, 3);
+    assert_eq!(layout.width, 112.0);
+    // Lines are 110 pixels apart: ascent, descent and line gap.
+    assert_eq!(layout.height, 320.0);
+    let second = layout.glyphs.iter().find(|glyph| glyph.index == 3).unwrap();
+    assert_eq!((second.x, second.y), (0.0, 190.0));
+
+    // Words wider than the maximum width break between characters.
+    let layout = font.layout("AVAV", &LayoutOptions::new(100.0).max_width(130.0));
+    assert_eq!(layout.lines, 2);
+    let layout = font.layout("AV\nAV", &LayoutOptions::new(100.0));
+    assert_eq!(layout.lines, 2);
+    assert_eq!(layout.glyphs[2].index, 3);
+}
+
+#[test]
+fn atlas_growth_and_eviction() {
+    let font = font();
+    let mut atlas = GlyphAtlas::new(32, 16, 64);
+    let first = GlyphKey::new(0, GlyphId(4), 20.0);
+    let location = atlas.get_or_insert(first, &font).unwrap();
+    assert_eq!((location.width, location.height), (8, 14));
+    assert_eq!(
+        atlas.take_dirty_region(),
+        Some(AtlasRegion {
+            x: location.x,
+            y: location.y,
+            width: 8,
+            height: 14,
+        })
+    );
+    let mut size = 21.0;
+    while atlas
+        .get_or_insert(GlyphKey::new(0, GlyphId(4), size), &font)
+        .is_some()
+    {
+        size += 1.0;
+    }
+    // The atlas grew up to its maximum height, keeping glyph locations.
+    assert_eq!(atlas.height(), 64);
+    assert_eq!(atlas.data().len(), 32 * 64);
+    assert!(atlas.len() > 2);
+    assert_eq!(atlas.get(&first), Some(location));
+    assert!(atlas.take_dirty_region().is_some());
+
+    // Clearing evicts every glyph, making room for new ones.
+    atlas.clear();
+    assert!(atlas.is_empty());
+    assert_eq!(atlas.get(&first), None);
+    let key = GlyphKey::new(0, GlyphId(4), size);
+    assert!(atlas.get_or_insert(key, &font).is_some());
+    // Empty glyphs take no room.
+    let space = atlas
+        .get_or_insert(GlyphKey::new(0, GlyphId(1), 20.0), &font)
+        .unwrap();
+    assert_eq!((space.width, space.height), (0, 0));
+}
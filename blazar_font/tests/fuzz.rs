@@ -0,0 +1,60 @@
+use blazar_font::{Font, GlyphId, GlyphMode, LayoutOptions};
+
+/// Deterministic pseudo-random number generator.
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+}
+
+/// Parses randomly mutated valid fonts, checking that parsing, rasterizing and laying
+/// out text never panic.
+#[test]
+fn fuzz_font() {
+    let file = include_bytes!("data/glyphs.ttf");
+    let mut random = XorShift(1);
+    for _ in 0..2000 {
+        let mut data = file.to_vec();
+        for _ in 0..1 + random.below(8) {
+            let index = random.below(data.len());
+            match random.below(4) {
+                0 => data[index] = random.next() as u8,
+                1 => data[index] ^= 1 << random.below(8),
+                2 => data.truncate(index.max(1)),
+                _ => {
+                    let byte = random.next() as u8;
+                    data.insert(index, byte);
+                }
+            }
+        }
+        if let Ok(font) = Font::from_bytes(data) {
+            for glyph in 0..=font.glyph_count() {
+                let glyph = GlyphId(glyph);
+                let bitmap = font.rasterize(glyph, 16.0);
+                assert_eq!(
+                    bitmap.data.len(),
+                    (bitmap.width * bitmap.height * bitmap.channels) as usize
+                );
+                font.render(
+                    glyph,
+                    16.0,
+                    GlyphMode::Sdf {
+                        size: 8.0,
+                        range: 2.0,
+                    },
+                );
+                font.kerning(glyph, GlyphId(2));
+            }
+            font.layout("AV BO\nVA", &LayoutOptions::new(16.0).max_width(20.0));
+        }
+    }
+}
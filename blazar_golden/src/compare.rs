@@ -0,0 +1,126 @@
+//! Image comparison.
+
+use blazar_image::{Image, PixelFormat};
+
+/// Largest possible squared perceptual distance between two colors.
+const MAX_PERCEPTUAL_DELTA: f32 = 35215.0;
+
+/// Metrics telling whether two pixels differ.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Metric {
+    /// Largest absolute difference between 8-bit components.
+    Channel(u8),
+    /// Weighted distance in the YIQ color space, which follows human perception more
+    /// closely, normalized to `[0, 1]`.
+    Perceptual(f32),
+}
+
+/// Parameters of a comparison.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CompareOptions {
+    /// Difference beyond which pixels mismatch.
+    pub metric: Metric,
+    /// Proportion of mismatched pixels tolerated, in `[0, 1]`.
+    pub max_mismatched_ratio: f32,
+}
+
+impl Default for CompareOptions {
+    fn default() -> CompareOptions {
+        CompareOptions {
+            metric: Metric::Perceptual(0.1),
+            max_mismatched_ratio: 0.0,
+        }
+    }
+}
+
+/// Result of a comparison.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Comparison {
+    /// Number of mismatched pixels.
+    pub mismatched: usize,
+    /// Number of compared pixels.
+    pub total: usize,
+    /// Largest difference found, normalized to `[0, 1]` with the chosen metric.
+    pub max_difference: f32,
+    /// Faded grayscale copy of the expected image, mismatched pixels being red.
+    pub diff: Image,
+}
+
+impl Comparison {
+    /// Returns `true` if the proportion of mismatched pixels is tolerated.
+    pub fn passes(&self, options: &CompareOptions) -> bool {
+        self.mismatched as f32 <= options.max_mismatched_ratio * self.total as f32
+    }
+}
+
+/// Compares two images, or returns `None` if their sizes differ.
+///
+/// Pixels are blended over white before being compared, so that fully transparent
+/// pixels match whatever their color.
+pub fn compare(expected: &Image, actual: &Image, options: &CompareOptions) -> Option<Comparison> {
+    if expected.width() != actual.width() || expected.height() != actual.height() {
+        return None;
+    }
+    let (expected, actual) = (expected.to_rgba8(), actual.to_rgba8());
+    let mut diff = Image::new(expected.width(), expected.height(), PixelFormat::Rgba8);
+    let mut mismatched = 0;
+    let mut max_difference = 0.0f32;
+    let pixels = expected
+        .data()
+        .chunks(4)
+        .zip(actual.data().chunks(4))
+        .zip(diff.data_mut().chunks_mut(4));
+    for ((expected, actual), diff) in pixels {
+        let (first, second) = (over_white(expected), over_white(actual));
+        let (difference, mismatch) = match options.metric {
+            Metric::Channel(tolerance) => {
+                let difference = first
+                    .iter()
+                    .zip(&second)
+                    .map(|(a, b)| (a - b).abs())
+                    .fold(0.0, f32::max);
+                (difference / 255.0, difference > f32::from(tolerance))
+            }
+            Metric::Perceptual(threshold) => {
+                let difference = (perceptual_delta(first, second) / MAX_PERCEPTUAL_DELTA).sqrt();
+                (difference, difference > threshold)
+            }
+        };
+        max_difference = max_difference.max(difference);
+        if mismatch {
+            mismatched += 1;
+            diff.copy_from_slice(&[255, 0, 0, 255]);
+        } else {
+            let gray = (luma(first) / 255.0 * 64.0 + 191.0) as u8;
+            diff.copy_from_slice(&[gray, gray, gray, 255]);
+        }
+    }
+    Some(Comparison {
+        mismatched,
+        total: expected.width() as usize * expected.height() as usize,
+        max_difference,
+        diff,
+    })
+}
+
+/// Blends an 8-bit pixel over white.
+fn over_white(pixel: &[u8]) -> [f32; 3] {
+    let alpha = f32::from(pixel[3]) / 255.0;
+    let blend = |component: u8| 255.0 + (f32::from(component) - 255.0) * alpha;
+    [blend(pixel[0]), blend(pixel[1]), blend(pixel[2])]
+}
+
+/// Returns the luma of a color.
+fn luma(color: [f32; 3]) -> f32 {
+    color[0] * 0.298_9 + color[1] * 0.586_6 + color[2] * 0.114_5
+}
+
+/// Returns the squared perceptual distance between two colors in the YIQ color space.
+fn perceptual_delta(a: [f32; 3], b: [f32; 3]) -> f32 {
+    let in_phase = |color: [f32; 3]| color[0] * 0.596_0 - color[1] * 0.274_2 - color[2] * 0.321_8;
+    let quadrature = |color: [f32; 3]| color[0] * 0.211_5 - color[1] * 0.522_6 + color[2] * 0.311_1;
+    let y = luma(a) - luma(b);
+    let i = in_phase(a) - in_phase(b);
+    let q = quadrature(a) - quadrature(b);
+    0.5053 * y * y + 0.299 * i * i + 0.1957 * q * q
+}
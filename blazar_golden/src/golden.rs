@@ -0,0 +1,134 @@
+//! Golden image regression tests.
+//!
+//! Rendered images are compared against reference PNG images. On failure, the actual
+//! and diff images are written next to each other for inspection. Setting the
+//! `BLAZAR_UPDATE_GOLDEN` environment variable writes the references instead.
+
+use crate::compare::{compare, CompareOptions};
+use blazar_image::{DecodeImageError, Image};
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Environment variable requesting references to be written.
+pub const UPDATE_VARIABLE: &str = "BLAZAR_UPDATE_GOLDEN";
+
+/// Kinds of golden image errors.
+#[derive(Debug)]
+pub enum GoldenError {
+    Io(io::Error),
+    Decode(DecodeImageError),
+    MissingReference(PathBuf),
+    SizeMismatch {
+        expected: (u32, u32),
+        actual: (u32, u32),
+        actual_path: PathBuf,
+    },
+    Mismatch {
+        mismatched: usize,
+        total: usize,
+        max_difference: f32,
+        actual_path: PathBuf,
+        diff_path: PathBuf,
+    },
+}
+
+impl From<io::Error> for GoldenError {
+    fn from(error: io::Error) -> GoldenError {
+        GoldenError::Io(error)
+    }
+}
+
+impl From<DecodeImageError> for GoldenError {
+    fn from(error: DecodeImageError) -> GoldenError {
+        GoldenError::Decode(error)
+    }
+}
+
+/// Checks images against references stored in a directory.
+#[derive(Clone, Debug)]
+pub struct Golden {
+    references: PathBuf,
+    failures: PathBuf,
+    options: CompareOptions,
+    update: bool,
+}
+
+impl Golden {
+    /// Creates a checker of references in `references`, writing the images of failed
+    /// checks in `failures`.
+    pub fn new<P: AsRef<Path>, Q: AsRef<Path>>(references: P, failures: Q) -> Golden {
+        Golden {
+            references: references.as_ref().to_path_buf(),
+            failures: failures.as_ref().to_path_buf(),
+            options: CompareOptions::default(),
+            update: env::var_os(UPDATE_VARIABLE).is_some(),
+        }
+    }
+
+    /// Sets the comparison options.
+    pub fn options(mut self, options: CompareOptions) -> Golden {
+        self.options = options;
+        self
+    }
+
+    /// Sets whether references are written instead of checked.
+    pub fn update(mut self, update: bool) -> Golden {
+        self.update = update;
+        self
+    }
+
+    /// Returns the path of a reference.
+    pub fn reference_path(&self, name: &str) -> PathBuf {
+        self.references.join(format!("{}.png", name))
+    }
+
+    /// Checks an image against the reference `name`.
+    ///
+    /// Stale images of a previous failure are removed when the check passes.
+    pub fn check(&self, name: &str, actual: &Image) -> Result<(), GoldenError> {
+        let reference_path = self.reference_path(name);
+        let actual_path = self.failures.join(format!("{}.actual.png", name));
+        let diff_path = self.failures.join(format!("{}.diff.png", name));
+        if self.update {
+            fs::create_dir_all(&self.references)?;
+            actual.save_png(&reference_path)?;
+            return Ok(());
+        }
+        if !reference_path.exists() {
+            return Err(GoldenError::MissingReference(reference_path));
+        }
+        let expected = Image::open(&reference_path)?;
+        let comparison = match compare(&expected, actual, &self.options) {
+            Some(comparison) => comparison,
+            None => {
+                fs::create_dir_all(&self.failures)?;
+                actual.save_png(&actual_path)?;
+                return Err(GoldenError::SizeMismatch {
+                    expected: (expected.width(), expected.height()),
+                    actual: (actual.width(), actual.height()),
+                    actual_path,
+                });
+            }
+        };
+        if comparison.passes(&self.options) {
+            for path in &[&actual_path, &diff_path] {
+                if path.exists() {
+                    fs::remove_file(path)?;
+                }
+            }
+            return Ok(());
+        }
+        fs::create_dir_all(&self.failures)?;
+        actual.save_png(&actual_path)?;
+        comparison.diff.save_png(&diff_path)?;
+        Err(GoldenError::Mismatch {
+            mismatched: comparison.mismatched,
+            total: comparison.total,
+            max_difference: comparison.max_difference,
+            actual_path,
+            diff_path,
+        })
+    }
+}
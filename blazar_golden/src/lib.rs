@@ -0,0 +1,4 @@
+//! Image comparison for golden image tests of the workspace crates.
+
+pub mod compare;
+pub mod golden;
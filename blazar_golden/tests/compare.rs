@@ -0,0 +1,124 @@
+use blazar_golden::{
+    compare::{compare, CompareOptions, Metric},
+    golden::{Golden, GoldenError},
+};
+use blazar_image::{Image, PixelFormat};
+use std::{env, fs};
+
+/// Creates an 8-bit image filled with a color.
+fn filled(width: u32, height: u32, color: [u8; 4]) -> Image {
+    let data = color
+        .iter()
+        .cycle()
+        .take(width as usize * height as usize * 4);
+    Image::from_raw(width, height, PixelFormat::Rgba8, data.copied().collect()).unwrap()
+}
+
+/// Sets a pixel of an 8-bit image.
+fn set_pixel(image: &mut Image, x: u32, y: u32, color: [u8; 4]) {
+    let index = (y * image.width() + x) as usize * 4;
+    image.data_mut()[index..index + 4].copy_from_slice(&color);
+}
+
+#[test]
+fn identical_images_match() {
+    let image = filled(8, 4, [10, 200, 30, 255]);
+    let comparison = compare(&image, &image, &CompareOptions::default()).unwrap();
+    assert_eq!(comparison.mismatched, 0);
+    assert_eq!(comparison.total, 32);
+    assert_eq!(comparison.max_difference, 0.0);
+    assert!(comparison.passes(&CompareOptions::default()));
+}
+
+#[test]
+fn size_mismatch() {
+    let options = CompareOptions::default();
+    assert!(compare(&filled(4, 4, [0; 4]), &filled(4, 5, [0; 4]), &options).is_none());
+}
+
+#[test]
+fn channel_tolerance() {
+    let expected = filled(4, 4, [100, 100, 100, 255]);
+    let mut actual = expected.clone();
+    set_pixel(&mut actual, 1, 2, [103, 100, 100, 255]);
+    let strict = CompareOptions {
+        metric: Metric::Channel(2),
+        ..CompareOptions::default()
+    };
+    let comparison = compare(&expected, &actual, &strict).unwrap();
+    assert_eq!(comparison.mismatched, 1);
+    assert!(!comparison.passes(&strict));
+    assert_eq!(comparison.diff.pixel(1, 2), [65535, 0, 0, 65535]);
+    assert_ne!(comparison.diff.pixel(0, 0), [65535, 0, 0, 65535]);
+    let tolerant = CompareOptions {
+        metric: Metric::Channel(3),
+        ..strict
+    };
+    assert_eq!(
+        compare(&expected, &actual, &tolerant).unwrap().mismatched,
+        0
+    );
+    let ratio = CompareOptions {
+        max_mismatched_ratio: 1.0 / 16.0,
+        ..strict
+    };
+    assert!(compare(&expected, &actual, &ratio).unwrap().passes(&ratio));
+}
+
+#[test]
+fn perceptual_metric() {
+    let options = CompareOptions::default();
+    let black = filled(1, 1, [0, 0, 0, 255]);
+    let white = filled(1, 1, [255, 255, 255, 255]);
+    let comparison = compare(&black, &white, &options).unwrap();
+    assert!(comparison.max_difference > 0.9 && comparison.max_difference <= 1.0);
+    assert_eq!(comparison.mismatched, 1);
+    // Blue changes are less noticeable than green ones.
+    let gray = filled(1, 1, [128, 128, 128, 255]);
+    let bluer = filled(1, 1, [128, 128, 160, 255]);
+    let greener = filled(1, 1, [128, 160, 128, 255]);
+    let blue = compare(&gray, &bluer, &options).unwrap().max_difference;
+    let green = compare(&gray, &greener, &options).unwrap().max_difference;
+    assert!(blue < green);
+}
+
+#[test]
+fn transparent_pixels_match() {
+    let options = CompareOptions::default();
+    let red = filled(2, 2, [255, 0, 0, 0]);
+    let blue = filled(2, 2, [0, 0, 255, 0]);
+    assert_eq!(compare(&red, &blue, &options).unwrap().mismatched, 0);
+}
+
+#[test]
+fn golden_failure_writes_images() {
+    let directory = env::temp_dir().join(format!("blazar_golden_{}", std::process::id()));
+    let references = directory.join("references");
+    let failures = directory.join("failures");
+    let expected = filled(4, 4, [0, 0, 0, 255]);
+    let golden = Golden::new(&references, &failures).update(true);
+    golden.check("scene", &expected).unwrap();
+    let golden = golden.update(false);
+    golden.check("scene", &expected).unwrap();
+    let mut actual = expected.clone();
+    set_pixel(&mut actual, 3, 3, [255, 255, 255, 255]);
+    match golden.check("scene", &actual) {
+        Err(GoldenError::Mismatch {
+            mismatched,
+            actual_path,
+            diff_path,
+            ..
+        }) => {
+            assert_eq!(mismatched, 1);
+            assert_eq!(Image::open(actual_path).unwrap().to_rgba8(), actual);
+            let diff = Image::open(diff_path).unwrap();
+            assert_eq!(diff.pixel(3, 3), [65535, 0, 0, 65535]);
+        }
+        result => panic!("unexpected result: {:?}", result),
+    }
+    match golden.check("missing", &actual) {
+        Err(GoldenError::MissingReference(_)) => {}
+        result => panic!("unexpected result: {:?}", result),
+    }
+    fs::remove_dir_all(directory).unwrap();
+}
@@ -0,0 +1,288 @@
+#version 450
+
+const float PI = 3.14159265359;
+
+const uint BASE_COLOR_TEXTURE = 1;
+const uint METALLIC_ROUGHNESS_TEXTURE = 2;
+const uint NORMAL_TEXTURE = 4;
+const uint OCCLUSION_TEXTURE = 8;
+const uint EMISSIVE_TEXTURE = 16;
+
+const uint ALPHA_MASK = 1;
+const uint ALPHA_BLEND = 2;
+
+const uint DIRECTIONAL_LIGHT = 0;
+const uint SPOT_LIGHT = 2;
+
+struct Light {
+    vec3 position;
+    float range;
+    vec3 direction;
+    uint kind;
+    vec3 color;
+    float intensity;
+    float cone_scale;
+    float cone_offset;
+    int shadow;
+};
+
+layout(set = 0, binding = 0) uniform Scene {
+    mat4 view_projection;
+    vec3 camera_position;
+    uint light_count;
+    float environment_intensity;
+    float prefiltered_levels;
+    Light lights[16];
+} scene;
+layout(set = 0, binding = 1) uniform samplerCube irradiance_map;
+layout(set = 0, binding = 2) uniform samplerCube prefiltered_map;
+layout(set = 0, binding = 3) uniform sampler2D brdf_lut;
+layout(set = 0, binding = 4) uniform Shadows {
+    mat4 cascades[4];
+    vec4 cascade_splits;
+    vec4 cascade_texel_sizes;
+    mat4 spots[4];
+    vec4 spot_texel_scales;
+    // Terms mapping the distance along the major axis to the depth, `x + y / distance`.
+    vec4 point_depths[4];
+    vec3 camera_forward;
+    uint cascade_count;
+    int pcf_radius;
+    float normal_bias;
+    float texel_size;
+} shadows;
+layout(set = 0, binding = 5) uniform sampler2DArrayShadow cascade_map;
+layout(set = 0, binding = 6) uniform sampler2DArrayShadow spot_map;
+// Cube map arrays need the `imageCubeArray` feature: define `NO_POINT_SHADOWS` on
+// devices without it, point shadows being disabled.
+#ifndef NO_POINT_SHADOWS
+layout(set = 0, binding = 7) uniform samplerCubeArrayShadow point_map;
+#endif
+
+layout(set = 1, binding = 0) uniform Material {
+    vec4 base_color;
+    vec3 emissive;
+    float alpha_cutoff;
+    // Metallic, roughness, normal scale and occlusion strength.
+    vec4 factors;
+    // Texture bits, alpha mode and double-sidedness.
+    uvec4 flags;
+} material;
+layout(set = 1, binding = 1) uniform sampler2D base_color_texture;
+layout(set = 1, binding = 2) uniform sampler2D metallic_roughness_texture;
+layout(set = 1, binding = 3) uniform sampler2D normal_texture;
+layout(set = 1, binding = 4) uniform sampler2D occlusion_texture;
+layout(set = 1, binding = 5) uniform sampler2D emissive_texture;
+
+layout(location = 0) in vec3 position;
+layout(location = 1) in vec3 normal;
+layout(location = 2) in vec4 tangent;
+layout(location = 3) in vec2 tex_coord;
+layout(location = 4) in vec4 color;
+
+layout(location = 0) out vec4 out_color;
+
+bool has_texture(uint texture) {
+    return (material.flags.x & texture) != 0;
+}
+
+float distribution_ggx(float n_dot_h, float alpha) {
+    float alpha2 = alpha * alpha;
+    float denominator = n_dot_h * n_dot_h * (alpha2 - 1.0) + 1.0;
+    return alpha2 / (PI * denominator * denominator);
+}
+
+float visibility_smith_ggx(float n_dot_v, float n_dot_l, float alpha) {
+    float alpha2 = alpha * alpha;
+    float ggx_v = n_dot_l * sqrt(n_dot_v * n_dot_v * (1.0 - alpha2) + alpha2);
+    float ggx_l = n_dot_v * sqrt(n_dot_l * n_dot_l * (1.0 - alpha2) + alpha2);
+    float ggx = ggx_v + ggx_l;
+    return ggx > 0.0 ? 0.5 / ggx : 0.0;
+}
+
+vec3 fresnel_schlick(vec3 f0, float v_dot_h) {
+    return f0 + (1.0 - f0) * pow(1.0 - v_dot_h, 5.0);
+}
+
+vec3 surface_normal() {
+    vec3 n = normalize(normal);
+    if (has_texture(NORMAL_TEXTURE) && dot(tangent.xyz, tangent.xyz) != 0.0) {
+        vec3 t = normalize(tangent.xyz - n * dot(n, tangent.xyz));
+        vec3 b = cross(n, t) * tangent.w;
+        vec3 sampled = texture(normal_texture, tex_coord).xyz * 2.0 - 1.0;
+        sampled.xy *= material.factors.z;
+        n = normalize(mat3(t, b, n) * sampled);
+    }
+    // Flipping the whole basis keeps the normal map from being mirrored on back faces.
+    if (material.flags.z != 0 && !gl_FrontFacing) {
+        n = -n;
+    }
+    return n;
+}
+
+// Attenuation of the intensity of a light reaching a fragment, from
+// `KHR_lights_punctual`.
+float attenuation(Light light, vec3 to_light) {
+    if (light.kind == DIRECTIONAL_LIGHT) {
+        return 1.0;
+    }
+    float distance2 = dot(to_light, to_light);
+    float falloff = 1.0 / max(distance2, 0.0001);
+    if (light.range > 0.0) {
+        float ratio = distance2 / (light.range * light.range);
+        falloff *= clamp(1.0 - ratio * ratio, 0.0, 1.0);
+    }
+    if (light.kind == SPOT_LIGHT) {
+        float cone = clamp(
+            dot(light.direction, -normalize(to_light)) * light.cone_scale + light.cone_offset,
+            0.0,
+            1.0
+        );
+        falloff *= cone * cone;
+    }
+    return falloff;
+}
+
+// Percentage-closer filtering of a layer of a 2D shadow map.
+float filter_shadow(sampler2DArrayShadow map, int layer, vec4 clip) {
+    vec3 coords = clip.xyz / clip.w;
+    if (clip.w <= 0.0 || coords.z >= 1.0) {
+        return 1.0;
+    }
+    vec2 uv = coords.xy * 0.5 + 0.5;
+    float lit = 0.0;
+    for (int y = -shadows.pcf_radius; y <= shadows.pcf_radius; y++) {
+        for (int x = -shadows.pcf_radius; x <= shadows.pcf_radius; x++) {
+            vec2 offset = vec2(x, y) * shadows.texel_size;
+            lit += texture(map, vec4(uv + offset, layer, coords.z));
+        }
+    }
+    float width = float(2 * shadows.pcf_radius + 1);
+    return lit / (width * width);
+}
+
+// Percentage-closer filtering of a point shadow, offsetting the direction across the
+// face it points to.
+float filter_point_shadow(int layer, vec3 to_fragment) {
+#ifdef NO_POINT_SHADOWS
+    return 1.0;
+#else
+    vec3 axis = abs(to_fragment);
+    float major = max(axis.x, max(axis.y, axis.z));
+    vec4 depths = shadows.point_depths[layer];
+    float depth = depths.x + depths.y / major;
+    vec3 up = axis.y < axis.x || axis.y < axis.z ? vec3(0.0, 1.0, 0.0) : vec3(1.0, 0.0, 0.0);
+    vec3 side = normalize(cross(to_fragment, up));
+    up = cross(side, normalize(to_fragment));
+    float spacing = 2.0 * major * shadows.texel_size;
+    float lit = 0.0;
+    for (int y = -shadows.pcf_radius; y <= shadows.pcf_radius; y++) {
+        for (int x = -shadows.pcf_radius; x <= shadows.pcf_radius; x++) {
+            vec3 direction = to_fragment + (side * float(x) + up * float(y)) * spacing;
+            lit += texture(point_map, vec4(direction, layer), depth);
+        }
+    }
+    float width = float(2 * shadows.pcf_radius + 1);
+    return lit / (width * width);
+#endif
+}
+
+// Fraction of the light reaching a fragment, offset along its normal by the size of a
+// shadow map texel there.
+float shadow(Light light, vec3 n) {
+    if (light.shadow < 0) {
+        return 1.0;
+    }
+    if (light.kind == DIRECTIONAL_LIGHT) {
+        float depth = dot(position - scene.camera_position, shadows.camera_forward);
+        for (uint cascade = 0; cascade < shadows.cascade_count; cascade++) {
+            if (depth < shadows.cascade_splits[cascade]) {
+                float bias = shadows.normal_bias * shadows.cascade_texel_sizes[cascade];
+                vec4 clip = shadows.cascades[cascade] * vec4(position + n * bias, 1.0);
+                return filter_shadow(cascade_map, int(cascade), clip);
+            }
+        }
+        return 1.0;
+    }
+    float light_distance = length(position - light.position);
+    if (light.kind == SPOT_LIGHT) {
+        float texel = light_distance * shadows.spot_texel_scales[light.shadow];
+        float bias = shadows.normal_bias * texel;
+        vec4 clip = shadows.spots[light.shadow] * vec4(position + n * bias, 1.0);
+        return filter_shadow(spot_map, light.shadow, clip);
+    }
+    float bias = shadows.normal_bias * light_distance * 2.0 * shadows.texel_size;
+    return filter_point_shadow(light.shadow, position + n * bias - light.position);
+}
+
+void main() {
+    vec4 base_color = material.base_color * color;
+    if (has_texture(BASE_COLOR_TEXTURE)) {
+        base_color *= texture(base_color_texture, tex_coord);
+    }
+    if (material.flags.y == ALPHA_MASK && base_color.a < material.alpha_cutoff) {
+        discard;
+    }
+    float metallic = material.factors.x;
+    float roughness = material.factors.y;
+    if (has_texture(METALLIC_ROUGHNESS_TEXTURE)) {
+        vec4 texel = texture(metallic_roughness_texture, tex_coord);
+        roughness *= texel.g;
+        metallic *= texel.b;
+    }
+    roughness = clamp(roughness, 0.04, 1.0);
+    float alpha = roughness * roughness;
+
+    vec3 n = surface_normal();
+    vec3 v = normalize(scene.camera_position - position);
+    float n_dot_v = clamp(abs(dot(n, v)), 0.001, 1.0);
+    vec3 f0 = mix(vec3(0.04), base_color.rgb, metallic);
+    vec3 diffuse_color = base_color.rgb * (1.0 - metallic);
+
+    vec3 lit = vec3(0.0);
+    for (uint index = 0; index < scene.light_count; index++) {
+        Light light = scene.lights[index];
+        vec3 to_light = light.kind == DIRECTIONAL_LIGHT
+            ? -light.direction
+            : light.position - position;
+        vec3 l = normalize(to_light);
+        float n_dot_l = clamp(dot(n, l), 0.0, 1.0);
+        if (n_dot_l == 0.0) {
+            continue;
+        }
+        vec3 h = normalize(l + v);
+        float n_dot_h = clamp(dot(n, h), 0.0, 1.0);
+        float v_dot_h = clamp(dot(v, h), 0.0, 1.0);
+        vec3 fresnel = fresnel_schlick(f0, v_dot_h);
+        vec3 diffuse = (1.0 - fresnel) * diffuse_color / PI;
+        vec3 specular = fresnel
+            * distribution_ggx(n_dot_h, alpha)
+            * visibility_smith_ggx(n_dot_v, n_dot_l, alpha);
+        vec3 radiance = light.color
+            * light.intensity
+            * attenuation(light, to_light)
+            * shadow(light, n);
+        lit += (diffuse + specular) * radiance * n_dot_l;
+    }
+
+    if (scene.environment_intensity > 0.0) {
+        vec2 brdf = texture(brdf_lut, vec2(n_dot_v, roughness)).rg;
+        vec3 irradiance = texture(irradiance_map, n).rgb;
+        float lod = roughness * (scene.prefiltered_levels - 1.0);
+        vec3 reflection = textureLod(prefiltered_map, reflect(-v, n), lod).rgb;
+        vec3 ambient = irradiance * diffuse_color + reflection * (f0 * brdf.x + brdf.y);
+        if (has_texture(OCCLUSION_TEXTURE)) {
+            float occlusion = texture(occlusion_texture, tex_coord).r;
+            ambient *= 1.0 + material.factors.w * (occlusion - 1.0);
+        }
+        lit += ambient * scene.environment_intensity;
+    }
+
+    vec3 emissive = material.emissive;
+    if (has_texture(EMISSIVE_TEXTURE)) {
+        emissive *= texture(emissive_texture, tex_coord).rgb;
+    }
+    lit += emissive;
+
+    out_color = vec4(lit, material.flags.y == ALPHA_BLEND ? base_color.a : 1.0);
+}
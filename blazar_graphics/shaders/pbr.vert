@@ -0,0 +1,49 @@
+#version 450
+
+struct Light {
+    vec3 position;
+    float range;
+    vec3 direction;
+    uint kind;
+    vec3 color;
+    float intensity;
+    float cone_scale;
+    float cone_offset;
+    int shadow;
+};
+
+layout(set = 0, binding = 0) uniform Scene {
+    mat4 view_projection;
+    vec3 camera_position;
+    uint light_count;
+    float environment_intensity;
+    float prefiltered_levels;
+    Light lights[16];
+} scene;
+
+layout(push_constant) uniform Model {
+    mat4 transform;
+} model;
+
+layout(location = 0) in vec3 position;
+layout(location = 1) in vec3 normal;
+layout(location = 2) in vec4 tangent;
+layout(location = 3) in vec2 tex_coord;
+layout(location = 4) in vec4 color;
+
+layout(location = 0) out vec3 out_position;
+layout(location = 1) out vec3 out_normal;
+layout(location = 2) out vec4 out_tangent;
+layout(location = 3) out vec2 out_tex_coord;
+layout(location = 4) out vec4 out_color;
+
+void main() {
+    vec4 world_position = model.transform * vec4(position, 1.0);
+    mat3 normal_matrix = transpose(inverse(mat3(model.transform)));
+    gl_Position = scene.view_projection * world_position;
+    out_position = world_position.xyz;
+    out_normal = normal_matrix * normal;
+    out_tangent = vec4(mat3(model.transform) * tangent.xyz, tangent.w);
+    out_tex_coord = tex_coord;
+    out_color = color;
+}
@@ -0,0 +1,17 @@
+#version 450
+
+layout(set = 0, binding = 0) uniform sampler2D source;
+layout(set = 0, binding = 1) uniform sampler2D bloom;
+
+layout(push_constant) uniform Parameters {
+    float intensity;
+} parameters;
+
+layout(location = 0) in vec2 uv;
+
+layout(location = 0) out vec4 out_color;
+
+void main() {
+    vec4 color = texture(source, uv);
+    out_color = vec4(color.rgb + texture(bloom, uv).rgb * parameters.intensity, color.a);
+}
@@ -0,0 +1,18 @@
+#version 450
+
+layout(set = 0, binding = 0) uniform sampler2D source;
+
+layout(push_constant) uniform Parameters {
+    float threshold;
+} parameters;
+
+layout(location = 0) in vec2 uv;
+
+layout(location = 0) out vec4 out_color;
+
+void main() {
+    vec3 color = texture(source, uv).rgb;
+    float brightness = max(color.r, max(color.g, color.b));
+    float contribution = max(brightness - parameters.threshold, 0.0) / max(brightness, 1e-4);
+    out_color = vec4(color * contribution, 1.0);
+}
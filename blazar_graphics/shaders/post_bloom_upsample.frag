@@ -0,0 +1,22 @@
+#version 450
+
+layout(set = 0, binding = 0) uniform sampler2D source;
+
+layout(location = 0) in vec2 uv;
+
+layout(location = 0) out vec4 out_color;
+
+void main() {
+    // 3x3 tent filter, added to the larger level by blending.
+    vec2 texel = 1.0 / vec2(textureSize(source, 0));
+    vec3 color = texture(source, uv).rgb * 4.0;
+    color += (texture(source, uv + texel * vec2(-1.0, 0.0)).rgb
+        + texture(source, uv + texel * vec2(1.0, 0.0)).rgb
+        + texture(source, uv + texel * vec2(0.0, -1.0)).rgb
+        + texture(source, uv + texel * vec2(0.0, 1.0)).rgb) * 2.0;
+    color += texture(source, uv + texel * vec2(-1.0, -1.0)).rgb
+        + texture(source, uv + texel * vec2(1.0, -1.0)).rgb
+        + texture(source, uv + texel * vec2(-1.0, 1.0)).rgb
+        + texture(source, uv + texel * vec2(1.0, 1.0)).rgb;
+    out_color = vec4(color / 16.0, 1.0);
+}
@@ -0,0 +1,32 @@
+#version 450
+
+layout(set = 0, binding = 0) uniform sampler2D source;
+layout(set = 0, binding = 1) uniform sampler2D lut;
+
+layout(push_constant) uniform Parameters {
+    float strength;
+} parameters;
+
+layout(location = 0) in vec2 uv;
+
+layout(location = 0) out vec4 out_color;
+
+// Samples the table at the center of texels, made of `size` slices of `size` by `size`
+// texels laid out horizontally.
+vec3 sample_slice(vec2 coordinates, float slice, float size) {
+    vec2 position = vec2(slice * size + coordinates.x, coordinates.y) + 0.5;
+    return texture(lut, position / vec2(size * size, size)).rgb;
+}
+
+void main() {
+    vec4 color = texture(source, uv);
+    float size = float(textureSize(lut, 0).y);
+    vec3 coordinates = clamp(color.rgb, 0.0, 1.0) * (size - 1.0);
+    float slice = floor(coordinates.b);
+    vec3 graded = mix(
+        sample_slice(coordinates.rg, slice, size),
+        sample_slice(coordinates.rg, min(slice + 1.0, size - 1.0), size),
+        coordinates.b - slice
+    );
+    out_color = vec4(mix(color.rgb, graded, parameters.strength), color.a);
+}
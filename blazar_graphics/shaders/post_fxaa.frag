@@ -0,0 +1,47 @@
+#version 450
+
+layout(set = 0, binding = 0) uniform sampler2D source;
+
+layout(location = 0) in vec2 uv;
+
+layout(location = 0) out vec4 out_color;
+
+const float REDUCE_MIN = 1.0 / 128.0;
+const float REDUCE_MUL = 1.0 / 8.0;
+const float SPAN_MAX = 8.0;
+
+float luma(vec3 color) {
+    return dot(color, vec3(0.299, 0.587, 0.114));
+}
+
+void main() {
+    vec2 texel = 1.0 / vec2(textureSize(source, 0));
+    float luma_nw = luma(texture(source, uv + texel * vec2(-1.0, -1.0)).rgb);
+    float luma_ne = luma(texture(source, uv + texel * vec2(1.0, -1.0)).rgb);
+    float luma_sw = luma(texture(source, uv + texel * vec2(-1.0, 1.0)).rgb);
+    float luma_se = luma(texture(source, uv + texel * vec2(1.0, 1.0)).rgb);
+    vec4 center = texture(source, uv);
+    float luma_m = luma(center.rgb);
+    float luma_min = min(luma_m, min(min(luma_nw, luma_ne), min(luma_sw, luma_se)));
+    float luma_max = max(luma_m, max(max(luma_nw, luma_ne), max(luma_sw, luma_se)));
+
+    vec2 direction = vec2(
+        -((luma_nw + luma_ne) - (luma_sw + luma_se)),
+        (luma_nw + luma_sw) - (luma_ne + luma_se)
+    );
+    float reduce = max((luma_nw + luma_ne + luma_sw + luma_se) * 0.25 * REDUCE_MUL, REDUCE_MIN);
+    float scale = 1.0 / (min(abs(direction.x), abs(direction.y)) + reduce);
+    direction = clamp(direction * scale, -SPAN_MAX, SPAN_MAX) * texel;
+
+    vec3 near = 0.5 * (
+        texture(source, uv + direction * (1.0 / 3.0 - 0.5)).rgb
+        + texture(source, uv + direction * (2.0 / 3.0 - 0.5)).rgb
+    );
+    vec3 far = near * 0.5 + 0.25 * (
+        texture(source, uv - direction * 0.5).rgb
+        + texture(source, uv + direction * 0.5).rgb
+    );
+    float luma_far = luma(far);
+    vec3 color = luma_far < luma_min || luma_far > luma_max ? near : far;
+    out_color = vec4(color, center.a);
+}
@@ -0,0 +1,16 @@
+#version 450
+
+layout(set = 0, binding = 0) uniform sampler2D source;
+
+layout(push_constant) uniform Parameters {
+    float gamma;
+} parameters;
+
+layout(location = 0) in vec2 uv;
+
+layout(location = 0) out vec4 out_color;
+
+void main() {
+    vec4 color = texture(source, uv);
+    out_color = vec4(pow(max(color.rgb, 0.0), vec3(1.0 / parameters.gamma)), color.a);
+}
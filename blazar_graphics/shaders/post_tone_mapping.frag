@@ -0,0 +1,32 @@
+#version 450
+
+layout(set = 0, binding = 0) uniform sampler2D source;
+
+layout(push_constant) uniform Parameters {
+    float exposure;
+    float operator;
+} parameters;
+
+layout(location = 0) in vec2 uv;
+
+layout(location = 0) out vec4 out_color;
+
+vec3 reinhard(vec3 color) {
+    return color / (1.0 + color);
+}
+
+// Narkowicz's fit of the ACES filmic curve.
+vec3 aces(vec3 color) {
+    return clamp(
+        (color * (2.51 * color + 0.03)) / (color * (2.43 * color + 0.59) + 0.14),
+        0.0,
+        1.0
+    );
+}
+
+void main() {
+    vec4 color = texture(source, uv);
+    vec3 exposed = color.rgb * parameters.exposure;
+    vec3 mapped = parameters.operator < 0.5 ? reinhard(exposed) : aces(exposed);
+    out_color = vec4(mapped, color.a);
+}
@@ -0,0 +1,25 @@
+#version 450
+
+layout(set = 0, binding = 0) uniform sampler2D source;
+
+layout(push_constant) uniform Parameters {
+    float intensity;
+    float radius;
+    float smoothness;
+} parameters;
+
+layout(location = 0) in vec2 uv;
+
+layout(location = 0) out vec4 out_color;
+
+void main() {
+    vec4 color = texture(source, uv);
+    // Distance to the center, 1 at the corners.
+    float distance = length(uv - 0.5) / length(vec2(0.5));
+    float factor = smoothstep(
+        parameters.radius,
+        parameters.radius + parameters.smoothness,
+        distance
+    );
+    out_color = vec4(color.rgb * (1.0 - factor * parameters.intensity), color.a);
+}
@@ -0,0 +1,12 @@
+#version 450
+
+layout(push_constant) uniform Caster {
+    mat4 view_projection;
+    mat4 transform;
+} caster;
+
+layout(location = 0) in vec3 position;
+
+void main() {
+    gl_Position = caster.view_projection * caster.transform * vec4(position, 1.0);
+}
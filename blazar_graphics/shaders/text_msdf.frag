@@ -0,0 +1,62 @@
+#version 450
+
+layout(set = 0, binding = 0) uniform sampler2D glyph_atlas;
+
+layout(push_constant) uniform Effects {
+    layout(offset = 64) vec4 outline_color;
+    vec4 shadow_color;
+    vec2 shadow_offset;
+    float outline_width;
+    float shadow_softness;
+    float distance_range;
+} effects;
+
+layout(location = 0) in vec2 uv;
+layout(location = 1) in vec4 color;
+
+layout(location = 0) out vec4 out_color;
+
+float median(float r, float g, float b) {
+    return max(min(r, g), min(max(r, g), b));
+}
+
+// Converts a sampled value to a signed distance in atlas pixels, positive inside.
+float to_distance(float value) {
+    return (value - 0.5) * 2.0 * effects.distance_range;
+}
+
+// Composites two colors with straight alpha.
+vec4 over(vec4 top, vec4 bottom) {
+    float alpha = top.a + bottom.a * (1.0 - top.a);
+    vec3 rgb = top.rgb * top.a + bottom.rgb * bottom.a * (1.0 - top.a);
+    return alpha > 0.0 ? vec4(rgb / alpha, alpha) : vec4(0.0);
+}
+
+void main() {
+    vec2 atlas_size = vec2(textureSize(glyph_atlas, 0));
+    // Atlas pixels covered by a screen pixel, for anti-aliasing at any scale.
+    vec2 footprint = fwidth(uv) * atlas_size;
+    float texels = max(0.5 * (footprint.x + footprint.y), 0.0001);
+
+    // The median keeps corners sharp, while the true distance in alpha extends cleanly
+    // beyond the outline.
+    vec4 field = texture(glyph_atlas, uv);
+    float distance = to_distance(median(field.r, field.g, field.b));
+    float fill = clamp(distance / texels + 0.5, 0.0, 1.0);
+    float outline_distance = effects.outline_width > 0.0
+        ? to_distance(field.a) + effects.outline_width
+        : distance;
+    float outline = clamp(outline_distance / texels + 0.5, 0.0, 1.0);
+    vec4 glyph = over(
+        vec4(color.rgb, color.a * fill),
+        vec4(effects.outline_color.rgb, effects.outline_color.a * color.a * outline)
+    );
+
+    float shadow_distance = to_distance(texture(glyph_atlas, uv - effects.shadow_offset / atlas_size).a)
+        + effects.outline_width;
+    float shadow = smoothstep(-effects.shadow_softness - 0.5 * texels, 0.5 * texels, shadow_distance);
+    out_color = over(
+        glyph,
+        vec4(effects.shadow_color.rgb, effects.shadow_color.a * color.a * shadow)
+    );
+}
@@ -0,0 +1,243 @@
+//! Screenshots and frame capture.
+//!
+//! Capturing an image records commands copying it into a host-visible buffer. Once the
+//! submission completed, the buffer content is converted into an `Image` and saved to
+//! PNG. The presented image must have been created with the transfer source usage.
+
+use crate::{
+    color::{encode_srgb_u8, half_to_f32},
+    texture::{BufferImageCopy, ImageBarrier, ImageSubresourceRange, TextureFormat},
+};
+use blazar_image::{Image, PixelFormat};
+use blazar_vk_sys as vk_sys;
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Kinds of capture errors.
+#[derive(Debug)]
+pub enum CaptureError {
+    Io(io::Error),
+    UnsupportedFormat(TextureFormat),
+    InvalidData,
+}
+
+impl From<io::Error> for CaptureError {
+    fn from(error: io::Error) -> CaptureError {
+        CaptureError::Io(error)
+    }
+}
+
+/// Commands to record for a readback.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CaptureCommand<T> {
+    Barrier(ImageBarrier<T>),
+    /// Copy from an image to the readback buffer (`vkCmdCopyImageToBuffer`).
+    Copy {
+        image: T,
+        region: BufferImageCopy,
+    },
+    /// Global memory barrier making transfer writes visible to the host.
+    HostBarrier,
+}
+
+/// Returns the size in bytes of the readback of an image.
+pub fn readback_size(width: u32, height: u32, format: TextureFormat) -> u64 {
+    width as u64 * height as u64 * format.bytes_per_texel() as u64
+}
+
+/// Returns the commands copying the first layer of an image in `layout` into a buffer
+/// at `buffer_offset`, leaving the image in its original layout.
+///
+/// `layout` is typically `VK_IMAGE_LAYOUT_PRESENT_SRC_KHR` for a presented image, or
+/// `VK_IMAGE_LAYOUT_COLOR_ATTACHMENT_OPTIMAL` for an offscreen target.
+pub fn readback_commands<T: Copy>(
+    image: T,
+    width: u32,
+    height: u32,
+    layout: vk_sys::VkImageLayout,
+    buffer_offset: u64,
+) -> [CaptureCommand<T>; 4] {
+    let range = ImageSubresourceRange {
+        base_mip_level: 0,
+        level_count: 1,
+        base_array_layer: 0,
+        layer_count: 1,
+    };
+    let barrier = ImageBarrier {
+        image,
+        range,
+        old_layout: layout,
+        new_layout: vk_sys::VK_IMAGE_LAYOUT_TRANSFER_SRC_OPTIMAL,
+        src_access: vk_sys::VK_ACCESS_COLOR_ATTACHMENT_WRITE_BIT,
+        dst_access: vk_sys::VK_ACCESS_TRANSFER_READ_BIT,
+        src_stage: vk_sys::VK_PIPELINE_STAGE_COLOR_ATTACHMENT_OUTPUT_BIT,
+        dst_stage: vk_sys::VK_PIPELINE_STAGE_TRANSFER_BIT,
+        src_queue_family: vk_sys::VK_QUEUE_FAMILY_IGNORED,
+        dst_queue_family: vk_sys::VK_QUEUE_FAMILY_IGNORED,
+    };
+    [
+        CaptureCommand::Barrier(barrier),
+        CaptureCommand::Copy {
+            image,
+            region: BufferImageCopy {
+                buffer_offset,
+                mip_level: 0,
+                base_array_layer: 0,
+                layer_count: 1,
+                width,
+                height,
+            },
+        },
+        CaptureCommand::Barrier(ImageBarrier {
+            old_layout: vk_sys::VK_IMAGE_LAYOUT_TRANSFER_SRC_OPTIMAL,
+            new_layout: layout,
+            src_access: vk_sys::VK_ACCESS_TRANSFER_READ_BIT,
+            dst_access: vk_sys::VK_ACCESS_MEMORY_READ_BIT,
+            src_stage: vk_sys::VK_PIPELINE_STAGE_TRANSFER_BIT,
+            dst_stage: vk_sys::VK_PIPELINE_STAGE_BOTTOM_OF_PIPE_BIT,
+            ..barrier
+        }),
+        CaptureCommand::HostBarrier,
+    ]
+}
+
+/// Converts the tightly packed readback of an image into an `Image`.
+///
+/// The alpha of presented images being meaningless, it is made opaque. Floating-point
+/// formats are considered linear and encoded to sRGB, other formats are kept as is.
+pub fn readback_image(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    format: TextureFormat,
+) -> Result<Image, CaptureError> {
+    if data.len() as u64 != readback_size(width, height, format) {
+        return Err(CaptureError::InvalidData);
+    }
+    let texel_size = format.bytes_per_texel();
+    if format == TextureFormat::Rgba16Unorm {
+        let mut pixels = data.to_vec();
+        for texel in pixels.chunks_mut(texel_size) {
+            texel[6..8].copy_from_slice(&u16::MAX.to_ne_bytes());
+        }
+        return Image::from_raw(width, height, PixelFormat::Rgba16, pixels)
+            .ok_or(CaptureError::InvalidData);
+    }
+    let convert: fn(&[u8]) -> [u8; 3] = match format {
+        TextureFormat::R8Unorm => |texel| [texel[0], texel[0], texel[0]],
+        TextureFormat::Rg8Unorm => |texel| [texel[0], texel[1], 0],
+        TextureFormat::Rgba8Unorm | TextureFormat::Rgba8Srgb => {
+            |texel| [texel[0], texel[1], texel[2]]
+        }
+        TextureFormat::Bgra8Unorm | TextureFormat::Bgra8Srgb => {
+            |texel| [texel[2], texel[1], texel[0]]
+        }
+        TextureFormat::Rgba16Float => |texel| {
+            let channel = |index: usize| {
+                let bits = u16::from_ne_bytes([texel[index * 2], texel[index * 2 + 1]]);
+                encode_srgb_u8(half_to_f32(bits))
+            };
+            [channel(0), channel(1), channel(2)]
+        },
+        TextureFormat::Rgba32Float => |texel| {
+            let channel = |index: usize| {
+                let mut bytes = [0; 4];
+                bytes.copy_from_slice(&texel[index * 4..index * 4 + 4]);
+                encode_srgb_u8(f32::from_bits(u32::from_ne_bytes(bytes)))
+            };
+            [channel(0), channel(1), channel(2)]
+        },
+        _ => return Err(CaptureError::UnsupportedFormat(format)),
+    };
+    let mut pixels = Vec::with_capacity(width as usize * height as usize * 4);
+    for texel in data.chunks(texel_size) {
+        let [red, green, blue] = convert(texel);
+        pixels.extend_from_slice(&[red, green, blue, 255]);
+    }
+    Image::from_raw(width, height, PixelFormat::Rgba8, pixels).ok_or(CaptureError::InvalidData)
+}
+
+/// Numbered image sequence.
+#[derive(Clone, Debug)]
+struct Sequence {
+    directory: PathBuf,
+    prefix: String,
+    interval: u32,
+    frame: u64,
+    number: u64,
+}
+
+/// Schedules screenshots and image sequences.
+///
+/// The renderer calls `begin_frame` once per frame, captures the presented image when
+/// it returns paths, then saves the image to all of them once read back.
+#[derive(Clone, Debug, Default)]
+pub struct FrameCapture {
+    screenshots: Vec<PathBuf>,
+    sequence: Option<Sequence>,
+}
+
+impl FrameCapture {
+    /// Creates a schedule capturing nothing.
+    pub fn new() -> FrameCapture {
+        FrameCapture::default()
+    }
+
+    /// Requests a screenshot of the next frame.
+    pub fn screenshot<P: AsRef<Path>>(&mut self, path: P) {
+        self.screenshots.push(path.as_ref().to_path_buf());
+    }
+
+    /// Starts saving every `interval`th frame, beginning with the next one, to
+    /// `directory/prefixNNNNNN.png`, creating the directory if needed.
+    pub fn start_sequence<P: AsRef<Path>>(
+        &mut self,
+        directory: P,
+        prefix: &str,
+        interval: u32,
+    ) -> io::Result<()> {
+        fs::create_dir_all(&directory)?;
+        self.sequence = Some(Sequence {
+            directory: directory.as_ref().to_path_buf(),
+            prefix: String::from(prefix),
+            interval: interval.max(1),
+            frame: 0,
+            number: 0,
+        });
+        Ok(())
+    }
+
+    /// Stops the image sequence.
+    pub fn stop_sequence(&mut self) {
+        self.sequence = None;
+    }
+
+    /// Returns `true` if an image sequence is being saved.
+    pub fn is_recording(&self) -> bool {
+        self.sequence.is_some()
+    }
+
+    /// Advances to a new frame, returning the paths it must be saved to, if any.
+    pub fn begin_frame(&mut self) -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = self.screenshots.drain(..).collect();
+        if let Some(sequence) = &mut self.sequence {
+            if sequence.frame % u64::from(sequence.interval) == 0 {
+                let name = format!("{}{:06}.png", sequence.prefix, sequence.number);
+                paths.push(sequence.directory.join(name));
+                sequence.number += 1;
+            }
+            sequence.frame += 1;
+        }
+        paths
+    }
+}
+
+/// Saves an image to PNG at every path.
+pub fn save(image: &Image, paths: &[PathBuf]) -> io::Result<()> {
+    for path in paths {
+        image.save_png(path)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,634 @@
+//! Colors and sRGB conversions.
+//!
+//! Shaders, blending, vertex colors, tints and clear values work on linear components;
+//! the hardware encodes them when writing to sRGB attachments. Colors picked by hand, in
+//! hex or HSL, are usually sRGB-encoded and must be converted first.
+
+use crate::texture::TextureFormat;
+
+/// Decodes an sRGB-encoded component with the exact piecewise transfer function.
+pub fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Encodes a linear component to sRGB with the exact piecewise transfer function.
+pub fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.003_130_8 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Color with linear components, alpha not premultiplied.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LinearRgba {
+    pub red: f32,
+    pub green: f32,
+    pub blue: f32,
+    pub alpha: f32,
+}
+
+impl LinearRgba {
+    pub const BLACK: LinearRgba = LinearRgba::new(0.0, 0.0, 0.0, 1.0);
+    pub const WHITE: LinearRgba = LinearRgba::new(1.0, 1.0, 1.0, 1.0);
+    pub const TRANSPARENT: LinearRgba = LinearRgba::new(0.0, 0.0, 0.0, 0.0);
+
+    /// Creates a color from linear components.
+    pub const fn new(red: f32, green: f32, blue: f32, alpha: f32) -> LinearRgba {
+        LinearRgba {
+            red,
+            green,
+            blue,
+            alpha,
+        }
+    }
+
+    /// Creates an opaque color from linear components.
+    pub const fn rgb(red: f32, green: f32, blue: f32) -> LinearRgba {
+        LinearRgba::new(red, green, blue, 1.0)
+    }
+
+    /// Returns the color with another alpha.
+    pub fn with_alpha(mut self, alpha: f32) -> LinearRgba {
+        self.alpha = alpha;
+        self
+    }
+
+    /// Returns the components in RGBA order.
+    pub fn to_array(self) -> [f32; 4] {
+        [self.red, self.green, self.blue, self.alpha]
+    }
+
+    /// Multiplies the color components by alpha, as expected by the
+    /// `BlendState::PREMULTIPLIED_ALPHA` blending.
+    pub fn premultiply(self) -> LinearRgba {
+        LinearRgba::new(
+            self.red * self.alpha,
+            self.green * self.alpha,
+            self.blue * self.alpha,
+            self.alpha,
+        )
+    }
+
+    /// Divides premultiplied color components by alpha, a zero alpha giving transparent
+    /// black.
+    pub fn unpremultiply(self) -> LinearRgba {
+        if self.alpha <= 0.0 {
+            return LinearRgba::TRANSPARENT;
+        }
+        LinearRgba::new(
+            self.red / self.alpha,
+            self.green / self.alpha,
+            self.blue / self.alpha,
+            self.alpha,
+        )
+    }
+
+    /// Interpolates linearly between both colors, which is physically correct unlike
+    /// interpolating sRGB-encoded components.
+    pub fn lerp(self, other: LinearRgba, t: f32) -> LinearRgba {
+        let mix = |a: f32, b: f32| a + (b - a) * t;
+        LinearRgba::new(
+            mix(self.red, other.red),
+            mix(self.green, other.green),
+            mix(self.blue, other.blue),
+            mix(self.alpha, other.alpha),
+        )
+    }
+
+    /// Returns the relative luminance.
+    pub fn luminance(self) -> f32 {
+        0.2126 * self.red + 0.7152 * self.green + 0.0722 * self.blue
+    }
+
+    /// Encodes the color to sRGB.
+    pub fn to_srgba(self) -> Srgba {
+        Srgba::new(
+            linear_to_srgb(self.red),
+            linear_to_srgb(self.green),
+            linear_to_srgb(self.blue),
+            self.alpha,
+        )
+    }
+
+    /// Encodes the color as a texel of `format`, sampling it giving back the linear
+    /// components, clamped to the range of the format.
+    ///
+    /// Only the red component is kept by `R8Unorm`, red and green by `Rg8Unorm`.
+    pub fn encode(self, format: TextureFormat) -> Vec<u8> {
+        let unorm8 = |value: f32| (clamp_unit(value) * 255.0 + 0.5) as u8;
+        let [red, green, blue, alpha] = self.to_array();
+        match format {
+            TextureFormat::R8Unorm => vec![unorm8(red)],
+            TextureFormat::Rg8Unorm => vec![unorm8(red), unorm8(green)],
+            TextureFormat::Rgba8Unorm => {
+                vec![unorm8(red), unorm8(green), unorm8(blue), unorm8(alpha)]
+            }
+            TextureFormat::Bgra8Unorm => {
+                vec![unorm8(blue), unorm8(green), unorm8(red), unorm8(alpha)]
+            }
+            TextureFormat::Rgba8Srgb => self.to_srgba().to_u8_array().to_vec(),
+            TextureFormat::Bgra8Srgb => {
+                let [red, green, blue, alpha] = self.to_srgba().to_u8_array();
+                vec![blue, green, red, alpha]
+            }
+            TextureFormat::Rgba16Unorm => self
+                .to_array()
+                .iter()
+                .flat_map(|value| {
+                    ((clamp_unit(*value) * 65535.0 + 0.5) as u16)
+                        .to_ne_bytes()
+                        .to_vec()
+                })
+                .collect(),
+            TextureFormat::Rgba16Float => self
+                .to_array()
+                .iter()
+                .flat_map(|value| f32_to_half(*value).to_ne_bytes().to_vec())
+                .collect(),
+            TextureFormat::Rgba32Float => self
+                .to_array()
+                .iter()
+                .flat_map(|value| value.to_ne_bytes().to_vec())
+                .collect(),
+        }
+    }
+
+    /// Decodes a texel of `format`, or returns `None` if its size does not match.
+    ///
+    /// Missing components are 0, and alpha 1.
+    pub fn decode(texel: &[u8], format: TextureFormat) -> Option<LinearRgba> {
+        if texel.len() != format.bytes_per_texel() {
+            return None;
+        }
+        let unorm8 = |index: usize| f32::from(texel[index]) / 255.0;
+        let wide = |index: usize| [texel[index * 2], texel[index * 2 + 1]];
+        Some(match format {
+            TextureFormat::R8Unorm => LinearRgba::rgb(unorm8(0), 0.0, 0.0),
+            TextureFormat::Rg8Unorm => LinearRgba::rgb(unorm8(0), unorm8(1), 0.0),
+            TextureFormat::Rgba8Unorm => {
+                LinearRgba::new(unorm8(0), unorm8(1), unorm8(2), unorm8(3))
+            }
+            TextureFormat::Bgra8Unorm => {
+                LinearRgba::new(unorm8(2), unorm8(1), unorm8(0), unorm8(3))
+            }
+            TextureFormat::Rgba8Srgb => {
+                Srgba::from_u8(texel[0], texel[1], texel[2], texel[3]).to_linear()
+            }
+            TextureFormat::Bgra8Srgb => {
+                Srgba::from_u8(texel[2], texel[1], texel[0], texel[3]).to_linear()
+            }
+            TextureFormat::Rgba16Unorm => {
+                let channel = |index| f32::from(u16::from_ne_bytes(wide(index))) / 65535.0;
+                LinearRgba::new(channel(0), channel(1), channel(2), channel(3))
+            }
+            TextureFormat::Rgba16Float => {
+                let channel = |index| half_to_f32(u16::from_ne_bytes(wide(index)));
+                LinearRgba::new(channel(0), channel(1), channel(2), channel(3))
+            }
+            TextureFormat::Rgba32Float => {
+                let channel = |index: usize| {
+                    let mut bytes = [0; 4];
+                    bytes.copy_from_slice(&texel[index * 4..index * 4 + 4]);
+                    f32::from_ne_bytes(bytes)
+                };
+                LinearRgba::new(channel(0), channel(1), channel(2), channel(3))
+            }
+        })
+    }
+}
+
+impl From<[f32; 4]> for LinearRgba {
+    fn from(array: [f32; 4]) -> LinearRgba {
+        LinearRgba::new(array[0], array[1], array[2], array[3])
+    }
+}
+
+impl From<LinearRgba> for [f32; 4] {
+    fn from(color: LinearRgba) -> [f32; 4] {
+        color.to_array()
+    }
+}
+
+/// Color with sRGB-encoded components, alpha being linear.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Srgba {
+    pub red: f32,
+    pub green: f32,
+    pub blue: f32,
+    pub alpha: f32,
+}
+
+impl Srgba {
+    pub const BLACK: Srgba = Srgba::new(0.0, 0.0, 0.0, 1.0);
+    pub const WHITE: Srgba = Srgba::new(1.0, 1.0, 1.0, 1.0);
+    pub const TRANSPARENT: Srgba = Srgba::new(0.0, 0.0, 0.0, 0.0);
+
+    /// Creates a color from sRGB-encoded components.
+    pub const fn new(red: f32, green: f32, blue: f32, alpha: f32) -> Srgba {
+        Srgba {
+            red,
+            green,
+            blue,
+            alpha,
+        }
+    }
+
+    /// Creates an opaque color from sRGB-encoded components.
+    pub const fn rgb(red: f32, green: f32, blue: f32) -> Srgba {
+        Srgba::new(red, green, blue, 1.0)
+    }
+
+    /// Creates a color from 8-bit sRGB-encoded components.
+    pub fn from_u8(red: u8, green: u8, blue: u8, alpha: u8) -> Srgba {
+        Srgba::new(
+            f32::from(red) / 255.0,
+            f32::from(green) / 255.0,
+            f32::from(blue) / 255.0,
+            f32::from(alpha) / 255.0,
+        )
+    }
+
+    /// Parses `RGB`, `RGBA`, `RRGGBB` or `RRGGBBAA` hexadecimal digits, optionally
+    /// prefixed by `#`.
+    pub fn hex(hex: &str) -> Option<Srgba> {
+        let hex = if hex.starts_with('#') { &hex[1..] } else { hex };
+        if !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return None;
+        }
+        let digit = |index: usize| u8::from_str_radix(&hex[index..index + 1], 16).unwrap();
+        let byte = |index: usize| u8::from_str_radix(&hex[index * 2..index * 2 + 2], 16).unwrap();
+        match hex.len() {
+            3 => Some(Srgba::from_u8(
+                digit(0) * 17,
+                digit(1) * 17,
+                digit(2) * 17,
+                255,
+            )),
+            4 => Some(Srgba::from_u8(
+                digit(0) * 17,
+                digit(1) * 17,
+                digit(2) * 17,
+                digit(3) * 17,
+            )),
+            6 => Some(Srgba::from_u8(byte(0), byte(1), byte(2), 255)),
+            8 => Some(Srgba::from_u8(byte(0), byte(1), byte(2), byte(3))),
+            _ => None,
+        }
+    }
+
+    /// Formats the color as `#rrggbb`, or `#rrggbbaa` if it is not opaque.
+    pub fn to_hex(self) -> String {
+        let [red, green, blue, alpha] = self.to_u8_array();
+        if alpha == 255 {
+            format!("#{:02x}{:02x}{:02x}", red, green, blue)
+        } else {
+            format!("#{:02x}{:02x}{:02x}{:02x}", red, green, blue, alpha)
+        }
+    }
+
+    /// Returns the color with another alpha.
+    pub fn with_alpha(mut self, alpha: f32) -> Srgba {
+        self.alpha = alpha;
+        self
+    }
+
+    /// Returns the components in RGBA order.
+    pub fn to_array(self) -> [f32; 4] {
+        [self.red, self.green, self.blue, self.alpha]
+    }
+
+    /// Returns the components rounded to 8 bits, clamped to `[0, 1]`.
+    pub fn to_u8_array(self) -> [u8; 4] {
+        let unorm8 = |value: f32| (clamp_unit(value) * 255.0 + 0.5) as u8;
+        [
+            unorm8(self.red),
+            unorm8(self.green),
+            unorm8(self.blue),
+            unorm8(self.alpha),
+        ]
+    }
+
+    /// Decodes the color to linear components.
+    pub fn to_linear(self) -> LinearRgba {
+        LinearRgba::new(
+            srgb_to_linear(self.red),
+            srgb_to_linear(self.green),
+            srgb_to_linear(self.blue),
+            self.alpha,
+        )
+    }
+
+    /// Converts the color to hue, saturation and lightness.
+    pub fn to_hsla(self) -> Hsla {
+        let max = self.red.max(self.green).max(self.blue);
+        let min = self.red.min(self.green).min(self.blue);
+        let lightness = (max + min) / 2.0;
+        let chroma = max - min;
+        if chroma <= 0.0 {
+            return Hsla::new(0.0, 0.0, lightness, self.alpha);
+        }
+        let saturation = chroma / (1.0 - (2.0 * lightness - 1.0).abs());
+        let hue = if max == self.red {
+            ((self.green - self.blue) / chroma).rem_euclid(6.0)
+        } else if max == self.green {
+            (self.blue - self.red) / chroma + 2.0
+        } else {
+            (self.red - self.green) / chroma + 4.0
+        };
+        Hsla::new(hue * 60.0, saturation, lightness, self.alpha)
+    }
+}
+
+impl From<[u8; 4]> for Srgba {
+    fn from(array: [u8; 4]) -> Srgba {
+        Srgba::from_u8(array[0], array[1], array[2], array[3])
+    }
+}
+
+/// Color given by its hue, saturation and lightness in the sRGB color space.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Hsla {
+    /// Hue in degrees, wrapped to `[0, 360)`.
+    pub hue: f32,
+    pub saturation: f32,
+    pub lightness: f32,
+    pub alpha: f32,
+}
+
+impl Hsla {
+    /// Creates a color from its hue in degrees, saturation, lightness and alpha.
+    pub const fn new(hue: f32, saturation: f32, lightness: f32, alpha: f32) -> Hsla {
+        Hsla {
+            hue,
+            saturation,
+            lightness,
+            alpha,
+        }
+    }
+
+    /// Creates an opaque color from its hue in degrees, saturation and lightness.
+    pub const fn hsl(hue: f32, saturation: f32, lightness: f32) -> Hsla {
+        Hsla::new(hue, saturation, lightness, 1.0)
+    }
+
+    /// Returns the color with another alpha.
+    pub fn with_alpha(mut self, alpha: f32) -> Hsla {
+        self.alpha = alpha;
+        self
+    }
+
+    /// Converts the color to sRGB-encoded components.
+    pub fn to_srgba(self) -> Srgba {
+        let chroma = (1.0 - (2.0 * self.lightness - 1.0).abs()) * self.saturation;
+        let hue = self.hue.rem_euclid(360.0) / 60.0;
+        let x = chroma * (1.0 - (hue.rem_euclid(2.0) - 1.0).abs());
+        let (red, green, blue) = match hue as u32 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        let m = self.lightness - chroma / 2.0;
+        Srgba::new(red + m, green + m, blue + m, self.alpha)
+    }
+
+    /// Converts the color to linear components.
+    pub fn to_linear(self) -> LinearRgba {
+        self.to_srgba().to_linear()
+    }
+}
+
+/// Color in any of the supported color spaces.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Color {
+    Srgba(Srgba),
+    LinearRgba(LinearRgba),
+    Hsla(Hsla),
+}
+
+impl Default for Color {
+    fn default() -> Color {
+        Color::WHITE
+    }
+}
+
+impl Color {
+    pub const BLACK: Color = Color::Srgba(Srgba::BLACK);
+    pub const WHITE: Color = Color::Srgba(Srgba::WHITE);
+    pub const TRANSPARENT: Color = Color::Srgba(Srgba::TRANSPARENT);
+    pub const RED: Color = Color::Srgba(Srgba::rgb(1.0, 0.0, 0.0));
+    pub const GREEN: Color = Color::Srgba(Srgba::rgb(0.0, 1.0, 0.0));
+    pub const BLUE: Color = Color::Srgba(Srgba::rgb(0.0, 0.0, 1.0));
+
+    /// Creates an opaque color from sRGB-encoded components.
+    pub const fn srgb(red: f32, green: f32, blue: f32) -> Color {
+        Color::Srgba(Srgba::rgb(red, green, blue))
+    }
+
+    /// Creates a color from sRGB-encoded components.
+    pub const fn srgba(red: f32, green: f32, blue: f32, alpha: f32) -> Color {
+        Color::Srgba(Srgba::new(red, green, blue, alpha))
+    }
+
+    /// Creates an opaque color from 8-bit sRGB-encoded components.
+    pub fn srgb_u8(red: u8, green: u8, blue: u8) -> Color {
+        Color::Srgba(Srgba::from_u8(red, green, blue, 255))
+    }
+
+    /// Creates a color from 8-bit sRGB-encoded components.
+    pub fn srgba_u8(red: u8, green: u8, blue: u8, alpha: u8) -> Color {
+        Color::Srgba(Srgba::from_u8(red, green, blue, alpha))
+    }
+
+    /// Creates an opaque color from linear components.
+    pub const fn linear_rgb(red: f32, green: f32, blue: f32) -> Color {
+        Color::LinearRgba(LinearRgba::rgb(red, green, blue))
+    }
+
+    /// Creates a color from linear components.
+    pub const fn linear_rgba(red: f32, green: f32, blue: f32, alpha: f32) -> Color {
+        Color::LinearRgba(LinearRgba::new(red, green, blue, alpha))
+    }
+
+    /// Creates an opaque color from its hue in degrees, saturation and lightness.
+    pub const fn hsl(hue: f32, saturation: f32, lightness: f32) -> Color {
+        Color::Hsla(Hsla::hsl(hue, saturation, lightness))
+    }
+
+    /// Creates a color from its hue in degrees, saturation, lightness and alpha.
+    pub const fn hsla(hue: f32, saturation: f32, lightness: f32, alpha: f32) -> Color {
+        Color::Hsla(Hsla::new(hue, saturation, lightness, alpha))
+    }
+
+    /// Parses an sRGB hex color, see `Srgba::hex`.
+    pub fn hex(hex: &str) -> Option<Color> {
+        Srgba::hex(hex).map(Color::Srgba)
+    }
+
+    /// Returns the alpha, which is the same in every color space.
+    pub fn alpha(self) -> f32 {
+        match self {
+            Color::Srgba(color) => color.alpha,
+            Color::LinearRgba(color) => color.alpha,
+            Color::Hsla(color) => color.alpha,
+        }
+    }
+
+    /// Returns the color with another alpha, keeping its color space.
+    pub fn with_alpha(self, alpha: f32) -> Color {
+        match self {
+            Color::Srgba(color) => Color::Srgba(color.with_alpha(alpha)),
+            Color::LinearRgba(color) => Color::LinearRgba(color.with_alpha(alpha)),
+            Color::Hsla(color) => Color::Hsla(color.with_alpha(alpha)),
+        }
+    }
+
+    /// Converts the color to linear components.
+    pub fn to_linear(self) -> LinearRgba {
+        match self {
+            Color::Srgba(color) => color.to_linear(),
+            Color::LinearRgba(color) => color,
+            Color::Hsla(color) => color.to_linear(),
+        }
+    }
+
+    /// Converts the color to sRGB-encoded components.
+    pub fn to_srgba(self) -> Srgba {
+        match self {
+            Color::Srgba(color) => color,
+            Color::LinearRgba(color) => color.to_srgba(),
+            Color::Hsla(color) => color.to_srgba(),
+        }
+    }
+
+    /// Converts the color to hue, saturation and lightness.
+    pub fn to_hsla(self) -> Hsla {
+        match self {
+            Color::Hsla(color) => color,
+            color => color.to_srgba().to_hsla(),
+        }
+    }
+
+    /// Returns the linear components with alpha premultiplied.
+    pub fn premultiplied(self) -> LinearRgba {
+        self.to_linear().premultiply()
+    }
+
+    /// Encodes the color as a texel of `format`, see `LinearRgba::encode`.
+    pub fn encode(self, format: TextureFormat) -> Vec<u8> {
+        self.to_linear().encode(format)
+    }
+}
+
+impl From<Srgba> for Color {
+    fn from(color: Srgba) -> Color {
+        Color::Srgba(color)
+    }
+}
+
+impl From<LinearRgba> for Color {
+    fn from(color: LinearRgba) -> Color {
+        Color::LinearRgba(color)
+    }
+}
+
+impl From<Hsla> for Color {
+    fn from(color: Hsla) -> Color {
+        Color::Hsla(color)
+    }
+}
+
+impl From<Color> for LinearRgba {
+    fn from(color: Color) -> LinearRgba {
+        color.to_linear()
+    }
+}
+
+impl From<Srgba> for LinearRgba {
+    fn from(color: Srgba) -> LinearRgba {
+        color.to_linear()
+    }
+}
+
+impl From<Hsla> for LinearRgba {
+    fn from(color: Hsla) -> LinearRgba {
+        color.to_linear()
+    }
+}
+
+/// Returns the linear components, as expected by shaders and clear values.
+impl From<Color> for [f32; 4] {
+    fn from(color: Color) -> [f32; 4] {
+        color.to_linear().to_array()
+    }
+}
+
+/// Clamps a value to `[0, 1]`, NaN giving 0.
+fn clamp_unit(value: f32) -> f32 {
+    if value.is_nan() {
+        0.0
+    } else {
+        value.max(0.0).min(1.0)
+    }
+}
+
+/// Encodes a linear value to an 8-bit sRGB value, clamping it to `[0, 1]`.
+pub(crate) fn encode_srgb_u8(value: f32) -> u8 {
+    (linear_to_srgb(clamp_unit(value)) * 255.0 + 0.5) as u8
+}
+
+/// Converts a half-precision float to single precision.
+pub(crate) fn half_to_f32(half: u16) -> f32 {
+    let sign = if half & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = (half >> 10) & 0x1f;
+    let mantissa = f32::from(half & 0x3ff);
+    sign * match exponent {
+        0 => mantissa * 2f32.powi(-24),
+        0x1f if mantissa == 0.0 => f32::INFINITY,
+        0x1f => f32::NAN,
+        _ => (1.0 + mantissa / 1024.0) * 2f32.powi(i32::from(exponent) - 15),
+    }
+}
+
+/// Converts a single-precision float to half precision, rounding to nearest even.
+pub(crate) fn f32_to_half(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x007f_ffff;
+    if exponent == 0xff {
+        let nan = if mantissa != 0 { 0x200 } else { 0 };
+        return sign | 0x7c00 | nan;
+    }
+    let exponent = exponent - 127 + 15;
+    if exponent >= 0x1f {
+        return sign | 0x7c00;
+    }
+    if exponent <= 0 {
+        if exponent < -10 {
+            return sign;
+        }
+        // Subnormal, the implicit bit made explicit.
+        let mantissa = mantissa | 0x0080_0000;
+        let shift = (14 - exponent) as u32;
+        let half = mantissa >> shift;
+        let remainder = mantissa & ((1 << shift) - 1);
+        let halfway = 1 << (shift - 1);
+        let round = remainder > halfway || (remainder == halfway && half & 1 != 0);
+        return sign | (half + round as u32) as u16;
+    }
+    let half = ((exponent as u32) << 10) | (mantissa >> 13);
+    let remainder = mantissa & 0x1fff;
+    let round = remainder > 0x1000 || (remainder == 0x1000 && half & 1 != 0);
+    // A carry into the exponent correctly rounds up to the next power of two or infinity.
+    sign | (half + round as u32) as u16
+}
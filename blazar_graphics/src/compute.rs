@@ -0,0 +1,276 @@
+//! Compute dispatches and asynchronous compute.
+//!
+//! Compute pipelines are described by `ComputePipelineDescription`, their storage buffers
+//! and images being bound with `DescriptorType::StorageBuffer` and
+//! `DescriptorType::StorageImage`. Work can run on a dedicated compute queue family,
+//! alongside rendering: buffers shared with the graphics queue then move from a family to
+//! the other, the consuming submission waiting on a semaphore signaled by the producing
+//! one.
+
+use blazar_vk_sys as vk_sys;
+
+/// Properties of a queue family (`VkQueueFamilyProperties`).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct QueueFamily {
+    pub flags: vk_sys::VkQueueFlags,
+    pub queue_count: u32,
+}
+
+impl QueueFamily {
+    /// Returns `true` if the family supports graphics operations.
+    pub fn supports_graphics(&self) -> bool {
+        self.queue_count > 0 && self.flags & vk_sys::VK_QUEUE_GRAPHICS_BIT != 0
+    }
+
+    /// Returns `true` if the family supports compute operations.
+    pub fn supports_compute(&self) -> bool {
+        self.queue_count > 0 && self.flags & vk_sys::VK_QUEUE_COMPUTE_BIT != 0
+    }
+}
+
+/// Queues submitting work.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Queue {
+    Graphics,
+    Compute,
+}
+
+/// Queue families submitting rendering and compute work.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ComputeQueues {
+    pub graphics_family: u32,
+    pub compute_family: u32,
+}
+
+impl ComputeQueues {
+    /// Selects the queue families among the ones returned by
+    /// `vkGetPhysicalDeviceQueueFamilyProperties`.
+    ///
+    /// With `async_compute`, a family supporting compute but not graphics is preferred for
+    /// compute work; otherwise, or if there is none, compute work is submitted to the
+    /// graphics queue. Returns `None` if no family supports both graphics and compute.
+    pub fn select(families: &[QueueFamily], async_compute: bool) -> Option<ComputeQueues> {
+        let graphics_family = families
+            .iter()
+            .position(|family| family.supports_graphics() && family.supports_compute())?
+            as u32;
+        let compute_family = if async_compute {
+            families
+                .iter()
+                .position(|family| family.supports_compute() && !family.supports_graphics())
+                .map_or(graphics_family, |index| index as u32)
+        } else {
+            graphics_family
+        };
+        Some(ComputeQueues {
+            graphics_family,
+            compute_family,
+        })
+    }
+
+    /// Returns `true` if compute work runs on a dedicated queue family.
+    pub fn is_async(&self) -> bool {
+        self.graphics_family != self.compute_family
+    }
+
+    /// Returns the family of a queue.
+    pub fn family(&self, queue: Queue) -> u32 {
+        match queue {
+            Queue::Graphics => self.graphics_family,
+            Queue::Compute => self.compute_family,
+        }
+    }
+
+    /// Makes the accesses of `src` to a whole buffer visible to the ones of `dst`.
+    ///
+    /// If both accesses happen on the same queue family, a single barrier is recorded.
+    /// Otherwise, the ownership of the buffer moves from a family to the other.
+    pub fn hand_off<T: Copy>(&self, buffer: T, src: BufferAccess, dst: BufferAccess) -> HandOff<T> {
+        let barrier = BufferBarrier {
+            buffer,
+            offset: 0,
+            size: vk_sys::VK_WHOLE_SIZE,
+            src_access: src.access,
+            dst_access: dst.access,
+            src_stage: src.stage,
+            dst_stage: dst.stage,
+            src_queue_family: vk_sys::VK_QUEUE_FAMILY_IGNORED,
+            dst_queue_family: vk_sys::VK_QUEUE_FAMILY_IGNORED,
+        };
+        let src_family = self.family(src.queue);
+        let dst_family = self.family(dst.queue);
+        if src_family == dst_family {
+            return HandOff {
+                release: None,
+                acquire: barrier,
+                wait_stage: None,
+            };
+        }
+        // Release on the source queue, then acquire on the destination queue.
+        let barrier = BufferBarrier {
+            src_queue_family: src_family,
+            dst_queue_family: dst_family,
+            ..barrier
+        };
+        HandOff {
+            release: Some(BufferBarrier {
+                dst_access: 0,
+                dst_stage: vk_sys::VK_PIPELINE_STAGE_BOTTOM_OF_PIPE_BIT,
+                ..barrier
+            }),
+            acquire: BufferBarrier {
+                src_access: 0,
+                src_stage: vk_sys::VK_PIPELINE_STAGE_TOP_OF_PIPE_BIT,
+                ..barrier
+            },
+            wait_stage: Some(dst.stage),
+        }
+    }
+}
+
+/// Access to a buffer by a queue.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct BufferAccess {
+    pub queue: Queue,
+    pub stage: vk_sys::VkPipelineStageFlags,
+    pub access: vk_sys::VkAccessFlags,
+}
+
+impl BufferAccess {
+    /// Storage buffer written by a compute shader.
+    pub const COMPUTE_WRITE: BufferAccess = BufferAccess {
+        queue: Queue::Compute,
+        stage: vk_sys::VK_PIPELINE_STAGE_COMPUTE_SHADER_BIT,
+        access: vk_sys::VK_ACCESS_SHADER_WRITE_BIT,
+    };
+    /// Storage buffer read by a compute shader.
+    pub const COMPUTE_READ: BufferAccess = BufferAccess {
+        queue: Queue::Compute,
+        stage: vk_sys::VK_PIPELINE_STAGE_COMPUTE_SHADER_BIT,
+        access: vk_sys::VK_ACCESS_SHADER_READ_BIT,
+    };
+    /// Vertex buffer read by draws.
+    pub const VERTEX_READ: BufferAccess = BufferAccess {
+        queue: Queue::Graphics,
+        stage: vk_sys::VK_PIPELINE_STAGE_VERTEX_INPUT_BIT,
+        access: vk_sys::VK_ACCESS_VERTEX_ATTRIBUTE_READ_BIT,
+    };
+    /// Storage buffer read by vertex and fragment shaders.
+    pub const SHADER_READ: BufferAccess = BufferAccess {
+        queue: Queue::Graphics,
+        stage: vk_sys::VK_PIPELINE_STAGE_VERTEX_SHADER_BIT
+            | vk_sys::VK_PIPELINE_STAGE_FRAGMENT_SHADER_BIT,
+        access: vk_sys::VK_ACCESS_SHADER_READ_BIT,
+    };
+    /// Parameters read by indirect draws.
+    pub const DRAW_INDIRECT_READ: BufferAccess = BufferAccess {
+        queue: Queue::Graphics,
+        stage: vk_sys::VK_PIPELINE_STAGE_DRAW_INDIRECT_BIT,
+        access: vk_sys::VK_ACCESS_INDIRECT_COMMAND_READ_BIT,
+    };
+    /// Parameters read by indirect dispatches.
+    pub const DISPATCH_INDIRECT_READ: BufferAccess = BufferAccess {
+        queue: Queue::Compute,
+        stage: vk_sys::VK_PIPELINE_STAGE_DRAW_INDIRECT_BIT,
+        access: vk_sys::VK_ACCESS_INDIRECT_COMMAND_READ_BIT,
+    };
+}
+
+/// Describes a buffer memory barrier (`VkBufferMemoryBarrier`) and the stages it
+/// synchronizes.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct BufferBarrier<T> {
+    pub buffer: T,
+    pub offset: u64,
+    pub size: u64,
+    pub src_access: vk_sys::VkAccessFlags,
+    pub dst_access: vk_sys::VkAccessFlags,
+    pub src_stage: vk_sys::VkPipelineStageFlags,
+    pub dst_stage: vk_sys::VkPipelineStageFlags,
+    pub src_queue_family: u32,
+    pub dst_queue_family: u32,
+}
+
+/// Barriers handing a buffer off between accesses.
+///
+/// `release` is recorded on the source queue and `acquire` on the destination queue. With
+/// a `wait_stage`, the destination submission waits at this stage on a semaphore signaled
+/// by the source one.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct HandOff<T> {
+    pub release: Option<BufferBarrier<T>>,
+    pub acquire: BufferBarrier<T>,
+    pub wait_stage: Option<vk_sys::VkPipelineStageFlags>,
+}
+
+/// Commands to record for compute work, once the compute pipeline and its descriptor sets
+/// are bound.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ComputeCommand<T> {
+    /// Dispatch (`vkCmdDispatch`).
+    Dispatch {
+        group_count_x: u32,
+        group_count_y: u32,
+        group_count_z: u32,
+    },
+    /// Dispatch reading a `DispatchIndirectCommand` from a buffer
+    /// (`vkCmdDispatchIndirect`).
+    DispatchIndirect {
+        buffer: T,
+        offset: u64,
+    },
+    Barrier(BufferBarrier<T>),
+}
+
+impl<T> ComputeCommand<T> {
+    /// Creates a dispatch covering `size` invocations with workgroups of `local_size`
+    /// invocations, as declared by the shader.
+    pub fn dispatch(size: [u32; 3], local_size: [u32; 3]) -> ComputeCommand<T> {
+        ComputeCommand::Dispatch {
+            group_count_x: group_count(size[0], local_size[0]),
+            group_count_y: group_count(size[1], local_size[1]),
+            group_count_z: group_count(size[2], local_size[2]),
+        }
+    }
+}
+
+/// Returns the number of workgroups of `local_size` invocations covering `size`
+/// invocations.
+///
+/// Shaders must skip invocations beyond `size` in the last workgroup.
+pub fn group_count(size: u32, local_size: u32) -> u32 {
+    let local_size = local_size.max(1);
+    size / local_size + (size % local_size != 0) as u32
+}
+
+/// Parameters of an indirect dispatch (`VkDispatchIndirectCommand`).
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct DispatchIndirectCommand {
+    pub x: u32,
+    pub y: u32,
+    pub z: u32,
+}
+
+/// Parameters of an indirect draw (`VkDrawIndirectCommand`), such as written by GPU
+/// culling.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct DrawIndirectCommand {
+    pub vertex_count: u32,
+    pub instance_count: u32,
+    pub first_vertex: u32,
+    pub first_instance: u32,
+}
+
+/// Parameters of an indirect indexed draw (`VkDrawIndexedIndirectCommand`), such as
+/// written by GPU culling.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct DrawIndexedIndirectCommand {
+    pub index_count: u32,
+    pub instance_count: u32,
+    pub first_index: u32,
+    pub vertex_offset: i32,
+    pub first_instance: u32,
+}
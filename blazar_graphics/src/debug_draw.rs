@@ -0,0 +1,562 @@
+//! Immediate-mode debug drawing.
+//!
+//! Shapes are added from anywhere during a frame, either through a `DebugDraw` or the
+//! global functions of this module, then flushed by the renderer at the end of the
+//! frame into line lists and text labels. The matching GLSL shaders are
+//! `shaders/debug.vert` and `shaders/debug.frag`.
+
+use crate::{
+    color::{Color, LinearRgba},
+    pipeline::{
+        BlendState, DepthState, GraphicsPipelineDescription, PipelineLayoutDescription,
+        PrimitiveTopology, PushConstantRange, Shader, ShaderStage, VertexFormat, VertexInputRate,
+        VertexLayout,
+    },
+    sprite::SpriteBatch,
+    text::{FontId, TextRenderer},
+};
+use blazar_font::LayoutOptions;
+use std::{
+    ptr,
+    sync::{
+        atomic::{AtomicPtr, Ordering},
+        Mutex,
+    },
+};
+
+/// Number of lines approximating a circle.
+const CIRCLE_SEGMENTS: usize = 32;
+
+/// Coordinate spaces of debug shapes.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum DebugSpace {
+    /// World units, transformed by the view-projection matrix given when flushing.
+    World,
+    /// Pixels from the top-left corner of the viewport.
+    Screen,
+}
+
+/// Appearance and lifetime of debug shapes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DebugStyle {
+    pub color: [f32; 4],
+    pub space: DebugSpace,
+    /// Hides world space shapes behind scene geometry.
+    pub depth_test: bool,
+    /// Time in seconds the shape stays visible, 0 drawing it for a single frame.
+    pub duration: f32,
+}
+
+impl DebugStyle {
+    /// Creates a style for depth-tested world space shapes drawn for a single frame.
+    pub fn new<C: Into<LinearRgba>>(color: C) -> DebugStyle {
+        DebugStyle {
+            color: color.into().to_array(),
+            space: DebugSpace::World,
+            depth_test: true,
+            duration: 0.0,
+        }
+    }
+
+    /// Places shapes in screen space.
+    pub fn screen(mut self) -> DebugStyle {
+        self.space = DebugSpace::Screen;
+        self
+    }
+
+    /// Enables or disables the depth test.
+    pub fn depth_test(mut self, depth_test: bool) -> DebugStyle {
+        self.depth_test = depth_test;
+        self
+    }
+
+    /// Sets the time in seconds shapes stay visible.
+    pub fn duration(mut self, duration: f32) -> DebugStyle {
+        self.duration = duration;
+        self
+    }
+}
+
+impl From<[f32; 4]> for DebugStyle {
+    fn from(color: [f32; 4]) -> DebugStyle {
+        DebugStyle::new(color)
+    }
+}
+
+impl From<Color> for DebugStyle {
+    fn from(color: Color) -> DebugStyle {
+        DebugStyle::new(color)
+    }
+}
+
+/// Vertex of a debug line.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DebugVertex {
+    pub position: [f32; 3],
+    pub color: [f32; 4],
+}
+
+impl DebugVertex {
+    /// Returns the vertex layout.
+    pub fn layout() -> VertexLayout {
+        VertexLayout::new().interleaved(
+            VertexInputRate::Vertex,
+            &[VertexFormat::Float3, VertexFormat::Float4],
+        )
+    }
+}
+
+/// Text label, in screen space.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DebugLabel {
+    /// Position of the top-left corner of the text, in pixels.
+    pub position: [f32; 2],
+    pub text: String,
+    pub color: [f32; 4],
+}
+
+/// Debug shapes to draw in a frame.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DebugFrame {
+    /// Line list of depth-tested world space shapes.
+    pub world: Vec<DebugVertex>,
+    /// Line list of world space shapes drawn on top of the scene.
+    pub overlay: Vec<DebugVertex>,
+    /// Line list of screen space shapes, in pixels.
+    pub screen: Vec<DebugVertex>,
+    pub labels: Vec<DebugLabel>,
+}
+
+impl DebugFrame {
+    /// Size in bytes of the push constants, holding the view-projection matrix.
+    pub const PUSH_CONSTANTS_SIZE: u32 = 64;
+
+    /// Creates an empty frame.
+    pub fn new() -> DebugFrame {
+        DebugFrame::default()
+    }
+
+    /// Removes all shapes, keeping allocated memory.
+    pub fn clear(&mut self) {
+        self.world.clear();
+        self.overlay.clear();
+        self.screen.clear();
+        self.labels.clear();
+    }
+
+    /// Returns `true` if there is nothing to draw.
+    pub fn is_empty(&self) -> bool {
+        self.world.is_empty()
+            && self.overlay.is_empty()
+            && self.screen.is_empty()
+            && self.labels.is_empty()
+    }
+
+    /// Adds the sprites of the labels to a batch.
+    pub fn draw_labels<T: Copy + Ord>(
+        &self,
+        renderer: &mut TextRenderer<T>,
+        batch: &mut SpriteBatch<T>,
+        font: FontId,
+        options: &LayoutOptions,
+    ) {
+        for label in &self.labels {
+            renderer.draw_text(
+                batch,
+                font,
+                &label.text,
+                options,
+                label.position,
+                label.color,
+            );
+        }
+    }
+
+    /// Describes the pipeline of debug lines, alpha-blended and either depth-tested
+    /// without writing depth, or drawn on top.
+    pub fn pipeline_description(
+        vertex_shader: Shader,
+        fragment_shader: Shader,
+        depth_test: bool,
+    ) -> GraphicsPipelineDescription {
+        GraphicsPipelineDescription::new()
+            .shader(vertex_shader)
+            .shader(fragment_shader)
+            .layout(
+                PipelineLayoutDescription::new().push_constants(PushConstantRange {
+                    stages: ShaderStage::Vertex as u32,
+                    offset: 0,
+                    size: Self::PUSH_CONSTANTS_SIZE,
+                }),
+            )
+            .vertex_layout(DebugVertex::layout())
+            .topology(PrimitiveTopology::LineList)
+            .depth(if depth_test {
+                DepthState::READ_ONLY
+            } else {
+                DepthState::DISABLED
+            })
+            .blend(&[BlendState::ALPHA])
+    }
+}
+
+/// Returns the column-major matrix transforming pixels from the top-left corner of the
+/// viewport to Vulkan clip space, for screen space lines.
+pub fn screen_projection(width: f32, height: f32) -> [[f32; 4]; 4] {
+    [
+        [2.0 / width.max(1.0), 0.0, 0.0, 0.0],
+        [0.0, 2.0 / height.max(1.0), 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [-1.0, -1.0, 0.0, 1.0],
+    ]
+}
+
+/// Line waiting to be drawn.
+#[derive(Clone, Copy, Debug)]
+struct Line {
+    from: [f32; 3],
+    to: [f32; 3],
+    style: DebugStyle,
+}
+
+/// Label waiting to be drawn.
+#[derive(Clone, Debug)]
+struct Label {
+    position: [f32; 3],
+    text: String,
+    style: DebugStyle,
+}
+
+/// Collects debug shapes until they are flushed.
+#[derive(Debug, Default)]
+pub struct DebugDraw {
+    lines: Vec<Line>,
+    labels: Vec<Label>,
+}
+
+impl DebugDraw {
+    /// Creates an empty collection.
+    pub fn new() -> DebugDraw {
+        DebugDraw::default()
+    }
+
+    /// Removes every shape, whatever its duration.
+    pub fn clear(&mut self) {
+        self.lines.clear();
+        self.labels.clear();
+    }
+
+    /// Adds a line.
+    pub fn line<S: Into<DebugStyle>>(&mut self, from: [f32; 3], to: [f32; 3], style: S) {
+        self.lines.push(Line {
+            from,
+            to,
+            style: style.into(),
+        });
+    }
+
+    /// Adds the outline of a rectangle of the z = 0 plane.
+    pub fn rect<S: Into<DebugStyle>>(&mut self, min: [f32; 2], max: [f32; 2], style: S) {
+        let style = style.into();
+        let corners = [
+            [min[0], min[1], 0.0],
+            [max[0], min[1], 0.0],
+            [max[0], max[1], 0.0],
+            [min[0], max[1], 0.0],
+        ];
+        self.polygon(&corners, style);
+    }
+
+    /// Adds a circle lying in the plane perpendicular to `normal`.
+    pub fn circle<S: Into<DebugStyle>>(
+        &mut self,
+        center: [f32; 3],
+        radius: f32,
+        normal: [f32; 3],
+        style: S,
+    ) {
+        let (u, v) = orthonormal_basis(normal);
+        let points: Vec<[f32; 3]> = (0..CIRCLE_SEGMENTS)
+            .map(|index| {
+                let angle = index as f32 / CIRCLE_SEGMENTS as f32 * 2.0 * std::f32::consts::PI;
+                let (sin, cos) = angle.sin_cos();
+                let mut point = center;
+                for (axis, value) in point.iter_mut().enumerate() {
+                    *value += radius * (cos * u[axis] + sin * v[axis]);
+                }
+                point
+            })
+            .collect();
+        self.polygon(&points, style.into());
+    }
+
+    /// Adds the edges of an axis-aligned bounding box.
+    pub fn aabb<S: Into<DebugStyle>>(&mut self, min: [f32; 3], max: [f32; 3], style: S) {
+        let corner = |index: usize| {
+            [
+                if index & 1 == 0 { min[0] } else { max[0] },
+                if index & 2 == 0 { min[1] } else { max[1] },
+                if index & 4 == 0 { min[2] } else { max[2] },
+            ]
+        };
+        self.box_edges(corner, style.into());
+    }
+
+    /// Adds a sphere, drawn as three great circles.
+    pub fn sphere<S: Into<DebugStyle>>(&mut self, center: [f32; 3], radius: f32, style: S) {
+        let style = style.into();
+        self.circle(center, radius, [1.0, 0.0, 0.0], style);
+        self.circle(center, radius, [0.0, 1.0, 0.0], style);
+        self.circle(center, radius, [0.0, 0.0, 1.0], style);
+    }
+
+    /// Adds the edges of the frustum of a camera, given the inverse of its column-major
+    /// view-projection matrix.
+    pub fn frustum<S: Into<DebugStyle>>(
+        &mut self,
+        inverse_view_projection: &[[f32; 4]; 4],
+        style: S,
+    ) {
+        let corner = |index: usize| {
+            // Vulkan clip space, depth ranging from 0 to 1.
+            let ndc = [
+                if index & 1 == 0 { -1.0 } else { 1.0 },
+                if index & 2 == 0 { -1.0 } else { 1.0 },
+                if index & 4 == 0 { 0.0 } else { 1.0 },
+                1.0,
+            ];
+            let clip = transform(inverse_view_projection, ndc);
+            [clip[0] / clip[3], clip[1] / clip[3], clip[2] / clip[3]]
+        };
+        self.box_edges(corner, style.into());
+    }
+
+    /// Adds the axes of a column-major transformation matrix, in red, green and blue,
+    /// keeping the alpha of the style color.
+    pub fn axes<S: Into<DebugStyle>>(&mut self, transform: &[[f32; 4]; 4], length: f32, style: S) {
+        let style = style.into();
+        let origin = [transform[3][0], transform[3][1], transform[3][2]];
+        for axis in 0..3 {
+            let mut end = origin;
+            for (component, value) in end.iter_mut().enumerate() {
+                *value += transform[axis][component] * length;
+            }
+            let mut color = [0.0, 0.0, 0.0, style.color[3]];
+            color[axis] = 1.0;
+            self.line(origin, end, DebugStyle { color, ..style });
+        }
+    }
+
+    /// Adds a text label, its top-left corner at `position`, drawn on top of the scene.
+    pub fn text<S: Into<DebugStyle>>(&mut self, position: [f32; 3], text: &str, style: S) {
+        self.labels.push(Label {
+            position,
+            text: String::from(text),
+            style: style.into(),
+        });
+    }
+
+    /// Outputs the shapes to draw in `frame`, then removes those whose duration ended
+    /// after `elapsed` seconds.
+    ///
+    /// World space labels are projected to the viewport with `view_projection`, and
+    /// skipped when behind the camera.
+    pub fn flush(
+        &mut self,
+        elapsed: f32,
+        view_projection: &[[f32; 4]; 4],
+        viewport: [f32; 2],
+        frame: &mut DebugFrame,
+    ) {
+        frame.clear();
+        for line in &self.lines {
+            let vertices = match (line.style.space, line.style.depth_test) {
+                (DebugSpace::World, true) => &mut frame.world,
+                (DebugSpace::World, false) => &mut frame.overlay,
+                (DebugSpace::Screen, _) => &mut frame.screen,
+            };
+            let color = line.style.color;
+            vertices.push(DebugVertex {
+                position: line.from,
+                color,
+            });
+            vertices.push(DebugVertex {
+                position: line.to,
+                color,
+            });
+        }
+        for label in &self.labels {
+            let position = match label.style.space {
+                DebugSpace::World => {
+                    let [x, y, z] = label.position;
+                    let clip = transform(view_projection, [x, y, z, 1.0]);
+                    if clip[3] <= 0.0 {
+                        continue;
+                    }
+                    [
+                        (clip[0] / clip[3] + 1.0) * 0.5 * viewport[0],
+                        (clip[1] / clip[3] + 1.0) * 0.5 * viewport[1],
+                    ]
+                }
+                DebugSpace::Screen => [label.position[0], label.position[1]],
+            };
+            frame.labels.push(DebugLabel {
+                position,
+                text: label.text.clone(),
+                color: label.style.color,
+            });
+        }
+
+        for line in &mut self.lines {
+            line.style.duration -= elapsed;
+        }
+        self.lines.retain(|line| line.style.duration > 0.0);
+        for label in &mut self.labels {
+            label.style.duration -= elapsed;
+        }
+        self.labels.retain(|label| label.style.duration > 0.0);
+    }
+
+    /// Adds a closed polygon.
+    fn polygon(&mut self, points: &[[f32; 3]], style: DebugStyle) {
+        for (index, point) in points.iter().enumerate() {
+            self.line(*point, points[(index + 1) % points.len()], style);
+        }
+    }
+
+    /// Adds the twelve edges of a box, corners being indexed by their x, y and z bits.
+    fn box_edges<F: Fn(usize) -> [f32; 3]>(&mut self, corner: F, style: DebugStyle) {
+        for from in 0..8 {
+            for bit in &[1, 2, 4] {
+                if from & bit == 0 {
+                    self.line(corner(from), corner(from | bit), style);
+                }
+            }
+        }
+    }
+}
+
+/// Multiplies a column-major matrix and a vector.
+fn transform(matrix: &[[f32; 4]; 4], vector: [f32; 4]) -> [f32; 4] {
+    let mut result = [0.0; 4];
+    for (column, value) in matrix.iter().zip(&vector) {
+        for (row, result) in result.iter_mut().enumerate() {
+            *result += column[row] * value;
+        }
+    }
+    result
+}
+
+/// Returns two unit vectors perpendicular to `normal` and to each other.
+fn orthonormal_basis(normal: [f32; 3]) -> ([f32; 3], [f32; 3]) {
+    let normalize = |vector: [f32; 3]| {
+        let length = (vector[0] * vector[0] + vector[1] * vector[1] + vector[2] * vector[2])
+            .sqrt()
+            .max(f32::MIN_POSITIVE);
+        [vector[0] / length, vector[1] / length, vector[2] / length]
+    };
+    let cross = |a: [f32; 3], b: [f32; 3]| {
+        [
+            a[1] * b[2] - a[2] * b[1],
+            a[2] * b[0] - a[0] * b[2],
+            a[0] * b[1] - a[1] * b[0],
+        ]
+    };
+    let normal = normalize(normal);
+    let reference = if normal[0].abs() < 0.9 {
+        [1.0, 0.0, 0.0]
+    } else {
+        [0.0, 1.0, 0.0]
+    };
+    let u = normalize(cross(normal, reference));
+    (u, cross(normal, u))
+}
+
+/// Shared collection of the global functions, null until first used.
+///
+/// `Mutex::new` is not `const` before Rust 1.63, so the collection is created lazily and
+/// published through a pointer; it is never freed.
+static GLOBAL: AtomicPtr<Mutex<DebugDraw>> = AtomicPtr::new(ptr::null_mut());
+
+/// Returns the shared collection, creating it on first use.
+fn global() -> &'static Mutex<DebugDraw> {
+    let mut pointer = GLOBAL.load(Ordering::Acquire);
+    if pointer.is_null() {
+        let created = Box::into_raw(Box::new(Mutex::new(DebugDraw::new())));
+        pointer = match GLOBAL.compare_exchange(
+            ptr::null_mut(),
+            created,
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        ) {
+            Ok(_) => created,
+            Err(existing) => {
+                // Another thread published its collection first.
+                drop(unsafe { Box::from_raw(created) });
+                existing
+            }
+        };
+    }
+    // Only ever set once, to a leaked box.
+    unsafe { &*pointer }
+}
+
+/// Calls `f` with the shared collection.
+///
+/// The collection stays locked while `f` runs: calling `with` or any other global
+/// function of this module from `f` deadlocks.
+pub fn with<R, F: FnOnce(&mut DebugDraw) -> R>(f: F) -> R {
+    let mut draw = global().lock().unwrap_or_else(|error| error.into_inner());
+    f(&mut draw)
+}
+
+/// Adds a line to the shared collection.
+pub fn line<S: Into<DebugStyle>>(from: [f32; 3], to: [f32; 3], style: S) {
+    with(|draw| draw.line(from, to, style));
+}
+
+/// Adds the outline of a rectangle of the z = 0 plane to the shared collection.
+pub fn rect<S: Into<DebugStyle>>(min: [f32; 2], max: [f32; 2], style: S) {
+    with(|draw| draw.rect(min, max, style));
+}
+
+/// Adds a circle to the shared collection.
+pub fn circle<S: Into<DebugStyle>>(center: [f32; 3], radius: f32, normal: [f32; 3], style: S) {
+    with(|draw| draw.circle(center, radius, normal, style));
+}
+
+/// Adds an axis-aligned bounding box to the shared collection.
+pub fn aabb<S: Into<DebugStyle>>(min: [f32; 3], max: [f32; 3], style: S) {
+    with(|draw| draw.aabb(min, max, style));
+}
+
+/// Adds a sphere to the shared collection.
+pub fn sphere<S: Into<DebugStyle>>(center: [f32; 3], radius: f32, style: S) {
+    with(|draw| draw.sphere(center, radius, style));
+}
+
+/// Adds the frustum of a camera to the shared collection.
+pub fn frustum<S: Into<DebugStyle>>(inverse_view_projection: &[[f32; 4]; 4], style: S) {
+    with(|draw| draw.frustum(inverse_view_projection, style));
+}
+
+/// Adds the axes of a transformation to the shared collection.
+pub fn axes<S: Into<DebugStyle>>(transform: &[[f32; 4]; 4], length: f32, style: S) {
+    with(|draw| draw.axes(transform, length, style));
+}
+
+/// Adds a text label to the shared collection.
+pub fn text<S: Into<DebugStyle>>(position: [f32; 3], text: &str, style: S) {
+    with(|draw| draw.text(position, text, style));
+}
+
+/// Flushes the shared collection, see `DebugDraw::flush`.
+pub fn flush(
+    elapsed: f32,
+    view_projection: &[[f32; 4]; 4],
+    viewport: [f32; 2],
+    frame: &mut DebugFrame,
+) {
+    with(|draw| draw.flush(elapsed, view_projection, viewport, frame));
+}
@@ -0,0 +1,201 @@
+//! Bindless descriptor tables relying on descriptor indexing.
+
+use super::{DescriptorBinding, DescriptorSetLayoutDescription, DescriptorType};
+use crate::pipeline::ShaderStageFlags;
+use blazar_vk_sys as vk_sys;
+
+/// Descriptor indexing support of a device.
+///
+/// Fields come from `VkPhysicalDeviceDescriptorIndexingFeatures` and
+/// `VkPhysicalDeviceDescriptorIndexingProperties`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct DescriptorIndexingSupport {
+    pub shader_sampled_image_array_non_uniform_indexing: bool,
+    pub shader_storage_buffer_array_non_uniform_indexing: bool,
+    pub descriptor_binding_sampled_image_update_after_bind: bool,
+    pub descriptor_binding_storage_buffer_update_after_bind: bool,
+    pub descriptor_binding_partially_bound: bool,
+    pub descriptor_binding_update_unused_while_pending: bool,
+    pub runtime_descriptor_array: bool,
+    pub max_update_after_bind_sampled_images: u32,
+    pub max_update_after_bind_storage_buffers: u32,
+}
+
+impl DescriptorIndexingSupport {
+    /// Returns `true` if the features needed by bindless tables are supported.
+    pub fn supports_bindless(&self) -> bool {
+        self.shader_sampled_image_array_non_uniform_indexing
+            && self.shader_storage_buffer_array_non_uniform_indexing
+            && self.descriptor_binding_sampled_image_update_after_bind
+            && self.descriptor_binding_storage_buffer_update_after_bind
+            && self.descriptor_binding_partially_bound
+            && self.descriptor_binding_update_unused_while_pending
+            && self.runtime_descriptor_array
+    }
+}
+
+/// Ways of binding resources to shaders.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DescriptorMode {
+    /// Resources are bound once into global tables and accessed by index.
+    Bindless { textures: u32, buffers: u32 },
+    /// Resources are bound per draw through sets allocated from `FrameDescriptors`.
+    Bindful,
+}
+
+impl DescriptorMode {
+    /// Selects bindless tables of the requested capacities if the device supports them,
+    /// clamping capacities to device limits, and falls back to bindful mode otherwise.
+    pub fn select(
+        support: Option<&DescriptorIndexingSupport>,
+        textures: u32,
+        buffers: u32,
+    ) -> DescriptorMode {
+        match support {
+            Some(support) if support.supports_bindless() => DescriptorMode::Bindless {
+                textures: textures.min(support.max_update_after_bind_sampled_images),
+                buffers: buffers.min(support.max_update_after_bind_storage_buffers),
+            },
+            _ => DescriptorMode::Bindful,
+        }
+    }
+}
+
+/// Index of a texture in a bindless table.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct TextureIndex(pub u32);
+
+/// Index of a buffer in a bindless table.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct BufferIndex(pub u32);
+
+/// Hands out slots of a fixed-size array, delaying reuse until frames in flight are done.
+struct SlotAllocator {
+    capacity: u32,
+    next: u32,
+    free: Vec<u32>,
+    pending: Vec<Vec<u32>>,
+}
+
+impl SlotAllocator {
+    fn new(capacity: u32, frames_in_flight: usize) -> SlotAllocator {
+        SlotAllocator {
+            capacity,
+            next: 0,
+            free: Vec::new(),
+            pending: vec![Vec::new(); frames_in_flight.max(1)],
+        }
+    }
+
+    fn allocate(&mut self) -> Option<u32> {
+        if let Some(slot) = self.free.pop() {
+            Some(slot)
+        } else if self.next < self.capacity {
+            self.next += 1;
+            Some(self.next - 1)
+        } else {
+            None
+        }
+    }
+
+    fn release(&mut self, frame: usize, slot: u32) {
+        self.pending[frame].push(slot);
+    }
+
+    fn recycle(&mut self, frame: usize) {
+        self.free.append(&mut self.pending[frame]);
+    }
+}
+
+/// Global table of textures and storage buffers indexed from shaders.
+///
+/// Binding 0 is an array of combined image samplers and binding 1 an array of storage
+/// buffers. Released indices are only reused once the frames that may still reference
+/// them are done.
+pub struct BindlessTable {
+    textures: SlotAllocator,
+    buffers: SlotAllocator,
+    frame: usize,
+    frames_in_flight: usize,
+}
+
+impl BindlessTable {
+    /// Binding of the texture array.
+    pub const TEXTURE_BINDING: u32 = 0;
+
+    /// Binding of the storage buffer array.
+    pub const BUFFER_BINDING: u32 = 1;
+
+    /// Creates a table of the given capacities.
+    pub fn new(textures: u32, buffers: u32, frames_in_flight: usize) -> BindlessTable {
+        BindlessTable {
+            textures: SlotAllocator::new(textures, frames_in_flight),
+            buffers: SlotAllocator::new(buffers, frames_in_flight),
+            frame: 0,
+            frames_in_flight: frames_in_flight.max(1),
+        }
+    }
+
+    /// Creates a table from the selected mode, if bindless.
+    pub fn from_mode(mode: DescriptorMode, frames_in_flight: usize) -> Option<BindlessTable> {
+        match mode {
+            DescriptorMode::Bindless { textures, buffers } => {
+                Some(BindlessTable::new(textures, buffers, frames_in_flight))
+            }
+            DescriptorMode::Bindful => None,
+        }
+    }
+
+    /// Returns the layout of the table's descriptor set.
+    ///
+    /// Its pool must be created with `VK_DESCRIPTOR_POOL_CREATE_UPDATE_AFTER_BIND_BIT`.
+    pub fn layout_description(&self, stages: ShaderStageFlags) -> DescriptorSetLayoutDescription {
+        let flags = vk_sys::VK_DESCRIPTOR_BINDING_PARTIALLY_BOUND_BIT
+            | vk_sys::VK_DESCRIPTOR_BINDING_UPDATE_AFTER_BIND_BIT
+            | vk_sys::VK_DESCRIPTOR_BINDING_UPDATE_UNUSED_WHILE_PENDING_BIT;
+        DescriptorSetLayoutDescription::new()
+            .binding(DescriptorBinding {
+                binding: BindlessTable::TEXTURE_BINDING,
+                descriptor_type: DescriptorType::CombinedImageSampler,
+                count: self.textures.capacity,
+                stages,
+                flags,
+            })
+            .binding(DescriptorBinding {
+                binding: BindlessTable::BUFFER_BINDING,
+                descriptor_type: DescriptorType::StorageBuffer,
+                count: self.buffers.capacity,
+                stages,
+                flags,
+            })
+    }
+
+    /// Reserves a texture index, or returns `None` if the table is full.
+    pub fn allocate_texture(&mut self) -> Option<TextureIndex> {
+        self.textures.allocate().map(TextureIndex)
+    }
+
+    /// Reserves a buffer index, or returns `None` if the table is full.
+    pub fn allocate_buffer(&mut self) -> Option<BufferIndex> {
+        self.buffers.allocate().map(BufferIndex)
+    }
+
+    /// Releases a texture index.
+    pub fn release_texture(&mut self, index: TextureIndex) {
+        self.textures.release(self.frame, index.0);
+    }
+
+    /// Releases a buffer index.
+    pub fn release_buffer(&mut self, index: BufferIndex) {
+        self.buffers.release(self.frame, index.0);
+    }
+
+    /// Moves to the next frame, making indices released during its previous use available.
+    ///
+    /// The device must be done with that frame, i.e. its fence must be signaled.
+    pub fn begin_frame(&mut self) {
+        self.frame = (self.frame + 1) % self.frames_in_flight;
+        self.textures.recycle(self.frame);
+        self.buffers.recycle(self.frame);
+    }
+}
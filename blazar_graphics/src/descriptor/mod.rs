@@ -0,0 +1,274 @@
+//! Descriptor set layouts and pooled descriptor set allocation.
+
+mod bindless;
+
+pub use bindless::*;
+
+use crate::pipeline::ShaderStageFlags;
+use blazar_vk_sys as vk_sys;
+
+/// Types of descriptors.
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum DescriptorType {
+    Sampler = vk_sys::VK_DESCRIPTOR_TYPE_SAMPLER,
+    CombinedImageSampler = vk_sys::VK_DESCRIPTOR_TYPE_COMBINED_IMAGE_SAMPLER,
+    SampledImage = vk_sys::VK_DESCRIPTOR_TYPE_SAMPLED_IMAGE,
+    StorageImage = vk_sys::VK_DESCRIPTOR_TYPE_STORAGE_IMAGE,
+    UniformTexelBuffer = vk_sys::VK_DESCRIPTOR_TYPE_UNIFORM_TEXEL_BUFFER,
+    StorageTexelBuffer = vk_sys::VK_DESCRIPTOR_TYPE_STORAGE_TEXEL_BUFFER,
+    UniformBuffer = vk_sys::VK_DESCRIPTOR_TYPE_UNIFORM_BUFFER,
+    StorageBuffer = vk_sys::VK_DESCRIPTOR_TYPE_STORAGE_BUFFER,
+    UniformBufferDynamic = vk_sys::VK_DESCRIPTOR_TYPE_UNIFORM_BUFFER_DYNAMIC,
+    StorageBufferDynamic = vk_sys::VK_DESCRIPTOR_TYPE_STORAGE_BUFFER_DYNAMIC,
+    InputAttachment = vk_sys::VK_DESCRIPTOR_TYPE_INPUT_ATTACHMENT,
+}
+
+/// Describes a binding of a descriptor set layout.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct DescriptorBinding {
+    pub binding: u32,
+    pub descriptor_type: DescriptorType,
+    pub count: u32,
+    pub stages: ShaderStageFlags,
+    pub flags: vk_sys::VkDescriptorBindingFlags,
+}
+
+impl DescriptorBinding {
+    /// Creates a binding holding a single descriptor.
+    pub fn new(
+        binding: u32,
+        descriptor_type: DescriptorType,
+        stages: ShaderStageFlags,
+    ) -> DescriptorBinding {
+        DescriptorBinding {
+            binding,
+            descriptor_type,
+            count: 1,
+            stages,
+            flags: 0,
+        }
+    }
+}
+
+/// Describes a descriptor set layout.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct DescriptorSetLayoutDescription {
+    pub bindings: Vec<DescriptorBinding>,
+}
+
+impl DescriptorSetLayoutDescription {
+    /// Creates an empty description.
+    pub fn new() -> DescriptorSetLayoutDescription {
+        DescriptorSetLayoutDescription::default()
+    }
+
+    /// Adds a binding.
+    pub fn binding(mut self, binding: DescriptorBinding) -> DescriptorSetLayoutDescription {
+        self.bindings.push(binding);
+        self
+    }
+
+    /// Returns the number of descriptors of each type needed by one set.
+    pub fn pool_sizes(&self) -> Vec<DescriptorPoolSize> {
+        let mut sizes: Vec<DescriptorPoolSize> = Vec::new();
+        for binding in &self.bindings {
+            match sizes
+                .iter_mut()
+                .find(|size| size.descriptor_type == binding.descriptor_type)
+            {
+                Some(size) => size.count += binding.count,
+                None => sizes.push(DescriptorPoolSize {
+                    descriptor_type: binding.descriptor_type,
+                    count: binding.count,
+                }),
+            }
+        }
+        sizes
+    }
+}
+
+/// Number of descriptors of a given type.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct DescriptorPoolSize {
+    pub descriptor_type: DescriptorType,
+    pub count: u32,
+}
+
+/// Device operations needed to manage descriptor pools.
+///
+/// Errors are the `VkResult` codes returned by the underlying Vulkan calls.
+pub trait DescriptorBackend {
+    type Pool;
+    type Layout;
+    type Set;
+
+    /// Creates a pool (`vkCreateDescriptorPool`).
+    fn create_pool(
+        &mut self,
+        max_sets: u32,
+        sizes: &[DescriptorPoolSize],
+        flags: vk_sys::VkDescriptorPoolCreateFlags,
+    ) -> Result<Self::Pool, vk_sys::VkResult>;
+
+    /// Allocates a set from a pool (`vkAllocateDescriptorSets`).
+    fn allocate_set(
+        &mut self,
+        pool: &Self::Pool,
+        layout: &Self::Layout,
+    ) -> Result<Self::Set, vk_sys::VkResult>;
+
+    /// Returns all sets of a pool to it (`vkResetDescriptorPool`).
+    fn reset_pool(&mut self, pool: &Self::Pool);
+
+    /// Destroys a pool (`vkDestroyDescriptorPool`).
+    fn destroy_pool(&mut self, pool: Self::Pool);
+}
+
+/// Maximum number of sets of a single pool.
+const MAX_SETS_PER_POOL: u32 = 4096;
+
+/// Allocates descriptor sets from pools created on demand.
+///
+/// When a pool is exhausted, a new one twice as large is created. Sets are never freed
+/// individually: `reset` returns all of them at once.
+pub struct DescriptorAllocator<B: DescriptorBackend> {
+    sizes_per_set: Vec<DescriptorPoolSize>,
+    sets_per_pool: u32,
+    flags: vk_sys::VkDescriptorPoolCreateFlags,
+    current: Option<B::Pool>,
+    used: Vec<B::Pool>,
+    free: Vec<B::Pool>,
+}
+
+impl<B: DescriptorBackend> DescriptorAllocator<B> {
+    /// Creates an allocator whose pools hold `sizes_per_set` descriptors for each set.
+    pub fn new(sizes_per_set: &[DescriptorPoolSize], sets_per_pool: u32) -> DescriptorAllocator<B> {
+        DescriptorAllocator {
+            sizes_per_set: sizes_per_set.to_vec(),
+            sets_per_pool: sets_per_pool.max(1).min(MAX_SETS_PER_POOL),
+            flags: 0,
+            current: None,
+            used: Vec::new(),
+            free: Vec::new(),
+        }
+    }
+
+    /// Sets the flags pools are created with.
+    pub fn with_flags(
+        mut self,
+        flags: vk_sys::VkDescriptorPoolCreateFlags,
+    ) -> DescriptorAllocator<B> {
+        self.flags = flags;
+        self
+    }
+
+    /// Returns the number of pools created so far.
+    pub fn pool_count(&self) -> usize {
+        self.current.iter().count() + self.used.len() + self.free.len()
+    }
+
+    /// Allocates a set, growing the pools if needed.
+    pub fn allocate(
+        &mut self,
+        backend: &mut B,
+        layout: &B::Layout,
+    ) -> Result<B::Set, vk_sys::VkResult> {
+        if self.current.is_none() {
+            self.current = Some(self.next_pool(backend)?);
+        }
+        match backend.allocate_set(self.current.as_ref().unwrap(), layout) {
+            Err(vk_sys::VK_ERROR_OUT_OF_POOL_MEMORY) | Err(vk_sys::VK_ERROR_FRAGMENTED_POOL) => {
+                // Retires the exhausted pool and tries once more with a fresh one.
+                let pool = self.next_pool(backend)?;
+                self.used.extend(self.current.replace(pool));
+                backend.allocate_set(self.current.as_ref().unwrap(), layout)
+            }
+            result => result,
+        }
+    }
+
+    /// Returns every set allocated so far to the pools.
+    ///
+    /// The sets must no longer be in use by the device.
+    pub fn reset(&mut self, backend: &mut B) {
+        for pool in self.current.take().into_iter().chain(self.used.drain(..)) {
+            backend.reset_pool(&pool);
+            self.free.push(pool);
+        }
+    }
+
+    /// Destroys all pools.
+    pub fn destroy(&mut self, backend: &mut B) {
+        let pools = self.current.take().into_iter();
+        for pool in pools.chain(self.used.drain(..)).chain(self.free.drain(..)) {
+            backend.destroy_pool(pool);
+        }
+    }
+
+    /// Returns a reset pool if any, or creates a larger one.
+    fn next_pool(&mut self, backend: &mut B) -> Result<B::Pool, vk_sys::VkResult> {
+        if let Some(pool) = self.free.pop() {
+            return Ok(pool);
+        }
+        let sizes: Vec<_> = self
+            .sizes_per_set
+            .iter()
+            .map(|size| DescriptorPoolSize {
+                descriptor_type: size.descriptor_type,
+                count: size.count.saturating_mul(self.sets_per_pool),
+            })
+            .collect();
+        let pool = backend.create_pool(self.sets_per_pool, &sizes, self.flags)?;
+        self.sets_per_pool = (self.sets_per_pool * 2).min(MAX_SETS_PER_POOL);
+        Ok(pool)
+    }
+}
+
+/// Allocates descriptor sets that live for a single frame.
+///
+/// Each frame in flight owns an allocator, reset when the frame is recorded again.
+pub struct FrameDescriptors<B: DescriptorBackend> {
+    frames: Vec<DescriptorAllocator<B>>,
+    current: usize,
+}
+
+impl<B: DescriptorBackend> FrameDescriptors<B> {
+    /// Creates per-frame allocators.
+    pub fn new(
+        frames_in_flight: usize,
+        sizes_per_set: &[DescriptorPoolSize],
+        sets_per_pool: u32,
+    ) -> FrameDescriptors<B> {
+        FrameDescriptors {
+            frames: (0..frames_in_flight.max(1))
+                .map(|_| DescriptorAllocator::new(sizes_per_set, sets_per_pool))
+                .collect(),
+            current: 0,
+        }
+    }
+
+    /// Moves to the next frame, recycling the sets it used last time.
+    ///
+    /// The device must be done with that frame, i.e. its fence must be signaled.
+    pub fn begin_frame(&mut self, backend: &mut B) {
+        self.current = (self.current + 1) % self.frames.len();
+        self.frames[self.current].reset(backend);
+    }
+
+    /// Allocates a set for the current frame.
+    pub fn allocate(
+        &mut self,
+        backend: &mut B,
+        layout: &B::Layout,
+    ) -> Result<B::Set, vk_sys::VkResult> {
+        self.frames[self.current].allocate(backend, layout)
+    }
+
+    /// Destroys all pools.
+    pub fn destroy(&mut self, backend: &mut B) {
+        for frame in &mut self.frames {
+            frame.destroy(backend);
+        }
+    }
+}
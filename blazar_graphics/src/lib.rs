@@ -0,0 +1,20 @@
+//! Vulkan-based graphics API.
+
+mod hash;
+mod order;
+
+pub mod capture;
+pub mod color;
+pub mod compute;
+pub mod debug_draw;
+pub mod descriptor;
+pub mod pbr;
+pub mod pipeline;
+pub mod postprocess;
+pub mod present;
+pub mod profiler;
+pub mod recovery;
+pub mod sprite;
+pub mod target;
+pub mod text;
+pub mod texture;
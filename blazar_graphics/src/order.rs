@@ -0,0 +1,15 @@
+//! Total ordering of floats.
+
+/// Returns a key ordering floats by value.
+///
+/// Unlike `partial_cmp`, keys are totally ordered: zeros of both signs compare equal and
+/// NaNs, whatever their sign and payload, come after every other value.
+pub(crate) fn float_key(value: f32) -> i32 {
+    if value.is_nan() {
+        return i32::MAX;
+    }
+    let value = if value == 0.0 { 0.0 } else { value };
+    let bits = value.to_bits() as i32;
+    // Negative floats order backwards as integers: flips all but their sign bit.
+    bits ^ (((bits >> 31) as u32) >> 1) as i32
+}
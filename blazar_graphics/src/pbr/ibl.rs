@@ -0,0 +1,216 @@
+//! Image-based lighting from cube maps.
+//!
+//! An environment cube map is baked into an irradiance cube map, lighting diffuse
+//! surfaces, and a prefiltered cube map whose mip levels hold the reflections of
+//! increasingly rough surfaces. Together with a lookup table of the scale and bias
+//! applied to the Fresnel reflectance, they approximate the lighting of a material by
+//! its surroundings. Cube maps are baked on the device by `shaders/ibl_irradiance.comp`
+//! and `shaders/ibl_prefilter.comp`; the lookup table is computed once on the CPU.
+
+use crate::{
+    compute::group_count,
+    descriptor::{DescriptorBinding, DescriptorSetLayoutDescription, DescriptorType},
+    pipeline::{
+        ComputePipelineDescription, PipelineLayoutDescription, PushConstantRange, Shader,
+        ShaderStage,
+    },
+};
+use std::f32::consts::PI;
+
+/// Textures lighting a scene from its surroundings.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Environment<T> {
+    /// Irradiance cube map.
+    pub irradiance: T,
+    /// Prefiltered cube map, its mip levels going from smooth to rough reflections.
+    pub prefiltered: T,
+    /// Lookup table returned by `brdf_lut`.
+    pub brdf_lut: T,
+    pub prefiltered_levels: u32,
+    /// Factor applied to the lighting of the environment.
+    pub intensity: f32,
+}
+
+impl<T> Environment<T> {
+    /// Creates an environment from baked textures, at full intensity.
+    pub fn new(
+        irradiance: T,
+        prefiltered: T,
+        brdf_lut: T,
+        prefiltered_levels: u32,
+    ) -> Environment<T> {
+        Environment {
+            irradiance,
+            prefiltered,
+            brdf_lut,
+            prefiltered_levels,
+            intensity: 1.0,
+        }
+    }
+}
+
+/// Compute shaders baking environment cube maps.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum BakeShader {
+    Irradiance,
+    Prefilter,
+}
+
+impl BakeShader {
+    /// Returns the name of the shader, its source being `shaders/<name>.comp`.
+    pub fn name(self) -> &'static str {
+        match self {
+            BakeShader::Irradiance => "ibl_irradiance",
+            BakeShader::Prefilter => "ibl_prefilter",
+        }
+    }
+}
+
+/// Dispatch writing a mip level of a baked cube map.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BakePass {
+    pub shader: BakeShader,
+    pub mip_level: u32,
+    /// Size of the faces of the mip level.
+    pub size: u32,
+    /// Roughness the reflections are prefiltered for.
+    pub roughness: f32,
+    /// Workgroups to dispatch, the z dimension covering the six faces.
+    pub group_counts: [u32; 3],
+}
+
+/// Sizes of the cube maps baked from an environment cube map.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct EnvironmentBake {
+    pub irradiance_size: u32,
+    pub prefiltered_size: u32,
+    pub prefiltered_levels: u32,
+}
+
+impl EnvironmentBake {
+    /// Workgroup size of the bake shaders in x and y.
+    pub const LOCAL_SIZE: u32 = 8;
+
+    /// Size in bytes of the push constants of the bake shaders: roughness and face size.
+    pub const PUSH_CONSTANTS_SIZE: u32 = 8;
+
+    /// Describes a bake into a prefiltered cube map of `prefiltered_size` texels wide
+    /// faces, its smallest mip level being 8 texels wide.
+    pub fn new(prefiltered_size: u32) -> EnvironmentBake {
+        let prefiltered_size = prefiltered_size.max(1);
+        let levels = 32 - prefiltered_size.leading_zeros();
+        EnvironmentBake {
+            irradiance_size: 32,
+            prefiltered_size,
+            prefiltered_levels: levels.saturating_sub(3).max(1),
+        }
+    }
+
+    /// Returns the dispatches writing the irradiance cube map, then each mip level of the
+    /// prefiltered cube map.
+    ///
+    /// Each pass samples the environment cube map and writes a mip level, viewed as a
+    /// 2D array of six layers in the general layout.
+    pub fn passes(&self) -> Vec<BakePass> {
+        let pass = |shader, mip_level, size: u32, roughness| BakePass {
+            shader,
+            mip_level,
+            size,
+            roughness,
+            group_counts: [
+                group_count(size, Self::LOCAL_SIZE),
+                group_count(size, Self::LOCAL_SIZE),
+                6,
+            ],
+        };
+        let mut passes = vec![pass(BakeShader::Irradiance, 0, self.irradiance_size, 1.0)];
+        for level in 0..self.prefiltered_levels {
+            let roughness = if self.prefiltered_levels > 1 {
+                level as f32 / (self.prefiltered_levels - 1) as f32
+            } else {
+                0.0
+            };
+            let size = (self.prefiltered_size >> level).max(1);
+            passes.push(pass(BakeShader::Prefilter, level, size, roughness));
+        }
+        passes
+    }
+
+    /// Returns the layout of the descriptor set binding the environment cube map and the
+    /// storage image written by a pass, typically `Rgba16Float`.
+    pub fn set_layout() -> DescriptorSetLayoutDescription {
+        let compute = ShaderStage::Compute as u32;
+        DescriptorSetLayoutDescription::new()
+            .binding(DescriptorBinding::new(
+                0,
+                DescriptorType::CombinedImageSampler,
+                compute,
+            ))
+            .binding(DescriptorBinding::new(
+                1,
+                DescriptorType::StorageImage,
+                compute,
+            ))
+    }
+
+    /// Describes the pipeline of a bake shader.
+    pub fn pipeline_description(shader: Shader) -> ComputePipelineDescription {
+        ComputePipelineDescription::new(shader).layout(
+            PipelineLayoutDescription::new()
+                .set_layout(Self::set_layout())
+                .push_constants(PushConstantRange {
+                    stages: ShaderStage::Compute as u32,
+                    offset: 0,
+                    size: Self::PUSH_CONSTANTS_SIZE,
+                }),
+        )
+    }
+}
+
+/// Computes the lookup table of the scale and bias applied to the Fresnel reflectance of
+/// environment lighting, with `sample_count` samples per texel.
+///
+/// Texels are in rows of increasing roughness, the cosine of the view angle increasing
+/// along each row. Each texel holds the scale and bias in its red and green components,
+/// ready to be uploaded as `Rgba32Float`.
+pub fn brdf_lut(size: u32, sample_count: u32) -> Vec<[f32; 4]> {
+    let sample_count = sample_count.max(1);
+    let mut texels = Vec::with_capacity((size * size) as usize);
+    for y in 0..size {
+        let roughness = (y as f32 + 0.5) / size as f32;
+        let alpha = roughness * roughness;
+        // Geometry term of image-based lighting, with `k = alpha / 2`.
+        let k = alpha / 2.0;
+        let geometry = |cosine: f32| cosine / (cosine * (1.0 - k) + k);
+        for x in 0..size {
+            let n_dot_v = (x as f32 + 0.5) / size as f32;
+            let view = [(1.0 - n_dot_v * n_dot_v).sqrt(), 0.0, n_dot_v];
+            let (mut scale, mut bias) = (0.0, 0.0);
+            for index in 0..sample_count {
+                // Hammersley point, importance sampling the GGX distribution.
+                let u = index as f32 / sample_count as f32;
+                let v = index.reverse_bits() as f32 * 2.328_306_4e-10;
+                let phi = 2.0 * PI * u;
+                let cos_theta = ((1.0 - v) / (1.0 + (alpha * alpha - 1.0) * v)).sqrt();
+                let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
+                let half = [sin_theta * phi.cos(), sin_theta * phi.sin(), cos_theta];
+                let v_dot_h = view[0] * half[0] + view[2] * half[2];
+                let n_dot_l = 2.0 * v_dot_h * half[2] - view[2];
+                if n_dot_l > 0.0 {
+                    let visibility =
+                        geometry(n_dot_v) * geometry(n_dot_l) * v_dot_h / (half[2] * n_dot_v);
+                    let fresnel = (1.0 - v_dot_h).powi(5);
+                    scale += (1.0 - fresnel) * visibility;
+                    bias += fresnel * visibility;
+                }
+            }
+            texels.push([
+                scale / sample_count as f32,
+                bias / sample_count as f32,
+                0.0,
+                1.0,
+            ]);
+        }
+    }
+    texels
+}
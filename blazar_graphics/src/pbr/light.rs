@@ -0,0 +1,212 @@
+//! Punctual lights and the scene uniform block.
+
+use super::{Environment, ShadowMaps};
+use std::cmp::Ordering;
+
+/// Maximum number of lights lighting a frame.
+pub const MAX_LIGHTS: usize = 16;
+
+/// Punctual lights, as defined by the glTF `KHR_lights_punctual` extension.
+///
+/// Colors are linear and intensities are in lux for directional lights, in candela for
+/// point and spot lights. Without range, the intensity of point and spot lights
+/// decreases with the inverse square of the distance; with a range, it smoothly reaches
+/// zero at that distance.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Light {
+    Directional {
+        /// Direction the light travels in.
+        direction: [f32; 3],
+        color: [f32; 3],
+        intensity: f32,
+    },
+    Point {
+        position: [f32; 3],
+        color: [f32; 3],
+        intensity: f32,
+        range: Option<f32>,
+    },
+    Spot {
+        position: [f32; 3],
+        direction: [f32; 3],
+        color: [f32; 3],
+        intensity: f32,
+        range: Option<f32>,
+        /// Angle in radians from the direction where the intensity starts to decrease.
+        inner_cone_angle: f32,
+        /// Angle in radians from the direction where the intensity reaches zero.
+        outer_cone_angle: f32,
+    },
+}
+
+impl Light {
+    /// Returns the uniform block of the light.
+    pub fn uniform(&self) -> LightUniform {
+        match *self {
+            Light::Directional {
+                direction,
+                color,
+                intensity,
+            } => LightUniform {
+                position: [0.0; 3],
+                range: 0.0,
+                direction: normalize(direction),
+                kind: 0,
+                color,
+                intensity,
+                cone_scale: 0.0,
+                cone_offset: 1.0,
+                shadow: -1,
+                padding: 0.0,
+            },
+            Light::Point {
+                position,
+                color,
+                intensity,
+                range,
+            } => LightUniform {
+                position,
+                range: range.unwrap_or(0.0),
+                direction: [0.0; 3],
+                kind: 1,
+                color,
+                intensity,
+                cone_scale: 0.0,
+                cone_offset: 1.0,
+                shadow: -1,
+                padding: 0.0,
+            },
+            Light::Spot {
+                position,
+                direction,
+                color,
+                intensity,
+                range,
+                inner_cone_angle,
+                outer_cone_angle,
+            } => {
+                // Attenuation of `KHR_lights_punctual`, linear in the cosine of the angle.
+                let inner = inner_cone_angle.cos();
+                let outer = outer_cone_angle.cos();
+                let cone_scale = 1.0 / f32::max(inner - outer, 0.001);
+                LightUniform {
+                    position,
+                    range: range.unwrap_or(0.0),
+                    direction: normalize(direction),
+                    kind: 2,
+                    color,
+                    intensity,
+                    cone_scale,
+                    cone_offset: -outer * cone_scale,
+                    shadow: -1,
+                    padding: 0.0,
+                }
+            }
+        }
+    }
+
+    /// Returns the position of point and spot lights.
+    pub fn position(&self) -> Option<[f32; 3]> {
+        match *self {
+            Light::Directional { .. } => None,
+            Light::Point { position, .. } | Light::Spot { position, .. } => Some(position),
+        }
+    }
+}
+
+/// Uniform block of a light, with the std140 layout.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LightUniform {
+    pub position: [f32; 3],
+    /// Range, `0` meaning infinite.
+    pub range: f32,
+    pub direction: [f32; 3],
+    /// Kind of light: `0` directional, `1` point, `2` spot.
+    pub kind: u32,
+    pub color: [f32; 3],
+    pub intensity: f32,
+    /// Spot cone attenuation, `clamp(dot(direction, to_fragment) * scale + offset, 0, 1)`.
+    pub cone_scale: f32,
+    pub cone_offset: f32,
+    /// Layer of the shadow of the light in the shadow map of its kind, `-1` without
+    /// shadow.
+    pub shadow: i32,
+    padding: f32,
+}
+
+/// Uniform block shared by every draw of a frame, with the std140 layout.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SceneUniform {
+    /// Column-major view-projection matrix.
+    pub view_projection: [[f32; 4]; 4],
+    pub camera_position: [f32; 3],
+    pub light_count: u32,
+    /// Intensity of image-based lighting, `0` without environment.
+    pub environment_intensity: f32,
+    /// Number of mip levels of the prefiltered environment cube map.
+    pub prefiltered_levels: f32,
+    padding: [f32; 2],
+    pub lights: [LightUniform; MAX_LIGHTS],
+}
+
+impl SceneUniform {
+    /// Creates the uniform block of a frame seen from a camera.
+    ///
+    /// Beyond `MAX_LIGHTS`, directional lights are kept first, then the point and spot
+    /// lights nearest to the camera. With `shadows`, updated for the same lights, lights
+    /// casting shadows sample their shadow map.
+    pub fn new<T>(
+        view_projection: [[f32; 4]; 4],
+        camera_position: [f32; 3],
+        lights: &[Light],
+        environment: Option<&Environment<T>>,
+        shadows: Option<&ShadowMaps>,
+    ) -> SceneUniform {
+        let mut selected: Vec<(usize, &Light)> = lights.iter().enumerate().collect();
+        if selected.len() > MAX_LIGHTS {
+            let distance = |light: &Light| {
+                light.position().map(|position| {
+                    (0..3)
+                        .map(|axis| (position[axis] - camera_position[axis]).powi(2))
+                        .sum::<f32>()
+                })
+            };
+            // Directional lights, without position, come first.
+            selected.sort_by(|(_, a), (_, b)| {
+                distance(a)
+                    .partial_cmp(&distance(b))
+                    .unwrap_or(Ordering::Equal)
+            });
+            selected.truncate(MAX_LIGHTS);
+        }
+        let mut uniforms = [LightUniform::default(); MAX_LIGHTS];
+        for (uniform, (index, light)) in uniforms.iter_mut().zip(&selected) {
+            *uniform = light.uniform();
+            if let Some(shadow) = shadows.and_then(|shadows| shadows.shadow(*index)) {
+                uniform.shadow = shadow as i32;
+            }
+        }
+        SceneUniform {
+            view_projection,
+            camera_position,
+            light_count: selected.len() as u32,
+            environment_intensity: environment.map_or(0.0, |environment| environment.intensity),
+            prefiltered_levels: environment
+                .map_or(0.0, |environment| environment.prefiltered_levels as f32),
+            padding: [0.0; 2],
+            lights: uniforms,
+        }
+    }
+}
+
+/// Normalizes a vector, leaving zero vectors unchanged.
+fn normalize(vector: [f32; 3]) -> [f32; 3] {
+    let length = vector.iter().map(|value| value * value).sum::<f32>().sqrt();
+    if length > 0.0 {
+        [vector[0] / length, vector[1] / length, vector[2] / length]
+    } else {
+        vector
+    }
+}
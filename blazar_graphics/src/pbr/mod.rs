@@ -0,0 +1,490 @@
+//! Forward physically based rendering of 3D scenes.
+//!
+//! Materials follow the glTF metallic-roughness model, with normal, occlusion and
+//! emissive textures and glTF alpha modes, so that loaded models render as authored.
+//! Opaque and masked meshes are drawn first, grouped by pipeline and material, then
+//! blended ones from back to front. Every draw is lit by up to `MAX_LIGHTS` punctual
+//! lights and, optionally, by an `Environment` baked from a cube map, lights casting
+//! shadows from `ShadowMaps`. The matching GLSL shaders are `shaders/pbr.vert` and
+//! `shaders/pbr.frag`.
+
+mod ibl;
+mod light;
+mod shadow;
+
+pub use ibl::*;
+pub use light::*;
+pub use shadow::*;
+
+use crate::{
+    descriptor::{DescriptorBinding, DescriptorSetLayoutDescription, DescriptorType},
+    order::float_key,
+    pipeline::{
+        BlendState, CullMode, DepthState, GraphicsPipelineDescription, PipelineLayoutDescription,
+        PushConstantRange, RasterState, Shader, ShaderStage, VertexFormat, VertexInputRate,
+        VertexLayout,
+    },
+    target::SampleCount,
+    texture::TextureFormat,
+};
+use blazar_model::{AlphaMode, Material, Vertex};
+
+/// Vertex of a mesh.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PbrVertex {
+    pub position: [f32; 3],
+    pub normal: [f32; 3],
+    /// Tangent, its `w` component being the handedness of the bitangent. A zero tangent
+    /// disables normal mapping.
+    pub tangent: [f32; 4],
+    pub tex_coord: [f32; 2],
+    /// Linear color multiplied with the base color.
+    pub color: [f32; 4],
+}
+
+impl PbrVertex {
+    /// Returns the vertex layout.
+    pub fn layout() -> VertexLayout {
+        VertexLayout::new().interleaved(
+            VertexInputRate::Vertex,
+            &[
+                VertexFormat::Float3,
+                VertexFormat::Float3,
+                VertexFormat::Float4,
+                VertexFormat::Float2,
+                VertexFormat::Float4,
+            ],
+        )
+    }
+}
+
+impl From<&Vertex> for PbrVertex {
+    fn from(vertex: &Vertex) -> PbrVertex {
+        PbrVertex {
+            position: vertex.position,
+            normal: vertex.normal,
+            tangent: vertex.tangent,
+            tex_coord: vertex.tex_coord,
+            color: vertex.color,
+        }
+    }
+}
+
+/// Textures of a material.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum MaterialTexture {
+    BaseColor,
+    /// Roughness in the green channel, metalness in the blue channel.
+    MetallicRoughness,
+    /// Tangent space normal.
+    Normal,
+    /// Ambient occlusion in the red channel.
+    Occlusion,
+    Emissive,
+}
+
+impl MaterialTexture {
+    /// All material textures, in binding order.
+    pub const ALL: [MaterialTexture; 5] = [
+        MaterialTexture::BaseColor,
+        MaterialTexture::MetallicRoughness,
+        MaterialTexture::Normal,
+        MaterialTexture::Occlusion,
+        MaterialTexture::Emissive,
+    ];
+
+    /// Returns the binding of the texture in the material descriptor set.
+    pub fn binding(self) -> u32 {
+        self as u32 + 1
+    }
+
+    /// Returns the format to upload the texture with, color textures being sRGB encoded.
+    pub fn format(self) -> TextureFormat {
+        match self {
+            MaterialTexture::BaseColor | MaterialTexture::Emissive => TextureFormat::Rgba8Srgb,
+            _ => TextureFormat::Rgba8Unorm,
+        }
+    }
+}
+
+/// Metallic-roughness material.
+///
+/// `T` identifies the textures of the material. Factors are multiplied with the texels
+/// of the matching textures, if any.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PbrMaterial<T> {
+    /// Linear base color.
+    pub base_color: [f32; 4],
+    pub metallic: f32,
+    pub roughness: f32,
+    /// Linear emissive color.
+    pub emissive: [f32; 3],
+    /// Scale of the x and y components of the normal texture.
+    pub normal_scale: f32,
+    /// Strength of the occlusion texture, from `0` (none) to `1`.
+    pub occlusion_strength: f32,
+    pub alpha_mode: AlphaMode,
+    /// Whether back faces are drawn, their normal being flipped.
+    pub double_sided: bool,
+    /// Textures, indexed by `MaterialTexture`.
+    pub textures: [Option<T>; 5],
+}
+
+impl<T: Copy> PbrMaterial<T> {
+    /// Creates an opaque dielectric material of a base color.
+    pub fn new(base_color: [f32; 4]) -> PbrMaterial<T> {
+        PbrMaterial {
+            base_color,
+            metallic: 0.0,
+            roughness: 0.5,
+            emissive: [0.0; 3],
+            normal_scale: 1.0,
+            occlusion_strength: 1.0,
+            alpha_mode: AlphaMode::Opaque,
+            double_sided: false,
+            textures: [None; 5],
+        }
+    }
+
+    /// Converts a material of a loaded model, `texture` returning the texture uploaded
+    /// for a texture index of the model, in the format given by `MaterialTexture::format`.
+    ///
+    /// Meshes only having their first set of texture coordinates, textures using another
+    /// set (`TEXCOORD_1` and beyond) are left out rather than sampled with the wrong
+    /// coordinates.
+    pub fn from_model<F>(material: &Material, mut texture: F) -> PbrMaterial<T>
+    where
+        F: FnMut(usize, MaterialTexture) -> T,
+    {
+        let references = [
+            material.base_color_texture,
+            material.metallic_roughness_texture,
+            material.normal_texture,
+            material.occlusion_texture,
+            material.emissive_texture,
+        ];
+        let mut textures = [None; 5];
+        for (kind, reference) in MaterialTexture::ALL.iter().zip(&references) {
+            textures[*kind as usize] = reference
+                .filter(|reference| reference.tex_coord == 0)
+                .map(|reference| texture(reference.texture, *kind));
+        }
+        PbrMaterial {
+            base_color: material.base_color,
+            metallic: material.metallic,
+            roughness: material.roughness,
+            emissive: material.emissive,
+            normal_scale: material.normal_texture.map_or(1.0, |texture| texture.scale),
+            occlusion_strength: material
+                .occlusion_texture
+                .map_or(1.0, |texture| texture.scale),
+            alpha_mode: material.alpha_mode,
+            double_sided: material.double_sided,
+            textures,
+        }
+    }
+
+    /// Sets the metallic and roughness factors.
+    pub fn metallic_roughness(mut self, metallic: f32, roughness: f32) -> PbrMaterial<T> {
+        self.metallic = metallic;
+        self.roughness = roughness;
+        self
+    }
+
+    /// Sets the emissive color.
+    pub fn emissive(mut self, emissive: [f32; 3]) -> PbrMaterial<T> {
+        self.emissive = emissive;
+        self
+    }
+
+    /// Sets the alpha mode.
+    pub fn alpha_mode(mut self, alpha_mode: AlphaMode) -> PbrMaterial<T> {
+        self.alpha_mode = alpha_mode;
+        self
+    }
+
+    /// Sets whether back faces are drawn.
+    pub fn double_sided(mut self, double_sided: bool) -> PbrMaterial<T> {
+        self.double_sided = double_sided;
+        self
+    }
+
+    /// Sets a texture.
+    pub fn texture(mut self, kind: MaterialTexture, texture: T) -> PbrMaterial<T> {
+        self.textures[kind as usize] = Some(texture);
+        self
+    }
+
+    /// Returns a texture, if any.
+    pub fn get_texture(&self, kind: MaterialTexture) -> Option<T> {
+        self.textures[kind as usize]
+    }
+
+    /// Returns the pipeline states of the material.
+    pub fn pipeline(&self) -> MaterialPipeline {
+        MaterialPipeline {
+            blend: self.alpha_mode == AlphaMode::Blend,
+            double_sided: self.double_sided,
+        }
+    }
+
+    /// Returns the uniform block of the material.
+    ///
+    /// Missing textures must still be bound, such as to a 1x1 white texture; the shader
+    /// ignores them.
+    pub fn uniform(&self) -> MaterialUniform {
+        let texture_flags = MaterialTexture::ALL
+            .iter()
+            .filter(|kind| self.textures[**kind as usize].is_some())
+            .fold(0, |flags, kind| flags | 1 << *kind as u32);
+        let (alpha_mode, alpha_cutoff) = match self.alpha_mode {
+            AlphaMode::Opaque => (0, 0.0),
+            AlphaMode::Mask(cutoff) => (1, cutoff),
+            AlphaMode::Blend => (2, 0.0),
+        };
+        MaterialUniform {
+            base_color: self.base_color,
+            emissive: self.emissive,
+            alpha_cutoff,
+            factors: [
+                self.metallic,
+                self.roughness,
+                self.normal_scale,
+                self.occlusion_strength,
+            ],
+            flags: [texture_flags, alpha_mode, self.double_sided as u32, 0],
+        }
+    }
+}
+
+/// Uniform block of a material, with the std140 layout.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MaterialUniform {
+    pub base_color: [f32; 4],
+    pub emissive: [f32; 3],
+    pub alpha_cutoff: f32,
+    /// Metallic, roughness, normal scale and occlusion strength.
+    pub factors: [f32; 4],
+    /// Bits of the bound textures (`1 << MaterialTexture`), alpha mode (`0` opaque, `1`
+    /// masked, `2` blended) and whether the material is double-sided.
+    pub flags: [u32; 4],
+}
+
+/// Pipeline states depending on the material.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct MaterialPipeline {
+    pub blend: bool,
+    pub double_sided: bool,
+}
+
+/// Identifies a material added to a renderer.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct MaterialId(u32);
+
+/// Draw call of a mesh.
+///
+/// `M` identifies what must be bound to draw the mesh, typically its vertex and index
+/// buffers.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PbrDraw<M> {
+    pub mesh: M,
+    pub material: MaterialId,
+    pub pipeline: MaterialPipeline,
+    /// Column-major model matrix, passed as push constants.
+    pub transform: [[f32; 4]; 4],
+}
+
+/// Materials of a scene, and meshes drawn each frame sorted for forward rendering.
+pub struct PbrRenderer<M, T> {
+    materials: Vec<PbrMaterial<T>>,
+    draws: Vec<PbrDraw<M>>,
+    opaque_count: usize,
+}
+
+impl<M: Copy, T: Copy> PbrRenderer<M, T> {
+    /// Size in bytes of the push constants of the vertex shader.
+    pub const PUSH_CONSTANTS_SIZE: u32 = 64;
+
+    /// Creates a renderer without materials.
+    pub fn new() -> PbrRenderer<M, T> {
+        PbrRenderer {
+            materials: Vec::new(),
+            draws: Vec::new(),
+            opaque_count: 0,
+        }
+    }
+
+    /// Adds a material.
+    pub fn add_material(&mut self, material: PbrMaterial<T>) -> MaterialId {
+        self.materials.push(material);
+        MaterialId(self.materials.len() as u32 - 1)
+    }
+
+    /// Returns a material.
+    pub fn material(&self, id: MaterialId) -> &PbrMaterial<T> {
+        &self.materials[id.0 as usize]
+    }
+
+    /// Returns the materials, indexed by `MaterialId`.
+    pub fn materials(&self) -> &[PbrMaterial<T>] {
+        &self.materials
+    }
+
+    /// Replaces a material, whose uniform block must be updated.
+    pub fn set_material(&mut self, id: MaterialId, material: PbrMaterial<T>) {
+        self.materials[id.0 as usize] = material;
+    }
+
+    /// Draws a mesh with a material and a column-major model matrix this frame.
+    pub fn draw(&mut self, mesh: M, material: MaterialId, transform: [[f32; 4]; 4]) {
+        self.draws.push(PbrDraw {
+            mesh,
+            material,
+            pipeline: self.material(material).pipeline(),
+            transform,
+        });
+    }
+
+    /// Removes the draws of the frame.
+    pub fn clear(&mut self) {
+        self.draws.clear();
+        self.opaque_count = 0;
+    }
+
+    /// Sorts the draws of the frame, seen from `camera_position`.
+    ///
+    /// Opaque and masked draws are grouped by pipeline and material. Blended draws come
+    /// last, sorted from back to front by the distance of their origin to the camera;
+    /// blended draws whose distance is NaN come first.
+    pub fn build(&mut self, camera_position: [f32; 3]) {
+        let distance = |draw: &PbrDraw<M>| {
+            let origin = draw.transform[3];
+            (0..3)
+                .map(|axis| (origin[axis] - camera_position[axis]).powi(2))
+                .sum::<f32>()
+        };
+        self.draws.sort_by(|a, b| {
+            a.pipeline.blend.cmp(&b.pipeline.blend).then_with(|| {
+                if a.pipeline.blend {
+                    float_key(distance(b)).cmp(&float_key(distance(a)))
+                } else {
+                    (a.pipeline, a.material).cmp(&(b.pipeline, b.material))
+                }
+            })
+        });
+        self.opaque_count = self
+            .draws
+            .iter()
+            .take_while(|draw| !draw.pipeline.blend)
+            .count();
+    }
+
+    /// Returns the draws sorted by `build`.
+    pub fn draws(&self) -> &[PbrDraw<M>] {
+        &self.draws
+    }
+
+    /// Returns the opaque and masked draws sorted by `build`.
+    pub fn opaque_draws(&self) -> &[PbrDraw<M>] {
+        &self.draws[..self.opaque_count]
+    }
+
+    /// Returns the blended draws sorted by `build`.
+    pub fn blended_draws(&self) -> &[PbrDraw<M>] {
+        &self.draws[self.opaque_count..]
+    }
+
+    /// Returns the layout of the descriptor set binding the `SceneUniform` and the
+    /// textures of the `Environment`, then the `ShadowUniform` and the cascade, spot and
+    /// point shadow maps, shared by every draw. The point shadow map is not read when
+    /// `shaders/pbr.frag` is compiled with `NO_POINT_SHADOWS`.
+    pub fn scene_set_layout() -> DescriptorSetLayoutDescription {
+        let fragment = ShaderStage::Fragment as u32;
+        (1..8).fold(
+            DescriptorSetLayoutDescription::new().binding(DescriptorBinding::new(
+                0,
+                DescriptorType::UniformBuffer,
+                ShaderStage::Vertex as u32 | fragment,
+            )),
+            |layout, binding| {
+                let descriptor_type = if binding == 4 {
+                    DescriptorType::UniformBuffer
+                } else {
+                    DescriptorType::CombinedImageSampler
+                };
+                layout.binding(DescriptorBinding::new(binding, descriptor_type, fragment))
+            },
+        )
+    }
+
+    /// Returns the layout of the descriptor set binding the `MaterialUniform` and the
+    /// textures of a material, at `MaterialTexture::binding`.
+    pub fn material_set_layout() -> DescriptorSetLayoutDescription {
+        let fragment = ShaderStage::Fragment as u32;
+        MaterialTexture::ALL.iter().fold(
+            DescriptorSetLayoutDescription::new().binding(DescriptorBinding::new(
+                0,
+                DescriptorType::UniformBuffer,
+                fragment,
+            )),
+            |layout, kind| {
+                layout.binding(DescriptorBinding::new(
+                    kind.binding(),
+                    DescriptorType::CombinedImageSampler,
+                    fragment,
+                ))
+            },
+        )
+    }
+
+    /// Describes the pipeline drawing meshes with the given material states into a
+    /// target of `samples` samples per pixel.
+    ///
+    /// Blended meshes test depth without writing it. Back faces are culled unless the
+    /// material is double-sided, front faces winding counter-clockwise as in glTF.
+    pub fn pipeline_description(
+        vertex_shader: Shader,
+        fragment_shader: Shader,
+        pipeline: MaterialPipeline,
+        samples: SampleCount,
+    ) -> GraphicsPipelineDescription {
+        let (depth, blend) = if pipeline.blend {
+            (DepthState::READ_ONLY, BlendState::ALPHA)
+        } else {
+            (DepthState::LESS, BlendState::REPLACE)
+        };
+        GraphicsPipelineDescription::new()
+            .shader(vertex_shader)
+            .shader(fragment_shader)
+            .layout(
+                PipelineLayoutDescription::new()
+                    .set_layout(Self::scene_set_layout())
+                    .set_layout(Self::material_set_layout())
+                    .push_constants(PushConstantRange {
+                        stages: ShaderStage::Vertex as u32,
+                        offset: 0,
+                        size: Self::PUSH_CONSTANTS_SIZE,
+                    }),
+            )
+            .vertex_layout(PbrVertex::layout())
+            .raster(RasterState {
+                cull_mode: if pipeline.double_sided {
+                    CullMode::None
+                } else {
+                    CullMode::Back
+                },
+                ..RasterState::default()
+            })
+            .depth(depth)
+            .blend(&[blend])
+            .samples(samples)
+    }
+}
+
+impl<M: Copy, T: Copy> Default for PbrRenderer<M, T> {
+    fn default() -> PbrRenderer<M, T> {
+        PbrRenderer::new()
+    }
+}
@@ -0,0 +1,516 @@
+//! Shadow maps of punctual lights.
+//!
+//! Shadow casters are drawn into depth-only shadow maps from the point of view of the
+//! lights, then sampled by `shaders/pbr.frag` with percentage-closer filtering. The first
+//! directional light gets cascaded shadow maps, layers covering increasingly distant
+//! slices of the view frustum; spot lights get a perspective shadow map and point lights
+//! a cube shadow map. Casters are drawn by `shaders/shadow.vert`, without fragment
+//! shader, so masked materials cast the shadow of their whole mesh.
+//!
+//! Point shadow maps are cube map arrays, which need the `imageCubeArray` device feature.
+//! Without it, point shadows are disabled through `ShadowSettings::supported` and
+//! `shaders/pbr.frag` is compiled with `NO_POINT_SHADOWS` defined.
+
+use super::{Light, PbrVertex};
+use crate::{
+    pipeline::{
+        CullMode, DepthBias, DepthState, GraphicsPipelineDescription, PipelineLayoutDescription,
+        PushConstantRange, RasterState, Shader, ShaderStage,
+    },
+    target::DepthFormat,
+    texture::TextureKind,
+};
+use blazar_math::{Mat4, Vec3, Vec4};
+
+/// Maximum number of cascades of the directional shadow.
+pub const MAX_CASCADES: usize = 4;
+
+/// Maximum number of spot lights casting shadows.
+pub const MAX_SPOT_SHADOWS: usize = 4;
+
+/// Maximum number of point lights casting shadows.
+pub const MAX_POINT_SHADOWS: usize = 4;
+
+/// Settings of shadow maps.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ShadowSettings {
+    /// Width and height of shadow maps, in texels.
+    pub resolution: u32,
+    /// Depth bias applied when drawing casters, scaled by the depth slope.
+    pub depth_bias: DepthBias,
+    /// Offset of sampled positions along their normal, in shadow map texels.
+    pub normal_bias: f32,
+    /// Radius of the percentage-closer filter, which averages `(2 * radius + 1)²`
+    /// comparisons; `0` takes a single one.
+    pub pcf_radius: u32,
+    /// Number of cascades of the directional shadow, up to `MAX_CASCADES`.
+    pub cascade_count: u32,
+    /// Blend between uniform (`0`) and logarithmic (`1`) cascade splits.
+    pub split_lambda: f32,
+    /// Distance from the camera beyond which directional shadows end. It is also the
+    /// range of point and spot lights without one.
+    pub max_distance: f32,
+    /// Distance towards directional lights beyond cascades where casters are still
+    /// drawn.
+    pub caster_margin: f32,
+    /// Near plane of spot and point shadow maps.
+    pub near: f32,
+    /// Whether point lights cast shadows.
+    pub point_shadows: bool,
+}
+
+impl Default for ShadowSettings {
+    fn default() -> ShadowSettings {
+        ShadowSettings {
+            resolution: 2048,
+            depth_bias: DepthBias {
+                constant_factor: 1.25,
+                clamp: 0.0,
+                slope_factor: 1.75,
+            },
+            normal_bias: 1.0,
+            pcf_radius: 1,
+            cascade_count: MAX_CASCADES as u32,
+            split_lambda: 0.75,
+            max_distance: 100.0,
+            caster_margin: 50.0,
+            near: 0.05,
+            point_shadows: true,
+        }
+    }
+}
+
+impl ShadowSettings {
+    /// Sets the resolution of shadow maps.
+    pub fn resolution(mut self, resolution: u32) -> ShadowSettings {
+        self.resolution = resolution;
+        self
+    }
+
+    /// Sets the depth bias and the normal bias.
+    pub fn bias(mut self, depth_bias: DepthBias, normal_bias: f32) -> ShadowSettings {
+        self.depth_bias = depth_bias;
+        self.normal_bias = normal_bias;
+        self
+    }
+
+    /// Sets the radius of the percentage-closer filter.
+    pub fn pcf_radius(mut self, pcf_radius: u32) -> ShadowSettings {
+        self.pcf_radius = pcf_radius;
+        self
+    }
+
+    /// Sets the number of cascades, clamped to `1..=MAX_CASCADES`.
+    pub fn cascade_count(mut self, cascade_count: u32) -> ShadowSettings {
+        self.cascade_count = cascade_count.max(1).min(MAX_CASCADES as u32);
+        self
+    }
+
+    /// Sets the distance beyond which directional shadows end.
+    pub fn max_distance(mut self, max_distance: f32) -> ShadowSettings {
+        self.max_distance = max_distance;
+        self
+    }
+
+    /// Enables or disables point shadows.
+    pub fn point_shadows(mut self, point_shadows: bool) -> ShadowSettings {
+        self.point_shadows = point_shadows;
+        self
+    }
+
+    /// Disables the shadows the device does not support.
+    pub fn supported(mut self, support: &ShadowSupport) -> ShadowSettings {
+        self.point_shadows &= support.supports_point_shadows();
+        self
+    }
+}
+
+/// Shadow support of a device.
+///
+/// Fields come from `VkPhysicalDeviceFeatures`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ShadowSupport {
+    pub image_cube_array: bool,
+}
+
+impl ShadowSupport {
+    /// Returns `true` if point shadows, whose shadow map is a cube map array, are
+    /// supported.
+    pub fn supports_point_shadows(&self) -> bool {
+        self.image_cube_array
+    }
+}
+
+/// Camera the directional shadow is fitted to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ShadowCamera {
+    /// Column-major matrix from view space, looking down -z, to world space.
+    pub transform: [[f32; 4]; 4],
+    /// Vertical field of view in radians.
+    pub fov_y: f32,
+    pub aspect_ratio: f32,
+    pub near: f32,
+    pub far: f32,
+}
+
+/// Kinds of shadow maps.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ShadowKind {
+    /// Layer of the cascaded shadow map of the directional light.
+    Cascade,
+    /// Layer of the shadow map of spot lights.
+    Spot,
+    /// Face of the shadow map of point lights.
+    Point,
+}
+
+/// Describes a shadow map image.
+///
+/// Shadow maps are sampled with depth comparison (`VK_COMPARE_OP_LESS_OR_EQUAL`),
+/// linear filtering and a white border clamping coordinates.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct ShadowMapDescription {
+    /// `TextureKind::Cube` for point lights, whose map is a cube map array.
+    pub kind: TextureKind,
+    pub size: u32,
+    pub layers: u32,
+    pub format: DepthFormat,
+}
+
+/// Pass drawing shadow casters into a layer of a shadow map.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ShadowPass {
+    pub kind: ShadowKind,
+    /// Index of the light.
+    pub light: usize,
+    /// Array layer of the shadow map, the faces of point lights being at
+    /// `6 * slot + face`.
+    pub layer: u32,
+    /// Column-major view-projection matrix, passed as push constants.
+    pub view_projection: [[f32; 4]; 4],
+}
+
+/// Uniform block of shadows, with the std140 layout.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ShadowUniform {
+    /// Column-major view-projection matrices of the cascades.
+    pub cascades: [[[f32; 4]; 4]; MAX_CASCADES],
+    /// View depths where each cascade ends.
+    pub cascade_splits: [f32; MAX_CASCADES],
+    /// Sizes in world units of the texels of each cascade.
+    pub cascade_texel_sizes: [f32; MAX_CASCADES],
+    /// Column-major view-projection matrices of spot shadows.
+    pub spots: [[[f32; 4]; 4]; MAX_SPOT_SHADOWS],
+    /// Sizes of the texels of spot shadows at a unit distance from the light.
+    pub spot_texel_scales: [f32; MAX_SPOT_SHADOWS],
+    /// Terms mapping the distance along the major axis of a point shadow to its depth,
+    /// `x + y / distance`.
+    pub point_depths: [[f32; 4]; MAX_POINT_SHADOWS],
+    /// Direction the camera looks in, giving the view depth selecting cascades.
+    pub camera_forward: [f32; 3],
+    pub cascade_count: u32,
+    pub pcf_radius: u32,
+    /// Normal bias, in texels.
+    pub normal_bias: f32,
+    /// Inverse of the resolution of shadow maps.
+    pub texel_size: f32,
+    padding: f32,
+}
+
+/// Shadow maps of the lights of a scene.
+pub struct ShadowMaps {
+    settings: ShadowSettings,
+    passes: Vec<ShadowPass>,
+    shadows: Vec<Option<u32>>,
+    uniform: ShadowUniform,
+}
+
+impl ShadowMaps {
+    /// Size in bytes of the push constants of the vertex shader: the view-projection and
+    /// model matrices.
+    pub const PUSH_CONSTANTS_SIZE: u32 = 128;
+
+    /// Depth format of shadow maps.
+    pub const FORMAT: DepthFormat = DepthFormat::D32Float;
+
+    /// Creates shadow maps without shadows.
+    pub fn new(settings: ShadowSettings) -> ShadowMaps {
+        let settings = settings.cascade_count(settings.cascade_count);
+        ShadowMaps {
+            settings,
+            passes: Vec::new(),
+            shadows: Vec::new(),
+            uniform: ShadowUniform {
+                cascades: [[[0.0; 4]; 4]; MAX_CASCADES],
+                cascade_splits: [0.0; MAX_CASCADES],
+                cascade_texel_sizes: [0.0; MAX_CASCADES],
+                spots: [[[0.0; 4]; 4]; MAX_SPOT_SHADOWS],
+                spot_texel_scales: [0.0; MAX_SPOT_SHADOWS],
+                point_depths: [[0.0; 4]; MAX_POINT_SHADOWS],
+                camera_forward: [0.0, 0.0, -1.0],
+                cascade_count: 0,
+                pcf_radius: settings.pcf_radius,
+                normal_bias: settings.normal_bias,
+                texel_size: 1.0 / settings.resolution.max(1) as f32,
+                padding: 0.0,
+            },
+        }
+    }
+
+    /// Returns the settings.
+    pub fn settings(&self) -> &ShadowSettings {
+        &self.settings
+    }
+
+    /// Describes the shadow map of a kind of shadows.
+    ///
+    /// Shadow maps keep their size whatever the number of shadows, so that they can
+    /// always be bound; they must be recreated when the resolution changes. The point
+    /// shadow map is not needed, and cannot be created without `imageCubeArray`, when
+    /// point shadows are disabled.
+    pub fn map_description(&self, kind: ShadowKind) -> ShadowMapDescription {
+        let (kind, layers) = match kind {
+            ShadowKind::Cascade => (TextureKind::D2, self.settings.cascade_count),
+            ShadowKind::Spot => (TextureKind::D2, MAX_SPOT_SHADOWS as u32),
+            ShadowKind::Point => (TextureKind::Cube, 6 * MAX_POINT_SHADOWS as u32),
+        };
+        ShadowMapDescription {
+            kind,
+            size: self.settings.resolution,
+            layers,
+            format: Self::FORMAT,
+        }
+    }
+
+    /// Places the shadows of `lights`, the ones given to `SceneUniform::new`, for a frame
+    /// seen from `camera`.
+    ///
+    /// The first directional light, the first `MAX_SPOT_SHADOWS` spot lights and the
+    /// first `MAX_POINT_SHADOWS` point lights cast shadows, unless point shadows are
+    /// disabled; lights should be ordered by importance.
+    pub fn update(&mut self, camera: &ShadowCamera, lights: &[Light]) {
+        self.passes.clear();
+        self.shadows.clear();
+        self.uniform.cascade_count = 0;
+        let transform = Mat4::from(camera.transform);
+        self.uniform.camera_forward = (transform * Vec4::new(0.0, 0.0, -1.0, 0.0))
+            .truncate()
+            .normalize_or_zero()
+            .to_array();
+        let (mut spot_count, mut point_count) = (0, 0);
+        for (index, light) in lights.iter().enumerate() {
+            let shadow = match *light {
+                Light::Directional { direction, .. } if self.uniform.cascade_count == 0 => {
+                    self.update_cascades(index, camera, direction.into());
+                    Some(0)
+                }
+                Light::Spot {
+                    position,
+                    direction,
+                    range,
+                    outer_cone_angle,
+                    ..
+                } if spot_count < MAX_SPOT_SHADOWS => {
+                    let fov = (2.0 * outer_cone_angle + 0.1).min(3.0);
+                    let view = Mat4::look_to_rh(
+                        position.into(),
+                        direction.into(),
+                        up_vector(direction.into()),
+                    );
+                    let far = range.unwrap_or(self.settings.max_distance);
+                    let projection = Mat4::perspective_rh(fov, 1.0, self.settings.near, far);
+                    let view_projection = (projection * view).to_cols_array_2d();
+                    self.uniform.spots[spot_count] = view_projection;
+                    self.uniform.spot_texel_scales[spot_count] =
+                        2.0 * (fov * 0.5).tan() * self.uniform.texel_size;
+                    self.passes.push(ShadowPass {
+                        kind: ShadowKind::Spot,
+                        light: index,
+                        layer: spot_count as u32,
+                        view_projection,
+                    });
+                    spot_count += 1;
+                    Some(spot_count as u32 - 1)
+                }
+                Light::Point {
+                    position, range, ..
+                } if self.settings.point_shadows && point_count < MAX_POINT_SHADOWS => {
+                    let near = self.settings.near;
+                    let far = range.unwrap_or(self.settings.max_distance);
+                    self.uniform.point_depths[point_count] =
+                        [far / (far - near), -far * near / (far - near), 0.0, 0.0];
+                    for (face, view_projection) in cube_view_projections(position.into(), near, far)
+                        .iter()
+                        .enumerate()
+                    {
+                        self.passes.push(ShadowPass {
+                            kind: ShadowKind::Point,
+                            light: index,
+                            layer: (6 * point_count + face) as u32,
+                            view_projection: *view_projection,
+                        });
+                    }
+                    point_count += 1;
+                    Some(point_count as u32 - 1)
+                }
+                _ => None,
+            };
+            self.shadows.push(shadow);
+        }
+    }
+
+    /// Fits the cascades of a directional light to the view frustum of the camera.
+    ///
+    /// Each cascade bounds the sphere around its slice of the frustum, whose size does
+    /// not change as the camera rotates, and moves by whole texels, so that shadow edges
+    /// do not shimmer as the camera moves.
+    fn update_cascades(&mut self, light: usize, camera: &ShadowCamera, direction: Vec3) {
+        let settings = self.settings;
+        let transform = Mat4::from(camera.transform);
+        let view = Mat4::look_to_rh(Vec3::ZERO, direction, up_vector(direction));
+        let far = camera.far.min(settings.max_distance);
+        let splits = cascade_splits(
+            camera.near,
+            far,
+            settings.cascade_count,
+            settings.split_lambda,
+        );
+        let tangent = (camera.fov_y * 0.5).tan();
+        let mut near = camera.near;
+        for (index, &split) in splits.iter().enumerate() {
+            let mut corners = Vec::with_capacity(8);
+            for &depth in &[near, split] {
+                let height = depth * tangent;
+                let width = height * camera.aspect_ratio;
+                for &(x, y) in &[(-1.0, -1.0), (1.0, -1.0), (-1.0, 1.0), (1.0, 1.0)] {
+                    let corner = Vec3::new(x * width, y * height, -depth);
+                    corners.push(transform.transform_point3(corner));
+                }
+            }
+            let center = corners.iter().fold(Vec3::ZERO, |sum, corner| sum + *corner) / 8.0;
+            let radius = corners
+                .iter()
+                .map(|corner| corner.distance(center))
+                .fold(0.0, f32::max);
+            // Rounded up so that precision errors do not change the extent of the cascade.
+            let radius = (radius * 16.0).ceil() / 16.0;
+            let texel = 2.0 * radius / settings.resolution.max(1) as f32;
+            let center = view.transform_point3(center);
+            let x = (center.x / texel).floor() * texel;
+            let y = (center.y / texel).floor() * texel;
+            let projection = Mat4::orthographic_rh(
+                x - radius,
+                x + radius,
+                y - radius,
+                y + radius,
+                -center.z - radius - settings.caster_margin,
+                -center.z + radius,
+            );
+            let view_projection = (projection * view).to_cols_array_2d();
+            self.uniform.cascades[index] = view_projection;
+            self.uniform.cascade_splits[index] = split;
+            self.uniform.cascade_texel_sizes[index] = texel;
+            self.passes.push(ShadowPass {
+                kind: ShadowKind::Cascade,
+                light,
+                layer: index as u32,
+                view_projection,
+            });
+            near = split;
+        }
+        self.uniform.cascade_count = splits.len() as u32;
+    }
+
+    /// Returns the passes placed by `update`.
+    pub fn passes(&self) -> &[ShadowPass] {
+        &self.passes
+    }
+
+    /// Returns the shadow of a light, as its layer in the shadow map of its kind (or its
+    /// cube map for point lights).
+    pub fn shadow(&self, light: usize) -> Option<u32> {
+        self.shadows.get(light).copied().flatten()
+    }
+
+    /// Returns the uniform block of the shadows placed by `update`.
+    pub fn uniform(&self) -> &ShadowUniform {
+        &self.uniform
+    }
+
+    /// Describes the pipeline drawing shadow casters, without color attachments.
+    ///
+    /// Both faces of casters are drawn, so that open meshes cast shadows.
+    pub fn pipeline_description(&self, vertex_shader: Shader) -> GraphicsPipelineDescription {
+        GraphicsPipelineDescription::new()
+            .shader(vertex_shader)
+            .layout(
+                PipelineLayoutDescription::new().push_constants(PushConstantRange {
+                    stages: ShaderStage::Vertex as u32,
+                    offset: 0,
+                    size: Self::PUSH_CONSTANTS_SIZE,
+                }),
+            )
+            .vertex_layout(PbrVertex::layout())
+            .raster(RasterState {
+                cull_mode: CullMode::None,
+                depth_bias: Some(self.settings.depth_bias),
+                ..RasterState::default()
+            })
+            .depth(DepthState::LESS)
+            .blend(&[])
+    }
+}
+
+/// Returns the view depths where each of `count` cascades covering `near` to `far` ends.
+///
+/// Splits blend uniform and logarithmic distributions by `lambda`, logarithmic splits
+/// keeping the size of texels on screen constant.
+pub fn cascade_splits(near: f32, far: f32, count: u32, lambda: f32) -> Vec<f32> {
+    (1..=count)
+        .map(|index| {
+            let ratio = index as f32 / count as f32;
+            let logarithmic = near * (far / near).powf(ratio);
+            let uniform = near + (far - near) * ratio;
+            uniform + (logarithmic - uniform) * lambda
+        })
+        .collect()
+}
+
+/// Returns the view-projection matrices of the faces of a cube map, ordered +X, -X, +Y,
+/// -Y, +Z, -Z.
+///
+/// Faces follow the orientation of cube map sampling, upside down compared to other
+/// views: front faces wind clockwise.
+fn cube_view_projections(position: Vec3, near: f32, far: f32) -> [[[f32; 4]; 4]; 6] {
+    let range = far / (near - far);
+    let projection = Mat4::from_columns(
+        Vec4::new(1.0, 0.0, 0.0, 0.0),
+        Vec4::new(0.0, 1.0, 0.0, 0.0),
+        Vec4::new(0.0, 0.0, range, -1.0),
+        Vec4::new(0.0, 0.0, near * range, 0.0),
+    );
+    let faces = [
+        (Vec3::X, -Vec3::Y),
+        (-Vec3::X, -Vec3::Y),
+        (Vec3::Y, Vec3::Z),
+        (-Vec3::Y, -Vec3::Z),
+        (Vec3::Z, -Vec3::Y),
+        (-Vec3::Z, -Vec3::Y),
+    ];
+    let mut view_projections = [[[0.0; 4]; 4]; 6];
+    for (view_projection, &(direction, up)) in view_projections.iter_mut().zip(&faces) {
+        *view_projection =
+            (projection * Mat4::look_to_rh(position, direction, up)).to_cols_array_2d();
+    }
+    view_projections
+}
+
+/// Returns a vertical direction not parallel to `direction`.
+fn up_vector(direction: Vec3) -> Vec3 {
+    if direction.normalize_or_zero().y.abs() > 0.99 {
+        Vec3::Z
+    } else {
+        Vec3::Y
+    }
+}
@@ -0,0 +1,132 @@
+//! On-disk persistence of Vulkan pipeline caches.
+
+use blazar_vk_sys as vk_sys;
+use std::{
+    convert::TryInto,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Size in bytes of `VkPipelineCacheHeaderVersionOne`.
+const HEADER_SIZE: usize = 16 + vk_sys::VK_UUID_SIZE;
+
+/// Kinds of pipeline cache errors.
+#[derive(Debug)]
+pub enum PipelineCacheError {
+    Io(io::Error),
+    InvalidHeader,
+    IncompatibleDevice,
+}
+
+impl From<io::Error> for PipelineCacheError {
+    fn from(error: io::Error) -> PipelineCacheError {
+        PipelineCacheError::Io(error)
+    }
+}
+
+/// Identifies the physical device and driver a pipeline cache was produced by.
+///
+/// Fields come from `VkPhysicalDeviceProperties`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct DeviceIdentity {
+    pub vendor_id: u32,
+    pub device_id: u32,
+    pub pipeline_cache_uuid: [u8; vk_sys::VK_UUID_SIZE],
+}
+
+/// Header written by drivers at the start of pipeline cache data.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PipelineCacheHeader {
+    pub header_size: u32,
+    pub header_version: vk_sys::VkPipelineCacheHeaderVersion,
+    pub vendor_id: u32,
+    pub device_id: u32,
+    pub pipeline_cache_uuid: [u8; vk_sys::VK_UUID_SIZE],
+}
+
+impl PipelineCacheHeader {
+    /// Parses the header at the start of pipeline cache data.
+    pub fn parse(data: &[u8]) -> Option<PipelineCacheHeader> {
+        if data.len() < HEADER_SIZE {
+            return None;
+        }
+        let read = |offset: usize| u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap());
+        let header = PipelineCacheHeader {
+            header_size: read(0),
+            header_version: read(4) as vk_sys::VkPipelineCacheHeaderVersion,
+            vendor_id: read(8),
+            device_id: read(12),
+            pipeline_cache_uuid: data[16..HEADER_SIZE].try_into().unwrap(),
+        };
+        if (header.header_size as usize) < HEADER_SIZE
+            || header.header_size as usize > data.len()
+            || header.header_version != vk_sys::VK_PIPELINE_CACHE_HEADER_VERSION_ONE
+        {
+            return None;
+        }
+        Some(header)
+    }
+
+    /// Returns `true` if the cache was produced by the given device and driver.
+    pub fn is_compatible(&self, device: &DeviceIdentity) -> bool {
+        self.vendor_id == device.vendor_id
+            && self.device_id == device.device_id
+            && self.pipeline_cache_uuid == device.pipeline_cache_uuid
+    }
+}
+
+/// Pipeline cache data stored in a file.
+pub struct PipelineCacheFile {
+    path: PathBuf,
+    device: DeviceIdentity,
+}
+
+impl PipelineCacheFile {
+    /// Creates a pipeline cache file bound to a device.
+    pub fn new<P: Into<PathBuf>>(path: P, device: DeviceIdentity) -> PipelineCacheFile {
+        PipelineCacheFile {
+            path: path.into(),
+            device,
+        }
+    }
+
+    /// Returns the path of the file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Loads the cache data, to be passed as `pInitialData` of `vkCreatePipelineCache`.
+    ///
+    /// Data produced by another device or driver version is rejected.
+    pub fn load(&self) -> Result<Vec<u8>, PipelineCacheError> {
+        let data = fs::read(&self.path)?;
+        self.validate(&data)?;
+        Ok(data)
+    }
+
+    /// Saves the cache data returned by `vkGetPipelineCacheData`.
+    ///
+    /// Data is written to a temporary file first, so an interrupted save never leaves a
+    /// truncated cache behind.
+    pub fn save(&self, data: &[u8]) -> Result<(), PipelineCacheError> {
+        self.validate(data)?;
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut temporary = self.path.clone().into_os_string();
+        temporary.push(".tmp");
+        fs::write(&temporary, data)?;
+        fs::rename(&temporary, &self.path)?;
+        Ok(())
+    }
+
+    /// Checks that the data starts with a header matching the device.
+    fn validate(&self, data: &[u8]) -> Result<(), PipelineCacheError> {
+        let header = PipelineCacheHeader::parse(data).ok_or(PipelineCacheError::InvalidHeader)?;
+        if header.is_compatible(&self.device) {
+            Ok(())
+        } else {
+            Err(PipelineCacheError::IncompatibleDevice)
+        }
+    }
+}
@@ -0,0 +1,724 @@
+//! Declarative pipeline descriptions and runtime deduplication.
+
+mod cache;
+
+pub use cache::*;
+
+use crate::{descriptor::DescriptorSetLayoutDescription, hash::FnvHasher, target::SampleCount};
+use blazar_vk_sys as vk_sys;
+use std::{
+    collections::HashMap,
+    hash::{Hash, Hasher},
+};
+
+/// Shader stages.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ShaderStage {
+    Vertex = vk_sys::VK_SHADER_STAGE_VERTEX_BIT,
+    TessellationControl = vk_sys::VK_SHADER_STAGE_TESSELLATION_CONTROL_BIT,
+    TessellationEvaluation = vk_sys::VK_SHADER_STAGE_TESSELLATION_EVALUATION_BIT,
+    Geometry = vk_sys::VK_SHADER_STAGE_GEOMETRY_BIT,
+    Fragment = vk_sys::VK_SHADER_STAGE_FRAGMENT_BIT,
+    Compute = vk_sys::VK_SHADER_STAGE_COMPUTE_BIT,
+}
+
+/// Mask of shader stages, made of `ShaderStage` values.
+pub type ShaderStageFlags = vk_sys::VkShaderStageFlags;
+
+/// Represents a SPIR-V shader bound to a stage.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Shader {
+    pub stage: ShaderStage,
+    pub code: Vec<u32>,
+    pub entry_point: String,
+}
+
+impl Shader {
+    /// Creates a shader using the `main` entry point.
+    pub fn new(stage: ShaderStage, code: Vec<u32>) -> Shader {
+        Shader {
+            stage,
+            code,
+            entry_point: String::from("main"),
+        }
+    }
+}
+
+/// Vertex attribute formats.
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum VertexFormat {
+    Float = vk_sys::VK_FORMAT_R32_SFLOAT,
+    Float2 = vk_sys::VK_FORMAT_R32G32_SFLOAT,
+    Float3 = vk_sys::VK_FORMAT_R32G32B32_SFLOAT,
+    Float4 = vk_sys::VK_FORMAT_R32G32B32A32_SFLOAT,
+    Int = vk_sys::VK_FORMAT_R32_SINT,
+    Int2 = vk_sys::VK_FORMAT_R32G32_SINT,
+    Int3 = vk_sys::VK_FORMAT_R32G32B32_SINT,
+    Int4 = vk_sys::VK_FORMAT_R32G32B32A32_SINT,
+    UInt = vk_sys::VK_FORMAT_R32_UINT,
+    UInt2 = vk_sys::VK_FORMAT_R32G32_UINT,
+    UInt3 = vk_sys::VK_FORMAT_R32G32B32_UINT,
+    UInt4 = vk_sys::VK_FORMAT_R32G32B32A32_UINT,
+    UByte4 = vk_sys::VK_FORMAT_R8G8B8A8_UINT,
+    UByte4Norm = vk_sys::VK_FORMAT_R8G8B8A8_UNORM,
+    Half2 = vk_sys::VK_FORMAT_R16G16_SFLOAT,
+    Half4 = vk_sys::VK_FORMAT_R16G16B16A16_SFLOAT,
+}
+
+impl VertexFormat {
+    /// Returns the size in bytes of an attribute of this format.
+    pub fn size(self) -> u32 {
+        match self {
+            VertexFormat::Float | VertexFormat::Int | VertexFormat::UInt => 4,
+            VertexFormat::UByte4 | VertexFormat::UByte4Norm | VertexFormat::Half2 => 4,
+            VertexFormat::Float2 | VertexFormat::Int2 | VertexFormat::UInt2 => 8,
+            VertexFormat::Half4 => 8,
+            VertexFormat::Float3 | VertexFormat::Int3 | VertexFormat::UInt3 => 12,
+            VertexFormat::Float4 | VertexFormat::Int4 | VertexFormat::UInt4 => 16,
+        }
+    }
+}
+
+/// Rates at which vertex attributes are pulled from buffers.
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum VertexInputRate {
+    Vertex = vk_sys::VK_VERTEX_INPUT_RATE_VERTEX,
+    Instance = vk_sys::VK_VERTEX_INPUT_RATE_INSTANCE,
+}
+
+/// Describes a vertex buffer binding.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct VertexBinding {
+    pub binding: u32,
+    pub stride: u32,
+    pub input_rate: VertexInputRate,
+}
+
+/// Describes a vertex attribute.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct VertexAttribute {
+    pub location: u32,
+    pub binding: u32,
+    pub format: VertexFormat,
+    pub offset: u32,
+}
+
+/// Describes how vertices are laid out in vertex buffers.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct VertexLayout {
+    pub bindings: Vec<VertexBinding>,
+    pub attributes: Vec<VertexAttribute>,
+}
+
+impl VertexLayout {
+    /// Creates an empty layout, for pipelines generating their vertices in shaders.
+    pub fn new() -> VertexLayout {
+        VertexLayout::default()
+    }
+
+    /// Appends a binding whose attributes are tightly packed in the given order.
+    ///
+    /// Attribute locations continue from the last declared attribute.
+    pub fn interleaved(
+        mut self,
+        input_rate: VertexInputRate,
+        formats: &[VertexFormat],
+    ) -> VertexLayout {
+        let binding = self.bindings.len() as u32;
+        let first_location = self
+            .attributes
+            .iter()
+            .map(|attribute| attribute.location + 1)
+            .max()
+            .unwrap_or(0);
+        let mut offset = 0;
+        for (index, format) in formats.iter().enumerate() {
+            self.attributes.push(VertexAttribute {
+                location: first_location + index as u32,
+                binding,
+                format: *format,
+                offset,
+            });
+            offset += format.size();
+        }
+        self.bindings.push(VertexBinding {
+            binding,
+            stride: offset,
+            input_rate,
+        });
+        self
+    }
+}
+
+/// Primitive topologies.
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum PrimitiveTopology {
+    PointList = vk_sys::VK_PRIMITIVE_TOPOLOGY_POINT_LIST,
+    LineList = vk_sys::VK_PRIMITIVE_TOPOLOGY_LINE_LIST,
+    LineStrip = vk_sys::VK_PRIMITIVE_TOPOLOGY_LINE_STRIP,
+    TriangleList = vk_sys::VK_PRIMITIVE_TOPOLOGY_TRIANGLE_LIST,
+    TriangleStrip = vk_sys::VK_PRIMITIVE_TOPOLOGY_TRIANGLE_STRIP,
+    TriangleFan = vk_sys::VK_PRIMITIVE_TOPOLOGY_TRIANGLE_FAN,
+}
+
+/// Polygon rasterization modes.
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum PolygonMode {
+    Fill = vk_sys::VK_POLYGON_MODE_FILL,
+    Line = vk_sys::VK_POLYGON_MODE_LINE,
+    Point = vk_sys::VK_POLYGON_MODE_POINT,
+}
+
+/// Triangle facing-direction culling modes.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum CullMode {
+    None = vk_sys::VK_CULL_MODE_NONE,
+    Front = vk_sys::VK_CULL_MODE_FRONT_BIT,
+    Back = vk_sys::VK_CULL_MODE_BACK_BIT,
+    FrontAndBack = vk_sys::VK_CULL_MODE_FRONT_AND_BACK,
+}
+
+/// Winding orders of front-facing triangles.
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum FrontFace {
+    CounterClockwise = vk_sys::VK_FRONT_FACE_COUNTER_CLOCKWISE,
+    Clockwise = vk_sys::VK_FRONT_FACE_CLOCKWISE,
+}
+
+/// Describes the depth bias applied to fragments.
+///
+/// Factors are compared and hashed by their bits, so that `0.0` and `-0.0` describe
+/// distinct pipelines and NaN equals itself.
+#[derive(Clone, Copy, Debug)]
+pub struct DepthBias {
+    pub constant_factor: f32,
+    pub clamp: f32,
+    pub slope_factor: f32,
+}
+
+impl PartialEq for DepthBias {
+    fn eq(&self, other: &DepthBias) -> bool {
+        self.constant_factor.to_bits() == other.constant_factor.to_bits()
+            && self.clamp.to_bits() == other.clamp.to_bits()
+            && self.slope_factor.to_bits() == other.slope_factor.to_bits()
+    }
+}
+
+impl Eq for DepthBias {}
+
+impl Hash for DepthBias {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.constant_factor.to_bits().hash(state);
+        self.clamp.to_bits().hash(state);
+        self.slope_factor.to_bits().hash(state);
+    }
+}
+
+/// Describes the rasterization state.
+///
+/// As with `DepthBias`, the line width is compared and hashed by its bits.
+#[derive(Clone, Copy, Debug)]
+pub struct RasterState {
+    pub polygon_mode: PolygonMode,
+    pub cull_mode: CullMode,
+    pub front_face: FrontFace,
+    pub depth_bias: Option<DepthBias>,
+    pub depth_clamp: bool,
+    pub line_width: f32,
+}
+
+impl Default for RasterState {
+    fn default() -> RasterState {
+        RasterState {
+            polygon_mode: PolygonMode::Fill,
+            cull_mode: CullMode::None,
+            front_face: FrontFace::CounterClockwise,
+            depth_bias: None,
+            depth_clamp: false,
+            line_width: 1.0,
+        }
+    }
+}
+
+impl PartialEq for RasterState {
+    fn eq(&self, other: &RasterState) -> bool {
+        self.polygon_mode == other.polygon_mode
+            && self.cull_mode == other.cull_mode
+            && self.front_face == other.front_face
+            && self.depth_bias == other.depth_bias
+            && self.depth_clamp == other.depth_clamp
+            && self.line_width.to_bits() == other.line_width.to_bits()
+    }
+}
+
+impl Eq for RasterState {}
+
+impl Hash for RasterState {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.polygon_mode.hash(state);
+        self.cull_mode.hash(state);
+        self.front_face.hash(state);
+        self.depth_bias.hash(state);
+        self.depth_clamp.hash(state);
+        self.line_width.to_bits().hash(state);
+    }
+}
+
+/// Comparison operators used by depth tests.
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum CompareOp {
+    Never = vk_sys::VK_COMPARE_OP_NEVER,
+    Less = vk_sys::VK_COMPARE_OP_LESS,
+    Equal = vk_sys::VK_COMPARE_OP_EQUAL,
+    LessOrEqual = vk_sys::VK_COMPARE_OP_LESS_OR_EQUAL,
+    Greater = vk_sys::VK_COMPARE_OP_GREATER,
+    NotEqual = vk_sys::VK_COMPARE_OP_NOT_EQUAL,
+    GreaterOrEqual = vk_sys::VK_COMPARE_OP_GREATER_OR_EQUAL,
+    Always = vk_sys::VK_COMPARE_OP_ALWAYS,
+}
+
+/// Describes the depth test.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct DepthState {
+    pub test: bool,
+    pub write: bool,
+    pub compare_op: CompareOp,
+}
+
+impl DepthState {
+    /// Disables depth testing and writing.
+    pub const DISABLED: DepthState = DepthState {
+        test: false,
+        write: false,
+        compare_op: CompareOp::Always,
+    };
+
+    /// Tests and writes depth, keeping the nearest fragments.
+    pub const LESS: DepthState = DepthState {
+        test: true,
+        write: true,
+        compare_op: CompareOp::Less,
+    };
+
+    /// Tests depth without writing it, typically for transparent geometry.
+    pub const READ_ONLY: DepthState = DepthState {
+        test: true,
+        write: false,
+        compare_op: CompareOp::LessOrEqual,
+    };
+}
+
+impl Default for DepthState {
+    fn default() -> DepthState {
+        DepthState::DISABLED
+    }
+}
+
+/// Blend factors.
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum BlendFactor {
+    Zero = vk_sys::VK_BLEND_FACTOR_ZERO,
+    One = vk_sys::VK_BLEND_FACTOR_ONE,
+    SrcColor = vk_sys::VK_BLEND_FACTOR_SRC_COLOR,
+    OneMinusSrcColor = vk_sys::VK_BLEND_FACTOR_ONE_MINUS_SRC_COLOR,
+    DstColor = vk_sys::VK_BLEND_FACTOR_DST_COLOR,
+    OneMinusDstColor = vk_sys::VK_BLEND_FACTOR_ONE_MINUS_DST_COLOR,
+    SrcAlpha = vk_sys::VK_BLEND_FACTOR_SRC_ALPHA,
+    OneMinusSrcAlpha = vk_sys::VK_BLEND_FACTOR_ONE_MINUS_SRC_ALPHA,
+    DstAlpha = vk_sys::VK_BLEND_FACTOR_DST_ALPHA,
+    OneMinusDstAlpha = vk_sys::VK_BLEND_FACTOR_ONE_MINUS_DST_ALPHA,
+    ConstantColor = vk_sys::VK_BLEND_FACTOR_CONSTANT_COLOR,
+    OneMinusConstantColor = vk_sys::VK_BLEND_FACTOR_ONE_MINUS_CONSTANT_COLOR,
+    ConstantAlpha = vk_sys::VK_BLEND_FACTOR_CONSTANT_ALPHA,
+    OneMinusConstantAlpha = vk_sys::VK_BLEND_FACTOR_ONE_MINUS_CONSTANT_ALPHA,
+    SrcAlphaSaturate = vk_sys::VK_BLEND_FACTOR_SRC_ALPHA_SATURATE,
+}
+
+/// Blend operations.
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum BlendOp {
+    Add = vk_sys::VK_BLEND_OP_ADD,
+    Subtract = vk_sys::VK_BLEND_OP_SUBTRACT,
+    ReverseSubtract = vk_sys::VK_BLEND_OP_REVERSE_SUBTRACT,
+    Min = vk_sys::VK_BLEND_OP_MIN,
+    Max = vk_sys::VK_BLEND_OP_MAX,
+}
+
+/// Describes how source and destination values of a component are combined.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct BlendComponent {
+    pub src_factor: BlendFactor,
+    pub dst_factor: BlendFactor,
+    pub op: BlendOp,
+}
+
+impl BlendComponent {
+    /// Overwrites the destination with the source.
+    pub const REPLACE: BlendComponent = BlendComponent {
+        src_factor: BlendFactor::One,
+        dst_factor: BlendFactor::Zero,
+        op: BlendOp::Add,
+    };
+}
+
+/// Mask of the color components written to an attachment.
+pub type ColorWriteMask = vk_sys::VkColorComponentFlags;
+
+/// Writes all color components.
+pub const COLOR_WRITE_ALL: ColorWriteMask = vk_sys::VK_COLOR_COMPONENT_R_BIT
+    | vk_sys::VK_COLOR_COMPONENT_G_BIT
+    | vk_sys::VK_COLOR_COMPONENT_B_BIT
+    | vk_sys::VK_COLOR_COMPONENT_A_BIT;
+
+/// Describes the blending of a color attachment.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct BlendState {
+    pub enabled: bool,
+    pub color: BlendComponent,
+    pub alpha: BlendComponent,
+    pub write_mask: ColorWriteMask,
+}
+
+impl BlendState {
+    /// Disables blending.
+    pub const REPLACE: BlendState = BlendState {
+        enabled: false,
+        color: BlendComponent::REPLACE,
+        alpha: BlendComponent::REPLACE,
+        write_mask: COLOR_WRITE_ALL,
+    };
+
+    /// Blends with straight (non-premultiplied) alpha.
+    pub const ALPHA: BlendState = BlendState {
+        enabled: true,
+        color: BlendComponent {
+            src_factor: BlendFactor::SrcAlpha,
+            dst_factor: BlendFactor::OneMinusSrcAlpha,
+            op: BlendOp::Add,
+        },
+        alpha: BlendComponent {
+            src_factor: BlendFactor::One,
+            dst_factor: BlendFactor::OneMinusSrcAlpha,
+            op: BlendOp::Add,
+        },
+        write_mask: COLOR_WRITE_ALL,
+    };
+
+    /// Blends with premultiplied alpha.
+    pub const PREMULTIPLIED_ALPHA: BlendState = BlendState {
+        enabled: true,
+        color: BlendComponent {
+            src_factor: BlendFactor::One,
+            dst_factor: BlendFactor::OneMinusSrcAlpha,
+            op: BlendOp::Add,
+        },
+        alpha: BlendComponent {
+            src_factor: BlendFactor::One,
+            dst_factor: BlendFactor::OneMinusSrcAlpha,
+            op: BlendOp::Add,
+        },
+        write_mask: COLOR_WRITE_ALL,
+    };
+
+    /// Adds the source to the destination.
+    pub const ADDITIVE: BlendState = BlendState {
+        enabled: true,
+        color: BlendComponent {
+            src_factor: BlendFactor::SrcAlpha,
+            dst_factor: BlendFactor::One,
+            op: BlendOp::Add,
+        },
+        alpha: BlendComponent {
+            src_factor: BlendFactor::One,
+            dst_factor: BlendFactor::One,
+            op: BlendOp::Add,
+        },
+        write_mask: COLOR_WRITE_ALL,
+    };
+}
+
+impl Default for BlendState {
+    fn default() -> BlendState {
+        BlendState::REPLACE
+    }
+}
+
+/// Pipeline states that can be changed while recording command buffers.
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum DynamicState {
+    Viewport = vk_sys::VK_DYNAMIC_STATE_VIEWPORT,
+    Scissor = vk_sys::VK_DYNAMIC_STATE_SCISSOR,
+    LineWidth = vk_sys::VK_DYNAMIC_STATE_LINE_WIDTH,
+    DepthBias = vk_sys::VK_DYNAMIC_STATE_DEPTH_BIAS,
+    BlendConstants = vk_sys::VK_DYNAMIC_STATE_BLEND_CONSTANTS,
+    DepthBounds = vk_sys::VK_DYNAMIC_STATE_DEPTH_BOUNDS,
+    StencilCompareMask = vk_sys::VK_DYNAMIC_STATE_STENCIL_COMPARE_MASK,
+    StencilWriteMask = vk_sys::VK_DYNAMIC_STATE_STENCIL_WRITE_MASK,
+    StencilReference = vk_sys::VK_DYNAMIC_STATE_STENCIL_REFERENCE,
+}
+
+/// Describes a range of push constants.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct PushConstantRange {
+    pub stages: ShaderStageFlags,
+    pub offset: u32,
+    pub size: u32,
+}
+
+/// Describes the resources accessed by a pipeline.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct PipelineLayoutDescription {
+    pub set_layouts: Vec<DescriptorSetLayoutDescription>,
+    pub push_constants: Vec<PushConstantRange>,
+}
+
+impl PipelineLayoutDescription {
+    /// Creates an empty layout.
+    pub fn new() -> PipelineLayoutDescription {
+        PipelineLayoutDescription::default()
+    }
+
+    /// Appends a descriptor set layout.
+    pub fn set_layout(
+        mut self,
+        set_layout: DescriptorSetLayoutDescription,
+    ) -> PipelineLayoutDescription {
+        self.set_layouts.push(set_layout);
+        self
+    }
+
+    /// Appends a push constant range.
+    pub fn push_constants(mut self, range: PushConstantRange) -> PipelineLayoutDescription {
+        self.push_constants.push(range);
+        self
+    }
+}
+
+/// Describes a graphics pipeline.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct GraphicsPipelineDescription {
+    pub shaders: Vec<Shader>,
+    pub layout: PipelineLayoutDescription,
+    pub vertex_layout: VertexLayout,
+    pub topology: PrimitiveTopology,
+    pub raster: RasterState,
+    pub depth: DepthState,
+    pub blend: Vec<BlendState>,
+    pub dynamic_states: Vec<DynamicState>,
+    pub samples: SampleCount,
+}
+
+impl GraphicsPipelineDescription {
+    /// Creates a description drawing triangle lists into a single color attachment, with
+    /// dynamic viewport and scissor.
+    pub fn new() -> GraphicsPipelineDescription {
+        GraphicsPipelineDescription {
+            shaders: Vec::new(),
+            layout: PipelineLayoutDescription::new(),
+            vertex_layout: VertexLayout::new(),
+            topology: PrimitiveTopology::TriangleList,
+            raster: RasterState::default(),
+            depth: DepthState::default(),
+            blend: vec![BlendState::default()],
+            dynamic_states: vec![DynamicState::Viewport, DynamicState::Scissor],
+            samples: SampleCount::X1,
+        }
+    }
+
+    /// Adds a shader stage.
+    pub fn shader(mut self, shader: Shader) -> GraphicsPipelineDescription {
+        self.shaders.push(shader);
+        self
+    }
+
+    /// Sets the pipeline layout.
+    pub fn layout(mut self, layout: PipelineLayoutDescription) -> GraphicsPipelineDescription {
+        self.layout = layout;
+        self
+    }
+
+    /// Sets the vertex layout.
+    pub fn vertex_layout(mut self, vertex_layout: VertexLayout) -> GraphicsPipelineDescription {
+        self.vertex_layout = vertex_layout;
+        self
+    }
+
+    /// Sets the primitive topology.
+    pub fn topology(mut self, topology: PrimitiveTopology) -> GraphicsPipelineDescription {
+        self.topology = topology;
+        self
+    }
+
+    /// Sets the rasterization state.
+    pub fn raster(mut self, raster: RasterState) -> GraphicsPipelineDescription {
+        self.raster = raster;
+        self
+    }
+
+    /// Sets the depth state.
+    pub fn depth(mut self, depth: DepthState) -> GraphicsPipelineDescription {
+        self.depth = depth;
+        self
+    }
+
+    /// Sets the blend state of every color attachment.
+    pub fn blend(mut self, blend: &[BlendState]) -> GraphicsPipelineDescription {
+        self.blend = blend.to_vec();
+        self
+    }
+
+    /// Sets the dynamic states.
+    pub fn dynamic_states(
+        mut self,
+        dynamic_states: &[DynamicState],
+    ) -> GraphicsPipelineDescription {
+        self.dynamic_states = dynamic_states.to_vec();
+        self
+    }
+
+    /// Sets the sample count, matching the render target the pipeline draws into.
+    pub fn samples(mut self, samples: SampleCount) -> GraphicsPipelineDescription {
+        self.samples = samples;
+        self
+    }
+}
+
+impl Default for GraphicsPipelineDescription {
+    fn default() -> GraphicsPipelineDescription {
+        GraphicsPipelineDescription::new()
+    }
+}
+
+/// Describes a compute pipeline.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ComputePipelineDescription {
+    pub shader: Shader,
+    pub layout: PipelineLayoutDescription,
+}
+
+impl ComputePipelineDescription {
+    /// Creates a description from a compute shader.
+    pub fn new(shader: Shader) -> ComputePipelineDescription {
+        ComputePipelineDescription {
+            shader,
+            layout: PipelineLayoutDescription::new(),
+        }
+    }
+
+    /// Sets the pipeline layout.
+    pub fn layout(mut self, layout: PipelineLayoutDescription) -> ComputePipelineDescription {
+        self.layout = layout;
+        self
+    }
+}
+
+/// Describes any kind of pipeline.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum PipelineDescription {
+    Graphics(GraphicsPipelineDescription),
+    Compute(ComputePipelineDescription),
+}
+
+impl PipelineDescription {
+    /// Returns the key identifying this description.
+    pub fn key(&self) -> PipelineKey {
+        let mut hasher = FnvHasher::default();
+        self.hash(&mut hasher);
+        PipelineKey(hasher.finish())
+    }
+}
+
+impl From<GraphicsPipelineDescription> for PipelineDescription {
+    fn from(description: GraphicsPipelineDescription) -> PipelineDescription {
+        PipelineDescription::Graphics(description)
+    }
+}
+
+impl From<ComputePipelineDescription> for PipelineDescription {
+    fn from(description: ComputePipelineDescription) -> PipelineDescription {
+        PipelineDescription::Compute(description)
+    }
+}
+
+/// Hash of a pipeline description, stable across runs.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct PipelineKey(pub u64);
+
+/// Set of pipelines deduplicated by description.
+///
+/// Pipelines are only created the first time their description is requested.
+pub struct PipelineSet<P> {
+    pipelines: HashMap<PipelineKey, Vec<(PipelineDescription, P)>>,
+}
+
+impl<P> PipelineSet<P> {
+    /// Creates an empty set.
+    pub fn new() -> PipelineSet<P> {
+        PipelineSet {
+            pipelines: HashMap::new(),
+        }
+    }
+
+    /// Returns the pipeline matching the description, if any.
+    pub fn get(&self, description: &PipelineDescription) -> Option<&P> {
+        self.pipelines
+            .get(&description.key())
+            .and_then(|bucket| bucket.iter().find(|(other, _)| other == description))
+            .map(|(_, pipeline)| pipeline)
+    }
+
+    /// Returns the pipeline matching the description, creating it with `create` if needed.
+    pub fn get_or_create<E, F>(
+        &mut self,
+        description: &PipelineDescription,
+        create: F,
+    ) -> Result<&P, E>
+    where
+        F: FnOnce(&PipelineDescription) -> Result<P, E>,
+    {
+        let bucket = self.pipelines.entry(description.key()).or_default();
+        let index = match bucket.iter().position(|(other, _)| other == description) {
+            Some(index) => index,
+            None => {
+                let pipeline = create(description)?;
+                bucket.push((description.clone(), pipeline));
+                bucket.len() - 1
+            }
+        };
+        Ok(&bucket[index].1)
+    }
+
+    /// Returns the number of pipelines.
+    pub fn len(&self) -> usize {
+        self.pipelines.values().map(Vec::len).sum()
+    }
+
+    /// Returns `true` if the set contains no pipelines.
+    pub fn is_empty(&self) -> bool {
+        self.pipelines.values().all(Vec::is_empty)
+    }
+
+    /// Removes all pipelines, returning them so they can be destroyed.
+    pub fn drain(&mut self) -> impl Iterator<Item = P> + '_ {
+        self.pipelines
+            .drain()
+            .flat_map(|(_, bucket)| bucket.into_iter().map(|(_, pipeline)| pipeline))
+    }
+}
+
+impl<P> Default for PipelineSet<P> {
+    fn default() -> PipelineSet<P> {
+        PipelineSet::new()
+    }
+}
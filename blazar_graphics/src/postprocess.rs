@@ -0,0 +1,379 @@
+//! Post-processing chain.
+//!
+//! Effects are applied in order to the rendered scene, as full-screen passes each
+//! sampling the output of the previous one. Intermediate images are render targets sized
+//! after the swapchain, rebuilt by `RenderTargets` when the window is resized; the last
+//! pass writes into the swapchain image. Passes draw a single triangle with
+//! `shaders/fullscreen.vert`, the matching fragment shaders being `shaders/post_*.frag`.
+
+use crate::{
+    descriptor::{DescriptorBinding, DescriptorSetLayoutDescription, DescriptorType},
+    pipeline::{
+        BlendState, DepthState, GraphicsPipelineDescription, PipelineLayoutDescription,
+        PushConstantRange, Shader, ShaderStage,
+    },
+    target::{RenderTargetDescription, RenderTargetError, RenderTargets, TargetId, TargetSize},
+    texture::TextureFormat,
+};
+
+/// Maximum number of downsampled images used by bloom.
+pub const MAX_BLOOM_LEVELS: u32 = 6;
+
+/// Operators mapping HDR colors to the displayable range.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ToneMapOperator {
+    Reinhard,
+    Aces,
+}
+
+/// Post-processing effects.
+///
+/// `T` identifies the textures sampled by effects.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PostEffect<T> {
+    /// Scales colors by `exposure`, then maps them to the displayable range.
+    ToneMapping {
+        operator: ToneMapOperator,
+        exposure: f32,
+    },
+    /// Gamma correction, typically `2.2`, for swapchains without an sRGB format.
+    Gamma(f32),
+    /// Blurs colors brighter than `threshold` over `levels` downsampled images, then
+    /// adds them scaled by `intensity`.
+    Bloom {
+        threshold: f32,
+        intensity: f32,
+        levels: u32,
+    },
+    /// Fast approximate anti-aliasing, expecting colors in the displayable range.
+    Fxaa,
+    /// Remaps colors through a lookup table, blended with the original colors by
+    /// `strength`.
+    ///
+    /// The table is a 2D texture of `size * size` by `size` texels, made of `size`
+    /// horizontal slices of increasing blue, red increasing horizontally and green
+    /// vertically in each slice.
+    ColorGrading { lut: T, strength: f32 },
+    /// Darkens the borders, from `radius` to `radius + smoothness` relative to the half
+    /// diagonal, by up to `intensity`.
+    Vignette {
+        intensity: f32,
+        radius: f32,
+        smoothness: f32,
+    },
+}
+
+/// Fragment shaders of post-processing passes.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum PostShader {
+    Copy,
+    ToneMapping,
+    Gamma,
+    BloomPrefilter,
+    BloomDownsample,
+    BloomUpsample,
+    BloomComposite,
+    Fxaa,
+    ColorGrading,
+    Vignette,
+}
+
+impl PostShader {
+    /// Returns the name of the shader, its source being `shaders/<name>.frag`.
+    pub fn name(self) -> &'static str {
+        match self {
+            PostShader::Copy => "post_copy",
+            PostShader::ToneMapping => "post_tone_mapping",
+            PostShader::Gamma => "post_gamma",
+            PostShader::BloomPrefilter => "post_bloom_prefilter",
+            PostShader::BloomDownsample => "post_bloom_downsample",
+            PostShader::BloomUpsample => "post_bloom_upsample",
+            PostShader::BloomComposite => "post_bloom_composite",
+            PostShader::Fxaa => "post_fxaa",
+            PostShader::ColorGrading => "post_color_grading",
+            PostShader::Vignette => "post_vignette",
+        }
+    }
+
+    /// Returns the number of images sampled by the shader.
+    pub fn input_count(self) -> u32 {
+        match self {
+            PostShader::BloomComposite | PostShader::ColorGrading => 2,
+            _ => 1,
+        }
+    }
+
+    /// Returns `true` if the output of the shader is added to the image it writes into.
+    pub fn is_additive(self) -> bool {
+        self == PostShader::BloomUpsample
+    }
+}
+
+/// Images sampled by post-processing passes.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum PassImage<T> {
+    /// Single-sampled color image of a render target: its first resolve attachment if
+    /// multisampled, or its first color attachment otherwise.
+    Target(TargetId),
+    Texture(T),
+}
+
+/// Images written by post-processing passes.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum PassOutput {
+    Target(TargetId),
+    Swapchain,
+}
+
+/// Full-screen pass drawing a triangle (`vkCmdDraw` of 3 vertices).
+#[derive(Clone, Debug, PartialEq)]
+pub struct PostPass<T> {
+    pub shader: PostShader,
+    /// Images bound in order to the set described by `PostProcessChain::set_layout`.
+    pub inputs: Vec<PassImage<T>>,
+    pub output: PassOutput,
+    /// Push constants of the fragment shader.
+    pub parameters: [f32; 4],
+}
+
+/// Ordered list of post-processing effects and their intermediate targets.
+pub struct PostProcessChain<T> {
+    effects: Vec<(PostEffect<T>, bool)>,
+    intermediate: [TargetId; 2],
+    bloom: Vec<TargetId>,
+}
+
+impl<T: Copy> PostProcessChain<T> {
+    /// Size in bytes of the push constants of the fragment shaders.
+    pub const PUSH_CONSTANTS_SIZE: u32 = 16;
+
+    /// Creates an empty chain, adding its intermediate targets of `format`, typically
+    /// `Rgba16Float` to keep HDR colors, to `targets`.
+    pub fn new<R>(
+        targets: &mut RenderTargets<R>,
+        format: TextureFormat,
+    ) -> Result<PostProcessChain<T>, RenderTargetError> {
+        let mut add = |divisor| {
+            let description = RenderTargetDescription::new(TargetSize::Swapchain(divisor));
+            targets.add(description.color(format))
+        };
+        let intermediate = [add(1)?, add(1)?];
+        let bloom = (1..=MAX_BLOOM_LEVELS)
+            .map(|level| add(1 << level))
+            .collect::<Result<_, _>>()?;
+        Ok(PostProcessChain {
+            effects: Vec::new(),
+            intermediate,
+            bloom,
+        })
+    }
+
+    /// Appends an effect, enabled.
+    pub fn push(&mut self, effect: PostEffect<T>) {
+        self.effects.push((effect, true));
+    }
+
+    /// Inserts an effect, enabled, at `index`.
+    pub fn insert(&mut self, index: usize, effect: PostEffect<T>) {
+        self.effects.insert(index, (effect, true));
+    }
+
+    /// Removes the effect at `index`.
+    pub fn remove(&mut self, index: usize) -> PostEffect<T> {
+        self.effects.remove(index).0
+    }
+
+    /// Removes all effects.
+    pub fn clear(&mut self) {
+        self.effects.clear();
+    }
+
+    /// Returns the number of effects.
+    pub fn len(&self) -> usize {
+        self.effects.len()
+    }
+
+    /// Returns `true` if the chain has no effects.
+    pub fn is_empty(&self) -> bool {
+        self.effects.is_empty()
+    }
+
+    /// Returns the effect at `index`.
+    pub fn effect(&self, index: usize) -> Option<&PostEffect<T>> {
+        self.effects.get(index).map(|(effect, _)| effect)
+    }
+
+    /// Returns the effect at `index` mutably, typically to tweak its parameters.
+    pub fn effect_mut(&mut self, index: usize) -> Option<&mut PostEffect<T>> {
+        self.effects.get_mut(index).map(|(effect, _)| effect)
+    }
+
+    /// Returns `true` if the effect at `index` is applied.
+    pub fn is_enabled(&self, index: usize) -> bool {
+        self.effects
+            .get(index)
+            .map_or(false, |(_, enabled)| *enabled)
+    }
+
+    /// Enables or disables the effect at `index`.
+    pub fn set_enabled(&mut self, index: usize, enabled: bool) {
+        if let Some(effect) = self.effects.get_mut(index) {
+            effect.1 = enabled;
+        }
+    }
+
+    /// Returns the passes applying the enabled effects to `scene`, in order.
+    ///
+    /// Without enabled effects, the scene is copied to the swapchain image.
+    pub fn passes(&self, scene: PassImage<T>) -> Vec<PostPass<T>> {
+        let effects: Vec<&PostEffect<T>> = self
+            .effects
+            .iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(effect, _)| effect)
+            .collect();
+        let mut passes = Vec::new();
+        if effects.is_empty() {
+            passes.push(PostPass {
+                shader: PostShader::Copy,
+                inputs: vec![scene],
+                output: PassOutput::Swapchain,
+                parameters: [0.0; 4],
+            });
+            return passes;
+        }
+        let mut input = scene;
+        for (index, effect) in effects.iter().enumerate() {
+            let output = if index + 1 == effects.len() {
+                PassOutput::Swapchain
+            } else {
+                // Alternate between the intermediate targets, never writing the input.
+                PassOutput::Target(self.intermediate[index % 2])
+            };
+            let (shader, inputs, parameters) = match **effect {
+                PostEffect::ToneMapping { operator, exposure } => {
+                    let operator = match operator {
+                        ToneMapOperator::Reinhard => 0.0,
+                        ToneMapOperator::Aces => 1.0,
+                    };
+                    (
+                        PostShader::ToneMapping,
+                        vec![input],
+                        [exposure, operator, 0.0, 0.0],
+                    )
+                }
+                PostEffect::Gamma(gamma) => {
+                    (PostShader::Gamma, vec![input], [gamma, 0.0, 0.0, 0.0])
+                }
+                PostEffect::Bloom {
+                    threshold,
+                    intensity,
+                    levels,
+                } => {
+                    self.bloom_passes(input, threshold, levels, &mut passes);
+                    (
+                        PostShader::BloomComposite,
+                        vec![input, PassImage::Target(self.bloom[0])],
+                        [intensity, 0.0, 0.0, 0.0],
+                    )
+                }
+                PostEffect::Fxaa => (PostShader::Fxaa, vec![input], [0.0; 4]),
+                PostEffect::ColorGrading { lut, strength } => (
+                    PostShader::ColorGrading,
+                    vec![input, PassImage::Texture(lut)],
+                    [strength, 0.0, 0.0, 0.0],
+                ),
+                PostEffect::Vignette {
+                    intensity,
+                    radius,
+                    smoothness,
+                } => (
+                    PostShader::Vignette,
+                    vec![input],
+                    [intensity, radius, smoothness, 0.0],
+                ),
+            };
+            passes.push(PostPass {
+                shader,
+                inputs,
+                output,
+                parameters,
+            });
+            if let PassOutput::Target(target) = output {
+                input = PassImage::Target(target);
+            }
+        }
+        passes
+    }
+
+    /// Adds the passes blurring the bright colors of `input` into the first bloom target.
+    fn bloom_passes(
+        &self,
+        input: PassImage<T>,
+        threshold: f32,
+        levels: u32,
+        passes: &mut Vec<PostPass<T>>,
+    ) {
+        let levels = levels.max(1).min(MAX_BLOOM_LEVELS) as usize;
+        passes.push(PostPass {
+            shader: PostShader::BloomPrefilter,
+            inputs: vec![input],
+            output: PassOutput::Target(self.bloom[0]),
+            parameters: [threshold, 0.0, 0.0, 0.0],
+        });
+        for level in 1..levels {
+            passes.push(PostPass {
+                shader: PostShader::BloomDownsample,
+                inputs: vec![PassImage::Target(self.bloom[level - 1])],
+                output: PassOutput::Target(self.bloom[level]),
+                parameters: [0.0; 4],
+            });
+        }
+        for level in (1..levels).rev() {
+            passes.push(PostPass {
+                shader: PostShader::BloomUpsample,
+                inputs: vec![PassImage::Target(self.bloom[level])],
+                output: PassOutput::Target(self.bloom[level - 1]),
+                parameters: [0.0; 4],
+            });
+        }
+    }
+
+    /// Returns the layout of the descriptor set binding the images sampled by a shader.
+    pub fn set_layout(shader: PostShader) -> DescriptorSetLayoutDescription {
+        (0..shader.input_count()).fold(DescriptorSetLayoutDescription::new(), |layout, binding| {
+            layout.binding(DescriptorBinding::new(
+                binding,
+                DescriptorType::CombinedImageSampler,
+                ShaderStage::Fragment as u32,
+            ))
+        })
+    }
+
+    /// Describes the pipeline of a pass, without depth testing, given
+    /// `shaders/fullscreen.vert` and the fragment shader of `shader`.
+    pub fn pipeline_description(
+        shader: PostShader,
+        vertex_shader: Shader,
+        fragment_shader: Shader,
+    ) -> GraphicsPipelineDescription {
+        GraphicsPipelineDescription::new()
+            .shader(vertex_shader)
+            .shader(fragment_shader)
+            .layout(
+                PipelineLayoutDescription::new()
+                    .set_layout(Self::set_layout(shader))
+                    .push_constants(PushConstantRange {
+                        stages: ShaderStage::Fragment as u32,
+                        offset: 0,
+                        size: Self::PUSH_CONSTANTS_SIZE,
+                    }),
+            )
+            .depth(DepthState::DISABLED)
+            .blend(&[if shader.is_additive() {
+                BlendState::ADDITIVE
+            } else {
+                BlendState::REPLACE
+            }])
+    }
+}
@@ -0,0 +1,237 @@
+//! Presentation modes and frame pacing.
+//!
+//! The present mode of a swapchain is fixed at creation. Changing it at runtime marks the
+//! swapchain for recreation, the mode actually used falling back to a supported one.
+//! Without vertical synchronization, the frame rate can be capped on the CPU.
+
+use blazar_vk_sys as vk_sys;
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Presentation modes.
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum PresentMode {
+    /// Presents immediately, possibly tearing.
+    Immediate = vk_sys::VK_PRESENT_MODE_IMMEDIATE_KHR,
+    /// Waits for the vertical blank, replacing the queued image by newer ones.
+    Mailbox = vk_sys::VK_PRESENT_MODE_MAILBOX_KHR,
+    /// Waits for the vertical blank, always supported.
+    Fifo = vk_sys::VK_PRESENT_MODE_FIFO_KHR,
+    /// Waits for the vertical blank unless the image is late, possibly tearing.
+    FifoRelaxed = vk_sys::VK_PRESENT_MODE_FIFO_RELAXED_KHR,
+}
+
+impl PresentMode {
+    /// Returns the mode matching a `VkPresentModeKHR`, if any.
+    pub fn from_raw(mode: vk_sys::VkPresentModeKHR) -> Option<PresentMode> {
+        match mode {
+            vk_sys::VK_PRESENT_MODE_IMMEDIATE_KHR => Some(PresentMode::Immediate),
+            vk_sys::VK_PRESENT_MODE_MAILBOX_KHR => Some(PresentMode::Mailbox),
+            vk_sys::VK_PRESENT_MODE_FIFO_KHR => Some(PresentMode::Fifo),
+            vk_sys::VK_PRESENT_MODE_FIFO_RELAXED_KHR => Some(PresentMode::FifoRelaxed),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if presentation is synchronized with the vertical blank.
+    pub fn is_vsync(self) -> bool {
+        matches!(self, PresentMode::Fifo | PresentMode::FifoRelaxed)
+    }
+
+    /// Returns the modes tried in order when this one is requested, ending with `Fifo`.
+    ///
+    /// Modes without vertical synchronization fall back to each other before `Fifo`.
+    pub fn fallbacks(self) -> &'static [PresentMode] {
+        match self {
+            PresentMode::Immediate => &[
+                PresentMode::Immediate,
+                PresentMode::Mailbox,
+                PresentMode::Fifo,
+            ],
+            PresentMode::Mailbox => &[
+                PresentMode::Mailbox,
+                PresentMode::Immediate,
+                PresentMode::Fifo,
+            ],
+            PresentMode::Fifo => &[PresentMode::Fifo],
+            PresentMode::FifoRelaxed => &[PresentMode::FifoRelaxed, PresentMode::Fifo],
+        }
+    }
+
+    /// Selects the first supported mode of `fallbacks`, given the modes returned by
+    /// `vkGetPhysicalDeviceSurfacePresentModesKHR`.
+    pub fn select(self, supported: &[vk_sys::VkPresentModeKHR]) -> PresentMode {
+        self.fallbacks()
+            .iter()
+            .copied()
+            .find(|mode| supported.contains(&(*mode as vk_sys::VkPresentModeKHR)))
+            .unwrap_or(PresentMode::Fifo)
+    }
+
+    /// Returns the number of swapchain images to request, given `minImageCount` and
+    /// `maxImageCount` of the surface capabilities, `0` meaning no maximum.
+    ///
+    /// Mailbox needs an image more than the minimum to never block.
+    pub fn image_count(self, min_image_count: u32, max_image_count: u32) -> u32 {
+        let count = match self {
+            PresentMode::Mailbox => min_image_count.max(2) + 1,
+            _ => min_image_count + 1,
+        };
+        if max_image_count == 0 {
+            count
+        } else {
+            count.min(max_image_count)
+        }
+    }
+}
+
+impl Default for PresentMode {
+    fn default() -> PresentMode {
+        PresentMode::Fifo
+    }
+}
+
+/// Caps the frame rate by sleeping before presenting.
+///
+/// Frames are scheduled at a fixed interval; a late frame restarts the schedule instead
+/// of being followed by a burst of frames.
+#[derive(Clone, Debug)]
+pub struct FrameLimiter {
+    interval: Option<Duration>,
+    next: Option<Instant>,
+}
+
+impl FrameLimiter {
+    /// Creates a limiter capping the frame rate to `max_frame_rate` frames per second,
+    /// if any.
+    pub fn new(max_frame_rate: Option<u32>) -> FrameLimiter {
+        let mut limiter = FrameLimiter {
+            interval: None,
+            next: None,
+        };
+        limiter.set_max_frame_rate(max_frame_rate);
+        limiter
+    }
+
+    /// Sets the frame rate cap, `None` or `Some(0)` disabling it.
+    pub fn set_max_frame_rate(&mut self, max_frame_rate: Option<u32>) {
+        self.interval = max_frame_rate
+            .filter(|rate| *rate > 0)
+            .map(|rate| Duration::from_secs_f64(1.0 / f64::from(rate)));
+        self.next = None;
+    }
+
+    /// Returns the frame rate cap.
+    pub fn max_frame_rate(&self) -> Option<u32> {
+        self.interval
+            .map(|interval| (1.0 / interval.as_secs_f64()).round() as u32)
+    }
+
+    /// Returns how long to wait at `now` before presenting, and schedules the next
+    /// frame.
+    pub fn delay(&mut self, now: Instant) -> Duration {
+        let interval = match self.interval {
+            Some(interval) => interval,
+            None => return Duration::from_secs(0),
+        };
+        match self.next {
+            Some(next) if next > now => {
+                self.next = Some(next + interval);
+                next - now
+            }
+            _ => {
+                self.next = Some(now + interval);
+                Duration::from_secs(0)
+            }
+        }
+    }
+
+    /// Sleeps until the next frame is due.
+    pub fn wait(&mut self) {
+        let delay = self.delay(Instant::now());
+        if delay > Duration::from_secs(0) {
+            thread::sleep(delay);
+        }
+    }
+}
+
+/// Present mode requested by the application and used by the swapchain.
+#[derive(Clone, Debug)]
+pub struct PresentSettings {
+    requested: PresentMode,
+    current: Option<PresentMode>,
+    limiter: FrameLimiter,
+}
+
+impl PresentSettings {
+    /// Requests a present mode, without frame rate cap.
+    pub fn new(mode: PresentMode) -> PresentSettings {
+        PresentSettings {
+            requested: mode,
+            current: None,
+            limiter: FrameLimiter::new(None),
+        }
+    }
+
+    /// Returns the requested present mode.
+    pub fn requested(&self) -> PresentMode {
+        self.requested
+    }
+
+    /// Returns the present mode of the swapchain, if created.
+    pub fn current(&self) -> Option<PresentMode> {
+        self.current
+    }
+
+    /// Requests a present mode, the swapchain being recreated if it changed.
+    pub fn set_present_mode(&mut self, mode: PresentMode) {
+        if mode != self.requested {
+            self.requested = mode;
+            self.current = None;
+        }
+    }
+
+    /// Marks the swapchain for recreation, such as after a device loss.
+    pub fn invalidate(&mut self) {
+        self.current = None;
+    }
+
+    /// Returns `true` if the swapchain must be (re)created with `select`.
+    pub fn needs_recreation(&self) -> bool {
+        self.current.is_none()
+    }
+
+    /// Selects the present mode of a new swapchain, given the supported ones.
+    pub fn select(&mut self, supported: &[vk_sys::VkPresentModeKHR]) -> PresentMode {
+        let mode = self.requested.select(supported);
+        self.current = Some(mode);
+        mode
+    }
+
+    /// Caps the frame rate when the swapchain presents immediately.
+    pub fn set_max_frame_rate(&mut self, max_frame_rate: Option<u32>) {
+        self.limiter.set_max_frame_rate(max_frame_rate);
+    }
+
+    /// Returns the frame rate cap.
+    pub fn max_frame_rate(&self) -> Option<u32> {
+        self.limiter.max_frame_rate()
+    }
+
+    /// Sleeps until the next frame is due, if the swapchain presents immediately and the
+    /// frame rate is capped. To be called before presenting.
+    pub fn throttle(&mut self) {
+        if self.current == Some(PresentMode::Immediate) {
+            self.limiter.wait();
+        }
+    }
+}
+
+impl Default for PresentSettings {
+    fn default() -> PresentSettings {
+        PresentSettings::new(PresentMode::default())
+    }
+}
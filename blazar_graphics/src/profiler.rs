@@ -0,0 +1,352 @@
+//! GPU profiling with timestamp queries.
+//!
+//! The profiler splits a `VK_QUERY_TYPE_TIMESTAMP` pool into one region per frame in
+//! flight. Each frame resets its region and writes a timestamp at the beginning and end
+//! of every scope, typically a pass. Once the fence of the frame signaled, a few frames
+//! later, the region is read with `vkGetQueryPoolResults` and `VK_QUERY_RESULT_64_BIT`,
+//! then resolved into timings in milliseconds.
+
+use blazar_vk_sys as vk_sys;
+use std::{collections::VecDeque, fmt::Write as _, fs, io, path::Path};
+
+/// Commands to record for profiling.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ProfilerCommand {
+    /// Reset of queries (`vkCmdResetQueryPool`), recorded outside render passes.
+    Reset { first_query: u32, query_count: u32 },
+    /// Timestamp write (`vkCmdWriteTimestamp`).
+    WriteTimestamp {
+        stage: vk_sys::VkPipelineStageFlags,
+        query: u32,
+    },
+}
+
+/// GPU time spent in a scope.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScopeTiming {
+    pub name: String,
+    /// Nesting level, `0` for top-level scopes.
+    pub depth: u32,
+    /// Start in milliseconds since the start of the frame.
+    pub start: f64,
+    /// Duration in milliseconds.
+    pub duration: f64,
+}
+
+/// GPU timings of a frame.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FrameTimings {
+    pub frame: u64,
+    /// GPU time of the first timestamp in milliseconds, only meaningful relative to
+    /// other frames.
+    pub start: f64,
+    /// Milliseconds from the first timestamp to the last one.
+    pub duration: f64,
+    /// Scopes in the order they began.
+    pub scopes: Vec<ScopeTiming>,
+}
+
+impl FrameTimings {
+    /// Returns the timing of the first scope named `name`.
+    pub fn scope(&self, name: &str) -> Option<&ScopeTiming> {
+        self.scopes.iter().find(|scope| scope.name == name)
+    }
+}
+
+/// Scope recorded in a frame, its end timestamp written in the query after its begin.
+struct Scope {
+    name: String,
+    depth: u32,
+    query: u32,
+    ended: bool,
+}
+
+/// Queries of a frame in flight.
+#[derive(Default)]
+struct FrameQueries {
+    frame: u64,
+    scopes: Vec<Scope>,
+    query_count: u32,
+    pending: bool,
+}
+
+impl FrameQueries {
+    /// Returns the number of queries written before the first scope left open, whose
+    /// end timestamp is never written.
+    fn written_count(&self) -> u32 {
+        self.scopes
+            .iter()
+            .find(|scope| !scope.ended)
+            .map_or(self.query_count, |scope| scope.query)
+    }
+}
+
+/// Hands out timestamp queries and turns their results into timings.
+pub struct GpuProfiler {
+    period: f64,
+    mask: u64,
+    queries_per_frame: u32,
+    frames: Vec<FrameQueries>,
+    current: usize,
+    next_frame: u64,
+    /// Indices of the open scopes, `None` for scopes skipped for lack of queries.
+    open: Vec<Option<usize>>,
+    history: VecDeque<FrameTimings>,
+    history_length: usize,
+}
+
+impl GpuProfiler {
+    /// Creates a profiler over a pool of `frames_in_flight * queries_per_frame` queries,
+    /// keeping the timings of the last `history_length` frames.
+    ///
+    /// `timestamp_period` is `VkPhysicalDeviceLimits::timestampPeriod`, in nanoseconds
+    /// per tick. `timestamp_valid_bits` is `VkQueueFamilyProperties::timestampValidBits`
+    /// of the queue; if it is `0`, timestamps are unsupported and no scope is recorded.
+    pub fn new(
+        frames_in_flight: u32,
+        queries_per_frame: u32,
+        timestamp_period: f32,
+        timestamp_valid_bits: u32,
+        history_length: usize,
+    ) -> GpuProfiler {
+        let frames_in_flight = frames_in_flight.max(1) as usize;
+        let mask = match timestamp_valid_bits {
+            0 => 0,
+            bits if bits >= 64 => !0,
+            bits => (1 << bits) - 1,
+        };
+        GpuProfiler {
+            period: f64::from(timestamp_period),
+            mask,
+            queries_per_frame,
+            frames: (0..frames_in_flight)
+                .map(|_| FrameQueries::default())
+                .collect(),
+            current: frames_in_flight - 1,
+            next_frame: 0,
+            open: Vec::new(),
+            history: VecDeque::with_capacity(history_length),
+            history_length,
+        }
+    }
+
+    /// Returns the number of queries of the pool.
+    pub fn query_count(&self) -> u32 {
+        self.queries_per_frame * self.frames.len() as u32
+    }
+
+    /// Returns `true` if scopes are recorded.
+    pub fn is_enabled(&self) -> bool {
+        self.mask != 0 && self.queries_per_frame >= 2
+    }
+
+    /// Returns the first query and the number of queries to read before `begin_frame`,
+    /// if the region it reuses holds unresolved results.
+    ///
+    /// The queries of a scope left open in the frame and of the scopes after it are
+    /// excluded, waiting for them never completing.
+    pub fn pending_queries(&self) -> Option<(u32, u32)> {
+        let index = (self.current + 1) % self.frames.len();
+        let frame = &self.frames[index];
+        let count = frame.written_count();
+        if frame.pending && count > 0 {
+            Some((index as u32 * self.queries_per_frame, count))
+        } else {
+            None
+        }
+    }
+
+    /// Resolves the results of `pending_queries` and adds the frame to the history.
+    ///
+    /// Returns `false` if no results are pending or their number does not match. Scopes
+    /// whose queries were excluded from the results are left out of the timings.
+    pub fn resolve(&mut self, results: &[u64]) -> bool {
+        let index = (self.current + 1) % self.frames.len();
+        let frame = &mut self.frames[index];
+        let count = frame.written_count();
+        if !frame.pending || results.len() != count as usize {
+            return false;
+        }
+        frame.pending = false;
+        let (mask, period) = (self.mask, self.period);
+        let to_milliseconds = |ticks: u64| ticks as f64 * period / 1_000_000.0;
+        let written = |scope: &&Scope| scope.query < count;
+        let origin = frame
+            .scopes
+            .iter()
+            .find(written)
+            .map_or(0, |scope| results[scope.query as usize] & mask);
+        let mut duration = 0.0;
+        let mut scopes = Vec::with_capacity(frame.scopes.len());
+        for scope in frame.scopes.iter().filter(written) {
+            let begin = results[scope.query as usize] & mask;
+            let end = results[scope.query as usize + 1] & mask;
+            let timing = ScopeTiming {
+                name: scope.name.clone(),
+                depth: scope.depth,
+                start: to_milliseconds(begin.wrapping_sub(origin) & mask),
+                duration: to_milliseconds(end.wrapping_sub(begin) & mask),
+            };
+            duration = f64::max(duration, timing.start + timing.duration);
+            scopes.push(timing);
+        }
+        let timings = FrameTimings {
+            frame: frame.frame,
+            start: to_milliseconds(origin),
+            duration,
+            scopes,
+        };
+        if self.history_length > 0 {
+            if self.history.len() == self.history_length {
+                self.history.pop_front();
+            }
+            self.history.push_back(timings);
+        }
+        true
+    }
+
+    /// Starts a frame, returning the command resetting its queries.
+    ///
+    /// Unresolved results of the region it reuses and scopes left open are discarded.
+    pub fn begin_frame(&mut self) -> ProfilerCommand {
+        self.current = (self.current + 1) % self.frames.len();
+        self.open.clear();
+        let frame = &mut self.frames[self.current];
+        frame.frame = self.next_frame;
+        frame.scopes.clear();
+        frame.query_count = 0;
+        frame.pending = true;
+        self.next_frame += 1;
+        ProfilerCommand::Reset {
+            first_query: self.current as u32 * self.queries_per_frame,
+            query_count: self.queries_per_frame,
+        }
+    }
+
+    /// Begins a scope, returning the timestamp to write at the top of the pipe.
+    ///
+    /// Returns `None` if timestamps are unsupported or the queries of the frame are
+    /// exhausted; the scope must be ended anyway.
+    pub fn begin_scope(&mut self, name: &str) -> Option<ProfilerCommand> {
+        let first_query = self.current as u32 * self.queries_per_frame;
+        let enabled = self.is_enabled();
+        let frame = &mut self.frames[self.current];
+        if !enabled || frame.query_count + 2 > self.queries_per_frame {
+            self.open.push(None);
+            return None;
+        }
+        let query = frame.query_count;
+        frame.query_count += 2;
+        frame.scopes.push(Scope {
+            name: String::from(name),
+            depth: self.open.len() as u32,
+            query,
+            ended: false,
+        });
+        self.open.push(Some(frame.scopes.len() - 1));
+        Some(ProfilerCommand::WriteTimestamp {
+            stage: vk_sys::VK_PIPELINE_STAGE_TOP_OF_PIPE_BIT,
+            query: first_query + query,
+        })
+    }
+
+    /// Ends the innermost scope, returning the timestamp to write at the bottom of the
+    /// pipe.
+    pub fn end_scope(&mut self) -> Option<ProfilerCommand> {
+        let index = self.open.pop()??;
+        let scope = &mut self.frames[self.current].scopes[index];
+        scope.ended = true;
+        Some(ProfilerCommand::WriteTimestamp {
+            stage: vk_sys::VK_PIPELINE_STAGE_BOTTOM_OF_PIPE_BIT,
+            query: self.current as u32 * self.queries_per_frame + scope.query + 1,
+        })
+    }
+
+    /// Discards unresolved results and open scopes, such as after a device loss.
+    pub fn discard_pending(&mut self) {
+        self.open.clear();
+        for frame in &mut self.frames {
+            frame.pending = false;
+        }
+    }
+
+    /// Returns the timings of the last resolved frame.
+    pub fn latest(&self) -> Option<&FrameTimings> {
+        self.history.back()
+    }
+
+    /// Returns the timings of the last resolved frames, oldest first.
+    pub fn history(&self) -> &VecDeque<FrameTimings> {
+        &self.history
+    }
+
+    /// Returns the average duration in milliseconds of the scopes named `name` over the
+    /// history.
+    pub fn average(&self, name: &str) -> Option<f64> {
+        let durations: Vec<f64> = self
+            .history
+            .iter()
+            .flat_map(|frame| frame.scopes.iter())
+            .filter(|scope| scope.name == name)
+            .map(|scope| scope.duration)
+            .collect();
+        if durations.is_empty() {
+            None
+        } else {
+            Some(durations.iter().sum::<f64>() / durations.len() as f64)
+        }
+    }
+
+    /// Clears the history.
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+    }
+
+    /// Exports the history in the Chrome trace event format, viewable in
+    /// `chrome://tracing` or Perfetto.
+    pub fn to_chrome_trace(&self) -> String {
+        let origin = self.history.front().map_or(0.0, |frame| frame.start);
+        let mut trace = String::from(
+            "{\"traceEvents\":[\
+             {\"name\":\"thread_name\",\"ph\":\"M\",\"pid\":0,\"tid\":0,\"args\":{\"name\":\"GPU\"}}",
+        );
+        for frame in &self.history {
+            for scope in &frame.scopes {
+                write!(
+                    trace,
+                    ",{{\"name\":\"{}\",\"cat\":\"gpu\",\"ph\":\"X\",\"pid\":0,\"tid\":0,\
+                     \"ts\":{:.3},\"dur\":{:.3},\"args\":{{\"frame\":{}}}}}",
+                    escape_json(&scope.name),
+                    (frame.start - origin + scope.start) * 1000.0,
+                    scope.duration * 1000.0,
+                    frame.frame,
+                )
+                .unwrap();
+            }
+        }
+        trace.push_str("]}");
+        trace
+    }
+
+    /// Saves the history in the Chrome trace event format.
+    pub fn save_chrome_trace<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_chrome_trace())
+    }
+}
+
+/// Escapes a string to be written between quotes in JSON.
+fn escape_json(string: &str) -> String {
+    let mut escaped = String::with_capacity(string.len());
+    for character in string.chars() {
+        match character {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            character if (character as u32) < 0x20 => {
+                write!(escaped, "\\u{:04x}", character as u32).unwrap()
+            }
+            character => escaped.push(character),
+        }
+    }
+    escaped
+}
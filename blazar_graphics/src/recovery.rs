@@ -0,0 +1,166 @@
+//! Device-lost detection and recovery.
+//!
+//! Once a call returned `VK_ERROR_DEVICE_LOST`, every object created from the device is
+//! unusable: `DeviceRecovery::recover` destroys them along with the device, then creates
+//! a new device. Renderer-owned objects are recreated from their descriptions: the
+//! pipelines and targets drained from `PipelineSet` and `RenderTargets` are destroyed,
+//! pipelines being created again on request and targets by the next
+//! `RenderTargets::rebuild`, the swapchain is recreated through `PresentSettings` and
+//! results of the lost queries are dropped from `GpuProfiler`. Application-owned
+//! resources, such as textures, are uploaded again on `Event::DeviceRestored`.
+
+use crate::{
+    pipeline::PipelineSet, present::PresentSettings, profiler::GpuProfiler, target::RenderTargets,
+};
+use blazar_event::Event;
+use blazar_vk_sys as vk_sys;
+use std::collections::VecDeque;
+
+/// Device operations needed to recover from a device loss.
+///
+/// Errors are the `VkResult` codes returned by the underlying Vulkan calls.
+pub trait RecoveryBackend {
+    /// Pipelines of the `PipelineSet`.
+    type Pipeline;
+
+    /// Resources of the `RenderTargets`.
+    type Target;
+
+    /// Destroys a pipeline of the lost device (`vkDestroyPipeline`).
+    fn destroy_pipeline(&mut self, pipeline: Self::Pipeline);
+
+    /// Destroys the resources of a render target of the lost device.
+    fn destroy_target(&mut self, target: Self::Target);
+
+    /// Destroys every other object created from the lost device, then the device
+    /// (`vkDestroyDevice`), without waiting for it.
+    fn destroy_device(&mut self);
+
+    /// Creates a device (`vkCreateDevice`) and the objects needed to render again.
+    ///
+    /// On failure, objects created so far must be destroyed.
+    fn create_device(&mut self) -> Result<(), vk_sys::VkResult>;
+}
+
+/// Renderer-owned objects recreated after a device loss.
+pub struct RendererObjects<'a, P, T> {
+    pub pipelines: &'a mut PipelineSet<P>,
+    pub targets: &'a mut RenderTargets<T>,
+    pub present: &'a mut PresentSettings,
+    pub profiler: &'a mut GpuProfiler,
+}
+
+/// States of the device.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum DeviceState {
+    Valid,
+    Lost,
+    /// Lost and destroyed, a new device being not created yet.
+    Destroyed,
+}
+
+/// Detects device losses, recovers from them and notifies the application.
+pub struct DeviceRecovery {
+    state: DeviceState,
+    generation: u64,
+    failed_attempts: u32,
+    events: VecDeque<Event>,
+}
+
+impl DeviceRecovery {
+    /// Creates a tracker of a valid device.
+    pub fn new() -> DeviceRecovery {
+        DeviceRecovery {
+            state: DeviceState::Valid,
+            generation: 0,
+            failed_attempts: 0,
+            events: VecDeque::new(),
+        }
+    }
+
+    /// Turns the result of a device call into a `Result`, recording a device loss.
+    ///
+    /// Success codes such as `VK_SUBOPTIMAL_KHR` are returned as `Ok`.
+    pub fn check(
+        &mut self,
+        result: vk_sys::VkResult,
+    ) -> Result<vk_sys::VkResult, vk_sys::VkResult> {
+        if result == vk_sys::VK_ERROR_DEVICE_LOST && self.state == DeviceState::Valid {
+            self.state = DeviceState::Lost;
+            self.events.push_back(Event::DeviceLost);
+        }
+        if result < 0 {
+            Err(result)
+        } else {
+            Ok(result)
+        }
+    }
+
+    /// Returns `true` if the device is lost and must not be used until recovered.
+    pub fn is_lost(&self) -> bool {
+        self.state != DeviceState::Valid
+    }
+
+    /// Returns the number of times the device was recreated.
+    ///
+    /// Objects tagged with an older generation belong to a lost device.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Returns the number of failed attempts to create a device since it was lost.
+    pub fn failed_attempts(&self) -> u32 {
+        self.failed_attempts
+    }
+
+    /// Recreates the device if it was lost, returning `true` if it was.
+    ///
+    /// The lost device is destroyed once, after the pipelines and targets of `objects`,
+    /// which also marks the swapchain for recreation and discards the pending profiler
+    /// results. On failure, the device stays lost and recovery can be attempted again
+    /// later, such as on the next frame.
+    pub fn recover<B: RecoveryBackend>(
+        &mut self,
+        backend: &mut B,
+        objects: RendererObjects<'_, B::Pipeline, B::Target>,
+    ) -> Result<bool, vk_sys::VkResult> {
+        match self.state {
+            DeviceState::Valid => return Ok(false),
+            DeviceState::Lost => {
+                for pipeline in objects.pipelines.drain() {
+                    backend.destroy_pipeline(pipeline);
+                }
+                for target in objects.targets.drain() {
+                    backend.destroy_target(target);
+                }
+                objects.present.invalidate();
+                objects.profiler.discard_pending();
+                backend.destroy_device();
+                self.state = DeviceState::Destroyed;
+            }
+            DeviceState::Destroyed => {}
+        }
+        if let Err(error) = backend.create_device() {
+            self.failed_attempts += 1;
+            return Err(error);
+        }
+        self.state = DeviceState::Valid;
+        self.generation += 1;
+        self.failed_attempts = 0;
+        self.events.push_back(Event::DeviceRestored);
+        Ok(true)
+    }
+
+    /// Pops the oldest pending device event, if any.
+    ///
+    /// Device events are only reported here, not by `Window::poll_event`.
+    pub fn poll_event(&mut self) -> Option<Event> {
+        self.events.pop_front()
+    }
+}
+
+impl Default for DeviceRecovery {
+    fn default() -> DeviceRecovery {
+        DeviceRecovery::new()
+    }
+}
@@ -0,0 +1,94 @@
+//! Orthographic 2D camera.
+
+use blazar_event::Event;
+
+/// Orthographic camera mapping world units to pixels, the y-axis pointing down.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Camera2d {
+    /// World position shown at the center of the viewport.
+    pub position: [f32; 2],
+    /// Number of pixels per world unit.
+    pub zoom: f32,
+    /// Clockwise rotation of the view in radians.
+    pub rotation: f32,
+    width: u32,
+    height: u32,
+}
+
+impl Camera2d {
+    /// Creates a camera for a viewport of the given size, showing the world origin at its
+    /// top-left corner.
+    pub fn new(width: u32, height: u32) -> Camera2d {
+        Camera2d {
+            position: [width as f32 / 2.0, height as f32 / 2.0],
+            zoom: 1.0,
+            rotation: 0.0,
+            width,
+            height,
+        }
+    }
+
+    /// Returns the viewport size.
+    pub fn viewport(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Changes the viewport size, keeping the center of the view.
+    pub fn resize(&mut self, width: u32, height: u32) {
+        self.width = width;
+        self.height = height;
+    }
+
+    /// Follows the window size, returning `true` if the event resized the viewport.
+    pub fn handle_event(&mut self, event: &Event) -> bool {
+        match *event {
+            Event::Resize { width, height } => {
+                self.resize(width, height);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the column-major matrix transforming world positions to Vulkan clip space.
+    pub fn view_projection(&self) -> [[f32; 4]; 4] {
+        let (sin, cos) = self.rotation.sin_cos();
+        let scale_x = 2.0 * self.zoom / self.width.max(1) as f32;
+        let scale_y = 2.0 * self.zoom / self.height.max(1) as f32;
+        let [x, y] = self.position;
+        [
+            [scale_x * cos, -scale_y * sin, 0.0, 0.0],
+            [scale_x * sin, scale_y * cos, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [
+                -scale_x * (cos * x + sin * y),
+                scale_y * (sin * x - cos * y),
+                0.0,
+                1.0,
+            ],
+        ]
+    }
+
+    /// Converts a position in pixels from the top-left corner of the viewport to world
+    /// space.
+    pub fn screen_to_world(&self, position: [f32; 2]) -> [f32; 2] {
+        let (sin, cos) = self.rotation.sin_cos();
+        let x = (position[0] - self.width as f32 / 2.0) / self.zoom;
+        let y = (position[1] - self.height as f32 / 2.0) / self.zoom;
+        [
+            self.position[0] + x * cos - y * sin,
+            self.position[1] + x * sin + y * cos,
+        ]
+    }
+
+    /// Converts a world position to pixels from the top-left corner of the viewport.
+    pub fn world_to_screen(&self, position: [f32; 2]) -> [f32; 2] {
+        let (sin, cos) = self.rotation.sin_cos();
+        let x = position[0] - self.position[0];
+        let y = position[1] - self.position[1];
+        [
+            (x * cos + y * sin) * self.zoom + self.width as f32 / 2.0,
+            (y * cos - x * sin) * self.zoom + self.height as f32 / 2.0,
+        ]
+    }
+}
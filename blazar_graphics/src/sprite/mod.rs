@@ -0,0 +1,341 @@
+//! 2D sprite batching.
+//!
+//! Sprites are sorted by z-order and texture, then merged into as few draw calls as
+//! possible. The matching GLSL shaders are `shaders/sprite.vert` and
+//! `shaders/sprite.frag`.
+
+mod camera;
+
+pub use camera::*;
+
+use crate::{
+    color::LinearRgba,
+    descriptor::{DescriptorBinding, DescriptorSetLayoutDescription, DescriptorType},
+    order::float_key,
+    pipeline::{
+        BlendState, DepthState, GraphicsPipelineDescription, PipelineLayoutDescription,
+        PushConstantRange, Shader, ShaderStage, VertexFormat, VertexInputRate, VertexLayout,
+    },
+};
+
+/// Region of a texture, in normalized coordinates.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TextureRegion {
+    pub min: [f32; 2],
+    pub max: [f32; 2],
+}
+
+impl TextureRegion {
+    /// Whole texture.
+    pub const FULL: TextureRegion = TextureRegion {
+        min: [0.0, 0.0],
+        max: [1.0, 1.0],
+    };
+
+    /// Creates a region from a rectangle in pixels, typically a frame of an atlas.
+    pub fn from_pixels(
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        texture_width: u32,
+        texture_height: u32,
+    ) -> TextureRegion {
+        let (texture_width, texture_height) = (texture_width as f32, texture_height as f32);
+        TextureRegion {
+            min: [x as f32 / texture_width, y as f32 / texture_height],
+            max: [
+                (x + width) as f32 / texture_width,
+                (y + height) as f32 / texture_height,
+            ],
+        }
+    }
+
+    /// Mirrors the region horizontally.
+    pub fn flip_x(self) -> TextureRegion {
+        TextureRegion {
+            min: [self.max[0], self.min[1]],
+            max: [self.min[0], self.max[1]],
+        }
+    }
+
+    /// Mirrors the region vertically.
+    pub fn flip_y(self) -> TextureRegion {
+        TextureRegion {
+            min: [self.min[0], self.max[1]],
+            max: [self.max[0], self.min[1]],
+        }
+    }
+}
+
+impl Default for TextureRegion {
+    fn default() -> TextureRegion {
+        TextureRegion::FULL
+    }
+}
+
+/// Describes a textured quad.
+///
+/// `T` identifies what must be bound to draw the sprite, typically a texture, or a
+/// tuple of a texture and a material.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Sprite<T> {
+    pub texture: T,
+    pub region: TextureRegion,
+    /// Position of the origin in world space.
+    pub position: [f32; 2],
+    /// Size before scaling, in world units.
+    pub size: [f32; 2],
+    /// Point the sprite is positioned, scaled and rotated around, normalized to its size.
+    pub origin: [f32; 2],
+    /// Clockwise rotation in radians, the y-axis pointing down.
+    pub rotation: f32,
+    pub scale: [f32; 2],
+    /// Linear color multiplied with texels.
+    pub tint: [f32; 4],
+    /// Sprites with a higher z are drawn on top.
+    pub z: f32,
+}
+
+impl<T> Sprite<T> {
+    /// Creates an untinted sprite showing the whole texture, centered on `position`.
+    pub fn new(texture: T, position: [f32; 2], size: [f32; 2]) -> Sprite<T> {
+        Sprite {
+            texture,
+            region: TextureRegion::FULL,
+            position,
+            size,
+            origin: [0.5, 0.5],
+            rotation: 0.0,
+            scale: [1.0, 1.0],
+            tint: [1.0; 4],
+            z: 0.0,
+        }
+    }
+
+    /// Sets the texture region.
+    pub fn region(mut self, region: TextureRegion) -> Sprite<T> {
+        self.region = region;
+        self
+    }
+
+    /// Sets the origin.
+    pub fn origin(mut self, origin: [f32; 2]) -> Sprite<T> {
+        self.origin = origin;
+        self
+    }
+
+    /// Sets the rotation.
+    pub fn rotation(mut self, rotation: f32) -> Sprite<T> {
+        self.rotation = rotation;
+        self
+    }
+
+    /// Sets the scale.
+    pub fn scale(mut self, scale: [f32; 2]) -> Sprite<T> {
+        self.scale = scale;
+        self
+    }
+
+    /// Sets the tint.
+    pub fn tint<C: Into<LinearRgba>>(mut self, tint: C) -> Sprite<T> {
+        self.tint = tint.into().to_array();
+        self
+    }
+
+    /// Sets the z-order.
+    pub fn z(mut self, z: f32) -> Sprite<T> {
+        self.z = z;
+        self
+    }
+}
+
+/// Vertex of a sprite quad.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpriteVertex {
+    pub position: [f32; 2],
+    pub uv: [f32; 2],
+    pub color: [f32; 4],
+}
+
+impl SpriteVertex {
+    /// Returns the vertex layout.
+    pub fn layout() -> VertexLayout {
+        VertexLayout::new().interleaved(
+            VertexInputRate::Vertex,
+            &[
+                VertexFormat::Float2,
+                VertexFormat::Float2,
+                VertexFormat::Float4,
+            ],
+        )
+    }
+}
+
+/// Indexed draw call of a batch.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SpriteDraw<T> {
+    pub texture: T,
+    pub first_index: u32,
+    pub index_count: u32,
+}
+
+/// Collects the sprites of a frame and builds their vertices, indices and draw calls.
+pub struct SpriteBatch<T> {
+    sprites: Vec<Sprite<T>>,
+    vertices: Vec<SpriteVertex>,
+    indices: Vec<u32>,
+    draws: Vec<SpriteDraw<T>>,
+}
+
+impl<T: Copy + Ord> SpriteBatch<T> {
+    /// Size in bytes of the push constants, holding the view-projection matrix.
+    pub const PUSH_CONSTANTS_SIZE: u32 = 64;
+
+    /// Creates an empty batch.
+    pub fn new() -> SpriteBatch<T> {
+        SpriteBatch {
+            sprites: Vec::new(),
+            vertices: Vec::new(),
+            indices: Vec::new(),
+            draws: Vec::new(),
+        }
+    }
+
+    /// Adds a sprite.
+    pub fn draw(&mut self, sprite: Sprite<T>) {
+        self.sprites.push(sprite);
+    }
+
+    /// Returns the number of sprites added since the last call to `clear`.
+    pub fn len(&self) -> usize {
+        self.sprites.len()
+    }
+
+    /// Returns `true` if no sprites were added since the last call to `clear`.
+    pub fn is_empty(&self) -> bool {
+        self.sprites.is_empty()
+    }
+
+    /// Removes all sprites, keeping allocated memory.
+    pub fn clear(&mut self) {
+        self.sprites.clear();
+        self.vertices.clear();
+        self.indices.clear();
+        self.draws.clear();
+    }
+
+    /// Sorts sprites and builds vertices, indices and draw calls.
+    ///
+    /// Sprites are drawn by increasing z. Sprites sharing the same z are grouped by
+    /// texture, otherwise keeping the order they were added in; give overlapping
+    /// sprites distinct z values to control their order. Sprites whose z is NaN are drawn
+    /// last.
+    pub fn build(&mut self) {
+        self.sprites.sort_by(|a, b| {
+            float_key(a.z)
+                .cmp(&float_key(b.z))
+                .then_with(|| a.texture.cmp(&b.texture))
+        });
+        self.vertices.clear();
+        self.indices.clear();
+        self.draws.clear();
+        for sprite in &self.sprites {
+            let first_vertex = self.vertices.len() as u32;
+            self.vertices.extend_from_slice(&quad(sprite));
+            let first_index = self.indices.len() as u32;
+            self.indices
+                .extend([0, 1, 2, 2, 3, 0].iter().map(|index| first_vertex + index));
+            match self.draws.last_mut() {
+                Some(draw) if draw.texture == sprite.texture => draw.index_count += 6,
+                _ => self.draws.push(SpriteDraw {
+                    texture: sprite.texture,
+                    first_index,
+                    index_count: 6,
+                }),
+            }
+        }
+    }
+
+    /// Returns the vertices built by `build`.
+    pub fn vertices(&self) -> &[SpriteVertex] {
+        &self.vertices
+    }
+
+    /// Returns the indices built by `build`.
+    pub fn indices(&self) -> &[u32] {
+        &self.indices
+    }
+
+    /// Returns the draw calls built by `build`.
+    pub fn draws(&self) -> &[SpriteDraw<T>] {
+        &self.draws
+    }
+
+    /// Returns the layout of the descriptor set binding the texture of a draw call.
+    pub fn texture_set_layout() -> DescriptorSetLayoutDescription {
+        DescriptorSetLayoutDescription::new().binding(DescriptorBinding::new(
+            0,
+            DescriptorType::CombinedImageSampler,
+            ShaderStage::Fragment as u32,
+        ))
+    }
+
+    /// Describes the sprite pipeline, alpha-blended without depth testing.
+    pub fn pipeline_description(
+        vertex_shader: Shader,
+        fragment_shader: Shader,
+    ) -> GraphicsPipelineDescription {
+        GraphicsPipelineDescription::new()
+            .shader(vertex_shader)
+            .shader(fragment_shader)
+            .layout(
+                PipelineLayoutDescription::new()
+                    .set_layout(Self::texture_set_layout())
+                    .push_constants(PushConstantRange {
+                        stages: ShaderStage::Vertex as u32,
+                        offset: 0,
+                        size: Self::PUSH_CONSTANTS_SIZE,
+                    }),
+            )
+            .vertex_layout(SpriteVertex::layout())
+            .depth(DepthState::DISABLED)
+            .blend(&[BlendState::ALPHA])
+    }
+}
+
+impl<T: Copy + Ord> Default for SpriteBatch<T> {
+    fn default() -> SpriteBatch<T> {
+        SpriteBatch::new()
+    }
+}
+
+/// Computes the vertices of a sprite, clockwise from its top-left corner.
+fn quad<T>(sprite: &Sprite<T>) -> [SpriteVertex; 4] {
+    let (sin, cos) = sprite.rotation.sin_cos();
+    let width = sprite.size[0] * sprite.scale[0];
+    let height = sprite.size[1] * sprite.scale[1];
+    let region = sprite.region;
+    let corners = [
+        ([0.0, 0.0], region.min),
+        ([1.0, 0.0], [region.max[0], region.min[1]]),
+        ([1.0, 1.0], region.max),
+        ([0.0, 1.0], [region.min[0], region.max[1]]),
+    ];
+    let mut vertices = [SpriteVertex {
+        position: [0.0; 2],
+        uv: [0.0; 2],
+        color: sprite.tint,
+    }; 4];
+    for (vertex, (corner, uv)) in vertices.iter_mut().zip(corners.iter()) {
+        let x = (corner[0] - sprite.origin[0]) * width;
+        let y = (corner[1] - sprite.origin[1]) * height;
+        vertex.position = [
+            sprite.position[0] + x * cos - y * sin,
+            sprite.position[1] + x * sin + y * cos,
+        ];
+        vertex.uv = *uv;
+    }
+    vertices
+}
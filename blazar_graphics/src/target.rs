@@ -0,0 +1,508 @@
+//! Render target descriptions and multisample anti-aliasing.
+//!
+//! A render target is a set of attachments rendered together: color attachments, an
+//! optional depth/stencil attachment and, when multisampled, the single-sampled images
+//! the color attachments are resolved into. Targets sized after the swapchain, or using
+//! the sample count selected at runtime, are rebuilt by `RenderTargets` when either
+//! changes.
+
+use crate::texture::TextureFormat;
+use blazar_vk_sys as vk_sys;
+
+/// Kinds of render target errors.
+#[derive(Debug)]
+pub enum RenderTargetError {
+    NoAttachments,
+    InvalidSize,
+    /// The sample count is not in `framebufferColorSampleCounts` or
+    /// `framebufferDepthSampleCounts`.
+    UnsupportedSampleCount(SampleCount),
+}
+
+/// Numbers of samples per pixel.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum SampleCount {
+    X1 = vk_sys::VK_SAMPLE_COUNT_1_BIT,
+    X2 = vk_sys::VK_SAMPLE_COUNT_2_BIT,
+    X4 = vk_sys::VK_SAMPLE_COUNT_4_BIT,
+    X8 = vk_sys::VK_SAMPLE_COUNT_8_BIT,
+    X16 = vk_sys::VK_SAMPLE_COUNT_16_BIT,
+}
+
+impl SampleCount {
+    /// All sample counts, in increasing order.
+    pub const ALL: [SampleCount; 5] = [
+        SampleCount::X1,
+        SampleCount::X2,
+        SampleCount::X4,
+        SampleCount::X8,
+        SampleCount::X16,
+    ];
+
+    /// Returns the sample count matching a number of samples, if any.
+    pub fn from_count(count: u32) -> Option<SampleCount> {
+        SampleCount::ALL
+            .iter()
+            .copied()
+            .find(|samples| samples.count() == count)
+    }
+
+    /// Returns the number of samples.
+    pub fn count(self) -> u32 {
+        self as u32
+    }
+
+    /// Returns `true` if the sample count is in `flags`, such as
+    /// `framebufferColorSampleCounts`.
+    pub fn is_supported(self, flags: vk_sys::VkSampleCountFlags) -> bool {
+        flags & self as u32 != 0
+    }
+
+    /// Returns the largest sample count in `flags` not exceeding `self`, falling back
+    /// to `X1`.
+    pub fn clamp_to(self, flags: vk_sys::VkSampleCountFlags) -> SampleCount {
+        SampleCount::ALL
+            .iter()
+            .rev()
+            .copied()
+            .find(|samples| *samples <= self && samples.is_supported(flags))
+            .unwrap_or(SampleCount::X1)
+    }
+}
+
+impl Default for SampleCount {
+    fn default() -> SampleCount {
+        SampleCount::X1
+    }
+}
+
+/// Depth/stencil formats.
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum DepthFormat {
+    D16Unorm = vk_sys::VK_FORMAT_D16_UNORM,
+    D32Float = vk_sys::VK_FORMAT_D32_SFLOAT,
+    D24UnormS8Uint = vk_sys::VK_FORMAT_D24_UNORM_S8_UINT,
+    D32FloatS8Uint = vk_sys::VK_FORMAT_D32_SFLOAT_S8_UINT,
+}
+
+impl DepthFormat {
+    /// Returns `true` if the format has a stencil component.
+    pub fn has_stencil(self) -> bool {
+        matches!(
+            self,
+            DepthFormat::D24UnormS8Uint | DepthFormat::D32FloatS8Uint
+        )
+    }
+}
+
+/// Sizes of render targets.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum TargetSize {
+    /// Size of the swapchain, divided by the given factor, typically `1`.
+    Swapchain(u32),
+    Fixed(u32, u32),
+}
+
+/// Sample counts of render targets.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum TargetSamples {
+    Fixed(SampleCount),
+    /// Sample count selected at runtime with `RenderTargets::set_msaa`.
+    Msaa,
+}
+
+/// Describes a render target.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct RenderTargetDescription {
+    pub size: TargetSize,
+    pub color_formats: Vec<TextureFormat>,
+    pub depth_format: Option<DepthFormat>,
+    pub samples: TargetSamples,
+    /// Whether the depth/stencil attachment is sampled after the pass, instead of being
+    /// discarded.
+    pub sampled_depth: bool,
+}
+
+impl RenderTargetDescription {
+    /// Describes a single-sampled target without attachments.
+    pub fn new(size: TargetSize) -> RenderTargetDescription {
+        RenderTargetDescription {
+            size,
+            color_formats: Vec::new(),
+            depth_format: None,
+            samples: TargetSamples::Fixed(SampleCount::X1),
+            sampled_depth: false,
+        }
+    }
+
+    /// Describes a target of the size of the swapchain, with a color and a depth
+    /// attachment, using the runtime MSAA setting.
+    pub fn main(color_format: TextureFormat, depth_format: DepthFormat) -> RenderTargetDescription {
+        RenderTargetDescription::new(TargetSize::Swapchain(1))
+            .color(color_format)
+            .depth(depth_format)
+            .samples(TargetSamples::Msaa)
+    }
+
+    /// Adds a color attachment.
+    pub fn color(mut self, format: TextureFormat) -> RenderTargetDescription {
+        self.color_formats.push(format);
+        self
+    }
+
+    /// Sets the depth/stencil attachment.
+    pub fn depth(mut self, format: DepthFormat) -> RenderTargetDescription {
+        self.depth_format = Some(format);
+        self
+    }
+
+    /// Sets the sample count.
+    pub fn samples(mut self, samples: TargetSamples) -> RenderTargetDescription {
+        self.samples = samples;
+        self
+    }
+
+    /// Sets whether the depth/stencil attachment is sampled after the pass.
+    pub fn sampled_depth(mut self, sampled_depth: bool) -> RenderTargetDescription {
+        self.sampled_depth = sampled_depth;
+        self
+    }
+
+    /// Resolves the size and sample count given the swapchain extent and the MSAA
+    /// setting, and lists the attachments to create.
+    ///
+    /// Multisampled color attachments are transient, each resolved into a
+    /// single-sampled attachment that can be sampled or presented; the resolve
+    /// attachment of a presented target is the swapchain image. Single-sampled color
+    /// attachments are sampled directly.
+    pub fn layout(
+        &self,
+        swapchain_extent: (u32, u32),
+        msaa: SampleCount,
+    ) -> Result<RenderTargetLayout, RenderTargetError> {
+        if self.color_formats.is_empty() && self.depth_format.is_none() {
+            return Err(RenderTargetError::NoAttachments);
+        }
+        let (width, height) = match self.size {
+            TargetSize::Swapchain(0) => return Err(RenderTargetError::InvalidSize),
+            TargetSize::Swapchain(divisor) => (
+                (swapchain_extent.0 / divisor).max(1),
+                (swapchain_extent.1 / divisor).max(1),
+            ),
+            TargetSize::Fixed(width, height) => (width, height),
+        };
+        if width == 0 || height == 0 {
+            return Err(RenderTargetError::InvalidSize);
+        }
+        let samples = match self.samples {
+            TargetSamples::Fixed(samples) => samples,
+            TargetSamples::Msaa => msaa,
+        };
+        let multisampled = samples != SampleCount::X1;
+        let mut attachments = Vec::new();
+        for format in &self.color_formats {
+            attachments.push(AttachmentDescription {
+                kind: AttachmentKind::Color,
+                format: *format as vk_sys::VkFormat,
+                samples,
+                usage: if multisampled {
+                    vk_sys::VK_IMAGE_USAGE_COLOR_ATTACHMENT_BIT
+                        | vk_sys::VK_IMAGE_USAGE_TRANSIENT_ATTACHMENT_BIT
+                } else {
+                    vk_sys::VK_IMAGE_USAGE_COLOR_ATTACHMENT_BIT
+                        | vk_sys::VK_IMAGE_USAGE_SAMPLED_BIT
+                        | vk_sys::VK_IMAGE_USAGE_TRANSFER_SRC_BIT
+                },
+            });
+        }
+        if let Some(format) = self.depth_format {
+            attachments.push(AttachmentDescription {
+                kind: AttachmentKind::DepthStencil,
+                format: format as vk_sys::VkFormat,
+                samples,
+                usage: if self.sampled_depth {
+                    vk_sys::VK_IMAGE_USAGE_DEPTH_STENCIL_ATTACHMENT_BIT
+                        | vk_sys::VK_IMAGE_USAGE_SAMPLED_BIT
+                } else {
+                    vk_sys::VK_IMAGE_USAGE_DEPTH_STENCIL_ATTACHMENT_BIT
+                        | vk_sys::VK_IMAGE_USAGE_TRANSIENT_ATTACHMENT_BIT
+                },
+            });
+        }
+        if multisampled {
+            for format in &self.color_formats {
+                attachments.push(AttachmentDescription {
+                    kind: AttachmentKind::Resolve,
+                    format: *format as vk_sys::VkFormat,
+                    samples: SampleCount::X1,
+                    usage: vk_sys::VK_IMAGE_USAGE_COLOR_ATTACHMENT_BIT
+                        | vk_sys::VK_IMAGE_USAGE_SAMPLED_BIT
+                        | vk_sys::VK_IMAGE_USAGE_TRANSFER_SRC_BIT,
+                });
+            }
+        }
+        Ok(RenderTargetLayout {
+            width,
+            height,
+            samples,
+            attachments,
+        })
+    }
+
+    /// Checks that the sample count is supported for the attachments of the target,
+    /// given `framebufferColorSampleCounts` and `framebufferDepthSampleCounts`.
+    pub fn validate(
+        &self,
+        msaa: SampleCount,
+        color_sample_counts: vk_sys::VkSampleCountFlags,
+        depth_sample_counts: vk_sys::VkSampleCountFlags,
+    ) -> Result<(), RenderTargetError> {
+        let samples = match self.samples {
+            TargetSamples::Fixed(samples) => samples,
+            TargetSamples::Msaa => msaa,
+        };
+        if (!self.color_formats.is_empty() && !samples.is_supported(color_sample_counts))
+            || (self.depth_format.is_some() && !samples.is_supported(depth_sample_counts))
+        {
+            return Err(RenderTargetError::UnsupportedSampleCount(samples));
+        }
+        Ok(())
+    }
+}
+
+/// Roles of attachments.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum AttachmentKind {
+    Color,
+    DepthStencil,
+    /// Single-sampled image a multisampled color attachment is resolved into, in the
+    /// same order.
+    Resolve,
+}
+
+/// Describes an attachment image to create.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct AttachmentDescription {
+    pub kind: AttachmentKind,
+    pub format: vk_sys::VkFormat,
+    pub samples: SampleCount,
+    pub usage: vk_sys::VkImageUsageFlags,
+}
+
+/// Resolved size, sample count and attachments of a render target.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct RenderTargetLayout {
+    pub width: u32,
+    pub height: u32,
+    pub samples: SampleCount,
+    /// Color attachments, then the depth/stencil attachment, then resolve attachments.
+    pub attachments: Vec<AttachmentDescription>,
+}
+
+impl RenderTargetLayout {
+    /// Returns the attachments of a kind.
+    pub fn attachments(
+        &self,
+        kind: AttachmentKind,
+    ) -> impl Iterator<Item = &AttachmentDescription> {
+        self.attachments
+            .iter()
+            .filter(move |attachment| attachment.kind == kind)
+    }
+
+    /// Returns `true` if color attachments are resolved.
+    pub fn is_multisampled(&self) -> bool {
+        self.samples != SampleCount::X1
+    }
+
+    /// Returns the index in `attachments` of the single-sampled image holding a color
+    /// attachment after the pass, to be sampled by later passes: its resolve attachment
+    /// if multisampled, or the attachment itself otherwise.
+    pub fn sampled_color(&self, color_index: usize) -> Option<usize> {
+        let kind = if self.is_multisampled() {
+            AttachmentKind::Resolve
+        } else {
+            AttachmentKind::Color
+        };
+        self.attachments
+            .iter()
+            .enumerate()
+            .filter(|(_, attachment)| attachment.kind == kind)
+            .nth(color_index)
+            .map(|(index, _)| index)
+    }
+}
+
+/// Identifies a render target added to a set.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct TargetId(u32);
+
+/// Render target and its resources, if built.
+struct Target<T> {
+    description: RenderTargetDescription,
+    layout: RenderTargetLayout,
+    resources: Option<T>,
+    stale: bool,
+}
+
+/// Set of render targets rebuilt when the swapchain or the MSAA setting changes.
+///
+/// `T` holds the images, views and framebuffer of a target. Once the set changed,
+/// `rebuild` destroys the stale resources and creates them from their layout again.
+/// Pipelines rendering into targets using MSAA take the sample count from `msaa`, so that
+/// new pipelines are requested from `PipelineSet` too.
+pub struct RenderTargets<T> {
+    targets: Vec<Target<T>>,
+    swapchain_extent: (u32, u32),
+    msaa: SampleCount,
+    color_sample_counts: vk_sys::VkSampleCountFlags,
+    depth_sample_counts: vk_sys::VkSampleCountFlags,
+}
+
+impl<T> RenderTargets<T> {
+    /// Creates an empty set, without MSAA.
+    ///
+    /// Sample counts come from `framebufferColorSampleCounts` and
+    /// `framebufferDepthSampleCounts` of `VkPhysicalDeviceLimits`.
+    pub fn new(
+        swapchain_extent: (u32, u32),
+        color_sample_counts: vk_sys::VkSampleCountFlags,
+        depth_sample_counts: vk_sys::VkSampleCountFlags,
+    ) -> RenderTargets<T> {
+        RenderTargets {
+            targets: Vec::new(),
+            swapchain_extent,
+            msaa: SampleCount::X1,
+            color_sample_counts,
+            depth_sample_counts,
+        }
+    }
+
+    /// Adds a target, built by the next `rebuild`.
+    pub fn add(
+        &mut self,
+        description: RenderTargetDescription,
+    ) -> Result<TargetId, RenderTargetError> {
+        description.validate(
+            self.msaa,
+            self.color_sample_counts,
+            self.depth_sample_counts,
+        )?;
+        let layout = description.layout(self.swapchain_extent, self.msaa)?;
+        self.targets.push(Target {
+            description,
+            layout,
+            resources: None,
+            stale: true,
+        });
+        Ok(TargetId(self.targets.len() as u32 - 1))
+    }
+
+    /// Returns the sample count selected for MSAA.
+    pub fn msaa(&self) -> SampleCount {
+        self.msaa
+    }
+
+    /// Returns the sample counts supported by color and depth attachments.
+    pub fn supported_msaa(&self) -> Vec<SampleCount> {
+        SampleCount::ALL
+            .iter()
+            .copied()
+            .filter(|samples| {
+                samples.is_supported(self.color_sample_counts & self.depth_sample_counts)
+            })
+            .collect()
+    }
+
+    /// Selects the sample count of targets using MSAA, marking them stale if it changed.
+    ///
+    /// Fails without changing anything if a target does not support it.
+    pub fn set_msaa(&mut self, samples: SampleCount) -> Result<(), RenderTargetError> {
+        if samples == self.msaa {
+            return Ok(());
+        }
+        let mut layouts = Vec::with_capacity(self.targets.len());
+        for target in &self.targets {
+            target.description.validate(
+                samples,
+                self.color_sample_counts,
+                self.depth_sample_counts,
+            )?;
+            layouts.push(target.description.layout(self.swapchain_extent, samples)?);
+        }
+        self.msaa = samples;
+        self.update_layouts(layouts);
+        Ok(())
+    }
+
+    /// Updates the swapchain extent, marking the targets sized after it stale.
+    pub fn resize(&mut self, width: u32, height: u32) -> Result<(), RenderTargetError> {
+        let extent = (width, height);
+        let layouts = self
+            .targets
+            .iter()
+            .map(|target| target.description.layout(extent, self.msaa))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.swapchain_extent = extent;
+        self.update_layouts(layouts);
+        Ok(())
+    }
+
+    /// Returns the layout of a target.
+    pub fn layout(&self, id: TargetId) -> &RenderTargetLayout {
+        &self.targets[id.0 as usize].layout
+    }
+
+    /// Returns the resources of a target, if built.
+    pub fn get(&self, id: TargetId) -> Option<&T> {
+        self.targets[id.0 as usize].resources.as_ref()
+    }
+
+    /// Returns `true` if some targets must be rebuilt.
+    pub fn is_stale(&self) -> bool {
+        self.targets.iter().any(|target| target.stale)
+    }
+
+    /// Rebuilds stale targets, destroying their resources with `destroy` and creating
+    /// them again with `create`, and returns the number of targets built.
+    ///
+    /// The resources being destroyed must not be in use by the device anymore.
+    pub fn rebuild<E, C, D>(&mut self, mut create: C, mut destroy: D) -> Result<usize, E>
+    where
+        C: FnMut(TargetId, &RenderTargetLayout) -> Result<T, E>,
+        D: FnMut(T),
+    {
+        let mut count = 0;
+        for (index, target) in self.targets.iter_mut().enumerate() {
+            if target.stale {
+                if let Some(resources) = target.resources.take() {
+                    destroy(resources);
+                }
+                target.resources = Some(create(TargetId(index as u32), &target.layout)?);
+                target.stale = false;
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    /// Removes all resources, returning them so they can be destroyed.
+    pub fn drain(&mut self) -> impl Iterator<Item = T> + '_ {
+        self.targets.iter_mut().filter_map(|target| {
+            target.stale = true;
+            target.resources.take()
+        })
+    }
+
+    /// Replaces the layouts of targets, marking the changed ones stale.
+    fn update_layouts(&mut self, layouts: Vec<RenderTargetLayout>) {
+        for (target, layout) in self.targets.iter_mut().zip(layouts) {
+            if target.layout != layout {
+                target.layout = layout;
+                target.stale = true;
+            }
+        }
+    }
+}
@@ -0,0 +1,284 @@
+//! Text rendering through sprite batches.
+//!
+//! Glyphs are rendered into an atlas and drawn as sprites. Coverage atlases use the GLSL
+//! shaders `shaders/sprite.vert` and `shaders/text.frag` with
+//! `SpriteBatch::pipeline_description`. Distance field atlases, which stay sharp at any
+//! size, use `shaders/sprite.vert` with `shaders/text_sdf.frag` or
+//! `shaders/text_msdf.frag` and `TextRenderer::distance_field_pipeline_description`.
+
+use crate::{
+    color::LinearRgba,
+    pipeline::{
+        BlendState, DepthState, GraphicsPipelineDescription, PipelineLayoutDescription,
+        PushConstantRange, Shader, ShaderStage,
+    },
+    sprite::{Sprite, SpriteBatch, SpriteVertex, TextureRegion},
+    texture::{MipLevels, TextureDescription, TextureFormat},
+};
+use blazar_font::{AtlasRegion, Font, GlyphAtlas, GlyphKey, GlyphMode, LayoutOptions, TextLayout};
+
+/// Outline and drop shadow of distance field text, pushed as fragment shader constants
+/// after the view-projection matrix.
+///
+/// Widths and offsets are in pixels of the atlas glyphs; outlines and shadows fade out
+/// beyond the distance range.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TextEffects {
+    pub outline_color: [f32; 4],
+    pub shadow_color: [f32; 4],
+    pub shadow_offset: [f32; 2],
+    pub outline_width: f32,
+    /// Distance over which the shadow fades out.
+    pub shadow_softness: f32,
+    /// Distance range of the atlas.
+    pub distance_range: f32,
+}
+
+impl TextEffects {
+    /// Offset in bytes of the push constants.
+    pub const PUSH_CONSTANTS_OFFSET: u32 = 64;
+
+    /// Size in bytes of the push constants.
+    pub const PUSH_CONSTANTS_SIZE: u32 = 52;
+
+    /// Creates effects without outline nor shadow.
+    pub fn new(distance_range: f32) -> TextEffects {
+        TextEffects {
+            outline_color: [0.0; 4],
+            shadow_color: [0.0; 4],
+            shadow_offset: [0.0; 2],
+            outline_width: 0.0,
+            shadow_softness: 0.0,
+            distance_range,
+        }
+    }
+
+    /// Sets the outline.
+    pub fn outline<C: Into<LinearRgba>>(mut self, width: f32, color: C) -> TextEffects {
+        self.outline_width = width;
+        self.outline_color = color.into().to_array();
+        self
+    }
+
+    /// Sets the drop shadow.
+    pub fn shadow<C: Into<LinearRgba>>(
+        mut self,
+        offset: [f32; 2],
+        softness: f32,
+        color: C,
+    ) -> TextEffects {
+        self.shadow_offset = offset;
+        self.shadow_softness = softness;
+        self.shadow_color = color.into().to_array();
+        self
+    }
+}
+
+/// Draws text with glyphs cached in an atlas.
+///
+/// `T` identifies the atlas texture in sprite batches. The caller creates the texture
+/// from `texture_description` and uploads the region returned by `take_dirty_region`
+/// before drawing. When the atlas grows, its texture must be recreated and the sprites
+/// of the frame drawn again, their texture coordinates being relative to its height.
+pub struct TextRenderer<T> {
+    texture: T,
+    atlas: GlyphAtlas,
+    fonts: Vec<Font>,
+}
+
+/// Identifies a font added to a text renderer.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct FontId(u32);
+
+impl<T: Copy + Ord> TextRenderer<T> {
+    /// Creates a renderer with an empty coverage atlas.
+    pub fn new(texture: T, width: u32, height: u32, max_height: u32) -> TextRenderer<T> {
+        TextRenderer::with_mode(texture, GlyphMode::Coverage, width, height, max_height)
+    }
+
+    /// Creates a renderer with an empty atlas of glyphs rendered with `mode`.
+    pub fn with_mode(
+        texture: T,
+        mode: GlyphMode,
+        width: u32,
+        height: u32,
+        max_height: u32,
+    ) -> TextRenderer<T> {
+        TextRenderer {
+            texture,
+            atlas: GlyphAtlas::with_mode(mode, width, height, max_height),
+            fonts: Vec::new(),
+        }
+    }
+
+    /// Adds a font.
+    pub fn add_font(&mut self, font: Font) -> FontId {
+        self.fonts.push(font);
+        FontId(self.fonts.len() as u32 - 1)
+    }
+
+    /// Returns a font.
+    pub fn font(&self, id: FontId) -> &Font {
+        &self.fonts[id.0 as usize]
+    }
+
+    /// Lays out text with a font.
+    pub fn layout(&self, font: FontId, text: &str, options: &LayoutOptions) -> TextLayout {
+        self.font(font).layout(text, options)
+    }
+
+    /// Returns the atlas.
+    pub fn atlas(&self) -> &GlyphAtlas {
+        &self.atlas
+    }
+
+    /// Returns the atlas mutably, typically to clear it.
+    pub fn atlas_mut(&mut self) -> &mut GlyphAtlas {
+        &mut self.atlas
+    }
+
+    /// Returns the texture identifier.
+    pub fn texture(&self) -> T {
+        self.texture
+    }
+
+    /// Sets the texture identifier, typically after recreating the texture.
+    pub fn set_texture(&mut self, texture: T) {
+        self.texture = texture;
+    }
+
+    /// Describes the atlas texture, without mipmaps.
+    pub fn texture_description(&self) -> TextureDescription {
+        let format = match self.atlas.channels() {
+            1 => TextureFormat::R8Unorm,
+            _ => TextureFormat::Rgba8Unorm,
+        };
+        TextureDescription {
+            mip_levels: MipLevels::Count(1),
+            ..TextureDescription::new_2d(self.atlas.width(), self.atlas.height(), format)
+        }
+    }
+
+    /// Returns effects without outline nor shadow, matching the distance range of the
+    /// atlas.
+    pub fn effects(&self) -> TextEffects {
+        TextEffects::new(self.atlas.mode().range())
+    }
+
+    /// Returns the region of the atlas to upload, and its pixels.
+    pub fn take_dirty_region(&mut self) -> Option<(AtlasRegion, Vec<u8>)> {
+        self.atlas
+            .take_dirty_region()
+            .map(|region| (region, self.atlas.region_data(region)))
+    }
+
+    /// Adds the sprites of laid out text, its top-left corner at `position` in pixels.
+    ///
+    /// Returns `false` if some glyphs did not fit in the atlas and were skipped.
+    pub fn draw_layout<C: Into<LinearRgba>>(
+        &mut self,
+        batch: &mut SpriteBatch<T>,
+        font: FontId,
+        layout: &TextLayout,
+        position: [f32; 2],
+        color: C,
+        z: f32,
+    ) -> bool {
+        let color = color.into();
+        let mode = self.atlas.mode();
+        let raster_size = mode.raster_size(layout.size);
+        // Distance fields are rasterized once and scaled.
+        let scale = layout.size / raster_size;
+        let mut complete = true;
+        for glyph in &layout.glyphs {
+            let key = GlyphKey::new(font.0, glyph.glyph, raster_size);
+            let entry = match self.atlas.get_or_insert(key, &self.fonts[font.0 as usize]) {
+                Some(entry) => entry,
+                None => {
+                    complete = false;
+                    continue;
+                }
+            };
+            if entry.width == 0 || entry.height == 0 {
+                continue;
+            }
+            let (x, y) = match mode {
+                // Snaps glyphs to pixels to keep them sharp at a zoom of 1.
+                GlyphMode::Coverage => (
+                    (position[0] + glyph.x).round() + entry.left as f32,
+                    (position[1] + glyph.y).round() - entry.top as f32,
+                ),
+                _ => (
+                    position[0] + glyph.x + entry.left as f32 * scale,
+                    position[1] + glyph.y - entry.top as f32 * scale,
+                ),
+            };
+            let region = TextureRegion::from_pixels(
+                entry.x,
+                entry.y,
+                entry.width,
+                entry.height,
+                self.atlas.width(),
+                self.atlas.height(),
+            );
+            batch.draw(
+                Sprite::new(
+                    self.texture,
+                    [x, y],
+                    [entry.width as f32 * scale, entry.height as f32 * scale],
+                )
+                .origin([0.0, 0.0])
+                .region(region)
+                .tint(color)
+                .z(z),
+            );
+        }
+        complete
+    }
+
+    /// Lays out text and adds its sprites, returning the layout.
+    ///
+    /// This is handy for debug overlays; cache the layout of static text instead.
+    pub fn draw_text<C: Into<LinearRgba>>(
+        &mut self,
+        batch: &mut SpriteBatch<T>,
+        font: FontId,
+        text: &str,
+        options: &LayoutOptions,
+        position: [f32; 2],
+        color: C,
+    ) -> TextLayout {
+        let layout = self.layout(font, text, options);
+        self.draw_layout(batch, font, &layout, position, color, 0.0);
+        layout
+    }
+
+    /// Describes the pipeline of distance field text, alpha-blended without depth
+    /// testing, its fragment shader receiving `TextEffects`.
+    pub fn distance_field_pipeline_description(
+        vertex_shader: Shader,
+        fragment_shader: Shader,
+    ) -> GraphicsPipelineDescription {
+        GraphicsPipelineDescription::new()
+            .shader(vertex_shader)
+            .shader(fragment_shader)
+            .layout(
+                PipelineLayoutDescription::new()
+                    .set_layout(SpriteBatch::<T>::texture_set_layout())
+                    .push_constants(PushConstantRange {
+                        stages: ShaderStage::Vertex as u32,
+                        offset: 0,
+                        size: SpriteBatch::<T>::PUSH_CONSTANTS_SIZE,
+                    })
+                    .push_constants(PushConstantRange {
+                        stages: ShaderStage::Fragment as u32,
+                        offset: TextEffects::PUSH_CONSTANTS_OFFSET,
+                        size: TextEffects::PUSH_CONSTANTS_SIZE,
+                    }),
+            )
+            .vertex_layout(SpriteVertex::layout())
+            .depth(DepthState::DISABLED)
+            .blend(&[BlendState::ALPHA])
+    }
+}
@@ -0,0 +1,277 @@
+//! Texture descriptions, mipmap generation and uploads.
+
+mod staging;
+mod upload;
+
+pub use staging::*;
+pub use upload::*;
+
+use crate::color::{linear_to_srgb, srgb_to_linear};
+use blazar_vk_sys as vk_sys;
+
+/// Texture formats.
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum TextureFormat {
+    R8Unorm = vk_sys::VK_FORMAT_R8_UNORM,
+    Rg8Unorm = vk_sys::VK_FORMAT_R8G8_UNORM,
+    Rgba8Unorm = vk_sys::VK_FORMAT_R8G8B8A8_UNORM,
+    Rgba8Srgb = vk_sys::VK_FORMAT_R8G8B8A8_SRGB,
+    Bgra8Unorm = vk_sys::VK_FORMAT_B8G8R8A8_UNORM,
+    Bgra8Srgb = vk_sys::VK_FORMAT_B8G8R8A8_SRGB,
+    Rgba16Unorm = vk_sys::VK_FORMAT_R16G16B16A16_UNORM,
+    Rgba16Float = vk_sys::VK_FORMAT_R16G16B16A16_SFLOAT,
+    Rgba32Float = vk_sys::VK_FORMAT_R32G32B32A32_SFLOAT,
+}
+
+impl TextureFormat {
+    /// Returns the size in bytes of a texel.
+    pub fn bytes_per_texel(self) -> usize {
+        match self {
+            TextureFormat::R8Unorm => 1,
+            TextureFormat::Rg8Unorm => 2,
+            TextureFormat::Rgba8Unorm
+            | TextureFormat::Rgba8Srgb
+            | TextureFormat::Bgra8Unorm
+            | TextureFormat::Bgra8Srgb => 4,
+            TextureFormat::Rgba16Unorm | TextureFormat::Rgba16Float => 8,
+            TextureFormat::Rgba32Float => 16,
+        }
+    }
+
+    /// Returns `true` if color components are sRGB-encoded.
+    pub fn is_srgb(self) -> bool {
+        matches!(self, TextureFormat::Rgba8Srgb | TextureFormat::Bgra8Srgb)
+    }
+
+    /// Returns the sRGB-encoded variant of this format, if any.
+    pub fn to_srgb(self) -> TextureFormat {
+        match self {
+            TextureFormat::Rgba8Unorm => TextureFormat::Rgba8Srgb,
+            TextureFormat::Bgra8Unorm => TextureFormat::Bgra8Srgb,
+            format => format,
+        }
+    }
+
+    /// Returns the linear variant of this format.
+    pub fn to_linear(self) -> TextureFormat {
+        match self {
+            TextureFormat::Rgba8Srgb => TextureFormat::Rgba8Unorm,
+            TextureFormat::Bgra8Srgb => TextureFormat::Bgra8Unorm,
+            format => format,
+        }
+    }
+}
+
+/// Kinds of textures.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum TextureKind {
+    /// 2D texture, possibly with several array layers.
+    D2,
+    /// Cube map made of 6 square layers ordered +X, -X, +Y, -Y, +Z, -Z.
+    Cube,
+}
+
+/// Numbers of mip levels.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum MipLevels {
+    /// Full chain down to 1x1.
+    Full,
+    /// Given count, clamped to the full chain length.
+    Count(u32),
+}
+
+/// Describes a texture.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct TextureDescription {
+    pub width: u32,
+    pub height: u32,
+    pub format: TextureFormat,
+    pub kind: TextureKind,
+    pub layers: u32,
+    pub mip_levels: MipLevels,
+}
+
+impl TextureDescription {
+    /// Describes a 2D texture with a full mip chain.
+    pub fn new_2d(width: u32, height: u32, format: TextureFormat) -> TextureDescription {
+        TextureDescription {
+            width,
+            height,
+            format,
+            kind: TextureKind::D2,
+            layers: 1,
+            mip_levels: MipLevels::Full,
+        }
+    }
+
+    /// Describes a cube map with a full mip chain.
+    pub fn new_cube(size: u32, format: TextureFormat) -> TextureDescription {
+        TextureDescription {
+            width: size,
+            height: size,
+            format,
+            kind: TextureKind::Cube,
+            layers: 6,
+            mip_levels: MipLevels::Full,
+        }
+    }
+
+    /// Returns the number of mip levels.
+    pub fn mip_level_count(&self) -> u32 {
+        let full = 32 - self.width.max(self.height).max(1).leading_zeros();
+        match self.mip_levels {
+            MipLevels::Full => full,
+            MipLevels::Count(count) => count.max(1).min(full),
+        }
+    }
+
+    /// Returns the extent of a mip level.
+    pub fn mip_extent(&self, level: u32) -> (u32, u32) {
+        ((self.width >> level).max(1), (self.height >> level).max(1))
+    }
+
+    /// Returns the size in bytes of a layer of a mip level.
+    pub fn mip_size(&self, level: u32) -> usize {
+        let (width, height) = self.mip_extent(level);
+        width as usize * height as usize * self.format.bytes_per_texel()
+    }
+
+    /// Returns the image view type.
+    pub fn view_type(&self) -> vk_sys::VkImageViewType {
+        match (self.kind, self.layers) {
+            (TextureKind::Cube, 6) => vk_sys::VK_IMAGE_VIEW_TYPE_CUBE,
+            (TextureKind::Cube, _) => vk_sys::VK_IMAGE_VIEW_TYPE_CUBE_ARRAY,
+            (TextureKind::D2, 1) => vk_sys::VK_IMAGE_VIEW_TYPE_2D,
+            (TextureKind::D2, _) => vk_sys::VK_IMAGE_VIEW_TYPE_2D_ARRAY,
+        }
+    }
+
+    /// Returns the image creation flags.
+    pub fn create_flags(&self) -> vk_sys::VkImageCreateFlags {
+        match self.kind {
+            TextureKind::Cube => vk_sys::VK_IMAGE_CREATE_CUBE_COMPATIBLE_BIT,
+            TextureKind::D2 => 0,
+        }
+    }
+
+    /// Checks that the description is consistent.
+    fn validate(&self) -> Result<(), UploadError> {
+        let valid = self.width > 0
+            && self.height > 0
+            && self.layers > 0
+            && match self.kind {
+                TextureKind::Cube => self.width == self.height && self.layers % 6 == 0,
+                TextureKind::D2 => true,
+            };
+        if valid {
+            Ok(())
+        } else {
+            Err(UploadError::InvalidDescription)
+        }
+    }
+}
+
+/// Ways of generating mip levels.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum MipmapGeneration {
+    /// Each level is blitted from the previous one on the graphics queue.
+    Blit,
+    /// Levels are computed on the CPU and uploaded along with the base level.
+    Cpu,
+    /// Only the base level is uploaded.
+    None,
+}
+
+impl MipmapGeneration {
+    /// Selects the best way of generating mip levels given the
+    /// `optimalTilingFeatures` of the format.
+    pub fn select(
+        format: TextureFormat,
+        features: vk_sys::VkFormatFeatureFlags,
+    ) -> MipmapGeneration {
+        let blit = vk_sys::VK_FORMAT_FEATURE_BLIT_SRC_BIT
+            | vk_sys::VK_FORMAT_FEATURE_BLIT_DST_BIT
+            | vk_sys::VK_FORMAT_FEATURE_SAMPLED_IMAGE_FILTER_LINEAR_BIT;
+        if features & blit == blit {
+            MipmapGeneration::Blit
+        } else if supports_cpu_mipmaps(format) {
+            MipmapGeneration::Cpu
+        } else {
+            MipmapGeneration::None
+        }
+    }
+}
+
+/// Returns `true` if mip levels of the format can be computed on the CPU.
+fn supports_cpu_mipmaps(format: TextureFormat) -> bool {
+    !matches!(
+        format,
+        TextureFormat::Rgba16Float | TextureFormat::Rgba32Float
+    )
+}
+
+/// Computes the next mip level of a layer using a box filter.
+///
+/// sRGB-encoded components are averaged in linear space. Returns `None` if the format is
+/// not supported or the size of `texels` does not match.
+pub fn downsample(
+    format: TextureFormat,
+    width: u32,
+    height: u32,
+    texels: &[u8],
+) -> Option<Vec<u8>> {
+    let (width, height) = (width as usize, height as usize);
+    let texel_size = format.bytes_per_texel();
+    if !supports_cpu_mipmaps(format) || width * height * texel_size != texels.len() {
+        return None;
+    }
+    let (next_width, next_height) = ((width / 2).max(1), (height / 2).max(1));
+    let (channels, wide) = match format {
+        TextureFormat::Rgba16Unorm => (4, true),
+        _ => (texel_size, false),
+    };
+    let srgb_channels = if format.is_srgb() { 3 } else { 0 };
+    let read = |x: usize, y: usize, channel: usize| -> f32 {
+        let offset = (y * width + x) * texel_size;
+        if wide {
+            let offset = offset + channel * 2;
+            f32::from(u16::from_le_bytes([texels[offset], texels[offset + 1]])) / 65535.0
+        } else {
+            let value = f32::from(texels[offset + channel]) / 255.0;
+            if channel < srgb_channels {
+                srgb_to_linear(value)
+            } else {
+                value
+            }
+        }
+    };
+    let mut output = vec![0; next_width * next_height * texel_size];
+    for y in 0..next_height {
+        for x in 0..next_width {
+            let (x0, y0) = ((x * 2).min(width - 1), (y * 2).min(height - 1));
+            let (x1, y1) = ((x * 2 + 1).min(width - 1), (y * 2 + 1).min(height - 1));
+            for channel in 0..channels {
+                let value = (read(x0, y0, channel)
+                    + read(x1, y0, channel)
+                    + read(x0, y1, channel)
+                    + read(x1, y1, channel))
+                    / 4.0;
+                let offset = (y * next_width + x) * texel_size;
+                if wide {
+                    let value = (value * 65535.0 + 0.5) as u16;
+                    let offset = offset + channel * 2;
+                    output[offset..offset + 2].copy_from_slice(&value.to_le_bytes());
+                } else {
+                    let value = if channel < srgb_channels {
+                        linear_to_srgb(value)
+                    } else {
+                        value
+                    };
+                    output[offset + channel] = (value * 255.0 + 0.5) as u8;
+                }
+            }
+        }
+    }
+    Some(output)
+}
@@ -0,0 +1,82 @@
+//! Ring allocator over a persistently mapped staging buffer.
+
+use std::collections::VecDeque;
+
+/// Sub-allocates a staging buffer as a ring.
+///
+/// Allocations made before a submission are released together once the device has
+/// completed that submission.
+pub struct StagingRing {
+    size: u64,
+    head: u64,
+    tail: u64,
+    submissions: VecDeque<(u64, u64)>,
+}
+
+impl StagingRing {
+    /// Creates a ring over a buffer of `size` bytes.
+    pub fn new(size: u64) -> StagingRing {
+        StagingRing {
+            size,
+            head: 0,
+            tail: 0,
+            submissions: VecDeque::new(),
+        }
+    }
+
+    /// Returns the size of the buffer.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Returns the number of bytes in use.
+    pub fn used(&self) -> u64 {
+        self.head - self.tail
+    }
+
+    /// Allocates `length` bytes aligned to `alignment`, returning their offset in the
+    /// buffer, or `None` if there is not enough free space.
+    ///
+    /// Allocations larger than the buffer, or from a ring of size 0, always fail.
+    pub fn allocate(&mut self, length: u64, alignment: u64) -> Option<u64> {
+        if length > self.size || self.size == 0 {
+            return None;
+        }
+        let alignment = alignment.max(1);
+        let mut head = self.head;
+        let mut offset = align_up(head % self.size, alignment);
+        if offset + length > self.size {
+            // Skips the end of the buffer, allocations never wrap around.
+            head += self.size - head % self.size;
+            offset = 0;
+        } else {
+            head += offset - head % self.size;
+        }
+        if head + length - self.tail > self.size {
+            return None;
+        }
+        self.head = head + length;
+        Some(offset)
+    }
+
+    /// Marks allocations made so far as used by a submission.
+    pub fn submit(&mut self, submission: u64) {
+        self.submissions.push_back((submission, self.head));
+    }
+
+    /// Releases allocations of every submission up to `submission` included.
+    pub fn retire(&mut self, submission: u64) {
+        while let Some(&(id, head)) = self.submissions.front() {
+            if id > submission {
+                break;
+            }
+            self.tail = head;
+            self.submissions.pop_front();
+        }
+    }
+}
+
+/// Rounds `value` up to a multiple of `alignment`.
+fn align_up(value: u64, alignment: u64) -> u64 {
+    (value + alignment - 1) / alignment * alignment
+}
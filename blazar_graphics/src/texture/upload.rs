@@ -0,0 +1,345 @@
+//! Batched texture uploads through a staging ring.
+
+use super::{downsample, MipmapGeneration, StagingRing, TextureDescription};
+use blazar_vk_sys as vk_sys;
+
+/// Kinds of texture upload errors.
+#[derive(Debug)]
+pub enum UploadError {
+    InvalidDescription,
+    InvalidData,
+    MipmapsUnsupported,
+    StagingTooSmall,
+    StagingFull,
+}
+
+/// Queue families taking part in uploads.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct UploadQueues {
+    pub transfer_family: u32,
+    pub graphics_family: u32,
+}
+
+/// Range of mip levels and array layers of an image.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ImageSubresourceRange {
+    pub base_mip_level: u32,
+    pub level_count: u32,
+    pub base_array_layer: u32,
+    pub layer_count: u32,
+}
+
+/// Describes an image memory barrier (`VkImageMemoryBarrier`) and the stages it
+/// synchronizes.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ImageBarrier<T> {
+    pub image: T,
+    pub range: ImageSubresourceRange,
+    pub old_layout: vk_sys::VkImageLayout,
+    pub new_layout: vk_sys::VkImageLayout,
+    pub src_access: vk_sys::VkAccessFlags,
+    pub dst_access: vk_sys::VkAccessFlags,
+    pub src_stage: vk_sys::VkPipelineStageFlags,
+    pub dst_stage: vk_sys::VkPipelineStageFlags,
+    pub src_queue_family: u32,
+    pub dst_queue_family: u32,
+}
+
+/// Describes a copy from the staging buffer to an image (`VkBufferImageCopy`).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct BufferImageCopy {
+    pub buffer_offset: u64,
+    pub mip_level: u32,
+    pub base_array_layer: u32,
+    pub layer_count: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Describes a linear blit between two mip levels of an image (`VkImageBlit`).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct MipBlit {
+    pub src_mip_level: u32,
+    pub src_extent: (u32, u32),
+    pub dst_mip_level: u32,
+    pub dst_extent: (u32, u32),
+    pub layer_count: u32,
+}
+
+/// Commands to record for an upload.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum UploadCommand<T> {
+    Barrier(ImageBarrier<T>),
+    Copy { image: T, region: BufferImageCopy },
+    Blit { image: T, blit: MipBlit },
+}
+
+/// Commands of uploads submitted together.
+///
+/// `transfer` commands are recorded on the transfer queue and `graphics` commands on the
+/// graphics queue, waiting for the former through a semaphore.
+#[derive(Debug)]
+pub struct UploadBatch<T> {
+    pub submission: u64,
+    pub transfer: Vec<UploadCommand<T>>,
+    pub graphics: Vec<UploadCommand<T>>,
+}
+
+/// Uploads textures through a staging ring and batches the resulting commands.
+pub struct TextureUploader<T> {
+    ring: StagingRing,
+    queues: UploadQueues,
+    transfer: Vec<UploadCommand<T>>,
+    graphics: Vec<UploadCommand<T>>,
+    next_submission: u64,
+}
+
+impl<T: Copy> TextureUploader<T> {
+    /// Creates an uploader over a staging buffer of `staging_size` bytes.
+    pub fn new(staging_size: u64, queues: UploadQueues) -> TextureUploader<T> {
+        TextureUploader {
+            ring: StagingRing::new(staging_size),
+            queues,
+            transfer: Vec::new(),
+            graphics: Vec::new(),
+            next_submission: 1,
+        }
+    }
+
+    /// Writes texture data into the mapped staging buffer and queues the commands
+    /// uploading it to `image`.
+    ///
+    /// `layers` holds the tightly packed base level of each array layer. On success, the
+    /// image ends up in `VK_IMAGE_LAYOUT_SHADER_READ_ONLY_OPTIMAL`, owned by the graphics
+    /// queue. `StagingFull` means previous submissions must be retired first.
+    pub fn upload(
+        &mut self,
+        staging: &mut [u8],
+        image: T,
+        description: &TextureDescription,
+        layers: &[&[u8]],
+        mipmaps: MipmapGeneration,
+    ) -> Result<(), UploadError> {
+        description.validate()?;
+        if layers.len() != description.layers as usize
+            || layers
+                .iter()
+                .any(|layer| layer.len() != description.mip_size(0))
+        {
+            return Err(UploadError::InvalidData);
+        }
+        let level_count = description.mip_level_count();
+        let uploaded_levels = match mipmaps {
+            MipmapGeneration::Cpu => level_count,
+            _ if level_count == 1 => 1,
+            MipmapGeneration::Blit => 1,
+            MipmapGeneration::None => return Err(UploadError::MipmapsUnsupported),
+        };
+
+        // Computes mip levels on the CPU if needed.
+        let mut levels: Vec<Vec<u8>> = vec![layers.concat()];
+        for level in 1..uploaded_levels {
+            let (width, height) = description.mip_extent(level - 1);
+            let source_size = description.mip_size(level - 1);
+            let mut data = Vec::with_capacity(description.mip_size(level) * layers.len());
+            for source in levels[level as usize - 1].chunks(source_size) {
+                let texels = downsample(description.format, width, height, source)
+                    .ok_or(UploadError::MipmapsUnsupported)?;
+                data.extend_from_slice(&texels);
+            }
+            levels.push(data);
+        }
+
+        // Copies data into the staging buffer, each region starting at a multiple of the
+        // texel size and of 4 bytes, as required on transfer queues.
+        let alignment = description.format.bytes_per_texel().max(4) as u64;
+        let aligned = |size: usize| (size as u64 + alignment - 1) / alignment * alignment;
+        let total: u64 = levels.iter().map(|data| aligned(data.len())).sum();
+        if total > self.ring.size() || staging.len() as u64 != self.ring.size() {
+            return Err(UploadError::StagingTooSmall);
+        }
+        let offset = self
+            .ring
+            .allocate(total, alignment)
+            .ok_or(UploadError::StagingFull)?;
+        let mut region_offset = offset;
+        let all_levels = ImageSubresourceRange {
+            base_mip_level: 0,
+            level_count,
+            base_array_layer: 0,
+            layer_count: description.layers,
+        };
+        self.transfer.push(UploadCommand::Barrier(ImageBarrier {
+            image,
+            range: all_levels,
+            old_layout: vk_sys::VK_IMAGE_LAYOUT_UNDEFINED,
+            new_layout: vk_sys::VK_IMAGE_LAYOUT_TRANSFER_DST_OPTIMAL,
+            src_access: 0,
+            dst_access: vk_sys::VK_ACCESS_TRANSFER_WRITE_BIT,
+            src_stage: vk_sys::VK_PIPELINE_STAGE_TOP_OF_PIPE_BIT,
+            dst_stage: vk_sys::VK_PIPELINE_STAGE_TRANSFER_BIT,
+            src_queue_family: vk_sys::VK_QUEUE_FAMILY_IGNORED,
+            dst_queue_family: vk_sys::VK_QUEUE_FAMILY_IGNORED,
+        }));
+        for (level, data) in levels.iter().enumerate() {
+            let start = region_offset as usize;
+            staging[start..start + data.len()].copy_from_slice(data);
+            let (width, height) = description.mip_extent(level as u32);
+            self.transfer.push(UploadCommand::Copy {
+                image,
+                region: BufferImageCopy {
+                    buffer_offset: region_offset,
+                    mip_level: level as u32,
+                    base_array_layer: 0,
+                    layer_count: description.layers,
+                    width,
+                    height,
+                },
+            });
+            region_offset += aligned(data.len());
+        }
+
+        // Blits mip levels on the graphics queue if needed.
+        if uploaded_levels < level_count {
+            self.transfer_ownership(
+                image,
+                all_levels,
+                vk_sys::VK_IMAGE_LAYOUT_TRANSFER_DST_OPTIMAL,
+                vk_sys::VK_ACCESS_TRANSFER_READ_BIT | vk_sys::VK_ACCESS_TRANSFER_WRITE_BIT,
+                vk_sys::VK_PIPELINE_STAGE_TRANSFER_BIT,
+            );
+            for level in 1..level_count {
+                let previous = ImageSubresourceRange {
+                    base_mip_level: level - 1,
+                    level_count: 1,
+                    ..all_levels
+                };
+                self.graphics.push(UploadCommand::Barrier(ImageBarrier {
+                    image,
+                    range: previous,
+                    old_layout: vk_sys::VK_IMAGE_LAYOUT_TRANSFER_DST_OPTIMAL,
+                    new_layout: vk_sys::VK_IMAGE_LAYOUT_TRANSFER_SRC_OPTIMAL,
+                    src_access: vk_sys::VK_ACCESS_TRANSFER_WRITE_BIT,
+                    dst_access: vk_sys::VK_ACCESS_TRANSFER_READ_BIT,
+                    src_stage: vk_sys::VK_PIPELINE_STAGE_TRANSFER_BIT,
+                    dst_stage: vk_sys::VK_PIPELINE_STAGE_TRANSFER_BIT,
+                    src_queue_family: vk_sys::VK_QUEUE_FAMILY_IGNORED,
+                    dst_queue_family: vk_sys::VK_QUEUE_FAMILY_IGNORED,
+                }));
+                self.graphics.push(UploadCommand::Blit {
+                    image,
+                    blit: MipBlit {
+                        src_mip_level: level - 1,
+                        src_extent: description.mip_extent(level - 1),
+                        dst_mip_level: level,
+                        dst_extent: description.mip_extent(level),
+                        layer_count: description.layers,
+                    },
+                });
+                self.graphics.push(UploadCommand::Barrier(ImageBarrier {
+                    image,
+                    range: previous,
+                    old_layout: vk_sys::VK_IMAGE_LAYOUT_TRANSFER_SRC_OPTIMAL,
+                    new_layout: vk_sys::VK_IMAGE_LAYOUT_SHADER_READ_ONLY_OPTIMAL,
+                    src_access: vk_sys::VK_ACCESS_TRANSFER_READ_BIT,
+                    dst_access: vk_sys::VK_ACCESS_SHADER_READ_BIT,
+                    src_stage: vk_sys::VK_PIPELINE_STAGE_TRANSFER_BIT,
+                    dst_stage: vk_sys::VK_PIPELINE_STAGE_FRAGMENT_SHADER_BIT,
+                    src_queue_family: vk_sys::VK_QUEUE_FAMILY_IGNORED,
+                    dst_queue_family: vk_sys::VK_QUEUE_FAMILY_IGNORED,
+                }));
+            }
+            self.graphics.push(UploadCommand::Barrier(ImageBarrier {
+                image,
+                range: ImageSubresourceRange {
+                    base_mip_level: level_count - 1,
+                    level_count: 1,
+                    ..all_levels
+                },
+                old_layout: vk_sys::VK_IMAGE_LAYOUT_TRANSFER_DST_OPTIMAL,
+                new_layout: vk_sys::VK_IMAGE_LAYOUT_SHADER_READ_ONLY_OPTIMAL,
+                src_access: vk_sys::VK_ACCESS_TRANSFER_WRITE_BIT,
+                dst_access: vk_sys::VK_ACCESS_SHADER_READ_BIT,
+                src_stage: vk_sys::VK_PIPELINE_STAGE_TRANSFER_BIT,
+                dst_stage: vk_sys::VK_PIPELINE_STAGE_FRAGMENT_SHADER_BIT,
+                src_queue_family: vk_sys::VK_QUEUE_FAMILY_IGNORED,
+                dst_queue_family: vk_sys::VK_QUEUE_FAMILY_IGNORED,
+            }));
+        } else {
+            self.transfer_ownership(
+                image,
+                all_levels,
+                vk_sys::VK_IMAGE_LAYOUT_SHADER_READ_ONLY_OPTIMAL,
+                vk_sys::VK_ACCESS_SHADER_READ_BIT,
+                vk_sys::VK_PIPELINE_STAGE_FRAGMENT_SHADER_BIT,
+            );
+        }
+        Ok(())
+    }
+
+    /// Returns the commands queued since the last flush, if any.
+    ///
+    /// The batch's `submission` must be passed to `retire` once the device completed it.
+    pub fn flush(&mut self) -> Option<UploadBatch<T>> {
+        if self.transfer.is_empty() && self.graphics.is_empty() {
+            return None;
+        }
+        let submission = self.next_submission;
+        self.next_submission += 1;
+        self.ring.submit(submission);
+        Some(UploadBatch {
+            submission,
+            transfer: self.transfer.drain(..).collect(),
+            graphics: self.graphics.drain(..).collect(),
+        })
+    }
+
+    /// Releases the staging memory of every batch up to `submission` included.
+    pub fn retire(&mut self, submission: u64) {
+        self.ring.retire(submission);
+    }
+
+    /// Transitions the image out of the transfer layout, moving it from the transfer
+    /// queue family to the graphics one if they differ.
+    fn transfer_ownership(
+        &mut self,
+        image: T,
+        range: ImageSubresourceRange,
+        new_layout: vk_sys::VkImageLayout,
+        dst_access: vk_sys::VkAccessFlags,
+        dst_stage: vk_sys::VkPipelineStageFlags,
+    ) {
+        let barrier = ImageBarrier {
+            image,
+            range,
+            old_layout: vk_sys::VK_IMAGE_LAYOUT_TRANSFER_DST_OPTIMAL,
+            new_layout,
+            src_access: vk_sys::VK_ACCESS_TRANSFER_WRITE_BIT,
+            dst_access,
+            src_stage: vk_sys::VK_PIPELINE_STAGE_TRANSFER_BIT,
+            dst_stage,
+            src_queue_family: vk_sys::VK_QUEUE_FAMILY_IGNORED,
+            dst_queue_family: vk_sys::VK_QUEUE_FAMILY_IGNORED,
+        };
+        if self.queues.transfer_family == self.queues.graphics_family {
+            self.transfer.push(UploadCommand::Barrier(barrier));
+        } else {
+            // Release on the transfer queue, then acquire on the graphics queue.
+            let barrier = ImageBarrier {
+                src_queue_family: self.queues.transfer_family,
+                dst_queue_family: self.queues.graphics_family,
+                ..barrier
+            };
+            self.transfer.push(UploadCommand::Barrier(ImageBarrier {
+                dst_access: 0,
+                dst_stage: vk_sys::VK_PIPELINE_STAGE_BOTTOM_OF_PIPE_BIT,
+                ..barrier
+            }));
+            self.graphics.push(UploadCommand::Barrier(ImageBarrier {
+                src_access: 0,
+                src_stage: vk_sys::VK_PIPELINE_STAGE_TOP_OF_PIPE_BIT,
+                ..barrier
+            }));
+        }
+    }
+}
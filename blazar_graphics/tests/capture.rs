@@ -0,0 +1,102 @@
+use blazar_graphics::{
+    capture::{self, CaptureCommand, CaptureError, FrameCapture},
+    texture::TextureFormat,
+};
+use blazar_image::PixelFormat;
+use blazar_vk_sys as vk_sys;
+use std::{env, fs};
+
+#[test]
+fn readback_rows_are_tightly_packed() {
+    // A 3x2 image of 4-byte texels, whose rows are not a multiple of any usual pitch.
+    assert_eq!(capture::readback_size(3, 2, TextureFormat::Rgba8Unorm), 24);
+    assert_eq!(capture::readback_size(3, 2, TextureFormat::Rgba16Float), 48);
+    let commands =
+        capture::readback_commands(7u32, 3, 2, vk_sys::VK_IMAGE_LAYOUT_PRESENT_SRC_KHR, 256);
+    match commands[1] {
+        CaptureCommand::Copy { image, region } => {
+            assert_eq!(image, 7);
+            assert_eq!(
+                (region.buffer_offset, region.width, region.height),
+                (256, 3, 2)
+            );
+        }
+        command => panic!("unexpected command: {:?}", command),
+    }
+
+    let data: Vec<u8> = (0..24).collect();
+    let image = capture::readback_image(&data, 3, 2, TextureFormat::Rgba8Unorm).unwrap();
+    assert_eq!((image.width(), image.height()), (3, 2));
+    assert_eq!(image.format(), PixelFormat::Rgba8);
+    assert_eq!(&image.data()[12..16], &[12, 13, 14, 255]);
+    assert_eq!(&image.data()[20..24], &[20, 21, 22, 255]);
+    // Rows padded to a pitch are rejected rather than misread.
+    let padded = vec![0; 32];
+    match capture::readback_image(&padded, 3, 2, TextureFormat::Rgba8Unorm) {
+        Err(CaptureError::InvalidData) => {}
+        result => panic!("unexpected result: {:?}", result.map(|image| image.width())),
+    }
+}
+
+#[test]
+fn readback_swizzles_bgra() {
+    let data = [10, 20, 30, 0, 40, 50, 60, 128];
+    for &format in &[TextureFormat::Bgra8Unorm, TextureFormat::Bgra8Srgb] {
+        let image = capture::readback_image(&data, 2, 1, format).unwrap();
+        assert_eq!(image.data(), &[30, 20, 10, 255, 60, 50, 40, 255]);
+    }
+    for &format in &[TextureFormat::Rgba8Unorm, TextureFormat::Rgba8Srgb] {
+        let image = capture::readback_image(&data, 2, 1, format).unwrap();
+        assert_eq!(image.data(), &[10, 20, 30, 255, 40, 50, 60, 255]);
+    }
+}
+
+#[test]
+fn readback_encodes_float_formats_to_srgb() {
+    // 0, 0.5, 1 and 2 as half-precision floats, then as single-precision ones.
+    let halves: Vec<u8> = [0x0000u16, 0x3800, 0x3c00, 0x4000]
+        .iter()
+        .flat_map(|half| half.to_ne_bytes().to_vec())
+        .collect();
+    let image = capture::readback_image(&halves, 1, 1, TextureFormat::Rgba16Float).unwrap();
+    assert_eq!(image.data(), &[0, 188, 255, 255]);
+    let floats: Vec<u8> = [0.0f32, 0.5, 1.0, -1.0]
+        .iter()
+        .flat_map(|float| float.to_bits().to_ne_bytes().to_vec())
+        .collect();
+    let image = capture::readback_image(&floats, 1, 1, TextureFormat::Rgba32Float).unwrap();
+    assert_eq!(image.data(), &[0, 188, 255, 255]);
+    // 16-bit unsigned images are kept linear, only made opaque.
+    let image = capture::readback_image(&halves, 1, 1, TextureFormat::Rgba16Unorm).unwrap();
+    assert_eq!(image.format(), PixelFormat::Rgba16);
+    assert_eq!(image.pixel(0, 0), [0x0000, 0x3800, 0x3c00, 0xffff]);
+}
+
+#[test]
+fn frame_sequence() {
+    let directory = env::temp_dir().join(format!("blazar_capture_{}", std::process::id()));
+    let mut frames = FrameCapture::new();
+    assert!(frames.begin_frame().is_empty());
+    frames.screenshot("shot.png");
+    frames.start_sequence(&directory, "frame", 3).unwrap();
+    assert!(directory.is_dir());
+    assert!(frames.is_recording());
+    let paths: Vec<_> = (0..7).map(|_| frames.begin_frame()).collect();
+    assert_eq!(
+        paths[0],
+        ["shot.png".into(), directory.join("frame000000.png")]
+    );
+    // Every third frame is saved, numbered without gaps.
+    assert!(paths[1].is_empty() && paths[2].is_empty());
+    assert_eq!(paths[3], [directory.join("frame000001.png")]);
+    assert!(paths[4].is_empty() && paths[5].is_empty());
+    assert_eq!(paths[6], [directory.join("frame000002.png")]);
+    frames.stop_sequence();
+    assert!(!frames.is_recording());
+    assert!(frames.begin_frame().is_empty());
+    // An interval of 0 saves every frame.
+    frames.start_sequence(&directory, "", 0).unwrap();
+    assert_eq!(frames.begin_frame(), [directory.join("000000.png")]);
+    assert_eq!(frames.begin_frame(), [directory.join("000001.png")]);
+    fs::remove_dir_all(&directory).unwrap();
+}
@@ -0,0 +1,222 @@
+use blazar_graphics::{
+    color::{linear_to_srgb, srgb_to_linear, Color, Hsla, LinearRgba, Srgba},
+    sprite::Sprite,
+    texture::TextureFormat,
+};
+
+const EPSILON: f32 = 1e-5;
+
+fn close(a: LinearRgba, b: LinearRgba) -> bool {
+    a.to_array()
+        .iter()
+        .zip(b.to_array().iter())
+        .all(|(a, b)| (a - b).abs() < EPSILON)
+}
+
+#[test]
+fn transfer_functions() {
+    assert_eq!(srgb_to_linear(0.0), 0.0);
+    assert_eq!(srgb_to_linear(1.0), 1.0);
+    assert_eq!(linear_to_srgb(0.0), 0.0);
+    assert!((linear_to_srgb(1.0) - 1.0).abs() < EPSILON);
+    // Linear segment and the known mid-gray.
+    assert!((srgb_to_linear(0.04) - 0.04 / 12.92).abs() < EPSILON);
+    assert!((srgb_to_linear(0.5) - 0.214_041).abs() < EPSILON);
+    assert!((linear_to_srgb(0.214_041) - 0.5).abs() < EPSILON);
+    // Every 8-bit value survives a round trip.
+    for value in 0..=255u8 {
+        let encoded = f32::from(value) / 255.0;
+        let round_trip = linear_to_srgb(srgb_to_linear(encoded));
+        assert_eq!((round_trip * 255.0 + 0.5) as u8, value);
+    }
+    // Both pieces meet at the threshold.
+    assert!((srgb_to_linear(0.040_45) - srgb_to_linear(0.040_451)).abs() < 1e-6);
+}
+
+#[test]
+fn conversions() {
+    let color = Color::srgb(0.5, 1.0, 0.0);
+    let linear = color.to_linear();
+    assert!(close(linear, LinearRgba::rgb(0.214_041, 1.0, 0.0)));
+    assert!(close(linear.to_srgba().to_linear(), linear));
+    assert_eq!(Color::LinearRgba(linear).to_linear(), linear);
+    assert_eq!(Color::WHITE.to_linear(), LinearRgba::WHITE);
+    assert_eq!(Color::BLACK.to_linear(), LinearRgba::BLACK);
+    let array: [f32; 4] = Color::srgba(0.5, 0.5, 0.5, 0.25).into();
+    assert!((array[0] - 0.214_041).abs() < EPSILON);
+    assert_eq!(array[3], 0.25);
+    assert_eq!(
+        LinearRgba::from([0.1, 0.2, 0.3, 0.4]).to_array(),
+        [0.1, 0.2, 0.3, 0.4]
+    );
+    assert_eq!(Color::RED.with_alpha(0.5).alpha(), 0.5);
+    assert_eq!(Color::default(), Color::WHITE);
+}
+
+#[test]
+fn hsl() {
+    let cases = [
+        (Hsla::hsl(0.0, 1.0, 0.5), Srgba::rgb(1.0, 0.0, 0.0)),
+        (Hsla::hsl(120.0, 1.0, 0.5), Srgba::rgb(0.0, 1.0, 0.0)),
+        (Hsla::hsl(240.0, 1.0, 0.5), Srgba::rgb(0.0, 0.0, 1.0)),
+        (Hsla::hsl(60.0, 1.0, 0.25), Srgba::rgb(0.5, 0.5, 0.0)),
+        (Hsla::hsl(300.0, 0.5, 0.5), Srgba::rgb(0.75, 0.25, 0.75)),
+        (Hsla::hsl(0.0, 0.0, 0.5), Srgba::rgb(0.5, 0.5, 0.5)),
+    ];
+    for (hsla, srgba) in &cases {
+        let converted = hsla.to_srgba();
+        assert!(close(
+            LinearRgba::from(converted.to_array()),
+            LinearRgba::from(srgba.to_array())
+        ));
+        let back = srgba.to_hsla();
+        assert!((back.hue - hsla.hue).abs() < EPSILON);
+        assert!((back.saturation - hsla.saturation).abs() < EPSILON);
+        assert!((back.lightness - hsla.lightness).abs() < EPSILON);
+    }
+    // Hues wrap around.
+    assert_eq!(
+        Hsla::hsl(480.0, 1.0, 0.5).to_srgba(),
+        Hsla::hsl(120.0, 1.0, 0.5).to_srgba()
+    );
+    assert_eq!(
+        Hsla::hsl(-120.0, 1.0, 0.5).to_srgba(),
+        Hsla::hsl(240.0, 1.0, 0.5).to_srgba()
+    );
+    assert_eq!(
+        Color::hsla(0.0, 1.0, 0.5, 0.5).to_srgba(),
+        Srgba::new(1.0, 0.0, 0.0, 0.5)
+    );
+    assert_eq!(Color::RED.to_hsla(), Hsla::hsl(0.0, 1.0, 0.5));
+}
+
+#[test]
+fn hex() {
+    assert_eq!(
+        Srgba::hex("#ff8000"),
+        Some(Srgba::from_u8(255, 128, 0, 255))
+    );
+    assert_eq!(
+        Srgba::hex("FF800080"),
+        Some(Srgba::from_u8(255, 128, 0, 128))
+    );
+    assert_eq!(Srgba::hex("#f80"), Some(Srgba::from_u8(255, 136, 0, 255)));
+    assert_eq!(Srgba::hex("f808"), Some(Srgba::from_u8(255, 136, 0, 136)));
+    assert_eq!(Srgba::hex("#ff80"), Some(Srgba::from_u8(255, 255, 136, 0)));
+    assert_eq!(Srgba::hex(""), None);
+    assert_eq!(Srgba::hex("#ff800"), None);
+    assert_eq!(Srgba::hex("#gg8000"), None);
+    assert_eq!(Srgba::hex("#+f8000"), None);
+    assert_eq!(Srgba::hex("ff80€"), None);
+    assert_eq!(Srgba::from_u8(255, 128, 0, 255).to_hex(), "#ff8000");
+    assert_eq!(Srgba::from_u8(1, 2, 3, 4).to_hex(), "#01020304");
+    assert_eq!(Color::hex("#000"), Some(Color::BLACK));
+    assert_eq!(
+        Srgba::new(2.0, -1.0, 0.5, 1.0).to_u8_array(),
+        [255, 0, 128, 255]
+    );
+}
+
+#[test]
+fn premultiplied_alpha() {
+    let color = LinearRgba::new(0.5, 1.0, 0.25, 0.5);
+    assert_eq!(color.premultiply(), LinearRgba::new(0.25, 0.5, 0.125, 0.5));
+    assert_eq!(color.premultiply().unpremultiply(), color);
+    assert_eq!(
+        LinearRgba::new(1.0, 1.0, 1.0, 0.0)
+            .premultiply()
+            .unpremultiply(),
+        LinearRgba::TRANSPARENT
+    );
+    // Premultiplication happens on linear components.
+    let premultiplied = Color::srgba(0.5, 0.5, 0.5, 0.5).premultiplied();
+    assert!((premultiplied.red - 0.214_041 * 0.5).abs() < EPSILON);
+}
+
+#[test]
+fn interpolation() {
+    let mid = LinearRgba::BLACK.lerp(LinearRgba::WHITE, 0.5);
+    assert_eq!(mid, LinearRgba::rgb(0.5, 0.5, 0.5));
+    assert!((mid.to_srgba().red - 0.735_357).abs() < EPSILON);
+    assert!((LinearRgba::WHITE.luminance() - 1.0).abs() < EPSILON);
+    assert!(
+        LinearRgba::rgb(0.0, 1.0, 0.0).luminance() > LinearRgba::rgb(1.0, 0.0, 0.0).luminance()
+    );
+}
+
+#[test]
+fn texture_formats() {
+    let color = Color::srgba_u8(255, 128, 0, 64);
+    assert_eq!(
+        color.encode(TextureFormat::Rgba8Srgb),
+        vec![255, 128, 0, 64]
+    );
+    assert_eq!(
+        color.encode(TextureFormat::Bgra8Srgb),
+        vec![0, 128, 255, 64]
+    );
+    // Unorm formats store linear components.
+    assert_eq!(
+        color.encode(TextureFormat::Rgba8Unorm),
+        vec![255, 55, 0, 64]
+    );
+    assert_eq!(
+        color.encode(TextureFormat::Bgra8Unorm),
+        vec![0, 55, 255, 64]
+    );
+    assert_eq!(color.encode(TextureFormat::R8Unorm), vec![255]);
+    assert_eq!(color.encode(TextureFormat::Rg8Unorm), vec![255, 55]);
+    let linear = LinearRgba::new(1.0, 0.5, 0.25, -1.0);
+    let half = linear.encode(TextureFormat::Rgba16Float);
+    assert_eq!(half.len(), 8);
+    assert_eq!(u16::from_ne_bytes([half[0], half[1]]), 0x3c00);
+    assert_eq!(u16::from_ne_bytes([half[2], half[3]]), 0x3800);
+    assert_eq!(u16::from_ne_bytes([half[6], half[7]]), 0xbc00);
+    let wide = linear.encode(TextureFormat::Rgba16Unorm);
+    assert_eq!(u16::from_ne_bytes([wide[0], wide[1]]), 65535);
+    assert_eq!(u16::from_ne_bytes([wide[6], wide[7]]), 0);
+    for format in &[
+        TextureFormat::Rgba8Unorm,
+        TextureFormat::Rgba8Srgb,
+        TextureFormat::Bgra8Unorm,
+        TextureFormat::Bgra8Srgb,
+        TextureFormat::Rgba16Unorm,
+        TextureFormat::Rgba16Float,
+        TextureFormat::Rgba32Float,
+    ] {
+        let texel = color.encode(*format);
+        assert_eq!(texel.len(), format.bytes_per_texel());
+        let decoded = LinearRgba::decode(&texel, *format).unwrap();
+        let expected = color.to_linear();
+        for (a, b) in decoded.to_array().iter().zip(expected.to_array().iter()) {
+            assert!((a - b).abs() < 0.01, "{:?}", format);
+        }
+    }
+    let float = LinearRgba::new(2.0, 0.1, 1e-6, 1.0);
+    let decoded = LinearRgba::decode(
+        &float.encode(TextureFormat::Rgba32Float),
+        TextureFormat::Rgba32Float,
+    );
+    assert_eq!(decoded, Some(float));
+    let decoded = LinearRgba::decode(
+        &float.encode(TextureFormat::Rgba16Float),
+        TextureFormat::Rgba16Float,
+    )
+    .unwrap();
+    assert_eq!(decoded.red, 2.0);
+    assert!((decoded.green - 0.1).abs() < 1e-4);
+    assert!((decoded.blue - 1e-6).abs() < 1e-7);
+    assert_eq!(LinearRgba::decode(&[0; 3], TextureFormat::Rgba8Unorm), None);
+    assert_eq!(
+        LinearRgba::decode(&[255], TextureFormat::R8Unorm),
+        Some(LinearRgba::rgb(1.0, 0.0, 0.0))
+    );
+}
+
+#[test]
+fn tints() {
+    let sprite = Sprite::new(0u32, [0.0; 2], [1.0; 2]).tint(Color::srgb(0.5, 0.5, 0.5));
+    assert!((sprite.tint[0] - 0.214_041).abs() < EPSILON);
+    let sprite = sprite.tint([0.5, 0.5, 0.5, 1.0]);
+    assert_eq!(sprite.tint, [0.5, 0.5, 0.5, 1.0]);
+}
@@ -0,0 +1,99 @@
+use blazar_graphics::compute::{
+    group_count, BufferAccess, ComputeCommand, ComputeQueues, QueueFamily,
+};
+use blazar_vk_sys as vk_sys;
+
+const GRAPHICS: QueueFamily = QueueFamily {
+    flags: vk_sys::VK_QUEUE_GRAPHICS_BIT | vk_sys::VK_QUEUE_COMPUTE_BIT,
+    queue_count: 1,
+};
+const COMPUTE: QueueFamily = QueueFamily {
+    flags: vk_sys::VK_QUEUE_COMPUTE_BIT | vk_sys::VK_QUEUE_TRANSFER_BIT,
+    queue_count: 2,
+};
+const TRANSFER: QueueFamily = QueueFamily {
+    flags: vk_sys::VK_QUEUE_TRANSFER_BIT,
+    queue_count: 1,
+};
+
+#[test]
+fn select_queues() {
+    let families = [TRANSFER, GRAPHICS, COMPUTE];
+    let queues = ComputeQueues::select(&families, true).unwrap();
+    assert_eq!(queues.graphics_family, 1);
+    assert_eq!(queues.compute_family, 2);
+    assert!(queues.is_async());
+    let queues = ComputeQueues::select(&families, false).unwrap();
+    assert_eq!(queues.compute_family, 1);
+    assert!(!queues.is_async());
+    let queues = ComputeQueues::select(&[GRAPHICS], true).unwrap();
+    assert_eq!(queues.compute_family, 0);
+    assert!(ComputeQueues::select(&[TRANSFER, COMPUTE], true).is_none());
+}
+
+#[test]
+fn hand_off_between_families() {
+    let queues = ComputeQueues {
+        graphics_family: 0,
+        compute_family: 1,
+    };
+    let hand_off = queues.hand_off(7, BufferAccess::COMPUTE_WRITE, BufferAccess::VERTEX_READ);
+    let release = hand_off.release.unwrap();
+    assert_eq!(release.buffer, 7);
+    assert_eq!((release.src_queue_family, release.dst_queue_family), (1, 0));
+    assert_eq!(release.src_access, vk_sys::VK_ACCESS_SHADER_WRITE_BIT);
+    assert_eq!(release.dst_access, 0);
+    let acquire = hand_off.acquire;
+    assert_eq!((acquire.src_queue_family, acquire.dst_queue_family), (1, 0));
+    assert_eq!(acquire.src_access, 0);
+    assert_eq!(
+        acquire.dst_access,
+        vk_sys::VK_ACCESS_VERTEX_ATTRIBUTE_READ_BIT
+    );
+    assert_eq!(
+        hand_off.wait_stage,
+        Some(vk_sys::VK_PIPELINE_STAGE_VERTEX_INPUT_BIT)
+    );
+}
+
+#[test]
+fn hand_off_within_family() {
+    let queues = ComputeQueues {
+        graphics_family: 0,
+        compute_family: 0,
+    };
+    let hand_off = queues.hand_off(
+        7,
+        BufferAccess::COMPUTE_WRITE,
+        BufferAccess::DRAW_INDIRECT_READ,
+    );
+    assert!(hand_off.release.is_none());
+    assert!(hand_off.wait_stage.is_none());
+    let barrier = hand_off.acquire;
+    assert_eq!(barrier.src_queue_family, vk_sys::VK_QUEUE_FAMILY_IGNORED);
+    assert_eq!(
+        barrier.src_stage,
+        vk_sys::VK_PIPELINE_STAGE_COMPUTE_SHADER_BIT
+    );
+    assert_eq!(
+        barrier.dst_stage,
+        vk_sys::VK_PIPELINE_STAGE_DRAW_INDIRECT_BIT
+    );
+    assert_eq!(barrier.size, vk_sys::VK_WHOLE_SIZE);
+}
+
+#[test]
+fn dispatch_size() {
+    assert_eq!(group_count(0, 64), 0);
+    assert_eq!(group_count(64, 64), 1);
+    assert_eq!(group_count(65, 64), 2);
+    assert_eq!(group_count(10, 0), 10);
+    assert_eq!(
+        ComputeCommand::<u32>::dispatch([1000, 30, 1], [256, 8, 1]),
+        ComputeCommand::Dispatch {
+            group_count_x: 4,
+            group_count_y: 4,
+            group_count_z: 1,
+        }
+    );
+}
@@ -0,0 +1,220 @@
+use blazar_graphics::debug_draw::{
+    self, DebugDraw, DebugFrame, DebugLabel, DebugStyle, DebugVertex,
+};
+
+const IDENTITY: [[f32; 4]; 4] = [
+    [1.0, 0.0, 0.0, 0.0],
+    [0.0, 1.0, 0.0, 0.0],
+    [0.0, 0.0, 1.0, 0.0],
+    [0.0, 0.0, 0.0, 1.0],
+];
+
+const RED: [f32; 4] = [1.0, 0.0, 0.0, 1.0];
+
+/// Returns the lines of a line list as pairs of positions.
+fn lines(vertices: &[DebugVertex]) -> Vec<([f32; 3], [f32; 3])> {
+    vertices
+        .chunks(2)
+        .map(|line| (line[0].position, line[1].position))
+        .collect()
+}
+
+/// Checks that a line list holds the twelve edges of a box whose corners have the given
+/// coordinates on each axis.
+fn assert_box_edges(vertices: &[DebugVertex], coordinates: [[f32; 2]; 3]) {
+    let lines = lines(vertices);
+    assert_eq!(lines.len(), 12);
+    for axis in 0..3 {
+        let along: Vec<_> = lines
+            .iter()
+            .filter(|(from, to)| from[axis] != to[axis])
+            .collect();
+        assert_eq!(along.len(), 4);
+        for (from, to) in along {
+            assert_eq!([from[axis], to[axis]], coordinates[axis]);
+            for other in (0..3).filter(|&other| other != axis) {
+                assert_eq!(from[other], to[other]);
+                assert!(coordinates[other].contains(&from[other]));
+            }
+        }
+    }
+}
+
+#[test]
+fn flush_and_clear() {
+    let mut draw = DebugDraw::new();
+    let mut frame = DebugFrame::new();
+    draw.line([0.0; 3], [1.0, 0.0, 0.0], RED);
+    draw.line(
+        [0.0; 3],
+        [0.0, 1.0, 0.0],
+        DebugStyle::new(RED).depth_test(false),
+    );
+    draw.rect([0.0, 0.0], [4.0, 2.0], DebugStyle::new(RED).screen());
+    draw.text([5.0, 6.0, 0.0], "label", DebugStyle::new(RED).screen());
+    draw.flush(0.0, &IDENTITY, [100.0, 100.0], &mut frame);
+
+    assert_eq!(
+        frame.world,
+        vec![
+            DebugVertex {
+                position: [0.0; 3],
+                color: RED,
+            },
+            DebugVertex {
+                position: [1.0, 0.0, 0.0],
+                color: RED,
+            },
+        ]
+    );
+    assert_eq!(lines(&frame.overlay), vec![([0.0; 3], [0.0, 1.0, 0.0])]);
+    assert_eq!(
+        lines(&frame.screen),
+        vec![
+            ([0.0, 0.0, 0.0], [4.0, 0.0, 0.0]),
+            ([4.0, 0.0, 0.0], [4.0, 2.0, 0.0]),
+            ([4.0, 2.0, 0.0], [0.0, 2.0, 0.0]),
+            ([0.0, 2.0, 0.0], [0.0, 0.0, 0.0]),
+        ]
+    );
+    assert_eq!(
+        frame.labels,
+        vec![DebugLabel {
+            position: [5.0, 6.0],
+            text: String::from("label"),
+            color: RED,
+        }]
+    );
+
+    // Shapes without a duration are only drawn once.
+    draw.flush(0.0, &IDENTITY, [100.0, 100.0], &mut frame);
+    assert!(frame.is_empty());
+
+    draw.line([0.0; 3], [1.0; 3], DebugStyle::new(RED).duration(10.0));
+    draw.text([0.0; 3], "label", DebugStyle::new(RED).duration(10.0));
+    draw.clear();
+    draw.flush(0.0, &IDENTITY, [100.0, 100.0], &mut frame);
+    assert!(frame.is_empty());
+}
+
+#[test]
+fn duration_expiry() {
+    let mut draw = DebugDraw::new();
+    let mut frame = DebugFrame::new();
+    draw.line([0.0; 3], [1.0; 3], DebugStyle::new(RED).duration(1.0));
+    draw.text([0.0; 3], "label", DebugStyle::new(RED).duration(0.5));
+    draw.line([0.0; 3], [2.0; 3], RED);
+
+    draw.flush(0.25, &IDENTITY, [100.0, 100.0], &mut frame);
+    assert_eq!(frame.world.len(), 4);
+    assert_eq!(frame.labels.len(), 1);
+
+    draw.flush(0.25, &IDENTITY, [100.0, 100.0], &mut frame);
+    assert_eq!(lines(&frame.world), vec![([0.0; 3], [1.0; 3])]);
+    assert_eq!(frame.labels.len(), 1);
+
+    draw.flush(0.25, &IDENTITY, [100.0, 100.0], &mut frame);
+    assert_eq!(frame.world.len(), 2);
+    assert!(frame.labels.is_empty());
+
+    draw.flush(0.25, &IDENTITY, [100.0, 100.0], &mut frame);
+    assert_eq!(frame.world.len(), 2);
+
+    draw.flush(0.25, &IDENTITY, [100.0, 100.0], &mut frame);
+    assert!(frame.is_empty());
+}
+
+#[test]
+fn label_projection() {
+    // Perspective projection with w = -z, the camera looking down the negative z axis.
+    let projection = [
+        [1.0, 0.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0, 0.0],
+        [0.0, 0.0, 0.0, -1.0],
+        [0.0, 0.0, 0.1, 0.0],
+    ];
+    let mut draw = DebugDraw::new();
+    let mut frame = DebugFrame::new();
+    draw.text([0.0, 0.0, -1.0], "center", RED);
+    draw.text([2.0, -2.0, -4.0], "left", RED);
+    draw.text([0.0, 0.0, 1.0], "behind", RED);
+    draw.text([10.0, 20.0, 1.0], "screen", DebugStyle::new(RED).screen());
+    draw.flush(0.0, &projection, [200.0, 100.0], &mut frame);
+
+    let labels: Vec<_> = frame
+        .labels
+        .iter()
+        .map(|label| (label.text.as_str(), label.position))
+        .collect();
+    assert_eq!(
+        labels,
+        vec![
+            ("center", [100.0, 50.0]),
+            ("left", [150.0, 25.0]),
+            ("screen", [10.0, 20.0]),
+        ]
+    );
+}
+
+#[test]
+fn box_lines() {
+    let mut draw = DebugDraw::new();
+    let mut frame = DebugFrame::new();
+    draw.aabb([-1.0, 0.0, 2.0], [3.0, 1.0, 5.0], RED);
+    draw.flush(0.0, &IDENTITY, [100.0, 100.0], &mut frame);
+    assert_box_edges(&frame.world, [[-1.0, 3.0], [0.0, 1.0], [2.0, 5.0]]);
+
+    // Orthographic projection mapping [-2, 2] x [-4, 4] x [0, 8] to clip space.
+    let inverse_view_projection = [
+        [2.0, 0.0, 0.0, 0.0],
+        [0.0, 4.0, 0.0, 0.0],
+        [0.0, 0.0, 8.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ];
+    draw.frustum(&inverse_view_projection, RED);
+    draw.flush(0.0, &IDENTITY, [100.0, 100.0], &mut frame);
+    assert_box_edges(&frame.world, [[-2.0, 2.0], [-4.0, 4.0], [0.0, 8.0]]);
+
+    // Perspective projection, the far plane being twice as large as the near one.
+    let inverse_view_projection = [
+        [1.0, 0.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0, 0.0],
+        [0.0, 0.0, 0.0, -0.5],
+        [0.0, 0.0, 1.0, 1.0],
+    ];
+    draw.frustum(&inverse_view_projection, RED);
+    draw.flush(0.0, &IDENTITY, [100.0, 100.0], &mut frame);
+    let lines = lines(&frame.world);
+    assert_eq!(lines.len(), 12);
+    let mut corners: Vec<[f32; 3]> = lines
+        .iter()
+        .flat_map(|&(from, to)| vec![from, to])
+        .collect();
+    corners.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    corners.dedup();
+    assert_eq!(
+        corners,
+        vec![
+            [-2.0, -2.0, 2.0],
+            [-2.0, 2.0, 2.0],
+            [-1.0, -1.0, 1.0],
+            [-1.0, 1.0, 1.0],
+            [1.0, -1.0, 1.0],
+            [1.0, 1.0, 1.0],
+            [2.0, -2.0, 2.0],
+            [2.0, 2.0, 2.0],
+        ]
+    );
+}
+
+#[test]
+fn global_functions() {
+    let mut frame = DebugFrame::new();
+    debug_draw::line([0.0; 3], [1.0; 3], RED);
+    debug_draw::aabb([0.0; 3], [1.0; 3], DebugStyle::new(RED).depth_test(false));
+    debug_draw::with(|draw| draw.text([1.0, 2.0, 0.0], "label", DebugStyle::new(RED).screen()));
+    debug_draw::flush(0.0, &IDENTITY, [100.0, 100.0], &mut frame);
+    assert_eq!(frame.world.len(), 2);
+    assert_eq!(frame.overlay.len(), 24);
+    assert_eq!(frame.labels.len(), 1);
+}
@@ -0,0 +1,208 @@
+use blazar_graphics::descriptor::{
+    BindlessTable, DescriptorAllocator, DescriptorBackend, DescriptorIndexingSupport,
+    DescriptorMode, DescriptorPoolSize, DescriptorType, FrameDescriptors, TextureIndex,
+};
+use blazar_vk_sys as vk_sys;
+
+/// Pool of the mock backend.
+struct Pool {
+    id: usize,
+}
+
+/// Backend whose pools fail with `error` once `max_sets` sets are allocated from them.
+struct Backend {
+    error: vk_sys::VkResult,
+    pools: Vec<(u32, Vec<DescriptorPoolSize>)>,
+    allocated: Vec<u32>,
+    resets: Vec<usize>,
+    destroyed: Vec<usize>,
+}
+
+impl Backend {
+    fn new(error: vk_sys::VkResult) -> Backend {
+        Backend {
+            error,
+            pools: Vec::new(),
+            allocated: Vec::new(),
+            resets: Vec::new(),
+            destroyed: Vec::new(),
+        }
+    }
+}
+
+impl DescriptorBackend for Backend {
+    type Pool = Pool;
+    type Layout = ();
+    type Set = (usize, u32);
+
+    fn create_pool(
+        &mut self,
+        max_sets: u32,
+        sizes: &[DescriptorPoolSize],
+        _flags: vk_sys::VkDescriptorPoolCreateFlags,
+    ) -> Result<Pool, vk_sys::VkResult> {
+        self.pools.push((max_sets, sizes.to_vec()));
+        self.allocated.push(0);
+        Ok(Pool {
+            id: self.pools.len() - 1,
+        })
+    }
+
+    fn allocate_set(
+        &mut self,
+        pool: &Pool,
+        _layout: &(),
+    ) -> Result<(usize, u32), vk_sys::VkResult> {
+        if self.allocated[pool.id] == self.pools[pool.id].0 {
+            return Err(self.error);
+        }
+        self.allocated[pool.id] += 1;
+        Ok((pool.id, self.allocated[pool.id] - 1))
+    }
+
+    fn reset_pool(&mut self, pool: &Pool) {
+        self.allocated[pool.id] = 0;
+        self.resets.push(pool.id);
+    }
+
+    fn destroy_pool(&mut self, pool: Pool) {
+        self.destroyed.push(pool.id);
+    }
+}
+
+fn sizes() -> Vec<DescriptorPoolSize> {
+    vec![DescriptorPoolSize {
+        descriptor_type: DescriptorType::UniformBuffer,
+        count: 2,
+    }]
+}
+
+#[test]
+fn pool_growth() {
+    for &error in &[
+        vk_sys::VK_ERROR_OUT_OF_POOL_MEMORY,
+        vk_sys::VK_ERROR_FRAGMENTED_POOL,
+    ] {
+        let mut backend = Backend::new(error);
+        let mut allocator = DescriptorAllocator::new(&sizes(), 2);
+        for _ in 0..6 {
+            allocator.allocate(&mut backend, &()).unwrap();
+        }
+        assert_eq!(allocator.pool_count(), 2);
+        assert_eq!(backend.pools[0].0, 2);
+        assert_eq!(backend.pools[0].1[0].count, 4);
+        assert_eq!(backend.pools[1].0, 4);
+        assert_eq!(backend.pools[1].1[0].count, 8);
+        assert_eq!(backend.allocated, [2, 4]);
+
+        allocator.reset(&mut backend);
+        assert_eq!(backend.allocated, [0, 0]);
+        allocator.allocate(&mut backend, &()).unwrap();
+        assert_eq!(allocator.pool_count(), 2);
+
+        allocator.destroy(&mut backend);
+        backend.destroyed.sort();
+        assert_eq!(backend.destroyed, [0, 1]);
+        assert_eq!(allocator.pool_count(), 0);
+    }
+}
+
+#[test]
+fn other_errors_are_returned() {
+    let mut backend = Backend::new(vk_sys::VK_ERROR_OUT_OF_DEVICE_MEMORY);
+    let mut allocator = DescriptorAllocator::new(&sizes(), 1);
+    allocator.allocate(&mut backend, &()).unwrap();
+    assert_eq!(
+        allocator.allocate(&mut backend, &()).unwrap_err(),
+        vk_sys::VK_ERROR_OUT_OF_DEVICE_MEMORY
+    );
+    assert_eq!(allocator.pool_count(), 1);
+}
+
+#[test]
+fn pool_sizes_saturate() {
+    let mut backend = Backend::new(vk_sys::VK_ERROR_OUT_OF_POOL_MEMORY);
+    let sizes = [DescriptorPoolSize {
+        descriptor_type: DescriptorType::SampledImage,
+        count: u32::MAX,
+    }];
+    let mut allocator = DescriptorAllocator::new(&sizes, 8);
+    allocator.allocate(&mut backend, &()).unwrap();
+    assert_eq!(backend.pools[0].1[0].count, u32::MAX);
+}
+
+#[test]
+fn frame_recycling() {
+    let mut backend = Backend::new(vk_sys::VK_ERROR_OUT_OF_POOL_MEMORY);
+    let mut frames = FrameDescriptors::new(2, &sizes(), 4);
+    assert_eq!(frames.allocate(&mut backend, &()).unwrap(), (0, 0));
+    frames.begin_frame(&mut backend);
+    assert_eq!(frames.allocate(&mut backend, &()).unwrap(), (1, 0));
+    assert!(backend.resets.is_empty());
+
+    // Back to the first frame: its pool is reset and reused.
+    frames.begin_frame(&mut backend);
+    assert_eq!(backend.resets, [0]);
+    assert_eq!(frames.allocate(&mut backend, &()).unwrap(), (0, 0));
+    assert_eq!(backend.pools.len(), 2);
+
+    frames.destroy(&mut backend);
+    assert_eq!(backend.destroyed, [0, 1]);
+}
+
+#[test]
+fn bindless_slot_reuse() {
+    let mut table = BindlessTable::new(2, 1, 2);
+    let first = table.allocate_texture().unwrap();
+    let second = table.allocate_texture().unwrap();
+    assert_ne!(first, second);
+    assert_eq!(table.allocate_texture(), None);
+
+    // The slot stays reserved while the frame releasing it may be in flight.
+    table.release_texture(first);
+    table.begin_frame();
+    assert_eq!(table.allocate_texture(), None);
+    table.begin_frame();
+    assert_eq!(table.allocate_texture(), Some(first));
+
+    let buffer = table.allocate_buffer().unwrap();
+    assert_eq!(table.allocate_buffer(), None);
+    table.release_buffer(buffer);
+    table.begin_frame();
+    table.begin_frame();
+    assert_eq!(table.allocate_buffer(), Some(buffer));
+    assert_eq!(first, TextureIndex(0));
+}
+
+#[test]
+fn mode_selection() {
+    let mut support = DescriptorIndexingSupport {
+        shader_sampled_image_array_non_uniform_indexing: true,
+        shader_storage_buffer_array_non_uniform_indexing: true,
+        descriptor_binding_sampled_image_update_after_bind: true,
+        descriptor_binding_storage_buffer_update_after_bind: true,
+        descriptor_binding_partially_bound: true,
+        descriptor_binding_update_unused_while_pending: true,
+        runtime_descriptor_array: true,
+        max_update_after_bind_sampled_images: 100,
+        max_update_after_bind_storage_buffers: 1000,
+    };
+    assert_eq!(
+        DescriptorMode::select(Some(&support), 500, 500),
+        DescriptorMode::Bindless {
+            textures: 100,
+            buffers: 500
+        }
+    );
+    assert_eq!(
+        DescriptorMode::select(None, 500, 500),
+        DescriptorMode::Bindful
+    );
+
+    support.descriptor_binding_update_unused_while_pending = false;
+    assert!(!support.supports_bindless());
+    assert_eq!(
+        DescriptorMode::select(Some(&support), 500, 500),
+        DescriptorMode::Bindful
+    );
+}
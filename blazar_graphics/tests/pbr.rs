@@ -0,0 +1,183 @@
+use blazar_graphics::pbr::{
+    brdf_lut, BakeShader, Environment, EnvironmentBake, Light, MaterialTexture, PbrMaterial,
+    PbrRenderer, SceneUniform, MAX_LIGHTS,
+};
+use blazar_model::{AlphaMode, Material, TextureRef};
+
+fn translation(x: f32, y: f32, z: f32) -> [[f32; 4]; 4] {
+    [
+        [1.0, 0.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [x, y, z, 1.0],
+    ]
+}
+
+#[test]
+fn material_from_model() {
+    let material = Material {
+        base_color_texture: Some(TextureRef::new(3)),
+        normal_texture: Some(TextureRef {
+            texture: 5,
+            tex_coord: 0,
+            scale: 0.5,
+        }),
+        emissive_texture: Some(TextureRef {
+            tex_coord: 1,
+            ..TextureRef::new(7)
+        }),
+        alpha_mode: AlphaMode::Mask(0.25),
+        double_sided: true,
+        ..Material::default()
+    };
+    let mut requested = Vec::new();
+    let material = PbrMaterial::from_model(&material, |texture, kind| {
+        requested.push((texture, kind.format()));
+        texture * 10
+    });
+    assert_eq!(material.get_texture(MaterialTexture::BaseColor), Some(30));
+    assert_eq!(material.get_texture(MaterialTexture::Normal), Some(50));
+    // The second set of texture coordinates is not loaded.
+    assert_eq!(material.get_texture(MaterialTexture::Emissive), None);
+    assert_eq!(
+        requested,
+        vec![
+            (3, MaterialTexture::BaseColor.format()),
+            (5, MaterialTexture::Normal.format()),
+        ]
+    );
+    assert_ne!(
+        MaterialTexture::BaseColor.format(),
+        MaterialTexture::Normal.format()
+    );
+    let uniform = material.uniform();
+    assert_eq!(uniform.flags, [0b101, 1, 1, 0]);
+    assert_eq!(uniform.alpha_cutoff, 0.25);
+    assert_eq!(uniform.factors, [1.0, 1.0, 0.5, 1.0]);
+    assert!(!material.pipeline().blend);
+    assert!(material.pipeline().double_sided);
+}
+
+#[test]
+fn draws_sorted() {
+    let mut renderer = PbrRenderer::<u32, u32>::new();
+    let opaque = renderer.add_material(PbrMaterial::new([1.0; 4]));
+    let masked = renderer.add_material(PbrMaterial::new([1.0; 4]).alpha_mode(AlphaMode::Mask(0.5)));
+    let glass =
+        renderer.add_material(PbrMaterial::new([1.0, 1.0, 1.0, 0.5]).alpha_mode(AlphaMode::Blend));
+    renderer.draw(0, glass, translation(0.0, 0.0, -2.0));
+    renderer.draw(1, masked, translation(0.0, 0.0, -1.0));
+    renderer.draw(2, glass, translation(0.0, 0.0, -8.0));
+    renderer.draw(3, opaque, translation(0.0, 0.0, -4.0));
+    renderer.draw(4, masked, translation(0.0, 0.0, -3.0));
+    renderer.build([0.0; 3]);
+    let meshes: Vec<u32> = renderer.draws().iter().map(|draw| draw.mesh).collect();
+    assert_eq!(meshes, vec![3, 1, 4, 2, 0]);
+    assert_eq!(renderer.opaque_draws().len(), 3);
+    assert_eq!(renderer.blended_draws().len(), 2);
+    renderer.clear();
+    assert!(renderer.draws().is_empty());
+}
+
+#[test]
+fn draws_sorted_with_nan_transforms() {
+    let mut renderer = PbrRenderer::<u32, u32>::new();
+    let glass =
+        renderer.add_material(PbrMaterial::new([1.0, 1.0, 1.0, 0.5]).alpha_mode(AlphaMode::Blend));
+    renderer.draw(0, glass, translation(0.0, 0.0, -2.0));
+    renderer.draw(1, glass, translation(f32::NAN, 0.0, 0.0));
+    renderer.draw(2, glass, translation(0.0, 0.0, -8.0));
+    renderer.draw(3, glass, translation(0.0, 0.0, f32::INFINITY));
+    renderer.draw(4, glass, translation(0.0, -f32::NAN, -1.0));
+    renderer.build([0.0; 3]);
+    let meshes: Vec<u32> = renderer.draws().iter().map(|draw| draw.mesh).collect();
+    assert_eq!(meshes, vec![1, 4, 3, 2, 0]);
+}
+
+#[test]
+fn scene_lights() {
+    let spot = Light::Spot {
+        position: [0.0; 3],
+        direction: [0.0, -2.0, 0.0],
+        color: [1.0; 3],
+        intensity: 10.0,
+        range: Some(5.0),
+        inner_cone_angle: 0.0,
+        outer_cone_angle: std::f32::consts::FRAC_PI_4,
+    }
+    .uniform();
+    assert_eq!(spot.kind, 2);
+    assert_eq!(spot.direction, [0.0, -1.0, 0.0]);
+    assert_eq!(spot.range, 5.0);
+    // Full intensity along the direction, none at the outer cone angle.
+    assert!((spot.cone_scale + spot.cone_offset - 1.0).abs() < 1e-4);
+    let outer = std::f32::consts::FRAC_PI_4.cos();
+    assert!((outer * spot.cone_scale + spot.cone_offset).abs() < 1e-4);
+
+    let mut lights: Vec<Light> = (0..MAX_LIGHTS + 4)
+        .map(|index| Light::Point {
+            position: [index as f32, 0.0, 0.0],
+            color: [1.0; 3],
+            intensity: 1.0,
+            range: None,
+        })
+        .rev()
+        .collect();
+    lights.push(Light::Directional {
+        direction: [0.0, -1.0, 0.0],
+        color: [1.0; 3],
+        intensity: 3.0,
+    });
+    let environment = Environment::new(0, 1, 2, 6);
+    let uniform = SceneUniform::new(
+        translation(0.0, 0.0, 0.0),
+        [0.0; 3],
+        &lights,
+        Some(&environment),
+        None,
+    );
+    assert_eq!(uniform.light_count as usize, MAX_LIGHTS);
+    assert_eq!(uniform.lights[0].kind, 0);
+    assert_eq!(uniform.lights[0].shadow, -1);
+    assert_eq!(uniform.lights[1].position, [0.0; 3]);
+    assert_eq!(
+        uniform.lights[MAX_LIGHTS - 1].position[0],
+        (MAX_LIGHTS - 2) as f32
+    );
+    assert_eq!(uniform.environment_intensity, 1.0);
+    assert_eq!(uniform.prefiltered_levels, 6.0);
+    let uniform = SceneUniform::new::<u32>(translation(0.0, 0.0, 0.0), [0.0; 3], &[], None, None);
+    assert_eq!(uniform.light_count, 0);
+    assert_eq!(uniform.environment_intensity, 0.0);
+}
+
+#[test]
+fn environment_bake() {
+    let bake = EnvironmentBake::new(256);
+    assert_eq!(bake.prefiltered_levels, 6);
+    let passes = bake.passes();
+    assert_eq!(passes.len(), 7);
+    assert_eq!(passes[0].shader, BakeShader::Irradiance);
+    assert_eq!(passes[0].group_counts, [4, 4, 6]);
+    assert_eq!(passes[1].shader, BakeShader::Prefilter);
+    assert_eq!((passes[1].size, passes[1].roughness), (256, 0.0));
+    assert_eq!((passes[6].size, passes[6].roughness), (8, 1.0));
+    assert_eq!(passes[6].group_counts, [1, 1, 6]);
+}
+
+#[test]
+fn brdf_lookup_table() {
+    let size = 16;
+    let lut = brdf_lut(size, 128);
+    assert_eq!(lut.len(), (size * size) as usize);
+    for texel in &lut {
+        assert!(texel[0] >= 0.0 && texel[1] >= 0.0);
+        assert!(texel[0] + texel[1] <= 1.05);
+    }
+    // Smooth surfaces seen head-on reflect all the light.
+    let smooth = lut[(size - 1) as usize];
+    assert!((smooth[0] + smooth[1] - 1.0).abs() < 0.05);
+    // Rough surfaces reflect less light at grazing angles.
+    let rough = lut[((size - 1) * size) as usize];
+    assert!(rough[0] + rough[1] < smooth[0] + smooth[1]);
+}
@@ -0,0 +1,128 @@
+use blazar_graphics::pipeline::{
+    DepthBias, DeviceIdentity, GraphicsPipelineDescription, PipelineCacheError, PipelineCacheFile,
+    PipelineCacheHeader, PipelineDescription, PipelineSet, RasterState,
+};
+use std::{env, fs};
+
+const DEVICE: DeviceIdentity = DeviceIdentity {
+    vendor_id: 0x10de,
+    device_id: 0x2204,
+    pipeline_cache_uuid: [7; 16],
+};
+
+/// Returns pipeline cache data produced by `device`, followed by a driver payload.
+fn cache_data(device: &DeviceIdentity) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(&32u32.to_le_bytes());
+    data.extend_from_slice(&1u32.to_le_bytes());
+    data.extend_from_slice(&device.vendor_id.to_le_bytes());
+    data.extend_from_slice(&device.device_id.to_le_bytes());
+    data.extend_from_slice(&device.pipeline_cache_uuid);
+    data.extend_from_slice(b"payload");
+    data
+}
+
+fn description(constant_factor: f32) -> PipelineDescription {
+    GraphicsPipelineDescription::new()
+        .raster(RasterState {
+            depth_bias: Some(DepthBias {
+                constant_factor,
+                clamp: 0.0,
+                slope_factor: 1.0,
+            }),
+            ..RasterState::default()
+        })
+        .into()
+}
+
+#[test]
+fn float_states_deduplicate_by_bits() {
+    let mut pipelines = PipelineSet::new();
+    for &constant_factor in &[0.0, -0.0, 0.0, f32::NAN, f32::NAN] {
+        let description = description(constant_factor);
+        assert_eq!(description, description.clone());
+        pipelines
+            .get_or_create(&description, |_| Ok::<_, ()>(constant_factor.to_bits()))
+            .unwrap();
+    }
+    assert_eq!(pipelines.len(), 3);
+    assert_ne!(description(0.0), description(-0.0));
+    assert_ne!(description(0.0).key(), description(-0.0).key());
+}
+
+#[test]
+fn cache_headers() {
+    let data = cache_data(&DEVICE);
+    let header = PipelineCacheHeader::parse(&data).unwrap();
+    assert_eq!(header.header_size, 32);
+    assert!(header.is_compatible(&DEVICE));
+
+    // Truncated data, or a header of the wrong size or version.
+    assert_eq!(PipelineCacheHeader::parse(&data[..31]), None);
+    let mut corrupt = data.clone();
+    corrupt[0] = 16;
+    assert_eq!(PipelineCacheHeader::parse(&corrupt), None);
+    corrupt[0] = 0xff;
+    assert_eq!(PipelineCacheHeader::parse(&corrupt), None);
+    let mut corrupt = data;
+    corrupt[4] = 2;
+    assert_eq!(PipelineCacheHeader::parse(&corrupt), None);
+}
+
+#[test]
+fn cache_files() {
+    let directory = env::temp_dir().join(format!("blazar_pipeline_cache_{}", std::process::id()));
+    let path = directory.join("caches").join("pipelines.bin");
+    let file = PipelineCacheFile::new(&path, DEVICE);
+    assert_eq!(file.path(), path);
+    match file.load() {
+        Err(PipelineCacheError::Io(_)) => {}
+        result => panic!("unexpected result {:?}", result),
+    }
+
+    let data = cache_data(&DEVICE);
+    file.save(&data).unwrap();
+    assert_eq!(file.load().unwrap(), data);
+    let temporary = directory.join("caches").join("pipelines.bin.tmp");
+    assert!(!temporary.exists());
+
+    // Invalid data is neither saved nor loaded.
+    match file.save(&data[..20]) {
+        Err(PipelineCacheError::InvalidHeader) => {}
+        result => panic!("unexpected result {:?}", result),
+    }
+    assert_eq!(fs::read(&path).unwrap(), data);
+    fs::write(&path, &data[..20]).unwrap();
+    match file.load() {
+        Err(PipelineCacheError::InvalidHeader) => {}
+        result => panic!("unexpected result {:?}", result),
+    }
+
+    // Data of another vendor, device or driver version is rejected.
+    let others = [
+        DeviceIdentity {
+            vendor_id: 0x1002,
+            ..DEVICE
+        },
+        DeviceIdentity {
+            device_id: 0x2206,
+            ..DEVICE
+        },
+        DeviceIdentity {
+            pipeline_cache_uuid: [8; 16],
+            ..DEVICE
+        },
+    ];
+    for other in &others {
+        match file.save(&cache_data(other)) {
+            Err(PipelineCacheError::IncompatibleDevice) => {}
+            result => panic!("unexpected result {:?}", result),
+        }
+        fs::write(&path, cache_data(other)).unwrap();
+        match file.load() {
+            Err(PipelineCacheError::IncompatibleDevice) => {}
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+    fs::remove_dir_all(&directory).unwrap();
+}
@@ -0,0 +1,141 @@
+use blazar_graphics::{
+    postprocess::{
+        PassImage, PassOutput, PostEffect, PostProcessChain, PostShader, ToneMapOperator,
+        MAX_BLOOM_LEVELS,
+    },
+    target::{RenderTargetDescription, RenderTargets, SampleCount, TargetSize},
+    texture::TextureFormat,
+};
+
+const SAMPLE_COUNTS: u32 = 0x0f;
+
+fn chain() -> (RenderTargets<u32>, PostProcessChain<u32>, PassImage<u32>) {
+    let mut targets = RenderTargets::new((800, 600), SAMPLE_COUNTS, SAMPLE_COUNTS);
+    let scene = targets
+        .add(
+            RenderTargetDescription::new(TargetSize::Swapchain(1))
+                .color(TextureFormat::Rgba16Float),
+        )
+        .unwrap();
+    let chain = PostProcessChain::new(&mut targets, TextureFormat::Rgba16Float).unwrap();
+    (targets, chain, PassImage::Target(scene))
+}
+
+#[test]
+fn empty_chain_copies_scene() {
+    let (_, chain, scene) = chain();
+    let passes = chain.passes(scene);
+    assert_eq!(passes.len(), 1);
+    assert_eq!(passes[0].shader, PostShader::Copy);
+    assert_eq!(passes[0].inputs, vec![scene]);
+    assert_eq!(passes[0].output, PassOutput::Swapchain);
+}
+
+#[test]
+fn effects_chain_in_order() {
+    let (_, mut chain, scene) = chain();
+    chain.push(PostEffect::ToneMapping {
+        operator: ToneMapOperator::Aces,
+        exposure: 1.5,
+    });
+    chain.push(PostEffect::Fxaa);
+    chain.push(PostEffect::Vignette {
+        intensity: 0.5,
+        radius: 0.6,
+        smoothness: 0.4,
+    });
+    chain.push(PostEffect::Gamma(2.2));
+    chain.set_enabled(2, false);
+    let passes = chain.passes(scene);
+    let shaders: Vec<PostShader> = passes.iter().map(|pass| pass.shader).collect();
+    assert_eq!(
+        shaders,
+        vec![PostShader::ToneMapping, PostShader::Fxaa, PostShader::Gamma]
+    );
+    assert_eq!(passes[0].inputs, vec![scene]);
+    assert_eq!(passes[0].parameters, [1.5, 1.0, 0.0, 0.0]);
+    for pair in passes.windows(2) {
+        match pair[0].output {
+            PassOutput::Target(target) => {
+                assert_eq!(pair[1].inputs[0], PassImage::Target(target));
+                assert_ne!(pair[1].output, pair[0].output);
+            }
+            PassOutput::Swapchain => panic!("intermediate pass writes the swapchain"),
+        }
+    }
+    assert_eq!(passes[2].output, PassOutput::Swapchain);
+}
+
+#[test]
+fn bloom_passes() {
+    let (targets, mut chain, scene) = chain();
+    chain.push(PostEffect::Bloom {
+        threshold: 1.0,
+        intensity: 0.3,
+        levels: 3,
+    });
+    chain.push(PostEffect::ColorGrading {
+        lut: 42,
+        strength: 1.0,
+    });
+    let passes = chain.passes(scene);
+    let shaders: Vec<PostShader> = passes.iter().map(|pass| pass.shader).collect();
+    assert_eq!(
+        shaders,
+        vec![
+            PostShader::BloomPrefilter,
+            PostShader::BloomDownsample,
+            PostShader::BloomDownsample,
+            PostShader::BloomUpsample,
+            PostShader::BloomUpsample,
+            PostShader::BloomComposite,
+            PostShader::ColorGrading,
+        ]
+    );
+    let bloom = match passes[0].output {
+        PassOutput::Target(target) => target,
+        PassOutput::Swapchain => panic!("bloom prefilter writes the swapchain"),
+    };
+    assert_eq!(targets.layout(bloom).width, 400);
+    assert_eq!(passes[4].output, PassOutput::Target(bloom));
+    assert!(passes[4].shader.is_additive());
+    assert_eq!(passes[5].inputs, vec![scene, PassImage::Target(bloom)]);
+    assert_eq!(passes[6].inputs[1], PassImage::Texture(42));
+    assert_eq!(passes[6].output, PassOutput::Swapchain);
+
+    if let Some(effect) = chain.effect_mut(0) {
+        *effect = PostEffect::Bloom {
+            threshold: 1.0,
+            intensity: 0.3,
+            levels: 100,
+        };
+    }
+    let downsamples = chain
+        .passes(scene)
+        .iter()
+        .filter(|pass| pass.shader == PostShader::BloomDownsample)
+        .count();
+    assert_eq!(downsamples as u32, MAX_BLOOM_LEVELS - 1);
+}
+
+#[test]
+fn intermediate_targets_resize() {
+    let (mut targets, _chain, _) = chain();
+    targets.rebuild::<(), _, _>(|_, _| Ok(0), |_| {}).unwrap();
+    targets.resize(1024, 768).unwrap();
+    assert!(targets.is_stale());
+    let mut sizes = Vec::new();
+    targets
+        .rebuild::<(), _, _>(
+            |_, layout| {
+                sizes.push((layout.width, layout.height));
+                Ok(0)
+            },
+            |_| {},
+        )
+        .unwrap();
+    assert_eq!(sizes.len() as u32, 3 + MAX_BLOOM_LEVELS);
+    assert!(sizes.contains(&(1024, 768)));
+    assert!(sizes.contains(&(512, 384)));
+    assert!(targets.set_msaa(SampleCount::X4).is_ok());
+}
@@ -0,0 +1,89 @@
+use blazar_graphics::present::{FrameLimiter, PresentMode, PresentSettings};
+use blazar_vk_sys as vk_sys;
+use std::time::{Duration, Instant};
+
+const FIFO_ONLY: [vk_sys::VkPresentModeKHR; 1] = [vk_sys::VK_PRESENT_MODE_FIFO_KHR];
+
+#[test]
+fn fallbacks() {
+    let all = [
+        vk_sys::VK_PRESENT_MODE_IMMEDIATE_KHR,
+        vk_sys::VK_PRESENT_MODE_MAILBOX_KHR,
+        vk_sys::VK_PRESENT_MODE_FIFO_KHR,
+        vk_sys::VK_PRESENT_MODE_FIFO_RELAXED_KHR,
+    ];
+    for mode in &[
+        PresentMode::Immediate,
+        PresentMode::Mailbox,
+        PresentMode::Fifo,
+        PresentMode::FifoRelaxed,
+    ] {
+        assert_eq!(mode.select(&all), *mode);
+        assert_eq!(mode.select(&FIFO_ONLY), PresentMode::Fifo);
+    }
+    let no_mailbox = [
+        vk_sys::VK_PRESENT_MODE_IMMEDIATE_KHR,
+        vk_sys::VK_PRESENT_MODE_FIFO_KHR,
+    ];
+    assert_eq!(
+        PresentMode::Mailbox.select(&no_mailbox),
+        PresentMode::Immediate
+    );
+    assert_eq!(
+        PresentMode::FifoRelaxed.select(&no_mailbox),
+        PresentMode::Fifo
+    );
+    assert_eq!(PresentMode::from_raw(1), Some(PresentMode::Mailbox));
+    assert_eq!(PresentMode::from_raw(1_000_111_000), None);
+    assert!(PresentMode::FifoRelaxed.is_vsync());
+    assert!(!PresentMode::Mailbox.is_vsync());
+}
+
+#[test]
+fn image_counts() {
+    assert_eq!(PresentMode::Fifo.image_count(2, 0), 3);
+    assert_eq!(PresentMode::Mailbox.image_count(1, 0), 3);
+    assert_eq!(PresentMode::Mailbox.image_count(3, 3), 3);
+}
+
+#[test]
+fn runtime_changes_recreate_swapchain() {
+    let mut settings = PresentSettings::default();
+    assert!(settings.needs_recreation());
+    assert_eq!(settings.select(&FIFO_ONLY), PresentMode::Fifo);
+    assert!(!settings.needs_recreation());
+    settings.set_present_mode(PresentMode::Fifo);
+    assert!(!settings.needs_recreation());
+    settings.set_present_mode(PresentMode::Immediate);
+    assert!(settings.needs_recreation());
+    assert_eq!(settings.current(), None);
+    assert_eq!(settings.select(&FIFO_ONLY), PresentMode::Fifo);
+    assert_eq!(settings.requested(), PresentMode::Immediate);
+    assert_eq!(settings.current(), Some(PresentMode::Fifo));
+}
+
+#[test]
+fn frame_limiter() {
+    let mut limiter = FrameLimiter::new(Some(100));
+    assert_eq!(limiter.max_frame_rate(), Some(100));
+    let start = Instant::now();
+    let interval = Duration::from_millis(10);
+    assert_eq!(limiter.delay(start), Duration::from_secs(0));
+    // Early frames wait for their slot.
+    assert_eq!(
+        limiter.delay(start + Duration::from_millis(4)),
+        Duration::from_millis(6)
+    );
+    assert_eq!(limiter.delay(start + interval * 2), Duration::from_secs(0));
+    // Late frames restart the schedule.
+    let late = start + Duration::from_millis(55);
+    assert_eq!(limiter.delay(late), Duration::from_secs(0));
+    assert_eq!(
+        limiter.delay(late + Duration::from_millis(1)),
+        Duration::from_millis(9)
+    );
+
+    limiter.set_max_frame_rate(Some(0));
+    assert_eq!(limiter.max_frame_rate(), None);
+    assert_eq!(limiter.delay(start), Duration::from_secs(0));
+}
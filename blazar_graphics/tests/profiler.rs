@@ -0,0 +1,170 @@
+use blazar_graphics::profiler::{GpuProfiler, ProfilerCommand};
+use blazar_vk_sys as vk_sys;
+
+const EPSILON: f64 = 1e-9;
+
+fn timestamp_query(command: Option<ProfilerCommand>) -> u32 {
+    match command {
+        Some(ProfilerCommand::WriteTimestamp { query, .. }) => query,
+        command => panic!("unexpected command: {:?}", command),
+    }
+}
+
+#[test]
+fn regions_per_frame_in_flight() {
+    let mut profiler = GpuProfiler::new(2, 8, 1.0, 64, 4);
+    assert_eq!(profiler.query_count(), 16);
+    assert_eq!(
+        profiler.begin_frame(),
+        ProfilerCommand::Reset {
+            first_query: 0,
+            query_count: 8
+        }
+    );
+    assert_eq!(
+        profiler.begin_scope("shadows"),
+        Some(ProfilerCommand::WriteTimestamp {
+            stage: vk_sys::VK_PIPELINE_STAGE_TOP_OF_PIPE_BIT,
+            query: 0
+        })
+    );
+    assert_eq!(
+        profiler.end_scope(),
+        Some(ProfilerCommand::WriteTimestamp {
+            stage: vk_sys::VK_PIPELINE_STAGE_BOTTOM_OF_PIPE_BIT,
+            query: 1
+        })
+    );
+    assert_eq!(
+        profiler.begin_frame(),
+        ProfilerCommand::Reset {
+            first_query: 8,
+            query_count: 8
+        }
+    );
+    assert_eq!(timestamp_query(profiler.begin_scope("shadows")), 8);
+    // The first region is reused next, once its results are read.
+    assert_eq!(profiler.pending_queries(), Some((0, 2)));
+}
+
+#[test]
+fn resolves_nested_scopes() {
+    let mut profiler = GpuProfiler::new(1, 8, 2.0, 64, 4);
+    profiler.begin_frame();
+    profiler.begin_scope("frame");
+    profiler.begin_scope("opaque");
+    profiler.end_scope();
+    profiler.begin_scope("transparent");
+    profiler.end_scope();
+    profiler.end_scope();
+    assert_eq!(profiler.pending_queries(), Some((0, 6)));
+    // Ticks of 2 ns.
+    let results = [
+        1_000_000, 6_000_000, 1_000_000, 3_000_000, 3_500_000, 5_500_000,
+    ];
+    assert!(profiler.resolve(&results));
+    assert_eq!(profiler.pending_queries(), None);
+    assert!(!profiler.resolve(&results));
+
+    let frame = profiler.latest().unwrap();
+    assert_eq!(frame.frame, 0);
+    assert!((frame.start - 2.0).abs() < EPSILON);
+    assert!((frame.duration - 10.0).abs() < EPSILON);
+    assert_eq!(frame.scopes.len(), 3);
+    let transparent = frame.scope("transparent").unwrap();
+    assert_eq!(transparent.depth, 1);
+    assert!((transparent.start - 5.0).abs() < EPSILON);
+    assert!((transparent.duration - 4.0).abs() < EPSILON);
+    assert_eq!(frame.scope("frame").unwrap().depth, 0);
+}
+
+#[test]
+fn excludes_unclosed_scopes() {
+    let mut profiler = GpuProfiler::new(2, 8, 1.0, 64, 4);
+    profiler.begin_frame();
+    profiler.begin_scope("shadows");
+    profiler.end_scope();
+    // Left open, its end timestamp is never written.
+    profiler.begin_scope("opaque");
+    profiler.begin_scope("sky");
+    profiler.end_scope();
+    profiler.begin_frame();
+    // Only the queries of `shadows` are read, waiting for the others never completing.
+    assert_eq!(profiler.pending_queries(), Some((0, 2)));
+    assert!(!profiler.resolve(&[1, 2, 3, 4, 5, 6]));
+    assert!(profiler.resolve(&[1_000_000, 3_000_000]));
+    let frame = profiler.latest().unwrap();
+    assert_eq!(frame.scopes.len(), 1);
+    assert!((frame.scope("shadows").unwrap().duration - 2.0).abs() < EPSILON);
+
+    // A frame whose first scope is left open has nothing to read.
+    profiler.begin_scope("opaque");
+    profiler.begin_frame();
+    assert_eq!(profiler.pending_queries(), None);
+}
+
+#[test]
+fn wraps_around_valid_bits() {
+    let mut profiler = GpuProfiler::new(1, 2, 1.0, 8, 1);
+    profiler.begin_frame();
+    profiler.begin_scope("pass");
+    profiler.end_scope();
+    // The counter wrapped from 250 to 4, ignoring the invalid high bits.
+    assert!(profiler.resolve(&[0xff00 | 250, 0xff00 | 4]));
+    let duration = profiler.latest().unwrap().scopes[0].duration;
+    assert!((duration - 10.0 / 1_000_000.0).abs() < EPSILON);
+}
+
+#[test]
+fn skips_scopes_beyond_capacity() {
+    let mut profiler = GpuProfiler::new(1, 2, 1.0, 64, 1);
+    profiler.begin_frame();
+    assert!(profiler.begin_scope("outer").is_some());
+    assert!(profiler.begin_scope("inner").is_none());
+    assert!(profiler.end_scope().is_none());
+    assert_eq!(timestamp_query(profiler.end_scope()), 1);
+    assert!(profiler.end_scope().is_none());
+}
+
+#[test]
+fn disabled_without_valid_bits() {
+    let mut profiler = GpuProfiler::new(2, 8, 1.0, 0, 4);
+    assert!(!profiler.is_enabled());
+    profiler.begin_frame();
+    assert!(profiler.begin_scope("pass").is_none());
+    assert!(profiler.end_scope().is_none());
+    assert_eq!(profiler.pending_queries(), None);
+}
+
+#[test]
+fn rolling_history() {
+    let mut profiler = GpuProfiler::new(1, 2, 1_000_000.0, 64, 2);
+    for duration in 1..=3 {
+        profiler.begin_frame();
+        profiler.begin_scope("pass");
+        profiler.end_scope();
+        assert!(profiler.resolve(&[10, 10 + duration]));
+    }
+    let frames: Vec<u64> = profiler.history().iter().map(|frame| frame.frame).collect();
+    assert_eq!(frames, [1, 2]);
+    assert!((profiler.average("pass").unwrap() - 2.5).abs() < EPSILON);
+    assert_eq!(profiler.average("missing"), None);
+    profiler.clear_history();
+    assert!(profiler.latest().is_none());
+}
+
+#[test]
+fn chrome_trace() {
+    let mut profiler = GpuProfiler::new(1, 4, 1_000.0, 64, 4);
+    profiler.begin_frame();
+    profiler.begin_scope("post \"fx\"");
+    profiler.end_scope();
+    assert!(profiler.resolve(&[5_000, 7_500]));
+    assert_eq!(
+        profiler.to_chrome_trace(),
+        "{\"traceEvents\":[\
+         {\"name\":\"thread_name\",\"ph\":\"M\",\"pid\":0,\"tid\":0,\"args\":{\"name\":\"GPU\"}},\
+         {\"name\":\"post \\\"fx\\\"\",\"cat\":\"gpu\",\"ph\":\"X\",\"pid\":0,\"tid\":0,\
+         \"ts\":0.000,\"dur\":2500.000,\"args\":{\"frame\":0}}]}"
+    );
+}
@@ -0,0 +1,218 @@
+use blazar_event::Event;
+use blazar_graphics::{
+    pipeline::{GraphicsPipelineDescription, PipelineDescription, PipelineSet},
+    present::{PresentMode, PresentSettings},
+    profiler::GpuProfiler,
+    recovery::{DeviceRecovery, RecoveryBackend, RendererObjects},
+    target::{DepthFormat, RenderTargetDescription, RenderTargets},
+    texture::TextureFormat,
+};
+use blazar_vk_sys as vk_sys;
+
+/// Backend whose device creation fails a given number of times, its objects being
+/// numbered after the device generation.
+#[derive(Default)]
+struct Backend {
+    destroyed: u32,
+    created: u32,
+    failures: u32,
+    destroyed_pipelines: Vec<u32>,
+    destroyed_targets: Vec<u32>,
+}
+
+impl RecoveryBackend for Backend {
+    type Pipeline = u32;
+    type Target = u32;
+
+    fn destroy_pipeline(&mut self, pipeline: u32) {
+        self.destroyed_pipelines.push(pipeline);
+    }
+
+    fn destroy_target(&mut self, target: u32) {
+        self.destroyed_targets.push(target);
+    }
+
+    fn destroy_device(&mut self) {
+        self.destroyed += 1;
+    }
+
+    fn create_device(&mut self) -> Result<(), vk_sys::VkResult> {
+        if self.failures > 0 {
+            self.failures -= 1;
+            return Err(vk_sys::VK_ERROR_INITIALIZATION_FAILED);
+        }
+        self.created += 1;
+        Ok(())
+    }
+}
+
+/// Objects of a renderer drawing into a main target.
+struct Renderer {
+    pipelines: PipelineSet<u32>,
+    targets: RenderTargets<u32>,
+    present: PresentSettings,
+    profiler: GpuProfiler,
+}
+
+impl Renderer {
+    fn new() -> Renderer {
+        let mut targets = RenderTargets::new((800, 600), 0x1, 0x1);
+        targets
+            .add(RenderTargetDescription::main(
+                TextureFormat::Bgra8Srgb,
+                DepthFormat::D32Float,
+            ))
+            .unwrap();
+        Renderer {
+            pipelines: PipelineSet::new(),
+            targets,
+            present: PresentSettings::new(PresentMode::Fifo),
+            profiler: GpuProfiler::new(2, 4, 1.0, 64, 4),
+        }
+    }
+
+    fn objects(&mut self) -> RendererObjects<'_, u32, u32> {
+        RendererObjects {
+            pipelines: &mut self.pipelines,
+            targets: &mut self.targets,
+            present: &mut self.present,
+            profiler: &mut self.profiler,
+        }
+    }
+
+    /// Renders a frame with objects of the given device generation, returning the
+    /// number of pipelines and targets created.
+    fn render(&mut self, generation: u32) -> (usize, usize) {
+        let mut pipelines = 0;
+        let description: PipelineDescription = GraphicsPipelineDescription::new().into();
+        self.pipelines
+            .get_or_create(&description, |_| {
+                pipelines += 1;
+                Ok::<_, ()>(generation)
+            })
+            .unwrap();
+        let targets = self
+            .targets
+            .rebuild(|_, _| Ok::<_, ()>(generation), |_| {})
+            .unwrap();
+        if self.present.needs_recreation() {
+            self.present.select(&[vk_sys::VK_PRESENT_MODE_FIFO_KHR]);
+        }
+        self.profiler.begin_frame();
+        self.profiler.begin_scope("frame");
+        self.profiler.end_scope();
+        (pipelines, targets)
+    }
+}
+
+#[test]
+fn check_results() {
+    let mut recovery = DeviceRecovery::new();
+    assert_eq!(recovery.check(vk_sys::VK_SUCCESS), Ok(vk_sys::VK_SUCCESS));
+    assert_eq!(
+        recovery.check(vk_sys::VK_SUBOPTIMAL_KHR),
+        Ok(vk_sys::VK_SUBOPTIMAL_KHR)
+    );
+    assert_eq!(
+        recovery.check(vk_sys::VK_ERROR_OUT_OF_DATE_KHR),
+        Err(vk_sys::VK_ERROR_OUT_OF_DATE_KHR)
+    );
+    assert!(!recovery.is_lost());
+    assert!(recovery.poll_event().is_none());
+}
+
+#[test]
+fn recovers_lost_device() {
+    let mut recovery = DeviceRecovery::new();
+    let mut backend = Backend::default();
+    let mut renderer = Renderer::new();
+    assert_eq!(
+        recovery.recover(&mut backend, renderer.objects()),
+        Ok(false)
+    );
+
+    assert_eq!(
+        recovery.check(vk_sys::VK_ERROR_DEVICE_LOST),
+        Err(vk_sys::VK_ERROR_DEVICE_LOST)
+    );
+    // Further calls failing the same way are a single loss.
+    assert!(recovery.check(vk_sys::VK_ERROR_DEVICE_LOST).is_err());
+    assert!(recovery.is_lost());
+    assert!(matches!(recovery.poll_event(), Some(Event::DeviceLost)));
+    assert!(recovery.poll_event().is_none());
+
+    assert_eq!(recovery.recover(&mut backend, renderer.objects()), Ok(true));
+    assert!(!recovery.is_lost());
+    assert_eq!(recovery.generation(), 1);
+    assert_eq!((backend.destroyed, backend.created), (1, 1));
+    assert!(matches!(recovery.poll_event(), Some(Event::DeviceRestored)));
+}
+
+#[test]
+fn recreates_renderer_objects() {
+    let mut recovery = DeviceRecovery::new();
+    let mut backend = Backend::default();
+    let mut renderer = Renderer::new();
+    assert_eq!(renderer.render(0), (1, 1));
+    assert_eq!(renderer.render(0), (0, 0));
+    renderer.profiler.begin_frame();
+    assert!(renderer.profiler.pending_queries().is_some());
+
+    let _ = recovery.check(vk_sys::VK_ERROR_DEVICE_LOST);
+    assert_eq!(recovery.recover(&mut backend, renderer.objects()), Ok(true));
+    assert_eq!(backend.destroyed_pipelines, [0]);
+    assert_eq!(backend.destroyed_targets, [0]);
+    assert!(renderer.pipelines.is_empty());
+    assert!(renderer.targets.is_stale());
+    assert!(renderer.present.needs_recreation());
+    assert!(renderer.profiler.pending_queries().is_none());
+
+    // Pipelines are requested and targets rebuilt for the new device.
+    assert_eq!(renderer.render(1), (1, 1));
+    assert_eq!(renderer.pipelines.len(), 1);
+    assert!(!renderer.targets.is_stale());
+    assert!(!renderer.present.needs_recreation());
+    // Nothing is destroyed twice.
+    assert_eq!(
+        recovery.recover(&mut backend, renderer.objects()),
+        Ok(false)
+    );
+    assert_eq!(backend.destroyed_pipelines, [0]);
+    assert_eq!(backend.destroyed_targets, [0]);
+}
+
+#[test]
+fn retries_failed_creation() {
+    let mut recovery = DeviceRecovery::new();
+    let mut backend = Backend {
+        failures: 2,
+        ..Backend::default()
+    };
+    let mut renderer = Renderer::new();
+    let _ = recovery.check(vk_sys::VK_ERROR_DEVICE_LOST);
+    for attempt in 1..=2 {
+        assert_eq!(
+            recovery.recover(&mut backend, renderer.objects()),
+            Err(vk_sys::VK_ERROR_INITIALIZATION_FAILED)
+        );
+        assert_eq!(recovery.failed_attempts(), attempt);
+        assert!(recovery.is_lost());
+    }
+    assert_eq!(recovery.recover(&mut backend, renderer.objects()), Ok(true));
+    assert_eq!(recovery.failed_attempts(), 0);
+    // The lost device is only destroyed once.
+    assert_eq!(backend.destroyed, 1);
+}
+
+#[test]
+fn profiler_discards_lost_queries() {
+    let mut profiler = GpuProfiler::new(2, 4, 1.0, 64, 4);
+    profiler.begin_frame();
+    profiler.begin_scope("pass");
+    profiler.end_scope();
+    profiler.begin_frame();
+    assert!(profiler.pending_queries().is_some());
+    profiler.discard_pending();
+    assert!(profiler.pending_queries().is_none());
+    assert!(!profiler.resolve(&[0, 0]));
+}
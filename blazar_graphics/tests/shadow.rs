@@ -0,0 +1,205 @@
+use blazar_graphics::{
+    pbr::{
+        cascade_splits, Light, SceneUniform, ShadowCamera, ShadowKind, ShadowMaps, ShadowSettings,
+        ShadowSupport, ShadowUniform, MAX_POINT_SHADOWS,
+    },
+    texture::TextureKind,
+};
+use std::{f32::consts::FRAC_PI_4, mem};
+
+fn camera(x: f32, z: f32) -> ShadowCamera {
+    ShadowCamera {
+        transform: [
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [x, 1.0, z, 1.0],
+        ],
+        fov_y: FRAC_PI_4,
+        aspect_ratio: 16.0 / 9.0,
+        near: 0.1,
+        far: 1000.0,
+    }
+}
+
+fn transform(matrix: [[f32; 4]; 4], point: [f32; 3]) -> [f32; 4] {
+    let mut result = [0.0; 4];
+    for (row, value) in result.iter_mut().enumerate() {
+        *value = (0..3)
+            .map(|column| matrix[column][row] * point[column])
+            .sum::<f32>()
+            + matrix[3][row];
+    }
+    result
+}
+
+const SUN: Light = Light::Directional {
+    direction: [0.3, -1.0, 0.2],
+    color: [1.0; 3],
+    intensity: 3.0,
+};
+
+#[test]
+fn splits() {
+    let splits = cascade_splits(0.1, 100.0, 4, 0.75);
+    assert_eq!(splits.len(), 4);
+    assert!(splits.windows(2).all(|pair| pair[0] < pair[1]));
+    assert!((splits[3] - 100.0).abs() < 1e-3);
+    let uniform = cascade_splits(20.0, 100.0, 4, 0.0);
+    assert_eq!(uniform, vec![40.0, 60.0, 80.0, 100.0]);
+}
+
+#[test]
+fn stable_cascades() {
+    let settings = ShadowSettings::default().resolution(1024);
+    let mut shadows = ShadowMaps::new(settings);
+    shadows.update(&camera(0.0, 0.0), &[SUN]);
+    assert_eq!(shadows.passes().len(), 4);
+    assert_eq!(shadows.uniform().cascade_count, 4);
+    assert_eq!(shadows.uniform().camera_forward, [0.0, 0.0, -1.0]);
+    let texel_sizes = shadows.uniform().cascade_texel_sizes;
+    shadows.update(&camera(0.37, -2.91), &[SUN]);
+    // The extent of cascades does not change as the camera moves.
+    assert_eq!(shadows.uniform().cascade_texel_sizes, texel_sizes);
+    for pass in shadows.passes() {
+        assert_eq!(pass.kind, ShadowKind::Cascade);
+        // The world origin stays on texel corners.
+        let clip = transform(pass.view_projection, [0.0; 3]);
+        for &coordinate in &clip[..2] {
+            let texel = (coordinate + 1.0) * 0.5 * 1024.0;
+            assert!((texel - texel.round()).abs() < 0.01);
+        }
+    }
+    assert_eq!(shadows.shadow(0), Some(0));
+}
+
+#[test]
+fn spot_and_point_shadows() {
+    let spot = Light::Spot {
+        position: [0.0, 5.0, 0.0],
+        direction: [0.0, -1.0, 0.0],
+        color: [1.0; 3],
+        intensity: 10.0,
+        range: Some(20.0),
+        inner_cone_angle: 0.2,
+        outer_cone_angle: 0.4,
+    };
+    let point = Light::Point {
+        position: [2.0, 1.0, 0.0],
+        color: [1.0; 3],
+        intensity: 10.0,
+        range: Some(10.0),
+    };
+    let mut lights = vec![SUN, SUN, spot];
+    lights.extend((0..MAX_POINT_SHADOWS + 1).map(|_| point));
+    let mut shadows = ShadowMaps::new(ShadowSettings::default().cascade_count(2));
+    shadows.update(&camera(0.0, 0.0), &lights);
+    assert_eq!(shadows.passes().len(), 2 + 1 + 6 * MAX_POINT_SHADOWS);
+    assert_eq!(shadows.shadow(1), None);
+    assert_eq!(shadows.shadow(2), Some(0));
+    assert_eq!(shadows.shadow(6), Some(3));
+    assert_eq!(shadows.shadow(lights.len() - 1), None);
+
+    // The spot light looks down its cone.
+    let pass = shadows.passes()[2];
+    assert_eq!(
+        (pass.kind, pass.light, pass.layer),
+        (ShadowKind::Spot, 2, 0)
+    );
+    let clip = transform(pass.view_projection, [0.0, 0.0, 0.0]);
+    assert!(clip[0].abs() < 1e-4 && clip[1].abs() < 1e-4);
+    let edge = transform(pass.view_projection, [0.4f32.tan() * 5.0, 0.0, 0.0]);
+    assert!(edge[0] / edge[3] < 1.0);
+
+    // Each face of the cube looks down its axis.
+    let faces = &shadows.passes()[3..9];
+    let directions = [
+        [1.0, 0.0, 0.0],
+        [-1.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0],
+        [0.0, -1.0, 0.0],
+        [0.0, 0.0, 1.0],
+        [0.0, 0.0, -1.0],
+    ];
+    for (face, (pass, direction)) in faces.iter().zip(&directions).enumerate() {
+        assert_eq!(
+            (pass.kind, pass.light, pass.layer),
+            (ShadowKind::Point, 3, face as u32)
+        );
+        let target = [
+            2.0 + direction[0] * 5.0,
+            1.0 + direction[1] * 5.0,
+            direction[2] * 5.0,
+        ];
+        let clip = transform(pass.view_projection, target);
+        assert!(clip[0].abs() < 1e-4 && clip[1].abs() < 1e-4);
+        let depth = clip[2] / clip[3];
+        let depths = shadows.uniform().point_depths[0];
+        assert!((depth - (depths[0] + depths[1] / 5.0)).abs() < 1e-4);
+    }
+
+    let uniform = SceneUniform::new::<u32>(
+        camera(0.0, 0.0).transform,
+        [0.0, 1.0, 0.0],
+        &lights,
+        None,
+        Some(&shadows),
+    );
+    let layers: Vec<i32> = uniform.lights[..lights.len()]
+        .iter()
+        .map(|light| light.shadow)
+        .collect();
+    assert_eq!(layers, vec![0, -1, 0, 0, 1, 2, 3, -1]);
+}
+
+#[test]
+fn point_shadows_need_cube_arrays() {
+    let point = Light::Point {
+        position: [2.0, 1.0, 0.0],
+        color: [1.0; 3],
+        intensity: 10.0,
+        range: Some(10.0),
+    };
+    let support = ShadowSupport::default();
+    assert!(!support.supports_point_shadows());
+    let settings = ShadowSettings::default().supported(&support);
+    assert!(!settings.point_shadows);
+    let mut shadows = ShadowMaps::new(settings);
+    shadows.update(&camera(0.0, 0.0), &[SUN, point]);
+    assert!(shadows
+        .passes()
+        .iter()
+        .all(|pass| pass.kind == ShadowKind::Cascade));
+    assert_eq!(shadows.shadow(1), None);
+
+    let support = ShadowSupport {
+        image_cube_array: true,
+    };
+    let settings = ShadowSettings::default().supported(&support);
+    assert!(settings.point_shadows);
+    assert!(
+        !settings
+            .point_shadows(false)
+            .supported(&support)
+            .point_shadows
+    );
+    let mut shadows = ShadowMaps::new(settings);
+    shadows.update(&camera(0.0, 0.0), &[SUN, point]);
+    assert_eq!(shadows.shadow(1), Some(0));
+}
+
+#[test]
+fn maps_and_pipeline() {
+    let shadows = ShadowMaps::new(ShadowSettings::default().cascade_count(9));
+    assert_eq!(shadows.settings().cascade_count, 4);
+    let cascades = shadows.map_description(ShadowKind::Cascade);
+    assert_eq!(
+        (cascades.kind, cascades.size, cascades.layers),
+        (TextureKind::D2, 2048, 4)
+    );
+    let points = shadows.map_description(ShadowKind::Point);
+    assert_eq!(points.kind, TextureKind::Cube);
+    assert_eq!(points.layers, 6 * MAX_POINT_SHADOWS as u32);
+    assert_eq!(mem::size_of::<ShadowUniform>(), 656);
+    assert_eq!(ShadowMaps::PUSH_CONSTANTS_SIZE, 128);
+}
@@ -0,0 +1,83 @@
+use blazar_graphics::sprite::{Sprite, SpriteBatch, SpriteDraw};
+
+/// Returns the x position of the sprites of a built batch, in drawing order.
+fn positions(batch: &SpriteBatch<u32>) -> Vec<f32> {
+    batch
+        .vertices()
+        .chunks(4)
+        .map(|quad| (quad[0].position[0] + quad[2].position[0]) / 2.0)
+        .collect()
+}
+
+#[test]
+fn texture_changes_split_draws() {
+    let mut batch = SpriteBatch::new();
+    batch.draw(Sprite::new(1, [0.0, 0.0], [1.0, 1.0]));
+    batch.draw(Sprite::new(0, [1.0, 0.0], [1.0, 1.0]));
+    batch.draw(Sprite::new(1, [2.0, 0.0], [1.0, 1.0]));
+    batch.draw(Sprite::new(0, [3.0, 0.0], [1.0, 1.0]).z(1.0));
+    batch.draw(Sprite::new(1, [4.0, 0.0], [1.0, 1.0]).z(2.0));
+    batch.build();
+
+    // Sprites sharing a z are grouped by texture, keeping the order they were added in.
+    assert_eq!(positions(&batch), vec![1.0, 0.0, 2.0, 3.0, 4.0]);
+    assert_eq!(
+        batch.draws(),
+        &[
+            SpriteDraw {
+                texture: 0,
+                first_index: 0,
+                index_count: 6,
+            },
+            SpriteDraw {
+                texture: 1,
+                first_index: 6,
+                index_count: 12,
+            },
+            SpriteDraw {
+                texture: 0,
+                first_index: 18,
+                index_count: 6,
+            },
+            SpriteDraw {
+                texture: 1,
+                first_index: 24,
+                index_count: 6,
+            },
+        ]
+    );
+    assert_eq!(batch.indices().len(), 30);
+    assert_eq!(batch.vertices().len(), 20);
+
+    batch.clear();
+    assert!(batch.is_empty());
+    batch.build();
+    assert!(batch.draws().is_empty());
+}
+
+#[test]
+fn z_order() {
+    let mut batch = SpriteBatch::new();
+    let zs = [
+        3.0,
+        f32::NAN,
+        -1.0,
+        f32::INFINITY,
+        0.0,
+        -f32::NAN,
+        -0.0,
+        f32::NEG_INFINITY,
+        -2.5,
+    ];
+    for (index, &z) in zs.iter().enumerate() {
+        batch.draw(Sprite::new(0, [index as f32, 0.0], [1.0, 1.0]).z(z));
+    }
+    batch.build();
+
+    // Zeros of both signs are equal and NaNs come last, the sort being stable.
+    assert_eq!(
+        positions(&batch),
+        vec![7.0, 8.0, 2.0, 4.0, 6.0, 0.0, 3.0, 1.0, 5.0]
+    );
+    assert_eq!(batch.draws().len(), 1);
+}
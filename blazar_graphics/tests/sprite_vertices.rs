@@ -0,0 +1,176 @@
+//! Vertex generation tests of sprite batches.
+//!
+//! The vertices of batches are turned into images by a minimal CPU rasterizer following
+//! the Vulkan rasterization rules, then compared against `tests/sprite_vertices/*.png`,
+//! so that changes to positions, texture coordinates, tints and the camera show up as
+//! image differences. Shaders, pipelines and rendering on a device are not covered. Run
+//! with `BLAZAR_UPDATE_GOLDEN=1` to update the references after an intended change.
+
+use blazar_golden::golden::Golden;
+use blazar_graphics::sprite::{Camera2d, Sprite, SpriteBatch, TextureRegion};
+use blazar_image::{Image, PixelFormat};
+
+/// Returns the checker of the reference images.
+fn golden() -> Golden {
+    Golden::new(
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/sprite_vertices"),
+        concat!(env!("CARGO_MANIFEST_DIR"), "/../target/golden-failures"),
+    )
+}
+
+/// Creates a checkerboard texture of 4x4 cells.
+fn checkerboard(size: u32, first: [u8; 4], second: [u8; 4]) -> Image {
+    let mut data = Vec::with_capacity(size as usize * size as usize * 4);
+    for y in 0..size {
+        for x in 0..size {
+            let even = (x / 4 + y / 4) % 2 == 0;
+            data.extend_from_slice(if even { &first } else { &second });
+        }
+    }
+    Image::from_raw(size, size, PixelFormat::Rgba8, data).unwrap()
+}
+
+/// Transforms a position by a column-major matrix, returning pixel coordinates.
+fn to_pixels(matrix: &[[f32; 4]; 4], position: [f32; 2], width: u32, height: u32) -> [f32; 2] {
+    let x = matrix[0][0] * position[0] + matrix[1][0] * position[1] + matrix[3][0];
+    let y = matrix[0][1] * position[0] + matrix[1][1] * position[1] + matrix[3][1];
+    [
+        (x + 1.0) * 0.5 * width as f32,
+        (y + 1.0) * 0.5 * height as f32,
+    ]
+}
+
+/// Rasterizes the vertices of a built batch into an opaque image, sampling textures with
+/// the nearest filter and blending with straight alpha.
+fn rasterize(
+    batch: &SpriteBatch<usize>,
+    textures: &[Image],
+    camera: &Camera2d,
+    clear: [f32; 4],
+) -> Image {
+    let (width, height) = camera.viewport();
+    let matrix = camera.view_projection();
+    let mut target = vec![clear; width as usize * height as usize];
+    for draw in batch.draws() {
+        let texture = &textures[draw.texture];
+        let indices = &batch.indices()[draw.first_index as usize..][..draw.index_count as usize];
+        for triangle in indices.chunks(3) {
+            let vertices: Vec<_> = triangle
+                .iter()
+                .map(|&index| batch.vertices()[index as usize])
+                .collect();
+            let mut points: Vec<_> = vertices
+                .iter()
+                .map(|vertex| to_pixels(&matrix, vertex.position, width, height))
+                .collect();
+            let mut vertices = vertices;
+            let edge = |a: [f32; 2], b: [f32; 2], p: [f32; 2]| {
+                (b[0] - a[0]) * (p[1] - a[1]) - (b[1] - a[1]) * (p[0] - a[0])
+            };
+            let mut area = edge(points[0], points[1], points[2]);
+            if area == 0.0 {
+                continue;
+            }
+            if area < 0.0 {
+                points.swap(1, 2);
+                vertices.swap(1, 2);
+                area = -area;
+            }
+            // Pixel centers on an edge belong to the triangle only if it is a top or a
+            // left edge.
+            let top_left = |a: [f32; 2], b: [f32; 2]| {
+                let (dx, dy) = (b[0] - a[0], b[1] - a[1]);
+                dy < 0.0 || dy == 0.0 && dx > 0.0
+            };
+            let edges = [(1, 2), (2, 0), (0, 1)];
+            for y in 0..height {
+                for x in 0..width {
+                    let p = [x as f32 + 0.5, y as f32 + 0.5];
+                    let mut weights = [0.0; 3];
+                    let mut inside = true;
+                    for (weight, &(a, b)) in weights.iter_mut().zip(&edges) {
+                        let value = edge(points[a], points[b], p);
+                        inside &= value > 0.0 || value == 0.0 && top_left(points[a], points[b]);
+                        *weight = value / area;
+                    }
+                    if !inside {
+                        continue;
+                    }
+                    let mut uv = [0.0; 2];
+                    let mut color = [0.0; 4];
+                    for (vertex, weight) in vertices.iter().zip(&weights) {
+                        for (component, value) in uv.iter_mut().zip(&vertex.uv) {
+                            *component += value * weight;
+                        }
+                        for (component, value) in color.iter_mut().zip(&vertex.color) {
+                            *component += value * weight;
+                        }
+                    }
+                    let u = ((uv[0] * texture.width() as f32) as u32).min(texture.width() - 1);
+                    let v = ((uv[1] * texture.height() as f32) as u32).min(texture.height() - 1);
+                    let texel = texture.pixel(u, v);
+                    let source: Vec<f32> = texel
+                        .iter()
+                        .zip(&color)
+                        .map(|(texel, tint)| f32::from(*texel) / 65535.0 * tint)
+                        .collect();
+                    let destination = &mut target[(y * width + x) as usize];
+                    for (channel, value) in destination.iter_mut().enumerate().take(3) {
+                        *value = source[channel] * source[3] + *value * (1.0 - source[3]);
+                    }
+                }
+            }
+        }
+    }
+    let data = target
+        .iter()
+        .flat_map(|pixel| {
+            let rgb = pixel
+                .iter()
+                .take(3)
+                .map(|value| (value * 255.0 + 0.5) as u8);
+            rgb.chain(std::iter::once(255))
+        })
+        .collect();
+    Image::from_raw(width, height, PixelFormat::Rgba8, data).unwrap()
+}
+
+#[test]
+fn sprite_vertices() {
+    let textures = [
+        checkerboard(16, [255, 255, 255, 255], [40, 40, 40, 255]),
+        checkerboard(16, [255, 200, 0, 255], [0, 0, 0, 0]),
+    ];
+    let mut batch = SpriteBatch::new();
+    batch.draw(Sprite::new(0, [4.0, 4.0], [32.0, 32.0]).origin([0.0, 0.0]));
+    batch.draw(
+        Sprite::new(1, [40.0, 40.0], [32.0, 24.0])
+            .rotation(0.4)
+            .tint([1.0, 1.0, 1.0, 0.75])
+            .z(1.0),
+    );
+    batch.draw(
+        Sprite::new(0, [48.0, 16.0], [16.0, 16.0])
+            .region(TextureRegion::from_pixels(0, 0, 8, 8, 16, 16).flip_x())
+            .tint([0.2, 0.4, 1.0, 1.0]),
+    );
+    batch.build();
+    let camera = Camera2d::new(64, 64);
+    let image = rasterize(&batch, &textures, &camera, [0.1, 0.1, 0.15, 1.0]);
+    golden().check("sprites", &image).unwrap();
+}
+
+#[test]
+fn camera_vertices() {
+    let textures = [checkerboard(16, [255, 0, 0, 255], [0, 0, 255, 255])];
+    let mut batch = SpriteBatch::new();
+    batch.draw(Sprite::new(0, [0.0, 0.0], [16.0, 16.0]));
+    batch.draw(Sprite::new(0, [20.0, -10.0], [8.0, 8.0]).scale([2.0, 1.0]));
+    batch.build();
+    let mut camera = Camera2d::new(48, 32);
+    camera.position = [4.0, 0.0];
+    camera.zoom = 1.5;
+    camera.rotation = 0.3;
+    let image = rasterize(&batch, &textures, &camera, [0.0, 0.0, 0.0, 1.0]);
+    golden().check("camera", &image).unwrap();
+}
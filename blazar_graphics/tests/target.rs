@@ -0,0 +1,175 @@
+use blazar_graphics::{
+    pipeline::{GraphicsPipelineDescription, PipelineDescription},
+    target::{
+        AttachmentKind, DepthFormat, RenderTargetDescription, RenderTargetError, RenderTargets,
+        SampleCount, TargetSamples, TargetSize,
+    },
+    texture::TextureFormat,
+};
+use blazar_vk_sys as vk_sys;
+
+/// Sample counts of a typical desktop device.
+const SAMPLE_COUNTS: vk_sys::VkSampleCountFlags = vk_sys::VK_SAMPLE_COUNT_1_BIT
+    | vk_sys::VK_SAMPLE_COUNT_2_BIT
+    | vk_sys::VK_SAMPLE_COUNT_4_BIT
+    | vk_sys::VK_SAMPLE_COUNT_8_BIT;
+
+#[test]
+fn sample_counts() {
+    assert_eq!(SampleCount::from_count(4), Some(SampleCount::X4));
+    assert_eq!(SampleCount::from_count(3), None);
+    assert_eq!(SampleCount::X8.count(), 8);
+    assert!(SampleCount::X8.is_supported(SAMPLE_COUNTS));
+    assert!(!SampleCount::X16.is_supported(SAMPLE_COUNTS));
+    assert_eq!(SampleCount::X16.clamp_to(SAMPLE_COUNTS), SampleCount::X8);
+    assert_eq!(SampleCount::X8.clamp_to(0x1 | 0x4), SampleCount::X4);
+    assert_eq!(SampleCount::X2.clamp_to(0), SampleCount::X1);
+}
+
+#[test]
+fn single_sampled_layout() {
+    let description = RenderTargetDescription::new(TargetSize::Swapchain(2))
+        .color(TextureFormat::Rgba16Float)
+        .depth(DepthFormat::D32Float)
+        .sampled_depth(true);
+    let layout = description.layout((1280, 721), SampleCount::X4).unwrap();
+    assert_eq!((layout.width, layout.height), (640, 360));
+    assert!(!layout.is_multisampled());
+    assert_eq!(layout.attachments.len(), 2);
+    let depth = layout
+        .attachments(AttachmentKind::DepthStencil)
+        .next()
+        .unwrap();
+    assert_eq!(depth.format, vk_sys::VK_FORMAT_D32_SFLOAT);
+    assert_ne!(depth.usage & vk_sys::VK_IMAGE_USAGE_SAMPLED_BIT, 0);
+    assert_eq!(layout.attachments(AttachmentKind::Resolve).count(), 0);
+}
+
+#[test]
+fn multisampled_layout_resolves_colors() {
+    let description =
+        RenderTargetDescription::main(TextureFormat::Bgra8Srgb, DepthFormat::D24UnormS8Uint)
+            .color(TextureFormat::Rgba16Float);
+    let layout = description.layout((800, 600), SampleCount::X4).unwrap();
+    assert_eq!(layout.samples, SampleCount::X4);
+    let kinds: Vec<AttachmentKind> = layout.attachments.iter().map(|a| a.kind).collect();
+    assert_eq!(
+        kinds,
+        [
+            AttachmentKind::Color,
+            AttachmentKind::Color,
+            AttachmentKind::DepthStencil,
+            AttachmentKind::Resolve,
+            AttachmentKind::Resolve,
+        ]
+    );
+    for color in layout.attachments(AttachmentKind::Color) {
+        assert_eq!(color.samples, SampleCount::X4);
+        assert_ne!(
+            color.usage & vk_sys::VK_IMAGE_USAGE_TRANSIENT_ATTACHMENT_BIT,
+            0
+        );
+    }
+    let resolve: Vec<_> = layout.attachments(AttachmentKind::Resolve).collect();
+    assert_eq!(resolve[0].format, vk_sys::VK_FORMAT_B8G8R8A8_SRGB);
+    assert_eq!(resolve[1].samples, SampleCount::X1);
+    assert!(DepthFormat::D24UnormS8Uint.has_stencil());
+}
+
+#[test]
+fn invalid_descriptions() {
+    assert!(matches!(
+        RenderTargetDescription::new(TargetSize::Fixed(64, 64)).layout((1, 1), SampleCount::X1),
+        Err(RenderTargetError::NoAttachments)
+    ));
+    assert!(matches!(
+        RenderTargetDescription::new(TargetSize::Fixed(0, 64))
+            .color(TextureFormat::Rgba8Unorm)
+            .layout((1, 1), SampleCount::X1),
+        Err(RenderTargetError::InvalidSize)
+    ));
+    let description = RenderTargetDescription::new(TargetSize::Fixed(64, 64))
+        .depth(DepthFormat::D16Unorm)
+        .samples(TargetSamples::Fixed(SampleCount::X8));
+    assert!(description
+        .validate(SampleCount::X1, SAMPLE_COUNTS, 0x1 | 0x4)
+        .is_err());
+    assert!(description
+        .validate(SampleCount::X1, 0x1, SAMPLE_COUNTS)
+        .is_ok());
+}
+
+type Resources = (u32, SampleCount);
+
+/// Rebuilds stale targets, their resources being their width and sample count.
+fn build(targets: &mut RenderTargets<Resources>, destroyed: &mut Vec<Resources>) -> usize {
+    targets
+        .rebuild::<(), _, _>(
+            |_, layout| Ok((layout.width, layout.samples)),
+            |resources| destroyed.push(resources),
+        )
+        .unwrap()
+}
+
+#[test]
+fn msaa_toggle_rebuilds_targets() {
+    let mut targets = RenderTargets::new((800, 600), SAMPLE_COUNTS, SAMPLE_COUNTS);
+    let main = targets
+        .add(RenderTargetDescription::main(
+            TextureFormat::Bgra8Srgb,
+            DepthFormat::D32Float,
+        ))
+        .unwrap();
+    let shadows = targets
+        .add(
+            RenderTargetDescription::new(TargetSize::Fixed(1024, 1024))
+                .depth(DepthFormat::D32Float),
+        )
+        .unwrap();
+    assert_eq!(
+        targets.supported_msaa(),
+        [
+            SampleCount::X1,
+            SampleCount::X2,
+            SampleCount::X4,
+            SampleCount::X8
+        ]
+    );
+
+    let mut destroyed = Vec::new();
+    assert_eq!(build(&mut targets, &mut destroyed), 2);
+    assert!(!targets.is_stale());
+    assert_eq!(build(&mut targets, &mut destroyed), 0);
+
+    // Only the target using MSAA is rebuilt.
+    targets.set_msaa(SampleCount::X4).unwrap();
+    assert!(targets.is_stale());
+    assert_eq!(build(&mut targets, &mut destroyed), 1);
+    assert_eq!(targets.get(main), Some(&(800, SampleCount::X4)));
+    assert_eq!(targets.get(shadows), Some(&(1024, SampleCount::X1)));
+    assert_eq!(destroyed, [(800, SampleCount::X1)]);
+
+    // Unsupported sample counts leave the set unchanged.
+    assert!(matches!(
+        targets.set_msaa(SampleCount::X16),
+        Err(RenderTargetError::UnsupportedSampleCount(SampleCount::X16))
+    ));
+    assert_eq!(targets.msaa(), SampleCount::X4);
+    assert!(!targets.is_stale());
+
+    // Only the target sized after the swapchain is rebuilt.
+    targets.resize(1920, 1080).unwrap();
+    assert_eq!(build(&mut targets, &mut destroyed), 1);
+    assert_eq!(targets.layout(main).width, 1920);
+    assert_eq!(targets.drain().count(), 2);
+    assert!(targets.is_stale());
+}
+
+#[test]
+fn pipelines_follow_sample_count() {
+    let single = PipelineDescription::from(GraphicsPipelineDescription::new());
+    let multi =
+        PipelineDescription::from(GraphicsPipelineDescription::new().samples(SampleCount::X4));
+    assert_ne!(single, multi);
+    assert_ne!(single.key(), multi.key());
+}
@@ -0,0 +1,65 @@
+use blazar_graphics::{
+    pipeline::{PushConstantRange, Shader, ShaderStage},
+    sprite::SpriteBatch,
+    text::{TextEffects, TextRenderer},
+};
+use std::mem;
+
+/// Returns the offset in bytes of a field of `effects`.
+fn offset<T>(effects: &TextEffects, field: &T) -> usize {
+    field as *const T as usize - effects as *const TextEffects as usize
+}
+
+#[test]
+fn effects_match_the_shader_block() {
+    // The block declared by both distance field shaders, laid out with std430 rules.
+    let block = "layout(push_constant) uniform Effects {\n    \
+                 layout(offset = 64) vec4 outline_color;\n    \
+                 vec4 shadow_color;\n    \
+                 vec2 shadow_offset;\n    \
+                 float outline_width;\n    \
+                 float shadow_softness;\n    \
+                 float distance_range;\n\
+                 } effects;";
+    for source in &[
+        include_str!("../shaders/text_sdf.frag"),
+        include_str!("../shaders/text_msdf.frag"),
+    ] {
+        assert!(source.contains(block));
+    }
+    assert_eq!(TextEffects::PUSH_CONSTANTS_OFFSET, 64);
+    assert_eq!(TextEffects::PUSH_CONSTANTS_SIZE, 52);
+    assert_eq!(mem::size_of::<TextEffects>(), 52);
+    let effects = TextEffects::new(4.0);
+    assert_eq!(offset(&effects, &effects.outline_color), 0);
+    assert_eq!(offset(&effects, &effects.shadow_color), 16);
+    assert_eq!(offset(&effects, &effects.shadow_offset), 32);
+    assert_eq!(offset(&effects, &effects.outline_width), 40);
+    assert_eq!(offset(&effects, &effects.shadow_softness), 44);
+    assert_eq!(offset(&effects, &effects.distance_range), 48);
+}
+
+#[test]
+fn distance_field_push_constants() {
+    let description = TextRenderer::<u32>::distance_field_pipeline_description(
+        Shader::new(ShaderStage::Vertex, vec![]),
+        Shader::new(ShaderStage::Fragment, vec![]),
+    );
+    assert_eq!(
+        description.layout.push_constants,
+        [
+            PushConstantRange {
+                stages: ShaderStage::Vertex as u32,
+                offset: 0,
+                size: SpriteBatch::<u32>::PUSH_CONSTANTS_SIZE,
+            },
+            PushConstantRange {
+                stages: ShaderStage::Fragment as u32,
+                offset: 64,
+                size: 52,
+            },
+        ]
+    );
+    // The fragment constants follow the view-projection matrix of the vertex shader.
+    assert_eq!(SpriteBatch::<u32>::PUSH_CONSTANTS_SIZE, 64);
+}
@@ -0,0 +1,104 @@
+use blazar_graphics::texture::{
+    MipmapGeneration, StagingRing, TextureDescription, TextureFormat, TextureUploader,
+    UploadCommand, UploadQueues,
+};
+
+#[test]
+fn odd_sized_regions_are_aligned() {
+    let queues = UploadQueues {
+        transfer_family: 1,
+        graphics_family: 0,
+    };
+    let mut uploader = TextureUploader::new(128, queues);
+    let mut staging = vec![0; 128];
+    let description = TextureDescription::new_2d(5, 5, TextureFormat::R8Unorm);
+    let texels: Vec<u8> = (0..25).collect();
+    uploader
+        .upload(
+            &mut staging,
+            0u32,
+            &description,
+            &[&texels],
+            MipmapGeneration::Cpu,
+        )
+        .unwrap();
+    let batch = uploader.flush().unwrap();
+    let regions: Vec<_> = batch
+        .transfer
+        .iter()
+        .filter_map(|command| match command {
+            UploadCommand::Copy { region, .. } => Some(*region),
+            _ => None,
+        })
+        .collect();
+    let offsets: Vec<u64> = regions.iter().map(|region| region.buffer_offset).collect();
+    assert_eq!(offsets, vec![0, 28, 32]);
+    assert_eq!(
+        regions
+            .iter()
+            .map(|region| (region.width, region.height))
+            .collect::<Vec<_>>(),
+        vec![(5, 5), (2, 2), (1, 1)]
+    );
+    assert_eq!(&staging[..25], &texels[..]);
+
+    // The next upload starts after the padding of the last region.
+    uploader
+        .upload(
+            &mut staging,
+            1u32,
+            &description,
+            &[&texels],
+            MipmapGeneration::Cpu,
+        )
+        .unwrap();
+    let batch = uploader.flush().unwrap();
+    let offsets: Vec<u64> = batch
+        .transfer
+        .iter()
+        .filter_map(|command| match command {
+            UploadCommand::Copy { region, .. } => Some(region.buffer_offset),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(offsets, vec![36, 64, 68]);
+}
+
+#[test]
+fn staging_ring_wraps_around() {
+    let mut ring = StagingRing::new(64);
+    assert_eq!(ring.allocate(24, 1), Some(0));
+    assert_eq!(ring.allocate(20, 16), Some(32));
+    ring.submit(0);
+    assert_eq!(ring.allocate(8, 4), Some(52));
+    ring.submit(1);
+    assert_eq!(ring.used(), 60);
+
+    // The end of the buffer is skipped, the start being still in use.
+    assert_eq!(ring.allocate(16, 1), None);
+    ring.retire(0);
+    assert_eq!(ring.used(), 8);
+    assert_eq!(ring.allocate(16, 1), Some(0));
+    // The skipped bytes are released with the allocation after them.
+    assert_eq!(ring.used(), 28);
+    assert_eq!(ring.allocate(48, 1), None);
+    ring.submit(2);
+    ring.retire(2);
+    assert_eq!(ring.used(), 0);
+    assert_eq!(ring.allocate(48, 1), Some(16));
+}
+
+#[test]
+fn staging_ring_oversized_allocations() {
+    let mut ring = StagingRing::new(64);
+    assert_eq!(ring.allocate(65, 1), None);
+    assert_eq!(ring.allocate(u64::MAX, 1), None);
+    assert_eq!(ring.used(), 0);
+    assert_eq!(ring.allocate(64, 1), Some(0));
+    assert_eq!(ring.allocate(1, 1), None);
+
+    let mut empty = StagingRing::new(0);
+    assert_eq!(empty.size(), 0);
+    assert_eq!(empty.allocate(0, 1), None);
+    assert_eq!(empty.allocate(1, 1), None);
+}
@@ -0,0 +1,38 @@
+//! Checksums used by zlib streams and PNG chunks.
+
+/// Computes the CRC-32 (ISO 3309) of the data, continuing from `crc`.
+pub(crate) fn crc32(crc: u32, data: &[u8]) -> u32 {
+    let mut table = [0; 256];
+    for (index, entry) in table.iter_mut().enumerate() {
+        let mut value = index as u32;
+        for _ in 0..8 {
+            value = if value & 1 == 1 {
+                0xedb8_8320 ^ (value >> 1)
+            } else {
+                value >> 1
+            };
+        }
+        *entry = value;
+    }
+    let mut crc = !crc;
+    for byte in data {
+        crc = table[((crc ^ u32::from(*byte)) & 0xff) as usize] ^ (crc >> 8);
+    }
+    !crc
+}
+
+/// Computes the Adler-32 checksum of the data.
+pub(crate) fn adler32(data: &[u8]) -> u32 {
+    const MODULO: u32 = 65521;
+    let (mut a, mut b) = (1, 0);
+    // 5552 is the largest block size for which `b` cannot overflow.
+    for block in data.chunks(5552) {
+        for byte in block {
+            a += u32::from(*byte);
+            b += a;
+        }
+        a %= MODULO;
+        b %= MODULO;
+    }
+    (b << 16) | a
+}
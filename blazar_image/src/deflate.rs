@@ -0,0 +1,168 @@
+//! zlib and DEFLATE compression (RFC 1950, RFC 1951).
+
+use crate::{
+    checksum,
+    inflate::{DISTANCE_BASES, DISTANCE_EXTRA_BITS, LENGTH_BASES, LENGTH_EXTRA_BITS},
+};
+
+/// Size of the sliding window.
+const WINDOW_SIZE: usize = 1 << 15;
+
+/// Number of bits of the hash of 3-byte sequences.
+const HASH_BITS: u32 = 15;
+
+/// Maximum number of previous positions examined when looking for a match.
+const MAX_CHAIN: usize = 64;
+
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+
+/// Compresses data into a zlib stream made of a single fixed Huffman block.
+pub(crate) fn compress(data: &[u8]) -> Vec<u8> {
+    let mut writer = BitWriter::new();
+    writer.output.extend_from_slice(&[0x78, 0x9c]);
+    writer.write(1, 1);
+    writer.write(1, 2);
+
+    let mut head = vec![usize::MAX; 1 << HASH_BITS];
+    let mut previous = vec![usize::MAX; WINDOW_SIZE];
+    let mut position = 0;
+    while position < data.len() {
+        let (length, distance) = find_match(data, position, &head, &previous);
+        let end = if length >= MIN_MATCH {
+            write_match(&mut writer, length, distance);
+            position + length
+        } else {
+            write_literal(&mut writer, u16::from(data[position]));
+            position + 1
+        };
+        while position < end {
+            if position + MIN_MATCH <= data.len() {
+                let hash = hash(&data[position..]);
+                previous[position % WINDOW_SIZE] = head[hash];
+                head[hash] = position;
+            }
+            position += 1;
+        }
+    }
+    write_literal(&mut writer, 256);
+    writer.flush();
+    writer
+        .output
+        .extend_from_slice(&checksum::adler32(data).to_be_bytes());
+    writer.output
+}
+
+/// Hashes the first 3 bytes of `data`.
+fn hash(data: &[u8]) -> usize {
+    let value = u32::from(data[0]) << 16 | u32::from(data[1]) << 8 | u32::from(data[2]);
+    (value.wrapping_mul(0x9e37_79b1) >> (32 - HASH_BITS)) as usize
+}
+
+/// Finds the longest previous match of the data at `position`, returning its length and
+/// distance.
+fn find_match(data: &[u8], position: usize, head: &[usize], previous: &[usize]) -> (usize, usize) {
+    if position + MIN_MATCH > data.len() {
+        return (0, 0);
+    }
+    let max_length = (data.len() - position).min(MAX_MATCH);
+    let mut best = (0, 0);
+    let mut candidate = head[hash(&data[position..])];
+    for _ in 0..MAX_CHAIN {
+        if candidate == usize::MAX || position - candidate > WINDOW_SIZE {
+            break;
+        }
+        let length = data[candidate..]
+            .iter()
+            .zip(&data[position..position + max_length])
+            .take_while(|(a, b)| a == b)
+            .count();
+        if length > best.0 {
+            best = (length, position - candidate);
+            if length == max_length {
+                break;
+            }
+        }
+        let next = previous[candidate % WINDOW_SIZE];
+        if next == usize::MAX || next >= candidate {
+            break;
+        }
+        candidate = next;
+    }
+    best
+}
+
+/// Writes a literal/length symbol with the fixed Huffman code.
+fn write_literal(writer: &mut BitWriter, symbol: u16) {
+    let (code, length) = match symbol {
+        0..=143 => (0x30 + symbol, 8),
+        144..=255 => (0x190 + symbol - 144, 9),
+        256..=279 => (symbol - 256, 7),
+        _ => (0xc0 + symbol - 280, 8),
+    };
+    writer.write_code(u32::from(code), length);
+}
+
+/// Writes a length and distance pair with the fixed Huffman code.
+fn write_match(writer: &mut BitWriter, length: usize, distance: usize) {
+    let index = LENGTH_BASES
+        .iter()
+        .rposition(|base| *base as usize <= length)
+        .unwrap();
+    write_literal(writer, 257 + index as u16);
+    writer.write(
+        (length - LENGTH_BASES[index] as usize) as u32,
+        u32::from(LENGTH_EXTRA_BITS[index]),
+    );
+    let index = DISTANCE_BASES
+        .iter()
+        .rposition(|base| *base as usize <= distance)
+        .unwrap();
+    writer.write_code(index as u32, 5);
+    writer.write(
+        (distance - DISTANCE_BASES[index] as usize) as u32,
+        u32::from(DISTANCE_EXTRA_BITS[index]),
+    );
+}
+
+/// Writes bits least significant first.
+struct BitWriter {
+    output: Vec<u8>,
+    buffer: u64,
+    count: u32,
+}
+
+impl BitWriter {
+    fn new() -> BitWriter {
+        BitWriter {
+            output: Vec::new(),
+            buffer: 0,
+            count: 0,
+        }
+    }
+
+    /// Writes the `count` low bits of `value`.
+    fn write(&mut self, value: u32, count: u32) {
+        self.buffer |= u64::from(value) << self.count;
+        self.count += count;
+        while self.count >= 8 {
+            self.output.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    /// Writes a Huffman code, most significant bit first.
+    fn write_code(&mut self, code: u32, length: u32) {
+        self.write(code.reverse_bits() >> (32 - length), length);
+    }
+
+    /// Pads the last byte with zeros.
+    fn flush(&mut self) {
+        if self.count > 0 {
+            self.output.push(self.buffer as u8);
+            self.buffer = 0;
+            self.count = 0;
+        }
+    }
+}
@@ -0,0 +1,355 @@
+//! zlib and DEFLATE decompression (RFC 1950, RFC 1951).
+
+use crate::{checksum, DecodeImageError, Result};
+
+/// Base lengths of length symbols 257 to 285.
+pub(crate) const LENGTH_BASES: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+
+/// Extra bits of length symbols 257 to 285.
+pub(crate) const LENGTH_EXTRA_BITS: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+
+/// Base distances of distance symbols 0 to 29.
+pub(crate) const DISTANCE_BASES: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+
+/// Extra bits of distance symbols 0 to 29.
+pub(crate) const DISTANCE_EXTRA_BITS: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+
+/// Order in which code length code lengths are stored.
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+/// Decompresses a zlib stream, failing if the output exceeds `limit` bytes.
+pub(crate) fn decompress(data: &[u8], limit: usize) -> Result<Vec<u8>> {
+    if data.len() < 6 {
+        return Err(invalid("Truncated zlib stream"));
+    }
+    let (cmf, flg) = (data[0], data[1]);
+    if cmf & 0x0f != 8 || cmf >> 4 > 7 || (u16::from(cmf) << 8 | u16::from(flg)) % 31 != 0 {
+        return Err(invalid("Invalid zlib header"));
+    }
+    if flg & 0x20 != 0 {
+        return Err(DecodeImageError::Unsupported(String::from(
+            "zlib preset dictionary",
+        )));
+    }
+    let mut reader = BitReader::new(&data[2..]);
+    let output = inflate(&mut reader, limit)?;
+    let trailer = reader.remaining_bytes();
+    if trailer.len() < 4 {
+        return Err(invalid("Missing zlib checksum"));
+    }
+    let expected = u32::from_be_bytes([trailer[0], trailer[1], trailer[2], trailer[3]]);
+    if checksum::adler32(&output) != expected {
+        return Err(invalid("zlib checksum mismatch"));
+    }
+    Ok(output)
+}
+
+/// Decompresses DEFLATE blocks.
+fn inflate(reader: &mut BitReader, limit: usize) -> Result<Vec<u8>> {
+    let mut output = Vec::new();
+    loop {
+        let last = reader.bits(1)? == 1;
+        match reader.bits(2)? {
+            0 => inflate_stored(reader, &mut output, limit)?,
+            1 => {
+                let (literals, distances) = fixed_codes();
+                inflate_codes(reader, &mut output, limit, &literals, &distances)?;
+            }
+            2 => {
+                let (literals, distances) = dynamic_codes(reader)?;
+                inflate_codes(reader, &mut output, limit, &literals, &distances)?;
+            }
+            _ => return Err(invalid("Invalid DEFLATE block type")),
+        }
+        if last {
+            return Ok(output);
+        }
+    }
+}
+
+/// Copies a stored block.
+fn inflate_stored(reader: &mut BitReader, output: &mut Vec<u8>, limit: usize) -> Result {
+    reader.align_to_byte();
+    let length = reader.bits(16)?;
+    let complement = reader.bits(16)?;
+    if length != !complement & 0xffff {
+        return Err(invalid("Invalid stored block length"));
+    }
+    let bytes = reader.bytes(length as usize)?;
+    if output.len() + bytes.len() > limit {
+        return Err(invalid("Decompressed data too large"));
+    }
+    output.extend_from_slice(bytes);
+    Ok(())
+}
+
+/// Decodes a block compressed with Huffman codes.
+fn inflate_codes(
+    reader: &mut BitReader,
+    output: &mut Vec<u8>,
+    limit: usize,
+    literals: &Huffman,
+    distances: &Huffman,
+) -> Result {
+    loop {
+        let symbol = literals.decode(reader)? as usize;
+        if symbol < 256 {
+            if output.len() >= limit {
+                return Err(invalid("Decompressed data too large"));
+            }
+            output.push(symbol as u8);
+        } else if symbol == 256 {
+            return Ok(());
+        } else {
+            let index = symbol - 257;
+            if index >= LENGTH_BASES.len() {
+                return Err(invalid("Invalid length symbol"));
+            }
+            let length = LENGTH_BASES[index] as usize
+                + reader.bits(u32::from(LENGTH_EXTRA_BITS[index]))? as usize;
+            let index = distances.decode(reader)? as usize;
+            if index >= DISTANCE_BASES.len() {
+                return Err(invalid("Invalid distance symbol"));
+            }
+            let distance = DISTANCE_BASES[index] as usize
+                + reader.bits(u32::from(DISTANCE_EXTRA_BITS[index]))? as usize;
+            if distance > output.len() {
+                return Err(invalid("Invalid distance"));
+            }
+            if output.len() + length > limit {
+                return Err(invalid("Decompressed data too large"));
+            }
+            let start = output.len() - distance;
+            for offset in 0..length {
+                let byte = output[start + offset];
+                output.push(byte);
+            }
+        }
+    }
+}
+
+/// Returns the fixed literal/length and distance codes.
+fn fixed_codes() -> (Huffman, Huffman) {
+    let mut lengths = [0; 288];
+    for (symbol, length) in lengths.iter_mut().enumerate() {
+        *length = match symbol {
+            0..=143 => 8,
+            144..=255 => 9,
+            256..=279 => 7,
+            _ => 8,
+        };
+    }
+    (
+        Huffman::new(&lengths).unwrap(),
+        Huffman::new(&[5; 30]).unwrap(),
+    )
+}
+
+/// Reads the literal/length and distance codes of a dynamic block.
+fn dynamic_codes(reader: &mut BitReader) -> Result<(Huffman, Huffman)> {
+    let literal_count = reader.bits(5)? as usize + 257;
+    let distance_count = reader.bits(5)? as usize + 1;
+    let code_length_count = reader.bits(4)? as usize + 4;
+    if literal_count > 286 || distance_count > 30 {
+        return Err(invalid("Invalid code counts"));
+    }
+    let mut code_length_lengths = [0; 19];
+    for index in CODE_LENGTH_ORDER.iter().take(code_length_count) {
+        code_length_lengths[*index] = reader.bits(3)? as u8;
+    }
+    let code_lengths = Huffman::new(&code_length_lengths)?;
+    let mut lengths = [0; 286 + 30];
+    let total = literal_count + distance_count;
+    let mut index = 0;
+    while index < total {
+        let symbol = code_lengths.decode(reader)?;
+        let (value, repeat) = match symbol {
+            0..=15 => (symbol as u8, 1),
+            16 if index > 0 => (lengths[index - 1], 3 + reader.bits(2)?),
+            16 => return Err(invalid("Repeated code length without previous one")),
+            17 => (0, 3 + reader.bits(3)?),
+            _ => (0, 11 + reader.bits(7)?),
+        };
+        if index + repeat as usize > total {
+            return Err(invalid("Too many code lengths"));
+        }
+        for length in &mut lengths[index..index + repeat as usize] {
+            *length = value;
+        }
+        index += repeat as usize;
+    }
+    if lengths[256] == 0 {
+        return Err(invalid("Missing end of block code"));
+    }
+    Ok((
+        Huffman::new(&lengths[..literal_count])?,
+        Huffman::new(&lengths[literal_count..total])?,
+    ))
+}
+
+/// Canonical Huffman code decoded through a lookup table.
+struct Huffman {
+    /// Entries made of the symbol shifted by 4 and the code length, indexed by
+    /// bit-reversed codes.
+    table: Vec<u16>,
+    max_length: u32,
+}
+
+impl Huffman {
+    /// Builds a code from the code length of each symbol.
+    fn new(lengths: &[u8]) -> Result<Huffman> {
+        let mut counts = [0u16; 16];
+        for length in lengths {
+            counts[*length as usize] += 1;
+        }
+        counts[0] = 0;
+        let max_length = (1..16)
+            .rev()
+            .find(|length| counts[*length] > 0)
+            .unwrap_or(1);
+
+        // Checks that the code is not over-subscribed.
+        let mut left: i32 = 1;
+        for count in &counts[1..] {
+            left = left * 2 - i32::from(*count);
+            if left < 0 {
+                return Err(invalid("Over-subscribed Huffman code"));
+            }
+        }
+
+        let mut next_code = [0u16; 16];
+        for length in 1..16 {
+            next_code[length] = (next_code[length - 1] + counts[length - 1]) << 1;
+        }
+        let mut table = vec![0; 1 << max_length];
+        for (symbol, length) in lengths.iter().enumerate() {
+            let length = *length as usize;
+            if length == 0 {
+                continue;
+            }
+            let code = next_code[length];
+            next_code[length] += 1;
+            let reversed = (code.reverse_bits() >> (16 - length)) as usize;
+            let entry = (symbol as u16) << 4 | length as u16;
+            for slot in table.iter_mut().skip(reversed).step_by(1 << length) {
+                *slot = entry;
+            }
+        }
+        Ok(Huffman {
+            table,
+            max_length: max_length as u32,
+        })
+    }
+
+    /// Decodes a symbol.
+    fn decode(&self, reader: &mut BitReader) -> Result<u16> {
+        let entry = self.table[reader.peek(self.max_length) as usize];
+        let length = u32::from(entry & 0x0f);
+        if length == 0 {
+            return Err(invalid("Invalid Huffman code"));
+        }
+        reader.consume(length)?;
+        Ok(entry >> 4)
+    }
+}
+
+/// Reads bits least significant first.
+struct BitReader<'a> {
+    data: &'a [u8],
+    position: usize,
+    buffer: u64,
+    count: u32,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8]) -> BitReader<'a> {
+        BitReader {
+            data,
+            position: 0,
+            buffer: 0,
+            count: 0,
+        }
+    }
+
+    /// Fills the bit buffer as much as possible.
+    fn refill(&mut self) {
+        while self.count <= 56 && self.position < self.data.len() {
+            self.buffer |= u64::from(self.data[self.position]) << self.count;
+            self.position += 1;
+            self.count += 8;
+        }
+    }
+
+    /// Returns the next `count` bits without consuming them, padded with zeros at the
+    /// end of the data.
+    fn peek(&mut self, count: u32) -> u32 {
+        if self.count < count {
+            self.refill();
+        }
+        (self.buffer & ((1 << count) - 1)) as u32
+    }
+
+    /// Consumes `count` bits.
+    fn consume(&mut self, count: u32) -> Result {
+        if self.count < count {
+            self.refill();
+            if self.count < count {
+                return Err(invalid("Unexpected end of compressed data"));
+            }
+        }
+        self.buffer >>= count;
+        self.count -= count;
+        Ok(())
+    }
+
+    /// Reads `count` bits, at most 32.
+    fn bits(&mut self, count: u32) -> Result<u32> {
+        let value = self.peek(count);
+        self.consume(count)?;
+        Ok(value)
+    }
+
+    /// Discards bits up to the next byte boundary, and gives back buffered whole bytes.
+    fn align_to_byte(&mut self) {
+        let bytes = self.count / 8;
+        self.position -= bytes as usize;
+        self.buffer = 0;
+        self.count = 0;
+    }
+
+    /// Reads `count` bytes, the reader being aligned to a byte boundary.
+    fn bytes(&mut self, count: usize) -> Result<&'a [u8]> {
+        self.align_to_byte();
+        let end = self.position + count;
+        if end > self.data.len() {
+            return Err(invalid("Unexpected end of compressed data"));
+        }
+        let bytes = &self.data[self.position..end];
+        self.position = end;
+        Ok(bytes)
+    }
+
+    /// Returns the bytes following the last consumed bit.
+    fn remaining_bytes(&mut self) -> &'a [u8] {
+        self.align_to_byte();
+        &self.data[self.position..]
+    }
+}
+
+/// Creates an invalid data error.
+fn invalid(message: &str) -> DecodeImageError {
+    DecodeImageError::InvalidData(String::from(message))
+}
@@ -0,0 +1,198 @@
+//! PNG, TGA and QOI image decoding.
+
+mod checksum;
+mod deflate;
+mod inflate;
+
+pub mod png;
+pub mod qoi;
+pub mod tga;
+
+use std::{fs, io, path::Path};
+
+/// Kinds of image decoding errors.
+#[derive(Debug)]
+pub enum DecodeImageError {
+    Io(io::Error),
+    UnknownFormat,
+    Unsupported(String),
+    InvalidData(String),
+}
+
+impl From<io::Error> for DecodeImageError {
+    fn from(error: io::Error) -> DecodeImageError {
+        DecodeImageError::Io(error)
+    }
+}
+
+/// Convenient result type consisting of a return type and a `DecodeImageError`.
+pub type Result<T = ()> = std::result::Result<T, DecodeImageError>;
+
+/// Maximum number of pixels of a decoded image.
+const MAX_PIXELS: usize = 1 << 28;
+
+/// Pixel formats.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum PixelFormat {
+    /// 8-bit RGBA components.
+    Rgba8,
+    /// 16-bit RGBA components, stored in native byte order.
+    Rgba16,
+}
+
+impl PixelFormat {
+    /// Returns the size in bytes of a pixel.
+    pub fn bytes_per_pixel(self) -> usize {
+        match self {
+            PixelFormat::Rgba8 => 4,
+            PixelFormat::Rgba16 => 8,
+        }
+    }
+}
+
+/// Represents an image made of tightly packed rows, top row first.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Image {
+    width: u32,
+    height: u32,
+    format: PixelFormat,
+    data: Vec<u8>,
+}
+
+impl Image {
+    /// Creates a transparent black image.
+    pub fn new(width: u32, height: u32, format: PixelFormat) -> Image {
+        let size = width as usize * height as usize * format.bytes_per_pixel();
+        Image {
+            width,
+            height,
+            format,
+            data: vec![0; size],
+        }
+    }
+
+    /// Creates an image from raw pixel data, or returns `None` if its size does not match.
+    pub fn from_raw(width: u32, height: u32, format: PixelFormat, data: Vec<u8>) -> Option<Image> {
+        if data.len() == width as usize * height as usize * format.bytes_per_pixel() {
+            Some(Image {
+                width,
+                height,
+                format,
+                data,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Decodes an image, detecting its format from its content.
+    pub fn decode(data: &[u8]) -> Result<Image> {
+        if png::is_png(data) {
+            png::decode(data)
+        } else if qoi::is_qoi(data) {
+            qoi::decode(data)
+        } else if tga::is_tga(data) {
+            tga::decode(data)
+        } else {
+            Err(DecodeImageError::UnknownFormat)
+        }
+    }
+
+    /// Opens and decodes an image file.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Image> {
+        Image::decode(&fs::read(path)?)
+    }
+
+    /// Encodes the image as PNG and writes it to a file.
+    pub fn save_png<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, png::encode(self))
+    }
+
+    /// Returns the width in pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the height in pixels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the pixel format.
+    pub fn format(&self) -> PixelFormat {
+        self.format
+    }
+
+    /// Returns the pixel data.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns the pixel data mutably.
+    pub fn data_mut(&mut self) -> &mut [u8] {
+        &mut self.data
+    }
+
+    /// Consumes the image and returns its pixel data.
+    pub fn into_data(self) -> Vec<u8> {
+        self.data
+    }
+
+    /// Returns the components of a pixel, scaled to 16 bits.
+    pub fn pixel(&self, x: u32, y: u32) -> [u16; 4] {
+        let offset =
+            (y as usize * self.width as usize + x as usize) * self.format.bytes_per_pixel();
+        let mut pixel = [0; 4];
+        for (channel, component) in pixel.iter_mut().enumerate() {
+            *component = match self.format {
+                PixelFormat::Rgba8 => u16::from(self.data[offset + channel]) * 257,
+                PixelFormat::Rgba16 => {
+                    let offset = offset + channel * 2;
+                    u16::from_ne_bytes([self.data[offset], self.data[offset + 1]])
+                }
+            };
+        }
+        pixel
+    }
+
+    /// Converts the image to 8-bit components.
+    pub fn to_rgba8(&self) -> Image {
+        match self.format {
+            PixelFormat::Rgba8 => self.clone(),
+            PixelFormat::Rgba16 => Image {
+                width: self.width,
+                height: self.height,
+                format: PixelFormat::Rgba8,
+                data: self
+                    .data
+                    .chunks_exact(2)
+                    .map(|bytes| {
+                        let value = u32::from(u16::from_ne_bytes([bytes[0], bytes[1]]));
+                        ((value * 255 + 32767) / 65535) as u8
+                    })
+                    .collect(),
+            },
+        }
+    }
+
+    /// Flips the image upside down.
+    pub fn flip_vertically(&mut self) {
+        let stride = self.width as usize * self.format.bytes_per_pixel();
+        let height = self.height as usize;
+        for y in 0..height / 2 {
+            let (top, bottom) = self.data.split_at_mut((height - 1 - y) * stride);
+            top[y * stride..(y + 1) * stride].swap_with_slice(&mut bottom[..stride]);
+        }
+    }
+}
+
+/// Returns the number of pixels of an image, or an error if it is empty or too large.
+fn pixel_count(width: u32, height: u32) -> Result<usize> {
+    match (width as usize).checked_mul(height as usize) {
+        Some(0) => Err(DecodeImageError::InvalidData(String::from("Empty image"))),
+        Some(count) if count <= MAX_PIXELS => Ok(count),
+        _ => Err(DecodeImageError::Unsupported(String::from(
+            "Image too large",
+        ))),
+    }
+}
@@ -0,0 +1,539 @@
+//! PNG decoding and encoding.
+
+use crate::{
+    checksum, deflate, inflate, pixel_count, DecodeImageError, Image, PixelFormat, Result,
+};
+
+/// PNG file signature.
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+
+/// Maximum compression ratio of DEFLATE streams, used to reject truncated or forged
+/// dimensions before allocating.
+const MAX_COMPRESSION_RATIO: usize = 1032;
+
+/// Adam7 passes as `(x, y, dx, dy)`.
+const ADAM7_PASSES: [(usize, usize, usize, usize); 7] = [
+    (0, 0, 8, 8),
+    (4, 0, 8, 8),
+    (0, 4, 4, 8),
+    (2, 0, 4, 4),
+    (0, 2, 2, 4),
+    (1, 0, 2, 2),
+    (0, 1, 1, 2),
+];
+
+/// Color types.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum ColorType {
+    Gray,
+    Rgb,
+    Indexed,
+    GrayAlpha,
+    Rgba,
+}
+
+impl ColorType {
+    fn from_byte(byte: u8) -> Option<ColorType> {
+        match byte {
+            0 => Some(ColorType::Gray),
+            2 => Some(ColorType::Rgb),
+            3 => Some(ColorType::Indexed),
+            4 => Some(ColorType::GrayAlpha),
+            6 => Some(ColorType::Rgba),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of samples per pixel.
+    fn channels(self) -> usize {
+        match self {
+            ColorType::Gray | ColorType::Indexed => 1,
+            ColorType::GrayAlpha => 2,
+            ColorType::Rgb => 3,
+            ColorType::Rgba => 4,
+        }
+    }
+
+    /// Returns `true` if the bit depth is allowed for this color type.
+    fn allows_bit_depth(self, depth: u8) -> bool {
+        match self {
+            ColorType::Gray => matches!(depth, 1 | 2 | 4 | 8 | 16),
+            ColorType::Indexed => matches!(depth, 1 | 2 | 4 | 8),
+            _ => matches!(depth, 8 | 16),
+        }
+    }
+}
+
+/// Image header.
+struct Header {
+    width: u32,
+    height: u32,
+    bit_depth: u8,
+    color_type: ColorType,
+    interlaced: bool,
+}
+
+impl Header {
+    /// Returns the number of bits per pixel.
+    fn bits_per_pixel(&self) -> usize {
+        self.color_type.channels() * self.bit_depth as usize
+    }
+
+    /// Returns the size in bytes of a scanline of `width` pixels, without its filter byte.
+    fn stride(&self, width: usize) -> usize {
+        (width * self.bits_per_pixel() + 7) / 8
+    }
+
+    /// Returns the passes as `(x, y, dx, dy, width, height)`, skipping empty ones.
+    fn passes(&self) -> Vec<(usize, usize, usize, usize, usize, usize)> {
+        let (width, height) = (self.width as usize, self.height as usize);
+        let passes: &[_] = if self.interlaced {
+            &ADAM7_PASSES
+        } else {
+            &[(0, 0, 1, 1)]
+        };
+        passes
+            .iter()
+            .filter(|(x, y, _, _)| *x < width && *y < height)
+            .map(|&(x, y, dx, dy)| {
+                (
+                    x,
+                    y,
+                    dx,
+                    dy,
+                    (width - x + dx - 1) / dx,
+                    (height - y + dy - 1) / dy,
+                )
+            })
+            .collect()
+    }
+}
+
+/// Transparency information.
+enum Transparency {
+    None,
+    /// Fully transparent gray or RGB sample values.
+    Key([u16; 3]),
+    /// Alpha of each palette entry.
+    Palette(Vec<u8>),
+}
+
+/// Returns `true` if the data starts with the PNG signature.
+pub fn is_png(data: &[u8]) -> bool {
+    data.starts_with(&SIGNATURE)
+}
+
+/// Decodes a PNG image.
+///
+/// 16-bit images are decoded to `PixelFormat::Rgba16`, other ones to
+/// `PixelFormat::Rgba8`.
+pub fn decode(data: &[u8]) -> Result<Image> {
+    if !is_png(data) {
+        return Err(invalid("Invalid PNG signature"));
+    }
+    let mut position = SIGNATURE.len();
+    let mut header = None;
+    let mut palette: Vec<[u8; 3]> = Vec::new();
+    let mut transparency = Transparency::None;
+    let mut compressed = Vec::new();
+    loop {
+        if data.len() - position < 12 {
+            return Err(invalid("Truncated chunk"));
+        }
+        let length = read_u32(&data[position..]) as usize;
+        let kind = &data[position + 4..position + 8];
+        if length > data.len() - position - 12 {
+            return Err(invalid("Truncated chunk"));
+        }
+        let body = &data[position + 8..position + 8 + length];
+        let crc = read_u32(&data[position + 8 + length..]);
+        if checksum::crc32(0, &data[position + 4..position + 8 + length]) != crc {
+            return Err(invalid("Chunk checksum mismatch"));
+        }
+        position += 12 + length;
+        if header.is_none() && kind != b"IHDR" {
+            return Err(invalid("Missing IHDR chunk"));
+        }
+        match kind {
+            b"IHDR" if header.is_none() => header = Some(parse_header(body)?),
+            b"IHDR" => return Err(invalid("Duplicate IHDR chunk")),
+            b"PLTE" => {
+                if body.is_empty() || body.len() % 3 != 0 || body.len() > 256 * 3 {
+                    return Err(invalid("Invalid PLTE chunk"));
+                }
+                palette = body.chunks_exact(3).map(|c| [c[0], c[1], c[2]]).collect();
+            }
+            b"tRNS" => {
+                let header = header.as_ref().unwrap();
+                transparency = parse_transparency(header, body)?;
+            }
+            b"IDAT" => compressed.extend_from_slice(body),
+            b"IEND" => break,
+            _ if kind[0] & 0x20 == 0 => {
+                return Err(DecodeImageError::Unsupported(format!(
+                    "Critical chunk {}",
+                    String::from_utf8_lossy(kind)
+                )))
+            }
+            _ => {}
+        }
+    }
+    let header = header.unwrap();
+    if header.color_type == ColorType::Indexed && palette.is_empty() {
+        return Err(invalid("Missing PLTE chunk"));
+    }
+
+    let pixels = pixel_count(header.width, header.height)?;
+    let passes = header.passes();
+    let raw_size: usize = passes
+        .iter()
+        .map(|pass| (header.stride(pass.4) + 1) * pass.5)
+        .sum();
+    if raw_size > compressed.len().saturating_mul(MAX_COMPRESSION_RATIO) {
+        return Err(invalid("Not enough image data"));
+    }
+    let mut raw = inflate::decompress(&compressed, raw_size)?;
+    if raw.len() != raw_size {
+        return Err(invalid("Not enough image data"));
+    }
+
+    let format = if header.bit_depth == 16 {
+        PixelFormat::Rgba16
+    } else {
+        PixelFormat::Rgba8
+    };
+    let mut output = vec![0; pixels * format.bytes_per_pixel()];
+    let filter_distance = (header.bits_per_pixel() / 8).max(1);
+    let mut offset = 0;
+    for &(x, y, dx, dy, width, height) in &passes {
+        let stride = header.stride(width);
+        let rows = &mut raw[offset..offset + (stride + 1) * height];
+        offset += rows.len();
+        for row in 0..height {
+            let (previous, current) = rows.split_at_mut(row * (stride + 1));
+            let previous = if row > 0 {
+                &previous[previous.len() - stride..]
+            } else {
+                &[]
+            };
+            let (filter, current) = current[..stride + 1].split_first_mut().unwrap();
+            unfilter(*filter, filter_distance, previous, current)?;
+            for column in 0..width {
+                let rgba = convert_pixel(&header, &palette, &transparency, current, column)?;
+                let index = (y + row * dy) * header.width as usize + x + column * dx;
+                match format {
+                    PixelFormat::Rgba8 => {
+                        for (channel, value) in rgba.iter().enumerate() {
+                            output[index * 4 + channel] = *value as u8;
+                        }
+                    }
+                    PixelFormat::Rgba16 => {
+                        for (channel, value) in rgba.iter().enumerate() {
+                            let offset = index * 8 + channel * 2;
+                            output[offset..offset + 2].copy_from_slice(&value.to_ne_bytes());
+                        }
+                    }
+                }
+            }
+        }
+    }
+    Ok(Image::from_raw(header.width, header.height, format, output).unwrap())
+}
+
+/// Encodes an image as a non-interlaced PNG.
+///
+/// Alpha is omitted if the image is fully opaque.
+pub fn encode(image: &Image) -> Vec<u8> {
+    let (width, height) = (image.width() as usize, image.height() as usize);
+    let (bit_depth, sample_size) = match image.format() {
+        PixelFormat::Rgba8 => (8, 1),
+        PixelFormat::Rgba16 => (16, 2),
+    };
+    let opaque = (0..image.height())
+        .flat_map(|y| (0..image.width()).map(move |x| (x, y)))
+        .all(|(x, y)| image.pixel(x, y)[3] == u16::MAX);
+    let (color_type, channels) = if opaque { (2, 3) } else { (6, 4) };
+
+    // Converts pixels to big-endian samples.
+    let pixel_size = channels * sample_size;
+    let stride = width * pixel_size;
+    let mut rows = Vec::with_capacity(stride * height);
+    for pixel in image.data().chunks_exact(image.format().bytes_per_pixel()) {
+        for channel in 0..channels {
+            match image.format() {
+                PixelFormat::Rgba8 => rows.push(pixel[channel]),
+                PixelFormat::Rgba16 => {
+                    let value = u16::from_ne_bytes([pixel[channel * 2], pixel[channel * 2 + 1]]);
+                    rows.extend_from_slice(&value.to_be_bytes());
+                }
+            }
+        }
+    }
+
+    // Filters each row with the filter giving the smallest sum of absolute differences.
+    let mut filtered = Vec::with_capacity((stride + 1) * height);
+    let mut candidate = vec![0; stride];
+    let mut best = vec![0; stride];
+    for y in 0..height {
+        let current = &rows[y * stride..(y + 1) * stride];
+        let previous = if y > 0 {
+            &rows[(y - 1) * stride..y * stride]
+        } else {
+            &[]
+        };
+        let mut best_filter = 0;
+        let mut best_score = u64::MAX;
+        for filter in 0..5 {
+            filter_row(filter, pixel_size, previous, current, &mut candidate);
+            let score = candidate
+                .iter()
+                .map(|byte| i16::from(*byte as i8).abs() as u64)
+                .sum();
+            if score < best_score {
+                best_score = score;
+                best_filter = filter;
+                best.copy_from_slice(&candidate);
+            }
+        }
+        filtered.push(best_filter);
+        filtered.extend_from_slice(&best);
+    }
+
+    let mut output = SIGNATURE.to_vec();
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&image.width().to_be_bytes());
+    header.extend_from_slice(&image.height().to_be_bytes());
+    header.extend_from_slice(&[bit_depth, color_type, 0, 0, 0]);
+    write_chunk(&mut output, b"IHDR", &header);
+    write_chunk(&mut output, b"IDAT", &deflate::compress(&filtered));
+    write_chunk(&mut output, b"IEND", &[]);
+    output
+}
+
+/// Parses the IHDR chunk.
+fn parse_header(body: &[u8]) -> Result<Header> {
+    if body.len() != 13 {
+        return Err(invalid("Invalid IHDR chunk"));
+    }
+    let color_type = ColorType::from_byte(body[9]).ok_or_else(|| invalid("Invalid color type"))?;
+    let header = Header {
+        width: read_u32(body),
+        height: read_u32(&body[4..]),
+        bit_depth: body[8],
+        color_type,
+        interlaced: match body[12] {
+            0 => false,
+            1 => true,
+            _ => return Err(invalid("Invalid interlace method")),
+        },
+    };
+    if !color_type.allows_bit_depth(header.bit_depth) {
+        return Err(invalid("Invalid bit depth"));
+    }
+    if body[10] != 0 || body[11] != 0 {
+        return Err(invalid("Invalid compression or filter method"));
+    }
+    if header.width > i32::MAX as u32 || header.height > i32::MAX as u32 {
+        return Err(invalid("Invalid dimensions"));
+    }
+    Ok(header)
+}
+
+/// Parses the tRNS chunk.
+fn parse_transparency(header: &Header, body: &[u8]) -> Result<Transparency> {
+    let sample = |index: usize| u16::from_be_bytes([body[index * 2], body[index * 2 + 1]]);
+    match header.color_type {
+        ColorType::Gray if body.len() == 2 => Ok(Transparency::Key([sample(0); 3])),
+        ColorType::Rgb if body.len() == 6 => {
+            Ok(Transparency::Key([sample(0), sample(1), sample(2)]))
+        }
+        ColorType::Indexed if body.len() <= 256 => Ok(Transparency::Palette(body.to_vec())),
+        _ => Err(invalid("Invalid tRNS chunk")),
+    }
+}
+
+/// Reverses the filter of a scanline, `previous` being empty for the first one.
+fn unfilter(filter: u8, distance: usize, previous: &[u8], current: &mut [u8]) -> Result {
+    let up = |index: usize| previous.get(index).cloned().unwrap_or(0);
+    match filter {
+        0 => {}
+        1 => {
+            for index in distance..current.len() {
+                current[index] = current[index].wrapping_add(current[index - distance]);
+            }
+        }
+        2 => {
+            for (index, byte) in current.iter_mut().enumerate() {
+                *byte = byte.wrapping_add(up(index));
+            }
+        }
+        3 => {
+            for index in 0..current.len() {
+                let left = if index >= distance {
+                    current[index - distance]
+                } else {
+                    0
+                };
+                let average = ((u16::from(left) + u16::from(up(index))) / 2) as u8;
+                current[index] = current[index].wrapping_add(average);
+            }
+        }
+        4 => {
+            for index in 0..current.len() {
+                let (left, upper_left) = if index >= distance {
+                    (current[index - distance], up(index - distance))
+                } else {
+                    (0, 0)
+                };
+                current[index] = current[index].wrapping_add(paeth(left, up(index), upper_left));
+            }
+        }
+        _ => return Err(invalid("Invalid filter type")),
+    }
+    Ok(())
+}
+
+/// Applies a filter to a scanline, `previous` being empty for the first one.
+fn filter_row(filter: u8, distance: usize, previous: &[u8], current: &[u8], output: &mut [u8]) {
+    let up = |index: usize| previous.get(index).cloned().unwrap_or(0);
+    let left = |index: usize| {
+        if index >= distance {
+            current[index - distance]
+        } else {
+            0
+        }
+    };
+    for (index, byte) in output.iter_mut().enumerate() {
+        let predictor = match filter {
+            0 => 0,
+            1 => left(index),
+            2 => up(index),
+            3 => ((u16::from(left(index)) + u16::from(up(index))) / 2) as u8,
+            _ => {
+                let upper_left = if index >= distance {
+                    up(index - distance)
+                } else {
+                    0
+                };
+                paeth(left(index), up(index), upper_left)
+            }
+        };
+        *byte = current[index].wrapping_sub(predictor);
+    }
+}
+
+/// Paeth predictor.
+fn paeth(left: u8, up: u8, upper_left: u8) -> u8 {
+    let (a, b, c) = (i16::from(left), i16::from(up), i16::from(upper_left));
+    let estimate = a + b - c;
+    let (pa, pb, pc) = (
+        (estimate - a).abs(),
+        (estimate - b).abs(),
+        (estimate - c).abs(),
+    );
+    if pa <= pb && pa <= pc {
+        left
+    } else if pb <= pc {
+        up
+    } else {
+        upper_left
+    }
+}
+
+/// Reads the `index`-th sample of an unfiltered scanline.
+fn read_sample(row: &[u8], index: usize, bit_depth: u8) -> u16 {
+    match bit_depth {
+        8 => u16::from(row[index]),
+        16 => u16::from_be_bytes([row[index * 2], row[index * 2 + 1]]),
+        _ => {
+            let bit = index * bit_depth as usize;
+            let shift = 8 - bit_depth as usize - bit % 8;
+            u16::from(row[bit / 8] >> shift) & ((1 << bit_depth) - 1)
+        }
+    }
+}
+
+/// Converts the `column`-th pixel of an unfiltered scanline to RGBA components of 8 or 16
+/// bits.
+fn convert_pixel(
+    header: &Header,
+    palette: &[[u8; 3]],
+    transparency: &Transparency,
+    row: &[u8],
+    column: usize,
+) -> Result<[u16; 4]> {
+    let depth = header.bit_depth;
+    let channels = header.color_type.channels();
+    let sample = |channel: usize| read_sample(row, column * channels + channel, depth);
+    let max = if depth == 16 { 0xffff } else { 0xff };
+    let scale = |value: u16| {
+        if depth < 8 {
+            value * 255 / ((1 << depth) - 1)
+        } else {
+            value
+        }
+    };
+    let key_alpha = |key: [u16; 3]| {
+        let matches = match transparency {
+            Transparency::Key(transparent) => *transparent == key,
+            _ => false,
+        };
+        if matches {
+            0
+        } else {
+            max
+        }
+    };
+    Ok(match header.color_type {
+        ColorType::Gray => {
+            let gray = sample(0);
+            let value = scale(gray);
+            [value, value, value, key_alpha([gray; 3])]
+        }
+        ColorType::Rgb => {
+            let rgb = [sample(0), sample(1), sample(2)];
+            [rgb[0], rgb[1], rgb[2], key_alpha(rgb)]
+        }
+        ColorType::Indexed => {
+            let index = sample(0) as usize;
+            let color = palette
+                .get(index)
+                .ok_or_else(|| invalid("Palette index out of range"))?;
+            let alpha = match transparency {
+                Transparency::Palette(alphas) => alphas.get(index).cloned().unwrap_or(0xff),
+                _ => 0xff,
+            };
+            [
+                u16::from(color[0]),
+                u16::from(color[1]),
+                u16::from(color[2]),
+                u16::from(alpha),
+            ]
+        }
+        ColorType::GrayAlpha => {
+            let gray = sample(0);
+            [gray, gray, gray, sample(1)]
+        }
+        ColorType::Rgba => [sample(0), sample(1), sample(2), sample(3)],
+    })
+}
+
+/// Appends a chunk.
+fn write_chunk(output: &mut Vec<u8>, kind: &[u8; 4], body: &[u8]) {
+    output.extend_from_slice(&(body.len() as u32).to_be_bytes());
+    let start = output.len();
+    output.extend_from_slice(kind);
+    output.extend_from_slice(body);
+    let crc = checksum::crc32(0, &output[start..]);
+    output.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// Reads a big-endian 32-bit integer.
+fn read_u32(data: &[u8]) -> u32 {
+    u32::from_be_bytes([data[0], data[1], data[2], data[3]])
+}
+
+/// Creates an invalid data error.
+fn invalid(message: &str) -> DecodeImageError {
+    DecodeImageError::InvalidData(String::from(message))
+}
@@ -0,0 +1,110 @@
+//! QOI decoding.
+
+use crate::{pixel_count, DecodeImageError, Image, PixelFormat, Result};
+
+/// QOI file signature.
+const MAGIC: [u8; 4] = *b"qoif";
+
+/// Size of the file header.
+const HEADER_SIZE: usize = 14;
+
+/// Stream end marker.
+const END_MARKER: [u8; 8] = [0, 0, 0, 0, 0, 0, 0, 1];
+
+/// Maximum number of pixels described by a byte, reached by run operations.
+const MAX_PIXELS_PER_BYTE: usize = 62;
+
+const OP_INDEX: u8 = 0x00;
+const OP_DIFF: u8 = 0x40;
+const OP_LUMA: u8 = 0x80;
+const OP_RGB: u8 = 0xfe;
+const OP_RGBA: u8 = 0xff;
+
+/// Returns `true` if the data starts with the QOI signature.
+pub fn is_qoi(data: &[u8]) -> bool {
+    data.starts_with(&MAGIC)
+}
+
+/// Decodes a QOI image.
+pub fn decode(data: &[u8]) -> Result<Image> {
+    if !is_qoi(data) || data.len() < HEADER_SIZE + END_MARKER.len() {
+        return Err(invalid("Invalid QOI header"));
+    }
+    let width = u32::from_be_bytes([data[4], data[5], data[6], data[7]]);
+    let height = u32::from_be_bytes([data[8], data[9], data[10], data[11]]);
+    let (channels, color_space) = (data[12], data[13]);
+    if !matches!(channels, 3 | 4) || color_space > 1 {
+        return Err(invalid("Invalid QOI header"));
+    }
+    let pixels = pixel_count(width, height)?;
+    let body = &data[HEADER_SIZE..data.len() - END_MARKER.len()];
+    if pixels > body.len().saturating_mul(MAX_PIXELS_PER_BYTE) {
+        return Err(invalid("Not enough image data"));
+    }
+
+    let mut output = Vec::with_capacity(pixels * 4);
+    let mut index = [[0u8; 4]; 64];
+    let mut pixel = [0, 0, 0, 0xff];
+    let mut position = 0;
+    let next = |position: &mut usize| -> Result<u8> {
+        let byte = *body
+            .get(*position)
+            .ok_or_else(|| invalid("Truncated QOI data"))?;
+        *position += 1;
+        Ok(byte)
+    };
+    while output.len() < pixels * 4 {
+        let op = next(&mut position)?;
+        let mut run = 1;
+        match op {
+            OP_RGB => {
+                for component in &mut pixel[..3] {
+                    *component = next(&mut position)?;
+                }
+            }
+            OP_RGBA => {
+                for component in &mut pixel {
+                    *component = next(&mut position)?;
+                }
+            }
+            _ => match op & 0xc0 {
+                OP_INDEX => pixel = index[op as usize],
+                OP_DIFF => {
+                    pixel[0] = pixel[0].wrapping_add((op >> 4 & 0x03).wrapping_sub(2));
+                    pixel[1] = pixel[1].wrapping_add((op >> 2 & 0x03).wrapping_sub(2));
+                    pixel[2] = pixel[2].wrapping_add((op & 0x03).wrapping_sub(2));
+                }
+                OP_LUMA => {
+                    let green = (op & 0x3f).wrapping_sub(32);
+                    let byte = next(&mut position)?;
+                    pixel[0] = pixel[0]
+                        .wrapping_add(green)
+                        .wrapping_add((byte >> 4).wrapping_sub(8));
+                    pixel[1] = pixel[1].wrapping_add(green);
+                    pixel[2] = pixel[2]
+                        .wrapping_add(green)
+                        .wrapping_add((byte & 0x0f).wrapping_sub(8));
+                }
+                // Run operation.
+                _ => run = (op & 0x3f) as usize + 1,
+            },
+        }
+        index[hash(pixel)] = pixel;
+        for _ in 0..run {
+            output.extend_from_slice(&pixel);
+        }
+    }
+    output.truncate(pixels * 4);
+    Ok(Image::from_raw(width, height, PixelFormat::Rgba8, output).unwrap())
+}
+
+/// Returns the position of a pixel in the index of previously seen pixels.
+fn hash(pixel: [u8; 4]) -> usize {
+    let [r, g, b, a] = pixel;
+    (r as usize * 3 + g as usize * 5 + b as usize * 7 + a as usize * 11) % 64
+}
+
+/// Creates an invalid data error.
+fn invalid(message: &str) -> DecodeImageError {
+    DecodeImageError::InvalidData(String::from(message))
+}
@@ -0,0 +1,180 @@
+//! TGA decoding.
+
+use crate::{pixel_count, DecodeImageError, Image, PixelFormat, Result};
+
+/// Size of the file header.
+const HEADER_SIZE: usize = 18;
+
+/// Maximum number of pixels described by a byte of RLE data.
+const MAX_RLE_PIXELS_PER_BYTE: usize = 128;
+
+/// Returns `true` if the data looks like a TGA file.
+///
+/// TGA files have no signature, so this checks the consistency of the header.
+pub fn is_tga(data: &[u8]) -> bool {
+    if data.len() < HEADER_SIZE {
+        return false;
+    }
+    let (color_map_type, image_type, pixel_depth) = (data[1], data[2], data[16]);
+    let color_map_depth = data[7];
+    let width = u16::from_le_bytes([data[12], data[13]]);
+    let height = u16::from_le_bytes([data[14], data[15]]);
+    let valid_color_map = match color_map_type {
+        0 => true,
+        1 => matches!(color_map_depth, 15 | 16 | 24 | 32),
+        _ => false,
+    };
+    let valid_depth = match image_type & !8 {
+        1 => color_map_type == 1 && matches!(pixel_depth, 8 | 16),
+        2 => matches!(pixel_depth, 15 | 16 | 24 | 32),
+        3 => matches!(pixel_depth, 8 | 16),
+        _ => false,
+    };
+    valid_color_map && valid_depth && width > 0 && height > 0
+}
+
+/// Decodes a TGA image.
+///
+/// Supports color-mapped, true-color and grayscale images, either uncompressed or
+/// run-length encoded.
+pub fn decode(data: &[u8]) -> Result<Image> {
+    if !is_tga(data) {
+        return Err(invalid("Invalid TGA header"));
+    }
+    let id_length = data[0] as usize;
+    let (color_map_type, image_type) = (data[1], data[2]);
+    let color_map_start = u16::from_le_bytes([data[3], data[4]]) as usize;
+    let color_map_length = u16::from_le_bytes([data[5], data[6]]) as usize;
+    let color_map_depth = data[7];
+    let width = u16::from_le_bytes([data[12], data[13]]);
+    let height = u16::from_le_bytes([data[14], data[15]]);
+    let pixel_depth = data[16];
+    let descriptor = data[17];
+    let alpha_bits = descriptor & 0x0f;
+
+    let mut position = HEADER_SIZE + id_length;
+    if position > data.len() {
+        return Err(invalid("Truncated image identifier"));
+    }
+    let mut color_map = Vec::new();
+    if color_map_type == 1 {
+        let entry_size = bytes_per_pixel(color_map_depth);
+        let end = position + color_map_length * entry_size;
+        if end > data.len() {
+            return Err(invalid("Truncated color map"));
+        }
+        color_map = data[position..end]
+            .chunks_exact(entry_size)
+            .map(|entry| read_color(entry, color_map_depth, alpha_bits))
+            .collect();
+        position = end;
+    }
+
+    let pixels = pixel_count(u32::from(width), u32::from(height))?;
+    let pixel_size = bytes_per_pixel(pixel_depth);
+    let body = &data[position..];
+    let rle = image_type & 8 != 0;
+    let enough_data = if rle {
+        pixels <= body.len().saturating_mul(MAX_RLE_PIXELS_PER_BYTE)
+    } else {
+        pixels * pixel_size <= body.len()
+    };
+    if !enough_data {
+        return Err(invalid("Not enough image data"));
+    }
+
+    // Reads raw pixels, run-length decoding them if needed.
+    let mut raw = Vec::with_capacity(pixels * pixel_size);
+    if rle {
+        let mut offset = 0;
+        while raw.len() < pixels * pixel_size {
+            let packet = *body
+                .get(offset)
+                .ok_or_else(|| invalid("Truncated RLE data"))?;
+            let count = (packet & 0x7f) as usize + 1;
+            let size = if packet & 0x80 != 0 {
+                pixel_size
+            } else {
+                count * pixel_size
+            };
+            let values = body
+                .get(offset + 1..offset + 1 + size)
+                .ok_or_else(|| invalid("Truncated RLE data"))?;
+            if packet & 0x80 != 0 {
+                for _ in 0..count {
+                    raw.extend_from_slice(values);
+                }
+            } else {
+                raw.extend_from_slice(values);
+            }
+            offset += 1 + size;
+        }
+        raw.truncate(pixels * pixel_size);
+    } else {
+        raw.extend_from_slice(&body[..pixels * pixel_size]);
+    }
+
+    // Converts pixels to RGBA, top row first.
+    let (width, height) = (width as usize, height as usize);
+    let top_to_bottom = descriptor & 0x20 != 0;
+    let right_to_left = descriptor & 0x10 != 0;
+    let mut output = vec![0; pixels * 4];
+    for (index, value) in raw.chunks_exact(pixel_size).enumerate() {
+        let color = match image_type & !8 {
+            1 => {
+                let entry = if pixel_size == 2 {
+                    u16::from_le_bytes([value[0], value[1]]) as usize
+                } else {
+                    value[0] as usize
+                };
+                *entry
+                    .checked_sub(color_map_start)
+                    .and_then(|entry| color_map.get(entry))
+                    .ok_or_else(|| invalid("Color map index out of range"))?
+            }
+            2 => read_color(value, pixel_depth, alpha_bits),
+            _ => {
+                let alpha = if pixel_size == 2 { value[1] } else { 0xff };
+                [value[0], value[0], value[0], alpha]
+            }
+        };
+        let (mut x, mut y) = (index % width, index / width);
+        if right_to_left {
+            x = width - 1 - x;
+        }
+        if !top_to_bottom {
+            y = height - 1 - y;
+        }
+        let offset = (y * width + x) * 4;
+        output[offset..offset + 4].copy_from_slice(&color);
+    }
+    Ok(Image::from_raw(width as u32, height as u32, PixelFormat::Rgba8, output).unwrap())
+}
+
+/// Returns the size in bytes of a pixel of the given depth.
+fn bytes_per_pixel(depth: u8) -> usize {
+    (depth as usize + 7) / 8
+}
+
+/// Reads a little-endian BGR(A) color of 15, 16, 24 or 32 bits.
+fn read_color(value: &[u8], depth: u8, alpha_bits: u8) -> [u8; 4] {
+    match depth {
+        15 | 16 => {
+            let value = u16::from_le_bytes([value[0], value[1]]);
+            let scale = |component: u16| ((component & 0x1f) * 255 / 31) as u8;
+            let alpha = if depth == 16 && alpha_bits > 0 && value & 0x8000 == 0 {
+                0
+            } else {
+                0xff
+            };
+            [scale(value >> 10), scale(value >> 5), scale(value), alpha]
+        }
+        24 => [value[2], value[1], value[0], 0xff],
+        _ => [value[2], value[1], value[0], value[3]],
+    }
+}
+
+/// Creates an invalid data error.
+fn invalid(message: &str) -> DecodeImageError {
+    DecodeImageError::InvalidData(String::from(message))
+}
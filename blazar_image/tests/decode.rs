@@ -0,0 +1,134 @@
+use blazar_image::{png, qoi, tga, DecodeImageError, Image, PixelFormat};
+
+/// Checks that an image matches its expected RGBA data, 16-bit components being stored in
+/// big-endian byte order.
+fn check(image: &Image, expected: &[u8]) {
+    let wide = image.format() == PixelFormat::Rgba16;
+    let component_size = if wide { 2 } else { 1 };
+    assert_eq!(
+        image.width() as usize * image.height() as usize * 4 * component_size,
+        expected.len()
+    );
+    for y in 0..image.height() {
+        for x in 0..image.width() {
+            let index = (y * image.width() + x) as usize * 4;
+            let pixel = image.pixel(x, y);
+            for (channel, component) in pixel.iter().enumerate() {
+                let expected = if wide {
+                    let offset = (index + channel) * 2;
+                    u16::from_be_bytes([expected[offset], expected[offset + 1]])
+                } else {
+                    u16::from(expected[index + channel]) * 257
+                };
+                assert_eq!(*component, expected, "pixel ({}, {})", x, y);
+            }
+        }
+    }
+}
+
+macro_rules! fixture_tests {
+    ($($name:ident: $file:expr, $expected:expr, $format:expr;)*) => {
+        $(
+            #[test]
+            fn $name() {
+                let image = Image::decode(include_bytes!(concat!("data/", $file))).unwrap();
+                assert_eq!(image.format(), $format);
+                check(&image, include_bytes!(concat!("data/", $expected)));
+            }
+        )*
+    };
+}
+
+fixture_tests! {
+    png_gray1: "gray1.png", "gray1.rgba8", PixelFormat::Rgba8;
+    png_gray2_interlaced: "gray2_interlaced.png", "gray2_interlaced.rgba8", PixelFormat::Rgba8;
+    png_gray4: "gray4.png", "gray4.rgba8", PixelFormat::Rgba8;
+    png_gray8_interlaced: "gray8_interlaced.png", "gray8_interlaced.rgba8", PixelFormat::Rgba8;
+    png_gray8_trns: "gray8_trns.png", "gray8_trns.rgba8", PixelFormat::Rgba8;
+    png_gray16: "gray16.png", "gray16.rgba16", PixelFormat::Rgba16;
+    png_gray_alpha8: "gray_alpha8.png", "gray_alpha8.rgba8", PixelFormat::Rgba8;
+    png_gray_alpha16_interlaced:
+        "gray_alpha16_interlaced.png", "gray_alpha16_interlaced.rgba16", PixelFormat::Rgba16;
+    png_rgb8: "rgb8.png", "rgb8.rgba8", PixelFormat::Rgba8;
+    png_rgb8_trns_stored: "rgb8_trns_stored.png", "rgb8_trns_stored.rgba8", PixelFormat::Rgba8;
+    png_rgb16_interlaced: "rgb16_interlaced.png", "rgb16_interlaced.rgba16", PixelFormat::Rgba16;
+    png_palette1: "palette1.png", "palette1.rgba8", PixelFormat::Rgba8;
+    png_palette2: "palette2.png", "palette2.rgba8", PixelFormat::Rgba8;
+    png_palette4_interlaced: "palette4_interlaced.png", "palette4_interlaced.rgba8", PixelFormat::Rgba8;
+    png_palette8: "palette8.png", "palette8.rgba8", PixelFormat::Rgba8;
+    png_rgba8_interlaced: "rgba8_interlaced.png", "rgba8_interlaced.rgba8", PixelFormat::Rgba8;
+    png_rgba8_tiny_interlaced:
+        "rgba8_tiny_interlaced.png", "rgba8_tiny_interlaced.rgba8", PixelFormat::Rgba8;
+    png_rgba16: "rgba16.png", "rgba16.rgba16", PixelFormat::Rgba16;
+    tga_rgb24: "rgb24.tga", "rgb24.rgba8", PixelFormat::Rgba8;
+    tga_rgb16_rle: "rgb16_rle.tga", "rgb16_rle.rgba8", PixelFormat::Rgba8;
+    tga_rgba32_rle: "rgba32_rle.tga", "rgba32_rle.rgba8", PixelFormat::Rgba8;
+    tga_gray8_rle: "gray8_rle.tga", "gray8_rle.rgba8", PixelFormat::Rgba8;
+    tga_color_mapped8: "color_mapped8.tga", "color_mapped8.rgba8", PixelFormat::Rgba8;
+    qoi_rgba: "rgba.qoi", "rgba.rgba8", PixelFormat::Rgba8;
+}
+
+#[test]
+fn format_detection() {
+    assert!(png::is_png(include_bytes!("data/rgb8.png")));
+    assert!(qoi::is_qoi(include_bytes!("data/rgba.qoi")));
+    assert!(tga::is_tga(include_bytes!("data/rgb24.tga")));
+    assert!(!tga::is_tga(include_bytes!("data/rgb8.png")));
+    match Image::decode(b"not an image") {
+        Err(DecodeImageError::UnknownFormat) => {}
+        result => panic!("unexpected result: {:?}", result),
+    }
+}
+
+#[test]
+fn png_checksum_mismatch() {
+    let mut data = include_bytes!("data/rgb8.png").to_vec();
+    let last = data.len() - 20;
+    data[last] ^= 0xff;
+    match png::decode(&data) {
+        Err(DecodeImageError::InvalidData(_)) => {}
+        result => panic!("unexpected result: {:?}", result),
+    }
+}
+
+#[test]
+fn png_roundtrip() {
+    for path in &[
+        "data/rgba8_interlaced.png",
+        "data/rgb8.png",
+        "data/rgba16.png",
+    ] {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join(path);
+        let image = Image::open(path).unwrap();
+        let decoded = png::decode(&png::encode(&image)).unwrap();
+        assert_eq!(decoded, image);
+    }
+}
+
+#[test]
+fn png_roundtrip_compressible() {
+    let mut image = Image::new(300, 200, PixelFormat::Rgba8);
+    for (index, pixel) in image.data_mut().chunks_exact_mut(4).enumerate() {
+        let (x, y) = (index % 300, index / 300);
+        pixel.copy_from_slice(&[(x / 10) as u8, (y / 10) as u8, 0x80, 0xff]);
+    }
+    let encoded = png::encode(&image);
+    assert!(encoded.len() < image.data().len() / 10);
+    assert_eq!(png::decode(&encoded).unwrap(), image);
+}
+
+#[test]
+fn flip_and_convert() {
+    let mut image = Image::decode(include_bytes!("data/rgba16.png")).unwrap();
+    let top = image.pixel(3, 0);
+    image.flip_vertically();
+    assert_eq!(image.pixel(3, image.height() - 1), top);
+    let image = image.to_rgba8();
+    assert_eq!(image.format(), PixelFormat::Rgba8);
+    assert_eq!(
+        u32::from(image.pixel(3, image.height() - 1)[0]) / 257,
+        (u32::from(top[0]) * 255 + 32767) / 65535
+    );
+}
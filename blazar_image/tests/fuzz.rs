@@ -0,0 +1,118 @@
+use blazar_image::Image;
+
+/// Deterministic pseudo-random number generator.
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+}
+
+/// Decodes randomly mutated valid files, checking that decoding never panics.
+fn fuzz(data: &[u8], iterations: usize, seed: u64) {
+    let mut random = XorShift(seed);
+    for _ in 0..iterations {
+        let mut data = data.to_vec();
+        for _ in 0..1 + random.below(8) {
+            let index = random.below(data.len());
+            match random.below(4) {
+                0 => data[index] = random.next() as u8,
+                1 => data[index] ^= 1 << random.below(8),
+                2 => data.truncate(index.max(1)),
+                _ => {
+                    let byte = random.next() as u8;
+                    data.insert(index, byte);
+                }
+            }
+        }
+        if let Ok(image) = Image::decode(&data) {
+            assert_eq!(
+                image.data().len(),
+                image.width() as usize * image.height() as usize * image.format().bytes_per_pixel()
+            );
+        }
+    }
+}
+
+/// Strips and recomputes the checksums of PNG chunks, so that mutations reach the
+/// decompressor and the unfiltering code.
+fn fix_png_checksums(data: &mut [u8]) {
+    let mut position = 8;
+    while position + 12 <= data.len() {
+        let length = u32::from_be_bytes([
+            data[position],
+            data[position + 1],
+            data[position + 2],
+            data[position + 3],
+        ]) as usize;
+        if position + 12 + length > data.len() {
+            return;
+        }
+        let crc = crc32(&data[position + 4..position + 8 + length]);
+        data[position + 8 + length..position + 12 + length].copy_from_slice(&crc.to_be_bytes());
+        position += 12 + length;
+    }
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in data {
+        crc ^= u32::from(*byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                0xedb8_8320 ^ (crc >> 1)
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+#[test]
+fn fuzz_png() {
+    let files: [&[u8]; 4] = [
+        include_bytes!("data/rgba8_interlaced.png"),
+        include_bytes!("data/palette2.png"),
+        include_bytes!("data/rgb16_interlaced.png"),
+        include_bytes!("data/rgb8_trns_stored.png"),
+    ];
+    for (seed, file) in files.iter().enumerate() {
+        fuzz(file, 500, seed as u64 + 1);
+        let mut random = XorShift(seed as u64 + 100);
+        for _ in 0..500 {
+            let mut data = file.to_vec();
+            for _ in 0..1 + random.below(4) {
+                let index = 33 + random.below(data.len() - 33);
+                data[index] = random.next() as u8;
+            }
+            fix_png_checksums(&mut data);
+            let _ = Image::decode(&data);
+        }
+    }
+}
+
+#[test]
+fn fuzz_tga() {
+    let files: [&[u8]; 3] = [
+        include_bytes!("data/rgba32_rle.tga"),
+        include_bytes!("data/color_mapped8.tga"),
+        include_bytes!("data/rgb16_rle.tga"),
+    ];
+    for (seed, file) in files.iter().enumerate() {
+        fuzz(file, 2000, seed as u64 + 200);
+    }
+}
+
+#[test]
+fn fuzz_qoi() {
+    fuzz(include_bytes!("data/rgba.qoi"), 2000, 300);
+}
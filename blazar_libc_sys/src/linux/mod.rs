@@ -1,8 +1,20 @@
 //! Linux-specific definitions.
 
+#![allow(non_camel_case_types)]
+
 use std::os::raw::*;
 
+// Types
+pub type key_t = c_int;
+pub type size_t = usize;
+
+// Opaque structures
+pub enum shmid_ds {}
+
 // Constants
+pub const IPC_CREAT: c_int = 0o1000;
+pub const IPC_PRIVATE: key_t = 0;
+pub const IPC_RMID: c_int = 0;
 pub const RTLD_NOW: c_int = 0x0002;
 
 // Functions
@@ -12,3 +24,11 @@ extern "C" {
     pub fn dlopen(filename: *const c_char, flags: c_int) -> *mut c_void;
     pub fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void;
 }
+
+#[link(name = "c")]
+extern "C" {
+    pub fn shmat(shmid: c_int, shmaddr: *const c_void, shmflg: c_int) -> *mut c_void;
+    pub fn shmctl(shmid: c_int, cmd: c_int, buf: *mut shmid_ds) -> c_int;
+    pub fn shmdt(shmaddr: *const c_void) -> c_int;
+    pub fn shmget(key: key_t, size: size_t, shmflg: c_int) -> c_int;
+}
@@ -0,0 +1,202 @@
+//! Axis-aligned bounding boxes.
+
+use crate::{
+    geometry::{Ray, RayHit, Sphere},
+    mat::Mat4,
+    vec::Vec3,
+};
+
+/// Axis-aligned bounding box.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Aabb {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Aabb {
+    /// Creates a box from its minimum and maximum corners.
+    pub fn new(min: Vec3, max: Vec3) -> Aabb {
+        Aabb { min, max }
+    }
+
+    /// Creates a box from its center and half its size along each axis.
+    pub fn from_center_half_extents(center: Vec3, half_extents: Vec3) -> Aabb {
+        Aabb::new(center - half_extents, center + half_extents)
+    }
+
+    /// Returns the smallest box containing the points, or `None` if there are none.
+    pub fn from_points<I: IntoIterator<Item = Vec3>>(points: I) -> Option<Aabb> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        Some(points.fold(Aabb::new(first, first), |aabb, point| {
+            aabb.expand_to_point(point)
+        }))
+    }
+
+    /// Returns the center of the box.
+    pub fn center(&self) -> Vec3 {
+        (self.min + self.max) * 0.5
+    }
+
+    /// Returns half the size of the box along each axis.
+    pub fn half_extents(&self) -> Vec3 {
+        (self.max - self.min) * 0.5
+    }
+
+    /// Returns the size of the box along each axis.
+    pub fn size(&self) -> Vec3 {
+        self.max - self.min
+    }
+
+    /// Returns the volume of the box.
+    pub fn volume(&self) -> f32 {
+        let size = self.size();
+        size.x * size.y * size.z
+    }
+
+    /// Returns the eight corners, the bits of the index selecting the maximum along x, y
+    /// and z.
+    pub fn corners(&self) -> [Vec3; 8] {
+        let mut corners = [Vec3::ZERO; 8];
+        for (index, corner) in corners.iter_mut().enumerate() {
+            *corner = Vec3::new(
+                if index & 1 == 0 {
+                    self.min.x
+                } else {
+                    self.max.x
+                },
+                if index & 2 == 0 {
+                    self.min.y
+                } else {
+                    self.max.y
+                },
+                if index & 4 == 0 {
+                    self.min.z
+                } else {
+                    self.max.z
+                },
+            );
+        }
+        corners
+    }
+
+    /// Returns the smallest box containing both boxes.
+    pub fn union(&self, other: &Aabb) -> Aabb {
+        Aabb::new(self.min.min(other.min), self.max.max(other.max))
+    }
+
+    /// Returns the overlap of both boxes, or `None` if they are disjoint.
+    pub fn intersection(&self, other: &Aabb) -> Option<Aabb> {
+        let aabb = Aabb::new(self.min.max(other.min), self.max.min(other.max));
+        if aabb.min.x <= aabb.max.x && aabb.min.y <= aabb.max.y && aabb.min.z <= aabb.max.z {
+            Some(aabb)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the smallest box containing this box and `point`.
+    pub fn expand_to_point(&self, point: Vec3) -> Aabb {
+        Aabb::new(self.min.min(point), self.max.max(point))
+    }
+
+    /// Returns the box grown by `margin` on every side.
+    pub fn inflate(&self, margin: f32) -> Aabb {
+        Aabb::new(self.min - margin, self.max + margin)
+    }
+
+    /// Returns the box containing this box once transformed by an affine matrix.
+    pub fn transform(&self, matrix: &Mat4) -> Aabb {
+        let center = matrix.transform_point3(self.center());
+        let half_extents = self.half_extents();
+        let mut extents = Vec3::ZERO;
+        for axis in 0..3 {
+            let column = matrix.columns[axis].truncate().abs();
+            extents += column * half_extents[axis];
+        }
+        Aabb::from_center_half_extents(center, extents)
+    }
+
+    /// Returns `true` if the point is inside the box or on its boundary.
+    pub fn contains_point(&self, point: Vec3) -> bool {
+        point.x >= self.min.x
+            && point.y >= self.min.y
+            && point.z >= self.min.z
+            && point.x <= self.max.x
+            && point.y <= self.max.y
+            && point.z <= self.max.z
+    }
+
+    /// Returns `true` if the other box is entirely inside this one.
+    pub fn contains_aabb(&self, other: &Aabb) -> bool {
+        self.contains_point(other.min) && self.contains_point(other.max)
+    }
+
+    /// Returns `true` if both boxes overlap or touch.
+    pub fn intersects_aabb(&self, other: &Aabb) -> bool {
+        self.min.x <= other.max.x
+            && self.min.y <= other.max.y
+            && self.min.z <= other.max.z
+            && other.min.x <= self.max.x
+            && other.min.y <= self.max.y
+            && other.min.z <= self.max.z
+    }
+
+    /// Returns `true` if the box and the sphere overlap or touch.
+    pub fn intersects_sphere(&self, sphere: &Sphere) -> bool {
+        self.distance_squared_to_point(sphere.center) <= sphere.radius * sphere.radius
+    }
+
+    /// Returns the point of the box closest to `point`, which is `point` itself if it is
+    /// inside.
+    pub fn closest_point(&self, point: Vec3) -> Vec3 {
+        point.clamp(self.min, self.max)
+    }
+
+    /// Returns the squared distance from the box to `point`, 0 if it is inside.
+    pub fn distance_squared_to_point(&self, point: Vec3) -> f32 {
+        self.closest_point(point).distance_squared(point)
+    }
+
+    /// Returns the distance from the box to `point`, 0 if it is inside.
+    pub fn distance_to_point(&self, point: Vec3) -> f32 {
+        self.distance_squared_to_point(point).sqrt()
+    }
+
+    /// Casts a ray against the box.
+    pub fn intersect_ray(&self, ray: &Ray) -> Option<RayHit> {
+        let mut near = 0.0f32;
+        let mut far = f32::INFINITY;
+        let mut normal = Vec3::ZERO;
+        for axis in 0..3 {
+            let origin = ray.origin[axis];
+            let direction = ray.direction[axis];
+            if direction.abs() <= f32::EPSILON {
+                if origin < self.min[axis] || origin > self.max[axis] {
+                    return None;
+                }
+                continue;
+            }
+            let inverse = 1.0 / direction;
+            let mut t0 = (self.min[axis] - origin) * inverse;
+            let mut t1 = (self.max[axis] - origin) * inverse;
+            if t0 > t1 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+            if t0 > near {
+                near = t0;
+                normal = Vec3::ZERO;
+                normal[axis] = -direction.signum();
+            }
+            far = far.min(t1);
+            if near > far {
+                return None;
+            }
+        }
+        if normal == Vec3::ZERO {
+            Some(RayHit::inside(ray))
+        } else {
+            Some(RayHit::new(ray, near, normal))
+        }
+    }
+}
@@ -0,0 +1,100 @@
+//! Capsules.
+
+use crate::{
+    geometry::{closest_point_on_segment, closest_points_on_segments, Aabb, Ray, RayHit, Sphere},
+    vec::Vec3,
+};
+
+/// Points within a radius of a segment.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Capsule {
+    pub start: Vec3,
+    pub end: Vec3,
+    pub radius: f32,
+}
+
+impl Capsule {
+    /// Creates a capsule around the segment from `start` to `end`.
+    pub fn new(start: Vec3, end: Vec3, radius: f32) -> Capsule {
+        Capsule { start, end, radius }
+    }
+
+    /// Returns the smallest box containing the capsule.
+    pub fn aabb(&self) -> Aabb {
+        Aabb::new(self.start.min(self.end), self.start.max(self.end)).inflate(self.radius)
+    }
+
+    /// Returns the point of the inner segment closest to `point`.
+    pub fn closest_point_on_segment(&self, point: Vec3) -> Vec3 {
+        closest_point_on_segment(self.start, self.end, point)
+    }
+
+    /// Returns `true` if the point is inside the capsule or on its boundary.
+    pub fn contains_point(&self, point: Vec3) -> bool {
+        self.closest_point_on_segment(point).distance_squared(point) <= self.radius * self.radius
+    }
+
+    /// Returns the point of the capsule closest to `point`, which is `point` itself if it
+    /// is inside.
+    pub fn closest_point(&self, point: Vec3) -> Vec3 {
+        Sphere::new(self.closest_point_on_segment(point), self.radius).closest_point(point)
+    }
+
+    /// Returns the distance from the capsule to `point`, 0 if it is inside.
+    pub fn distance_to_point(&self, point: Vec3) -> f32 {
+        (self.closest_point_on_segment(point).distance(point) - self.radius).max(0.0)
+    }
+
+    /// Returns `true` if the capsule and the sphere overlap or touch.
+    pub fn intersects_sphere(&self, sphere: &Sphere) -> bool {
+        let radius = self.radius + sphere.radius;
+        self.closest_point_on_segment(sphere.center)
+            .distance_squared(sphere.center)
+            <= radius * radius
+    }
+
+    /// Returns `true` if both capsules overlap or touch.
+    pub fn intersects_capsule(&self, other: &Capsule) -> bool {
+        let (a, b) = closest_points_on_segments(self.start, self.end, other.start, other.end);
+        let radius = self.radius + other.radius;
+        a.distance_squared(b) <= radius * radius
+    }
+
+    /// Casts a ray against the capsule.
+    pub fn intersect_ray(&self, ray: &Ray) -> Option<RayHit> {
+        if self.contains_point(ray.origin) {
+            return Some(RayHit::inside(ray));
+        }
+        let axis = self.end - self.start;
+        let offset = ray.origin - self.start;
+        let axis_length = axis.length_squared();
+        let axis_direction = axis.dot(ray.direction);
+        let axis_offset = axis.dot(offset);
+        // Infinite cylinder around the segment, the caps being tested as spheres.
+        let a = axis_length - axis_direction * axis_direction;
+        if a > f32::EPSILON * axis_length {
+            let b = axis_length * offset.dot(ray.direction) - axis_offset * axis_direction;
+            let c = axis_length * offset.length_squared()
+                - axis_offset * axis_offset
+                - self.radius * self.radius * axis_length;
+            let discriminant = b * b - a * c;
+            if discriminant < 0.0 {
+                return None;
+            }
+            let distance = (-b - discriminant.sqrt()) / a;
+            let height = axis_offset + distance * axis_direction;
+            if distance >= 0.0 && height > 0.0 && height < axis_length {
+                let point = ray.at(distance);
+                let normal = (point - self.closest_point_on_segment(point)).normalize_or_zero();
+                return Some(RayHit::new(ray, distance, normal));
+            }
+        }
+        let start = Sphere::new(self.start, self.radius).intersect_ray(ray);
+        let end = Sphere::new(self.end, self.radius).intersect_ray(ray);
+        match (start, end) {
+            (Some(start), Some(end)) if end.distance < start.distance => Some(end),
+            (Some(start), _) => Some(start),
+            (None, end) => end,
+        }
+    }
+}
@@ -0,0 +1,103 @@
+//! View frustums.
+
+use crate::{
+    geometry::{Aabb, Obb, Plane, Sphere},
+    mat::Mat4,
+    vec::{Vec3, Vec4},
+};
+
+/// Convex volume bounded by six planes facing inwards, typically the volume seen by a
+/// camera.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Frustum {
+    /// Left, right, top, bottom, near and far planes.
+    pub planes: [Plane; 6],
+}
+
+impl Frustum {
+    pub const LEFT: usize = 0;
+    pub const RIGHT: usize = 1;
+    pub const TOP: usize = 2;
+    pub const BOTTOM: usize = 3;
+    pub const NEAR: usize = 4;
+    pub const FAR: usize = 5;
+
+    /// Extracts the frustum of a matrix transforming to the Vulkan clip space.
+    ///
+    /// With a reversed depth, the near and far planes are swapped. The plane at infinity
+    /// of an infinite projection contains every point in front of it.
+    pub fn from_matrix(matrix: &Mat4) -> Frustum {
+        let (x, y, z, w) = (matrix.row(0), matrix.row(1), matrix.row(2), matrix.row(3));
+        let plane = |coefficients: Vec4| {
+            Plane::from_coefficients(coefficients).unwrap_or(Plane {
+                normal: Vec3::ZERO,
+                d: coefficients.w,
+            })
+        };
+        Frustum {
+            planes: [
+                plane(w + x),
+                plane(w - x),
+                plane(w + y),
+                plane(w - y),
+                plane(z),
+                plane(w - z),
+            ],
+        }
+    }
+
+    /// Returns `true` if the point is inside the frustum or on its boundary.
+    pub fn contains_point(&self, point: Vec3) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| plane.signed_distance(point) >= 0.0)
+    }
+
+    /// Returns `false` if the sphere is entirely outside the frustum.
+    ///
+    /// Spheres near the corners of the frustum may be reported as intersecting it while
+    /// they are outside, which is enough for culling.
+    pub fn intersects_sphere(&self, sphere: &Sphere) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| plane.signed_distance(sphere.center) >= -sphere.radius)
+    }
+
+    /// Returns `true` if the sphere is entirely inside the frustum.
+    pub fn contains_sphere(&self, sphere: &Sphere) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| plane.signed_distance(sphere.center) >= sphere.radius)
+    }
+
+    /// Returns `false` if the box is entirely outside the frustum, as conservatively as
+    /// `intersects_sphere`.
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        let center = aabb.center();
+        let half_extents = aabb.half_extents();
+        self.planes
+            .iter()
+            .all(|plane| plane.signed_distance(center) >= -plane.normal.abs().dot(half_extents))
+    }
+
+    /// Returns `true` if the box is entirely inside the frustum.
+    pub fn contains_aabb(&self, aabb: &Aabb) -> bool {
+        let center = aabb.center();
+        let half_extents = aabb.half_extents();
+        self.planes
+            .iter()
+            .all(|plane| plane.signed_distance(center) >= plane.normal.abs().dot(half_extents))
+    }
+
+    /// Returns `false` if the box is entirely outside the frustum, as conservatively as
+    /// `intersects_sphere`.
+    pub fn intersects_obb(&self, obb: &Obb) -> bool {
+        let axes = obb.axes();
+        self.planes.iter().all(|plane| {
+            let radius = plane.normal.dot(axes[0]).abs() * obb.half_extents.x
+                + plane.normal.dot(axes[1]).abs() * obb.half_extents.y
+                + plane.normal.dot(axes[2]).abs() * obb.half_extents.z;
+            plane.signed_distance(obb.center) >= -radius
+        })
+    }
+}
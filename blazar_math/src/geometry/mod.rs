@@ -0,0 +1,141 @@
+//! Geometry primitives and intersection queries.
+//!
+//! Ray casts return the nearest hit in front of the ray origin. A ray starting inside a
+//! solid shape hits it at a distance of zero, with a zero normal.
+
+mod aabb;
+mod capsule;
+mod frustum;
+mod obb;
+mod plane;
+mod sphere;
+mod triangle;
+
+pub use aabb::*;
+pub use capsule::*;
+pub use frustum::*;
+pub use obb::*;
+pub use plane::*;
+pub use sphere::*;
+pub use triangle::*;
+
+use crate::vec::Vec3;
+
+/// Half-line starting at an origin.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ray {
+    pub origin: Vec3,
+    /// Unit direction, distances along the ray being measured in world units.
+    pub direction: Vec3,
+}
+
+impl Ray {
+    /// Creates a ray, normalizing its direction.
+    pub fn new(origin: Vec3, direction: Vec3) -> Ray {
+        Ray {
+            origin,
+            direction: direction.normalize_or_zero(),
+        }
+    }
+
+    /// Creates a ray from `origin` through `target`.
+    pub fn from_points(origin: Vec3, target: Vec3) -> Ray {
+        Ray::new(origin, target - origin)
+    }
+
+    /// Returns the point at `distance` along the ray.
+    pub fn at(&self, distance: f32) -> Vec3 {
+        self.origin + self.direction * distance
+    }
+
+    /// Returns the point of the ray closest to `point`.
+    pub fn closest_point(&self, point: Vec3) -> Vec3 {
+        self.at((point - self.origin).dot(self.direction).max(0.0))
+    }
+
+    /// Returns the distance between the ray and `point`.
+    pub fn distance_to_point(&self, point: Vec3) -> f32 {
+        self.closest_point(point).distance(point)
+    }
+}
+
+/// Intersection of a ray with a shape.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RayHit {
+    /// Distance from the ray origin.
+    pub distance: f32,
+    pub point: Vec3,
+    /// Unit normal of the surface at the hit point, facing the ray origin.
+    pub normal: Vec3,
+}
+
+impl RayHit {
+    fn new(ray: &Ray, distance: f32, normal: Vec3) -> RayHit {
+        RayHit {
+            distance,
+            point: ray.at(distance),
+            normal,
+        }
+    }
+
+    fn inside(ray: &Ray) -> RayHit {
+        RayHit::new(ray, 0.0, Vec3::ZERO)
+    }
+}
+
+/// Returns the point of the segment `[start, end]` closest to `point`.
+pub(crate) fn closest_point_on_segment(start: Vec3, end: Vec3, point: Vec3) -> Vec3 {
+    let segment = end - start;
+    let length_squared = segment.length_squared();
+    if length_squared <= f32::EPSILON {
+        return start;
+    }
+    let t = ((point - start).dot(segment) / length_squared)
+        .max(0.0)
+        .min(1.0);
+    start + segment * t
+}
+
+/// Returns the closest points of the segments `[start_a, end_a]` and `[start_b, end_b]`.
+pub(crate) fn closest_points_on_segments(
+    start_a: Vec3,
+    end_a: Vec3,
+    start_b: Vec3,
+    end_b: Vec3,
+) -> (Vec3, Vec3) {
+    let direction_a = end_a - start_a;
+    let direction_b = end_b - start_b;
+    let offset = start_a - start_b;
+    let length_a = direction_a.length_squared();
+    let length_b = direction_b.length_squared();
+    let f = direction_b.dot(offset);
+    let (s, t) = if length_a <= f32::EPSILON && length_b <= f32::EPSILON {
+        (0.0, 0.0)
+    } else if length_a <= f32::EPSILON {
+        (0.0, (f / length_b).max(0.0).min(1.0))
+    } else {
+        let c = direction_a.dot(offset);
+        if length_b <= f32::EPSILON {
+            ((-c / length_a).max(0.0).min(1.0), 0.0)
+        } else {
+            let b = direction_a.dot(direction_b);
+            let denominator = length_a * length_b - b * b;
+            // Parallel segments pick an arbitrary point of the first one.
+            let mut s = if denominator > f32::EPSILON {
+                ((b * f - c * length_b) / denominator).max(0.0).min(1.0)
+            } else {
+                0.0
+            };
+            let mut t = (b * s + f) / length_b;
+            if t < 0.0 {
+                t = 0.0;
+                s = (-c / length_a).max(0.0).min(1.0);
+            } else if t > 1.0 {
+                t = 1.0;
+                s = ((b - c) / length_a).max(0.0).min(1.0);
+            }
+            (s, t)
+        }
+    };
+    (start_a + direction_a * s, start_b + direction_b * t)
+}
@@ -0,0 +1,172 @@
+//! Oriented bounding boxes.
+
+use crate::{
+    geometry::{Aabb, Ray, RayHit, Sphere},
+    quat::Quat,
+    transform::Transform,
+    vec::Vec3,
+};
+
+/// Oriented bounding box.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Obb {
+    pub center: Vec3,
+    pub half_extents: Vec3,
+    /// Rotation from the local axes of the box to world space.
+    pub rotation: Quat,
+}
+
+impl Obb {
+    /// Creates a box from its center, half its size along its local axes and its rotation.
+    pub fn new(center: Vec3, half_extents: Vec3, rotation: Quat) -> Obb {
+        Obb {
+            center,
+            half_extents,
+            rotation,
+        }
+    }
+
+    /// Returns the box covered by `aabb` once transformed, typically the local bounds of
+    /// a mesh placed in the world.
+    pub fn from_aabb_transform(aabb: &Aabb, transform: &Transform) -> Obb {
+        Obb::new(
+            transform.transform_point(aabb.center()),
+            aabb.half_extents() * transform.scale.abs(),
+            transform.rotation,
+        )
+    }
+
+    /// Returns the local x, y and z axes in world space.
+    pub fn axes(&self) -> [Vec3; 3] {
+        [
+            self.rotation * Vec3::X,
+            self.rotation * Vec3::Y,
+            self.rotation * Vec3::Z,
+        ]
+    }
+
+    /// Returns the eight corners, the bits of the index selecting the positive side along
+    /// the local x, y and z axes.
+    pub fn corners(&self) -> [Vec3; 8] {
+        let mut corners = Aabb::from_center_half_extents(Vec3::ZERO, self.half_extents).corners();
+        for corner in &mut corners {
+            *corner = self.center + self.rotation * *corner;
+        }
+        corners
+    }
+
+    /// Returns the smallest axis-aligned box containing this box.
+    pub fn aabb(&self) -> Aabb {
+        let axes = self.axes();
+        let extents = axes[0].abs() * self.half_extents.x
+            + axes[1].abs() * self.half_extents.y
+            + axes[2].abs() * self.half_extents.z;
+        Aabb::from_center_half_extents(self.center, extents)
+    }
+
+    /// Converts a world position to the local space of the box, centered on it.
+    pub fn to_local(&self, point: Vec3) -> Vec3 {
+        self.rotation.conjugate() * (point - self.center)
+    }
+
+    /// Returns `true` if the point is inside the box or on its boundary.
+    pub fn contains_point(&self, point: Vec3) -> bool {
+        let local = self.to_local(point).abs();
+        local.x <= self.half_extents.x
+            && local.y <= self.half_extents.y
+            && local.z <= self.half_extents.z
+    }
+
+    /// Returns the point of the box closest to `point`, which is `point` itself if it is
+    /// inside.
+    pub fn closest_point(&self, point: Vec3) -> Vec3 {
+        let local = self
+            .to_local(point)
+            .clamp(-self.half_extents, self.half_extents);
+        self.center + self.rotation * local
+    }
+
+    /// Returns the distance from the box to `point`, 0 if it is inside.
+    pub fn distance_to_point(&self, point: Vec3) -> f32 {
+        self.closest_point(point).distance(point)
+    }
+
+    /// Returns `true` if the box and the sphere overlap or touch.
+    pub fn intersects_sphere(&self, sphere: &Sphere) -> bool {
+        self.closest_point(sphere.center)
+            .distance_squared(sphere.center)
+            <= sphere.radius * sphere.radius
+    }
+
+    /// Returns `true` if both boxes overlap or touch.
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        self.intersects_obb(&Obb::from(*aabb))
+    }
+
+    /// Tests the boxes for overlap with the separating axis theorem.
+    pub fn intersects_obb(&self, other: &Obb) -> bool {
+        let axes_a = self.axes();
+        let axes_b = other.axes();
+        // Rotation of `other` in the local space of `self`, padded against the
+        // cross products of nearly parallel edges.
+        let mut rotation = [[0.0; 3]; 3];
+        let mut absolute = [[0.0; 3]; 3];
+        for i in 0..3 {
+            for j in 0..3 {
+                rotation[i][j] = axes_a[i].dot(axes_b[j]);
+                absolute[i][j] = rotation[i][j].abs() + 1e-6;
+            }
+        }
+        let offset = other.center - self.center;
+        let t = [
+            offset.dot(axes_a[0]),
+            offset.dot(axes_a[1]),
+            offset.dot(axes_a[2]),
+        ];
+        let a = self.half_extents;
+        let b = other.half_extents;
+        for i in 0..3 {
+            let radius_b = b[0] * absolute[i][0] + b[1] * absolute[i][1] + b[2] * absolute[i][2];
+            if t[i].abs() > a[i] + radius_b {
+                return false;
+            }
+        }
+        for j in 0..3 {
+            let radius_a = a[0] * absolute[0][j] + a[1] * absolute[1][j] + a[2] * absolute[2][j];
+            let distance = t[0] * rotation[0][j] + t[1] * rotation[1][j] + t[2] * rotation[2][j];
+            if distance.abs() > radius_a + b[j] {
+                return false;
+            }
+        }
+        for i in 0..3 {
+            let (i1, i2) = ((i + 1) % 3, (i + 2) % 3);
+            for j in 0..3 {
+                let (j1, j2) = ((j + 1) % 3, (j + 2) % 3);
+                let radius_a = a[i1] * absolute[i2][j] + a[i2] * absolute[i1][j];
+                let radius_b = b[j1] * absolute[i][j2] + b[j2] * absolute[i][j1];
+                let distance = t[i2] * rotation[i1][j] - t[i1] * rotation[i2][j];
+                if distance.abs() > radius_a + radius_b {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    /// Casts a ray against the box.
+    pub fn intersect_ray(&self, ray: &Ray) -> Option<RayHit> {
+        let local = Ray {
+            origin: self.to_local(ray.origin),
+            direction: self.rotation.conjugate() * ray.direction,
+        };
+        let hit =
+            Aabb::from_center_half_extents(Vec3::ZERO, self.half_extents).intersect_ray(&local)?;
+        Some(RayHit::new(ray, hit.distance, self.rotation * hit.normal))
+    }
+}
+
+impl From<Aabb> for Obb {
+    fn from(aabb: Aabb) -> Obb {
+        Obb::new(aabb.center(), aabb.half_extents(), Quat::IDENTITY)
+    }
+}
@@ -0,0 +1,84 @@
+//! Planes.
+
+use crate::{
+    geometry::{Ray, RayHit},
+    vec::{Vec3, Vec4},
+};
+
+/// Plane of the points `p` such that `normal.dot(p) + d == 0`.
+///
+/// The normal points to the positive half-space.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Plane {
+    /// Unit normal.
+    pub normal: Vec3,
+    /// Signed distance of the origin to the plane.
+    pub d: f32,
+}
+
+impl Plane {
+    /// Creates a plane from a unit normal and the signed distance of the origin to it.
+    pub fn new(normal: Vec3, d: f32) -> Plane {
+        Plane { normal, d }
+    }
+
+    /// Creates a plane passing through `point`, normalizing `normal`.
+    pub fn from_point_normal(point: Vec3, normal: Vec3) -> Plane {
+        let normal = normal.normalize_or_zero();
+        Plane::new(normal, -normal.dot(point))
+    }
+
+    /// Creates a plane passing through three points, facing the side they are seen
+    /// counterclockwise from, or `None` if they are collinear.
+    pub fn from_points(a: Vec3, b: Vec3, c: Vec3) -> Option<Plane> {
+        let normal = (b - a).cross(c - a).try_normalize()?;
+        Some(Plane::new(normal, -normal.dot(a)))
+    }
+
+    /// Creates a plane from the coefficients of its equation, normalizing them, or `None`
+    /// if the normal is zero.
+    pub fn from_coefficients(coefficients: Vec4) -> Option<Plane> {
+        let length = coefficients.truncate().length();
+        if length <= f32::EPSILON || !length.is_finite() {
+            return None;
+        }
+        Some(Plane::new(
+            coefficients.truncate() / length,
+            coefficients.w / length,
+        ))
+    }
+
+    /// Returns the signed distance of `point`, positive in front of the plane.
+    pub fn signed_distance(&self, point: Vec3) -> f32 {
+        self.normal.dot(point) + self.d
+    }
+
+    /// Returns the projection of `point` onto the plane.
+    pub fn closest_point(&self, point: Vec3) -> Vec3 {
+        point - self.normal * self.signed_distance(point)
+    }
+
+    /// Returns the plane facing the other side.
+    pub fn flip(&self) -> Plane {
+        Plane::new(-self.normal, -self.d)
+    }
+
+    /// Casts a ray against the plane, hitting it from either side.
+    pub fn intersect_ray(&self, ray: &Ray) -> Option<RayHit> {
+        let distance = self.signed_distance(ray.origin);
+        let cos = self.normal.dot(ray.direction);
+        if cos.abs() <= f32::EPSILON {
+            return None;
+        }
+        let t = -distance / cos;
+        if t < 0.0 {
+            return None;
+        }
+        let normal = if distance < 0.0 {
+            -self.normal
+        } else {
+            self.normal
+        };
+        Some(RayHit::new(ray, t, normal))
+    }
+}
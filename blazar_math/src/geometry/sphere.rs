@@ -0,0 +1,98 @@
+//! Spheres.
+
+use crate::{
+    geometry::{Aabb, Ray, RayHit},
+    vec::Vec3,
+};
+
+/// Bounding sphere.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Sphere {
+    pub center: Vec3,
+    pub radius: f32,
+}
+
+impl Sphere {
+    /// Creates a sphere from its center and radius.
+    pub fn new(center: Vec3, radius: f32) -> Sphere {
+        Sphere { center, radius }
+    }
+
+    /// Returns a sphere containing the points, centered on their bounding box, or `None`
+    /// if there are none.
+    pub fn from_points(points: &[Vec3]) -> Option<Sphere> {
+        let center = Aabb::from_points(points.iter().copied())?.center();
+        let radius = points
+            .iter()
+            .map(|point| point.distance_squared(center))
+            .fold(0.0, f32::max)
+            .sqrt();
+        Some(Sphere::new(center, radius))
+    }
+
+    /// Returns the smallest box containing the sphere.
+    pub fn aabb(&self) -> Aabb {
+        Aabb::from_center_half_extents(self.center, Vec3::splat(self.radius))
+    }
+
+    /// Returns `true` if the point is inside the sphere or on its boundary.
+    pub fn contains_point(&self, point: Vec3) -> bool {
+        self.center.distance_squared(point) <= self.radius * self.radius
+    }
+
+    /// Returns `true` if the other sphere is entirely inside this one.
+    pub fn contains_sphere(&self, other: &Sphere) -> bool {
+        self.center.distance(other.center) + other.radius <= self.radius
+    }
+
+    /// Returns `true` if both spheres overlap or touch.
+    pub fn intersects_sphere(&self, other: &Sphere) -> bool {
+        let radius = self.radius + other.radius;
+        self.center.distance_squared(other.center) <= radius * radius
+    }
+
+    /// Returns `true` if the sphere and the box overlap or touch.
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        aabb.intersects_sphere(self)
+    }
+
+    /// Returns the point of the sphere closest to `point`, which is `point` itself if it
+    /// is inside.
+    pub fn closest_point(&self, point: Vec3) -> Vec3 {
+        let offset = point - self.center;
+        if offset.length_squared() <= self.radius * self.radius {
+            point
+        } else {
+            self.center + offset.normalize_or_zero() * self.radius
+        }
+    }
+
+    /// Returns the distance from the sphere to `point`, 0 if it is inside.
+    pub fn distance_to_point(&self, point: Vec3) -> f32 {
+        (self.center.distance(point) - self.radius).max(0.0)
+    }
+
+    /// Casts a ray against the sphere.
+    pub fn intersect_ray(&self, ray: &Ray) -> Option<RayHit> {
+        let offset = ray.origin - self.center;
+        let c = offset.length_squared() - self.radius * self.radius;
+        if c <= 0.0 {
+            return Some(RayHit::inside(ray));
+        }
+        let b = offset.dot(ray.direction);
+        if b > 0.0 {
+            return None;
+        }
+        let discriminant = b * b - c;
+        if discriminant < 0.0 {
+            return None;
+        }
+        let distance = -b - discriminant.sqrt();
+        let point = ray.at(distance);
+        Some(RayHit::new(
+            ray,
+            distance,
+            (point - self.center).normalize_or_zero(),
+        ))
+    }
+}
@@ -0,0 +1,142 @@
+//! Triangles.
+
+use crate::{
+    geometry::{Aabb, Plane, Ray, RayHit},
+    vec::Vec3,
+};
+
+/// Triangle, its front face being the side its vertices are seen counterclockwise from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Triangle {
+    pub a: Vec3,
+    pub b: Vec3,
+    pub c: Vec3,
+}
+
+impl Triangle {
+    /// Creates a triangle from its vertices.
+    pub fn new(a: Vec3, b: Vec3, c: Vec3) -> Triangle {
+        Triangle { a, b, c }
+    }
+
+    /// Returns the unit normal of the front face, or zero if the triangle is degenerate.
+    pub fn normal(&self) -> Vec3 {
+        (self.b - self.a).cross(self.c - self.a).normalize_or_zero()
+    }
+
+    /// Returns the area of the triangle.
+    pub fn area(&self) -> f32 {
+        (self.b - self.a).cross(self.c - self.a).length() * 0.5
+    }
+
+    /// Returns the average of the vertices.
+    pub fn centroid(&self) -> Vec3 {
+        (self.a + self.b + self.c) / 3.0
+    }
+
+    /// Returns the plane of the triangle, or `None` if it is degenerate.
+    pub fn plane(&self) -> Option<Plane> {
+        Plane::from_points(self.a, self.b, self.c)
+    }
+
+    /// Returns the smallest box containing the triangle.
+    pub fn aabb(&self) -> Aabb {
+        Aabb::new(
+            self.a.min(self.b).min(self.c),
+            self.a.max(self.b).max(self.c),
+        )
+    }
+
+    /// Returns the barycentric coordinates of the projection of `point` onto the plane
+    /// of the triangle, or `None` if it is degenerate.
+    pub fn barycentric(&self, point: Vec3) -> Option<Vec3> {
+        let ab = self.b - self.a;
+        let ac = self.c - self.a;
+        let ap = point - self.a;
+        let d00 = ab.dot(ab);
+        let d01 = ab.dot(ac);
+        let d11 = ac.dot(ac);
+        let d20 = ap.dot(ab);
+        let d21 = ap.dot(ac);
+        let denominator = d00 * d11 - d01 * d01;
+        if denominator.abs() <= f32::EPSILON * d00 * d11 {
+            return None;
+        }
+        let v = (d11 * d20 - d01 * d21) / denominator;
+        let w = (d00 * d21 - d01 * d20) / denominator;
+        Some(Vec3::new(1.0 - v - w, v, w))
+    }
+
+    /// Returns the point of the triangle closest to `point`.
+    pub fn closest_point(&self, point: Vec3) -> Vec3 {
+        let (a, b, c) = (self.a, self.b, self.c);
+        let ab = b - a;
+        let ac = c - a;
+        let ap = point - a;
+        let d1 = ab.dot(ap);
+        let d2 = ac.dot(ap);
+        if d1 <= 0.0 && d2 <= 0.0 {
+            return a;
+        }
+        let bp = point - b;
+        let d3 = ab.dot(bp);
+        let d4 = ac.dot(bp);
+        if d3 >= 0.0 && d4 <= d3 {
+            return b;
+        }
+        let vc = d1 * d4 - d3 * d2;
+        if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+            return a + ab * (d1 / (d1 - d3));
+        }
+        let cp = point - c;
+        let d5 = ab.dot(cp);
+        let d6 = ac.dot(cp);
+        if d6 >= 0.0 && d5 <= d6 {
+            return c;
+        }
+        let vb = d5 * d2 - d1 * d6;
+        if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+            return a + ac * (d2 / (d2 - d6));
+        }
+        let va = d3 * d6 - d5 * d4;
+        if va <= 0.0 && d4 - d3 >= 0.0 && d5 - d6 >= 0.0 {
+            return b + (c - b) * ((d4 - d3) / ((d4 - d3) + (d5 - d6)));
+        }
+        let denominator = 1.0 / (va + vb + vc);
+        a + ab * (vb * denominator) + ac * (vc * denominator)
+    }
+
+    /// Returns the distance from the triangle to `point`.
+    pub fn distance_to_point(&self, point: Vec3) -> f32 {
+        self.closest_point(point).distance(point)
+    }
+
+    /// Casts a ray against both faces of the triangle.
+    pub fn intersect_ray(&self, ray: &Ray) -> Option<RayHit> {
+        let ab = self.b - self.a;
+        let ac = self.c - self.a;
+        let p = ray.direction.cross(ac);
+        let determinant = ab.dot(p);
+        if determinant.abs() <= f32::EPSILON * ab.length() * ac.length() {
+            return None;
+        }
+        let inverse = 1.0 / determinant;
+        let offset = ray.origin - self.a;
+        let u = offset.dot(p) * inverse;
+        if !(0.0..=1.0).contains(&u) {
+            return None;
+        }
+        let q = offset.cross(ab);
+        let v = ray.direction.dot(q) * inverse;
+        if v < 0.0 || u + v > 1.0 {
+            return None;
+        }
+        let distance = ac.dot(q) * inverse;
+        if distance < 0.0 {
+            return None;
+        }
+        let normal = self.normal();
+        let normal = if determinant < 0.0 { -normal } else { normal };
+        Some(RayHit::new(ray, distance, normal))
+    }
+}
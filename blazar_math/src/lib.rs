@@ -0,0 +1,25 @@
+//! Vectors, matrices, quaternions and geometry primitives.
+//!
+//! Types are `#[repr(C)]` to be copied into GPU buffers as is. Matrices are column-major
+//! and projections target the Vulkan clip space.
+
+mod geometry;
+mod mat;
+mod quat;
+#[cfg(all(
+    feature = "simd",
+    any(
+        target_arch = "x86_64",
+        all(target_arch = "x86", target_feature = "sse")
+    )
+))]
+mod simd;
+mod swizzle;
+mod transform;
+mod vec;
+
+pub use geometry::*;
+pub use mat::*;
+pub use quat::*;
+pub use transform::*;
+pub use vec::*;
@@ -0,0 +1,649 @@
+//! Column-major 3x3 and 4x4 matrices.
+//!
+//! Matrices transform column vectors, `a * b` applying `b` first. Projections target the
+//! Vulkan clip space: x pointing right, y pointing down and depth in `[0, 1]`.
+
+use crate::{
+    quat::Quat,
+    vec::{Vec2, Vec3, Vec4},
+};
+use std::ops::{Add, Mul, MulAssign, Sub};
+
+/// 3x3 matrix, for 3D rotations and scales, or 2D affine transforms.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Mat3 {
+    pub columns: [Vec3; 3],
+}
+
+impl Default for Mat3 {
+    fn default() -> Mat3 {
+        Mat3::IDENTITY
+    }
+}
+
+impl Mat3 {
+    pub const ZERO: Mat3 = Mat3::from_columns(Vec3::ZERO, Vec3::ZERO, Vec3::ZERO);
+
+    pub const IDENTITY: Mat3 = Mat3::from_columns(Vec3::X, Vec3::Y, Vec3::Z);
+
+    /// Creates a matrix from its columns.
+    pub const fn from_columns(x_axis: Vec3, y_axis: Vec3, z_axis: Vec3) -> Mat3 {
+        Mat3 {
+            columns: [x_axis, y_axis, z_axis],
+        }
+    }
+
+    /// Creates a rotation matrix from a unit quaternion.
+    pub fn from_quat(rotation: Quat) -> Mat3 {
+        let Quat { x, y, z, w } = rotation;
+        Mat3::from_columns(
+            Vec3::new(
+                1.0 - 2.0 * (y * y + z * z),
+                2.0 * (x * y + z * w),
+                2.0 * (x * z - y * w),
+            ),
+            Vec3::new(
+                2.0 * (x * y - z * w),
+                1.0 - 2.0 * (x * x + z * z),
+                2.0 * (y * z + x * w),
+            ),
+            Vec3::new(
+                2.0 * (x * z + y * w),
+                2.0 * (y * z - x * w),
+                1.0 - 2.0 * (x * x + y * y),
+            ),
+        )
+    }
+
+    /// Creates a rotation of `angle` radians around a unit `axis`.
+    pub fn from_axis_angle(axis: Vec3, angle: f32) -> Mat3 {
+        Mat3::from_quat(Quat::from_axis_angle(axis, angle))
+    }
+
+    /// Creates a rotation of `angle` radians around the x axis.
+    pub fn from_rotation_x(angle: f32) -> Mat3 {
+        let (sin, cos) = angle.sin_cos();
+        Mat3::from_columns(Vec3::X, Vec3::new(0.0, cos, sin), Vec3::new(0.0, -sin, cos))
+    }
+
+    /// Creates a rotation of `angle` radians around the y axis.
+    pub fn from_rotation_y(angle: f32) -> Mat3 {
+        let (sin, cos) = angle.sin_cos();
+        Mat3::from_columns(Vec3::new(cos, 0.0, -sin), Vec3::Y, Vec3::new(sin, 0.0, cos))
+    }
+
+    /// Creates a rotation of `angle` radians around the z axis.
+    pub fn from_rotation_z(angle: f32) -> Mat3 {
+        let (sin, cos) = angle.sin_cos();
+        Mat3::from_columns(Vec3::new(cos, sin, 0.0), Vec3::new(-sin, cos, 0.0), Vec3::Z)
+    }
+
+    /// Creates a scale along each axis.
+    pub fn from_scale(scale: Vec3) -> Mat3 {
+        Mat3::from_columns(
+            Vec3::new(scale.x, 0.0, 0.0),
+            Vec3::new(0.0, scale.y, 0.0),
+            Vec3::new(0.0, 0.0, scale.z),
+        )
+    }
+
+    /// Creates a 2D affine transform applying `scale`, then a rotation of `angle`
+    /// radians, then `translation`.
+    pub fn from_scale_angle_translation(scale: Vec2, angle: f32, translation: Vec2) -> Mat3 {
+        let (sin, cos) = angle.sin_cos();
+        Mat3::from_columns(
+            Vec3::new(cos * scale.x, sin * scale.x, 0.0),
+            Vec3::new(-sin * scale.y, cos * scale.y, 0.0),
+            translation.extend(1.0),
+        )
+    }
+
+    /// Returns the upper-left part of a 4x4 matrix.
+    pub fn from_mat4(matrix: &Mat4) -> Mat3 {
+        let [x_axis, y_axis, z_axis, _] = matrix.columns;
+        Mat3::from_columns(x_axis.truncate(), y_axis.truncate(), z_axis.truncate())
+    }
+
+    /// Returns a row.
+    pub fn row(&self, index: usize) -> Vec3 {
+        let [x_axis, y_axis, z_axis] = self.columns;
+        Vec3::new(x_axis[index], y_axis[index], z_axis[index])
+    }
+
+    /// Returns the transposed matrix.
+    pub fn transpose(&self) -> Mat3 {
+        Mat3::from_columns(self.row(0), self.row(1), self.row(2))
+    }
+
+    /// Returns the determinant.
+    pub fn determinant(&self) -> f32 {
+        let [x_axis, y_axis, z_axis] = self.columns;
+        x_axis.dot(y_axis.cross(z_axis))
+    }
+
+    /// Returns the inverse, or `None` if the matrix is singular.
+    pub fn inverse(&self) -> Option<Mat3> {
+        let [x_axis, y_axis, z_axis] = self.columns;
+        let determinant = self.determinant();
+        if determinant == 0.0 || !determinant.is_finite() {
+            return None;
+        }
+        let rows = Mat3::from_columns(
+            y_axis.cross(z_axis),
+            z_axis.cross(x_axis),
+            x_axis.cross(y_axis),
+        );
+        Some(rows.transpose() * (1.0 / determinant))
+    }
+
+    /// Transforms a 2D point by an affine transform.
+    pub fn transform_point2(&self, point: Vec2) -> Vec2 {
+        (*self * point.extend(1.0)).truncate()
+    }
+
+    /// Transforms a 2D vector by an affine transform, ignoring the translation.
+    pub fn transform_vector2(&self, vector: Vec2) -> Vec2 {
+        (*self * vector.extend(0.0)).truncate()
+    }
+
+    /// Returns `true` if the components differ by at most `epsilon`.
+    pub fn abs_diff_eq(&self, other: &Mat3, epsilon: f32) -> bool {
+        self.columns
+            .iter()
+            .zip(&other.columns)
+            .all(|(a, b)| a.abs_diff_eq(*b, epsilon))
+    }
+
+    /// Returns the columns as arrays.
+    pub fn to_cols_array_2d(&self) -> [[f32; 3]; 3] {
+        let [x_axis, y_axis, z_axis] = self.columns;
+        [x_axis.to_array(), y_axis.to_array(), z_axis.to_array()]
+    }
+}
+
+impl From<[[f32; 3]; 3]> for Mat3 {
+    fn from([x_axis, y_axis, z_axis]: [[f32; 3]; 3]) -> Mat3 {
+        Mat3::from_columns(x_axis.into(), y_axis.into(), z_axis.into())
+    }
+}
+
+impl From<Mat3> for [[f32; 3]; 3] {
+    fn from(matrix: Mat3) -> [[f32; 3]; 3] {
+        matrix.to_cols_array_2d()
+    }
+}
+
+impl Mul<Vec3> for Mat3 {
+    type Output = Vec3;
+
+    fn mul(self, vector: Vec3) -> Vec3 {
+        let [x_axis, y_axis, z_axis] = self.columns;
+        x_axis * vector.x + y_axis * vector.y + z_axis * vector.z
+    }
+}
+
+impl Mul for Mat3 {
+    type Output = Mat3;
+
+    fn mul(self, other: Mat3) -> Mat3 {
+        let [x_axis, y_axis, z_axis] = other.columns;
+        Mat3::from_columns(self * x_axis, self * y_axis, self * z_axis)
+    }
+}
+
+impl Mul<f32> for Mat3 {
+    type Output = Mat3;
+
+    fn mul(self, scalar: f32) -> Mat3 {
+        let [x_axis, y_axis, z_axis] = self.columns;
+        Mat3::from_columns(x_axis * scalar, y_axis * scalar, z_axis * scalar)
+    }
+}
+
+impl MulAssign for Mat3 {
+    fn mul_assign(&mut self, other: Mat3) {
+        *self = *self * other;
+    }
+}
+
+impl Add for Mat3 {
+    type Output = Mat3;
+
+    fn add(self, other: Mat3) -> Mat3 {
+        let ([a, b, c], [d, e, f]) = (self.columns, other.columns);
+        Mat3::from_columns(a + d, b + e, c + f)
+    }
+}
+
+impl Sub for Mat3 {
+    type Output = Mat3;
+
+    fn sub(self, other: Mat3) -> Mat3 {
+        let ([a, b, c], [d, e, f]) = (self.columns, other.columns);
+        Mat3::from_columns(a - d, b - e, c - f)
+    }
+}
+
+/// 4x4 matrix, for 3D affine transforms and projections.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Mat4 {
+    pub columns: [Vec4; 4],
+}
+
+impl Default for Mat4 {
+    fn default() -> Mat4 {
+        Mat4::IDENTITY
+    }
+}
+
+impl Mat4 {
+    pub const ZERO: Mat4 = Mat4::from_columns(Vec4::ZERO, Vec4::ZERO, Vec4::ZERO, Vec4::ZERO);
+
+    pub const IDENTITY: Mat4 = Mat4::from_columns(Vec4::X, Vec4::Y, Vec4::Z, Vec4::W);
+
+    /// Creates a matrix from its columns.
+    pub const fn from_columns(x_axis: Vec4, y_axis: Vec4, z_axis: Vec4, w_axis: Vec4) -> Mat4 {
+        Mat4 {
+            columns: [x_axis, y_axis, z_axis, w_axis],
+        }
+    }
+
+    /// Creates an affine transform from a 3x3 matrix and a translation.
+    pub fn from_mat3_translation(matrix: &Mat3, translation: Vec3) -> Mat4 {
+        let [x_axis, y_axis, z_axis] = matrix.columns;
+        Mat4::from_columns(
+            x_axis.extend(0.0),
+            y_axis.extend(0.0),
+            z_axis.extend(0.0),
+            translation.extend(1.0),
+        )
+    }
+
+    /// Creates a transform from a 3x3 matrix, without translation.
+    pub fn from_mat3(matrix: &Mat3) -> Mat4 {
+        Mat4::from_mat3_translation(matrix, Vec3::ZERO)
+    }
+
+    /// Creates a translation.
+    pub fn from_translation(translation: Vec3) -> Mat4 {
+        Mat4::from_mat3_translation(&Mat3::IDENTITY, translation)
+    }
+
+    /// Creates a scale along each axis.
+    pub fn from_scale(scale: Vec3) -> Mat4 {
+        Mat4::from_mat3(&Mat3::from_scale(scale))
+    }
+
+    /// Creates a rotation matrix from a unit quaternion.
+    pub fn from_quat(rotation: Quat) -> Mat4 {
+        Mat4::from_mat3(&Mat3::from_quat(rotation))
+    }
+
+    /// Creates a rotation of `angle` radians around a unit `axis`.
+    pub fn from_axis_angle(axis: Vec3, angle: f32) -> Mat4 {
+        Mat4::from_mat3(&Mat3::from_axis_angle(axis, angle))
+    }
+
+    /// Creates a rotation of `angle` radians around the x axis.
+    pub fn from_rotation_x(angle: f32) -> Mat4 {
+        Mat4::from_mat3(&Mat3::from_rotation_x(angle))
+    }
+
+    /// Creates a rotation of `angle` radians around the y axis.
+    pub fn from_rotation_y(angle: f32) -> Mat4 {
+        Mat4::from_mat3(&Mat3::from_rotation_y(angle))
+    }
+
+    /// Creates a rotation of `angle` radians around the z axis.
+    pub fn from_rotation_z(angle: f32) -> Mat4 {
+        Mat4::from_mat3(&Mat3::from_rotation_z(angle))
+    }
+
+    /// Creates a transform applying `scale`, then `rotation`, then `translation`.
+    pub fn from_scale_rotation_translation(scale: Vec3, rotation: Quat, translation: Vec3) -> Mat4 {
+        let [x_axis, y_axis, z_axis] = Mat3::from_quat(rotation).columns;
+        Mat4::from_columns(
+            (x_axis * scale.x).extend(0.0),
+            (y_axis * scale.y).extend(0.0),
+            (z_axis * scale.z).extend(0.0),
+            translation.extend(1.0),
+        )
+    }
+
+    /// Decomposes an affine transform without shear into its scale, rotation and
+    /// translation.
+    pub fn to_scale_rotation_translation(&self) -> (Vec3, Quat, Vec3) {
+        let [x_axis, y_axis, z_axis, w_axis] = self.columns;
+        let sign = if Mat3::from_mat4(self).determinant() < 0.0 {
+            -1.0
+        } else {
+            1.0
+        };
+        let scale = Vec3::new(
+            x_axis.truncate().length() * sign,
+            y_axis.truncate().length(),
+            z_axis.truncate().length(),
+        );
+        let inverse_scale = Vec3::new(
+            if scale.x != 0.0 { 1.0 / scale.x } else { 0.0 },
+            if scale.y != 0.0 { 1.0 / scale.y } else { 0.0 },
+            if scale.z != 0.0 { 1.0 / scale.z } else { 0.0 },
+        );
+        let rotation = Mat3::from_columns(
+            x_axis.truncate() * inverse_scale.x,
+            y_axis.truncate() * inverse_scale.y,
+            z_axis.truncate() * inverse_scale.z,
+        );
+        (scale, Quat::from_mat3(&rotation), w_axis.truncate())
+    }
+
+    /// Creates a right-handed view matrix looking from `eye` towards `target`, `up`
+    /// giving the vertical direction.
+    pub fn look_at_rh(eye: Vec3, target: Vec3, up: Vec3) -> Mat4 {
+        Mat4::look_to_rh(eye, target - eye, up)
+    }
+
+    /// Creates a right-handed view matrix looking from `eye` in `direction`, `up` giving
+    /// the vertical direction.
+    ///
+    /// The view space looks down -z, with y pointing up.
+    pub fn look_to_rh(eye: Vec3, direction: Vec3, up: Vec3) -> Mat4 {
+        let forward = direction.normalize_or_zero();
+        let side = forward.cross(up).normalize_or_zero();
+        let up = side.cross(forward);
+        Mat4::from_columns(
+            Vec4::new(side.x, up.x, -forward.x, 0.0),
+            Vec4::new(side.y, up.y, -forward.y, 0.0),
+            Vec4::new(side.z, up.z, -forward.z, 0.0),
+            Vec4::new(-side.dot(eye), -up.dot(eye), forward.dot(eye), 1.0),
+        )
+    }
+
+    /// Creates a perspective projection from a right-handed view space, mapping `near`
+    /// to a depth of `0` and `far` to `1`.
+    ///
+    /// `fov_y` is the vertical field of view in radians, `aspect_ratio` the width divided
+    /// by the height of the viewport.
+    pub fn perspective_rh(fov_y: f32, aspect_ratio: f32, near: f32, far: f32) -> Mat4 {
+        let focal = 1.0 / (fov_y * 0.5).tan();
+        let range = far / (near - far);
+        Mat4::from_columns(
+            Vec4::new(focal / aspect_ratio, 0.0, 0.0, 0.0),
+            Vec4::new(0.0, -focal, 0.0, 0.0),
+            Vec4::new(0.0, 0.0, range, -1.0),
+            Vec4::new(0.0, 0.0, near * range, 0.0),
+        )
+    }
+
+    /// Creates a perspective projection without far plane from a right-handed view
+    /// space, mapping `near` to a depth of `1` and infinity to `0`.
+    ///
+    /// Reversing the depth spreads the precision of floating-point depth buffers evenly;
+    /// use a greater depth comparison with it.
+    pub fn perspective_infinite_reverse_rh(fov_y: f32, aspect_ratio: f32, near: f32) -> Mat4 {
+        let focal = 1.0 / (fov_y * 0.5).tan();
+        Mat4::from_columns(
+            Vec4::new(focal / aspect_ratio, 0.0, 0.0, 0.0),
+            Vec4::new(0.0, -focal, 0.0, 0.0),
+            Vec4::new(0.0, 0.0, 0.0, -1.0),
+            Vec4::new(0.0, 0.0, near, 0.0),
+        )
+    }
+
+    /// Creates an orthographic projection from a right-handed view space, mapping
+    /// `left` and `top` to the top-left corner of the viewport, `near` to a depth of `0`
+    /// and `far` to `1`.
+    ///
+    /// With `top` lower than `bottom`, the y-axis points down, as with pixels.
+    pub fn orthographic_rh(
+        left: f32,
+        right: f32,
+        bottom: f32,
+        top: f32,
+        near: f32,
+        far: f32,
+    ) -> Mat4 {
+        let width = 1.0 / (right - left);
+        let height = 1.0 / (bottom - top);
+        let depth = 1.0 / (near - far);
+        Mat4::from_columns(
+            Vec4::new(2.0 * width, 0.0, 0.0, 0.0),
+            Vec4::new(0.0, 2.0 * height, 0.0, 0.0),
+            Vec4::new(0.0, 0.0, depth, 0.0),
+            Vec4::new(
+                -(left + right) * width,
+                -(top + bottom) * height,
+                near * depth,
+                1.0,
+            ),
+        )
+    }
+
+    /// Returns a row.
+    pub fn row(&self, index: usize) -> Vec4 {
+        let [x_axis, y_axis, z_axis, w_axis] = self.columns;
+        Vec4::new(x_axis[index], y_axis[index], z_axis[index], w_axis[index])
+    }
+
+    /// Returns the transposed matrix.
+    pub fn transpose(&self) -> Mat4 {
+        Mat4::from_columns(self.row(0), self.row(1), self.row(2), self.row(3))
+    }
+
+    /// Returns the determinant.
+    pub fn determinant(&self) -> f32 {
+        let (m, inverse) = self.adjugate();
+        m[0] * inverse[0] + m[1] * inverse[4] + m[2] * inverse[8] + m[3] * inverse[12]
+    }
+
+    /// Returns the inverse, or `None` if the matrix is singular.
+    pub fn inverse(&self) -> Option<Mat4> {
+        let (m, adjugate) = self.adjugate();
+        let determinant =
+            m[0] * adjugate[0] + m[1] * adjugate[4] + m[2] * adjugate[8] + m[3] * adjugate[12];
+        if determinant == 0.0 || !determinant.is_finite() {
+            return None;
+        }
+        let mut columns = [Vec4::ZERO; 4];
+        for (index, value) in adjugate.iter().enumerate() {
+            columns[index / 4][index % 4] = value / determinant;
+        }
+        Some(Mat4 { columns })
+    }
+
+    /// Returns the components and the adjugate matrix, both as flat column-major arrays.
+    fn adjugate(&self) -> ([f32; 16], [f32; 16]) {
+        let mut m = [0.0; 16];
+        for (index, value) in m.iter_mut().enumerate() {
+            *value = self.columns[index / 4][index % 4];
+        }
+        let mut a = [0.0; 16];
+        a[0] = m[5] * m[10] * m[15] - m[5] * m[11] * m[14] - m[9] * m[6] * m[15]
+            + m[9] * m[7] * m[14]
+            + m[13] * m[6] * m[11]
+            - m[13] * m[7] * m[10];
+        a[4] = -m[4] * m[10] * m[15] + m[4] * m[11] * m[14] + m[8] * m[6] * m[15]
+            - m[8] * m[7] * m[14]
+            - m[12] * m[6] * m[11]
+            + m[12] * m[7] * m[10];
+        a[8] = m[4] * m[9] * m[15] - m[4] * m[11] * m[13] - m[8] * m[5] * m[15]
+            + m[8] * m[7] * m[13]
+            + m[12] * m[5] * m[11]
+            - m[12] * m[7] * m[9];
+        a[12] = -m[4] * m[9] * m[14] + m[4] * m[10] * m[13] + m[8] * m[5] * m[14]
+            - m[8] * m[6] * m[13]
+            - m[12] * m[5] * m[10]
+            + m[12] * m[6] * m[9];
+        a[1] = -m[1] * m[10] * m[15] + m[1] * m[11] * m[14] + m[9] * m[2] * m[15]
+            - m[9] * m[3] * m[14]
+            - m[13] * m[2] * m[11]
+            + m[13] * m[3] * m[10];
+        a[5] = m[0] * m[10] * m[15] - m[0] * m[11] * m[14] - m[8] * m[2] * m[15]
+            + m[8] * m[3] * m[14]
+            + m[12] * m[2] * m[11]
+            - m[12] * m[3] * m[10];
+        a[9] = -m[0] * m[9] * m[15] + m[0] * m[11] * m[13] + m[8] * m[1] * m[15]
+            - m[8] * m[3] * m[13]
+            - m[12] * m[1] * m[11]
+            + m[12] * m[3] * m[9];
+        a[13] = m[0] * m[9] * m[14] - m[0] * m[10] * m[13] - m[8] * m[1] * m[14]
+            + m[8] * m[2] * m[13]
+            + m[12] * m[1] * m[10]
+            - m[12] * m[2] * m[9];
+        a[2] = m[1] * m[6] * m[15] - m[1] * m[7] * m[14] - m[5] * m[2] * m[15]
+            + m[5] * m[3] * m[14]
+            + m[13] * m[2] * m[7]
+            - m[13] * m[3] * m[6];
+        a[6] = -m[0] * m[6] * m[15] + m[0] * m[7] * m[14] + m[4] * m[2] * m[15]
+            - m[4] * m[3] * m[14]
+            - m[12] * m[2] * m[7]
+            + m[12] * m[3] * m[6];
+        a[10] = m[0] * m[5] * m[15] - m[0] * m[7] * m[13] - m[4] * m[1] * m[15]
+            + m[4] * m[3] * m[13]
+            + m[12] * m[1] * m[7]
+            - m[12] * m[3] * m[5];
+        a[14] = -m[0] * m[5] * m[14] + m[0] * m[6] * m[13] + m[4] * m[1] * m[14]
+            - m[4] * m[2] * m[13]
+            - m[12] * m[1] * m[6]
+            + m[12] * m[2] * m[5];
+        a[3] = -m[1] * m[6] * m[11] + m[1] * m[7] * m[10] + m[5] * m[2] * m[11]
+            - m[5] * m[3] * m[10]
+            - m[9] * m[2] * m[7]
+            + m[9] * m[3] * m[6];
+        a[7] = m[0] * m[6] * m[11] - m[0] * m[7] * m[10] - m[4] * m[2] * m[11]
+            + m[4] * m[3] * m[10]
+            + m[8] * m[2] * m[7]
+            - m[8] * m[3] * m[6];
+        a[11] = -m[0] * m[5] * m[11] + m[0] * m[7] * m[9] + m[4] * m[1] * m[11]
+            - m[4] * m[3] * m[9]
+            - m[8] * m[1] * m[7]
+            + m[8] * m[3] * m[5];
+        a[15] = m[0] * m[5] * m[10] - m[0] * m[6] * m[9] - m[4] * m[1] * m[10]
+            + m[4] * m[2] * m[9]
+            + m[8] * m[1] * m[6]
+            - m[8] * m[2] * m[5];
+        (m, a)
+    }
+
+    /// Transforms a point by an affine transform.
+    pub fn transform_point3(&self, point: Vec3) -> Vec3 {
+        (*self * point.extend(1.0)).truncate()
+    }
+
+    /// Transforms a vector by an affine transform, ignoring the translation.
+    pub fn transform_vector3(&self, vector: Vec3) -> Vec3 {
+        (*self * vector.extend(0.0)).truncate()
+    }
+
+    /// Transforms a point by a projection, dividing the result by its `w`.
+    pub fn project_point3(&self, point: Vec3) -> Vec3 {
+        (*self * point.extend(1.0)).project()
+    }
+
+    /// Returns `true` if the components differ by at most `epsilon`.
+    pub fn abs_diff_eq(&self, other: &Mat4, epsilon: f32) -> bool {
+        self.columns
+            .iter()
+            .zip(&other.columns)
+            .all(|(a, b)| a.abs_diff_eq(*b, epsilon))
+    }
+
+    /// Returns the columns as arrays.
+    pub fn to_cols_array_2d(&self) -> [[f32; 4]; 4] {
+        let [x_axis, y_axis, z_axis, w_axis] = self.columns;
+        [
+            x_axis.to_array(),
+            y_axis.to_array(),
+            z_axis.to_array(),
+            w_axis.to_array(),
+        ]
+    }
+}
+
+impl From<[[f32; 4]; 4]> for Mat4 {
+    fn from([x_axis, y_axis, z_axis, w_axis]: [[f32; 4]; 4]) -> Mat4 {
+        Mat4::from_columns(x_axis.into(), y_axis.into(), z_axis.into(), w_axis.into())
+    }
+}
+
+impl From<Mat4> for [[f32; 4]; 4] {
+    fn from(matrix: Mat4) -> [[f32; 4]; 4] {
+        matrix.to_cols_array_2d()
+    }
+}
+
+impl Mul<Vec4> for Mat4 {
+    type Output = Vec4;
+
+    #[cfg(not(all(
+        feature = "simd",
+        any(
+            target_arch = "x86_64",
+            all(target_arch = "x86", target_feature = "sse")
+        )
+    )))]
+    fn mul(self, vector: Vec4) -> Vec4 {
+        let [x_axis, y_axis, z_axis, w_axis] = self.columns;
+        x_axis * vector.x + y_axis * vector.y + z_axis * vector.z + w_axis * vector.w
+    }
+
+    #[cfg(all(
+        feature = "simd",
+        any(
+            target_arch = "x86_64",
+            all(target_arch = "x86", target_feature = "sse")
+        )
+    ))]
+    fn mul(self, vector: Vec4) -> Vec4 {
+        crate::simd::mul_mat4_vec4(&self, vector)
+    }
+}
+
+impl Mul for Mat4 {
+    type Output = Mat4;
+
+    fn mul(self, other: Mat4) -> Mat4 {
+        let [x_axis, y_axis, z_axis, w_axis] = other.columns;
+        Mat4::from_columns(self * x_axis, self * y_axis, self * z_axis, self * w_axis)
+    }
+}
+
+impl Mul<f32> for Mat4 {
+    type Output = Mat4;
+
+    fn mul(self, scalar: f32) -> Mat4 {
+        let [x_axis, y_axis, z_axis, w_axis] = self.columns;
+        Mat4::from_columns(
+            x_axis * scalar,
+            y_axis * scalar,
+            z_axis * scalar,
+            w_axis * scalar,
+        )
+    }
+}
+
+impl MulAssign for Mat4 {
+    fn mul_assign(&mut self, other: Mat4) {
+        *self = *self * other;
+    }
+}
+
+impl Add for Mat4 {
+    type Output = Mat4;
+
+    fn add(self, other: Mat4) -> Mat4 {
+        let ([a, b, c, d], [e, f, g, h]) = (self.columns, other.columns);
+        Mat4::from_columns(a + e, b + f, c + g, d + h)
+    }
+}
+
+impl Sub for Mat4 {
+    type Output = Mat4;
+
+    fn sub(self, other: Mat4) -> Mat4 {
+        let ([a, b, c, d], [e, f, g, h]) = (self.columns, other.columns);
+        Mat4::from_columns(a - e, b - f, c - g, d - h)
+    }
+}
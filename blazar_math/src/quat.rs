@@ -0,0 +1,285 @@
+//! Quaternions.
+
+use crate::{mat::Mat3, vec::Vec3};
+use std::ops::{Mul, MulAssign, Neg};
+
+/// Rotation quaternion `xi + yj + zk + w`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Quat {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32,
+}
+
+impl Default for Quat {
+    fn default() -> Quat {
+        Quat::IDENTITY
+    }
+}
+
+impl Quat {
+    /// Rotation by an angle of zero.
+    pub const IDENTITY: Quat = Quat::from_xyzw(0.0, 0.0, 0.0, 1.0);
+
+    /// Creates a quaternion from its components, which must be normalized to represent a
+    /// rotation.
+    pub const fn from_xyzw(x: f32, y: f32, z: f32, w: f32) -> Quat {
+        Quat { x, y, z, w }
+    }
+
+    /// Creates a rotation of `angle` radians around a unit `axis`, counterclockwise when
+    /// looking down the axis.
+    pub fn from_axis_angle(axis: Vec3, angle: f32) -> Quat {
+        let (sin, cos) = (angle * 0.5).sin_cos();
+        Quat::from_xyzw(axis.x * sin, axis.y * sin, axis.z * sin, cos)
+    }
+
+    /// Creates a rotation of `angle` radians around the x axis.
+    pub fn from_rotation_x(angle: f32) -> Quat {
+        Quat::from_axis_angle(Vec3::X, angle)
+    }
+
+    /// Creates a rotation of `angle` radians around the y axis.
+    pub fn from_rotation_y(angle: f32) -> Quat {
+        Quat::from_axis_angle(Vec3::Y, angle)
+    }
+
+    /// Creates a rotation of `angle` radians around the z axis.
+    pub fn from_rotation_z(angle: f32) -> Quat {
+        Quat::from_axis_angle(Vec3::Z, angle)
+    }
+
+    /// Creates a rotation from Euler angles in radians, applying `roll` around z, then
+    /// `pitch` around x, then `yaw` around y.
+    pub fn from_euler(yaw: f32, pitch: f32, roll: f32) -> Quat {
+        Quat::from_rotation_y(yaw) * Quat::from_rotation_x(pitch) * Quat::from_rotation_z(roll)
+    }
+
+    /// Returns the shortest rotation from unit vector `from` to unit vector `to`.
+    pub fn from_rotation_arc(from: Vec3, to: Vec3) -> Quat {
+        let dot = from.dot(to);
+        if dot < -1.0 + 1e-6 {
+            // Opposite vectors, any orthogonal axis works.
+            return Quat::from_axis_angle(from.any_orthogonal(), std::f32::consts::PI);
+        }
+        let axis = from.cross(to);
+        Quat::from_xyzw(axis.x, axis.y, axis.z, 1.0 + dot).normalize()
+    }
+
+    /// Creates a rotation from an orthonormal rotation matrix.
+    pub fn from_mat3(matrix: &Mat3) -> Quat {
+        let [x_axis, y_axis, z_axis] = matrix.columns;
+        let trace = x_axis.x + y_axis.y + z_axis.z;
+        let quaternion = if trace > 0.0 {
+            let s = (trace + 1.0).sqrt() * 2.0;
+            Quat::from_xyzw(
+                (y_axis.z - z_axis.y) / s,
+                (z_axis.x - x_axis.z) / s,
+                (x_axis.y - y_axis.x) / s,
+                0.25 * s,
+            )
+        } else if x_axis.x > y_axis.y && x_axis.x > z_axis.z {
+            let s = (1.0 + x_axis.x - y_axis.y - z_axis.z).sqrt() * 2.0;
+            Quat::from_xyzw(
+                0.25 * s,
+                (y_axis.x + x_axis.y) / s,
+                (z_axis.x + x_axis.z) / s,
+                (y_axis.z - z_axis.y) / s,
+            )
+        } else if y_axis.y > z_axis.z {
+            let s = (1.0 + y_axis.y - x_axis.x - z_axis.z).sqrt() * 2.0;
+            Quat::from_xyzw(
+                (y_axis.x + x_axis.y) / s,
+                0.25 * s,
+                (z_axis.y + y_axis.z) / s,
+                (z_axis.x - x_axis.z) / s,
+            )
+        } else {
+            let s = (1.0 + z_axis.z - x_axis.x - y_axis.y).sqrt() * 2.0;
+            Quat::from_xyzw(
+                (z_axis.x + x_axis.z) / s,
+                (z_axis.y + y_axis.z) / s,
+                0.25 * s,
+                (x_axis.y - y_axis.x) / s,
+            )
+        };
+        quaternion.normalize()
+    }
+
+    /// Returns the unit axis and the angle in radians of the rotation.
+    pub fn to_axis_angle(self) -> (Vec3, f32) {
+        let quaternion = if self.w < 0.0 { -self } else { self };
+        let sin = quaternion.xyz().length();
+        let angle = 2.0 * sin.atan2(quaternion.w);
+        let axis = (quaternion.xyz() / sin).try_normalize().unwrap_or(Vec3::X);
+        (axis, angle)
+    }
+
+    /// Returns the vector part.
+    pub fn xyz(self) -> Vec3 {
+        Vec3::new(self.x, self.y, self.z)
+    }
+
+    /// Returns the dot product.
+    pub fn dot(self, other: Quat) -> f32 {
+        self.x * other.x + self.y * other.y + self.z * other.z + self.w * other.w
+    }
+
+    /// Returns the length, `1` for rotations.
+    pub fn length(self) -> f32 {
+        self.dot(self).sqrt()
+    }
+
+    /// Returns the quaternion scaled to a length of `1`, or the identity if its length
+    /// is zero.
+    pub fn normalize(self) -> Quat {
+        let length = self.length();
+        if length > 0.0 {
+            Quat::from_xyzw(
+                self.x / length,
+                self.y / length,
+                self.z / length,
+                self.w / length,
+            )
+        } else {
+            Quat::IDENTITY
+        }
+    }
+
+    /// Returns `true` if the length is `1` within `epsilon`.
+    pub fn is_normalized(self, epsilon: f32) -> bool {
+        (self.dot(self) - 1.0).abs() <= epsilon * 2.0
+    }
+
+    /// Returns the inverse of a unit quaternion.
+    pub fn conjugate(self) -> Quat {
+        Quat::from_xyzw(-self.x, -self.y, -self.z, self.w)
+    }
+
+    /// Returns the inverse of a quaternion, or `None` if its length is zero.
+    pub fn inverse(self) -> Option<Quat> {
+        let length_squared = self.dot(self);
+        if length_squared > 0.0 {
+            let conjugate = self.conjugate();
+            Some(Quat::from_xyzw(
+                conjugate.x / length_squared,
+                conjugate.y / length_squared,
+                conjugate.z / length_squared,
+                conjugate.w / length_squared,
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Rotates a vector.
+    pub fn rotate(self, vector: Vec3) -> Vec3 {
+        let axis = self.xyz();
+        let t = axis.cross(vector) * 2.0;
+        vector + t * self.w + axis.cross(t)
+    }
+
+    /// Returns the angle in radians between two rotations.
+    pub fn angle_between(self, other: Quat) -> f32 {
+        2.0 * self.dot(other).abs().min(1.0).acos()
+    }
+
+    /// Linearly interpolates towards `other` along the shortest path, then normalizes,
+    /// which is faster than `slerp` but does not keep a constant angular velocity.
+    pub fn nlerp(self, other: Quat, t: f32) -> Quat {
+        let other = if self.dot(other) < 0.0 { -other } else { other };
+        Quat::from_xyzw(
+            self.x + (other.x - self.x) * t,
+            self.y + (other.y - self.y) * t,
+            self.z + (other.z - self.z) * t,
+            self.w + (other.w - self.w) * t,
+        )
+        .normalize()
+    }
+
+    /// Spherically interpolates towards `other` along the shortest path.
+    pub fn slerp(self, other: Quat, t: f32) -> Quat {
+        let mut cosine = self.dot(other);
+        let other = if cosine < 0.0 {
+            cosine = -cosine;
+            -other
+        } else {
+            other
+        };
+        if cosine > 0.9995 {
+            return self.nlerp(other, t);
+        }
+        let angle = cosine.acos();
+        let sine = angle.sin();
+        let (a, b) = (((1.0 - t) * angle).sin() / sine, (t * angle).sin() / sine);
+        Quat::from_xyzw(
+            self.x * a + other.x * b,
+            self.y * a + other.y * b,
+            self.z * a + other.z * b,
+            self.w * a + other.w * b,
+        )
+    }
+
+    /// Returns `true` if the components differ by at most `epsilon`.
+    pub fn abs_diff_eq(self, other: Quat, epsilon: f32) -> bool {
+        (self.x - other.x).abs() <= epsilon
+            && (self.y - other.y).abs() <= epsilon
+            && (self.z - other.z).abs() <= epsilon
+            && (self.w - other.w).abs() <= epsilon
+    }
+
+    /// Returns `true` if both quaternions represent the same rotation within `epsilon`.
+    pub fn same_rotation(self, other: Quat, epsilon: f32) -> bool {
+        self.abs_diff_eq(other, epsilon) || self.abs_diff_eq(-other, epsilon)
+    }
+}
+
+impl From<[f32; 4]> for Quat {
+    fn from([x, y, z, w]: [f32; 4]) -> Quat {
+        Quat::from_xyzw(x, y, z, w)
+    }
+}
+
+impl From<Quat> for [f32; 4] {
+    fn from(quaternion: Quat) -> [f32; 4] {
+        [quaternion.x, quaternion.y, quaternion.z, quaternion.w]
+    }
+}
+
+impl Neg for Quat {
+    type Output = Quat;
+
+    fn neg(self) -> Quat {
+        Quat::from_xyzw(-self.x, -self.y, -self.z, -self.w)
+    }
+}
+
+/// Composes rotations, `other` being applied first.
+impl Mul for Quat {
+    type Output = Quat;
+
+    fn mul(self, other: Quat) -> Quat {
+        Quat::from_xyzw(
+            self.w * other.x + self.x * other.w + self.y * other.z - self.z * other.y,
+            self.w * other.y - self.x * other.z + self.y * other.w + self.z * other.x,
+            self.w * other.z + self.x * other.y - self.y * other.x + self.z * other.w,
+            self.w * other.w - self.x * other.x - self.y * other.y - self.z * other.z,
+        )
+    }
+}
+
+impl MulAssign for Quat {
+    fn mul_assign(&mut self, other: Quat) {
+        *self = *self * other;
+    }
+}
+
+impl Mul<Vec3> for Quat {
+    type Output = Vec3;
+
+    fn mul(self, vector: Vec3) -> Vec3 {
+        self.rotate(vector)
+    }
+}
@@ -0,0 +1,32 @@
+//! SSE implementations.
+
+use crate::{mat::Mat4, vec::Vec4};
+#[cfg(target_arch = "x86")]
+use std::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+
+/// Multiplies a matrix by a vector.
+#[inline]
+pub(crate) fn mul_mat4_vec4(matrix: &Mat4, vector: Vec4) -> Vec4 {
+    let mut result = Vec4::ZERO;
+    // Safety: SSE is available on every targeted CPU, and `Vec4` is `repr(C)` with 4
+    // `f32`, read and written whole through pointers to the vectors with unaligned
+    // accesses.
+    unsafe {
+        let column =
+            |index: usize| _mm_loadu_ps(&matrix.columns[index] as *const Vec4 as *const f32);
+        let sum = _mm_add_ps(
+            _mm_add_ps(
+                _mm_mul_ps(column(0), _mm_set1_ps(vector.x)),
+                _mm_mul_ps(column(1), _mm_set1_ps(vector.y)),
+            ),
+            _mm_add_ps(
+                _mm_mul_ps(column(2), _mm_set1_ps(vector.z)),
+                _mm_mul_ps(column(3), _mm_set1_ps(vector.w)),
+            ),
+        );
+        _mm_storeu_ps(&mut result as *mut Vec4 as *mut f32, sum);
+    }
+    result
+}
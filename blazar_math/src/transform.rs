@@ -0,0 +1,107 @@
+//! Translation, rotation and scale transforms.
+
+use crate::{mat::Mat4, quat::Quat, vec::Vec3};
+use std::ops::Mul;
+
+/// Applies a scale, then a rotation, then a translation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transform {
+    pub translation: Vec3,
+    pub rotation: Quat,
+    pub scale: Vec3,
+}
+
+impl Default for Transform {
+    fn default() -> Transform {
+        Transform::IDENTITY
+    }
+}
+
+impl Transform {
+    pub const IDENTITY: Transform = Transform {
+        translation: Vec3::ZERO,
+        rotation: Quat::IDENTITY,
+        scale: Vec3::ONE,
+    };
+
+    /// Creates a translation.
+    pub fn from_translation(translation: Vec3) -> Transform {
+        Transform {
+            translation,
+            ..Transform::IDENTITY
+        }
+    }
+
+    /// Creates a rotation.
+    pub fn from_rotation(rotation: Quat) -> Transform {
+        Transform {
+            rotation,
+            ..Transform::IDENTITY
+        }
+    }
+
+    /// Creates a scale along each axis.
+    pub fn from_scale(scale: Vec3) -> Transform {
+        Transform {
+            scale,
+            ..Transform::IDENTITY
+        }
+    }
+
+    /// Decomposes an affine matrix without shear.
+    pub fn from_matrix(matrix: &Mat4) -> Transform {
+        let (scale, rotation, translation) = matrix.to_scale_rotation_translation();
+        Transform {
+            translation,
+            rotation,
+            scale,
+        }
+    }
+
+    /// Returns the matrix of the transform.
+    pub fn matrix(&self) -> Mat4 {
+        Mat4::from_scale_rotation_translation(self.scale, self.rotation, self.translation)
+    }
+
+    /// Transforms a point.
+    pub fn transform_point(&self, point: Vec3) -> Vec3 {
+        self.rotation * (point * self.scale) + self.translation
+    }
+
+    /// Transforms a vector, ignoring the translation.
+    pub fn transform_vector(&self, vector: Vec3) -> Vec3 {
+        self.rotation * (vector * self.scale)
+    }
+
+    /// Returns the inverse transform, exact for uniform scales only.
+    pub fn inverse(&self) -> Transform {
+        let rotation = self.rotation.conjugate();
+        let scale = self.scale.recip();
+        Transform {
+            translation: rotation * (-self.translation) * scale,
+            rotation,
+            scale,
+        }
+    }
+
+    /// Returns the unit vector the transform maps -z to, being the viewing direction of
+    /// cameras.
+    pub fn forward(&self) -> Vec3 {
+        self.rotation * -Vec3::Z
+    }
+}
+
+/// Composes transforms, `other` being applied first.
+///
+/// The result is exact for uniform scales only, shear being unrepresentable.
+impl Mul for Transform {
+    type Output = Transform;
+
+    fn mul(self, other: Transform) -> Transform {
+        Transform {
+            translation: self.transform_point(other.translation),
+            rotation: self.rotation * other.rotation,
+            scale: self.scale * other.scale,
+        }
+    }
+}
@@ -0,0 +1,367 @@
+//! 2D, 3D and 4D vectors.
+
+use std::ops::{
+    Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign,
+};
+
+/// Implements the methods and operators shared by all vectors.
+macro_rules! vector {
+    ($name:ident, $size:expr, $($index:literal $field:ident),+) => {
+        impl $name {
+            /// Vector with all components set to `0`.
+            pub const ZERO: $name = $name { $($field: 0.0),+ };
+
+            /// Vector with all components set to `1`.
+            pub const ONE: $name = $name { $($field: 1.0),+ };
+
+            /// Creates a vector.
+            pub const fn new($($field: f32),+) -> $name {
+                $name { $($field),+ }
+            }
+
+            /// Creates a vector with all components set to `value`.
+            pub const fn splat(value: f32) -> $name {
+                $name { $($field: value),+ }
+            }
+
+            /// Returns the components as an array.
+            pub fn to_array(self) -> [f32; $size] {
+                [$(self.$field),+]
+            }
+
+            /// Returns the dot product.
+            pub fn dot(self, other: $name) -> f32 {
+                0.0 $(+ self.$field * other.$field)+
+            }
+
+            /// Returns the squared length, cheaper than `length`.
+            pub fn length_squared(self) -> f32 {
+                self.dot(self)
+            }
+
+            /// Returns the length.
+            pub fn length(self) -> f32 {
+                self.length_squared().sqrt()
+            }
+
+            /// Returns the squared distance, cheaper than `distance`.
+            pub fn distance_squared(self, other: $name) -> f32 {
+                (self - other).length_squared()
+            }
+
+            /// Returns the distance to another point.
+            pub fn distance(self, other: $name) -> f32 {
+                (self - other).length()
+            }
+
+            /// Returns the vector scaled to a length of `1`, or `None` if its length is
+            /// zero or not finite.
+            pub fn try_normalize(self) -> Option<$name> {
+                let length = self.length();
+                if length > 0.0 && length.is_finite() {
+                    Some(self / length)
+                } else {
+                    None
+                }
+            }
+
+            /// Returns the vector scaled to a length of `1`, or zero if its length is
+            /// zero or not finite.
+            pub fn normalize_or_zero(self) -> $name {
+                self.try_normalize().unwrap_or($name::ZERO)
+            }
+
+            /// Returns `true` if the length is `1` within `epsilon`.
+            pub fn is_normalized(self, epsilon: f32) -> bool {
+                (self.length_squared() - 1.0).abs() <= epsilon * 2.0
+            }
+
+            /// Linearly interpolates towards `other`.
+            pub fn lerp(self, other: $name, t: f32) -> $name {
+                self + (other - self) * t
+            }
+
+            /// Returns the component-wise minimum.
+            pub fn min(self, other: $name) -> $name {
+                $name { $($field: self.$field.min(other.$field)),+ }
+            }
+
+            /// Returns the component-wise maximum.
+            pub fn max(self, other: $name) -> $name {
+                $name { $($field: self.$field.max(other.$field)),+ }
+            }
+
+            /// Clamps each component between those of `min` and `max`.
+            pub fn clamp(self, min: $name, max: $name) -> $name {
+                self.max(min).min(max)
+            }
+
+            /// Returns the smallest component.
+            pub fn min_element(self) -> f32 {
+                let mut min = f32::INFINITY;
+                $(min = min.min(self.$field);)+
+                min
+            }
+
+            /// Returns the largest component.
+            pub fn max_element(self) -> f32 {
+                let mut max = f32::NEG_INFINITY;
+                $(max = max.max(self.$field);)+
+                max
+            }
+
+            /// Returns the absolute value of each component.
+            pub fn abs(self) -> $name {
+                $name { $($field: self.$field.abs()),+ }
+            }
+
+            /// Rounds each component down.
+            pub fn floor(self) -> $name {
+                $name { $($field: self.$field.floor()),+ }
+            }
+
+            /// Rounds each component up.
+            pub fn ceil(self) -> $name {
+                $name { $($field: self.$field.ceil()),+ }
+            }
+
+            /// Rounds each component to the nearest integer, half-way cases away from zero.
+            pub fn round(self) -> $name {
+                $name { $($field: self.$field.round()),+ }
+            }
+
+            /// Returns the reciprocal of each component.
+            pub fn recip(self) -> $name {
+                $name { $($field: 1.0 / self.$field),+ }
+            }
+
+            /// Returns `true` if all components are finite.
+            pub fn is_finite(self) -> bool {
+                true $(&& self.$field.is_finite())+
+            }
+
+            /// Returns `true` if the components differ by at most `epsilon`.
+            pub fn abs_diff_eq(self, other: $name, epsilon: f32) -> bool {
+                true $(&& (self.$field - other.$field).abs() <= epsilon)+
+            }
+        }
+
+        impl From<[f32; $size]> for $name {
+            fn from(array: [f32; $size]) -> $name {
+                let [$($field),+] = array;
+                $name { $($field),+ }
+            }
+        }
+
+        impl From<$name> for [f32; $size] {
+            fn from(vector: $name) -> [f32; $size] {
+                vector.to_array()
+            }
+        }
+
+        impl Index<usize> for $name {
+            type Output = f32;
+
+            fn index(&self, index: usize) -> &f32 {
+                match index {
+                    $($index => &self.$field,)+
+                    _ => panic!("index out of bounds: {}", index),
+                }
+            }
+        }
+
+        impl IndexMut<usize> for $name {
+            fn index_mut(&mut self, index: usize) -> &mut f32 {
+                match index {
+                    $($index => &mut self.$field,)+
+                    _ => panic!("index out of bounds: {}", index),
+                }
+            }
+        }
+
+        impl Neg for $name {
+            type Output = $name;
+
+            fn neg(self) -> $name {
+                $name { $($field: -self.$field),+ }
+            }
+        }
+
+        vector!(@operator $name, Add, add, AddAssign, add_assign, +, $($field),+);
+        vector!(@operator $name, Sub, sub, SubAssign, sub_assign, -, $($field),+);
+        vector!(@operator $name, Mul, mul, MulAssign, mul_assign, *, $($field),+);
+        vector!(@operator $name, Div, div, DivAssign, div_assign, /, $($field),+);
+    };
+    (@operator $name:ident, $trait:ident, $method:ident, $assign_trait:ident,
+     $assign_method:ident, $operator:tt, $($field:ident),+) => {
+        impl $trait for $name {
+            type Output = $name;
+
+            fn $method(self, other: $name) -> $name {
+                $name { $($field: self.$field $operator other.$field),+ }
+            }
+        }
+
+        impl $trait<f32> for $name {
+            type Output = $name;
+
+            fn $method(self, other: f32) -> $name {
+                $name { $($field: self.$field $operator other),+ }
+            }
+        }
+
+        impl $trait<$name> for f32 {
+            type Output = $name;
+
+            fn $method(self, other: $name) -> $name {
+                $name { $($field: self $operator other.$field),+ }
+            }
+        }
+
+        impl $assign_trait for $name {
+            fn $assign_method(&mut self, other: $name) {
+                *self = *self $operator other;
+            }
+        }
+
+        impl $assign_trait<f32> for $name {
+            fn $assign_method(&mut self, other: f32) {
+                *self = *self $operator other;
+            }
+        }
+    };
+}
+
+/// 2D vector.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Vec2 {
+    pub x: f32,
+    pub y: f32,
+}
+
+vector!(Vec2, 2, 0 x, 1 y);
+
+impl Vec2 {
+    pub const X: Vec2 = Vec2::new(1.0, 0.0);
+    pub const Y: Vec2 = Vec2::new(0.0, 1.0);
+
+    /// Creates a 3D vector with the given `z`.
+    pub fn extend(self, z: f32) -> Vec3 {
+        Vec3::new(self.x, self.y, z)
+    }
+
+    /// Returns the `z` component of the 3D cross product, positive if `other` is
+    /// clockwise from the vector with the y-axis pointing down.
+    pub fn perp_dot(self, other: Vec2) -> f32 {
+        self.x * other.y - self.y * other.x
+    }
+
+    /// Returns the vector rotated by 90 degrees, clockwise with the y-axis pointing down.
+    pub fn perp(self) -> Vec2 {
+        Vec2::new(-self.y, self.x)
+    }
+
+    /// Returns the angle in radians from the x-axis, in `[-π, π]`.
+    pub fn angle(self) -> f32 {
+        self.y.atan2(self.x)
+    }
+
+    /// Returns the unit vector at `angle` radians from the x-axis.
+    pub fn from_angle(angle: f32) -> Vec2 {
+        let (sin, cos) = angle.sin_cos();
+        Vec2::new(cos, sin)
+    }
+
+    /// Rotates the vector by `angle` radians.
+    pub fn rotate(self, angle: f32) -> Vec2 {
+        let (sin, cos) = angle.sin_cos();
+        Vec2::new(self.x * cos - self.y * sin, self.x * sin + self.y * cos)
+    }
+}
+
+/// 3D vector.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Vec3 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+vector!(Vec3, 3, 0 x, 1 y, 2 z);
+
+impl Vec3 {
+    pub const X: Vec3 = Vec3::new(1.0, 0.0, 0.0);
+    pub const Y: Vec3 = Vec3::new(0.0, 1.0, 0.0);
+    pub const Z: Vec3 = Vec3::new(0.0, 0.0, 1.0);
+
+    /// Creates a 4D vector with the given `w`.
+    pub fn extend(self, w: f32) -> Vec4 {
+        Vec4::new(self.x, self.y, self.z, w)
+    }
+
+    /// Drops the `z` component.
+    pub fn truncate(self) -> Vec2 {
+        Vec2::new(self.x, self.y)
+    }
+
+    /// Returns the cross product, following the right-hand rule.
+    pub fn cross(self, other: Vec3) -> Vec3 {
+        Vec3::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+
+    /// Returns the angle in radians between two vectors.
+    pub fn angle_between(self, other: Vec3) -> f32 {
+        let cosine = self.dot(other) / (self.length_squared() * other.length_squared()).sqrt();
+        cosine.max(-1.0).min(1.0).acos()
+    }
+
+    /// Returns a unit vector orthogonal to this one.
+    pub fn any_orthogonal(self) -> Vec3 {
+        let other = if self.x.abs() > self.y.abs() {
+            Vec3::Y
+        } else {
+            Vec3::X
+        };
+        self.cross(other).normalize_or_zero()
+    }
+
+    /// Reflects the vector off a plane of unit `normal`.
+    pub fn reflect(self, normal: Vec3) -> Vec3 {
+        self - normal * (2.0 * self.dot(normal))
+    }
+}
+
+/// 4D vector, also used for homogeneous coordinates.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Vec4 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32,
+}
+
+vector!(Vec4, 4, 0 x, 1 y, 2 z, 3 w);
+
+impl Vec4 {
+    pub const X: Vec4 = Vec4::new(1.0, 0.0, 0.0, 0.0);
+    pub const Y: Vec4 = Vec4::new(0.0, 1.0, 0.0, 0.0);
+    pub const Z: Vec4 = Vec4::new(0.0, 0.0, 1.0, 0.0);
+    pub const W: Vec4 = Vec4::new(0.0, 0.0, 0.0, 1.0);
+
+    /// Drops the `w` component.
+    pub fn truncate(self) -> Vec3 {
+        Vec3::new(self.x, self.y, self.z)
+    }
+
+    /// Divides `x`, `y` and `z` by `w`.
+    pub fn project(self) -> Vec3 {
+        self.truncate() / self.w
+    }
+}
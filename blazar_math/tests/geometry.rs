@@ -0,0 +1,549 @@
+use blazar_math::{
+    Aabb, Capsule, Frustum, Mat4, Obb, Plane, Quat, Ray, Sphere, Transform, Triangle, Vec3, Vec4,
+};
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_4};
+
+const EPSILON: f32 = 1e-5;
+
+fn unit_box() -> Aabb {
+    Aabb::new(Vec3::splat(-1.0), Vec3::ONE)
+}
+
+#[test]
+fn ray() {
+    let ray = Ray::new(Vec3::ZERO, Vec3::new(0.0, 0.0, -2.0));
+    assert_eq!(ray.direction, -Vec3::Z);
+    assert_eq!(ray.at(3.0), Vec3::new(0.0, 0.0, -3.0));
+    assert_eq!(
+        ray.closest_point(Vec3::new(1.0, 0.0, -5.0)),
+        Vec3::new(0.0, 0.0, -5.0)
+    );
+    // Points behind the origin are closest to the origin.
+    assert_eq!(ray.closest_point(Vec3::new(1.0, 0.0, 5.0)), Vec3::ZERO);
+    assert_eq!(ray.distance_to_point(Vec3::new(3.0, 4.0, -1.0)), 5.0);
+    let ray = Ray::from_points(Vec3::ONE, Vec3::new(1.0, 4.0, 1.0));
+    assert_eq!(ray.direction, Vec3::Y);
+}
+
+#[test]
+fn plane() {
+    let plane = Plane::from_point_normal(Vec3::new(0.0, 2.0, 0.0), Vec3::new(0.0, 3.0, 0.0));
+    assert_eq!(plane.normal, Vec3::Y);
+    assert_eq!(plane.d, -2.0);
+    assert_eq!(plane.signed_distance(Vec3::new(5.0, 5.0, 5.0)), 3.0);
+    assert_eq!(plane.signed_distance(Vec3::ZERO), -2.0);
+    assert_eq!(
+        plane.closest_point(Vec3::new(1.0, -4.0, 2.0)),
+        Vec3::new(1.0, 2.0, 2.0)
+    );
+    assert_eq!(plane.flip().signed_distance(Vec3::ZERO), 2.0);
+    let plane = Plane::from_points(Vec3::ZERO, Vec3::X, Vec3::Y).unwrap();
+    assert_eq!(plane.normal, Vec3::Z);
+    assert_eq!(Plane::from_points(Vec3::ZERO, Vec3::X, Vec3::X * 2.0), None);
+    let plane = Plane::from_coefficients(Vec4::new(0.0, 0.0, 2.0, 4.0)).unwrap();
+    assert_eq!(plane, Plane::new(Vec3::Z, 2.0));
+    assert_eq!(Plane::from_coefficients(Vec4::W), None);
+}
+
+#[test]
+fn plane_ray() {
+    let plane = Plane::new(Vec3::Y, 0.0);
+    let hit = plane
+        .intersect_ray(&Ray::new(Vec3::new(1.0, 4.0, 0.0), -Vec3::Y))
+        .unwrap();
+    assert_eq!(hit.distance, 4.0);
+    assert_eq!(hit.point, Vec3::X);
+    assert_eq!(hit.normal, Vec3::Y);
+    // Hit from behind, the normal facing the ray origin.
+    let hit = plane
+        .intersect_ray(&Ray::new(
+            Vec3::new(0.0, -2.0, 0.0),
+            Vec3::new(1.0, 1.0, 0.0),
+        ))
+        .unwrap();
+    assert!((hit.distance - 2.0 * 2.0f32.sqrt()).abs() < EPSILON);
+    assert_eq!(hit.normal, -Vec3::Y);
+    assert_eq!(plane.intersect_ray(&Ray::new(Vec3::Y, Vec3::Y)), None);
+    assert_eq!(plane.intersect_ray(&Ray::new(Vec3::Y, Vec3::X)), None);
+}
+
+#[test]
+fn aabb() {
+    let aabb = Aabb::from_points(vec![
+        Vec3::new(1.0, -2.0, 3.0),
+        Vec3::new(-1.0, 4.0, 0.0),
+        Vec3::new(0.0, 0.0, 5.0),
+    ])
+    .unwrap();
+    assert_eq!(
+        aabb,
+        Aabb::new(Vec3::new(-1.0, -2.0, 0.0), Vec3::new(1.0, 4.0, 5.0))
+    );
+    assert_eq!(Aabb::from_points(Vec::new()), None);
+    assert_eq!(aabb.center(), Vec3::new(0.0, 1.0, 2.5));
+    assert_eq!(aabb.half_extents(), Vec3::new(1.0, 3.0, 2.5));
+    assert_eq!(aabb.size(), Vec3::new(2.0, 6.0, 5.0));
+    assert_eq!(aabb.volume(), 60.0);
+    let corners = unit_box().corners();
+    assert_eq!(corners[0], Vec3::splat(-1.0));
+    assert_eq!(corners[7], Vec3::ONE);
+    assert_eq!(corners[5], Vec3::new(1.0, -1.0, 1.0));
+    assert_eq!(
+        unit_box().union(&Aabb::new(Vec3::ZERO, Vec3::splat(3.0))),
+        Aabb::new(Vec3::splat(-1.0), Vec3::splat(3.0))
+    );
+    assert_eq!(
+        unit_box().intersection(&Aabb::new(Vec3::ZERO, Vec3::splat(3.0))),
+        Some(Aabb::new(Vec3::ZERO, Vec3::ONE))
+    );
+    assert_eq!(
+        unit_box().intersection(&Aabb::new(Vec3::splat(2.0), Vec3::splat(3.0))),
+        None
+    );
+    assert_eq!(
+        unit_box().inflate(1.0),
+        Aabb::new(Vec3::splat(-2.0), Vec3::splat(2.0))
+    );
+}
+
+#[test]
+fn aabb_queries() {
+    let aabb = unit_box();
+    assert!(aabb.contains_point(Vec3::ZERO));
+    assert!(aabb.contains_point(Vec3::ONE));
+    assert!(!aabb.contains_point(Vec3::new(0.0, 1.5, 0.0)));
+    assert!(aabb.contains_aabb(&Aabb::new(Vec3::ZERO, Vec3::splat(0.5))));
+    assert!(!aabb.contains_aabb(&Aabb::new(Vec3::ZERO, Vec3::splat(1.5))));
+    assert!(aabb.intersects_aabb(&Aabb::new(Vec3::ONE, Vec3::splat(2.0))));
+    assert!(!aabb.intersects_aabb(&Aabb::new(Vec3::new(1.1, 0.0, 0.0), Vec3::splat(2.0))));
+    assert!(aabb.intersects_sphere(&Sphere::new(Vec3::new(2.0, 0.0, 0.0), 1.0)));
+    assert!(!aabb.intersects_sphere(&Sphere::new(Vec3::splat(2.0), 1.5)));
+    assert_eq!(
+        aabb.closest_point(Vec3::new(3.0, 0.5, -4.0)),
+        Vec3::new(1.0, 0.5, -1.0)
+    );
+    assert_eq!(aabb.closest_point(Vec3::splat(0.5)), Vec3::splat(0.5));
+    assert_eq!(
+        aabb.distance_squared_to_point(Vec3::new(3.0, 3.0, 0.0)),
+        8.0
+    );
+    assert_eq!(aabb.distance_to_point(Vec3::new(0.0, 0.0, 4.0)), 3.0);
+}
+
+#[test]
+fn aabb_transform() {
+    let matrix = Mat4::from_translation(Vec3::X) * Mat4::from_rotation_z(FRAC_PI_4);
+    let transformed = unit_box().transform(&matrix);
+    let expected = Aabb::from_points(
+        unit_box()
+            .corners()
+            .iter()
+            .map(|corner| matrix.transform_point3(*corner)),
+    )
+    .unwrap();
+    assert!(transformed.min.abs_diff_eq(expected.min, EPSILON));
+    assert!(transformed.max.abs_diff_eq(expected.max, EPSILON));
+}
+
+#[test]
+fn aabb_ray() {
+    let aabb = unit_box();
+    let hit = aabb
+        .intersect_ray(&Ray::new(Vec3::new(-5.0, 0.5, 0.0), Vec3::X))
+        .unwrap();
+    assert_eq!(hit.distance, 4.0);
+    assert_eq!(hit.point, Vec3::new(-1.0, 0.5, 0.0));
+    assert_eq!(hit.normal, -Vec3::X);
+    let hit = aabb
+        .intersect_ray(&Ray::from_points(
+            Vec3::new(0.5, 5.0, 3.0),
+            Vec3::new(0.5, 1.0, -1.0),
+        ))
+        .unwrap();
+    assert_eq!(hit.normal, Vec3::Y);
+    assert!(hit.point.abs_diff_eq(Vec3::new(0.5, 1.0, -1.0), EPSILON));
+    let hit = aabb.intersect_ray(&Ray::new(Vec3::ZERO, Vec3::Z)).unwrap();
+    assert_eq!(hit.distance, 0.0);
+    assert_eq!(hit.normal, Vec3::ZERO);
+    assert_eq!(
+        aabb.intersect_ray(&Ray::new(Vec3::new(-5.0, 0.0, 0.0), -Vec3::X)),
+        None
+    );
+    assert_eq!(
+        aabb.intersect_ray(&Ray::new(Vec3::new(-5.0, 2.0, 0.0), Vec3::X)),
+        None
+    );
+    assert_eq!(
+        aabb.intersect_ray(&Ray::new(
+            Vec3::new(-5.0, 0.0, 0.0),
+            Vec3::new(1.0, 1.0, 0.0)
+        )),
+        None
+    );
+}
+
+#[test]
+fn sphere() {
+    let sphere = Sphere::new(Vec3::new(1.0, 0.0, 0.0), 2.0);
+    assert_eq!(
+        sphere.aabb(),
+        Aabb::new(Vec3::new(-1.0, -2.0, -2.0), Vec3::new(3.0, 2.0, 2.0))
+    );
+    assert!(sphere.contains_point(Vec3::new(3.0, 0.0, 0.0)));
+    assert!(!sphere.contains_point(Vec3::new(3.0, 0.1, 0.0)));
+    assert!(sphere.contains_sphere(&Sphere::new(Vec3::ZERO, 1.0)));
+    assert!(!sphere.contains_sphere(&Sphere::new(Vec3::new(-1.0, 0.0, 0.0), 1.0)));
+    assert!(sphere.intersects_sphere(&Sphere::new(Vec3::new(4.0, 0.0, 0.0), 1.0)));
+    assert!(!sphere.intersects_sphere(&Sphere::new(Vec3::new(4.5, 0.0, 0.0), 1.0)));
+    assert!(sphere.intersects_aabb(&unit_box()));
+    assert_eq!(
+        sphere.closest_point(Vec3::new(1.0, 5.0, 0.0)),
+        Vec3::new(1.0, 2.0, 0.0)
+    );
+    assert_eq!(sphere.closest_point(Vec3::ONE), Vec3::ONE);
+    assert_eq!(sphere.distance_to_point(Vec3::new(1.0, 0.0, -5.0)), 3.0);
+    assert_eq!(sphere.distance_to_point(Vec3::ZERO), 0.0);
+    let points = [
+        Vec3::new(-1.0, 0.0, 0.0),
+        Vec3::new(3.0, 0.0, 0.0),
+        Vec3::new(0.0, 1.0, 1.0),
+    ];
+    let bounds = Sphere::from_points(&points).unwrap();
+    assert!(points
+        .iter()
+        .all(|point| bounds.radius - bounds.center.distance(*point) >= -EPSILON));
+    assert_eq!(Sphere::from_points(&[]), None);
+}
+
+#[test]
+fn sphere_ray() {
+    let sphere = Sphere::new(Vec3::ZERO, 1.0);
+    let hit = sphere
+        .intersect_ray(&Ray::new(Vec3::new(0.0, 0.0, 5.0), -Vec3::Z))
+        .unwrap();
+    assert_eq!(hit.distance, 4.0);
+    assert_eq!(hit.point, Vec3::Z);
+    assert_eq!(hit.normal, Vec3::Z);
+    let hit = sphere
+        .intersect_ray(&Ray::new(Vec3::new(0.0, 0.5, 5.0), -Vec3::Z))
+        .unwrap();
+    assert!((hit.distance - (5.0 - 0.75f32.sqrt())).abs() < EPSILON);
+    assert!(hit.normal.is_normalized(EPSILON));
+    assert_eq!(
+        sphere
+            .intersect_ray(&Ray::new(Vec3::ZERO, Vec3::X))
+            .unwrap()
+            .distance,
+        0.0
+    );
+    assert_eq!(
+        sphere.intersect_ray(&Ray::new(Vec3::new(0.0, 0.0, 5.0), Vec3::Z)),
+        None
+    );
+    assert_eq!(
+        sphere.intersect_ray(&Ray::new(Vec3::new(0.0, 1.5, 5.0), -Vec3::Z)),
+        None
+    );
+}
+
+#[test]
+fn obb() {
+    let obb = Obb::new(
+        Vec3::X,
+        Vec3::new(2.0, 1.0, 1.0),
+        Quat::from_rotation_z(FRAC_PI_2),
+    );
+    let axes = obb.axes();
+    assert!(axes[0].abs_diff_eq(Vec3::Y, EPSILON));
+    assert!(axes[1].abs_diff_eq(-Vec3::X, EPSILON));
+    assert!(obb.contains_point(Vec3::new(1.0, 1.9, 0.0)));
+    assert!(!obb.contains_point(Vec3::new(2.5, 0.0, 0.0)));
+    let aabb = obb.aabb();
+    assert!(aabb.min.abs_diff_eq(Vec3::new(0.0, -2.0, -1.0), EPSILON));
+    assert!(aabb.max.abs_diff_eq(Vec3::new(2.0, 2.0, 1.0), EPSILON));
+    assert!(obb
+        .closest_point(Vec3::new(5.0, 5.0, 0.0))
+        .abs_diff_eq(Vec3::new(2.0, 2.0, 0.0), EPSILON));
+    assert!((obb.distance_to_point(Vec3::new(1.0, 5.0, 0.0)) - 3.0).abs() < EPSILON);
+    for corner in &obb.corners() {
+        assert!(aabb.inflate(EPSILON).contains_point(*corner));
+    }
+    let transform = Transform {
+        translation: Vec3::new(0.0, 3.0, 0.0),
+        rotation: Quat::from_rotation_y(FRAC_PI_2),
+        scale: Vec3::new(2.0, 1.0, -1.0),
+    };
+    let obb = Obb::from_aabb_transform(&unit_box(), &transform);
+    assert_eq!(obb.half_extents, Vec3::new(2.0, 1.0, 1.0));
+    assert_eq!(obb.center, Vec3::new(0.0, 3.0, 0.0));
+    assert!(obb.contains_point(Vec3::new(0.0, 3.0, 1.9)));
+}
+
+#[test]
+fn obb_intersections() {
+    let a = Obb::from(unit_box());
+    let rotated = |center: Vec3| Obb::new(center, Vec3::ONE, Quat::from_rotation_z(FRAC_PI_4));
+    // The corner of the rotated box reaches sqrt(2) from its center.
+    assert!(a.intersects_obb(&rotated(Vec3::new(2.3, 0.0, 0.0))));
+    assert!(!a.intersects_obb(&rotated(Vec3::new(2.5, 0.0, 0.0))));
+    // Separated along an edge-edge axis only.
+    let edge = Obb::new(
+        Vec3::new(2.0, 2.0, 0.0),
+        Vec3::ONE,
+        Quat::from_rotation_z(FRAC_PI_4) * Quat::from_rotation_x(FRAC_PI_4),
+    );
+    assert!(!a.intersects_obb(&edge));
+    assert!(a.intersects_obb(&a));
+    assert!(rotated(Vec3::new(2.3, 0.0, 0.0)).intersects_aabb(&unit_box()));
+    assert!(a.intersects_sphere(&Sphere::new(Vec3::new(0.0, 0.0, 1.5), 0.6)));
+    assert!(!rotated(Vec3::ZERO).intersects_sphere(&Sphere::new(Vec3::new(1.9, 0.0, 0.0), 0.4)));
+}
+
+#[test]
+fn obb_ray() {
+    let obb = Obb::new(Vec3::ZERO, Vec3::ONE, Quat::from_rotation_z(FRAC_PI_4));
+    let hit = obb
+        .intersect_ray(&Ray::new(Vec3::new(-5.0, 0.0, 0.0), Vec3::X))
+        .unwrap();
+    assert!((hit.distance - (5.0 - 2.0f32.sqrt())).abs() < EPSILON);
+    assert!(hit
+        .point
+        .abs_diff_eq(Vec3::new(-(2.0f32.sqrt()), 0.0, 0.0), EPSILON));
+    assert!(hit.normal.z.abs() < EPSILON && hit.normal.x < 0.0);
+    assert!(hit.normal.is_normalized(EPSILON));
+    assert_eq!(
+        obb.intersect_ray(&Ray::new(Vec3::new(-5.0, 1.5, 0.0), Vec3::X)),
+        None
+    );
+}
+
+#[test]
+fn capsule() {
+    let capsule = Capsule::new(Vec3::ZERO, Vec3::new(0.0, 4.0, 0.0), 1.0);
+    assert_eq!(
+        capsule.aabb(),
+        Aabb::new(Vec3::new(-1.0, -1.0, -1.0), Vec3::new(1.0, 5.0, 1.0))
+    );
+    assert!(capsule.contains_point(Vec3::new(1.0, 2.0, 0.0)));
+    assert!(capsule.contains_point(Vec3::new(0.0, 4.9, 0.0)));
+    assert!(!capsule.contains_point(Vec3::new(0.8, 4.8, 0.0)));
+    assert_eq!(
+        capsule.closest_point_on_segment(Vec3::new(3.0, 2.0, 0.0)),
+        Vec3::new(0.0, 2.0, 0.0)
+    );
+    assert_eq!(
+        capsule.closest_point(Vec3::new(3.0, 2.0, 0.0)),
+        Vec3::new(1.0, 2.0, 0.0)
+    );
+    assert_eq!(capsule.closest_point(Vec3::new(0.0, -3.0, 0.0)), -Vec3::Y);
+    assert_eq!(capsule.distance_to_point(Vec3::new(0.0, 7.0, 0.0)), 2.0);
+    assert!(capsule.intersects_sphere(&Sphere::new(Vec3::new(2.5, 3.0, 0.0), 1.5)));
+    assert!(!capsule.intersects_sphere(&Sphere::new(Vec3::new(2.5, 3.0, 0.0), 1.0)));
+    let crossing = Capsule::new(Vec3::new(-3.0, 2.0, 1.5), Vec3::new(3.0, 2.0, 1.5), 0.6);
+    assert!(capsule.intersects_capsule(&crossing));
+    let far = Capsule::new(Vec3::new(-3.0, 2.0, 2.0), Vec3::new(3.0, 2.0, 2.0), 0.5);
+    assert!(!capsule.intersects_capsule(&far));
+    let parallel = Capsule::new(Vec3::new(1.5, 3.0, 0.0), Vec3::new(1.5, 8.0, 0.0), 0.6);
+    assert!(capsule.intersects_capsule(&parallel));
+    let point = Capsule::new(Vec3::new(0.0, 6.5, 0.0), Vec3::new(0.0, 6.5, 0.0), 1.0);
+    assert!(!capsule.intersects_capsule(&point));
+}
+
+#[test]
+fn capsule_ray() {
+    let capsule = Capsule::new(Vec3::ZERO, Vec3::new(0.0, 4.0, 0.0), 1.0);
+    // Side of the cylinder.
+    let hit = capsule
+        .intersect_ray(&Ray::new(Vec3::new(-5.0, 2.0, 0.0), Vec3::X))
+        .unwrap();
+    assert!((hit.distance - 4.0).abs() < EPSILON);
+    assert!(hit.normal.abs_diff_eq(-Vec3::X, EPSILON));
+    // Cap, along the axis.
+    let hit = capsule
+        .intersect_ray(&Ray::new(Vec3::new(0.0, 10.0, 0.0), -Vec3::Y))
+        .unwrap();
+    assert!((hit.distance - 5.0).abs() < EPSILON);
+    assert!(hit.normal.abs_diff_eq(Vec3::Y, EPSILON));
+    let hit = capsule
+        .intersect_ray(&Ray::new(Vec3::new(0.0, -10.0, 0.0), Vec3::Y))
+        .unwrap();
+    assert!((hit.distance - 9.0).abs() < EPSILON);
+    // Cap, from the side.
+    let hit = capsule
+        .intersect_ray(&Ray::new(Vec3::new(-5.0, 4.5, 0.0), Vec3::X))
+        .unwrap();
+    assert!((hit.point.x + 0.75f32.sqrt()).abs() < EPSILON);
+    assert_eq!(
+        capsule
+            .intersect_ray(&Ray::new(Vec3::Y, Vec3::X))
+            .unwrap()
+            .distance,
+        0.0
+    );
+    assert_eq!(
+        capsule.intersect_ray(&Ray::new(Vec3::new(-5.0, 5.5, 0.0), Vec3::X)),
+        None
+    );
+    assert_eq!(
+        capsule.intersect_ray(&Ray::new(Vec3::new(-5.0, 2.0, 0.0), -Vec3::X)),
+        None
+    );
+}
+
+#[test]
+fn triangle() {
+    let triangle = Triangle::new(
+        Vec3::ZERO,
+        Vec3::new(2.0, 0.0, 0.0),
+        Vec3::new(0.0, 2.0, 0.0),
+    );
+    assert_eq!(triangle.normal(), Vec3::Z);
+    assert_eq!(triangle.area(), 2.0);
+    assert_eq!(triangle.centroid(), Vec3::new(2.0 / 3.0, 2.0 / 3.0, 0.0));
+    assert_eq!(triangle.plane(), Some(Plane::new(Vec3::Z, 0.0)));
+    assert_eq!(
+        triangle.aabb(),
+        Aabb::new(Vec3::ZERO, Vec3::new(2.0, 2.0, 0.0))
+    );
+    assert_eq!(
+        triangle.barycentric(Vec3::new(1.0, 0.0, 3.0)),
+        Some(Vec3::new(0.5, 0.5, 0.0))
+    );
+    let degenerate = Triangle::new(Vec3::ZERO, Vec3::X, Vec3::X * 2.0);
+    assert_eq!(degenerate.barycentric(Vec3::ZERO), None);
+    assert_eq!(degenerate.normal(), Vec3::ZERO);
+    assert_eq!(degenerate.plane(), None);
+}
+
+#[test]
+fn triangle_closest_point() {
+    let triangle = Triangle::new(
+        Vec3::ZERO,
+        Vec3::new(2.0, 0.0, 0.0),
+        Vec3::new(0.0, 2.0, 0.0),
+    );
+    let cases = [
+        (Vec3::new(-1.0, -1.0, 0.0), Vec3::ZERO),
+        (Vec3::new(3.0, -1.0, 0.0), Vec3::new(2.0, 0.0, 0.0)),
+        (Vec3::new(-1.0, 3.0, 1.0), Vec3::new(0.0, 2.0, 0.0)),
+        (Vec3::new(1.0, -1.0, 0.0), Vec3::new(1.0, 0.0, 0.0)),
+        (Vec3::new(-1.0, 1.0, 0.0), Vec3::new(0.0, 1.0, 0.0)),
+        (Vec3::new(2.0, 2.0, 0.0), Vec3::new(1.0, 1.0, 0.0)),
+        (Vec3::new(0.5, 0.5, 4.0), Vec3::new(0.5, 0.5, 0.0)),
+    ];
+    for (point, expected) in &cases {
+        assert!(triangle
+            .closest_point(*point)
+            .abs_diff_eq(*expected, EPSILON));
+    }
+    assert_eq!(triangle.distance_to_point(Vec3::new(0.5, 0.5, -4.0)), 4.0);
+}
+
+#[test]
+fn triangle_ray() {
+    let triangle = Triangle::new(
+        Vec3::ZERO,
+        Vec3::new(2.0, 0.0, 0.0),
+        Vec3::new(0.0, 2.0, 0.0),
+    );
+    let hit = triangle
+        .intersect_ray(&Ray::new(Vec3::new(0.5, 0.5, 3.0), -Vec3::Z))
+        .unwrap();
+    assert!((hit.distance - 3.0).abs() < EPSILON);
+    assert!(hit.point.abs_diff_eq(Vec3::new(0.5, 0.5, 0.0), EPSILON));
+    assert_eq!(hit.normal, Vec3::Z);
+    // Back face.
+    let hit = triangle
+        .intersect_ray(&Ray::new(Vec3::new(0.5, 0.5, -3.0), Vec3::Z))
+        .unwrap();
+    assert_eq!(hit.normal, -Vec3::Z);
+    assert_eq!(
+        triangle.intersect_ray(&Ray::new(Vec3::new(1.5, 1.5, 3.0), -Vec3::Z)),
+        None
+    );
+    assert_eq!(
+        triangle.intersect_ray(&Ray::new(Vec3::new(0.5, 0.5, 3.0), Vec3::Z)),
+        None
+    );
+    assert_eq!(
+        triangle.intersect_ray(&Ray::new(Vec3::new(-1.0, 0.5, 0.0), Vec3::X)),
+        None
+    );
+}
+
+#[test]
+fn frustum() {
+    let view = Mat4::look_at_rh(Vec3::new(0.0, 0.0, 5.0), Vec3::ZERO, Vec3::Y);
+    let projection = Mat4::perspective_rh(FRAC_PI_2, 1.0, 1.0, 10.0);
+    let frustum = Frustum::from_matrix(&(projection * view));
+    for plane in &frustum.planes {
+        assert!(plane.normal.is_normalized(EPSILON));
+    }
+    assert!(frustum.contains_point(Vec3::ZERO));
+    assert!(frustum.contains_point(Vec3::new(0.0, 0.0, 3.9)));
+    assert!(!frustum.contains_point(Vec3::new(0.0, 0.0, 4.1)));
+    assert!(!frustum.contains_point(Vec3::new(0.0, 0.0, -5.1)));
+    assert!(!frustum.contains_point(Vec3::new(5.5, 0.0, 0.0)));
+    // The top plane bounds positive y in world space, y pointing down in clip space.
+    assert!(frustum.planes[Frustum::TOP].signed_distance(Vec3::new(0.0, 5.5, 0.0)) < 0.0);
+    assert!(frustum.planes[Frustum::BOTTOM].signed_distance(Vec3::new(0.0, -5.5, 0.0)) < 0.0);
+    assert!(frustum.planes[Frustum::LEFT].signed_distance(Vec3::new(-5.5, 0.0, 0.0)) < 0.0);
+    assert!((frustum.planes[Frustum::NEAR].signed_distance(Vec3::ZERO) - 4.0).abs() < EPSILON);
+    assert!((frustum.planes[Frustum::FAR].signed_distance(Vec3::ZERO) - 5.0).abs() < EPSILON);
+}
+
+#[test]
+fn frustum_culling() {
+    let view = Mat4::look_at_rh(Vec3::new(0.0, 0.0, 5.0), Vec3::ZERO, Vec3::Y);
+    let projection = Mat4::perspective_rh(FRAC_PI_2, 1.0, 1.0, 10.0);
+    let frustum = Frustum::from_matrix(&(projection * view));
+    assert!(frustum.intersects_sphere(&Sphere::new(Vec3::new(6.0, 0.0, 0.0), 1.0)));
+    assert!(!frustum.intersects_sphere(&Sphere::new(Vec3::new(7.0, 0.0, 0.0), 1.0)));
+    assert!(frustum.contains_sphere(&Sphere::new(Vec3::ZERO, 1.0)));
+    assert!(!frustum.contains_sphere(&Sphere::new(Vec3::new(5.0, 0.0, 0.0), 1.0)));
+    assert!(frustum.intersects_aabb(&unit_box()));
+    assert!(frustum.contains_aabb(&unit_box()));
+    let straddling = Aabb::new(Vec3::new(4.0, -1.0, -1.0), Vec3::new(6.0, 1.0, 1.0));
+    assert!(frustum.intersects_aabb(&straddling));
+    assert!(!frustum.contains_aabb(&straddling));
+    let behind = Aabb::new(Vec3::new(-1.0, -1.0, 6.0), Vec3::new(1.0, 1.0, 8.0));
+    assert!(!frustum.intersects_aabb(&behind));
+    assert!(!frustum.intersects_aabb(&Aabb::new(
+        Vec3::new(-1.0, -1.0, -20.0),
+        Vec3::new(1.0, 1.0, -6.0)
+    )));
+    assert!(frustum.intersects_obb(&Obb::new(Vec3::ZERO, Vec3::ONE, Quat::from_rotation_x(1.0))));
+    let outside = Obb::new(
+        Vec3::new(0.0, 8.0, 0.0),
+        Vec3::new(1.0, 0.1, 1.0),
+        Quat::from_rotation_z(FRAC_PI_4),
+    );
+    assert!(!frustum.intersects_obb(&outside));
+}
+
+#[test]
+fn frustum_reverse_infinite() {
+    let projection = Mat4::perspective_infinite_reverse_rh(FRAC_PI_2, 1.0, 1.0);
+    let frustum = Frustum::from_matrix(&projection);
+    assert!(frustum.contains_point(Vec3::new(0.0, 0.0, -1e6)));
+    assert!(frustum.contains_point(Vec3::new(0.0, 0.0, -1.5)));
+    assert!(!frustum.contains_point(Vec3::new(0.0, 0.0, -0.5)));
+    assert!(!frustum.contains_point(Vec3::new(3.0, 0.0, -2.0)));
+    assert!(frustum.intersects_sphere(&Sphere::new(Vec3::new(0.0, 0.0, -1e4), 1.0)));
+}
+
+#[test]
+fn orthographic_frustum() {
+    let projection = Mat4::orthographic_rh(0.0, 800.0, 600.0, 0.0, 0.0, 1.0);
+    let frustum = Frustum::from_matrix(&projection);
+    assert!(frustum.contains_point(Vec3::new(400.0, 300.0, -0.5)));
+    assert!(!frustum.contains_point(Vec3::new(-1.0, 300.0, -0.5)));
+    assert!(frustum.intersects_aabb(&Aabb::new(
+        Vec3::new(-10.0, -10.0, -0.5),
+        Vec3::new(1.0, 1.0, -0.5)
+    )));
+    assert!(!frustum.intersects_aabb(&Aabb::new(
+        Vec3::new(801.0, 0.0, -0.5),
+        Vec3::new(900.0, 1.0, -0.5)
+    )));
+}
@@ -0,0 +1,258 @@
+use blazar_math::{Mat3, Mat4, Quat, Vec2, Vec3, Vec4};
+use std::f32::consts::FRAC_PI_2;
+
+const EPSILON: f32 = 1e-5;
+
+/// Returns a matrix mixing a scale, a rotation and a translation.
+fn affine() -> Mat4 {
+    Mat4::from_scale_rotation_translation(
+        Vec3::new(2.0, 0.5, 3.0),
+        Quat::from_euler(0.4, -0.3, 1.1),
+        Vec3::new(-1.0, 4.0, 2.5),
+    )
+}
+
+#[test]
+fn mat3_basics() {
+    assert_eq!(Mat3::default(), Mat3::IDENTITY);
+    let matrix = Mat3::from([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0], [7.0, 8.0, 10.0]]);
+    assert_eq!(matrix.row(0), Vec3::new(1.0, 4.0, 7.0));
+    assert_eq!(matrix.transpose().columns[0], Vec3::new(1.0, 4.0, 7.0));
+    assert_eq!(matrix.transpose().transpose(), matrix);
+    assert_eq!(matrix.determinant(), -3.0);
+    assert_eq!(Mat3::IDENTITY * matrix, matrix);
+    assert_eq!(matrix * Vec3::X, Vec3::new(1.0, 2.0, 3.0));
+    assert_eq!(matrix + Mat3::ZERO, matrix);
+    assert_eq!(matrix - matrix, Mat3::ZERO);
+    assert_eq!((matrix * 2.0).columns[2], Vec3::new(14.0, 16.0, 20.0));
+    let inverse = matrix.inverse().unwrap();
+    assert!((matrix * inverse).abs_diff_eq(&Mat3::IDENTITY, EPSILON));
+    assert!((inverse * matrix).abs_diff_eq(&Mat3::IDENTITY, EPSILON));
+    assert_eq!(Mat3::from_scale(Vec3::new(1.0, 0.0, 1.0)).inverse(), None);
+    let array: [[f32; 3]; 3] = matrix.into();
+    assert_eq!(Mat3::from(array), matrix);
+    let mut product = matrix;
+    product *= Mat3::from_scale(Vec3::splat(2.0));
+    assert_eq!(product, matrix * 2.0);
+}
+
+#[test]
+fn mat3_rotations() {
+    let cases = [
+        (
+            Mat3::from_rotation_x(FRAC_PI_2),
+            Quat::from_rotation_x(FRAC_PI_2),
+        ),
+        (
+            Mat3::from_rotation_y(FRAC_PI_2),
+            Quat::from_rotation_y(FRAC_PI_2),
+        ),
+        (
+            Mat3::from_rotation_z(FRAC_PI_2),
+            Quat::from_rotation_z(FRAC_PI_2),
+        ),
+        (
+            Mat3::from_axis_angle(Vec3::Y, 0.7),
+            Quat::from_rotation_y(0.7),
+        ),
+    ];
+    for (matrix, rotation) in &cases {
+        assert!(matrix.abs_diff_eq(&Mat3::from_quat(*rotation), EPSILON));
+        assert!((matrix.determinant() - 1.0).abs() < EPSILON);
+        assert!(matrix
+            .transpose()
+            .abs_diff_eq(&matrix.inverse().unwrap(), EPSILON));
+    }
+}
+
+#[test]
+fn mat3_2d_transforms() {
+    let transform =
+        Mat3::from_scale_angle_translation(Vec2::new(2.0, 3.0), FRAC_PI_2, Vec2::new(10.0, 20.0));
+    assert!(transform
+        .transform_point2(Vec2::new(1.0, 1.0))
+        .abs_diff_eq(Vec2::new(7.0, 22.0), EPSILON));
+    assert!(transform
+        .transform_vector2(Vec2::X)
+        .abs_diff_eq(Vec2::new(0.0, 2.0), EPSILON));
+    let inverse = transform.inverse().unwrap();
+    assert!(inverse
+        .transform_point2(Vec2::new(7.0, 22.0))
+        .abs_diff_eq(Vec2::ONE, EPSILON));
+}
+
+#[test]
+fn mat4_basics() {
+    assert_eq!(Mat4::default(), Mat4::IDENTITY);
+    let matrix = affine();
+    assert_eq!(Mat4::IDENTITY * matrix, matrix);
+    assert_eq!(matrix * Mat4::IDENTITY, matrix);
+    assert_eq!(matrix.transpose().transpose(), matrix);
+    assert_eq!(matrix.transpose().row(3), matrix.columns[3]);
+    assert_eq!(matrix + Mat4::ZERO, matrix);
+    assert_eq!(matrix - matrix, Mat4::ZERO);
+    assert_eq!((Mat4::IDENTITY * 2.0).row(1), Vec4::new(0.0, 2.0, 0.0, 0.0));
+    let array: [[f32; 4]; 4] = matrix.into();
+    assert_eq!(Mat4::from(array), matrix);
+    assert_eq!(matrix.to_cols_array_2d(), array);
+    assert_eq!(
+        Mat3::from_mat4(&Mat4::from_mat3(&Mat3::from_rotation_x(1.0))),
+        Mat3::from_rotation_x(1.0)
+    );
+    assert_eq!(std::mem::size_of::<Mat4>(), 64);
+    assert_eq!(std::mem::size_of::<Mat3>(), 36);
+}
+
+#[test]
+fn mat4_multiplication() {
+    let a = Mat4::from([
+        [1.0, 2.0, 3.0, 4.0],
+        [5.0, 6.0, 7.0, 8.0],
+        [9.0, 10.0, 11.0, 12.0],
+        [13.0, 14.0, 15.0, 16.0],
+    ]);
+    let b = a.transpose();
+    let product = a * b;
+    for column in 0..4 {
+        for row in 0..4 {
+            let expected = a.row(row).dot(b.columns[column]);
+            assert_eq!(product.columns[column][row], expected);
+        }
+    }
+    assert_eq!(a * Vec4::X, Vec4::new(1.0, 2.0, 3.0, 4.0));
+    assert_eq!(a * Vec4::ONE, Vec4::new(28.0, 32.0, 36.0, 40.0));
+    let mut accumulated = a;
+    accumulated *= b;
+    assert_eq!(accumulated, product);
+    // The right-hand matrix is applied first.
+    let translate_then_scale = Mat4::from_scale(Vec3::splat(2.0)) * Mat4::from_translation(Vec3::X);
+    assert_eq!(
+        translate_then_scale.transform_point3(Vec3::ZERO),
+        Vec3::new(2.0, 0.0, 0.0)
+    );
+}
+
+#[test]
+fn mat4_inverse() {
+    let matrix = affine();
+    let inverse = matrix.inverse().unwrap();
+    assert!((matrix * inverse).abs_diff_eq(&Mat4::IDENTITY, EPSILON));
+    assert!((inverse * matrix).abs_diff_eq(&Mat4::IDENTITY, EPSILON));
+    assert!((matrix.determinant() - 3.0).abs() < EPSILON);
+    let projection = Mat4::perspective_rh(1.0, 1.5, 0.1, 100.0);
+    let inverse = projection.inverse().unwrap();
+    assert!((projection * inverse).abs_diff_eq(&Mat4::IDENTITY, EPSILON));
+    assert_eq!(Mat4::ZERO.inverse(), None);
+    assert_eq!(Mat4::from_scale(Vec3::new(1.0, 1.0, 0.0)).inverse(), None);
+    assert_eq!(Mat4::IDENTITY.determinant(), 1.0);
+}
+
+#[test]
+fn mat4_transforms() {
+    let translation = Mat4::from_translation(Vec3::new(1.0, 2.0, 3.0));
+    assert_eq!(
+        translation.transform_point3(Vec3::ONE),
+        Vec3::new(2.0, 3.0, 4.0)
+    );
+    assert_eq!(translation.transform_vector3(Vec3::ONE), Vec3::ONE);
+    let scale = Mat4::from_scale(Vec3::new(1.0, 2.0, 3.0));
+    assert_eq!(scale.transform_point3(Vec3::ONE), Vec3::new(1.0, 2.0, 3.0));
+    let rotations = [
+        (Mat4::from_rotation_x(FRAC_PI_2), Vec3::Y, Vec3::Z),
+        (Mat4::from_rotation_y(FRAC_PI_2), Vec3::Z, Vec3::X),
+        (Mat4::from_rotation_z(FRAC_PI_2), Vec3::X, Vec3::Y),
+        (Mat4::from_axis_angle(Vec3::Z, FRAC_PI_2), Vec3::X, Vec3::Y),
+        (
+            Mat4::from_quat(Quat::from_rotation_x(FRAC_PI_2)),
+            Vec3::Y,
+            Vec3::Z,
+        ),
+    ];
+    for (matrix, from, to) in &rotations {
+        assert!(matrix.transform_vector3(*from).abs_diff_eq(*to, EPSILON));
+    }
+    let point = Vec3::new(0.5, -1.0, 2.0);
+    let rotation = Quat::from_euler(0.4, -0.3, 1.1);
+    let expected = rotation * (point * Vec3::new(2.0, 0.5, 3.0)) + Vec3::new(-1.0, 4.0, 2.5);
+    assert!(affine()
+        .transform_point3(point)
+        .abs_diff_eq(expected, EPSILON));
+    let combined = Mat4::from_mat3_translation(&Mat3::from_rotation_z(FRAC_PI_2), Vec3::Z);
+    assert!(combined
+        .transform_point3(Vec3::X)
+        .abs_diff_eq(Vec3::new(0.0, 1.0, 1.0), EPSILON));
+}
+
+#[test]
+fn mat4_decomposition() {
+    let (scale, rotation, translation) = affine().to_scale_rotation_translation();
+    assert!(scale.abs_diff_eq(Vec3::new(2.0, 0.5, 3.0), EPSILON));
+    assert!(rotation.same_rotation(Quat::from_euler(0.4, -0.3, 1.1), EPSILON));
+    assert_eq!(translation, Vec3::new(-1.0, 4.0, 2.5));
+    let mirrored = Mat4::from_scale(Vec3::new(-1.0, 1.0, 1.0));
+    let (scale, rotation, _) = mirrored.to_scale_rotation_translation();
+    assert_eq!(scale, Vec3::new(-1.0, 1.0, 1.0));
+    assert!(rotation.same_rotation(Quat::IDENTITY, EPSILON));
+}
+
+#[test]
+fn look_at() {
+    let eye = Vec3::new(1.0, 2.0, 5.0);
+    let view = Mat4::look_at_rh(eye, Vec3::new(1.0, 2.0, 0.0), Vec3::Y);
+    assert!(view.transform_point3(eye).abs_diff_eq(Vec3::ZERO, EPSILON));
+    // The target is in front of the camera, down -z.
+    assert!(view
+        .transform_point3(Vec3::new(1.0, 2.0, 0.0))
+        .abs_diff_eq(Vec3::new(0.0, 0.0, -5.0), EPSILON));
+    assert!(view
+        .transform_point3(Vec3::new(2.0, 3.0, 5.0))
+        .abs_diff_eq(Vec3::new(1.0, 1.0, 0.0), EPSILON));
+    let view = Mat4::look_to_rh(Vec3::ZERO, Vec3::X, Vec3::Y);
+    assert!(view
+        .transform_vector3(Vec3::X)
+        .abs_diff_eq(-Vec3::Z, EPSILON));
+    assert!((Mat3::from_mat4(&view).determinant() - 1.0).abs() < EPSILON);
+}
+
+#[test]
+fn perspective() {
+    let projection = Mat4::perspective_rh(FRAC_PI_2, 2.0, 1.0, 10.0);
+    // Near and far planes map to depths of 0 and 1.
+    let near = projection.project_point3(Vec3::new(0.0, 0.0, -1.0));
+    let far = projection.project_point3(Vec3::new(0.0, 0.0, -10.0));
+    assert!((near.z - 0.0).abs() < EPSILON);
+    assert!((far.z - 1.0).abs() < EPSILON);
+    // Y points down in clip space, x to the right.
+    let top_right = projection.project_point3(Vec3::new(2.0, 1.0, -1.0));
+    assert!(top_right.abs_diff_eq(Vec3::new(1.0, -1.0, 0.0), EPSILON));
+    let clip = projection * Vec4::new(0.0, 0.0, -4.0, 1.0);
+    assert_eq!(clip.w, 4.0);
+    let reverse = Mat4::perspective_infinite_reverse_rh(FRAC_PI_2, 1.0, 0.5);
+    assert!((reverse.project_point3(Vec3::new(0.0, 0.0, -0.5)).z - 1.0).abs() < EPSILON);
+    assert!((reverse.project_point3(Vec3::new(0.0, 0.0, -1.0)).z - 0.5).abs() < EPSILON);
+    assert!(reverse.project_point3(Vec3::new(0.0, 0.0, -1e6)).z < 1e-5);
+    assert!(reverse
+        .project_point3(Vec3::new(-1.0, -1.0, -1.0))
+        .truncate()
+        .abs_diff_eq(Vec2::new(-1.0, 1.0), EPSILON));
+}
+
+#[test]
+fn orthographic() {
+    // Pixel coordinates with the y-axis pointing down.
+    let projection = Mat4::orthographic_rh(0.0, 800.0, 600.0, 0.0, 0.0, 1.0);
+    assert!(projection
+        .transform_point3(Vec3::ZERO)
+        .abs_diff_eq(Vec3::new(-1.0, -1.0, 0.0), EPSILON));
+    assert!(projection
+        .transform_point3(Vec3::new(800.0, 600.0, -1.0))
+        .abs_diff_eq(Vec3::new(1.0, 1.0, 1.0), EPSILON));
+    // Y pointing up.
+    let projection = Mat4::orthographic_rh(-2.0, 2.0, -1.0, 1.0, 1.0, 3.0);
+    assert!(projection
+        .transform_point3(Vec3::new(-2.0, 1.0, -1.0))
+        .abs_diff_eq(Vec3::new(-1.0, -1.0, 0.0), EPSILON));
+    assert!(projection
+        .transform_point3(Vec3::new(0.0, 0.0, -2.0))
+        .abs_diff_eq(Vec3::new(0.0, 0.0, 0.5), EPSILON));
+}
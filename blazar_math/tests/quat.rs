@@ -0,0 +1,151 @@
+use blazar_math::{Mat3, Quat, Vec3};
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+const EPSILON: f32 = 1e-5;
+
+#[test]
+fn identity() {
+    assert_eq!(Quat::default(), Quat::IDENTITY);
+    assert_eq!(
+        Quat::IDENTITY * Vec3::new(1.0, 2.0, 3.0),
+        Vec3::new(1.0, 2.0, 3.0)
+    );
+    let rotation = Quat::from_rotation_y(1.0);
+    assert_eq!(rotation * Quat::IDENTITY, rotation);
+    assert_eq!(Quat::IDENTITY * rotation, rotation);
+}
+
+#[test]
+fn axis_rotations() {
+    let cases = [
+        (Quat::from_rotation_x(FRAC_PI_2), Vec3::Y, Vec3::Z),
+        (Quat::from_rotation_y(FRAC_PI_2), Vec3::Z, Vec3::X),
+        (Quat::from_rotation_z(FRAC_PI_2), Vec3::X, Vec3::Y),
+        (Quat::from_axis_angle(-Vec3::Z, FRAC_PI_2), Vec3::Y, Vec3::X),
+    ];
+    for (rotation, from, to) in &cases {
+        assert!(rotation.is_normalized(EPSILON));
+        assert!((*rotation * *from).abs_diff_eq(*to, EPSILON));
+        assert!(rotation.rotate(*from).abs_diff_eq(*to, EPSILON));
+    }
+}
+
+#[test]
+fn composition() {
+    // The right-hand rotation is applied first.
+    let rotation = Quat::from_rotation_z(FRAC_PI_2) * Quat::from_rotation_x(FRAC_PI_2);
+    assert!((rotation * Vec3::Y).abs_diff_eq(Vec3::Z, EPSILON));
+    assert!((rotation * Vec3::X).abs_diff_eq(Vec3::Y, EPSILON));
+    let mut accumulated = Quat::IDENTITY;
+    for _ in 0..4 {
+        accumulated *= Quat::from_rotation_y(FRAC_PI_4);
+    }
+    assert!(accumulated.same_rotation(Quat::from_rotation_y(PI), EPSILON));
+    let euler = Quat::from_euler(0.3, 0.2, 0.1);
+    let expected =
+        Quat::from_rotation_y(0.3) * Quat::from_rotation_x(0.2) * Quat::from_rotation_z(0.1);
+    assert!(euler.abs_diff_eq(expected, EPSILON));
+}
+
+#[test]
+fn inverse() {
+    let rotation = Quat::from_axis_angle(Vec3::new(1.0, 2.0, 3.0).normalize_or_zero(), 1.2);
+    let vector = Vec3::new(-3.0, 0.5, 2.0);
+    assert!((rotation.conjugate() * (rotation * vector)).abs_diff_eq(vector, EPSILON));
+    assert!((rotation * rotation.conjugate()).abs_diff_eq(Quat::IDENTITY, EPSILON));
+    let scaled = Quat::from_xyzw(0.0, 0.0, 0.0, 2.0);
+    assert_eq!(scaled.inverse(), Some(Quat::from_xyzw(0.0, 0.0, 0.0, 0.5)));
+    assert_eq!(Quat::from_xyzw(0.0, 0.0, 0.0, 0.0).inverse(), None);
+}
+
+#[test]
+fn normalization() {
+    let quaternion = Quat::from_xyzw(0.0, 3.0, 0.0, 4.0);
+    assert_eq!(quaternion.length(), 5.0);
+    assert_eq!(quaternion.normalize(), Quat::from_xyzw(0.0, 0.6, 0.0, 0.8));
+    assert_eq!(
+        Quat::from_xyzw(0.0, 0.0, 0.0, 0.0).normalize(),
+        Quat::IDENTITY
+    );
+    assert!(!quaternion.is_normalized(EPSILON));
+}
+
+#[test]
+fn axis_angle_round_trip() {
+    let axis = Vec3::new(-1.0, 2.0, 0.5).normalize_or_zero();
+    for &angle in &[0.1, 1.0, 2.5, -1.0] {
+        let (actual_axis, actual_angle) = Quat::from_axis_angle(axis, angle).to_axis_angle();
+        let sign = angle.signum();
+        assert!(actual_axis.abs_diff_eq(axis * sign, EPSILON));
+        assert!((actual_angle - angle.abs()).abs() < EPSILON);
+    }
+    assert_eq!(Quat::IDENTITY.to_axis_angle(), (Vec3::X, 0.0));
+}
+
+#[test]
+fn rotation_arc() {
+    let pairs = [
+        (Vec3::X, Vec3::Y),
+        (Vec3::Z, Vec3::new(1.0, 1.0, 1.0).normalize_or_zero()),
+        (Vec3::Y, -Vec3::Y),
+        (Vec3::X, Vec3::X),
+    ];
+    for (from, to) in &pairs {
+        let rotation = Quat::from_rotation_arc(*from, *to);
+        assert!(rotation.is_normalized(EPSILON));
+        assert!((rotation * *from).abs_diff_eq(*to, EPSILON));
+    }
+}
+
+#[test]
+fn matrix_round_trip() {
+    let rotations = [
+        Quat::IDENTITY,
+        Quat::from_rotation_x(PI),
+        Quat::from_rotation_y(PI),
+        Quat::from_rotation_z(PI),
+        Quat::from_axis_angle(Vec3::new(1.0, -1.0, 2.0).normalize_or_zero(), 2.0),
+        Quat::from_euler(2.0, -1.0, 3.0),
+    ];
+    for rotation in &rotations {
+        let matrix = Mat3::from_quat(*rotation);
+        assert!(Quat::from_mat3(&matrix).same_rotation(*rotation, EPSILON));
+        let vector = Vec3::new(0.3, -2.0, 1.5);
+        assert!((matrix * vector).abs_diff_eq(*rotation * vector, EPSILON));
+    }
+}
+
+#[test]
+fn interpolation() {
+    let a = Quat::from_rotation_z(0.0);
+    let b = Quat::from_rotation_z(FRAC_PI_2);
+    assert!(a.slerp(b, 0.0).abs_diff_eq(a, EPSILON));
+    assert!(a.slerp(b, 1.0).abs_diff_eq(b, EPSILON));
+    assert!(a
+        .slerp(b, 0.5)
+        .abs_diff_eq(Quat::from_rotation_z(FRAC_PI_4), EPSILON));
+    assert!(a
+        .slerp(b, 0.25)
+        .abs_diff_eq(Quat::from_rotation_z(PI / 8.0), EPSILON));
+    // The shortest path is taken from either sign.
+    assert!(a
+        .slerp(-b, 0.5)
+        .same_rotation(Quat::from_rotation_z(FRAC_PI_4), EPSILON));
+    assert!(a
+        .nlerp(b, 0.5)
+        .abs_diff_eq(Quat::from_rotation_z(FRAC_PI_4), EPSILON));
+    assert!(a.nlerp(b, 0.3).is_normalized(EPSILON));
+    // Nearly identical rotations.
+    let c = Quat::from_rotation_z(1e-4);
+    assert!(a.slerp(c, 0.5).is_normalized(EPSILON));
+    assert!((a.angle_between(b) - FRAC_PI_2).abs() < EPSILON);
+    assert!(a.angle_between(-a) < EPSILON);
+}
+
+#[test]
+fn conversions() {
+    let array: [f32; 4] = Quat::from_xyzw(1.0, 2.0, 3.0, 4.0).into();
+    assert_eq!(array, [1.0, 2.0, 3.0, 4.0]);
+    assert_eq!(Quat::from(array), Quat::from_xyzw(1.0, 2.0, 3.0, 4.0));
+    assert_eq!(std::mem::size_of::<Quat>(), 16);
+}
@@ -0,0 +1,68 @@
+use blazar_math::{Quat, Transform, Vec3};
+use std::f32::consts::FRAC_PI_2;
+
+const EPSILON: f32 = 1e-5;
+
+#[test]
+fn constructors() {
+    assert_eq!(Transform::default(), Transform::IDENTITY);
+    let translation = Transform::from_translation(Vec3::X);
+    assert_eq!(translation.transform_point(Vec3::ZERO), Vec3::X);
+    assert_eq!(translation.transform_vector(Vec3::Y), Vec3::Y);
+    let scale = Transform::from_scale(Vec3::splat(2.0));
+    assert_eq!(scale.transform_point(Vec3::ONE), Vec3::splat(2.0));
+    let rotation = Transform::from_rotation(Quat::from_rotation_y(FRAC_PI_2));
+    assert!(rotation.forward().abs_diff_eq(-Vec3::X, EPSILON));
+    assert_eq!(Transform::IDENTITY.forward(), -Vec3::Z);
+}
+
+#[test]
+fn matrix_round_trip() {
+    let transform = Transform {
+        translation: Vec3::new(1.0, -2.0, 3.0),
+        rotation: Quat::from_euler(0.5, 1.0, -0.2),
+        scale: Vec3::new(0.5, 2.0, 1.5),
+    };
+    let point = Vec3::new(3.0, 1.0, -1.0);
+    let matrix = transform.matrix();
+    assert!(matrix
+        .transform_point3(point)
+        .abs_diff_eq(transform.transform_point(point), EPSILON));
+    let decomposed = Transform::from_matrix(&matrix);
+    assert!(decomposed
+        .translation
+        .abs_diff_eq(transform.translation, EPSILON));
+    assert!(decomposed
+        .rotation
+        .same_rotation(transform.rotation, EPSILON));
+    assert!(decomposed.scale.abs_diff_eq(transform.scale, EPSILON));
+}
+
+#[test]
+fn composition() {
+    let parent = Transform {
+        translation: Vec3::new(0.0, 5.0, 0.0),
+        rotation: Quat::from_rotation_z(FRAC_PI_2),
+        scale: Vec3::splat(2.0),
+    };
+    let child = Transform {
+        translation: Vec3::X,
+        rotation: Quat::from_rotation_x(0.3),
+        scale: Vec3::splat(0.5),
+    };
+    let point = Vec3::new(1.0, 2.0, 3.0);
+    let combined = parent * child;
+    let expected = parent.transform_point(child.transform_point(point));
+    assert!(combined
+        .transform_point(point)
+        .abs_diff_eq(expected, EPSILON));
+    assert!((parent.matrix() * child.matrix()).abs_diff_eq(&combined.matrix(), EPSILON));
+    let inverse = parent.inverse();
+    assert!(inverse
+        .transform_point(parent.transform_point(point))
+        .abs_diff_eq(point, EPSILON));
+    let identity = parent * inverse;
+    assert!(identity.translation.abs_diff_eq(Vec3::ZERO, EPSILON));
+    assert!(identity.rotation.same_rotation(Quat::IDENTITY, EPSILON));
+    assert!(identity.scale.abs_diff_eq(Vec3::ONE, EPSILON));
+}
@@ -0,0 +1,216 @@
+use blazar_math::{Vec2, Vec3, Vec4};
+use std::f32::consts::{FRAC_PI_2, PI};
+
+const EPSILON: f32 = 1e-6;
+
+#[test]
+fn constructors() {
+    assert_eq!(Vec2::new(1.0, 2.0), Vec2 { x: 1.0, y: 2.0 });
+    assert_eq!(Vec3::splat(3.0), Vec3::new(3.0, 3.0, 3.0));
+    assert_eq!(Vec4::ZERO, Vec4::default());
+    assert_eq!(Vec4::ONE, Vec4::splat(1.0));
+    assert_eq!(Vec3::X + Vec3::Y + Vec3::Z, Vec3::ONE);
+    assert_eq!(Vec4::X + Vec4::Y + Vec4::Z + Vec4::W, Vec4::ONE);
+    assert_eq!(Vec2::X + Vec2::Y, Vec2::ONE);
+}
+
+#[test]
+fn conversions() {
+    assert_eq!(Vec3::from([1.0, 2.0, 3.0]), Vec3::new(1.0, 2.0, 3.0));
+    let array: [f32; 4] = Vec4::new(1.0, 2.0, 3.0, 4.0).into();
+    assert_eq!(array, [1.0, 2.0, 3.0, 4.0]);
+    assert_eq!(Vec2::new(1.0, 2.0).to_array(), [1.0, 2.0]);
+    assert_eq!(Vec2::new(1.0, 2.0).extend(3.0), Vec3::new(1.0, 2.0, 3.0));
+    assert_eq!(
+        Vec3::new(1.0, 2.0, 3.0).extend(4.0),
+        Vec4::new(1.0, 2.0, 3.0, 4.0)
+    );
+    assert_eq!(Vec3::new(1.0, 2.0, 3.0).truncate(), Vec2::new(1.0, 2.0));
+    assert_eq!(
+        Vec4::new(1.0, 2.0, 3.0, 4.0).truncate(),
+        Vec3::new(1.0, 2.0, 3.0)
+    );
+    assert_eq!(
+        Vec4::new(2.0, 4.0, 6.0, 2.0).project(),
+        Vec3::new(1.0, 2.0, 3.0)
+    );
+}
+
+#[test]
+fn operators() {
+    let a = Vec3::new(1.0, 2.0, 3.0);
+    let b = Vec3::new(4.0, 5.0, 6.0);
+    assert_eq!(a + b, Vec3::new(5.0, 7.0, 9.0));
+    assert_eq!(b - a, Vec3::splat(3.0));
+    assert_eq!(a * b, Vec3::new(4.0, 10.0, 18.0));
+    assert_eq!(b / a, Vec3::new(4.0, 2.5, 2.0));
+    assert_eq!(a * 2.0, Vec3::new(2.0, 4.0, 6.0));
+    assert_eq!(2.0 * a, a * 2.0);
+    assert_eq!(a / 2.0, Vec3::new(0.5, 1.0, 1.5));
+    assert_eq!(6.0 / a, Vec3::new(6.0, 3.0, 2.0));
+    assert_eq!(a + 1.0, Vec3::new(2.0, 3.0, 4.0));
+    assert_eq!(1.0 - a, Vec3::new(0.0, -1.0, -2.0));
+    assert_eq!(-a, Vec3::new(-1.0, -2.0, -3.0));
+    let mut c = a;
+    c += b;
+    assert_eq!(c, a + b);
+    c -= b;
+    assert_eq!(c, a);
+    c *= 3.0;
+    assert_eq!(c, a * 3.0);
+    c /= a;
+    assert_eq!(c, Vec3::splat(3.0));
+    c -= 1.0;
+    assert_eq!(c, Vec3::splat(2.0));
+    let mut d = Vec4::ONE;
+    d += 1.0;
+    d *= Vec4::new(1.0, 2.0, 3.0, 4.0);
+    d /= 2.0;
+    assert_eq!(d, Vec4::new(1.0, 2.0, 3.0, 4.0));
+    let mut e = Vec2::new(1.0, 2.0);
+    e *= Vec2::new(3.0, 4.0);
+    assert_eq!(e, Vec2::new(3.0, 8.0));
+}
+
+#[test]
+fn indexing() {
+    let mut vector = Vec4::new(1.0, 2.0, 3.0, 4.0);
+    for index in 0..4 {
+        assert_eq!(vector[index], index as f32 + 1.0);
+    }
+    vector[2] = 7.0;
+    assert_eq!(vector.z, 7.0);
+    let mut vector = Vec2::ZERO;
+    vector[1] = 1.0;
+    assert_eq!(vector, Vec2::Y);
+    assert_eq!(Vec3::Z[2], 1.0);
+}
+
+#[test]
+#[should_panic]
+fn index_out_of_bounds() {
+    let _ = Vec3::ZERO[3];
+}
+
+#[test]
+fn products() {
+    let a = Vec3::new(1.0, 2.0, 3.0);
+    let b = Vec3::new(4.0, 5.0, 6.0);
+    assert_eq!(a.dot(b), 32.0);
+    assert_eq!(Vec4::ONE.dot(Vec4::new(1.0, 2.0, 3.0, 4.0)), 10.0);
+    assert_eq!(Vec3::X.cross(Vec3::Y), Vec3::Z);
+    assert_eq!(Vec3::Y.cross(Vec3::Z), Vec3::X);
+    assert_eq!(Vec3::Z.cross(Vec3::X), Vec3::Y);
+    assert_eq!(Vec3::Y.cross(Vec3::X), -Vec3::Z);
+    assert_eq!(a.cross(b).dot(a), 0.0);
+    assert_eq!(a.cross(a), Vec3::ZERO);
+    assert_eq!(Vec2::X.perp_dot(Vec2::Y), 1.0);
+    assert_eq!(Vec2::X.perp(), Vec2::Y);
+    assert_eq!(Vec2::new(2.0, 3.0).perp().dot(Vec2::new(2.0, 3.0)), 0.0);
+}
+
+#[test]
+fn lengths() {
+    let vector = Vec3::new(2.0, 3.0, 6.0);
+    assert_eq!(vector.length_squared(), 49.0);
+    assert_eq!(vector.length(), 7.0);
+    assert_eq!(Vec2::new(3.0, 4.0).length(), 5.0);
+    assert_eq!(Vec4::splat(1.0).length(), 2.0);
+    assert_eq!(Vec3::ZERO.distance(vector), 7.0);
+    let normalized = vector.try_normalize().unwrap();
+    assert!(normalized.abs_diff_eq(vector / 7.0, EPSILON));
+    assert!(normalized.is_normalized(EPSILON));
+    assert!(!vector.is_normalized(EPSILON));
+    assert_eq!(Vec3::ZERO.try_normalize(), None);
+    assert_eq!(Vec3::splat(f32::INFINITY).try_normalize(), None);
+    assert_eq!(Vec2::ZERO.normalize_or_zero(), Vec2::ZERO);
+    assert_eq!(Vec4::new(0.0, 0.0, 5.0, 0.0).normalize_or_zero(), Vec4::Z);
+}
+
+#[test]
+fn component_wise() {
+    let a = Vec4::new(1.0, -2.0, 3.5, -4.5);
+    let b = Vec4::new(0.0, 1.0, 4.0, -5.0);
+    assert_eq!(a.min(b), Vec4::new(0.0, -2.0, 3.5, -5.0));
+    assert_eq!(a.max(b), Vec4::new(1.0, 1.0, 4.0, -4.5));
+    assert_eq!(a.abs(), Vec4::new(1.0, 2.0, 3.5, 4.5));
+    assert_eq!(a.floor(), Vec4::new(1.0, -2.0, 3.0, -5.0));
+    assert_eq!(a.ceil(), Vec4::new(1.0, -2.0, 4.0, -4.0));
+    assert_eq!(a.round(), Vec4::new(1.0, -2.0, 4.0, -5.0));
+    assert_eq!(a.min_element(), -4.5);
+    assert_eq!(a.max_element(), 3.5);
+    assert_eq!(
+        a.clamp(Vec4::splat(-3.0), Vec4::splat(3.0)),
+        Vec4::new(1.0, -2.0, 3.0, -3.0)
+    );
+    assert_eq!(Vec2::new(2.0, 4.0).recip(), Vec2::new(0.5, 0.25));
+    assert!(a.is_finite());
+    assert!(!Vec3::new(0.0, f32::NAN, 0.0).is_finite());
+}
+
+#[test]
+fn interpolation() {
+    let a = Vec3::new(0.0, 10.0, -4.0);
+    let b = Vec3::new(10.0, 20.0, 4.0);
+    assert_eq!(a.lerp(b, 0.0), a);
+    assert_eq!(a.lerp(b, 1.0), b);
+    assert_eq!(a.lerp(b, 0.5), Vec3::new(5.0, 15.0, 0.0));
+    assert_eq!(a.lerp(b, 2.0), Vec3::new(20.0, 30.0, 12.0));
+}
+
+#[test]
+fn angles() {
+    assert!((Vec2::Y.angle() - FRAC_PI_2).abs() < EPSILON);
+    assert!((Vec2::new(-1.0, 0.0).angle() - PI).abs() < EPSILON);
+    assert!(Vec2::from_angle(FRAC_PI_2).abs_diff_eq(Vec2::Y, EPSILON));
+    assert!(Vec2::X.rotate(FRAC_PI_2).abs_diff_eq(Vec2::Y, EPSILON));
+    assert!(Vec2::new(1.0, 1.0)
+        .rotate(PI)
+        .abs_diff_eq(Vec2::new(-1.0, -1.0), EPSILON));
+    assert!((Vec3::X.angle_between(Vec3::Y) - FRAC_PI_2).abs() < EPSILON);
+    assert!((Vec3::X.angle_between(-Vec3::X) - PI).abs() < EPSILON);
+    assert_eq!(Vec3::X.angle_between(Vec3::X * 3.0), 0.0);
+}
+
+#[test]
+fn geometry() {
+    for vector in &[
+        Vec3::X,
+        Vec3::Y,
+        Vec3::Z,
+        Vec3::new(1.0, 2.0, 3.0),
+        -Vec3::Y,
+    ] {
+        let orthogonal = vector.any_orthogonal();
+        assert!(orthogonal.is_normalized(EPSILON));
+        assert!(orthogonal.dot(*vector).abs() < EPSILON);
+    }
+    let incoming = Vec3::new(1.0, -1.0, 0.0);
+    assert_eq!(incoming.reflect(Vec3::Y), Vec3::new(1.0, 1.0, 0.0));
+}
+
+#[test]
+fn swizzles() {
+    let vector = Vec4::new(1.0, 2.0, 3.0, 4.0);
+    assert_eq!(vector.xy(), Vec2::new(1.0, 2.0));
+    assert_eq!(vector.wz(), Vec2::new(4.0, 3.0));
+    assert_eq!(vector.xyz(), Vec3::new(1.0, 2.0, 3.0));
+    assert_eq!(vector.zyx(), Vec3::new(3.0, 2.0, 1.0));
+    assert_eq!(vector.wwxy(), Vec4::new(4.0, 4.0, 1.0, 2.0));
+    assert_eq!(vector.xyzw(), vector);
+    let vector = Vec3::new(1.0, 2.0, 3.0);
+    assert_eq!(vector.zx(), Vec2::new(3.0, 1.0));
+    assert_eq!(vector.yyy(), Vec3::splat(2.0));
+    assert_eq!(vector.xyzx(), Vec4::new(1.0, 2.0, 3.0, 1.0));
+    let vector = Vec2::new(1.0, 2.0);
+    assert_eq!(vector.yx(), Vec2::new(2.0, 1.0));
+    assert_eq!(vector.xyx(), Vec3::new(1.0, 2.0, 1.0));
+    assert_eq!(vector.yyxx(), Vec4::new(2.0, 2.0, 1.0, 1.0));
+}
+
+#[test]
+fn layout() {
+    assert_eq!(std::mem::size_of::<Vec2>(), 8);
+    assert_eq!(std::mem::size_of::<Vec3>(), 12);
+    assert_eq!(std::mem::size_of::<Vec4>(), 16);
+}
@@ -0,0 +1,200 @@
+//! Keyframe animations of node transforms.
+
+use crate::scene::Node;
+
+/// Interpolation between keyframes.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Interpolation {
+    Step,
+    /// Linear interpolation, spherical for rotations.
+    Linear,
+    /// Cubic Hermite spline, each keyframe holding an in-tangent, a value and an
+    /// out-tangent.
+    CubicSpline,
+}
+
+/// Animated properties of nodes.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Property {
+    Translation,
+    Rotation,
+    Scale,
+    /// Morph target weights.
+    Weights,
+}
+
+/// Keyframes of an animated property.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnimationSampler {
+    /// Increasing keyframe times in seconds.
+    pub times: Vec<f32>,
+    /// Keyframe values, tightly packed.
+    pub values: Vec<f32>,
+    pub interpolation: Interpolation,
+}
+
+impl AnimationSampler {
+    /// Returns the interpolated value at `time`, made of `width` components.
+    ///
+    /// Times out of the keyframe range are clamped. Rotations are normalized
+    /// quaternions.
+    pub fn sample(&self, time: f32, width: usize, property: Property) -> Vec<f32> {
+        let count = self.times.len();
+        if count == 0 || width == 0 {
+            return vec![0.0; width];
+        }
+        let stride = match self.interpolation {
+            Interpolation::CubicSpline => width * 3,
+            _ => width,
+        };
+        let value_offset = match self.interpolation {
+            Interpolation::CubicSpline => width,
+            _ => 0,
+        };
+        let keyframe = |index: usize, offset: usize| {
+            let start = index * stride + offset;
+            self.values
+                .get(start..start + width)
+                .map(|values| values.to_vec())
+                .unwrap_or_else(|| vec![0.0; width])
+        };
+        let next = self.times.iter().position(|&keyframe| keyframe > time);
+        let (previous, next) = match next {
+            Some(0) => return keyframe(0, value_offset),
+            Some(next) => (next - 1, next),
+            None => return keyframe(count - 1, value_offset),
+        };
+        let duration = self.times[next] - self.times[previous];
+        let t = if duration > 0.0 {
+            (time - self.times[previous]) / duration
+        } else {
+            0.0
+        };
+        let mut result = match self.interpolation {
+            Interpolation::Step => keyframe(previous, 0),
+            Interpolation::Linear => {
+                let (a, b) = (keyframe(previous, 0), keyframe(next, 0));
+                if property == Property::Rotation && width == 4 {
+                    slerp(&a, &b, t)
+                } else {
+                    a.iter().zip(&b).map(|(a, b)| a + (b - a) * t).collect()
+                }
+            }
+            Interpolation::CubicSpline => {
+                let a = keyframe(previous, width);
+                let out_tangent = keyframe(previous, width * 2);
+                let in_tangent = keyframe(next, 0);
+                let b = keyframe(next, width);
+                let (t2, t3) = (t * t, t * t * t);
+                (0..width)
+                    .map(|i| {
+                        (2.0 * t3 - 3.0 * t2 + 1.0) * a[i]
+                            + (t3 - 2.0 * t2 + t) * duration * out_tangent[i]
+                            + (-2.0 * t3 + 3.0 * t2) * b[i]
+                            + (t3 - t2) * duration * in_tangent[i]
+                    })
+                    .collect()
+            }
+        };
+        if property == Property::Rotation {
+            normalize(&mut result);
+        }
+        result
+    }
+}
+
+/// Binds a sampler to a node property.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Channel {
+    /// Index of the node in the model.
+    pub node: usize,
+    pub property: Property,
+    /// Index of the sampler in the animation.
+    pub sampler: usize,
+}
+
+/// Set of animated node properties.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Animation {
+    pub name: Option<String>,
+    pub channels: Vec<Channel>,
+    pub samplers: Vec<AnimationSampler>,
+}
+
+impl Animation {
+    /// Returns the time of the last keyframe.
+    pub fn duration(&self) -> f32 {
+        self.samplers
+            .iter()
+            .filter_map(|sampler| sampler.times.last())
+            .fold(0.0, |duration, &time| duration.max(time))
+    }
+
+    /// Sets the animated properties of nodes to their value at `time`.
+    pub fn apply(&self, time: f32, nodes: &mut [Node]) {
+        for channel in &self.channels {
+            let (sampler, node) = match (
+                self.samplers.get(channel.sampler),
+                nodes.get_mut(channel.node),
+            ) {
+                (Some(sampler), Some(node)) => (sampler, node),
+                _ => continue,
+            };
+            let transform = &mut node.transform;
+            match channel.property {
+                Property::Translation => {
+                    let value = sampler.sample(time, 3, channel.property);
+                    transform.translation.copy_from_slice(&value);
+                }
+                Property::Rotation => {
+                    let value = sampler.sample(time, 4, channel.property);
+                    transform.rotation.copy_from_slice(&value);
+                }
+                Property::Scale => {
+                    let value = sampler.sample(time, 3, channel.property);
+                    transform.scale.copy_from_slice(&value);
+                }
+                Property::Weights => {
+                    let width = node.weights.len();
+                    node.weights = sampler.sample(time, width, channel.property);
+                }
+            }
+        }
+    }
+}
+
+/// Spherically interpolates two quaternions along the shortest path.
+fn slerp(a: &[f32], b: &[f32], t: f32) -> Vec<f32> {
+    let mut cosine: f32 = a.iter().zip(b).map(|(a, b)| a * b).sum();
+    let sign = if cosine < 0.0 {
+        cosine = -cosine;
+        -1.0
+    } else {
+        1.0
+    };
+    let (weight_a, weight_b) = if cosine > 0.9995 {
+        (1.0 - t, t)
+    } else {
+        let angle = cosine.acos();
+        let sine = angle.sin();
+        (((1.0 - t) * angle).sin() / sine, (t * angle).sin() / sine)
+    };
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| a * weight_a + b * weight_b * sign)
+        .collect()
+}
+
+/// Normalizes a quaternion, leaving null ones unchanged.
+fn normalize(quaternion: &mut [f32]) {
+    let length = quaternion
+        .iter()
+        .map(|value| value * value)
+        .sum::<f32>()
+        .sqrt();
+    if length > 0.0 {
+        for value in quaternion {
+            *value /= length;
+        }
+    }
+}
@@ -0,0 +1,35 @@
+//! Base64 decoding.
+
+/// Decodes standard or URL-safe base64, ignoring whitespace and accepting missing
+/// padding.
+pub(crate) fn decode(text: &str) -> Option<Vec<u8>> {
+    let mut data = Vec::with_capacity(text.len() / 4 * 3);
+    let mut accumulator = 0u32;
+    let mut bits = 0;
+    let mut padding = false;
+    for byte in text.bytes() {
+        let value = match byte {
+            b'A'..=b'Z' => byte - b'A',
+            b'a'..=b'z' => byte - b'a' + 26,
+            b'0'..=b'9' => byte - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            b'=' => {
+                padding = true;
+                continue;
+            }
+            b' ' | b'\t' | b'\n' | b'\r' => continue,
+            _ => return None,
+        };
+        if padding {
+            return None;
+        }
+        accumulator = accumulator << 6 | u32::from(value);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            data.push((accumulator >> bits) as u8);
+        }
+    }
+    Some(data)
+}
@@ -0,0 +1,950 @@
+//! glTF 2.0 loading, from `.gltf` files and `.glb` binaries.
+//!
+//! Buffers and images may be embedded as base64 data URIs, stored in the binary chunk
+//! of a `.glb` file, or stored in files relative to the model.
+
+use crate::{
+    animation::{Animation, AnimationSampler, Channel, Interpolation, Property},
+    base64,
+    json::{self, Value},
+    material::{AlphaMode, EncodedImage, Filter, Material, Sampler, Texture, TextureRef, Wrap},
+    mesh::{Mesh, Primitive, Topology, Vertex},
+    scene::{Matrix, Model, Node, Scene, Skin, Transform},
+    LoadModelError, Result,
+};
+use std::{fs, path::Path};
+
+/// Binary glTF signature.
+const MAGIC: [u8; 4] = *b"glTF";
+
+/// Size of the binary header.
+const HEADER_SIZE: usize = 12;
+
+/// Type of the JSON chunk.
+const CHUNK_JSON: u32 = 0x4e4f_534a;
+
+/// Type of the binary chunk.
+const CHUNK_BIN: u32 = 0x004e_4942;
+
+const BYTE: u64 = 5120;
+const UNSIGNED_BYTE: u64 = 5121;
+const SHORT: u64 = 5122;
+const UNSIGNED_SHORT: u64 = 5123;
+const UNSIGNED_INT: u64 = 5125;
+const FLOAT: u64 = 5126;
+
+const NEAREST: u64 = 9728;
+const LINEAR: u64 = 9729;
+const NEAREST_MIPMAP_NEAREST: u64 = 9984;
+const LINEAR_MIPMAP_NEAREST: u64 = 9985;
+const NEAREST_MIPMAP_LINEAR: u64 = 9986;
+const LINEAR_MIPMAP_LINEAR: u64 = 9987;
+const CLAMP_TO_EDGE: u64 = 33071;
+const MIRRORED_REPEAT: u64 = 33648;
+
+/// Maximum number of components of an accessor.
+const MAX_ACCESSOR_VALUES: usize = 1 << 28;
+
+/// Returns `true` if the data starts with the binary glTF signature.
+pub fn is_glb(data: &[u8]) -> bool {
+    data.starts_with(&MAGIC)
+}
+
+/// Loads a `.gltf` or `.glb` model.
+///
+/// External files are resolved relatively to `directory`; they are rejected without
+/// one.
+pub fn load(data: &[u8], directory: Option<&Path>) -> Result<Model> {
+    let (text, binary) = if is_glb(data) {
+        split_glb(data)?
+    } else {
+        (data, None)
+    };
+    let text = std::str::from_utf8(text).map_err(|_| invalid("Invalid UTF-8 in JSON"))?;
+    let root =
+        json::parse(text.trim_start_matches('\u{feff}')).map_err(LoadModelError::InvalidData)?;
+    let version = root.get("asset").get("version").as_str().unwrap_or("");
+    if !version.starts_with("2.") {
+        return Err(LoadModelError::Unsupported(format!(
+            "glTF version {:?}",
+            version
+        )));
+    }
+    if let Some(extension) = root.get("extensionsRequired").elements().first() {
+        return Err(LoadModelError::Unsupported(format!(
+            "glTF extension {}",
+            extension.as_str().unwrap_or("")
+        )));
+    }
+    let mut buffers = Vec::new();
+    for (index, buffer) in root.get("buffers").elements().iter().enumerate() {
+        let length = required_usize(buffer.get("byteLength"), "buffer length")?;
+        let mut data = match buffer.get("uri").as_str() {
+            Some(uri) => read_uri(uri, directory)?.1,
+            None if index == 0 => binary
+                .map(<[u8]>::to_vec)
+                .ok_or_else(|| invalid("Missing binary chunk"))?,
+            None => return Err(invalid("Missing buffer URI")),
+        };
+        if data.len() < length {
+            return Err(invalid("Truncated buffer"));
+        }
+        data.truncate(length);
+        buffers.push(data);
+    }
+    let document = Document {
+        root: &root,
+        buffers,
+    };
+    let mut model = Model {
+        images: document.images(directory)?,
+        ..Model::default()
+    };
+    model.textures = document.textures(model.images.len())?;
+    model.materials = document.materials(model.textures.len())?;
+    model.meshes = document.meshes(model.materials.len())?;
+    model.nodes = document.nodes(&model.meshes)?;
+    model.skins = document.skins(model.nodes.len())?;
+    for node in &model.nodes {
+        check_index(node.skin, model.skins.len(), "skin")?;
+    }
+    model.animations = document.animations(model.nodes.len())?;
+    for scene in root.get("scenes").elements() {
+        model.scenes.push(Scene {
+            name: name(scene),
+            nodes: indices(scene.get("nodes"), model.nodes.len(), "node")?,
+        });
+    }
+    model.scene = optional_index(root.get("scene"), model.scenes.len(), "scene")?;
+    Ok(model)
+}
+
+/// Returns the JSON and binary chunks of a `.glb` file.
+fn split_glb(data: &[u8]) -> Result<(&[u8], Option<&[u8]>)> {
+    if data.len() < HEADER_SIZE || u32_at(data, 4) != 2 {
+        return Err(invalid("Invalid binary glTF header"));
+    }
+    let length = (u32_at(data, 8) as usize).min(data.len());
+    let mut offset = HEADER_SIZE;
+    let mut chunks = Vec::new();
+    while offset + 8 <= length {
+        let chunk_length = u32_at(data, offset) as usize;
+        let chunk_type = u32_at(data, offset + 4);
+        let chunk = data
+            .get(offset + 8..offset + 8 + chunk_length)
+            .ok_or_else(|| invalid("Truncated chunk"))?;
+        chunks.push((chunk_type, chunk));
+        offset += 8 + chunk_length;
+    }
+    match chunks.as_slice() {
+        [(CHUNK_JSON, text), rest @ ..] => {
+            let binary = rest
+                .iter()
+                .find(|(chunk_type, _)| *chunk_type == CHUNK_BIN)
+                .map(|(_, chunk)| *chunk);
+            Ok((text, binary))
+        }
+        _ => Err(invalid("Missing JSON chunk")),
+    }
+}
+
+/// Parsed JSON and loaded buffers.
+struct Document<'a> {
+    root: &'a Value,
+    buffers: Vec<Vec<u8>>,
+}
+
+impl<'a> Document<'a> {
+    /// Returns the data of a buffer view and its byte stride.
+    fn buffer_view(&self, index: usize) -> Result<(&[u8], Option<usize>)> {
+        let view = self
+            .root
+            .get("bufferViews")
+            .elements()
+            .get(index)
+            .ok_or_else(|| invalid("Invalid buffer view index"))?;
+        let buffer = required_usize(view.get("buffer"), "buffer view buffer")?;
+        let offset = view.get("byteOffset").as_usize().unwrap_or(0);
+        let length = required_usize(view.get("byteLength"), "buffer view length")?;
+        let data = self
+            .buffers
+            .get(buffer)
+            .and_then(|data| data.get(offset..offset.checked_add(length)?))
+            .ok_or_else(|| invalid("Buffer view out of bounds"))?;
+        Ok((data, view.get("byteStride").as_usize()))
+    }
+
+    /// Reads the elements of an accessor, returning their components and the number of
+    /// components per element.
+    ///
+    /// Normalized integers are converted to `[0, 1]` or `[-1, 1]`.
+    fn accessor<T: Copy>(&self, index: usize, convert: fn(f64) -> T) -> Result<(Vec<T>, usize)> {
+        let accessor = self
+            .root
+            .get("accessors")
+            .elements()
+            .get(index)
+            .ok_or_else(|| invalid("Invalid accessor index"))?;
+        let count = required_usize(accessor.get("count"), "accessor count")?;
+        let component_type = accessor.get("componentType").as_f64().unwrap_or(0.0) as u64;
+        let normalized = accessor.get("normalized").as_bool().unwrap_or(false);
+        let (rows, columns) = match accessor.get("type").as_str() {
+            Some("SCALAR") => (1, 1),
+            Some("VEC2") => (2, 1),
+            Some("VEC3") => (3, 1),
+            Some("VEC4") => (4, 1),
+            Some("MAT2") => (2, 2),
+            Some("MAT3") => (3, 3),
+            Some("MAT4") => (4, 4),
+            _ => return Err(invalid("Invalid accessor type")),
+        };
+        let width = rows * columns;
+        let component = Component::new(component_type, normalized)?;
+        // Matrix columns are aligned to 4 bytes.
+        let column_size = if columns > 1 {
+            (rows * component.size + 3) & !3
+        } else {
+            rows * component.size
+        };
+        let value_count = count
+            .checked_mul(width)
+            .filter(|&value_count| value_count <= MAX_ACCESSOR_VALUES)
+            .ok_or_else(|| invalid("Accessor too large"))?;
+        let view = match accessor.get("bufferView").as_usize() {
+            Some(view) => {
+                let (data, stride) = self.buffer_view(view)?;
+                let offset = accessor.get("byteOffset").as_usize().unwrap_or(0);
+                let data = data.get(offset..).unwrap_or(&[]);
+                let stride = stride.unwrap_or(column_size * columns);
+                // Checks the bounds before allocating the values.
+                component.check_elements(data, stride, (rows, columns, column_size), count)?;
+                Some((data, stride))
+            }
+            None => None,
+        };
+        let mut values = vec![convert(0.0); value_count];
+        if let Some((data, stride)) = view {
+            component.read_elements(
+                data,
+                stride,
+                (rows, columns, column_size),
+                count,
+                |index, value| values[index] = convert(value),
+            )?;
+        }
+        let sparse = accessor.get("sparse");
+        if !sparse.is_null() {
+            let sparse_count = required_usize(sparse.get("count"), "sparse count")?;
+            if sparse_count > count {
+                return Err(invalid("Sparse count out of bounds"));
+            }
+            let indices = sparse.get("indices");
+            let (data, _) =
+                self.buffer_view(required_usize(indices.get("bufferView"), "sparse indices")?)?;
+            let offset = indices.get("byteOffset").as_usize().unwrap_or(0);
+            let index_component = Component::new(
+                indices.get("componentType").as_f64().unwrap_or(0.0) as u64,
+                false,
+            )?;
+            let index_data = data.get(offset..).unwrap_or(&[]);
+            let index_layout = (1, 1, index_component.size);
+            let sparse_values = sparse.get("values");
+            let (data, _) = self.buffer_view(required_usize(
+                sparse_values.get("bufferView"),
+                "sparse values",
+            )?)?;
+            let offset = sparse_values.get("byteOffset").as_usize().unwrap_or(0);
+            let value_data = data.get(offset..).unwrap_or(&[]);
+            let value_layout = (rows, columns, column_size);
+            index_component.check_elements(
+                index_data,
+                index_component.size,
+                index_layout,
+                sparse_count,
+            )?;
+            component.check_elements(
+                value_data,
+                column_size * columns,
+                value_layout,
+                sparse_count,
+            )?;
+            let mut targets = vec![0; sparse_count];
+            index_component.read_elements(
+                index_data,
+                index_component.size,
+                index_layout,
+                sparse_count,
+                |index, value| targets[index] = value as usize,
+            )?;
+            let mut replacements = vec![0.0; sparse_count * width];
+            component.read_elements(
+                value_data,
+                column_size * columns,
+                value_layout,
+                sparse_count,
+                |index, value| replacements[index] = value,
+            )?;
+            for (target, replacement) in targets.iter().zip(replacements.chunks(width)) {
+                let element = values
+                    .chunks_mut(width)
+                    .nth(*target)
+                    .ok_or_else(|| invalid("Sparse index out of bounds"))?;
+                for (value, replacement) in element.iter_mut().zip(replacement) {
+                    *value = convert(*replacement);
+                }
+            }
+        }
+        Ok((values, width))
+    }
+
+    /// Reads a float accessor of a given width.
+    fn floats(&self, index: &Value, width: usize) -> Result<Option<Vec<f32>>> {
+        let index = match index.as_usize() {
+            Some(index) => index,
+            None => return Ok(None),
+        };
+        let (values, actual_width) = self.accessor(index, |value| value as f32)?;
+        if actual_width != width {
+            return Err(invalid("Unexpected accessor type"));
+        }
+        Ok(Some(values))
+    }
+
+    fn images(&self, directory: Option<&Path>) -> Result<Vec<EncodedImage>> {
+        let mut images = Vec::new();
+        for image in self.root.get("images").elements() {
+            let mut mime_type = image.get("mimeType").as_str().map(String::from);
+            let data = match image.get("uri").as_str() {
+                Some(uri) => {
+                    let (uri_mime_type, data) = read_uri(uri, directory)?;
+                    mime_type = mime_type.or(uri_mime_type);
+                    data
+                }
+                None => {
+                    let view = required_usize(image.get("bufferView"), "image source")?;
+                    self.buffer_view(view)?.0.to_vec()
+                }
+            };
+            images.push(EncodedImage {
+                name: name(image),
+                mime_type,
+                data,
+            });
+        }
+        Ok(images)
+    }
+
+    fn textures(&self, image_count: usize) -> Result<Vec<Texture>> {
+        let samplers = self.root.get("samplers").elements();
+        let mut textures = Vec::new();
+        for texture in self.root.get("textures").elements() {
+            let mut source = texture.get("source");
+            if source.is_null() {
+                // Images in other formats are provided by extensions.
+                if let Value::Object(extensions) = texture.get("extensions") {
+                    if let Some(extension) =
+                        extensions.values().find(|e| !e.get("source").is_null())
+                    {
+                        source = extension.get("source");
+                    }
+                }
+            }
+            let image = required_usize(source, "texture source")?;
+            check_index(Some(image), image_count, "image")?;
+            let sampler = match optional_index(texture.get("sampler"), samplers.len(), "sampler")? {
+                Some(index) => sampler(&samplers[index]),
+                None => Sampler::default(),
+            };
+            textures.push(Texture { image, sampler });
+        }
+        Ok(textures)
+    }
+
+    fn materials(&self, texture_count: usize) -> Result<Vec<Material>> {
+        let mut materials = Vec::new();
+        for material in self.root.get("materials").elements() {
+            let pbr = material.get("pbrMetallicRoughness");
+            let default = Material::default();
+            let base_color =
+                floats::<[f32; 4]>(pbr.get("baseColorFactor")).unwrap_or(default.base_color);
+            let emissive =
+                floats::<[f32; 3]>(material.get("emissiveFactor")).unwrap_or(default.emissive);
+            let cutoff = material.get("alphaCutoff").as_f64().unwrap_or(0.5) as f32;
+            let alpha_mode = match material.get("alphaMode").as_str() {
+                None | Some("OPAQUE") => AlphaMode::Opaque,
+                Some("MASK") => AlphaMode::Mask(cutoff),
+                Some("BLEND") => AlphaMode::Blend,
+                Some(_) => return Err(invalid("Invalid alpha mode")),
+            };
+            let normal_texture =
+                texture_ref(material.get("normalTexture"), "scale", texture_count)?;
+            let occlusion_texture =
+                texture_ref(material.get("occlusionTexture"), "strength", texture_count)?;
+            materials.push(Material {
+                name: name(material),
+                base_color,
+                base_color_texture: texture_ref(pbr.get("baseColorTexture"), "", texture_count)?,
+                metallic: pbr
+                    .get("metallicFactor")
+                    .as_f64()
+                    .map_or(default.metallic, |v| v as f32),
+                roughness: pbr
+                    .get("roughnessFactor")
+                    .as_f64()
+                    .map_or(default.roughness, |v| v as f32),
+                metallic_roughness_texture: texture_ref(
+                    pbr.get("metallicRoughnessTexture"),
+                    "",
+                    texture_count,
+                )?,
+                normal_texture,
+                occlusion_texture,
+                emissive,
+                emissive_texture: texture_ref(material.get("emissiveTexture"), "", texture_count)?,
+                alpha_mode,
+                double_sided: material.get("doubleSided").as_bool().unwrap_or(false),
+            });
+        }
+        Ok(materials)
+    }
+
+    fn meshes(&self, material_count: usize) -> Result<Vec<Mesh>> {
+        let mut meshes = Vec::new();
+        for mesh in self.root.get("meshes").elements() {
+            let mut primitives = Vec::new();
+            for primitive in mesh.get("primitives").elements() {
+                primitives.push(self.primitive(primitive, material_count)?);
+            }
+            meshes.push(Mesh {
+                name: name(mesh),
+                primitives,
+            });
+        }
+        Ok(meshes)
+    }
+
+    fn primitive(&self, primitive: &Value, material_count: usize) -> Result<Primitive> {
+        let attributes = primitive.get("attributes");
+        let positions = self
+            .floats(attributes.get("POSITION"), 3)?
+            .ok_or_else(|| invalid("Missing primitive positions"))?;
+        let mut vertices = vec![Vertex::default(); positions.len() / 3];
+        for (vertex, position) in vertices.iter_mut().zip(positions.chunks(3)) {
+            vertex.position.copy_from_slice(position);
+        }
+        let normals = self.floats(attributes.get("NORMAL"), 3)?;
+        let tangents = self.floats(attributes.get("TANGENT"), 4)?;
+        let tex_coords = self.floats(attributes.get("TEXCOORD_0"), 2)?;
+        let has_normals = normals.is_some();
+        let has_tangents = tangents.is_some();
+        let has_tex_coords = tex_coords.is_some();
+        let mut set = |values: Option<Vec<f32>>,
+                       width: usize,
+                       field: fn(&mut Vertex) -> &mut [f32]|
+         -> Result<()> {
+            if let Some(values) = values {
+                if values.len() != vertices.len() * width {
+                    return Err(invalid("Mismatched attribute count"));
+                }
+                for (vertex, value) in vertices.iter_mut().zip(values.chunks(width)) {
+                    field(vertex).copy_from_slice(value);
+                }
+            }
+            Ok(())
+        };
+        set(normals, 3, |vertex| &mut vertex.normal)?;
+        set(tangents, 4, |vertex| &mut vertex.tangent)?;
+        set(tex_coords, 2, |vertex| &mut vertex.tex_coord)?;
+        set(self.floats(attributes.get("WEIGHTS_0"), 4)?, 4, |vertex| {
+            &mut vertex.weights
+        })?;
+        if let Some(index) = attributes.get("COLOR_0").as_usize() {
+            let (colors, width) = self.accessor(index, |value| value as f32)?;
+            if !matches!(width, 3 | 4) || colors.len() != vertices.len() * width {
+                return Err(invalid("Invalid vertex colors"));
+            }
+            for (vertex, color) in vertices.iter_mut().zip(colors.chunks(width)) {
+                vertex.color[..width].copy_from_slice(color);
+            }
+        }
+        if let Some(index) = attributes.get("JOINTS_0").as_usize() {
+            let (joints, width) = self.accessor(index, |value| value as u16)?;
+            if width != 4 || joints.len() != vertices.len() * 4 {
+                return Err(invalid("Invalid vertex joints"));
+            }
+            for (vertex, joints) in vertices.iter_mut().zip(joints.chunks(4)) {
+                vertex.joints.copy_from_slice(joints);
+            }
+        }
+        let indices = match primitive.get("indices").as_usize() {
+            Some(index) => self.accessor(index, |value| value as u32)?.0,
+            None => (0..vertices.len() as u32).collect(),
+        };
+        if indices
+            .iter()
+            .any(|&index| index as usize >= vertices.len())
+        {
+            return Err(invalid("Vertex index out of bounds"));
+        }
+        let (topology, indices) = match primitive.get("mode").as_usize().unwrap_or(4) {
+            0 => (Topology::Points, indices),
+            1 => (Topology::Lines, truncate(indices, 2)),
+            2 => (Topology::Lines, line_strip(&indices, true)),
+            3 => (Topology::Lines, line_strip(&indices, false)),
+            4 => (Topology::Triangles, truncate(indices, 3)),
+            5 => (Topology::Triangles, triangle_strip(&indices)),
+            6 => (Topology::Triangles, triangle_fan(&indices)),
+            _ => return Err(invalid("Invalid primitive mode")),
+        };
+        let mut primitive = Primitive {
+            topology,
+            vertices,
+            indices,
+            material: optional_index(primitive.get("material"), material_count, "material")?,
+        };
+        if !has_normals {
+            primitive.generate_flat_normals();
+        }
+        if !has_tangents && has_tex_coords {
+            primitive.generate_tangents();
+        }
+        Ok(primitive)
+    }
+
+    fn nodes(&self, meshes: &[Mesh]) -> Result<Vec<Node>> {
+        let nodes = self.root.get("nodes").elements();
+        let mut result = Vec::new();
+        for node in nodes {
+            let transform = match floats::<[f32; 16]>(node.get("matrix")) {
+                Some(matrix) => {
+                    let mut columns: Matrix = [[0.0; 4]; 4];
+                    for (column, values) in columns.iter_mut().zip(matrix.chunks(4)) {
+                        column.copy_from_slice(values);
+                    }
+                    Transform::from_matrix(&columns)
+                }
+                None => {
+                    let default = Transform::default();
+                    Transform {
+                        translation: floats(node.get("translation")).unwrap_or(default.translation),
+                        rotation: floats(node.get("rotation")).unwrap_or(default.rotation),
+                        scale: floats(node.get("scale")).unwrap_or(default.scale),
+                    }
+                }
+            };
+            let mesh = optional_index(node.get("mesh"), meshes.len(), "mesh")?;
+            let weights = match node.get("weights") {
+                Value::Array(weights) => weights
+                    .iter()
+                    .map(|w| w.as_f64().unwrap_or(0.0) as f32)
+                    .collect(),
+                _ => mesh
+                    .map(|mesh| &self.root.get("meshes").elements()[mesh])
+                    .map(|mesh| {
+                        mesh.get("weights")
+                            .elements()
+                            .iter()
+                            .map(|w| w.as_f64().unwrap_or(0.0) as f32)
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            };
+            result.push(Node {
+                name: name(node),
+                transform,
+                children: indices(node.get("children"), nodes.len(), "node")?,
+                mesh,
+                skin: node.get("skin").as_usize(),
+                weights,
+            });
+        }
+        Ok(result)
+    }
+
+    fn skins(&self, node_count: usize) -> Result<Vec<Skin>> {
+        let mut skins = Vec::new();
+        for skin in self.root.get("skins").elements() {
+            let joints = indices(skin.get("joints"), node_count, "joint")?;
+            let inverse_bind_matrices = match self.floats(skin.get("inverseBindMatrices"), 16)? {
+                Some(values) => {
+                    if values.len() < joints.len() * 16 {
+                        return Err(invalid("Missing inverse bind matrices"));
+                    }
+                    values
+                        .chunks(16)
+                        .map(|values| {
+                            let mut matrix: Matrix = [[0.0; 4]; 4];
+                            for (column, values) in matrix.iter_mut().zip(values.chunks(4)) {
+                                column.copy_from_slice(values);
+                            }
+                            matrix
+                        })
+                        .collect()
+                }
+                None => Vec::new(),
+            };
+            skins.push(Skin {
+                name: name(skin),
+                joints,
+                inverse_bind_matrices,
+                skeleton: optional_index(skin.get("skeleton"), node_count, "skeleton")?,
+            });
+        }
+        Ok(skins)
+    }
+
+    fn animations(&self, node_count: usize) -> Result<Vec<Animation>> {
+        let mut animations = Vec::new();
+        for animation in self.root.get("animations").elements() {
+            let mut samplers = Vec::new();
+            for sampler in animation.get("samplers").elements() {
+                let times = self
+                    .floats(sampler.get("input"), 1)?
+                    .ok_or_else(|| invalid("Missing animation input"))?;
+                let output = required_usize(sampler.get("output"), "animation output")?;
+                let (values, _) = self.accessor(output, |value| value as f32)?;
+                let interpolation = match sampler.get("interpolation").as_str() {
+                    None | Some("LINEAR") => Interpolation::Linear,
+                    Some("STEP") => Interpolation::Step,
+                    Some("CUBICSPLINE") => Interpolation::CubicSpline,
+                    Some(_) => return Err(invalid("Invalid interpolation")),
+                };
+                samplers.push(AnimationSampler {
+                    times,
+                    values,
+                    interpolation,
+                });
+            }
+            let mut channels = Vec::new();
+            for channel in animation.get("channels").elements() {
+                let target = channel.get("target");
+                // Channels targeting extensions are skipped.
+                let node = match optional_index(target.get("node"), node_count, "node")? {
+                    Some(node) => node,
+                    None => continue,
+                };
+                let property = match target.get("path").as_str() {
+                    Some("translation") => Property::Translation,
+                    Some("rotation") => Property::Rotation,
+                    Some("scale") => Property::Scale,
+                    Some("weights") => Property::Weights,
+                    _ => continue,
+                };
+                let sampler = required_usize(channel.get("sampler"), "channel sampler")?;
+                check_index(Some(sampler), samplers.len(), "sampler")?;
+                channels.push(Channel {
+                    node,
+                    property,
+                    sampler,
+                });
+            }
+            animations.push(Animation {
+                name: name(animation),
+                channels,
+                samplers,
+            });
+        }
+        Ok(animations)
+    }
+}
+
+/// Accessor component type.
+#[derive(Clone, Copy)]
+struct Component {
+    kind: u64,
+    size: usize,
+    normalized: bool,
+}
+
+impl Component {
+    fn new(kind: u64, normalized: bool) -> Result<Component> {
+        let size = match kind {
+            BYTE | UNSIGNED_BYTE => 1,
+            SHORT | UNSIGNED_SHORT => 2,
+            UNSIGNED_INT | FLOAT => 4,
+            _ => return Err(invalid("Invalid component type")),
+        };
+        Ok(Component {
+            kind,
+            size,
+            normalized,
+        })
+    }
+
+    /// Reads a component from little-endian data.
+    fn read(self, data: &[u8]) -> f64 {
+        let value = match self.kind {
+            BYTE => f64::from(data[0] as i8),
+            UNSIGNED_BYTE => f64::from(data[0]),
+            SHORT => f64::from(i16::from_le_bytes([data[0], data[1]])),
+            UNSIGNED_SHORT => f64::from(u16::from_le_bytes([data[0], data[1]])),
+            UNSIGNED_INT => f64::from(u32_at(data, 0)),
+            _ => f64::from(f32::from_bits(u32_at(data, 0))),
+        };
+        if !self.normalized {
+            return value;
+        }
+        match self.kind {
+            BYTE => (value / 127.0).max(-1.0),
+            UNSIGNED_BYTE => value / 255.0,
+            SHORT => (value / 32767.0).max(-1.0),
+            UNSIGNED_SHORT => value / 65535.0,
+            _ => value,
+        }
+    }
+
+    /// Checks that `count` elements of `(rows, columns, column_size)` layout separated by
+    /// `stride` bytes fit in `data`.
+    fn check_elements(
+        self,
+        data: &[u8],
+        stride: usize,
+        (rows, columns, column_size): (usize, usize, usize),
+        count: usize,
+    ) -> Result<()> {
+        if count == 0 {
+            return Ok(());
+        }
+        let element_size = column_size * (columns - 1) + rows * self.size;
+        let size = stride
+            .checked_mul(count - 1)
+            .and_then(|size| size.checked_add(element_size));
+        if size.map_or(true, |size| size > data.len()) {
+            return Err(invalid("Accessor out of bounds"));
+        }
+        Ok(())
+    }
+
+    /// Reads `count` elements of `(rows, columns, column_size)` layout separated by
+    /// `stride` bytes, passing each component with its index.
+    fn read_elements<F: FnMut(usize, f64)>(
+        self,
+        data: &[u8],
+        stride: usize,
+        (rows, columns, column_size): (usize, usize, usize),
+        count: usize,
+        mut store: F,
+    ) -> Result<()> {
+        self.check_elements(data, stride, (rows, columns, column_size), count)?;
+        for element in 0..count {
+            for column in 0..columns {
+                for row in 0..rows {
+                    let offset = element * stride + column * column_size + row * self.size;
+                    store(
+                        (element * columns + column) * rows + row,
+                        self.read(&data[offset..]),
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the MIME type and the content of a data URI or of a relative file.
+fn read_uri(uri: &str, directory: Option<&Path>) -> Result<(Option<String>, Vec<u8>)> {
+    if uri.starts_with("data:") {
+        let comma = uri.find(',').ok_or_else(|| invalid("Invalid data URI"))?;
+        let header = &uri[5..comma];
+        if !header.ends_with(";base64") {
+            return Err(LoadModelError::Unsupported(String::from(
+                "Non-base64 data URI",
+            )));
+        }
+        let mime_type = &header[..header.len() - 7];
+        let data =
+            base64::decode(&uri[comma + 1..]).ok_or_else(|| invalid("Invalid base64 data"))?;
+        let mime_type = if mime_type.is_empty() {
+            None
+        } else {
+            Some(String::from(mime_type))
+        };
+        return Ok((mime_type, data));
+    }
+    if uri.contains("://") {
+        return Err(LoadModelError::Unsupported(format!("URI {}", uri)));
+    }
+    let directory = directory.ok_or_else(|| {
+        LoadModelError::Unsupported(format!("External file {} without directory", uri))
+    })?;
+    Ok((None, fs::read(directory.join(percent_decode(uri)))?))
+}
+
+/// Decodes the percent-encoded characters of a relative URI.
+fn percent_decode(uri: &str) -> String {
+    let bytes = uri.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escaped = bytes
+            .get(index + 1..index + 3)
+            .filter(|_| bytes[index] == b'%')
+            .and_then(|digits| std::str::from_utf8(digits).ok())
+            .and_then(|digits| u8::from_str_radix(digits, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            None => {
+                decoded.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Converts a glTF sampler.
+fn sampler(sampler: &Value) -> Sampler {
+    let default = Sampler::default();
+    let filter = |value: &Value| match value.as_f64().map(|value| value as u64) {
+        Some(NEAREST) => Some(Filter::Nearest),
+        Some(LINEAR) => Some(Filter::Linear),
+        _ => None,
+    };
+    let wrap = |value: &Value| match value.as_f64().map(|value| value as u64) {
+        Some(CLAMP_TO_EDGE) => Wrap::ClampToEdge,
+        Some(MIRRORED_REPEAT) => Wrap::MirroredRepeat,
+        _ => Wrap::Repeat,
+    };
+    let (min_filter, mipmap_filter) = match sampler.get("minFilter").as_f64().map(|v| v as u64) {
+        Some(NEAREST) => (Filter::Nearest, None),
+        Some(LINEAR) => (Filter::Linear, None),
+        Some(NEAREST_MIPMAP_NEAREST) => (Filter::Nearest, Some(Filter::Nearest)),
+        Some(LINEAR_MIPMAP_NEAREST) => (Filter::Linear, Some(Filter::Nearest)),
+        Some(NEAREST_MIPMAP_LINEAR) => (Filter::Nearest, Some(Filter::Linear)),
+        Some(LINEAR_MIPMAP_LINEAR) => (Filter::Linear, Some(Filter::Linear)),
+        _ => (default.min_filter, default.mipmap_filter),
+    };
+    Sampler {
+        mag_filter: filter(sampler.get("magFilter")).unwrap_or(default.mag_filter),
+        min_filter,
+        mipmap_filter,
+        wrap_u: wrap(sampler.get("wrapS")),
+        wrap_v: wrap(sampler.get("wrapT")),
+    }
+}
+
+/// Converts a texture info, reading its scale from `scale_key` if not empty.
+fn texture_ref(info: &Value, scale_key: &str, texture_count: usize) -> Result<Option<TextureRef>> {
+    if info.is_null() {
+        return Ok(None);
+    }
+    let texture = required_usize(info.get("index"), "texture index")?;
+    check_index(Some(texture), texture_count, "texture")?;
+    let mut reference = TextureRef::new(texture);
+    reference.tex_coord = info.get("texCoord").as_usize().unwrap_or(0) as u32;
+    if !scale_key.is_empty() {
+        reference.scale = info.get(scale_key).as_f64().unwrap_or(1.0) as f32;
+    }
+    Ok(Some(reference))
+}
+
+/// Reads an array of numbers of a fixed size.
+fn floats<T: Default + AsMut<[f32]>>(value: &Value) -> Option<T> {
+    let mut result = T::default();
+    let elements = value.elements();
+    if elements.is_empty() || elements.len() != result.as_mut().len() {
+        return None;
+    }
+    for (value, element) in result.as_mut().iter_mut().zip(elements) {
+        *value = element.as_f64()? as f32;
+    }
+    Some(result)
+}
+
+fn name(value: &Value) -> Option<String> {
+    value.get("name").as_str().map(String::from)
+}
+
+fn required_usize(value: &Value, what: &str) -> Result<usize> {
+    value
+        .as_usize()
+        .ok_or_else(|| LoadModelError::InvalidData(format!("Missing {}", what)))
+}
+
+/// Reads an optional index, checking it is lower than `count`.
+fn optional_index(value: &Value, count: usize, what: &str) -> Result<Option<usize>> {
+    if value.is_null() {
+        return Ok(None);
+    }
+    let index = required_usize(value, what)?;
+    check_index(Some(index), count, what)?;
+    Ok(Some(index))
+}
+
+/// Reads an array of indices, checking they are lower than `count`.
+fn indices(value: &Value, count: usize, what: &str) -> Result<Vec<usize>> {
+    value
+        .elements()
+        .iter()
+        .map(|element| {
+            let index = required_usize(element, what)?;
+            check_index(Some(index), count, what)?;
+            Ok(index)
+        })
+        .collect()
+}
+
+fn check_index(index: Option<usize>, count: usize, what: &str) -> Result {
+    match index {
+        Some(index) if index >= count => Err(LoadModelError::InvalidData(format!(
+            "Invalid {} index",
+            what
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Drops the indices of an incomplete trailing primitive.
+fn truncate(mut indices: Vec<u32>, size: usize) -> Vec<u32> {
+    indices.truncate(indices.len() / size * size);
+    indices
+}
+
+/// Converts a line strip or loop to a line list.
+fn line_strip(indices: &[u32], closed: bool) -> Vec<u32> {
+    let mut lines: Vec<u32> = indices.windows(2).flatten().copied().collect();
+    if closed && indices.len() > 2 {
+        lines.extend_from_slice(&[indices[indices.len() - 1], indices[0]]);
+    }
+    lines
+}
+
+/// Converts a triangle strip to a triangle list, preserving the winding.
+fn triangle_strip(indices: &[u32]) -> Vec<u32> {
+    let mut triangles = Vec::with_capacity(indices.len().saturating_sub(2) * 3);
+    for (index, window) in indices.windows(3).enumerate() {
+        if index % 2 == 0 {
+            triangles.extend_from_slice(window);
+        } else {
+            triangles.extend_from_slice(&[window[1], window[0], window[2]]);
+        }
+    }
+    triangles
+}
+
+/// Converts a triangle fan to a triangle list.
+fn triangle_fan(indices: &[u32]) -> Vec<u32> {
+    let mut triangles = Vec::with_capacity(indices.len().saturating_sub(2) * 3);
+    for window in indices.get(1..).unwrap_or(&[]).windows(2) {
+        triangles.extend_from_slice(&[indices[0], window[0], window[1]]);
+    }
+    triangles
+}
+
+/// Reads a little-endian `u32`.
+fn u32_at(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([
+        data[offset],
+        data[offset + 1],
+        data[offset + 2],
+        data[offset + 3],
+    ])
+}
+
+/// Creates an invalid data error.
+fn invalid(message: &str) -> LoadModelError {
+    LoadModelError::InvalidData(String::from(message))
+}
@@ -0,0 +1,261 @@
+//! Minimal JSON parser.
+
+use std::collections::HashMap;
+
+/// JSON value.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    Object(HashMap<String, Value>),
+}
+
+/// Value returned when indexing a missing member.
+static NULL: Value = Value::Null;
+
+impl Value {
+    /// Returns a member of an object, or `Null`.
+    pub(crate) fn get(&self, key: &str) -> &Value {
+        match self {
+            Value::Object(members) => members.get(key).unwrap_or(&NULL),
+            _ => &NULL,
+        }
+    }
+
+    /// Returns `true` if the value is `Null`.
+    pub(crate) fn is_null(&self) -> bool {
+        *self == Value::Null
+    }
+
+    pub(crate) fn as_bool(&self) -> Option<bool> {
+        match *self {
+            Value::Bool(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_f64(&self) -> Option<f64> {
+        match *self {
+            Value::Number(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns a non-negative integer.
+    pub(crate) fn as_usize(&self) -> Option<usize> {
+        match *self {
+            Value::Number(value) if value >= 0.0 && value.fract() == 0.0 => Some(value as usize),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns the elements of an array, or an empty slice.
+    pub(crate) fn elements(&self) -> &[Value] {
+        match self {
+            Value::Array(elements) => elements,
+            _ => &[],
+        }
+    }
+}
+
+/// Maximum nesting depth of arrays and objects.
+const MAX_DEPTH: usize = 128;
+
+/// Parses a JSON document.
+pub(crate) fn parse(text: &str) -> Result<Value, String> {
+    let mut parser = Parser {
+        bytes: text.as_bytes(),
+        position: 0,
+    };
+    let value = parser.value(0)?;
+    parser.skip_whitespace();
+    if parser.position != parser.bytes.len() {
+        return Err(parser.error("Trailing characters"));
+    }
+    Ok(value)
+}
+
+/// Recursive descent parser.
+struct Parser<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, message: &str) -> String {
+        format!("{} at byte {}", message, self.position)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ') | Some(b'\t') | Some(b'\n') | Some(b'\r') = self.peek() {
+            self.position += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.position).copied()
+    }
+
+    fn expect(&mut self, literal: &str) -> Result<(), String> {
+        if self.bytes[self.position..].starts_with(literal.as_bytes()) {
+            self.position += literal.len();
+            Ok(())
+        } else {
+            Err(self.error("Unexpected character"))
+        }
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Value, String> {
+        if depth > MAX_DEPTH {
+            return Err(self.error("Too deeply nested"));
+        }
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'n') => self.expect("null").map(|_| Value::Null),
+            Some(b't') => self.expect("true").map(|_| Value::Bool(true)),
+            Some(b'f') => self.expect("false").map(|_| Value::Bool(false)),
+            Some(b'"') => self.string().map(Value::String),
+            Some(b'[') => {
+                self.position += 1;
+                let mut elements = Vec::new();
+                self.skip_whitespace();
+                if self.peek() == Some(b']') {
+                    self.position += 1;
+                    return Ok(Value::Array(elements));
+                }
+                loop {
+                    elements.push(self.value(depth + 1)?);
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(b',') => self.position += 1,
+                        Some(b']') => {
+                            self.position += 1;
+                            return Ok(Value::Array(elements));
+                        }
+                        _ => return Err(self.error("Expected ',' or ']'")),
+                    }
+                }
+            }
+            Some(b'{') => {
+                self.position += 1;
+                let mut members = HashMap::new();
+                self.skip_whitespace();
+                if self.peek() == Some(b'}') {
+                    self.position += 1;
+                    return Ok(Value::Object(members));
+                }
+                loop {
+                    self.skip_whitespace();
+                    if self.peek() != Some(b'"') {
+                        return Err(self.error("Expected member name"));
+                    }
+                    let key = self.string()?;
+                    self.skip_whitespace();
+                    self.expect(":")?;
+                    let value = self.value(depth + 1)?;
+                    members.insert(key, value);
+                    self.skip_whitespace();
+                    match self.peek() {
+                        Some(b',') => self.position += 1,
+                        Some(b'}') => {
+                            self.position += 1;
+                            return Ok(Value::Object(members));
+                        }
+                        _ => return Err(self.error("Expected ',' or '}'")),
+                    }
+                }
+            }
+            Some(b'-') | Some(b'0'..=b'9') => self.number(),
+            _ => Err(self.error("Unexpected character")),
+        }
+    }
+
+    fn number(&mut self) -> Result<Value, String> {
+        let start = self.position;
+        while let Some(b'-') | Some(b'+') | Some(b'.') | Some(b'e') | Some(b'E')
+        | Some(b'0'..=b'9') = self.peek()
+        {
+            self.position += 1;
+        }
+        std::str::from_utf8(&self.bytes[start..self.position])
+            .ok()
+            .and_then(|number| number.parse().ok())
+            .map(Value::Number)
+            .ok_or_else(|| self.error("Invalid number"))
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.position += 1;
+        let mut bytes = Vec::new();
+        loop {
+            match self.peek() {
+                Some(b'"') => {
+                    self.position += 1;
+                    break;
+                }
+                Some(b'\\') => {
+                    self.position += 1;
+                    let escaped = self.peek().ok_or_else(|| self.error("Truncated string"))?;
+                    self.position += 1;
+                    let character = match escaped {
+                        b'"' => '"',
+                        b'\\' => '\\',
+                        b'/' => '/',
+                        b'b' => '\u{8}',
+                        b'f' => '\u{c}',
+                        b'n' => '\n',
+                        b'r' => '\r',
+                        b't' => '\t',
+                        b'u' => self.unicode_escape()?,
+                        _ => return Err(self.error("Invalid escape")),
+                    };
+                    let mut buffer = [0; 4];
+                    bytes.extend_from_slice(character.encode_utf8(&mut buffer).as_bytes());
+                }
+                Some(byte) if byte >= 0x20 => {
+                    bytes.push(byte);
+                    self.position += 1;
+                }
+                _ => return Err(self.error("Invalid string")),
+            }
+        }
+        String::from_utf8(bytes).map_err(|_| self.error("Invalid UTF-8"))
+    }
+
+    /// Parses the digits of a `\u` escape, combining surrogate pairs.
+    fn unicode_escape(&mut self) -> Result<char, String> {
+        let high = self.hex_digits()?;
+        let code = if (0xd800..0xdc00).contains(&high) {
+            self.expect("\\u")?;
+            let low = self.hex_digits()?;
+            if !(0xdc00..0xe000).contains(&low) {
+                return Err(self.error("Invalid surrogate pair"));
+            }
+            0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)
+        } else {
+            high
+        };
+        std::char::from_u32(code).ok_or_else(|| self.error("Invalid code point"))
+    }
+
+    fn hex_digits(&mut self) -> Result<u32, String> {
+        let digits = self
+            .bytes
+            .get(self.position..self.position + 4)
+            .and_then(|digits| std::str::from_utf8(digits).ok())
+            .and_then(|digits| u32::from_str_radix(digits, 16).ok())
+            .ok_or_else(|| self.error("Invalid unicode escape"))?;
+        self.position += 4;
+        Ok(digits)
+    }
+}
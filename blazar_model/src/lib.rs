@@ -0,0 +1,64 @@
+//! glTF and OBJ model loading.
+
+mod animation;
+mod base64;
+mod json;
+mod material;
+mod mesh;
+mod scene;
+
+pub mod gltf;
+pub mod obj;
+
+pub use animation::*;
+pub use material::*;
+pub use mesh::*;
+pub use scene::*;
+
+use std::{
+    ffi::OsStr,
+    fs::{self, File},
+    io::{self, BufReader},
+    path::Path,
+};
+
+/// Kinds of model loading errors.
+#[derive(Debug)]
+pub enum LoadModelError {
+    Io(io::Error),
+    UnknownFormat,
+    Unsupported(String),
+    InvalidData(String),
+}
+
+impl From<io::Error> for LoadModelError {
+    fn from(error: io::Error) -> LoadModelError {
+        LoadModelError::Io(error)
+    }
+}
+
+/// Convenient result type consisting of a return type and a `LoadModelError`.
+pub type Result<T = ()> = std::result::Result<T, LoadModelError>;
+
+impl Model {
+    /// Opens and loads a model file, detecting its format from its extension, or from its
+    /// content for binary glTF files.
+    ///
+    /// External files are resolved relatively to the model.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Model> {
+        let path = path.as_ref();
+        let extension = path
+            .extension()
+            .and_then(OsStr::to_str)
+            .map(str::to_ascii_lowercase);
+        if extension.as_deref() == Some("obj") {
+            return obj::load(BufReader::new(File::open(path)?), path.parent());
+        }
+        let data = fs::read(path)?;
+        if gltf::is_glb(&data) || extension.as_deref() == Some("gltf") {
+            gltf::load(&data, path.parent())
+        } else {
+            Err(LoadModelError::UnknownFormat)
+        }
+    }
+}
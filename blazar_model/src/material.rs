@@ -0,0 +1,137 @@
+//! Metallic-roughness materials and their textures.
+
+use blazar_image::Image;
+
+/// Image in its file format, typically PNG or JPEG.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EncodedImage {
+    pub name: Option<String>,
+    /// MIME type, when known.
+    pub mime_type: Option<String>,
+    pub data: Vec<u8>,
+}
+
+impl EncodedImage {
+    /// Decodes the image.
+    pub fn decode(&self) -> blazar_image::Result<Image> {
+        Image::decode(&self.data)
+    }
+}
+
+/// Texture filters.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Filter {
+    Nearest,
+    Linear,
+}
+
+/// Texture coordinate wrapping modes.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Wrap {
+    Repeat,
+    MirroredRepeat,
+    ClampToEdge,
+}
+
+/// Sampling parameters of a texture.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Sampler {
+    pub mag_filter: Filter,
+    pub min_filter: Filter,
+    /// Filter between mip levels, or `None` to sample the base level only.
+    pub mipmap_filter: Option<Filter>,
+    pub wrap_u: Wrap,
+    pub wrap_v: Wrap,
+}
+
+impl Default for Sampler {
+    fn default() -> Sampler {
+        Sampler {
+            mag_filter: Filter::Linear,
+            min_filter: Filter::Linear,
+            mipmap_filter: Some(Filter::Linear),
+            wrap_u: Wrap::Repeat,
+            wrap_v: Wrap::Repeat,
+        }
+    }
+}
+
+/// Image sampled with a sampler.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Texture {
+    /// Index of the image in the model.
+    pub image: usize,
+    pub sampler: Sampler,
+}
+
+/// Reference from a material to a texture.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TextureRef {
+    /// Index of the texture in the model.
+    pub texture: usize,
+    /// Set of texture coordinates, only the first one being loaded.
+    pub tex_coord: u32,
+    /// Scale of normal textures, or strength of occlusion textures.
+    pub scale: f32,
+}
+
+impl TextureRef {
+    /// Creates a reference to a texture using the first texture coordinates.
+    pub fn new(texture: usize) -> TextureRef {
+        TextureRef {
+            texture,
+            tex_coord: 0,
+            scale: 1.0,
+        }
+    }
+}
+
+/// Alpha modes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AlphaMode {
+    Opaque,
+    /// Fragments below the cutoff are discarded, others are opaque.
+    Mask(f32),
+    Blend,
+}
+
+/// Metallic-roughness material.
+///
+/// The blue channel of the metallic-roughness texture holds the metalness, its green
+/// channel the roughness. Base color and emissive textures are sRGB encoded.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Material {
+    pub name: Option<String>,
+    /// Linear base color, multiplied by the base color texture.
+    pub base_color: [f32; 4],
+    pub base_color_texture: Option<TextureRef>,
+    pub metallic: f32,
+    pub roughness: f32,
+    pub metallic_roughness_texture: Option<TextureRef>,
+    pub normal_texture: Option<TextureRef>,
+    pub occlusion_texture: Option<TextureRef>,
+    /// Linear emissive color, multiplied by the emissive texture.
+    pub emissive: [f32; 3],
+    pub emissive_texture: Option<TextureRef>,
+    pub alpha_mode: AlphaMode,
+    pub double_sided: bool,
+}
+
+impl Default for Material {
+    fn default() -> Material {
+        Material {
+            name: None,
+            base_color: [1.0; 4],
+            base_color_texture: None,
+            metallic: 1.0,
+            roughness: 1.0,
+            metallic_roughness_texture: None,
+            normal_texture: None,
+            occlusion_texture: None,
+            emissive: [0.0; 3],
+            emissive_texture: None,
+            alpha_mode: AlphaMode::Opaque,
+            double_sided: false,
+        }
+    }
+}
@@ -0,0 +1,234 @@
+//! Meshes ready for GPU upload.
+
+/// Interleaved vertex holding every supported attribute.
+///
+/// Missing attributes keep their default value: white color, no skinning and a zero
+/// tangent when neither normals nor texture coordinates allow generating one.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Vertex {
+    pub position: [f32; 3],
+    pub normal: [f32; 3],
+    /// Tangent, its `w` component being the handedness of the bitangent.
+    pub tangent: [f32; 4],
+    pub tex_coord: [f32; 2],
+    pub color: [f32; 4],
+    /// Indices of the joints of the skin influencing the vertex.
+    pub joints: [u16; 4],
+    pub weights: [f32; 4],
+}
+
+impl Default for Vertex {
+    fn default() -> Vertex {
+        Vertex {
+            position: [0.0; 3],
+            normal: [0.0; 3],
+            tangent: [0.0; 4],
+            tex_coord: [0.0; 2],
+            color: [1.0; 4],
+            joints: [0; 4],
+            weights: [0.0; 4],
+        }
+    }
+}
+
+/// Kinds of primitives formed by indices.
+///
+/// Strips, loops and fans are converted to lists when loading.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Topology {
+    Points,
+    Lines,
+    Triangles,
+}
+
+/// Indexed geometry drawn with a single material.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Primitive {
+    pub topology: Topology,
+    pub vertices: Vec<Vertex>,
+    pub indices: Vec<u32>,
+    /// Index of the material in the model, or `None` for the default material.
+    pub material: Option<usize>,
+}
+
+impl Primitive {
+    /// Returns the axis-aligned bounds of the vertices, or `None` if there are none.
+    pub fn bounds(&self) -> Option<([f32; 3], [f32; 3])> {
+        let first = self.vertices.first()?.position;
+        Some(
+            self.vertices
+                .iter()
+                .fold((first, first), |(min, max), vertex| {
+                    let position = vertex.position;
+                    (
+                        [
+                            min[0].min(position[0]),
+                            min[1].min(position[1]),
+                            min[2].min(position[2]),
+                        ],
+                        [
+                            max[0].max(position[0]),
+                            max[1].max(position[1]),
+                            max[2].max(position[2]),
+                        ],
+                    )
+                }),
+        )
+    }
+
+    /// Gives each triangle its own vertices, with the normal of its face.
+    pub fn generate_flat_normals(&mut self) {
+        if self.topology != Topology::Triangles {
+            return;
+        }
+        let mut vertices = Vec::with_capacity(self.indices.len());
+        for triangle in self.indices.chunks_exact(3) {
+            let mut corners = [
+                self.vertices[triangle[0] as usize],
+                self.vertices[triangle[1] as usize],
+                self.vertices[triangle[2] as usize],
+            ];
+            let normal = normalize(face_normal(
+                corners[0].position,
+                corners[1].position,
+                corners[2].position,
+            ));
+            for corner in &mut corners {
+                corner.normal = normal;
+            }
+            vertices.extend_from_slice(&corners);
+        }
+        self.indices = (0..vertices.len() as u32).collect();
+        self.vertices = vertices;
+    }
+
+    /// Computes smooth normals, averaging the normals of the faces sharing each vertex
+    /// weighted by their area.
+    pub fn generate_smooth_normals(&mut self) {
+        if self.topology != Topology::Triangles {
+            return;
+        }
+        let mut normals = vec![[0.0; 3]; self.vertices.len()];
+        for triangle in self.indices.chunks_exact(3) {
+            let normal = face_normal(
+                self.vertices[triangle[0] as usize].position,
+                self.vertices[triangle[1] as usize].position,
+                self.vertices[triangle[2] as usize].position,
+            );
+            for &index in triangle {
+                add(&mut normals[index as usize], normal);
+            }
+        }
+        for (vertex, normal) in self.vertices.iter_mut().zip(normals) {
+            vertex.normal = normalize(normal);
+        }
+    }
+
+    /// Computes tangents from normals and texture coordinates.
+    ///
+    /// Tangents of the triangles sharing each vertex are accumulated, then
+    /// orthogonalized against its normal.
+    pub fn generate_tangents(&mut self) {
+        if self.topology != Topology::Triangles {
+            return;
+        }
+        let mut tangents = vec![[0.0; 3]; self.vertices.len()];
+        let mut bitangents = vec![[0.0; 3]; self.vertices.len()];
+        for triangle in self.indices.chunks_exact(3) {
+            let [a, b, c] = [
+                self.vertices[triangle[0] as usize],
+                self.vertices[triangle[1] as usize],
+                self.vertices[triangle[2] as usize],
+            ];
+            let edge1 = sub(b.position, a.position);
+            let edge2 = sub(c.position, a.position);
+            let (du1, dv1) = (
+                b.tex_coord[0] - a.tex_coord[0],
+                b.tex_coord[1] - a.tex_coord[1],
+            );
+            let (du2, dv2) = (
+                c.tex_coord[0] - a.tex_coord[0],
+                c.tex_coord[1] - a.tex_coord[1],
+            );
+            let determinant = du1 * dv2 - du2 * dv1;
+            if determinant.abs() <= f32::EPSILON {
+                continue;
+            }
+            let r = 1.0 / determinant;
+            let mut tangent = [0.0; 3];
+            let mut bitangent = [0.0; 3];
+            for axis in 0..3 {
+                tangent[axis] = (edge1[axis] * dv2 - edge2[axis] * dv1) * r;
+                bitangent[axis] = (edge2[axis] * du1 - edge1[axis] * du2) * r;
+            }
+            for &index in triangle {
+                add(&mut tangents[index as usize], tangent);
+                add(&mut bitangents[index as usize], bitangent);
+            }
+        }
+        for (vertex, (tangent, bitangent)) in self
+            .vertices
+            .iter_mut()
+            .zip(tangents.into_iter().zip(bitangents))
+        {
+            let normal = vertex.normal;
+            let projected = dot(normal, tangent);
+            let tangent = normalize([
+                tangent[0] - normal[0] * projected,
+                tangent[1] - normal[1] * projected,
+                tangent[2] - normal[2] * projected,
+            ]);
+            let handedness = if dot(cross(normal, tangent), bitangent) < 0.0 {
+                -1.0
+            } else {
+                1.0
+            };
+            vertex.tangent = [tangent[0], tangent[1], tangent[2], handedness];
+        }
+    }
+}
+
+/// Named set of primitives.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Mesh {
+    pub name: Option<String>,
+    pub primitives: Vec<Primitive>,
+}
+
+/// Returns the normal of a triangle, its length being twice the triangle area.
+fn face_normal(a: [f32; 3], b: [f32; 3], c: [f32; 3]) -> [f32; 3] {
+    cross(sub(b, a), sub(c, a))
+}
+
+fn add(a: &mut [f32; 3], b: [f32; 3]) {
+    a[0] += b[0];
+    a[1] += b[1];
+    a[2] += b[2];
+}
+
+fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+/// Normalizes a vector, leaving null vectors unchanged.
+fn normalize(vector: [f32; 3]) -> [f32; 3] {
+    let length = dot(vector, vector).sqrt();
+    if length > 0.0 {
+        [vector[0] / length, vector[1] / length, vector[2] / length]
+    } else {
+        vector
+    }
+}
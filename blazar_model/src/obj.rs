@@ -0,0 +1,612 @@
+//! Wavefront OBJ and MTL loading.
+//!
+//! Files are parsed line by line. Polygons are triangulated, normals missing from faces
+//! are generated according to their smoothing group, and corners sharing the same
+//! attributes are merged into indexed vertices. Each object becomes a mesh with one
+//! primitive per material and kind of element.
+
+use crate::{
+    material::{AlphaMode, EncodedImage, Material, Sampler, Texture, TextureRef, Wrap},
+    mesh::{Mesh, Primitive, Topology, Vertex},
+    scene::{Model, Node, Scene},
+    LoadModelError, Result,
+};
+use std::{
+    collections::HashMap,
+    fs,
+    io::BufRead,
+    path::{Path, PathBuf},
+};
+
+/// Loads an OBJ model.
+///
+/// Material libraries and textures are resolved relatively to `directory`; materials
+/// are left out without one. Texture coordinates are flipped vertically, images having
+/// their top row first.
+pub fn load<R: BufRead>(reader: R, directory: Option<&Path>) -> Result<Model> {
+    let mut parser = Parser::default();
+    for line in logical_lines(reader) {
+        parser.line(&line?, directory)?;
+    }
+    Ok(parser.finish())
+}
+
+/// Corner of a face, line or point, made of resolved indices.
+#[derive(Clone, Copy, Debug)]
+struct Corner {
+    position: usize,
+    tex_coord: Option<usize>,
+    normal: Option<usize>,
+    /// Smoothing group, `0` meaning flat shading.
+    smoothing: u32,
+    /// Index of the face.
+    face: usize,
+}
+
+/// Elements of an object sharing a material and a topology.
+#[derive(Debug)]
+struct Group {
+    material: Option<usize>,
+    topology: Topology,
+    /// Corners of the elements, by groups of 3 for triangles and 2 for lines.
+    corners: Vec<Corner>,
+}
+
+/// Named set of groups.
+#[derive(Debug, Default)]
+struct Object {
+    name: Option<String>,
+    groups: Vec<Group>,
+}
+
+/// Normal of a vertex, distinguishing merged vertices.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+enum NormalKey {
+    None,
+    Given(usize),
+    Smooth(u32),
+    /// Normal of the face with the given index.
+    Flat(usize),
+}
+
+/// Streaming OBJ parser state.
+#[derive(Default)]
+struct Parser {
+    positions: Vec<[f32; 3]>,
+    colors: Vec<[f32; 4]>,
+    tex_coords: Vec<[f32; 2]>,
+    normals: Vec<[f32; 3]>,
+    /// Unit normal of each face.
+    face_normals: Vec<[f32; 3]>,
+    /// Sum of the normals of the faces sharing a position within a smoothing group,
+    /// weighted by their area.
+    smooth_normals: HashMap<(usize, u32), [f32; 3]>,
+    objects: Vec<Object>,
+    model: Model,
+    materials: HashMap<String, usize>,
+    material: Option<usize>,
+    smoothing: u32,
+}
+
+impl Parser {
+    fn line(&mut self, line: &str, directory: Option<&Path>) -> Result {
+        let mut tokens = line.split_whitespace();
+        let keyword = match tokens.next() {
+            Some(keyword) if !keyword.starts_with('#') => keyword,
+            _ => return Ok(()),
+        };
+        let arguments: Vec<&str> = tokens.collect();
+        match keyword {
+            "v" => {
+                let values = parse_numbers(&arguments)?;
+                if values.len() < 3 {
+                    return Err(invalid("Invalid vertex position"));
+                }
+                self.positions.push([values[0], values[1], values[2]]);
+                // Vertex colors are a common extension.
+                self.colors.push(match values.len() {
+                    6 => [values[3], values[4], values[5], 1.0],
+                    _ => [1.0; 4],
+                });
+            }
+            "vt" => {
+                let values = parse_numbers(&arguments)?;
+                let u = *values
+                    .first()
+                    .ok_or_else(|| invalid("Invalid texture coordinate"))?;
+                let v = values.get(1).copied().unwrap_or(0.0);
+                self.tex_coords.push([u, 1.0 - v]);
+            }
+            "vn" => self.normals.push(color(&arguments, "Invalid normal")?),
+            "f" | "l" | "p" => self.element(keyword, &arguments)?,
+            "o" => self.objects.push(Object {
+                name: Some(arguments.join(" ")),
+                groups: Vec::new(),
+            }),
+            "s" => {
+                self.smoothing = match arguments.first() {
+                    None | Some(&"off") => 0,
+                    Some(group) => group
+                        .parse()
+                        .map_err(|_| invalid("Invalid smoothing group"))?,
+                }
+            }
+            "usemtl" => self.material = self.materials.get(&arguments.join(" ")).copied(),
+            "mtllib" => {
+                if let Some(directory) = directory {
+                    let whole = arguments.join(" ");
+                    let files = if directory.join(&whole).is_file() {
+                        vec![whole]
+                    } else {
+                        arguments.iter().map(|file| String::from(*file)).collect()
+                    };
+                    for file in files {
+                        let data = fs::read(directory.join(&file))?;
+                        let library_directory = directory.join(&file);
+                        let library_directory = library_directory.parent().unwrap_or(directory);
+                        for (name, material) in
+                            materials(&data[..], library_directory, &mut self.model)?
+                        {
+                            self.materials.insert(name, self.model.materials.len());
+                            self.model.materials.push(material);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Parses a face, a polyline or points.
+    fn element(&mut self, keyword: &str, arguments: &[&str]) -> Result {
+        let mut corners = Vec::with_capacity(arguments.len());
+        for argument in arguments {
+            let mut indices = argument.split('/');
+            let position = resolve(indices.next(), self.positions.len())?
+                .ok_or_else(|| invalid("Missing position index"))?;
+            corners.push(Corner {
+                position,
+                tex_coord: resolve(indices.next(), self.tex_coords.len())?,
+                normal: resolve(indices.next(), self.normals.len())?,
+                smoothing: self.smoothing,
+                face: self.face_normals.len(),
+            });
+        }
+        let (topology, corners) = match keyword {
+            "f" => (Topology::Triangles, self.face(&corners)),
+            "l" => (
+                Topology::Lines,
+                corners.windows(2).flatten().copied().collect(),
+            ),
+            _ => (Topology::Points, corners),
+        };
+        if self.objects.is_empty() {
+            self.objects.push(Object::default());
+        }
+        let material = self.material;
+        let groups = &mut self.objects.last_mut().unwrap().groups;
+        let index = match groups
+            .iter()
+            .position(|group| group.material == material && group.topology == topology)
+        {
+            Some(index) => index,
+            None => {
+                groups.push(Group {
+                    material,
+                    topology,
+                    corners: Vec::new(),
+                });
+                groups.len() - 1
+            }
+        };
+        groups[index].corners.extend(corners);
+        Ok(())
+    }
+
+    /// Records the normal of a polygon and triangulates it by ear clipping, in the plane
+    /// of its average normal.
+    fn face(&mut self, corners: &[Corner]) -> Vec<Corner> {
+        let positions: Vec<[f32; 3]> = corners
+            .iter()
+            .map(|corner| self.positions[corner.position])
+            .collect();
+        // Newell's method, the length of the normal being twice the polygon area.
+        let mut normal = [0.0f32; 3];
+        for (index, current) in positions.iter().enumerate() {
+            let next = positions[(index + 1) % positions.len()];
+            normal[0] += (current[1] - next[1]) * (current[2] + next[2]);
+            normal[1] += (current[2] - next[2]) * (current[0] + next[0]);
+            normal[2] += (current[0] - next[0]) * (current[1] + next[1]);
+        }
+        self.face_normals.push(normalize(normal));
+        for corner in corners {
+            if corner.normal.is_none() && corner.smoothing != 0 {
+                let sum = self
+                    .smooth_normals
+                    .entry((corner.position, corner.smoothing))
+                    .or_insert([0.0; 3]);
+                for (sum, value) in sum.iter_mut().zip(&normal) {
+                    *sum += value;
+                }
+            }
+        }
+        let (x, y) = if normal[0].abs() > normal[1].abs() && normal[0].abs() > normal[2].abs() {
+            (1, 2)
+        } else if normal[1].abs() > normal[2].abs() {
+            (2, 0)
+        } else {
+            (0, 1)
+        };
+        let points: Vec<[f32; 2]> = positions.iter().map(|p| [p[x], p[y]]).collect();
+        triangulate(&points)
+            .iter()
+            .flatten()
+            .map(|&index| corners[index])
+            .collect()
+    }
+
+    /// Builds the model from the parsed elements.
+    fn finish(mut self) -> Model {
+        for normal in self.smooth_normals.values_mut() {
+            *normal = normalize(*normal);
+        }
+        for object in std::mem::take(&mut self.objects) {
+            let mut mesh = Mesh {
+                name: object.name,
+                primitives: Vec::new(),
+            };
+            for group in object.groups {
+                if group.corners.is_empty() {
+                    continue;
+                }
+                mesh.primitives.push(self.primitive(&group));
+            }
+            if mesh.primitives.is_empty() {
+                continue;
+            }
+            self.model.nodes.push(Node {
+                name: mesh.name.clone(),
+                mesh: Some(self.model.meshes.len()),
+                ..Node::default()
+            });
+            self.model.meshes.push(mesh);
+        }
+        self.model.scenes.push(Scene {
+            name: None,
+            nodes: (0..self.model.nodes.len()).collect(),
+        });
+        self.model.scene = Some(0);
+        self.model
+    }
+
+    /// Merges the corners of a group into indexed vertices.
+    fn primitive(&self, group: &Group) -> Primitive {
+        let mut vertices = Vec::new();
+        let mut indices = Vec::with_capacity(group.corners.len());
+        let mut merged = HashMap::new();
+        for corner in &group.corners {
+            let (normal_key, normal) = match (group.topology, corner.normal, corner.smoothing) {
+                (Topology::Triangles, Some(normal), _) => {
+                    (NormalKey::Given(normal), self.normals[normal])
+                }
+                (Topology::Triangles, None, 0) => {
+                    (NormalKey::Flat(corner.face), self.face_normals[corner.face])
+                }
+                (Topology::Triangles, None, smoothing) => (
+                    NormalKey::Smooth(smoothing),
+                    self.smooth_normals[&(corner.position, smoothing)],
+                ),
+                (_, Some(normal), _) => (NormalKey::Given(normal), self.normals[normal]),
+                _ => (NormalKey::None, [0.0; 3]),
+            };
+            let key = (corner.position, corner.tex_coord, normal_key);
+            let vertex_index = *merged.entry(key).or_insert_with(|| {
+                vertices.push(Vertex {
+                    position: self.positions[corner.position],
+                    normal,
+                    tex_coord: corner
+                        .tex_coord
+                        .map_or([0.0; 2], |tex_coord| self.tex_coords[tex_coord]),
+                    color: self.colors[corner.position],
+                    ..Vertex::default()
+                });
+                vertices.len() as u32 - 1
+            });
+            indices.push(vertex_index);
+        }
+        let mut primitive = Primitive {
+            topology: group.topology,
+            vertices,
+            indices,
+            material: group.material,
+        };
+        if group
+            .corners
+            .iter()
+            .any(|corner| corner.tex_coord.is_some())
+        {
+            primitive.generate_tangents();
+        }
+        primitive
+    }
+}
+
+/// Loads MTL material definitions, returning them with their name.
+///
+/// Textures are resolved relatively to `directory`, and appended to `model`.
+fn materials<R: BufRead>(
+    reader: R,
+    directory: &Path,
+    model: &mut Model,
+) -> Result<Vec<(String, Material)>> {
+    let mut materials: Vec<(String, Material)> = Vec::new();
+    for line in logical_lines(reader) {
+        let line = line?;
+        let mut tokens = line.split_whitespace();
+        let keyword = match tokens.next() {
+            Some(keyword) if !keyword.starts_with('#') => keyword,
+            _ => continue,
+        };
+        let arguments: Vec<&str> = tokens.collect();
+        if keyword == "newmtl" {
+            let name = arguments.join(" ");
+            materials.push((
+                name.clone(),
+                Material {
+                    name: Some(name),
+                    // Unlike glTF materials, MTL materials are dielectric by default.
+                    metallic: 0.0,
+                    ..Material::default()
+                },
+            ));
+            continue;
+        }
+        let material = match materials.last_mut() {
+            Some((_, material)) => material,
+            None => continue,
+        };
+        match keyword {
+            "Kd" => {
+                let [red, green, blue] = color(&arguments, "Invalid diffuse color")?;
+                material.base_color = [red, green, blue, material.base_color[3]];
+            }
+            "Ke" => material.emissive = color(&arguments, "Invalid emissive color")?,
+            "d" => material.base_color[3] = number(&arguments)?,
+            "Tr" => material.base_color[3] = 1.0 - number(&arguments)?,
+            "Ns" => {
+                // Converts the Phong exponent to an approximate roughness.
+                material.roughness = (2.0 / (number(&arguments)?.max(0.0) + 2.0)).sqrt();
+            }
+            "Pr" => material.roughness = number(&arguments)?,
+            "Pm" => material.metallic = number(&arguments)?,
+            "map_Kd" => material.base_color_texture = Some(texture(&arguments, directory, model)?),
+            "map_Ke" => material.emissive_texture = Some(texture(&arguments, directory, model)?),
+            "map_Bump" | "map_bump" | "bump" | "norm" => {
+                material.normal_texture = Some(texture(&arguments, directory, model)?)
+            }
+            _ => {}
+        }
+        if material.base_color[3] < 1.0 {
+            material.alpha_mode = AlphaMode::Blend;
+        }
+    }
+    Ok(materials)
+}
+
+/// Parses a texture map statement, loading its image into the model.
+fn texture(arguments: &[&str], directory: &Path, model: &mut Model) -> Result<TextureRef> {
+    let mut sampler = Sampler::default();
+    let mut scale = 1.0;
+    let mut index = 0;
+    while index < arguments.len() && arguments[index].starts_with('-') {
+        let option = arguments[index];
+        index += 1;
+        let value = arguments.get(index).copied().unwrap_or("");
+        match option {
+            "-clamp" => {
+                if value == "on" {
+                    sampler.wrap_u = Wrap::ClampToEdge;
+                    sampler.wrap_v = Wrap::ClampToEdge;
+                }
+                index += 1;
+            }
+            "-bm" => {
+                scale = value
+                    .parse()
+                    .map_err(|_| invalid("Invalid bump multiplier"))?;
+                index += 1;
+            }
+            "-mm" => index += 2,
+            // Up to 3 numbers.
+            "-o" | "-s" | "-t" => {
+                let count = arguments[index..]
+                    .iter()
+                    .take(3)
+                    .take_while(|argument| argument.parse::<f32>().is_ok())
+                    .count();
+                index += count;
+            }
+            _ => index += 1,
+        }
+    }
+    let file = arguments.get(index..).unwrap_or(&[]).join(" ");
+    if file.is_empty() {
+        return Err(invalid("Missing texture file"));
+    }
+    let image = match model
+        .images
+        .iter()
+        .position(|image| image.name.as_deref() == Some(file.as_str()))
+    {
+        Some(image) => image,
+        None => {
+            let path = PathBuf::from(&file);
+            let mime_type = match path
+                .extension()
+                .and_then(|extension| extension.to_str())
+                .map(str::to_ascii_lowercase)
+                .as_deref()
+            {
+                Some("png") => Some(String::from("image/png")),
+                Some("jpg") | Some("jpeg") => Some(String::from("image/jpeg")),
+                _ => None,
+            };
+            model.images.push(EncodedImage {
+                name: Some(file.clone()),
+                mime_type,
+                data: fs::read(directory.join(path))?,
+            });
+            model.images.len() - 1
+        }
+    };
+    let texture = Texture { image, sampler };
+    let texture = match model.textures.iter().position(|other| *other == texture) {
+        Some(index) => index,
+        None => {
+            model.textures.push(texture);
+            model.textures.len() - 1
+        }
+    };
+    Ok(TextureRef {
+        scale,
+        ..TextureRef::new(texture)
+    })
+}
+
+/// Returns the lines of a file, joining those ending with a backslash.
+fn logical_lines<R: BufRead>(reader: R) -> impl Iterator<Item = Result<String>> {
+    let mut lines = reader.lines();
+    std::iter::from_fn(move || {
+        let mut logical = String::new();
+        loop {
+            match lines.next() {
+                Some(Ok(line)) => {
+                    let line = line.trim_end();
+                    if line.ends_with('\\') {
+                        logical.push_str(&line[..line.len() - 1]);
+                        logical.push(' ');
+                    } else {
+                        logical.push_str(line);
+                        return Some(Ok(logical));
+                    }
+                }
+                Some(Err(error)) => return Some(Err(error.into())),
+                None if logical.is_empty() => return None,
+                None => return Some(Ok(logical)),
+            }
+        }
+    })
+}
+
+/// Resolves a 1-based or negative relative index, empty indices being `None`.
+fn resolve(index: Option<&str>, count: usize) -> Result<Option<usize>> {
+    let index = match index {
+        Some(index) if !index.is_empty() => index,
+        _ => return Ok(None),
+    };
+    let index: i64 = index.parse().map_err(|_| invalid("Invalid index"))?;
+    let resolved = if index < 0 {
+        count as i64 + index
+    } else {
+        index - 1
+    };
+    if resolved < 0 || resolved >= count as i64 {
+        return Err(invalid("Index out of bounds"));
+    }
+    Ok(Some(resolved as usize))
+}
+
+fn parse_numbers(arguments: &[&str]) -> Result<Vec<f32>> {
+    arguments
+        .iter()
+        .map(|argument| argument.parse().map_err(|_| invalid("Invalid number")))
+        .collect()
+}
+
+fn number(arguments: &[&str]) -> Result<f32> {
+    parse_numbers(arguments)?
+        .first()
+        .copied()
+        .ok_or_else(|| invalid("Missing number"))
+}
+
+/// Parses 3 numbers, a single one being repeated.
+fn color(arguments: &[&str], message: &str) -> Result<[f32; 3]> {
+    match parse_numbers(arguments).as_ref().map(Vec::as_slice) {
+        Ok([value]) => Ok([*value; 3]),
+        Ok([x, y, z, ..]) => Ok([*x, *y, *z]),
+        _ => Err(invalid(message)),
+    }
+}
+
+/// Triangulates a simple polygon by ear clipping, returning the indices of its
+/// triangles with the winding of the polygon.
+///
+/// Degenerate polygons fall back to a fan.
+fn triangulate(points: &[[f32; 2]]) -> Vec<[usize; 3]> {
+    if points.len() < 3 {
+        return Vec::new();
+    }
+    let cross = |a: [f32; 2], b: [f32; 2], c: [f32; 2]| {
+        (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+    };
+    let area: f32 = (0..points.len())
+        .map(|index| {
+            let (a, b) = (points[index], points[(index + 1) % points.len()]);
+            a[0] * b[1] - b[0] * a[1]
+        })
+        .sum();
+    let orientation = if area < 0.0 { -1.0 } else { 1.0 };
+    let mut remaining: Vec<usize> = (0..points.len()).collect();
+    let mut triangles = Vec::with_capacity(points.len() - 2);
+    while remaining.len() > 3 {
+        let count = remaining.len();
+        let ear = (0..count).find(|&index| {
+            let previous = remaining[(index + count - 1) % count];
+            let current = remaining[index];
+            let next = remaining[(index + 1) % count];
+            let (a, b, c) = (points[previous], points[current], points[next]);
+            if cross(a, b, c) * orientation <= 0.0 {
+                return false;
+            }
+            remaining.iter().all(|&other| {
+                if other == previous || other == current || other == next {
+                    return true;
+                }
+                let p = points[other];
+                cross(a, b, p) * orientation < 0.0
+                    || cross(b, c, p) * orientation < 0.0
+                    || cross(c, a, p) * orientation < 0.0
+            })
+        });
+        match ear {
+            Some(index) => {
+                let previous = remaining[(index + count - 1) % count];
+                let next = remaining[(index + 1) % count];
+                triangles.push([previous, remaining[index], next]);
+                remaining.remove(index);
+            }
+            None => break,
+        }
+    }
+    for index in 1..remaining.len() - 1 {
+        triangles.push([remaining[0], remaining[index], remaining[index + 1]]);
+    }
+    triangles
+}
+
+/// Normalizes a vector, leaving null vectors unchanged.
+fn normalize(vector: [f32; 3]) -> [f32; 3] {
+    let length = (vector[0] * vector[0] + vector[1] * vector[1] + vector[2] * vector[2]).sqrt();
+    if length > 0.0 {
+        [vector[0] / length, vector[1] / length, vector[2] / length]
+    } else {
+        vector
+    }
+}
+
+/// Creates an invalid data error.
+fn invalid(message: &str) -> LoadModelError {
+    LoadModelError::InvalidData(String::from(message))
+}
@@ -0,0 +1,268 @@
+//! Node hierarchies and skins.
+
+use crate::{
+    animation::Animation,
+    material::{EncodedImage, Material, Texture},
+    mesh::Mesh,
+};
+
+/// Column-major 4x4 matrix.
+pub type Matrix = [[f32; 4]; 4];
+
+/// Identity matrix.
+pub const IDENTITY: Matrix = [
+    [1.0, 0.0, 0.0, 0.0],
+    [0.0, 1.0, 0.0, 0.0],
+    [0.0, 0.0, 1.0, 0.0],
+    [0.0, 0.0, 0.0, 1.0],
+];
+
+/// Translation, rotation and scale, applied in reverse order.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transform {
+    pub translation: [f32; 3],
+    /// Unit quaternion `[x, y, z, w]`.
+    pub rotation: [f32; 4],
+    pub scale: [f32; 3],
+}
+
+impl Default for Transform {
+    fn default() -> Transform {
+        Transform {
+            translation: [0.0; 3],
+            rotation: [0.0, 0.0, 0.0, 1.0],
+            scale: [1.0; 3],
+        }
+    }
+}
+
+impl Transform {
+    /// Decomposes an affine matrix without shear.
+    pub fn from_matrix(matrix: &Matrix) -> Transform {
+        let length = |column: &[f32; 4]| {
+            (column[0] * column[0] + column[1] * column[1] + column[2] * column[2]).sqrt()
+        };
+        let mut scale = [length(&matrix[0]), length(&matrix[1]), length(&matrix[2])];
+        let determinant = determinant3(matrix);
+        if determinant < 0.0 {
+            scale[0] = -scale[0];
+        }
+        let mut rotation = [[0.0; 3]; 3];
+        for (column, axis) in rotation.iter_mut().enumerate() {
+            for (row, value) in axis.iter_mut().enumerate() {
+                if scale[column] != 0.0 {
+                    *value = matrix[column][row] / scale[column];
+                }
+            }
+        }
+        Transform {
+            translation: [matrix[3][0], matrix[3][1], matrix[3][2]],
+            rotation: quaternion_from_rotation(&rotation),
+            scale,
+        }
+    }
+
+    /// Returns the matrix of the transform.
+    pub fn matrix(&self) -> Matrix {
+        let [x, y, z, w] = self.rotation;
+        let [sx, sy, sz] = self.scale;
+        let [tx, ty, tz] = self.translation;
+        [
+            [
+                (1.0 - 2.0 * (y * y + z * z)) * sx,
+                2.0 * (x * y + z * w) * sx,
+                2.0 * (x * z - y * w) * sx,
+                0.0,
+            ],
+            [
+                2.0 * (x * y - z * w) * sy,
+                (1.0 - 2.0 * (x * x + z * z)) * sy,
+                2.0 * (y * z + x * w) * sy,
+                0.0,
+            ],
+            [
+                2.0 * (x * z + y * w) * sz,
+                2.0 * (y * z - x * w) * sz,
+                (1.0 - 2.0 * (x * x + y * y)) * sz,
+                0.0,
+            ],
+            [tx, ty, tz, 1.0],
+        ]
+    }
+}
+
+/// Node of a hierarchy.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Node {
+    pub name: Option<String>,
+    /// Transform relative to the parent node.
+    pub transform: Transform,
+    /// Indices of the child nodes in the model.
+    pub children: Vec<usize>,
+    /// Index of the mesh in the model.
+    pub mesh: Option<usize>,
+    /// Index of the skin in the model, deforming the mesh.
+    pub skin: Option<usize>,
+    /// Morph target weights.
+    pub weights: Vec<f32>,
+}
+
+/// Joints deforming skinned meshes.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Skin {
+    pub name: Option<String>,
+    /// Indices of the joint nodes in the model.
+    pub joints: Vec<usize>,
+    /// Matrices transforming meshes to the local space of each joint.
+    pub inverse_bind_matrices: Vec<Matrix>,
+    /// Index of the common root of the joints.
+    pub skeleton: Option<usize>,
+}
+
+impl Skin {
+    /// Returns the skinning matrix of each joint, from the world matrices of the nodes.
+    ///
+    /// Vertices are transformed by the weighted sum of the matrices of their joints,
+    /// followed by the inverse of the world matrix of the skinned node.
+    pub fn joint_matrices(&self, world_matrices: &[Matrix]) -> Vec<Matrix> {
+        self.joints
+            .iter()
+            .enumerate()
+            .map(|(index, &joint)| {
+                let inverse_bind = self.inverse_bind_matrices.get(index).unwrap_or(&IDENTITY);
+                multiply(&world_matrices[joint], inverse_bind)
+            })
+            .collect()
+    }
+}
+
+/// Set of root nodes.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Scene {
+    pub name: Option<String>,
+    /// Indices of the root nodes in the model.
+    pub nodes: Vec<usize>,
+}
+
+/// Meshes, materials, node hierarchies and animations loaded from a file.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Model {
+    pub meshes: Vec<Mesh>,
+    pub materials: Vec<Material>,
+    pub textures: Vec<Texture>,
+    pub images: Vec<EncodedImage>,
+    pub nodes: Vec<Node>,
+    pub skins: Vec<Skin>,
+    pub animations: Vec<Animation>,
+    pub scenes: Vec<Scene>,
+    /// Index of the scene to display.
+    pub scene: Option<usize>,
+}
+
+impl Model {
+    /// Returns the world matrix of every node.
+    ///
+    /// Nodes outside of any hierarchy are positioned relatively to the origin.
+    pub fn world_matrices(&self) -> Vec<Matrix> {
+        let mut matrices: Vec<Option<Matrix>> = vec![None; self.nodes.len()];
+        let mut is_child = vec![false; self.nodes.len()];
+        for node in &self.nodes {
+            for &child in &node.children {
+                is_child[child] = true;
+            }
+        }
+        let mut stack: Vec<(usize, Matrix)> = (0..self.nodes.len())
+            .filter(|&index| !is_child[index])
+            .map(|index| (index, IDENTITY))
+            .collect();
+        while let Some((index, parent)) = stack.pop() {
+            if matrices[index].is_some() {
+                continue;
+            }
+            let world = multiply(&parent, &self.nodes[index].transform.matrix());
+            matrices[index] = Some(world);
+            stack.extend(
+                self.nodes[index]
+                    .children
+                    .iter()
+                    .map(|&child| (child, world)),
+            );
+        }
+        matrices
+            .into_iter()
+            .zip(&self.nodes)
+            .map(|(matrix, node)| matrix.unwrap_or_else(|| node.transform.matrix()))
+            .collect()
+    }
+}
+
+/// Multiplies two column-major matrices.
+pub fn multiply(a: &Matrix, b: &Matrix) -> Matrix {
+    let mut result = [[0.0; 4]; 4];
+    for (column, result) in result.iter_mut().enumerate() {
+        for (row, value) in result.iter_mut().enumerate() {
+            *value = (0..4).map(|k| a[k][row] * b[column][k]).sum();
+        }
+    }
+    result
+}
+
+/// Returns the determinant of the upper-left 3x3 part of a matrix.
+fn determinant3(m: &Matrix) -> f32 {
+    m[0][0] * (m[1][1] * m[2][2] - m[2][1] * m[1][2])
+        - m[1][0] * (m[0][1] * m[2][2] - m[2][1] * m[0][2])
+        + m[2][0] * (m[0][1] * m[1][2] - m[1][1] * m[0][2])
+}
+
+/// Converts a column-major rotation matrix to a quaternion.
+fn quaternion_from_rotation(m: &[[f32; 3]; 3]) -> [f32; 4] {
+    let trace = m[0][0] + m[1][1] + m[2][2];
+    let quaternion = if trace > 0.0 {
+        let s = (trace + 1.0).sqrt() * 2.0;
+        [
+            (m[1][2] - m[2][1]) / s,
+            (m[2][0] - m[0][2]) / s,
+            (m[0][1] - m[1][0]) / s,
+            0.25 * s,
+        ]
+    } else if m[0][0] > m[1][1] && m[0][0] > m[2][2] {
+        let s = (1.0 + m[0][0] - m[1][1] - m[2][2]).sqrt() * 2.0;
+        [
+            0.25 * s,
+            (m[1][0] + m[0][1]) / s,
+            (m[2][0] + m[0][2]) / s,
+            (m[1][2] - m[2][1]) / s,
+        ]
+    } else if m[1][1] > m[2][2] {
+        let s = (1.0 + m[1][1] - m[0][0] - m[2][2]).sqrt() * 2.0;
+        [
+            (m[1][0] + m[0][1]) / s,
+            0.25 * s,
+            (m[2][1] + m[1][2]) / s,
+            (m[2][0] - m[0][2]) / s,
+        ]
+    } else {
+        let s = (1.0 + m[2][2] - m[0][0] - m[1][1]).sqrt() * 2.0;
+        [
+            (m[2][0] + m[0][2]) / s,
+            (m[2][1] + m[1][2]) / s,
+            0.25 * s,
+            (m[0][1] - m[1][0]) / s,
+        ]
+    };
+    let length = quaternion
+        .iter()
+        .map(|value| value * value)
+        .sum::<f32>()
+        .sqrt();
+    if length > 0.0 {
+        [
+            quaternion[0] / length,
+            quaternion[1] / length,
+            quaternion[2] / length,
+            quaternion[3] / length,
+        ]
+    } else {
+        [0.0, 0.0, 0.0, 1.0]
+    }
+}
@@ -0,0 +1,398 @@
+use blazar_model::{
+    gltf, AlphaMode, AnimationSampler, Filter, Interpolation, LoadModelError, Model, Property,
+    Topology, Transform, Wrap,
+};
+use std::{env, fs};
+
+/// Encodes data as base64.
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut text = String::new();
+    for chunk in data.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let bits = u32::from(bytes[0]) << 16 | u32::from(bytes[1]) << 8 | u32::from(bytes[2]);
+        for index in 0..4 {
+            if index <= chunk.len() {
+                text.push(ALPHABET[(bits >> (18 - index * 6) & 63) as usize] as char);
+            } else {
+                text.push('=');
+            }
+        }
+    }
+    text
+}
+
+/// Appends floats to a buffer.
+fn push_floats(buffer: &mut Vec<u8>, values: &[f32]) {
+    for value in values {
+        buffer.extend_from_slice(&value.to_le_bytes());
+    }
+}
+
+/// Creates the buffer of a textured quad made of two triangles, followed by animation
+/// keyframes:
+/// - 0: positions (48 bytes)
+/// - 48: texture coordinates (32 bytes)
+/// - 80: indices (12 bytes)
+/// - 92: times (12 bytes)
+/// - 104: translations (36 bytes)
+/// - 140: sparse index and value (2 + 2 padding + 12 bytes)
+fn quad_buffer() -> Vec<u8> {
+    let mut buffer = Vec::new();
+    push_floats(
+        &mut buffer,
+        &[0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0, 0.0],
+    );
+    push_floats(&mut buffer, &[0.0, 1.0, 1.0, 1.0, 1.0, 0.0, 0.0, 0.0]);
+    for index in &[0u16, 1, 2, 2, 3, 0] {
+        buffer.extend_from_slice(&index.to_le_bytes());
+    }
+    push_floats(&mut buffer, &[0.0, 1.0, 2.0]);
+    push_floats(&mut buffer, &[0.0, 0.0, 0.0, 2.0, 0.0, 0.0, 2.0, 4.0, 0.0]);
+    buffer.extend_from_slice(&[1, 0, 0, 0]);
+    push_floats(&mut buffer, &[5.0, 6.0, 7.0]);
+    buffer
+}
+
+/// Returns the JSON of a quad model, its buffer having the given URI.
+fn quad_json(uri: Option<&str>) -> String {
+    let uri = uri.map_or(String::new(), |uri| format!(r#""uri": "{}","#, uri));
+    format!(
+        r#"{{
+    "asset": {{"version": "2.0"}},
+    "scene": 0,
+    "scenes": [{{"name": "Main", "nodes": [0]}}],
+    "nodes": [
+        {{"name": "Root", "children": [1, 2], "translation": [1, 2, 3]}},
+        {{"name": "Quad", "mesh": 0, "skin": 0, "rotation": [0, 0, 0.7071068, 0.7071068]}},
+        {{"name": "Joint", "matrix": [2,0,0,0, 0,2,0,0, 0,0,2,0, 4,5,6,1]}}
+    ],
+    "meshes": [{{
+        "name": "Quad",
+        "primitives": [
+            {{"attributes": {{"POSITION": 0, "TEXCOORD_0": 1}}, "indices": 2, "material": 0}},
+            {{"attributes": {{"POSITION": 0}}, "mode": 6}}
+        ]
+    }}],
+    "materials": [{{
+        "name": "Checker",
+        "pbrMetallicRoughness": {{
+            "baseColorFactor": [1, 0.5, 0.25, 1],
+            "baseColorTexture": {{"index": 0}},
+            "metallicFactor": 0.1,
+            "roughnessFactor": 0.8
+        }},
+        "normalTexture": {{"index": 0, "scale": 0.5}},
+        "emissiveFactor": [0.2, 0.3, 0.4],
+        "alphaMode": "MASK",
+        "alphaCutoff": 0.25,
+        "doubleSided": true
+    }}],
+    "textures": [{{"source": 0, "sampler": 0}}],
+    "samplers": [{{"magFilter": 9728, "minFilter": 9986, "wrapS": 33071, "wrapT": 33648}}],
+    "images": [{{"uri": "data:image/png;base64,iVBORw0KGgo="}}],
+    "skins": [{{"joints": [2], "inverseBindMatrices": 4}}],
+    "animations": [{{
+        "name": "Move",
+        "channels": [
+            {{"sampler": 0, "target": {{"node": 0, "path": "translation"}}}},
+            {{"sampler": 1, "target": {{"node": 2, "path": "translation"}}}}
+        ],
+        "samplers": [
+            {{"input": 3, "output": 5}},
+            {{"input": 3, "output": 6, "interpolation": "STEP"}}
+        ]
+    }}],
+    "accessors": [
+        {{"bufferView": 0, "componentType": 5126, "count": 4, "type": "VEC3"}},
+        {{"bufferView": 0, "byteOffset": 48, "componentType": 5126, "count": 4, "type": "VEC2"}},
+        {{"bufferView": 0, "byteOffset": 80, "componentType": 5123, "count": 6, "type": "SCALAR"}},
+        {{"bufferView": 0, "byteOffset": 92, "componentType": 5126, "count": 3, "type": "SCALAR"}},
+        {{"componentType": 5126, "count": 1, "type": "MAT4"}},
+        {{"bufferView": 0, "byteOffset": 104, "componentType": 5126, "count": 3, "type": "VEC3"}},
+        {{
+            "bufferView": 0, "byteOffset": 104, "componentType": 5126, "count": 3, "type": "VEC3",
+            "sparse": {{
+                "count": 1,
+                "indices": {{"bufferView": 0, "byteOffset": 140, "componentType": 5123}},
+                "values": {{"bufferView": 0, "byteOffset": 144}}
+            }}
+        }}
+    ],
+    "bufferViews": [{{"buffer": 0, "byteLength": 156}}],
+    "buffers": [{{{} "byteLength": 156}}]
+}}"#,
+        uri
+    )
+}
+
+/// Creates a binary glTF file.
+fn glb(json: &str, binary: &[u8]) -> Vec<u8> {
+    let mut json = json.as_bytes().to_vec();
+    while json.len() % 4 != 0 {
+        json.push(b' ');
+    }
+    let mut binary = binary.to_vec();
+    while binary.len() % 4 != 0 {
+        binary.push(0);
+    }
+    let length = 12 + 8 + json.len() + 8 + binary.len();
+    let mut data = b"glTF".to_vec();
+    data.extend_from_slice(&2u32.to_le_bytes());
+    data.extend_from_slice(&(length as u32).to_le_bytes());
+    data.extend_from_slice(&(json.len() as u32).to_le_bytes());
+    data.extend_from_slice(b"JSON");
+    data.extend_from_slice(&json);
+    data.extend_from_slice(&(binary.len() as u32).to_le_bytes());
+    data.extend_from_slice(b"BIN\0");
+    data.extend_from_slice(&binary);
+    data
+}
+
+/// Checks the content of the quad model.
+fn check_quad(model: &Model) {
+    assert_eq!(model.scene, Some(0));
+    assert_eq!(model.scenes[0].name.as_deref(), Some("Main"));
+    assert_eq!(model.scenes[0].nodes, [0]);
+    assert_eq!(model.meshes.len(), 1);
+
+    let textured = &model.meshes[0].primitives[0];
+    assert_eq!(textured.topology, Topology::Triangles);
+    assert_eq!(textured.material, Some(0));
+    // Missing normals are generated flat, un-indexing the triangles.
+    assert_eq!(textured.vertices.len(), 6);
+    assert_eq!(textured.indices, [0, 1, 2, 3, 4, 5]);
+    for vertex in &textured.vertices {
+        assert_eq!(vertex.normal, [0.0, 0.0, 1.0]);
+        assert_eq!(vertex.color, [1.0; 4]);
+        let [x, y, z, w] = vertex.tangent;
+        assert!((x - 1.0).abs() < 1e-6 && y.abs() < 1e-6 && z.abs() < 1e-6);
+        assert_eq!(w, -1.0);
+    }
+    assert_eq!(textured.vertices[2].position, [1.0, 1.0, 0.0]);
+    assert_eq!(textured.vertices[2].tex_coord, [1.0, 0.0]);
+    assert_eq!(textured.bounds(), Some(([0.0; 3], [1.0, 1.0, 0.0])));
+
+    let fan = &model.meshes[0].primitives[1];
+    assert_eq!(fan.material, None);
+    assert_eq!(fan.indices.len(), 6);
+
+    let material = &model.materials[0];
+    assert_eq!(material.name.as_deref(), Some("Checker"));
+    assert_eq!(material.base_color, [1.0, 0.5, 0.25, 1.0]);
+    assert_eq!(material.base_color_texture.unwrap().texture, 0);
+    assert_eq!(material.normal_texture.unwrap().scale, 0.5);
+    assert_eq!(material.metallic, 0.1);
+    assert_eq!(material.roughness, 0.8);
+    assert_eq!(material.emissive, [0.2, 0.3, 0.4]);
+    assert_eq!(material.alpha_mode, AlphaMode::Mask(0.25));
+    assert!(material.double_sided);
+    assert!(material.occlusion_texture.is_none());
+
+    let texture = model.textures[0];
+    assert_eq!(texture.image, 0);
+    assert_eq!(texture.sampler.mag_filter, Filter::Nearest);
+    assert_eq!(texture.sampler.min_filter, Filter::Nearest);
+    assert_eq!(texture.sampler.mipmap_filter, Some(Filter::Linear));
+    assert_eq!(texture.sampler.wrap_u, Wrap::ClampToEdge);
+    assert_eq!(texture.sampler.wrap_v, Wrap::MirroredRepeat);
+    let image = &model.images[0];
+    assert_eq!(image.mime_type.as_deref(), Some("image/png"));
+    assert_eq!(image.data, b"\x89PNG\r\n\x1a\n");
+
+    let nodes = &model.nodes;
+    assert_eq!(nodes[0].children, [1, 2]);
+    assert_eq!(nodes[1].mesh, Some(0));
+    assert_eq!(nodes[1].skin, Some(0));
+    let joint = nodes[2].transform;
+    assert_eq!(joint.translation, [4.0, 5.0, 6.0]);
+    assert_eq!(joint.scale, [2.0; 3]);
+    assert_eq!(joint.rotation, [0.0, 0.0, 0.0, 1.0]);
+    let world = model.world_matrices();
+    assert_eq!(world[2][3], [5.0, 7.0, 9.0, 1.0]);
+    // The quad is rotated by 90 degrees around z.
+    let x_axis = world[1][0];
+    assert!(x_axis[0].abs() < 1e-6 && (x_axis[1] - 1.0).abs() < 1e-6);
+
+    let skin = &model.skins[0];
+    assert_eq!(skin.joints, [2]);
+    assert_eq!(skin.inverse_bind_matrices, [[[0.0; 4]; 4]]);
+
+    let animation = &model.animations[0];
+    assert_eq!(animation.name.as_deref(), Some("Move"));
+    assert_eq!(animation.duration(), 2.0);
+    assert_eq!(animation.channels[0].property, Property::Translation);
+    assert_eq!(animation.samplers[1].interpolation, Interpolation::Step);
+    // The sparse accessor replaces the second translation.
+    assert_eq!(&animation.samplers[1].values[3..6], [5.0, 6.0, 7.0]);
+    let mut nodes = model.nodes.clone();
+    animation.apply(1.5, &mut nodes);
+    assert_eq!(nodes[0].transform.translation, [2.0, 2.0, 0.0]);
+    assert_eq!(nodes[2].transform.translation, [5.0, 6.0, 7.0]);
+    animation.apply(10.0, &mut nodes);
+    assert_eq!(nodes[0].transform.translation, [2.0, 4.0, 0.0]);
+}
+
+#[test]
+fn embedded_buffer() {
+    let uri = format!(
+        "data:application/octet-stream;base64,{}",
+        base64(&quad_buffer())
+    );
+    let model = gltf::load(quad_json(Some(&uri)).as_bytes(), None).unwrap();
+    check_quad(&model);
+}
+
+#[test]
+fn binary() {
+    let data = glb(&quad_json(None), &quad_buffer());
+    assert!(gltf::is_glb(&data));
+    check_quad(&gltf::load(&data, None).unwrap());
+}
+
+#[test]
+fn external_buffer() {
+    let directory = env::temp_dir().join(format!("blazar_gltf_{}", std::process::id()));
+    fs::create_dir_all(&directory).unwrap();
+    fs::write(directory.join("quad data.bin"), quad_buffer()).unwrap();
+    let path = directory.join("quad.gltf");
+    fs::write(&path, quad_json(Some("quad%20data.bin"))).unwrap();
+    let model = Model::open(&path);
+    fs::remove_dir_all(&directory).unwrap();
+    check_quad(&model.unwrap());
+    match gltf::load(quad_json(Some("quad.bin")).as_bytes(), None) {
+        Err(LoadModelError::Unsupported(_)) => {}
+        result => panic!("unexpected result: {:?}", result),
+    }
+}
+
+#[test]
+fn invalid_documents() {
+    let cases = [
+        r#"{"asset": {"version": "1.0"}}"#,
+        r#"{"asset": {"version": "2.0"}, "extensionsRequired": ["KHR_draco_mesh_compression"]}"#,
+    ];
+    for case in &cases {
+        match gltf::load(case.as_bytes(), None) {
+            Err(LoadModelError::Unsupported(_)) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+    let cases = [
+        "",
+        r#"{"asset": {"version": "2.0"}"#,
+        r#"{"asset": {"version": "2.0"}, "scenes": [{"nodes": [0]}]}"#,
+        r#"{"asset": {"version": "2.0"}, "buffers": [{"byteLength": 4}]}"#,
+    ];
+    for case in &cases {
+        match gltf::load(case.as_bytes(), None) {
+            Err(LoadModelError::InvalidData(_)) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+    let truncated = glb(&quad_json(None), &quad_buffer()[..100]);
+    assert!(gltf::load(&truncated, None).is_err());
+}
+
+/// Returns the JSON of a triangle whose positions are read by `accessor`, over a buffer
+/// of 48 bytes.
+fn triangle_json(accessor: &str) -> String {
+    format!(
+        r#"{{
+    "asset": {{"version": "2.0"}},
+    "meshes": [{{"primitives": [{{"attributes": {{"POSITION": 0}}}}]}}],
+    "accessors": [{}],
+    "bufferViews": [{{"buffer": 0, "byteLength": 48}}],
+    "buffers": [{{"uri": "data:application/octet-stream;base64,{}", "byteLength": 48}}]
+}}"#,
+        accessor,
+        base64(&[0; 48])
+    )
+}
+
+#[test]
+fn oversized_accessors() {
+    let accessor = |count: &str, view: &str, sparse_count: &str| {
+        let sparse = if sparse_count.is_empty() {
+            String::new()
+        } else {
+            format!(
+                r#", "sparse": {{
+                    "count": {},
+                    "indices": {{"bufferView": 0, "componentType": 5125}},
+                    "values": {{"bufferView": 0}}
+                }}"#,
+                sparse_count
+            )
+        };
+        triangle_json(&format!(
+            r#"{{{} "componentType": 5126, "count": {}, "type": "VEC3"{}}}"#,
+            view, count, sparse
+        ))
+    };
+    assert!(gltf::load(accessor("3", r#""bufferView": 0,"#, "").as_bytes(), None).is_ok());
+    assert!(gltf::load(accessor("3", "", "1").as_bytes(), None).is_ok());
+    let cases = [
+        // Overflowing the number of values, with or without a buffer view.
+        accessor("4611686018427387904", r#""bufferView": 0,"#, ""),
+        accessor("4611686018427387904", "", ""),
+        // Beyond the limit of accessors without a buffer view.
+        accessor("100000000000", "", ""),
+        // Beyond the buffer view.
+        accessor("80000000", r#""bufferView": 0,"#, ""),
+        // More sparse elements than elements, or than the buffer views hold.
+        accessor("3", "", "4611686018427387904"),
+        accessor("3", "", "4"),
+        accessor("1000000", "", "1000000"),
+    ];
+    for case in &cases {
+        match gltf::load(case.as_bytes(), None) {
+            Err(LoadModelError::InvalidData(_)) => {}
+            result => panic!("unexpected result: {:?}", result.map(|_| ())),
+        }
+    }
+}
+
+#[test]
+fn transform_round_trip() {
+    let transform = Transform {
+        translation: [1.0, -2.0, 3.0],
+        rotation: [0.182_574_18, 0.365_148_37, 0.547_722_6, 0.730_296_7],
+        scale: [2.0, 3.0, 0.5],
+    };
+    let decomposed = Transform::from_matrix(&transform.matrix());
+    for (a, b) in decomposed.rotation.iter().zip(&transform.rotation) {
+        assert!((a - b).abs() < 1e-5);
+    }
+    for (a, b) in decomposed.scale.iter().zip(&transform.scale) {
+        assert!((a - b).abs() < 1e-5);
+    }
+    assert_eq!(decomposed.translation, transform.translation);
+}
+
+#[test]
+fn animation_sampling() {
+    let rotation = AnimationSampler {
+        times: vec![0.0, 1.0],
+        values: vec![0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0],
+        interpolation: Interpolation::Linear,
+    };
+    let half = rotation.sample(0.5, 4, Property::Rotation);
+    let expected = std::f32::consts::FRAC_1_SQRT_2;
+    assert!((half[2] - expected).abs() < 1e-6 && (half[3] - expected).abs() < 1e-6);
+    // Keyframes hold an in-tangent, a value and an out-tangent.
+    let spline = AnimationSampler {
+        times: vec![0.0, 2.0],
+        values: vec![0.0, 0.0, 1.0, 1.0, 4.0, 0.0],
+        interpolation: Interpolation::CubicSpline,
+    };
+    assert_eq!(spline.sample(-1.0, 1, Property::Scale), [0.0]);
+    assert_eq!(spline.sample(2.0, 1, Property::Scale), [4.0]);
+    // Hermite basis at t = 0.5 with a duration of 2.
+    let middle = spline.sample(1.0, 1, Property::Scale)[0];
+    assert!((middle - (0.5 * 4.0 + 0.125 * 2.0 * 1.0 - 0.125 * 2.0 * 1.0)).abs() < 1e-6);
+}
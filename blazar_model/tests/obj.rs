@@ -0,0 +1,239 @@
+use blazar_model::{obj, AlphaMode, LoadModelError, Model, Primitive, Topology, Wrap};
+use std::{env, fs};
+
+const CUBE: &str = "
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+v 0 0 1
+v 1 0 1
+v 1 1 1
+v 0 1 1
+f 1 4 3 2
+f 5 6 7 8
+f 1 2 6 5
+f 2 3 7 6
+f 3 4 8 7
+f 4 1 5 8
+";
+
+fn load(source: &str) -> Model {
+    obj::load(source.as_bytes(), None).unwrap()
+}
+
+/// Returns the signed area of a triangle, projected along z.
+fn area(primitive: &Primitive, triangle: &[u32]) -> f32 {
+    let [a, b, c] = [
+        primitive.vertices[triangle[0] as usize].position,
+        primitive.vertices[triangle[1] as usize].position,
+        primitive.vertices[triangle[2] as usize].position,
+    ];
+    ((b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])) / 2.0
+}
+
+#[test]
+fn flat_normals() {
+    let model = load(CUBE);
+    assert_eq!(model.meshes.len(), 1);
+    assert_eq!(model.nodes[0].mesh, Some(0));
+    assert_eq!(model.scenes[0].nodes, [0]);
+    let primitive = &model.meshes[0].primitives[0];
+    assert_eq!(primitive.topology, Topology::Triangles);
+    assert_eq!(primitive.material, None);
+    assert_eq!(primitive.indices.len(), 36);
+    // Triangles of a same face share their vertices.
+    assert_eq!(primitive.vertices.len(), 24);
+    for triangle in primitive.indices.chunks(3) {
+        let vertex = primitive.vertices[triangle[0] as usize];
+        let center = [0.5; 3];
+        let outward: f32 = (0..3)
+            .map(|axis| vertex.normal[axis] * (vertex.position[axis] - center[axis]))
+            .sum();
+        assert!((outward - 0.5).abs() < 1e-6);
+        assert_eq!(
+            vertex.normal.iter().filter(|value| **value != 0.0).count(),
+            1
+        );
+    }
+}
+
+#[test]
+fn smoothing_groups() {
+    let model = load(&format!("s 1\n{}", CUBE));
+    let primitive = &model.meshes[0].primitives[0];
+    assert_eq!(primitive.vertices.len(), 8);
+    let expected = 1.0 / 3f32.sqrt();
+    for vertex in &primitive.vertices {
+        for axis in 0..3 {
+            let sign = if vertex.position[axis] > 0.5 {
+                1.0
+            } else {
+                -1.0
+            };
+            assert!((vertex.normal[axis] - sign * expected).abs() < 1e-6);
+        }
+    }
+    // Faces of distinct groups do not share vertices.
+    let split = CUBE.replacen("f 5 6 7 8", "s 2\nf 5 6 7 8\ns 1", 1);
+    let model = load(&format!("s 1\n{}", split));
+    assert_eq!(model.meshes[0].primitives[0].vertices.len(), 12);
+}
+
+#[test]
+fn concave_polygon() {
+    let model = load(
+        "
+v 0 0 0
+v 2 0 0
+v 2 1 0
+v 1 1 0
+v 1 2 0
+v 0 2 0
+f 1 2 3 4 5 6
+",
+    );
+    let primitive = &model.meshes[0].primitives[0];
+    assert_eq!(primitive.indices.len(), 12);
+    let mut total = 0.0;
+    for triangle in primitive.indices.chunks(3) {
+        let area = area(primitive, triangle);
+        assert!(area > 0.0);
+        total += area;
+    }
+    assert!((total - 3.0).abs() < 1e-6);
+}
+
+#[test]
+fn merged_vertices() {
+    let model = load(
+        "
+v 0 0 0 1 0 0
+v 1 0 0 0 1 0
+v 1 1 0 0 0 1
+v 0 1 0 1 1 1
+vt 0 0
+vt 1 0
+vt 1 1
+vt 0 1
+vn 0 0 1
+f 1/1/1 2/2/1 3/3/1
+f -4/-4/-1 -2/-2/-1 -1/-1/-1
+",
+    );
+    let primitive = &model.meshes[0].primitives[0];
+    assert_eq!(primitive.vertices.len(), 4);
+    assert_eq!(primitive.indices, [0, 1, 2, 0, 2, 3]);
+    let vertex = primitive.vertices[1];
+    assert_eq!(vertex.color, [0.0, 1.0, 0.0, 1.0]);
+    // Texture coordinates are flipped vertically.
+    assert_eq!(vertex.tex_coord, [1.0, 1.0]);
+    assert_eq!(vertex.normal, [0.0, 0.0, 1.0]);
+    assert_eq!(vertex.tangent, [1.0, 0.0, 0.0, -1.0]);
+}
+
+#[test]
+fn materials_and_objects() {
+    let directory = env::temp_dir().join(format!("blazar_obj_{}", std::process::id()));
+    fs::create_dir_all(directory.join("textures")).unwrap();
+    fs::write(directory.join("textures/albedo.png"), b"albedo").unwrap();
+    fs::write(directory.join("textures/normal.png"), b"normal").unwrap();
+    fs::write(
+        directory.join("scene.mtl"),
+        "
+# Materials
+newmtl Red
+Kd 1 0 0
+Ns 198
+map_Kd -clamp on -s 1 1 1 textures/albedo.png
+
+newmtl Glass
+Kd 0.5
+d 0.25
+Pm 0.5
+norm -bm 0.3 textures/normal.png
+map_Ke textures/albedo.png
+",
+    )
+    .unwrap();
+    fs::write(
+        directory.join("scene.obj"),
+        "
+mtllib scene.mtl
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+o First
+usemtl Red
+f 1 2 3
+usemtl Glass
+f 1 3 4
+usemtl Red
+f 2 3 4
+o Second
+usemtl Unknown
+f 1 2 3
+l 1 2 3 \\
+4
+",
+    )
+    .unwrap();
+    let model = Model::open(directory.join("scene.obj"));
+    fs::remove_dir_all(&directory).unwrap();
+    let model = model.unwrap();
+
+    assert_eq!(model.materials.len(), 2);
+    let red = &model.materials[0];
+    assert_eq!(red.name.as_deref(), Some("Red"));
+    assert_eq!(red.base_color, [1.0, 0.0, 0.0, 1.0]);
+    assert_eq!(red.metallic, 0.0);
+    assert!((red.roughness - 0.1).abs() < 1e-6);
+    assert_eq!(red.alpha_mode, AlphaMode::Opaque);
+    let albedo = red.base_color_texture.unwrap();
+    assert_eq!(
+        model.textures[albedo.texture].sampler.wrap_u,
+        Wrap::ClampToEdge
+    );
+    let glass = &model.materials[1];
+    assert_eq!(glass.base_color, [0.5, 0.5, 0.5, 0.25]);
+    assert_eq!(glass.metallic, 0.5);
+    assert_eq!(glass.alpha_mode, AlphaMode::Blend);
+    assert_eq!(glass.normal_texture.unwrap().scale, 0.3);
+    // Images are shared, textures with distinct samplers are not.
+    assert_eq!(model.images.len(), 2);
+    assert_eq!(model.images[0].data, b"albedo");
+    assert_eq!(model.images[0].mime_type.as_deref(), Some("image/png"));
+    assert_eq!(model.textures.len(), 3);
+    let emissive = glass.emissive_texture.unwrap();
+    assert_eq!(model.textures[emissive.texture].image, 0);
+
+    assert_eq!(model.meshes.len(), 2);
+    assert_eq!(model.nodes[1].name.as_deref(), Some("Second"));
+    let first = &model.meshes[0];
+    assert_eq!(first.name.as_deref(), Some("First"));
+    assert_eq!(first.primitives.len(), 2);
+    assert_eq!(first.primitives[0].material, Some(0));
+    assert_eq!(first.primitives[0].indices.len(), 6);
+    assert_eq!(first.primitives[1].material, Some(1));
+    let second = &model.meshes[1];
+    assert_eq!(second.primitives[0].material, None);
+    let lines = &second.primitives[1];
+    assert_eq!(lines.topology, Topology::Lines);
+    assert_eq!(lines.indices, [0, 1, 1, 2, 2, 3]);
+}
+
+#[test]
+fn invalid_files() {
+    for source in &[
+        "v 0 0\n",
+        "v 0 0 0\nf 1 2 3\n",
+        "vt 0\nv 0 0 0\nf 0 1 1\n",
+        "s x\n",
+    ] {
+        match obj::load(source.as_bytes(), None) {
+            Err(LoadModelError::InvalidData(_)) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+}
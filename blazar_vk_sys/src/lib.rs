@@ -2,9 +2,262 @@
 
 #![allow(non_camel_case_types)]
 
+use std::os::raw::*;
+
 // Types
 pub type PFN_vkVoidFunction = Option<unsafe extern "C" fn()>;
+pub type VkAccessFlags = VkFlags;
+pub type VkBlendFactor = c_int;
+pub type VkBlendOp = c_int;
+pub type VkColorComponentFlags = VkFlags;
+pub type VkCompareOp = c_int;
+pub type VkCullModeFlags = VkFlags;
+pub type VkDescriptorBindingFlags = VkFlags;
+pub type VkDescriptorPoolCreateFlags = VkFlags;
+pub type VkDescriptorType = c_int;
+pub type VkDynamicState = c_int;
+pub type VkFlags = u32;
+pub type VkFormat = c_int;
+pub type VkFormatFeatureFlags = VkFlags;
+pub type VkFrontFace = c_int;
+pub type VkImageCreateFlags = VkFlags;
+pub type VkImageLayout = c_int;
+pub type VkImageUsageFlags = VkFlags;
+pub type VkImageViewType = c_int;
 pub type VkInstance = *mut VkInstance_T;
+pub type VkPipelineCacheHeaderVersion = c_int;
+pub type VkPipelineStageFlags = VkFlags;
+pub type VkPolygonMode = c_int;
+pub type VkPresentModeKHR = c_int;
+pub type VkPrimitiveTopology = c_int;
+pub type VkQueryResultFlags = VkFlags;
+pub type VkQueryType = c_int;
+pub type VkQueueFlags = VkFlags;
+pub type VkResult = c_int;
+pub type VkSampleCountFlags = VkFlags;
+pub type VkShaderStageFlags = VkFlags;
+pub type VkVertexInputRate = c_int;
 
 // Opaque structures
 pub enum VkInstance_T {}
+
+// Constants
+pub const VK_QUEUE_FAMILY_IGNORED: u32 = !0;
+pub const VK_REMAINING_ARRAY_LAYERS: u32 = !0;
+pub const VK_REMAINING_MIP_LEVELS: u32 = !0;
+pub const VK_UUID_SIZE: usize = 16;
+pub const VK_WHOLE_SIZE: u64 = !0;
+
+pub const VK_ACCESS_INDIRECT_COMMAND_READ_BIT: VkAccessFlags = 0x0000_0001;
+pub const VK_ACCESS_INDEX_READ_BIT: VkAccessFlags = 0x0000_0002;
+pub const VK_ACCESS_VERTEX_ATTRIBUTE_READ_BIT: VkAccessFlags = 0x0000_0004;
+pub const VK_ACCESS_SHADER_READ_BIT: VkAccessFlags = 0x0000_0020;
+pub const VK_ACCESS_SHADER_WRITE_BIT: VkAccessFlags = 0x0000_0040;
+pub const VK_ACCESS_COLOR_ATTACHMENT_WRITE_BIT: VkAccessFlags = 0x0000_0100;
+pub const VK_ACCESS_TRANSFER_READ_BIT: VkAccessFlags = 0x0000_0800;
+pub const VK_ACCESS_TRANSFER_WRITE_BIT: VkAccessFlags = 0x0000_1000;
+pub const VK_ACCESS_HOST_READ_BIT: VkAccessFlags = 0x0000_2000;
+pub const VK_ACCESS_MEMORY_READ_BIT: VkAccessFlags = 0x0000_8000;
+
+pub const VK_BLEND_FACTOR_ZERO: VkBlendFactor = 0;
+pub const VK_BLEND_FACTOR_ONE: VkBlendFactor = 1;
+pub const VK_BLEND_FACTOR_SRC_COLOR: VkBlendFactor = 2;
+pub const VK_BLEND_FACTOR_ONE_MINUS_SRC_COLOR: VkBlendFactor = 3;
+pub const VK_BLEND_FACTOR_DST_COLOR: VkBlendFactor = 4;
+pub const VK_BLEND_FACTOR_ONE_MINUS_DST_COLOR: VkBlendFactor = 5;
+pub const VK_BLEND_FACTOR_SRC_ALPHA: VkBlendFactor = 6;
+pub const VK_BLEND_FACTOR_ONE_MINUS_SRC_ALPHA: VkBlendFactor = 7;
+pub const VK_BLEND_FACTOR_DST_ALPHA: VkBlendFactor = 8;
+pub const VK_BLEND_FACTOR_ONE_MINUS_DST_ALPHA: VkBlendFactor = 9;
+pub const VK_BLEND_FACTOR_CONSTANT_COLOR: VkBlendFactor = 10;
+pub const VK_BLEND_FACTOR_ONE_MINUS_CONSTANT_COLOR: VkBlendFactor = 11;
+pub const VK_BLEND_FACTOR_CONSTANT_ALPHA: VkBlendFactor = 12;
+pub const VK_BLEND_FACTOR_ONE_MINUS_CONSTANT_ALPHA: VkBlendFactor = 13;
+pub const VK_BLEND_FACTOR_SRC_ALPHA_SATURATE: VkBlendFactor = 14;
+
+pub const VK_BLEND_OP_ADD: VkBlendOp = 0;
+pub const VK_BLEND_OP_SUBTRACT: VkBlendOp = 1;
+pub const VK_BLEND_OP_REVERSE_SUBTRACT: VkBlendOp = 2;
+pub const VK_BLEND_OP_MIN: VkBlendOp = 3;
+pub const VK_BLEND_OP_MAX: VkBlendOp = 4;
+
+pub const VK_COLOR_COMPONENT_R_BIT: VkColorComponentFlags = 0x0000_0001;
+pub const VK_COLOR_COMPONENT_G_BIT: VkColorComponentFlags = 0x0000_0002;
+pub const VK_COLOR_COMPONENT_B_BIT: VkColorComponentFlags = 0x0000_0004;
+pub const VK_COLOR_COMPONENT_A_BIT: VkColorComponentFlags = 0x0000_0008;
+
+pub const VK_COMPARE_OP_NEVER: VkCompareOp = 0;
+pub const VK_COMPARE_OP_LESS: VkCompareOp = 1;
+pub const VK_COMPARE_OP_EQUAL: VkCompareOp = 2;
+pub const VK_COMPARE_OP_LESS_OR_EQUAL: VkCompareOp = 3;
+pub const VK_COMPARE_OP_GREATER: VkCompareOp = 4;
+pub const VK_COMPARE_OP_NOT_EQUAL: VkCompareOp = 5;
+pub const VK_COMPARE_OP_GREATER_OR_EQUAL: VkCompareOp = 6;
+pub const VK_COMPARE_OP_ALWAYS: VkCompareOp = 7;
+
+pub const VK_CULL_MODE_NONE: VkCullModeFlags = 0;
+pub const VK_CULL_MODE_FRONT_BIT: VkCullModeFlags = 0x0000_0001;
+pub const VK_CULL_MODE_BACK_BIT: VkCullModeFlags = 0x0000_0002;
+pub const VK_CULL_MODE_FRONT_AND_BACK: VkCullModeFlags = 0x0000_0003;
+
+pub const VK_DESCRIPTOR_BINDING_UPDATE_AFTER_BIND_BIT: VkDescriptorBindingFlags = 0x0000_0001;
+pub const VK_DESCRIPTOR_BINDING_UPDATE_UNUSED_WHILE_PENDING_BIT: VkDescriptorBindingFlags =
+    0x0000_0002;
+pub const VK_DESCRIPTOR_BINDING_PARTIALLY_BOUND_BIT: VkDescriptorBindingFlags = 0x0000_0004;
+pub const VK_DESCRIPTOR_BINDING_VARIABLE_DESCRIPTOR_COUNT_BIT: VkDescriptorBindingFlags =
+    0x0000_0008;
+
+pub const VK_DESCRIPTOR_POOL_CREATE_FREE_DESCRIPTOR_SET_BIT: VkDescriptorPoolCreateFlags =
+    0x0000_0001;
+pub const VK_DESCRIPTOR_POOL_CREATE_UPDATE_AFTER_BIND_BIT: VkDescriptorPoolCreateFlags =
+    0x0000_0002;
+
+pub const VK_DESCRIPTOR_TYPE_SAMPLER: VkDescriptorType = 0;
+pub const VK_DESCRIPTOR_TYPE_COMBINED_IMAGE_SAMPLER: VkDescriptorType = 1;
+pub const VK_DESCRIPTOR_TYPE_SAMPLED_IMAGE: VkDescriptorType = 2;
+pub const VK_DESCRIPTOR_TYPE_STORAGE_IMAGE: VkDescriptorType = 3;
+pub const VK_DESCRIPTOR_TYPE_UNIFORM_TEXEL_BUFFER: VkDescriptorType = 4;
+pub const VK_DESCRIPTOR_TYPE_STORAGE_TEXEL_BUFFER: VkDescriptorType = 5;
+pub const VK_DESCRIPTOR_TYPE_UNIFORM_BUFFER: VkDescriptorType = 6;
+pub const VK_DESCRIPTOR_TYPE_STORAGE_BUFFER: VkDescriptorType = 7;
+pub const VK_DESCRIPTOR_TYPE_UNIFORM_BUFFER_DYNAMIC: VkDescriptorType = 8;
+pub const VK_DESCRIPTOR_TYPE_STORAGE_BUFFER_DYNAMIC: VkDescriptorType = 9;
+pub const VK_DESCRIPTOR_TYPE_INPUT_ATTACHMENT: VkDescriptorType = 10;
+
+pub const VK_DYNAMIC_STATE_VIEWPORT: VkDynamicState = 0;
+pub const VK_DYNAMIC_STATE_SCISSOR: VkDynamicState = 1;
+pub const VK_DYNAMIC_STATE_LINE_WIDTH: VkDynamicState = 2;
+pub const VK_DYNAMIC_STATE_DEPTH_BIAS: VkDynamicState = 3;
+pub const VK_DYNAMIC_STATE_BLEND_CONSTANTS: VkDynamicState = 4;
+pub const VK_DYNAMIC_STATE_DEPTH_BOUNDS: VkDynamicState = 5;
+pub const VK_DYNAMIC_STATE_STENCIL_COMPARE_MASK: VkDynamicState = 6;
+pub const VK_DYNAMIC_STATE_STENCIL_WRITE_MASK: VkDynamicState = 7;
+pub const VK_DYNAMIC_STATE_STENCIL_REFERENCE: VkDynamicState = 8;
+
+pub const VK_FORMAT_UNDEFINED: VkFormat = 0;
+pub const VK_FORMAT_R8_UNORM: VkFormat = 9;
+pub const VK_FORMAT_R8G8_UNORM: VkFormat = 16;
+pub const VK_FORMAT_R8G8B8A8_UNORM: VkFormat = 37;
+pub const VK_FORMAT_R8G8B8A8_UINT: VkFormat = 41;
+pub const VK_FORMAT_R8G8B8A8_SRGB: VkFormat = 43;
+pub const VK_FORMAT_B8G8R8A8_UNORM: VkFormat = 44;
+pub const VK_FORMAT_B8G8R8A8_SRGB: VkFormat = 50;
+pub const VK_FORMAT_R16G16_SFLOAT: VkFormat = 83;
+pub const VK_FORMAT_R16G16B16A16_UNORM: VkFormat = 91;
+pub const VK_FORMAT_R16G16B16A16_SFLOAT: VkFormat = 97;
+pub const VK_FORMAT_R32_UINT: VkFormat = 98;
+pub const VK_FORMAT_R32_SINT: VkFormat = 99;
+pub const VK_FORMAT_R32_SFLOAT: VkFormat = 100;
+pub const VK_FORMAT_R32G32_UINT: VkFormat = 101;
+pub const VK_FORMAT_R32G32_SINT: VkFormat = 102;
+pub const VK_FORMAT_R32G32_SFLOAT: VkFormat = 103;
+pub const VK_FORMAT_R32G32B32_UINT: VkFormat = 104;
+pub const VK_FORMAT_R32G32B32_SINT: VkFormat = 105;
+pub const VK_FORMAT_R32G32B32_SFLOAT: VkFormat = 106;
+pub const VK_FORMAT_R32G32B32A32_UINT: VkFormat = 107;
+pub const VK_FORMAT_R32G32B32A32_SINT: VkFormat = 108;
+pub const VK_FORMAT_R32G32B32A32_SFLOAT: VkFormat = 109;
+pub const VK_FORMAT_D16_UNORM: VkFormat = 124;
+pub const VK_FORMAT_D32_SFLOAT: VkFormat = 126;
+pub const VK_FORMAT_D24_UNORM_S8_UINT: VkFormat = 129;
+pub const VK_FORMAT_D32_SFLOAT_S8_UINT: VkFormat = 130;
+
+pub const VK_FORMAT_FEATURE_SAMPLED_IMAGE_BIT: VkFormatFeatureFlags = 0x0000_0001;
+pub const VK_FORMAT_FEATURE_BLIT_SRC_BIT: VkFormatFeatureFlags = 0x0000_0400;
+pub const VK_FORMAT_FEATURE_BLIT_DST_BIT: VkFormatFeatureFlags = 0x0000_0800;
+pub const VK_FORMAT_FEATURE_SAMPLED_IMAGE_FILTER_LINEAR_BIT: VkFormatFeatureFlags = 0x0000_1000;
+
+pub const VK_FRONT_FACE_COUNTER_CLOCKWISE: VkFrontFace = 0;
+pub const VK_FRONT_FACE_CLOCKWISE: VkFrontFace = 1;
+
+pub const VK_IMAGE_CREATE_CUBE_COMPATIBLE_BIT: VkImageCreateFlags = 0x0000_0010;
+
+pub const VK_IMAGE_LAYOUT_UNDEFINED: VkImageLayout = 0;
+pub const VK_IMAGE_LAYOUT_GENERAL: VkImageLayout = 1;
+pub const VK_IMAGE_LAYOUT_COLOR_ATTACHMENT_OPTIMAL: VkImageLayout = 2;
+pub const VK_IMAGE_LAYOUT_DEPTH_STENCIL_ATTACHMENT_OPTIMAL: VkImageLayout = 3;
+pub const VK_IMAGE_LAYOUT_DEPTH_STENCIL_READ_ONLY_OPTIMAL: VkImageLayout = 4;
+pub const VK_IMAGE_LAYOUT_SHADER_READ_ONLY_OPTIMAL: VkImageLayout = 5;
+pub const VK_IMAGE_LAYOUT_TRANSFER_SRC_OPTIMAL: VkImageLayout = 6;
+pub const VK_IMAGE_LAYOUT_TRANSFER_DST_OPTIMAL: VkImageLayout = 7;
+pub const VK_IMAGE_LAYOUT_PRESENT_SRC_KHR: VkImageLayout = 1_000_001_002;
+
+pub const VK_IMAGE_USAGE_TRANSFER_SRC_BIT: VkImageUsageFlags = 0x0000_0001;
+pub const VK_IMAGE_USAGE_TRANSFER_DST_BIT: VkImageUsageFlags = 0x0000_0002;
+pub const VK_IMAGE_USAGE_SAMPLED_BIT: VkImageUsageFlags = 0x0000_0004;
+pub const VK_IMAGE_USAGE_STORAGE_BIT: VkImageUsageFlags = 0x0000_0008;
+pub const VK_IMAGE_USAGE_COLOR_ATTACHMENT_BIT: VkImageUsageFlags = 0x0000_0010;
+pub const VK_IMAGE_USAGE_DEPTH_STENCIL_ATTACHMENT_BIT: VkImageUsageFlags = 0x0000_0020;
+pub const VK_IMAGE_USAGE_TRANSIENT_ATTACHMENT_BIT: VkImageUsageFlags = 0x0000_0040;
+
+pub const VK_IMAGE_VIEW_TYPE_1D: VkImageViewType = 0;
+pub const VK_IMAGE_VIEW_TYPE_2D: VkImageViewType = 1;
+pub const VK_IMAGE_VIEW_TYPE_3D: VkImageViewType = 2;
+pub const VK_IMAGE_VIEW_TYPE_CUBE: VkImageViewType = 3;
+pub const VK_IMAGE_VIEW_TYPE_1D_ARRAY: VkImageViewType = 4;
+pub const VK_IMAGE_VIEW_TYPE_2D_ARRAY: VkImageViewType = 5;
+pub const VK_IMAGE_VIEW_TYPE_CUBE_ARRAY: VkImageViewType = 6;
+
+pub const VK_PIPELINE_CACHE_HEADER_VERSION_ONE: VkPipelineCacheHeaderVersion = 1;
+
+pub const VK_PIPELINE_STAGE_TOP_OF_PIPE_BIT: VkPipelineStageFlags = 0x0000_0001;
+pub const VK_PIPELINE_STAGE_DRAW_INDIRECT_BIT: VkPipelineStageFlags = 0x0000_0002;
+pub const VK_PIPELINE_STAGE_VERTEX_INPUT_BIT: VkPipelineStageFlags = 0x0000_0004;
+pub const VK_PIPELINE_STAGE_VERTEX_SHADER_BIT: VkPipelineStageFlags = 0x0000_0008;
+pub const VK_PIPELINE_STAGE_FRAGMENT_SHADER_BIT: VkPipelineStageFlags = 0x0000_0080;
+pub const VK_PIPELINE_STAGE_COLOR_ATTACHMENT_OUTPUT_BIT: VkPipelineStageFlags = 0x0000_0400;
+pub const VK_PIPELINE_STAGE_COMPUTE_SHADER_BIT: VkPipelineStageFlags = 0x0000_0800;
+pub const VK_PIPELINE_STAGE_TRANSFER_BIT: VkPipelineStageFlags = 0x0000_1000;
+pub const VK_PIPELINE_STAGE_BOTTOM_OF_PIPE_BIT: VkPipelineStageFlags = 0x0000_2000;
+pub const VK_PIPELINE_STAGE_HOST_BIT: VkPipelineStageFlags = 0x0000_4000;
+
+pub const VK_POLYGON_MODE_FILL: VkPolygonMode = 0;
+pub const VK_POLYGON_MODE_LINE: VkPolygonMode = 1;
+pub const VK_POLYGON_MODE_POINT: VkPolygonMode = 2;
+
+pub const VK_PRESENT_MODE_IMMEDIATE_KHR: VkPresentModeKHR = 0;
+pub const VK_PRESENT_MODE_MAILBOX_KHR: VkPresentModeKHR = 1;
+pub const VK_PRESENT_MODE_FIFO_KHR: VkPresentModeKHR = 2;
+pub const VK_PRESENT_MODE_FIFO_RELAXED_KHR: VkPresentModeKHR = 3;
+
+pub const VK_PRIMITIVE_TOPOLOGY_POINT_LIST: VkPrimitiveTopology = 0;
+pub const VK_PRIMITIVE_TOPOLOGY_LINE_LIST: VkPrimitiveTopology = 1;
+pub const VK_PRIMITIVE_TOPOLOGY_LINE_STRIP: VkPrimitiveTopology = 2;
+pub const VK_PRIMITIVE_TOPOLOGY_TRIANGLE_LIST: VkPrimitiveTopology = 3;
+pub const VK_PRIMITIVE_TOPOLOGY_TRIANGLE_STRIP: VkPrimitiveTopology = 4;
+pub const VK_PRIMITIVE_TOPOLOGY_TRIANGLE_FAN: VkPrimitiveTopology = 5;
+
+pub const VK_QUERY_RESULT_64_BIT: VkQueryResultFlags = 0x0000_0001;
+pub const VK_QUERY_RESULT_WAIT_BIT: VkQueryResultFlags = 0x0000_0002;
+
+pub const VK_QUERY_TYPE_TIMESTAMP: VkQueryType = 2;
+
+pub const VK_QUEUE_GRAPHICS_BIT: VkQueueFlags = 0x0000_0001;
+pub const VK_QUEUE_COMPUTE_BIT: VkQueueFlags = 0x0000_0002;
+pub const VK_QUEUE_TRANSFER_BIT: VkQueueFlags = 0x0000_0004;
+
+pub const VK_SUCCESS: VkResult = 0;
+pub const VK_SUBOPTIMAL_KHR: VkResult = 1_000_001_003;
+pub const VK_ERROR_OUT_OF_HOST_MEMORY: VkResult = -1;
+pub const VK_ERROR_OUT_OF_DEVICE_MEMORY: VkResult = -2;
+pub const VK_ERROR_INITIALIZATION_FAILED: VkResult = -3;
+pub const VK_ERROR_DEVICE_LOST: VkResult = -4;
+pub const VK_ERROR_FRAGMENTED_POOL: VkResult = -12;
+pub const VK_ERROR_OUT_OF_POOL_MEMORY: VkResult = -1_000_069_000;
+pub const VK_ERROR_OUT_OF_DATE_KHR: VkResult = -1_000_001_004;
+
+pub const VK_SAMPLE_COUNT_1_BIT: VkSampleCountFlags = 0x0000_0001;
+pub const VK_SAMPLE_COUNT_2_BIT: VkSampleCountFlags = 0x0000_0002;
+pub const VK_SAMPLE_COUNT_4_BIT: VkSampleCountFlags = 0x0000_0004;
+pub const VK_SAMPLE_COUNT_8_BIT: VkSampleCountFlags = 0x0000_0008;
+pub const VK_SAMPLE_COUNT_16_BIT: VkSampleCountFlags = 0x0000_0010;
+
+pub const VK_SHADER_STAGE_VERTEX_BIT: VkShaderStageFlags = 0x0000_0001;
+pub const VK_SHADER_STAGE_TESSELLATION_CONTROL_BIT: VkShaderStageFlags = 0x0000_0002;
+pub const VK_SHADER_STAGE_TESSELLATION_EVALUATION_BIT: VkShaderStageFlags = 0x0000_0004;
+pub const VK_SHADER_STAGE_GEOMETRY_BIT: VkShaderStageFlags = 0x0000_0008;
+pub const VK_SHADER_STAGE_FRAGMENT_BIT: VkShaderStageFlags = 0x0000_0010;
+pub const VK_SHADER_STAGE_COMPUTE_BIT: VkShaderStageFlags = 0x0000_0020;
+
+pub const VK_VERTEX_INPUT_RATE_VERTEX: VkVertexInputRate = 0;
+pub const VK_VERTEX_INPUT_RATE_INSTANCE: VkVertexInputRate = 1;
@@ -1,11 +1,13 @@
 //! Windows API raw FFI bindings.
 
+#![cfg(target_os = "windows")]
 #![allow(non_camel_case_types, non_snake_case)]
 
 use std::os::raw::*;
 
 // Types
 pub type ATOM = WORD;
+pub type BYTE = c_uchar;
 pub type BOOL = c_int;
 pub type CHAR = c_char;
 pub type DWORD = c_ulong;
@@ -13,6 +15,7 @@ pub type FARPROC = *mut __some_function;
 pub type HANDLE = *mut c_void;
 pub type HBRUSH = *mut HBRUSH__;
 pub type HCURSOR = HICON;
+pub type HDC = *mut HDC__;
 pub type HICON = *mut HICON__;
 pub type HINSTANCE = *mut HINSTANCE__;
 pub type HMENU = *mut HMENU__;
@@ -37,6 +40,7 @@ pub type wchar_t = u16;
 
 // Opaque structures
 pub enum HBRUSH__ {}
+pub enum HDC__ {}
 pub enum HICON__ {}
 pub enum HINSTANCE__ {}
 pub enum HMENU__ {}
@@ -44,6 +48,27 @@ pub enum HWND__ {}
 pub enum __some_function {}
 
 // Structures
+#[repr(C)]
+pub struct BITMAPINFO {
+    pub bmiHeader: BITMAPINFOHEADER,
+    pub bmiColors: [RGBQUAD; 1],
+}
+
+#[repr(C)]
+pub struct BITMAPINFOHEADER {
+    pub biSize: DWORD,
+    pub biWidth: LONG,
+    pub biHeight: LONG,
+    pub biPlanes: WORD,
+    pub biBitCount: WORD,
+    pub biCompression: DWORD,
+    pub biSizeImage: DWORD,
+    pub biXPelsPerMeter: LONG,
+    pub biYPelsPerMeter: LONG,
+    pub biClrUsed: DWORD,
+    pub biClrImportant: DWORD,
+}
+
 #[repr(C)]
 pub struct MSG {
     pub hwnd: HWND,
@@ -68,6 +93,14 @@ pub struct RECT {
     pub bottom: LONG,
 }
 
+#[repr(C)]
+pub struct RGBQUAD {
+    pub rgbBlue: BYTE,
+    pub rgbGreen: BYTE,
+    pub rgbRed: BYTE,
+    pub rgbReserved: BYTE,
+}
+
 #[repr(C)]
 pub struct WNDCLASSW {
     pub style: UINT,
@@ -85,11 +118,17 @@ pub struct WNDCLASSW {
 // Constants
 pub const FALSE: BOOL = 0;
 
+pub const BI_RGB: DWORD = 0;
+
+pub const COLORONCOLOR: c_int = 3;
+
 pub const CS_VREDRAW: UINT = 0x0001;
 pub const CS_HREDRAW: UINT = 0x0002;
 
 pub const CW_USEDEFAULT: c_int = 0x8000_0000u32 as c_int;
 
+pub const DIB_RGB_COLORS: UINT = 0;
+
 pub const KF_EXTENDED: WORD = 0x0100;
 pub const KF_REPEAT: WORD = 0x4000;
 
@@ -97,6 +136,8 @@ pub const MAPVK_VK_TO_VSC: UINT = 0;
 
 pub const PM_REMOVE: UINT = 0x0001;
 
+pub const SRCCOPY: DWORD = 0x00cc_0020;
+
 pub const VK_BACK: c_int = 0x08;
 pub const VK_TAB: c_int = 0x09;
 pub const VK_RETURN: c_int = 0x0d;
@@ -195,8 +236,28 @@ pub const WS_OVERLAPPEDWINDOW: DWORD =
 pub const XBUTTON1: WORD = 0x0001;
 
 // Functions
+#[link(name = "gdi32")]
+extern "system" {
+    pub fn SetStretchBltMode(hdc: HDC, mode: c_int) -> c_int;
+    pub fn StretchDIBits(
+        hdc: HDC,
+        xDest: c_int,
+        yDest: c_int,
+        DestWidth: c_int,
+        DestHeight: c_int,
+        xSrc: c_int,
+        ySrc: c_int,
+        SrcWidth: c_int,
+        SrcHeight: c_int,
+        lpBits: *const c_void,
+        lpbmi: *const BITMAPINFO,
+        iUsage: UINT,
+        rop: DWORD,
+    ) -> c_int;
+}
+
 #[link(name = "kernel32")]
-extern "stdcall" {
+extern "system" {
     pub fn FreeLibrary(hLibModule: HMODULE) -> BOOL;
     pub fn GetModuleHandleW(lpModuleName: LPCWSTR) -> HMODULE;
     pub fn GetProcAddress(hModule: HMODULE, lpProcName: LPCSTR) -> FARPROC;
@@ -204,7 +265,7 @@ extern "stdcall" {
 }
 
 #[link(name = "user32")]
-extern "stdcall" {
+extern "system" {
     pub fn AdjustWindowRect(lpRect: LPRECT, dwStyle: DWORD, bMenu: BOOL) -> BOOL;
     pub fn CreateWindowExW(
         dwExStyle: DWORD,
@@ -223,6 +284,8 @@ extern "stdcall" {
     pub fn DefWindowProcW(hWnd: HWND, Msg: UINT, wParam: WPARAM, lParam: LPARAM) -> LRESULT;
     pub fn DestroyWindow(hWnd: HWND) -> BOOL;
     pub fn DispatchMessageW(lpmsg: *const MSG) -> LRESULT;
+    pub fn GetClientRect(hWnd: HWND, lpRect: LPRECT) -> BOOL;
+    pub fn GetDC(hWnd: HWND) -> HDC;
     pub fn GetPropW(hwnd: HWND, lpString: LPCWSTR) -> HANDLE;
     pub fn MapVirtualKeyW(nCode: UINT, uMapType: UINT) -> UINT;
     pub fn PeekMessageW(
@@ -233,6 +296,7 @@ extern "stdcall" {
         wRemoveMsg: UINT,
     ) -> BOOL;
     pub fn RegisterClassW(lpWndClass: *const WNDCLASSW) -> ATOM;
+    pub fn ReleaseDC(hWnd: HWND, hDC: HDC) -> c_int;
     pub fn SetPropW(hWnd: HWND, lpString: LPCWSTR, hData: HANDLE) -> BOOL;
     pub fn TranslateMessage(lpmsg: *const MSG) -> BOOL;
     pub fn UnregisterClassW(lpClassName: LPCWSTR, hInstance: HINSTANCE) -> BOOL;
@@ -10,6 +10,101 @@ pub enum CreateWindowError {
 /// Convenient result type consisting of a return type and a `CreateWindowError`.
 pub type Result<T = ()> = std::result::Result<T, CreateWindowError>;
 
+/// Kinds of software presentation errors.
+#[derive(Debug)]
+pub enum PresentPixelsError {
+    /// The pixel buffer is empty or does not match the given size.
+    InvalidSize,
+    /// The display does not use 24-bit or 32-bit true color.
+    UnsupportedVisual,
+    /// The window system failed to create the image or to draw it into the window.
+    PresentFailed,
+}
+
+/// Copies pixels to a destination of another size with nearest-neighbor sampling, as
+/// `Window::present_pixels` stretches them.
+///
+/// Sizes are in pixels, and slices hold rows from the top-left corner.
+pub fn scale_pixels(
+    source: &[u32],
+    source_width: u32,
+    source_height: u32,
+    destination: &mut [u32],
+    destination_width: u32,
+    destination_height: u32,
+) {
+    if source_width == destination_width && source_height == destination_height {
+        destination.copy_from_slice(source);
+        return;
+    }
+    for (y, row) in destination
+        .chunks_mut(destination_width as usize)
+        .enumerate()
+    {
+        let source_y = y as u64 * u64::from(source_height) / u64::from(destination_height);
+        let source_row = &source[source_y as usize * source_width as usize..];
+        for (x, pixel) in row.iter_mut().enumerate() {
+            let source_x = x as u64 * u64::from(source_width) / u64::from(destination_width);
+            *pixel = source_row[source_x as usize];
+        }
+    }
+}
+
+/// Describes a window to create.
+#[derive(Clone, Debug)]
+pub struct WindowBuilder {
+    title: String,
+    width: u32,
+    height: u32,
+    vulkan: bool,
+}
+
+impl WindowBuilder {
+    /// Creates a 800x600 untitled window description, loading Vulkan if the `vulkan`
+    /// feature is enabled.
+    pub fn new() -> WindowBuilder {
+        WindowBuilder {
+            title: String::new(),
+            width: 800,
+            height: 600,
+            vulkan: cfg!(feature = "vulkan"),
+        }
+    }
+
+    /// Sets the title.
+    pub fn title(mut self, title: &str) -> WindowBuilder {
+        self.title = String::from(title);
+        self
+    }
+
+    /// Sets the size of the client area.
+    pub fn size(mut self, width: u32, height: u32) -> WindowBuilder {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    /// Sets whether the Vulkan library is loaded, creation failing if it is missing.
+    ///
+    /// Windows only presenting software-rendered pixels do not need it. Without the
+    /// `vulkan` feature, requesting it makes creation fail.
+    pub fn vulkan(mut self, vulkan: bool) -> WindowBuilder {
+        self.vulkan = vulkan;
+        self
+    }
+
+    /// Creates the window.
+    pub fn build(&self) -> Result<Window> {
+        Window::from_builder(self)
+    }
+}
+
+impl Default for WindowBuilder {
+    fn default() -> WindowBuilder {
+        WindowBuilder::new()
+    }
+}
+
 #[cfg(target_os = "windows")]
 mod windows;
 
@@ -0,0 +1,311 @@
+//! Software framebuffer presented with `XPutImage`, or `XShmPutImage` if the MIT-SHM
+//! extension is available.
+
+use super::Context;
+use crate::PresentPixelsError;
+use blazar_libc_sys as libc_sys;
+use blazar_xlib_dl as xlib_dl;
+use blazar_xlib_sys as xlib_sys;
+use std::{
+    os::raw::{c_char, c_int},
+    ptr, slice,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+/// Set by `handle_attach_error` when the X server cannot attach a shared memory segment,
+/// typically because it runs on another machine.
+static ATTACH_FAILED: AtomicBool = AtomicBool::new(false);
+
+/// Image memory, shared with the X server or sent through the connection.
+enum Memory {
+    Heap(Vec<u32>),
+    Shared(Box<xlib_sys::XShmSegmentInfo>),
+}
+
+/// Image of the size of the window.
+struct Image {
+    image: Box<xlib_sys::XImage>,
+    memory: Memory,
+}
+
+/// Copies software-rendered frames to a window.
+pub(crate) struct Framebuffer {
+    xext: Option<xlib_dl::XextLibrary>,
+    gc: xlib_sys::GC,
+    depth: c_int,
+    image: Option<Image>,
+}
+
+impl Framebuffer {
+    /// Creates a framebuffer for a window, if the default visual is 24-bit or 32-bit true
+    /// color.
+    pub(crate) unsafe fn create(
+        context: &Context,
+        window: xlib_sys::Window,
+    ) -> Result<Framebuffer, PresentPixelsError> {
+        let screen = context.x11.XDefaultScreen(context.display);
+        let visual = context.x11.XDefaultVisual(context.display, screen);
+        let depth = context.x11.XDefaultDepth(context.display, screen);
+        if visual.is_null()
+            || (*visual).class != xlib_sys::TrueColor
+            || (*visual).red_mask != 0x00ff_0000
+            || (*visual).green_mask != 0x0000_ff00
+            || (*visual).blue_mask != 0x0000_00ff
+            || (depth != 24 && depth != 32)
+        {
+            return Err(PresentPixelsError::UnsupportedVisual);
+        }
+        let gc = context
+            .x11
+            .XCreateGC(context.display, window, 0, ptr::null_mut());
+        if gc.is_null() {
+            return Err(PresentPixelsError::PresentFailed);
+        }
+        let xext = xlib_dl::XextLibrary::load()
+            .ok()
+            .filter(|xext| xext.XShmQueryExtension(context.display) != xlib_sys::FALSE);
+        Ok(Framebuffer {
+            xext,
+            gc,
+            depth,
+            image: None,
+        })
+    }
+
+    /// Recreates the image if its size changed.
+    pub(crate) unsafe fn resize(
+        &mut self,
+        context: &Context,
+        width: u32,
+        height: u32,
+    ) -> Result<(), PresentPixelsError> {
+        if let Some(image) = &self.image {
+            if image.image.width as u32 == width && image.image.height as u32 == height {
+                return Ok(());
+            }
+        }
+        self.destroy_image(context);
+        let mut image = self.create_shared_image(context, width, height);
+        if image.is_none() {
+            // Stops trying shared memory on failure.
+            self.xext = None;
+            image = self.create_heap_image(context, width, height);
+        }
+        self.image = Some(image.ok_or(PresentPixelsError::PresentFailed)?);
+        Ok(())
+    }
+
+    /// Returns the pixels of the image, row by row.
+    pub(crate) fn pixels_mut(&mut self) -> &mut [u32] {
+        match &mut self.image {
+            Some(Image {
+                memory: Memory::Heap(pixels),
+                ..
+            }) => pixels,
+            Some(Image {
+                image,
+                memory: Memory::Shared(info),
+            }) => unsafe {
+                let length = image.width as usize * image.height as usize;
+                slice::from_raw_parts_mut(info.shmaddr as *mut u32, length)
+            },
+            None => &mut [],
+        }
+    }
+
+    /// Copies the image to the top-left corner of the window.
+    pub(crate) unsafe fn present(
+        &mut self,
+        context: &Context,
+        window: xlib_sys::Window,
+    ) -> Result<(), PresentPixelsError> {
+        let image = match &mut self.image {
+            Some(image) => image,
+            None => return Ok(()),
+        };
+        let (width, height) = (image.image.width as u32, image.image.height as u32);
+        match (&image.memory, &self.xext) {
+            (Memory::Shared(_), Some(xext)) => {
+                let presented = xext.XShmPutImage(
+                    context.display,
+                    window,
+                    self.gc,
+                    &mut *image.image,
+                    0,
+                    0,
+                    0,
+                    0,
+                    width,
+                    height,
+                    xlib_sys::FALSE,
+                );
+                // Waits for the server to read the segment before it is written again.
+                context.x11.XSync(context.display, xlib_sys::FALSE);
+                if presented == xlib_sys::FALSE {
+                    return Err(PresentPixelsError::PresentFailed);
+                }
+            }
+            _ => {
+                context.x11.XPutImage(
+                    context.display,
+                    window,
+                    self.gc,
+                    &mut *image.image,
+                    0,
+                    0,
+                    0,
+                    0,
+                    width,
+                    height,
+                );
+                context.x11.XFlush(context.display);
+            }
+        }
+        Ok(())
+    }
+
+    /// Releases the image and the graphics context.
+    pub(crate) unsafe fn destroy(&mut self, context: &Context) {
+        self.destroy_image(context);
+        context.x11.XFreeGC(context.display, self.gc);
+    }
+
+    /// Creates an image in a shared memory segment attached to the X server.
+    unsafe fn create_shared_image(
+        &self,
+        context: &Context,
+        width: u32,
+        height: u32,
+    ) -> Option<Image> {
+        let xext = self.xext.as_ref()?;
+        let size = width as usize * height as usize * 4;
+        let id = libc_sys::shmget(libc_sys::IPC_PRIVATE, size, libc_sys::IPC_CREAT | 0o600);
+        if id < 0 {
+            return None;
+        }
+        let address = libc_sys::shmat(id, ptr::null(), 0);
+        if address as isize == -1 {
+            libc_sys::shmctl(id, libc_sys::IPC_RMID, ptr::null_mut());
+            return None;
+        }
+        let mut info = Box::new(xlib_sys::XShmSegmentInfo {
+            shmseg: 0,
+            shmid: id,
+            shmaddr: address as *mut c_char,
+            readOnly: xlib_sys::FALSE,
+        });
+        ATTACH_FAILED.store(false, Ordering::SeqCst);
+        let handler = context.x11.XSetErrorHandler(Some(handle_attach_error));
+        let attached = xext.XShmAttach(context.display, &mut *info) != xlib_sys::FALSE;
+        context.x11.XSync(context.display, xlib_sys::FALSE);
+        context.x11.XSetErrorHandler(handler);
+        // The server attached the segment once synchronized, so it can be marked for
+        // removal, which destroys it once detached by both processes.
+        libc_sys::shmctl(id, libc_sys::IPC_RMID, ptr::null_mut());
+        if !attached || ATTACH_FAILED.load(Ordering::SeqCst) {
+            libc_sys::shmdt(address);
+            return None;
+        }
+        // `XShmPutImage` finds the segment in the extension data of the image.
+        let obdata = &mut *info as *mut xlib_sys::XShmSegmentInfo as *mut c_char;
+        match self.new_image(context, width, height, info.shmaddr, obdata) {
+            Some(image) => Some(Image {
+                image,
+                memory: Memory::Shared(info),
+            }),
+            None => {
+                xext.XShmDetach(context.display, &mut *info);
+                context.x11.XSync(context.display, xlib_sys::FALSE);
+                libc_sys::shmdt(address);
+                None
+            }
+        }
+    }
+
+    /// Creates an image sent through the X connection.
+    unsafe fn create_heap_image(
+        &self,
+        context: &Context,
+        width: u32,
+        height: u32,
+    ) -> Option<Image> {
+        let mut pixels = vec![0; width as usize * height as usize];
+        let data = pixels.as_mut_ptr() as *mut c_char;
+        let image = self.new_image(context, width, height, data, ptr::null_mut())?;
+        Some(Image {
+            image,
+            memory: Memory::Heap(pixels),
+        })
+    }
+
+    /// Describes 32-bit pixels in native byte order.
+    unsafe fn new_image(
+        &self,
+        context: &Context,
+        width: u32,
+        height: u32,
+        data: *mut c_char,
+        obdata: *mut c_char,
+    ) -> Option<Box<xlib_sys::XImage>> {
+        let byte_order = if cfg!(target_endian = "little") {
+            xlib_sys::LSBFirst
+        } else {
+            xlib_sys::MSBFirst
+        };
+        let mut image = Box::new(xlib_sys::XImage {
+            width: width as c_int,
+            height: height as c_int,
+            xoffset: 0,
+            format: xlib_sys::ZPixmap,
+            data,
+            byte_order,
+            bitmap_unit: 32,
+            bitmap_bit_order: byte_order,
+            bitmap_pad: 32,
+            depth: self.depth,
+            bytes_per_line: width as c_int * 4,
+            bits_per_pixel: 32,
+            red_mask: 0x00ff_0000,
+            green_mask: 0x0000_ff00,
+            blue_mask: 0x0000_00ff,
+            obdata,
+            f: xlib_sys::XImageFunctions {
+                create_image: ptr::null_mut(),
+                destroy_image: ptr::null_mut(),
+                get_pixel: ptr::null_mut(),
+                put_pixel: ptr::null_mut(),
+                sub_image: ptr::null_mut(),
+                add_pixel: ptr::null_mut(),
+            },
+        });
+        if context.x11.XInitImage(&mut *image) == 0 {
+            None
+        } else {
+            Some(image)
+        }
+    }
+
+    /// Releases the image, detaching its shared memory segment if any.
+    unsafe fn destroy_image(&mut self, context: &Context) {
+        if let Some(Image {
+            memory: Memory::Shared(mut info),
+            ..
+        }) = self.image.take()
+        {
+            if let Some(xext) = &self.xext {
+                xext.XShmDetach(context.display, &mut *info);
+                context.x11.XSync(context.display, xlib_sys::FALSE);
+            }
+            libc_sys::shmdt(info.shmaddr as *const _);
+        }
+    }
+}
+
+/// Records the failure of `XShmAttach` instead of exiting the process.
+unsafe extern "C" fn handle_attach_error(
+    _display: *mut xlib_sys::Display,
+    _event: *mut xlib_sys::XErrorEvent,
+) -> c_int {
+    ATTACH_FAILED.store(true, Ordering::SeqCst);
+    0
+}
@@ -1,10 +1,14 @@
 //! Xlib-based windows.
 
-use crate::{CreateWindowError, Result};
+mod framebuffer;
+
+use crate::{scale_pixels, CreateWindowError, PresentPixelsError, Result, WindowBuilder};
 use blazar_event::{Button, Event, Key};
+#[cfg(feature = "vulkan")]
 use blazar_vk_dl as vk_dl;
 use blazar_xlib_dl as xlib_dl;
 use blazar_xlib_sys as xlib_sys;
+use framebuffer::Framebuffer;
 use std::{
     collections::VecDeque,
     ffi::CString,
@@ -16,7 +20,8 @@ use std::{
 /// Represents an object that holds on to global resources.
 pub(crate) struct Context {
     pub(crate) x11: xlib_dl::X11Library,
-    pub(crate) _vk: vk_dl::VulkanLibrary,
+    #[cfg(feature = "vulkan")]
+    pub(crate) vk: Option<vk_dl::VulkanLibrary>,
     pub(crate) display: *mut xlib_sys::Display,
     pub(crate) wm_protocols: xlib_sys::Atom,
     pub(crate) wm_delete_window: xlib_sys::Atom,
@@ -26,7 +31,15 @@ pub(crate) struct Context {
 }
 
 impl Context {
-    fn create() -> Result<Context> {
+    fn create(builder: &WindowBuilder) -> Result<Context> {
+        #[cfg(not(feature = "vulkan"))]
+        {
+            if builder.vulkan {
+                return Err(CreateWindowError::ContextCreationFailed(String::from(
+                    "Vulkan support is disabled",
+                )));
+            }
+        }
         unsafe {
             // Loads Xlib.
             let x11 = xlib_dl::X11Library::load().map_err(|_| {
@@ -34,9 +47,16 @@ impl Context {
             })?;
 
             // Loads Vulkan library.
-            let _vk = vk_dl::VulkanLibrary::load().map_err(|_| {
-                CreateWindowError::ContextCreationFailed(String::from("Cannot load Vulkan library"))
-            })?;
+            #[cfg(feature = "vulkan")]
+            let vk = if builder.vulkan {
+                Some(vk_dl::VulkanLibrary::load().map_err(|_| {
+                    CreateWindowError::ContextCreationFailed(String::from(
+                        "Cannot load Vulkan library",
+                    ))
+                })?)
+            } else {
+                None
+            };
 
             // Opens X display.
             let display = x11.XOpenDisplay(ptr::null());
@@ -62,7 +82,8 @@ impl Context {
 
             Ok(Context {
                 x11,
-                _vk,
+                #[cfg(feature = "vulkan")]
+                vk,
                 display,
                 wm_protocols,
                 wm_delete_window,
@@ -74,6 +95,18 @@ impl Context {
     }
 }
 
+impl Context {
+    #[cfg(feature = "vulkan")]
+    fn is_vulkan_loaded(&self) -> bool {
+        self.vk.is_some()
+    }
+
+    #[cfg(not(feature = "vulkan"))]
+    fn is_vulkan_loaded(&self) -> bool {
+        false
+    }
+}
+
 impl Drop for Context {
     fn drop(&mut self) {
         unsafe {
@@ -89,14 +122,24 @@ pub struct Window {
     width: u32,
     height: u32,
     events: VecDeque<Event>,
+    framebuffer: Option<Framebuffer>,
 }
 
 impl Window {
     /// Creates a new window.
     pub fn create(title: &str, width: u32, height: u32) -> Result<Window> {
+        WindowBuilder::new()
+            .title(title)
+            .size(width, height)
+            .build()
+    }
+
+    /// Creates a new window from its description.
+    pub(crate) fn from_builder(builder: &WindowBuilder) -> Result<Window> {
+        let (title, width, height) = (builder.title.as_str(), builder.width, builder.height);
         unsafe {
             // Creates context.
-            let context = Context::create()?;
+            let context = Context::create(builder)?;
 
             // Creates the window.
             let default_screen = context.x11.XDefaultScreen(context.display);
@@ -185,10 +228,50 @@ impl Window {
                 width,
                 height,
                 events,
+                framebuffer: None,
             })
         }
     }
 
+    /// Returns `true` if the Vulkan library was loaded.
+    pub fn is_vulkan_loaded(&self) -> bool {
+        self.context.is_vulkan_loaded()
+    }
+
+    /// Presents software-rendered pixels, without Vulkan.
+    ///
+    /// Pixels are `0x00RRGGBB` values, row by row from the top-left corner. They are
+    /// stretched to the window size with nearest-neighbor sampling if needed.
+    pub fn present_pixels(
+        &mut self,
+        pixels: &[u32],
+        width: u32,
+        height: u32,
+    ) -> std::result::Result<(), PresentPixelsError> {
+        if width == 0 || height == 0 || pixels.len() != width as usize * height as usize {
+            return Err(PresentPixelsError::InvalidSize);
+        }
+        if self.width == 0 || self.height == 0 {
+            return Ok(());
+        }
+        unsafe {
+            if self.framebuffer.is_none() {
+                self.framebuffer = Some(Framebuffer::create(&self.context, self.handle)?);
+            }
+            let framebuffer = self.framebuffer.as_mut().unwrap();
+            framebuffer.resize(&self.context, self.width, self.height)?;
+            scale_pixels(
+                pixels,
+                width,
+                height,
+                framebuffer.pixels_mut(),
+                self.width,
+                self.height,
+            );
+            framebuffer.present(&self.context, self.handle)
+        }
+    }
+
     /// Pop the event on the top of the event queue, if any, and return it.
     pub fn poll_event(&mut self) -> Option<Event> {
         self.update_event_queue();
@@ -228,7 +311,7 @@ impl Window {
                     || event.configure.height as u32 != self.height =>
             {
                 self.width = event.configure.width as u32;
-                self.height = event.configure.width as u32;
+                self.height = event.configure.height as u32;
                 Some(Event::Resize {
                     width: self.width,
                     height: self.height,
@@ -298,6 +381,9 @@ impl Window {
 impl Drop for Window {
     fn drop(&mut self) {
         unsafe {
+            if let Some(framebuffer) = &mut self.framebuffer {
+                framebuffer.destroy(&self.context);
+            }
             self.context
                 .x11
                 .XDestroyWindow(self.context.display, self.handle);
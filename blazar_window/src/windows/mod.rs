@@ -1,7 +1,8 @@
 //! Win32-based windows.
 
-use crate::{CreateWindowError, Result};
+use crate::{CreateWindowError, PresentPixelsError, Result, WindowBuilder};
 use blazar_event::{Button, Event, Key};
+#[cfg(feature = "vulkan")]
 use blazar_vk_dl as vk_dl;
 use blazar_winapi_sys as winapi_sys;
 use std::{
@@ -17,18 +18,34 @@ use std::{
 
 /// Represents an object that holds on to global resources.
 pub(crate) struct Context {
-    _vk: vk_dl::VulkanLibrary,
+    #[cfg(feature = "vulkan")]
+    vk: Option<vk_dl::VulkanLibrary>,
     instance: winapi_sys::HMODULE,
     class_name: Vec<winapi_sys::WCHAR>,
 }
 
 impl Context {
-    fn create() -> Result<Context> {
+    fn create(builder: &WindowBuilder) -> Result<Context> {
+        #[cfg(not(feature = "vulkan"))]
+        {
+            if builder.vulkan {
+                return Err(CreateWindowError::ContextCreationFailed(String::from(
+                    "Vulkan support is disabled",
+                )));
+            }
+        }
         unsafe {
             // Loads Vulkan library.
-            let _vk = vk_dl::VulkanLibrary::load().map_err(|_| {
-                CreateWindowError::ContextCreationFailed(String::from("Cannot load Vulkan library"))
-            })?;
+            #[cfg(feature = "vulkan")]
+            let vk = if builder.vulkan {
+                Some(vk_dl::VulkanLibrary::load().map_err(|_| {
+                    CreateWindowError::ContextCreationFailed(String::from(
+                        "Cannot load Vulkan library",
+                    ))
+                })?)
+            } else {
+                None
+            };
 
             // Retrieves a module handle.
             let instance = winapi_sys::GetModuleHandleW(ptr::null());
@@ -62,7 +79,8 @@ impl Context {
             }
 
             Ok(Context {
-                _vk,
+                #[cfg(feature = "vulkan")]
+                vk,
                 instance,
                 class_name,
             })
@@ -70,6 +88,18 @@ impl Context {
     }
 }
 
+impl Context {
+    #[cfg(feature = "vulkan")]
+    fn is_vulkan_loaded(&self) -> bool {
+        self.vk.is_some()
+    }
+
+    #[cfg(not(feature = "vulkan"))]
+    fn is_vulkan_loaded(&self) -> bool {
+        false
+    }
+}
+
 impl Drop for Context {
     fn drop(&mut self) {
         unsafe {
@@ -80,7 +110,7 @@ impl Drop for Context {
 
 /// Represents a window.
 pub struct Window {
-    _context: Context,
+    context: Context,
     handle: winapi_sys::HWND,
     events: VecDeque<Event>,
 }
@@ -88,9 +118,18 @@ pub struct Window {
 impl Window {
     /// Creates a new window.
     pub fn create(title: &str, width: u32, height: u32) -> Result<Window> {
+        WindowBuilder::new()
+            .title(title)
+            .size(width, height)
+            .build()
+    }
+
+    /// Creates a new window from its description.
+    pub(crate) fn from_builder(builder: &WindowBuilder) -> Result<Window> {
+        let (title, width, height) = (builder.title.as_str(), builder.width, builder.height);
         unsafe {
             // Creates context.
-            let _context = Context::create()?;
+            let context = Context::create(builder)?;
 
             // Creates the window.
             let mut rectangle = winapi_sys::RECT {
@@ -106,10 +145,10 @@ impl Window {
             );
             let width = rectangle.right - rectangle.left;
             let height = rectangle.bottom - rectangle.top;
-            let title = wide_string(&title);
+            let title = wide_string(title);
             let handle = winapi_sys::CreateWindowExW(
                 0,
-                _context.class_name.as_ptr(),
+                context.class_name.as_ptr(),
                 title.as_ptr(),
                 winapi_sys::WS_OVERLAPPEDWINDOW | winapi_sys::WS_VISIBLE,
                 winapi_sys::CW_USEDEFAULT,
@@ -118,7 +157,7 @@ impl Window {
                 height as c_int,
                 ptr::null_mut(),
                 ptr::null_mut(),
-                _context.instance,
+                context.instance,
                 ptr::null_mut(),
             );
             if handle.is_null() {
@@ -129,13 +168,75 @@ impl Window {
             let events = VecDeque::new();
 
             Ok(Window {
-                _context,
+                context,
                 handle,
                 events,
             })
         }
     }
 
+    /// Returns `true` if the Vulkan library was loaded.
+    pub fn is_vulkan_loaded(&self) -> bool {
+        self.context.is_vulkan_loaded()
+    }
+
+    /// Presents software-rendered pixels, without Vulkan.
+    ///
+    /// Pixels are `0x00RRGGBB` values, row by row from the top-left corner. They are
+    /// stretched to the window size with nearest-neighbor sampling if needed.
+    pub fn present_pixels(
+        &mut self,
+        pixels: &[u32],
+        width: u32,
+        height: u32,
+    ) -> std::result::Result<(), PresentPixelsError> {
+        if width == 0 || height == 0 || pixels.len() != width as usize * height as usize {
+            return Err(PresentPixelsError::InvalidSize);
+        }
+        unsafe {
+            let mut rectangle: winapi_sys::RECT = mem::zeroed();
+            winapi_sys::GetClientRect(self.handle, &mut rectangle);
+            let window_width = rectangle.right - rectangle.left;
+            let window_height = rectangle.bottom - rectangle.top;
+            if window_width <= 0 || window_height <= 0 {
+                return Ok(());
+            }
+            let mut info: winapi_sys::BITMAPINFO = mem::zeroed();
+            info.bmiHeader.biSize = mem::size_of::<winapi_sys::BITMAPINFOHEADER>() as _;
+            info.bmiHeader.biWidth = width as winapi_sys::LONG;
+            // A negative height makes the bitmap top-down.
+            info.bmiHeader.biHeight = -(height as winapi_sys::LONG);
+            info.bmiHeader.biPlanes = 1;
+            info.bmiHeader.biBitCount = 32;
+            info.bmiHeader.biCompression = winapi_sys::BI_RGB;
+            let device_context = winapi_sys::GetDC(self.handle);
+            if device_context.is_null() {
+                return Err(PresentPixelsError::PresentFailed);
+            }
+            winapi_sys::SetStretchBltMode(device_context, winapi_sys::COLORONCOLOR);
+            let lines = winapi_sys::StretchDIBits(
+                device_context,
+                0,
+                0,
+                window_width as c_int,
+                window_height as c_int,
+                0,
+                0,
+                width as c_int,
+                height as c_int,
+                pixels.as_ptr() as *const c_void,
+                &info,
+                winapi_sys::DIB_RGB_COLORS,
+                winapi_sys::SRCCOPY,
+            );
+            winapi_sys::ReleaseDC(self.handle, device_context);
+            if lines == 0 {
+                return Err(PresentPixelsError::PresentFailed);
+            }
+            Ok(())
+        }
+    }
+
     /// Pop the event on the top of the event queue, if any, and return it.
     pub fn poll_event(&mut self) -> Option<Event> {
         self.update_event_queue();
@@ -0,0 +1,40 @@
+use blazar_window::scale_pixels;
+
+#[test]
+fn same_size() {
+    let source = [1, 2, 3, 4, 5, 6];
+    let mut destination = [0; 6];
+    scale_pixels(&source, 3, 2, &mut destination, 3, 2);
+    assert_eq!(destination, source);
+}
+
+#[test]
+fn upscale() {
+    let source = [1, 2, 3, 4];
+    let mut destination = [0; 24];
+    scale_pixels(&source, 2, 2, &mut destination, 6, 4);
+    #[rustfmt::skip]
+    assert_eq!(destination, [
+        1, 1, 1, 2, 2, 2,
+        1, 1, 1, 2, 2, 2,
+        3, 3, 3, 4, 4, 4,
+        3, 3, 3, 4, 4, 4,
+    ]);
+}
+
+#[test]
+fn downscale() {
+    #[rustfmt::skip]
+    let source = [
+        1, 2, 3, 4,
+        5, 6, 7, 8,
+        9, 10, 11, 12,
+    ];
+    let mut destination = [0; 2];
+    scale_pixels(&source, 4, 3, &mut destination, 2, 1);
+    assert_eq!(destination, [1, 3]);
+    // Non-integer ratios sample the nearest pixel towards the top-left corner.
+    let mut destination = [0; 6];
+    scale_pixels(&source, 4, 3, &mut destination, 3, 2);
+    assert_eq!(destination, [1, 2, 3, 5, 6, 7]);
+}
@@ -1,4 +1,7 @@
 //! Xlib dynamic loading.
+//!
+//! The MIT-SHM functions of libXext are loaded separately by `XextLibrary`, the
+//! extension being optional.
 
 #![allow(clippy::too_many_arguments, non_snake_case)]
 
@@ -21,6 +24,12 @@ dynamic_loading! {
             data: *const c_uchar,
             nelements: c_int
         ) -> c_int;
+        pub fn XCreateGC(
+            display: *mut Display,
+            d: Drawable,
+            valuemask: c_ulong,
+            values: *mut XGCValues
+        ) -> GC;
         pub fn XCreateSimpleWindow(
             display: *mut Display,
             parent: Window,
@@ -32,10 +41,14 @@ dynamic_loading! {
             border: c_ulong,
             background: c_ulong
         ) -> Window;
+        pub fn XDefaultDepth(display: *mut Display, screen_number: c_int) -> c_int;
         pub fn XDefaultRootWindow(display: *mut Display) -> Window;
         pub fn XDefaultScreen(display: *mut Display) -> c_int;
+        pub fn XDefaultVisual(display: *mut Display, screen_number: c_int) -> *mut Visual;
         pub fn XDestroyWindow(display: *mut Display, window: Window) -> c_int;
         pub fn XFlush(display: *mut Display) -> c_int;
+        pub fn XFreeGC(display: *mut Display, gc: GC) -> c_int;
+        pub fn XInitImage(image: *mut XImage) -> Status;
         pub fn XInternAtom(
             display: *mut Display,
             atom_name: *const c_char,
@@ -47,13 +60,27 @@ dynamic_loading! {
         pub fn XOpenDisplay(display_name: *const c_char) -> *mut Display;
         pub fn XPeekEvent(display: *mut Display, event_return: *mut XEvent) -> c_int;
         pub fn XPending(display: *mut Display) -> c_int;
+        pub fn XPutImage(
+            display: *mut Display,
+            d: Drawable,
+            gc: GC,
+            image: *mut XImage,
+            src_x: c_int,
+            src_y: c_int,
+            dest_x: c_int,
+            dest_y: c_int,
+            width: c_uint,
+            height: c_uint
+        ) -> c_int;
         pub fn XSelectInput(display: *mut Display, w: Window, event_mask: c_long) -> c_int;
+        pub fn XSetErrorHandler(handler: XErrorHandler) -> XErrorHandler;
         pub fn XSetWMProtocols(
             display: *mut Display,
             w: Window,
             protocols: *mut Atom,
             count: c_int
         ) -> Status;
+        pub fn XSync(display: *mut Display, discard: Bool) -> c_int;
         pub fn Xutf8SetWMProperties(
             display: *mut Display,
             w: c_ulong,
@@ -67,3 +94,35 @@ dynamic_loading! {
         ) -> c_void;
     }
 }
+
+mod xext {
+    #![allow(non_snake_case)]
+
+    use blazar_dl::dynamic_loading;
+    use blazar_xlib_sys::*;
+    use std::os::raw::*;
+
+    dynamic_loading! {
+        #[load(wrapper = XextLibrary, error = LoadXextError, name = "Xext", version = 6)]
+        extern "C" {
+            pub fn XShmAttach(display: *mut Display, shminfo: *mut XShmSegmentInfo) -> Bool;
+            pub fn XShmDetach(display: *mut Display, shminfo: *mut XShmSegmentInfo) -> Bool;
+            pub fn XShmPutImage(
+                display: *mut Display,
+                d: Drawable,
+                gc: GC,
+                image: *mut XImage,
+                src_x: c_int,
+                src_y: c_int,
+                dst_x: c_int,
+                dst_y: c_int,
+                src_width: c_uint,
+                src_height: c_uint,
+                send_event: Bool
+            ) -> Bool;
+            pub fn XShmQueryExtension(display: *mut Display) -> Bool;
+        }
+    }
+}
+
+pub use xext::{LoadXextError, XextLibrary};
@@ -1,21 +1,30 @@
 //! Xlib raw FFI bindings.
 
-#![allow(non_upper_case_globals)]
+#![allow(non_snake_case, non_upper_case_globals)]
 
 use std::os::raw::*;
 
 // Types
 pub type Atom = XID;
 pub type Bool = c_int;
+pub type Drawable = XID;
+pub type GC = *mut XGC;
 pub type KeySym = XID;
 pub type Status = Bool;
+pub type ShmSeg = c_ulong;
 pub type Time = c_ulong;
+pub type VisualID = c_ulong;
 pub type Window = XID;
+pub type XErrorHandler = Option<unsafe extern "C" fn(*mut Display, *mut XErrorEvent) -> c_int>;
 pub type XID = c_ulong;
 
 // Opaque structures
 pub enum Display {}
 pub enum XClassHint {}
+pub enum XErrorEvent {}
+pub enum XExtData {}
+pub enum XGC {}
+pub enum XGCValues {}
 pub enum XSizeHints {}
 pub enum XWMHints {}
 
@@ -88,6 +97,39 @@ pub union XEvent {
     pub pad: [c_long; 24],
 }
 
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct XImage {
+    pub width: c_int,
+    pub height: c_int,
+    pub xoffset: c_int,
+    pub format: c_int,
+    pub data: *mut c_char,
+    pub byte_order: c_int,
+    pub bitmap_unit: c_int,
+    pub bitmap_bit_order: c_int,
+    pub bitmap_pad: c_int,
+    pub depth: c_int,
+    pub bytes_per_line: c_int,
+    pub bits_per_pixel: c_int,
+    pub red_mask: c_ulong,
+    pub green_mask: c_ulong,
+    pub blue_mask: c_ulong,
+    pub obdata: *mut c_char,
+    pub f: XImageFunctions,
+}
+
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct XImageFunctions {
+    pub create_image: *mut c_void,
+    pub destroy_image: *mut c_void,
+    pub get_pixel: *mut c_void,
+    pub put_pixel: *mut c_void,
+    pub sub_image: *mut c_void,
+    pub add_pixel: *mut c_void,
+}
+
 #[derive(Clone, Copy)]
 #[repr(C)]
 pub struct XKeyEvent {
@@ -108,6 +150,28 @@ pub struct XKeyEvent {
     pub same_screen: Bool,
 }
 
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct XShmSegmentInfo {
+    pub shmseg: ShmSeg,
+    pub shmid: c_int,
+    pub shmaddr: *mut c_char,
+    pub readOnly: Bool,
+}
+
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct Visual {
+    pub ext_data: *mut XExtData,
+    pub visualid: VisualID,
+    pub class: c_int,
+    pub red_mask: c_ulong,
+    pub green_mask: c_ulong,
+    pub blue_mask: c_ulong,
+    pub bits_per_rgb: c_int,
+    pub map_entries: c_int,
+}
+
 // Constants
 pub const Button1: c_uint = 1;
 pub const Button2: c_uint = 2;
@@ -135,6 +199,10 @@ pub const ConfigureNotify: c_int = 22;
 pub const ClientMessage: c_int = 33;
 
 pub const FALSE: Bool = 0;
+pub const LSBFirst: c_int = 0;
+pub const MSBFirst: c_int = 1;
+pub const TrueColor: c_int = 4;
+pub const ZPixmap: c_int = 2;
 
 pub const PropModeReplace: c_int = 0;
 
@@ -0,0 +1,40 @@
+//! Animates a pattern rendered on the CPU, presented without Vulkan.
+
+use blazar::{
+    event::{Event, Key},
+    window::{Result, WindowBuilder},
+};
+
+const WIDTH: u32 = 320;
+const HEIGHT: u32 = 240;
+
+fn main() -> Result {
+    let mut window = WindowBuilder::new()
+        .title("Software window")
+        .size(800, 600)
+        .vulkan(false)
+        .build()?;
+    let mut pixels = vec![0; (WIDTH * HEIGHT) as usize];
+    let mut frame = 0u32;
+    'running: loop {
+        while let Some(event) = window.poll_event() {
+            match event {
+                Event::Close | Event::KeyPress { key: Key::Escape } => break 'running,
+                _ => {}
+            }
+        }
+        for (index, pixel) in pixels.iter_mut().enumerate() {
+            let x = index as u32 % WIDTH;
+            let y = index as u32 / WIDTH;
+            let red = (x + frame) & 0xff;
+            let green = (y + frame / 2) & 0xff;
+            let blue = (x ^ y) & 0xff;
+            *pixel = red << 16 | green << 8 | blue;
+        }
+        window
+            .present_pixels(&pixels, WIDTH, HEIGHT)
+            .expect("Cannot present pixels");
+        frame = frame.wrapping_add(1);
+    }
+    Ok(())
+}
@@ -1,4 +1,9 @@
 //! Simple and lite game engine.
 
 pub use blazar_event as event;
+pub use blazar_font as font;
+pub use blazar_graphics as graphics;
+pub use blazar_image as image;
+pub use blazar_math as math;
+pub use blazar_model as model;
 pub use blazar_window as window;